@@ -0,0 +1,61 @@
+use consensus::{
+    consensus::test_consensus::create_temp_db,
+    model::stores::{
+        database::prelude::CachePolicy,
+        statuses::{DbStatusesStore, ShardedStatusesStore, StatusesStore, StatusesStoreReader},
+    },
+};
+use consensus_core::blockstatus::BlockStatus;
+use criterion::{criterion_group, criterion_main, Criterion};
+use hashes::Hash;
+use parking_lot::RwLock;
+use rand::Rng;
+use std::{sync::Arc, thread};
+
+const THREADS: usize = 4;
+const OPERATIONS_PER_THREAD: usize = 2000;
+const BLOCKS: usize = 1000;
+
+/// Emulates parallel header and body processing, where each worker reads the statuses of a block's
+/// parents and then sets the status of the block itself
+fn run_workers(hashes: &Arc<Vec<Hash>>, get: impl Fn(Hash) -> BlockStatus + Sync, set: impl Fn(Hash, BlockStatus) + Sync) {
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let mut rng = rand::thread_rng();
+                for _ in 0..OPERATIONS_PER_THREAD {
+                    for _ in 0..3 {
+                        get(hashes[rng.gen_range(0..BLOCKS)]);
+                    }
+                    set(hashes[rng.gen_range(0..BLOCKS)], BlockStatus::StatusUTXOPendingVerification);
+                }
+            });
+        }
+    });
+}
+
+pub fn statuses_benchmark(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+    let hashes: Arc<Vec<Hash>> = Arc::new((0..BLOCKS).map(|_| Hash::from_bytes(rng.gen())).collect());
+
+    let (_single_lifetime, db) = create_temp_db();
+    let single = RwLock::new(DbStatusesStore::new(db, CachePolicy::Count(BLOCKS)));
+    for &hash in hashes.iter() {
+        single.write().set(hash, BlockStatus::StatusHeaderOnly).unwrap();
+    }
+    c.bench_function("statuses store: single lock", |b| {
+        b.iter(|| run_workers(&hashes, |h| single.read().get(h).unwrap(), |h, status| single.write().set(h, status).unwrap()))
+    });
+
+    let (_sharded_lifetime, db) = create_temp_db();
+    let sharded = ShardedStatusesStore::new(db, CachePolicy::Count(BLOCKS));
+    for &hash in hashes.iter() {
+        sharded.set(hash, BlockStatus::StatusHeaderOnly).unwrap();
+    }
+    c.bench_function("statuses store: sharded locks", |b| {
+        b.iter(|| run_workers(&hashes, |h| sharded.get(h).unwrap(), |h, status| sharded.set(h, status).unwrap()))
+    });
+}
+
+criterion_group!(benches, statuses_benchmark);
+criterion_main!(benches);
@@ -1,16 +1,28 @@
+pub mod session;
+
 use futures_util::future::BoxFuture;
+use hashes::Hash;
 use std::sync::Arc;
 
 use crate::{
-    block::{Block, BlockTemplate},
+    block::{Block, BlockDagData, BlockTemplate, VirtualInfo},
     blockstatus::BlockStatus,
-    coinbase::MinerData,
+    chain::{ChainBlocksBatch, VirtualChainBatch},
+    coinbase::{CoinbasePreview, MinerData},
     errors::{
         block::{BlockProcessResult, RuleError},
+        coinbase::CoinbaseResult,
+        sync::SyncManagerResult,
         tx::TxResult,
     },
-    tx::{MutableTransaction, Transaction},
+    ghostdag::ExternalGhostdagData,
+    header::Header,
+    revalidation::BlockRevalidationReport,
+    stats::{BlockDagInfo, ChainThroughputStats, DaaScoreDrift},
+    trusted::{PruningPointTrustedData, TrustedBlock},
+    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
 };
+use session::SessionLock;
 
 /// Abstracts the consensus external API
 pub trait ConsensusApi: Send + Sync {
@@ -22,13 +34,193 @@ pub trait ConsensusApi: Send + Sync {
         update_virtual: bool,
     ) -> BoxFuture<'static, BlockProcessResult<BlockStatus>>;
 
-    /// Populates the mempool transaction with maximally found UTXO entry data and proceeds to full transaction
-    /// validation if all are found. If validation is successful, also [`calculated_fee`] is expected to be populated
-    fn validate_mempool_transaction_and_populate(self: Arc<Self>, transaction: &mut MutableTransaction) -> TxResult<()>;
+    /// Validates and inserts a batch of blocks given in topological order, processed as a group for throughput.
+    /// Results are returned in the order of the batch
+    fn validate_and_insert_blocks(
+        self: Arc<Self>,
+        blocks: Vec<Block>,
+        update_virtual: bool,
+    ) -> BoxFuture<'static, Vec<BlockProcessResult<BlockStatus>>>;
+
+    /// Begins a trusted sync, i.e., the IBD of pruned data, during which trusted blocks are accepted. Only a node holding
+    /// no blocks but genesis begins a sync, while a sync in progress is resumed. An interrupted trusted sync is detected
+    /// on the next startup
+    fn begin_trusted_sync(self: Arc<Self>) -> BlockProcessResult<()>;
+
+    /// Ends the trusted sync, after which trusted blocks are rejected
+    fn end_trusted_sync(self: Arc<Self>);
+
+    /// Inserts a block with its supplied GHOSTDAG and DAA data, skipping the validations which depend on its past.
+    /// Trusted blocks are expected in topological order and are only accepted during a trusted sync
+    fn validate_and_insert_trusted_block(self: Arc<Self>, block: TrustedBlock) -> BlockProcessResult<BlockStatus>;
+
+    /// Clears the UTXO set of the pruning point imported so far during a trusted sync, so that the import restarts
+    fn clear_imported_pruning_point_utxos(self: Arc<Self>) -> BlockProcessResult<()>;
+
+    /// Appends a chunk to the UTXO set of the pruning point imported during a trusted sync
+    fn append_imported_pruning_point_utxos(self: Arc<Self>, utxos: &[(TransactionOutpoint, UtxoEntry)]) -> BlockProcessResult<()>;
+
+    /// Completes the import of the pruning point, i.e., the last of `past_pruning_points`, once its trusted blocks were
+    /// inserted and its UTXO set was appended. The UTXO set is verified against the UTXO commitment of the pruning point,
+    /// which then becomes the pruning point of this node and the selected parent of the virtual
+    fn import_pruning_point_utxo_set(self: Arc<Self>, past_pruning_points: Vec<Hash>) -> BlockProcessResult<()>;
+
+    /// Populates the mempool transaction with maximally found UTXO entry data from the virtual UTXO set and proceeds
+    /// to full mempool-grade validation if all are found. If validation is successful, also [`calculated_fee`] and
+    /// [`calculated_mass`] are expected to be populated. The transaction is not inserted anywhere, so this can
+    /// serve as a pre-check of a transaction before submitting it
+    fn validate_mempool_transaction(self: Arc<Self>, transaction: &mut MutableTransaction) -> TxResult<()>;
 
     fn calculate_transaction_mass(self: Arc<Self>, transaction: &Transaction) -> u64;
 
     fn get_virtual_daa_score(self: Arc<Self>) -> u64;
+
+    /// Returns the parents of the virtual block, i.e., the tips a block built right now would point at
+    fn get_virtual_parents(self: Arc<Self>) -> Vec<Hash>;
+
+    fn get_virtual_info(self: Arc<Self>) -> VirtualInfo;
+
+    /// Returns the lock excluding consensus sessions from the commits of the virtual state, see
+    /// [`session::ConsensusSession`]
+    fn session_lock(self: Arc<Self>) -> SessionLock;
+
+    /// Returns up to `chunk_size` entries of the virtual UTXO set, starting right after `from_outpoint` in the UTXO set
+    /// order, or from the first entry if `None`. The whole set is retrieved by calling again with the last returned
+    /// outpoint until a partial chunk is returned
+    fn get_virtual_utxos(
+        self: Arc<Self>,
+        from_outpoint: Option<TransactionOutpoint>,
+        chunk_size: usize,
+    ) -> Vec<(TransactionOutpoint, UtxoEntry)>;
+
+    /// Re-runs header and body validations for a stored block and reports the outcome along with the
+    /// stored status. Returns `None` if the block header is not stored
+    fn revalidate_block(self: Arc<Self>, hash: Hash) -> Option<BlockRevalidationReport>;
+
+    /// Returns the coinbase a block built right now on top of the virtual would carry, paying to `miner_data`, along
+    /// with the breakdown of the rewards contributed by each merged block
+    fn get_coinbase_preview(self: Arc<Self>, miner_data: MinerData) -> CoinbaseResult<CoinbasePreview>;
+
+    /// Returns throughput statistics aggregated over the last `window_size` selected chain blocks
+    fn get_chain_throughput_stats(self: Arc<Self>, window_size: u64) -> ChainThroughputStats;
+
+    /// Returns a snapshot of the DAG state and of the number of blocks processed so far
+    fn get_block_dag_info(self: Arc<Self>) -> BlockDagInfo;
+
+    /// Returns the drift of the virtual DAA score from the consensus clock, measured from the sink timestamp
+    fn get_daa_score_drift(self: Arc<Self>) -> DaaScoreDrift;
+
+    /// Returns the max block mass of the network, used as the capacity unit for mass utilization
+    fn max_block_mass(self: Arc<Self>) -> u64;
+
+    /// Returns the status of the block, or `None` if the block is not known
+    fn get_block_status(self: Arc<Self>, hash: Hash) -> Option<BlockStatus>;
+
+    fn get_header(self: Arc<Self>, hash: Hash) -> Option<Arc<Header>>;
+
+    /// Returns the block with the given hash, or `None` if its header or body is not stored
+    fn get_block(self: Arc<Self>, hash: Hash) -> Option<Block>;
+
+    /// Returns the transaction payload with the given payload hash, or `None` if no stored transaction carries it
+    /// as a deduplicated payload, i.e., a payload of at least the deduplication threshold
+    fn get_payload(self: Arc<Self>, payload_hash: Hash) -> Option<Arc<Vec<u8>>>;
+
+    /// Returns the GHOSTDAG data and DAG relations of the block, or `None` if its header is not stored
+    fn get_block_dag_data(self: Arc<Self>, hash: Hash) -> Option<BlockDagData>;
+
+    /// Returns the GHOSTDAG data of the block, or `None` if its header is not stored
+    fn get_ghostdag_data(self: Arc<Self>, hash: Hash) -> Option<ExternalGhostdagData>;
+
+    /// Returns whether `low` is in the selected chain of `high`, a block being in its own selected chain. Fails if
+    /// one of the blocks is unknown or invalid
+    fn is_chain_ancestor_of(self: Arc<Self>, low: Hash, high: Hash) -> SyncManagerResult<bool>;
+
+    /// Returns the number of confirmations of the block, i.e., its blue score depth below the virtual selected parent,
+    /// measured from the selected chain block merging it, such that a block merged by the virtual selected parent has
+    /// a single confirmation. A transaction has the confirmations of its accepting block. Returns `None` if the block is
+    /// unknown, invalid or not yet merged by the virtual selected parent chain
+    fn get_confirmations(self: Arc<Self>, hash: Hash) -> Option<u64>;
+
+    fn get_headers_selected_tip(self: Arc<Self>) -> Hash;
+
+    /// Returns the virtual selected parent, i.e., the tip of the virtual selected chain
+    fn get_sink(self: Arc<Self>) -> Hash;
+
+    /// Hints that `hint` is a candidate to become the virtual selected parent, e.g., the highest header of a chain
+    /// being synced which is still lighter than the headers selected tip, so that reachability prepares for
+    /// extending its chain. Unknown or invalid blocks are ignored
+    fn hint_virtual_selected_parent(self: Arc<Self>, hint: Hash);
+
+    fn get_pruning_point(self: Arc<Self>) -> Hash;
+
+    /// Returns whether the node keeps the data of all blocks rather than pruning it
+    fn is_archival(self: Arc<Self>) -> bool;
+
+    /// Returns the lowest selected chain block from which this node keeps and serves the DAG, which is genesis for
+    /// archival nodes and the pruning point otherwise
+    fn get_history_root(self: Arc<Self>) -> Hash;
+
+    /// Pins blocks, so that they are kept along with their data when pruning until unpinned. Fails without pinning
+    /// any block if one of them is not known
+    fn pin_blocks(self: Arc<Self>, hashes: &[Hash]) -> SyncManagerResult<()>;
+
+    /// Unpins blocks, letting them be pruned. Blocks which are not pinned are ignored
+    fn unpin_blocks(self: Arc<Self>, hashes: &[Hash]);
+
+    fn get_pinned_blocks(self: Arc<Self>) -> Vec<Hash>;
+
+    /// Returns the hashes of the blocks in the future of `low` and in the past of `high`, both included, in topological
+    /// order. Fails if `low` is not in the past of `high` or if the region holds more than `max_blocks` blocks
+    fn get_block_region(self: Arc<Self>, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<Vec<Hash>>;
+
+    /// Returns a locator of the headers selected chain between `low` and `high`, ordered from `high` down
+    /// to `low`. `low` defaults to the pruning point and `high` to the headers selected tip
+    fn create_headers_selected_chain_block_locator(
+        self: Arc<Self>,
+        low: Option<Hash>,
+        high: Option<Hash>,
+    ) -> SyncManagerResult<Vec<Hash>>;
+
+    /// Returns up to about `max_blocks` hashes of blocks in the past of `high` and not in the past of `low`, in
+    /// topological order, along with the highest selected chain block reached. `low` must be in the selected chain of `high`
+    fn get_hashes_between(self: Arc<Self>, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<(Vec<Hash>, Hash)>;
+
+    /// Returns the hashes of header-only blocks between the pruning point and `high`, in topological order
+    fn get_missing_block_body_hashes(self: Arc<Self>, high: Hash) -> SyncManagerResult<Vec<Hash>>;
+
+    /// Returns the trusted data a node syncing from scratch imports along with the UTXO set of the pruning point
+    fn get_pruning_point_trusted_data(self: Arc<Self>) -> SyncManagerResult<PruningPointTrustedData>;
+
+    /// Returns up to `chunk_size` entries of the UTXO set of `pruning_point`, chunked as by [`Self::get_virtual_utxos`].
+    /// Fails if `pruning_point` is not the pruning point anymore, or if its UTXO set is not available yet
+    fn get_pruning_point_utxos(
+        self: Arc<Self>,
+        pruning_point: Hash,
+        from_outpoint: Option<TransactionOutpoint>,
+        chunk_size: usize,
+    ) -> SyncManagerResult<Vec<(TransactionOutpoint, UtxoEntry)>>;
+
+    /// Returns the changes turning the selected chain of `start` into the virtual selected parent chain, with at most
+    /// `max_added_blocks` added chain blocks and about `max_accepted_transaction_ids` accepted transaction ids. Further
+    /// changes are retrieved by calling again with the last added chain block as `start`
+    fn get_virtual_chain_from_block(
+        self: Arc<Self>,
+        start: Hash,
+        include_accepted_transaction_ids: bool,
+        max_added_blocks: usize,
+        max_accepted_transaction_ids: usize,
+    ) -> SyncManagerResult<VirtualChainBatch>;
+
+    /// Returns the selected chain blocks of `high` above `low` along with their GHOSTDAG and acceptance data, in low-to-high
+    /// order, with at most `max_blocks` blocks. `high` defaults to the virtual selected parent and must be in the virtual
+    /// chain, since only its blocks are guaranteed to hold acceptance data. Further blocks are retrieved by calling again
+    /// with the last returned block as `low`
+    fn get_chain_blocks_with_acceptance(
+        self: Arc<Self>,
+        low: Hash,
+        high: Option<Hash>,
+        max_blocks: usize,
+    ) -> SyncManagerResult<ChainBlocksBatch>;
 }
 
 pub type DynConsensus = Arc<dyn ConsensusApi>;
@@ -0,0 +1,89 @@
+use super::DynConsensus;
+use crate::{
+    block::{Block, BlockDagData, VirtualInfo},
+    blockstatus::BlockStatus,
+    errors::sync::SyncManagerResult,
+    ghostdag::ExternalGhostdagData,
+    header::Header,
+};
+use hashes::Hash;
+use parking_lot::{lock_api::ArcRwLockReadGuard, RawRwLock, RwLock, RwLockWriteGuard};
+use std::sync::Arc;
+
+pub type SessionReadGuard = ArcRwLockReadGuard<RawRwLock, ()>;
+
+/// Excludes consensus sessions from the commits of the virtual state
+#[derive(Clone, Default)]
+pub struct SessionLock(Arc<RwLock<()>>);
+
+impl SessionLock {
+    pub fn read_owned(&self) -> SessionReadGuard {
+        self.0.read_arc()
+    }
+
+    /// Waits for the active sessions to end, blocking new ones until the guard is dropped
+    pub fn write(&self) -> RwLockWriteGuard<'_, ()> {
+        self.0.write()
+    }
+}
+
+/// A read-only view of consensus for external consumers such as RPC, indexes and the mempool.
+///
+/// The virtual state is not committed while a session is held, so all queries of the session observe the same virtual
+/// state and virtual selected chain. Since sessions delay virtual processing they should be short lived, and since a
+/// pending commit blocks new sessions, a thread must not acquire a session while holding another one.
+pub struct ConsensusSession {
+    _guard: SessionReadGuard,
+    consensus: DynConsensus,
+}
+
+impl ConsensusSession {
+    /// Acquires a session, waiting for an ongoing commit of the virtual state to end
+    pub fn acquire(consensus: &DynConsensus) -> Self {
+        let guard = consensus.clone().session_lock().read_owned();
+        Self { _guard: guard, consensus: consensus.clone() }
+    }
+
+    /// Returns the status of the block, or `None` if the block is not known
+    pub fn get_block_status(&self, hash: Hash) -> Option<BlockStatus> {
+        self.consensus.clone().get_block_status(hash)
+    }
+
+    pub fn get_header(&self, hash: Hash) -> Option<Arc<Header>> {
+        self.consensus.clone().get_header(hash)
+    }
+
+    /// Returns the block with the given hash, or `None` if its header or body is not stored
+    pub fn get_block(&self, hash: Hash) -> Option<Block> {
+        self.consensus.clone().get_block(hash)
+    }
+
+    /// Returns the GHOSTDAG data and DAG relations of the block, or `None` if its header is not stored
+    pub fn get_block_dag_data(&self, hash: Hash) -> Option<BlockDagData> {
+        self.consensus.clone().get_block_dag_data(hash)
+    }
+
+    /// Returns the GHOSTDAG data of the block, or `None` if its header is not stored
+    pub fn get_ghostdag_data(&self, hash: Hash) -> Option<ExternalGhostdagData> {
+        self.consensus.clone().get_ghostdag_data(hash)
+    }
+
+    /// Returns the number of confirmations of the block, see [`super::ConsensusApi::get_confirmations`]
+    pub fn get_confirmations(&self, hash: Hash) -> Option<u64> {
+        self.consensus.clone().get_confirmations(hash)
+    }
+
+    /// Returns whether `low` is in the selected chain of `high`, a block being in its own selected chain
+    pub fn is_chain_ancestor_of(&self, low: Hash, high: Hash) -> SyncManagerResult<bool> {
+        self.consensus.clone().is_chain_ancestor_of(low, high)
+    }
+
+    /// Returns the virtual selected parent, i.e., the tip of the virtual selected chain
+    pub fn get_sink(&self) -> Hash {
+        self.consensus.clone().get_sink()
+    }
+
+    pub fn get_virtual_info(&self) -> VirtualInfo {
+        self.consensus.clone().get_virtual_info()
+    }
+}
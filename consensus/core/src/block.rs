@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{coinbase::MinerData, header::Header, tx::Transaction};
+use crate::{coinbase::MinerData, ghostdag::ExternalGhostdagData, hashing, header::Header, time::TimestampMs, tx::Transaction};
 use hashes::Hash;
 
 /// A mutable block structure where header and transactions within can still be mutated.
@@ -56,16 +56,76 @@ impl Block {
 }
 
 /// A block template for miners.
+///
+/// The GHOSTDAG data and DAG relations of a stored block, as reported to RPC clients along with the block
+#[derive(Debug, Clone)]
+pub struct BlockDagData {
+    pub is_header_only: bool,
+    /// The difficulty of the block, relative to the maximal target
+    pub difficulty: f64,
+    pub selected_parent: Hash,
+    pub blue_score: u64,
+    pub mergeset_blues: Vec<Hash>,
+    pub mergeset_reds: Vec<Hash>,
+    pub children: Vec<Hash>,
+    /// Whether the block is in the selected chain of the virtual
+    pub is_chain_block: bool,
+}
+
+/// The state of the virtual block, i.e., of a block built right now on top of all tips
+#[derive(Debug, Clone)]
+pub struct VirtualInfo {
+    pub parents: Vec<Hash>,
+    pub ghostdag_data: ExternalGhostdagData,
+    pub daa_score: u64,
+    pub bits: u32,
+    pub past_median_time: TimestampMs,
+}
+
+/// External miners are expected to compute [`BlockTemplate::pre_pow_hash`] once per template, roll
+/// nonces (and, when needed, timestamps) locally, and finally seal the solved block using
+/// [`BlockTemplate::with_nonce_and_time`]:
+///
+/// ```ignore
+/// let pre_pow_hash = template.pre_pow_hash();
+/// let mut state = pow::State::from_pre_pow_hash(pre_pow_hash, timestamp, template.block.header.bits);
+/// let nonce = (0..).find(|&nonce| state.check_pow(nonce).0).unwrap();
+/// let block = template.with_nonce_and_time(nonce, timestamp).block.to_immutable();
+/// ```
 #[derive(Debug, Clone)]
 pub struct BlockTemplate {
     pub block: MutableBlock,
     pub miner_data: MinerData,
     pub coinbase_has_red_reward: bool,
-    pub selected_parent_timestamp: u64,
+    pub selected_parent_timestamp: TimestampMs,
 }
 
 impl BlockTemplate {
-    pub fn new(block: MutableBlock, miner_data: MinerData, coinbase_has_red_reward: bool, selected_parent_timestamp: u64) -> Self {
+    pub fn new(
+        block: MutableBlock,
+        miner_data: MinerData,
+        coinbase_has_red_reward: bool,
+        selected_parent_timestamp: TimestampMs,
+    ) -> Self {
         Self { block, miner_data, coinbase_has_red_reward, selected_parent_timestamp }
     }
+
+    /// Returns the template with the header nonce and timestamp set to the provided values and the header hash recomputed
+    pub fn with_nonce_and_time(mut self, nonce: u64, timestamp: TimestampMs) -> Self {
+        self.block.header.nonce = nonce;
+        self.block.header.timestamp = timestamp;
+        self.block.header.finalize();
+        self
+    }
+
+    /// Returns the pre-PoW hash of the template header. This hash does not depend on the header
+    /// nonce and timestamp and thus remains valid while rolling them.
+    pub fn pre_pow_hash(&self) -> Hash {
+        hashing::header::pre_pow_hash(&self.block.header)
+    }
+
+    /// Returns the pre-PoW hash serialized as raw bytes, as expected by external mining software
+    pub fn serialized_pre_pow_hash(&self) -> Vec<u8> {
+        self.pre_pow_hash().as_bytes().to_vec()
+    }
 }
@@ -0,0 +1,51 @@
+use crate::{block::Block, tx::TransactionId, BlueWorkType};
+use hashes::Hash;
+use serde::{Deserialize, Serialize};
+
+/// The transactions of a merged block which were accepted by the merging chain block
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergesetBlockAcceptanceData {
+    pub block_hash: Hash,
+    /// The ids of accepted transactions in block order. For the selected parent this includes its coinbase transaction.
+    pub accepted_transaction_ids: Vec<TransactionId>,
+}
+
+/// The transactions accepted by a chain block, grouped by merged block in consensus order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainBlockAcceptanceData {
+    pub accepting_block_hash: Hash,
+    pub mergeset: Vec<MergesetBlockAcceptanceData>,
+}
+
+/// A batch of the changes turning the selected chain of some start block into the virtual selected parent chain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VirtualChainBatch {
+    /// The start block selected chain blocks which are not in the virtual chain, in high-to-low order
+    pub removed_chain_block_hashes: Vec<Hash>,
+    /// The virtual chain blocks above the start block selected chain, in low-to-high order
+    pub added_chain_block_hashes: Vec<Hash>,
+    /// The acceptance data of each added chain block, if requested
+    pub accepted_transaction_ids: Vec<ChainBlockAcceptanceData>,
+    /// Indicates that the batch limits were reached before the virtual selected parent. The next batch
+    /// starts from the last added chain block.
+    pub has_more: bool,
+}
+
+/// A selected chain block along with its GHOSTDAG data and the transactions it accepted
+#[derive(Debug, Clone)]
+pub struct ChainBlockWithAcceptance {
+    pub block: Block,
+    pub blue_score: u64,
+    pub blue_work: BlueWorkType,
+    pub selected_parent: Hash,
+    /// The transactions accepted by the block, grouped by merged block in consensus order
+    pub mergeset: Vec<MergesetBlockAcceptanceData>,
+}
+
+/// A batch of consecutive selected chain blocks in low-to-high order
+#[derive(Debug, Clone, Default)]
+pub struct ChainBlocksBatch {
+    pub blocks: Vec<ChainBlockWithAcceptance>,
+    /// Indicates that the batch limit was reached before the high block. The next batch starts from the last block.
+    pub has_more: bool,
+}
@@ -1,4 +1,5 @@
-use crate::tx::{ScriptPublicKey, Transaction};
+use crate::tx::{ScriptPublicKey, Transaction, TransactionOutput};
+use hashes::Hash;
 use serde::{Deserialize, Serialize};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -38,3 +39,26 @@ pub struct CoinbaseTransactionTemplate {
     pub tx: Transaction,
     pub has_red_reward: bool, // Does the last output contain reward for red blocks
 }
+
+/// The reward a single merged block contributes to the coinbase transaction of the merging block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergesetBlockReward {
+    pub hash: Hash,
+    pub is_blue: bool,
+    pub subsidy: u64,
+    pub total_fees: u64,
+    /// The script the reward is paid to. Rewards of red blocks are paid to the merging block miner
+    pub script_public_key: ScriptPublicKey,
+}
+
+/// The coinbase a block built on top of the current virtual would carry, along with the
+/// per merged block breakdown of the rewards it pays
+#[derive(Clone, Debug)]
+pub struct CoinbasePreview {
+    pub daa_score: u64,
+    pub blue_score: u64,
+    /// The subsidy of the merging block itself, paid by a future block merging it
+    pub subsidy: u64,
+    pub merged_block_rewards: Vec<MergesetBlockReward>,
+    pub outputs: Vec<TransactionOutput>,
+}
@@ -0,0 +1,252 @@
+//! The genesis blocks of the known networks.
+//!
+//! A genesis block is defined by its header fields and the payload of its single coinbase transaction, from which its
+//! hash is derived. The hash is stored alongside so that the params can be declared as constants, and [`GenesisBlock::verify`]
+//! checks that it matches the serialized header, which guards against a misconfigured network.
+
+use crate::{header::Header, merkle::calc_hash_merkle_root, subnets::SUBNETWORK_ID_COINBASE, time::TimestampMs, tx::Transaction};
+use hashes::{Hash, ZERO_HASH};
+use muhash::EMPTY_MUHASH;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GenesisError {
+    #[error("the configured genesis hash {0} does not match the hash {1} of the genesis header")]
+    HashMismatch(Hash, Hash),
+
+    #[error("the genesis hash merkle root {0} does not match the root {1} of the genesis coinbase transaction")]
+    HashMerkleRootMismatch(Hash, Hash),
+}
+
+/// The header fields and the coinbase payload of a genesis block, along with its precomputed hash
+#[derive(Clone, Debug)]
+pub struct GenesisBlock {
+    pub hash: Hash,
+    pub version: u16,
+    pub hash_merkle_root: Hash,
+    pub utxo_commitment: Hash,
+    pub timestamp: TimestampMs,
+    pub bits: u32,
+    pub nonce: u64,
+    pub daa_score: u64,
+    pub coinbase_payload: &'static [u8],
+}
+
+impl GenesisBlock {
+    /// Builds the transactions of the genesis block, which consist of a single coinbase transaction with no outputs
+    pub fn build_genesis_transactions(&self) -> Vec<Transaction> {
+        vec![Transaction::new(0, Vec::new(), Vec::new(), 0, SUBNETWORK_ID_COINBASE, 0, self.coinbase_payload.to_vec())]
+    }
+
+    /// Verifies that the precomputed hash and the hash merkle root match the genesis header and coinbase transaction
+    pub fn verify(&self) -> Result<(), GenesisError> {
+        let hash_merkle_root = calc_hash_merkle_root(self.build_genesis_transactions().iter());
+        if hash_merkle_root != self.hash_merkle_root {
+            return Err(GenesisError::HashMerkleRootMismatch(self.hash_merkle_root, hash_merkle_root));
+        }
+        let mut header = Header::from(self);
+        header.finalize();
+        if header.hash != self.hash {
+            return Err(GenesisError::HashMismatch(self.hash, header.hash));
+        }
+        Ok(())
+    }
+}
+
+impl From<&GenesisBlock> for Header {
+    fn from(genesis: &GenesisBlock) -> Self {
+        Header {
+            hash: genesis.hash,
+            version: genesis.version,
+            parents_by_level: Vec::new(),
+            hash_merkle_root: genesis.hash_merkle_root,
+            accepted_id_merkle_root: ZERO_HASH,
+            utxo_commitment: genesis.utxo_commitment,
+            timestamp: genesis.timestamp,
+            bits: genesis.bits,
+            nonce: genesis.nonce,
+            daa_score: genesis.daa_score,
+            blue_work: 0.into(),
+            blue_score: 0,
+            pruning_point: ZERO_HASH,
+        }
+    }
+}
+
+/// The genesis of mainnet. The network was relaunched from a checkpoint of the previous chain, hence the DAA score
+/// and the UTXO commitment of its genesis
+pub const GENESIS: GenesisBlock = GenesisBlock {
+    hash: Hash::from_bytes([
+        0x58, 0xc2, 0xd4, 0x19, 0x9e, 0x21, 0xf9, 0x10, 0xd1, 0x57, 0x1d, 0x11, 0x49, 0x69, 0xce, 0xce, 0xf4, 0x8f, 0x09, 0xf9, 0x34,
+        0xd4, 0x2c, 0xcb, 0x6a, 0x28, 0x1a, 0x15, 0x86, 0x8f, 0x29, 0x99,
+    ]),
+    version: 0,
+    hash_merkle_root: Hash::from_bytes([
+        0x8e, 0xc8, 0x98, 0x56, 0x8c, 0x68, 0x01, 0xd1, 0x3d, 0xf4, 0xee, 0x6e, 0x2a, 0x1b, 0x54, 0xb7, 0xe6, 0x23, 0x6f, 0x67, 0x1f,
+        0x20, 0x95, 0x4f, 0x05, 0x30, 0x64, 0x10, 0x51, 0x8e, 0xeb, 0x32,
+    ]),
+    utxo_commitment: Hash::from_bytes([
+        0x71, 0x0f, 0x27, 0xdf, 0x42, 0x3e, 0x63, 0xaa, 0x6c, 0xdb, 0x72, 0xb8, 0x9e, 0xa5, 0xa0, 0x6c, 0xff, 0xa3, 0x99, 0xd6, 0x6f,
+        0x16, 0x77, 0x04, 0x45, 0x5b, 0x5a, 0xf5, 0x9d, 0xef, 0x8e, 0x20,
+    ]),
+    timestamp: TimestampMs::from_millis(1637609671037),
+    bits: 486722099,
+    nonce: 0x3392c,
+    daa_score: 1312860,
+    coinbase_payload: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Blue score
+        0x00, 0xE1, 0xF5, 0x05, 0x00, 0x00, 0x00, 0x00, // Subsidy
+        0x00, 0x00, // Script version
+        0x01, // Varint
+        0x00, // OP-FALSE
+        // ומה די עליך ועל אחיך ייטב בשאר כספא ודהבה למעבד כרעות אלהכם תעבדון, followed by the hash of bitcoin
+        // block 0000000000000000000b1f8e1c17b0133d439174e52efbb0c41c3583a8aa66b0 and the hash of the checkpoint block
+        // 0fca37ca667c2d550a6c4416dad9717e50927128c424fa4edbebc436ab13aeef
+        0xd7, 0x95, 0xd7, 0x9e, 0xd7, 0x94, 0x20, 0xd7, 0x93, 0xd7, 0x99, 0x20, 0xd7, 0xa2, 0xd7, 0x9c, 0xd7, 0x99, 0xd7, 0x9a, 0x20,
+        0xd7, 0x95, 0xd7, 0xa2, 0xd7, 0x9c, 0x20, 0xd7, 0x90, 0xd7, 0x97, 0xd7, 0x99, 0xd7, 0x9a, 0x20, 0xd7, 0x99, 0xd7, 0x99, 0xd7,
+        0x98, 0xd7, 0x91, 0x20, 0xd7, 0x91, 0xd7, 0xa9, 0xd7, 0x90, 0xd7, 0xa8, 0x20, 0xd7, 0x9b, 0xd7, 0xa1, 0xd7, 0xa4, 0xd7, 0x90,
+        0x20, 0xd7, 0x95, 0xd7, 0x93, 0xd7, 0x94, 0xd7, 0x91, 0xd7, 0x94, 0x20, 0xd7, 0x9c, 0xd7, 0x9e, 0xd7, 0xa2, 0xd7, 0x91, 0xd7,
+        0x93, 0x20, 0xd7, 0x9b, 0xd7, 0xa8, 0xd7, 0xa2, 0xd7, 0x95, 0xd7, 0xaa, 0x20, 0xd7, 0x90, 0xd7, 0x9c, 0xd7, 0x94, 0xd7, 0x9b,
+        0xd7, 0x9d, 0x20, 0xd7, 0xaa, 0xd7, 0xa2, 0xd7, 0x91, 0xd7, 0x93, 0xd7, 0x95, 0xd7, 0x9f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x0b, 0x1f, 0x8e, 0x1c, 0x17, 0xb0, 0x13, 0x3d, 0x43, 0x91, 0x74, 0xe5, 0x2e, 0xfb, 0xb0, 0xc4, 0x1c, 0x35,
+        0x83, 0xa8, 0xaa, 0x66, 0xb0, 0x0f, 0xca, 0x37, 0xca, 0x66, 0x7c, 0x2d, 0x55, 0x0a, 0x6c, 0x44, 0x16, 0xda, 0xd9, 0x71, 0x7e,
+        0x50, 0x92, 0x71, 0x28, 0xc4, 0x24, 0xfa, 0x4e, 0xdb, 0xeb, 0xc4, 0x36, 0xab, 0x13, 0xae, 0xef,
+    ],
+};
+
+/// The genesis of testnet-10
+pub const TESTNET_GENESIS: GenesisBlock = GenesisBlock {
+    hash: Hash::from_bytes([
+        0xf8, 0x96, 0xa3, 0x03, 0x48, 0x73, 0xbe, 0x17, 0x39, 0xfc, 0x43, 0x59, 0x23, 0x68, 0x99, 0xfd, 0x3d, 0x65, 0xd2, 0xbc, 0x94,
+        0xf9, 0x78, 0x0d, 0xf0, 0xd0, 0xda, 0x3e, 0xb1, 0xcc, 0x43, 0x70,
+    ]),
+    version: 0,
+    hash_merkle_root: Hash::from_bytes([
+        0x17, 0x34, 0x14, 0x08, 0xa5, 0x72, 0x45, 0x56, 0x50, 0x4d, 0xf4, 0xd6, 0xcf, 0x51, 0x5c, 0xbf, 0xbb, 0x22, 0x04, 0x30, 0xdc,
+        0x45, 0x1c, 0x74, 0x3c, 0x22, 0xd5, 0xe9, 0x11, 0x72, 0x0c, 0x2a,
+    ]),
+    utxo_commitment: EMPTY_MUHASH,
+    timestamp: TimestampMs::from_millis(0x17c5f62fbb6),
+    bits: 0x1e7fffff,
+    nonce: 0x14582,
+    daa_score: 0,
+    coinbase_payload: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Blue score
+        0x00, 0xE1, 0xF5, 0x05, 0x00, 0x00, 0x00, 0x00, // Subsidy
+        0x00, 0x00, // Script version
+        0x01, // Varint
+        0x00, // OP-FALSE
+        // kaspa-testnet
+        0x6b, 0x61, 0x73, 0x70, 0x61, 0x2d, 0x74, 0x65, 0x73, 0x74, 0x6e, 0x65, 0x74,
+    ],
+};
+
+/// The genesis of testnet-11, relaunched with a distinct payload suffix and a lower genesis difficulty
+pub const TESTNET11_GENESIS: GenesisBlock = GenesisBlock {
+    hash: Hash::from_bytes([
+        0xcf, 0xa2, 0xa7, 0xeb, 0xfb, 0x8b, 0x4e, 0xa3, 0x45, 0x30, 0x7b, 0xc2, 0x5e, 0xf9, 0x42, 0x1b, 0x23, 0x91, 0xf0, 0x9c, 0x8b,
+        0x2c, 0xf2, 0x15, 0xf0, 0x75, 0x60, 0xaf, 0x0d, 0x4d, 0x71, 0x64,
+    ]),
+    version: 0,
+    hash_merkle_root: Hash::from_bytes([
+        0x3c, 0x35, 0xdb, 0x98, 0x02, 0x7e, 0x84, 0x6e, 0x02, 0xcc, 0x60, 0xb7, 0xa7, 0xfa, 0xb1, 0x6d, 0x6c, 0xf2, 0xa5, 0x42, 0xd8,
+        0xe1, 0x60, 0xad, 0x9c, 0xd9, 0x08, 0x5f, 0x51, 0x0c, 0x47, 0xbb,
+    ]),
+    utxo_commitment: EMPTY_MUHASH,
+    timestamp: TimestampMs::from_millis(0x17c5f62fbb6),
+    bits: 0x1e0ccccc,
+    nonce: 0x14582,
+    daa_score: 0,
+    coinbase_payload: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Blue score
+        0x00, 0xE1, 0xF5, 0x05, 0x00, 0x00, 0x00, 0x00, // Subsidy
+        0x00, 0x00, // Script version
+        0x01, // Varint
+        0x00, // OP-FALSE
+        // kaspa-testnet, followed by the testnet suffix 11 and the relaunch number 4
+        0x6b, 0x61, 0x73, 0x70, 0x61, 0x2d, 0x74, 0x65, 0x73, 0x74, 0x6e, 0x65, 0x74, 0x0b, 0x04,
+    ],
+};
+
+/// The genesis of devnet
+pub const DEVNET_GENESIS: GenesisBlock = GenesisBlock {
+    hash: Hash::from_bytes([
+        0xb3, 0x13, 0x87, 0x0a, 0x32, 0xc7, 0x04, 0xbd, 0xf1, 0x21, 0x4a, 0x3b, 0x27, 0x0c, 0xc4, 0x75, 0xd9, 0x42, 0xc2, 0x09, 0x2d,
+        0x37, 0x9b, 0xc8, 0x70, 0x0a, 0xb0, 0x43, 0x31, 0x9e, 0xf8, 0x46,
+    ]),
+    version: 0,
+    hash_merkle_root: Hash::from_bytes([
+        0x58, 0xab, 0xf2, 0x03, 0x21, 0xd7, 0x07, 0x16, 0x16, 0x2b, 0x6b, 0xf8, 0xd9, 0xf5, 0x89, 0xca, 0x33, 0xae, 0x6e, 0x32, 0xb3,
+        0xb1, 0x9a, 0xbb, 0x7f, 0xa6, 0x5d, 0x11, 0x41, 0xa3, 0xf9, 0x4d,
+    ]),
+    utxo_commitment: EMPTY_MUHASH,
+    timestamp: TimestampMs::from_millis(0x11e9db49828),
+    bits: 0x1f4ee5fb,
+    nonce: 0x48e5e,
+    daa_score: 0,
+    coinbase_payload: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Blue score
+        0x00, 0xE1, 0xF5, 0x05, 0x00, 0x00, 0x00, 0x00, // Subsidy
+        0x00, 0x00, // Script version
+        0x01, // Varint
+        0x00, // OP-FALSE
+        // kaspa-devnet
+        0x6b, 0x61, 0x73, 0x70, 0x61, 0x2d, 0x64, 0x65, 0x76, 0x6e, 0x65, 0x74,
+    ],
+};
+
+/// The genesis of simnet
+pub const SIMNET_GENESIS: GenesisBlock = GenesisBlock {
+    hash: Hash::from_bytes([
+        0x41, 0x1f, 0x8c, 0xd2, 0x6f, 0x3d, 0x41, 0xae, 0xa3, 0x9e, 0x78, 0x57, 0x39, 0x27, 0xda, 0x24, 0xd2, 0x39, 0x95, 0x70, 0x5b,
+        0x57, 0x9f, 0x30, 0x95, 0x9b, 0x91, 0x27, 0xe9, 0x6b, 0x79, 0xe3,
+    ]),
+    version: 0,
+    hash_merkle_root: Hash::from_bytes([
+        0x19, 0x46, 0xd6, 0x29, 0xf7, 0xe9, 0x22, 0xa7, 0xbc, 0xed, 0x59, 0x19, 0x05, 0x21, 0xc3, 0x77, 0x1f, 0x73, 0xd3, 0x52, 0xdd,
+        0xbb, 0xb6, 0x86, 0x56, 0x4a, 0xd7, 0xfd, 0x56, 0x85, 0x7c, 0x1b,
+    ]),
+    utxo_commitment: EMPTY_MUHASH,
+    timestamp: TimestampMs::from_millis(0x17c5f62fbb6),
+    bits: 0x207fffff,
+    nonce: 0x2,
+    daa_score: 0,
+    coinbase_payload: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Blue score
+        0x00, 0xE1, 0xF5, 0x05, 0x00, 0x00, 0x00, 0x00, // Subsidy
+        0x00, 0x00, // Script version
+        0x01, // Varint
+        0x00, // OP-FALSE
+        // kaspa-simnet
+        0x6b, 0x61, 0x73, 0x70, 0x61, 0x2d, 0x73, 0x69, 0x6d, 0x6e, 0x65, 0x74,
+    ],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::params::{DEVNET_PARAMS, MAINNET_PARAMS, SIMNET_PARAMS, TESTNET_PARAMS};
+
+    #[test]
+    fn test_genesis_hashes() {
+        for genesis in [GENESIS, TESTNET_GENESIS, TESTNET11_GENESIS, DEVNET_GENESIS, SIMNET_GENESIS] {
+            assert_eq!(genesis.verify(), Ok(()));
+        }
+        for params in [MAINNET_PARAMS, TESTNET_PARAMS, DEVNET_PARAMS, SIMNET_PARAMS] {
+            assert!(params.genesis.coinbase_payload.len() <= params.max_coinbase_payload_len, "{}", params.network_name);
+        }
+        assert_eq!(GENESIS.hash.to_string(), "58c2d4199e21f910d1571d114969cecef48f09f934d42ccb6a281a15868f2999");
+        assert_eq!(TESTNET_GENESIS.hash.to_string(), "f896a3034873be1739fc4359236899fd3d65d2bc94f9780df0d0da3eb1cc4370");
+    }
+
+    #[test]
+    fn test_genesis_mismatch() {
+        let mut genesis = DEVNET_GENESIS;
+        genesis.nonce += 1;
+        assert!(matches!(genesis.verify(), Err(GenesisError::HashMismatch(..))));
+
+        let mut genesis = DEVNET_GENESIS;
+        genesis.coinbase_payload = SIMNET_GENESIS.coinbase_payload;
+        assert!(matches!(genesis.verify(), Err(GenesisError::HashMerkleRootMismatch(..))));
+    }
+}
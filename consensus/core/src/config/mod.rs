@@ -0,0 +1,91 @@
+pub mod genesis;
+pub mod params;
+
+use crate::time::DurationMs;
+use params::Params;
+use std::ops::Deref;
+
+/// Local overrides of the params of a network, for running devnets and simulations with custom parameters. A node
+/// overriding the params of a public network cannot follow that network.
+#[derive(Clone, Debug, Default)]
+pub struct ParamsOverrides {
+    pub target_time_per_block: Option<DurationMs>,
+    pub max_block_mass: Option<u64>,
+    pub coinbase_maturity: Option<u64>,
+    pub skip_proof_of_work: Option<bool>,
+}
+
+impl ParamsOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.target_time_per_block.is_none()
+            && self.max_block_mass.is_none()
+            && self.coinbase_maturity.is_none()
+            && self.skip_proof_of_work.is_none()
+    }
+}
+
+/// The params of a network along with their local overrides. Dereferences to the resulting params.
+#[derive(Clone)]
+pub struct Config {
+    params: Params,
+    overrides: ParamsOverrides,
+}
+
+impl Config {
+    pub fn new(params: Params) -> Self {
+        Self::with_overrides(params, ParamsOverrides::default())
+    }
+
+    pub fn with_overrides(mut params: Params, overrides: ParamsOverrides) -> Self {
+        if let Some(target_time_per_block) = overrides.target_time_per_block {
+            params.target_time_per_block = target_time_per_block;
+        }
+        if let Some(max_block_mass) = overrides.max_block_mass {
+            params.max_block_mass = max_block_mass;
+        }
+        if let Some(coinbase_maturity) = overrides.coinbase_maturity {
+            params.coinbase_maturity = coinbase_maturity;
+        }
+        if let Some(skip_proof_of_work) = overrides.skip_proof_of_work {
+            params.skip_proof_of_work = skip_proof_of_work;
+        }
+        Self { params, overrides }
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    pub fn overrides(&self) -> &ParamsOverrides {
+        &self.overrides
+    }
+}
+
+impl Deref for Config {
+    type Target = Params;
+
+    fn deref(&self) -> &Self::Target {
+        &self.params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{params::DEVNET_PARAMS, *};
+
+    #[test]
+    fn test_config_overrides() {
+        let config = Config::new(DEVNET_PARAMS);
+        assert!(config.overrides().is_empty());
+        assert_eq!(config.max_block_mass, DEVNET_PARAMS.max_block_mass);
+
+        let overrides =
+            ParamsOverrides { max_block_mass: Some(1_000_000), skip_proof_of_work: Some(true), ..ParamsOverrides::default() };
+        let config = Config::with_overrides(DEVNET_PARAMS, overrides);
+        assert!(!config.overrides().is_empty());
+        assert_eq!(config.max_block_mass, 1_000_000);
+        assert!(config.skip_proof_of_work);
+        assert_eq!(config.coinbase_maturity, DEVNET_PARAMS.coinbase_maturity);
+        assert_eq!(config.params().target_time_per_block, DEVNET_PARAMS.target_time_per_block);
+    }
+}
@@ -0,0 +1,245 @@
+use super::genesis::{GenesisBlock, DEVNET_GENESIS, GENESIS, SIMNET_GENESIS, TESTNET_GENESIS};
+use crate::{ghostdag::KType, time::DurationMs};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Clone)]
+pub struct Params {
+    /// The name of the network, as advertised to peers and RPC clients
+    pub network_name: &'static str,
+    pub genesis: GenesisBlock,
+    pub ghostdag_k: KType,
+    pub timestamp_deviation_tolerance: u64,
+    pub target_time_per_block: DurationMs,
+    pub max_block_parents: u8,
+    pub difficulty_window_size: usize,
+    /// The DAA score of the selected parent from which on the difficulty and past median time windows of a block are
+    /// sampled rather than full
+    pub sampling_activation_daa_score: u64,
+    /// Once sampling is active, only blocks whose DAA score is a multiple of the rate are sampled into a difficulty window
+    pub difficulty_sample_rate: u64,
+    /// Once sampling is active, only blocks whose DAA score is a multiple of the rate are sampled into a past median time window
+    pub past_median_time_sample_rate: u64,
+    pub mergeset_size_limit: u64,
+    pub merge_depth: u64,
+    pub finality_depth: u64,
+    pub pruning_depth: u64,
+    pub coinbase_payload_script_public_key_max_len: u8,
+    pub max_coinbase_payload_len: usize,
+    pub max_tx_inputs: usize,
+    pub max_tx_outputs: usize,
+    pub max_signature_script_len: usize,
+    pub max_script_public_key_len: usize,
+    pub mass_per_tx_byte: u64,
+    pub mass_per_script_pub_key_byte: u64,
+    pub mass_per_sig_op: u64,
+    pub storage_mass_per_utxo: u64,
+    pub max_block_mass: u64,
+    pub deflationary_phase_daa_score: u64,
+    pub pre_deflationary_phase_base_subsidy: u64,
+    pub coinbase_maturity: u64,
+    pub skip_proof_of_work: bool,
+    pub max_block_level: u8,
+}
+
+impl Params {
+    /// Clones the params instance and sets `skip_proof_of_work = true`.
+    /// Should be used for testing purposes only.
+    pub fn clone_with_skip_pow(&self) -> Self {
+        let mut cloned_params = self.clone();
+        cloned_params.skip_proof_of_work = true;
+        cloned_params
+    }
+
+    /// Returns the sizes and sample rate of the difficulty window
+    pub fn difficulty_window(&self) -> WindowParams {
+        WindowParams::new(self.difficulty_window_size, self.difficulty_sample_rate)
+    }
+
+    /// Returns the sizes and sample rate of the past median time window
+    pub fn past_median_time_window(&self) -> WindowParams {
+        // TODO: incorporate target_time_per_block to this calculation
+        WindowParams::new((2 * self.timestamp_deviation_tolerance - 1) as usize, self.past_median_time_sample_rate)
+    }
+}
+
+/// The sizes of a block window type, both full and sampled, along with its sample rate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowParams {
+    /// The number of blocks of a full window
+    pub size: usize,
+    /// The number of blocks of a sampled window, spanning about as many blocks as a full window
+    pub sampled_size: usize,
+    pub sample_rate: u64,
+}
+
+impl WindowParams {
+    pub fn new(size: usize, sample_rate: u64) -> Self {
+        assert!(sample_rate > 0, "the sample rate of a window must be positive");
+        let sample_rate_as_size = sample_rate as usize;
+        Self { size, sampled_size: (size + sample_rate_as_size - 1) / sample_rate_as_size, sample_rate }
+    }
+}
+
+const DEFAULT_GHOSTDAG_K: KType = 18;
+pub const MAINNET_PARAMS: Params = Params {
+    network_name: "kaspa-mainnet",
+    genesis: GENESIS,
+    ghostdag_k: DEFAULT_GHOSTDAG_K,
+    timestamp_deviation_tolerance: 132,
+    target_time_per_block: DurationMs::from_millis(1000),
+    max_block_parents: 10,
+    difficulty_window_size: 2641,
+    // Window sampling is not activated on this network yet
+    sampling_activation_daa_score: u64::MAX,
+    difficulty_sample_rate: 4,
+    past_median_time_sample_rate: 10,
+    mergeset_size_limit: (DEFAULT_GHOSTDAG_K as u64) * 10,
+    merge_depth: 3600,
+    finality_depth: 86400,
+    pruning_depth: 185798,
+    coinbase_payload_script_public_key_max_len: 150,
+    max_coinbase_payload_len: 204,
+
+    // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
+    // check these rules, but in practice it's encorced by the network layer that limits the message
+    // size to 1 GB.
+    // These values should be lowered to more reasonable amounts on the next planned HF/SF.
+    max_tx_inputs: 1_000_000_000,
+    max_tx_outputs: 1_000_000_000,
+    max_signature_script_len: 1_000_000_000,
+    max_script_public_key_len: 1_000_000_000,
+
+    mass_per_tx_byte: 1,
+    mass_per_script_pub_key_byte: 10,
+    mass_per_sig_op: 1000,
+    // Storage mass is not enforced on this network yet
+    storage_mass_per_utxo: 0,
+    max_block_mass: 500_000,
+
+    // deflationary_phase_daa_score is the DAA score after which the pre-deflationary period
+    // switches to the deflationary period. This number is calculated as follows:
+    // We define a year as 365.25 days
+    // Half a year in seconds = 365.25 / 2 * 24 * 60 * 60 = 15778800
+    // The network was down for three days shortly after launch
+    // Three days in seconds = 3 * 24 * 60 * 60 = 259200
+    deflationary_phase_daa_score: 15778800 - 259200,
+    pre_deflationary_phase_base_subsidy: 50000000000,
+    coinbase_maturity: 100,
+    skip_proof_of_work: false,
+    max_block_level: 225,
+};
+
+pub const DEVNET_PARAMS: Params = Params {
+    network_name: "kaspa-devnet",
+    genesis: DEVNET_GENESIS,
+    ghostdag_k: DEFAULT_GHOSTDAG_K,
+    timestamp_deviation_tolerance: 132,
+    target_time_per_block: DurationMs::from_millis(1000),
+    max_block_parents: 10,
+    difficulty_window_size: 2641,
+    // Window sampling is not activated on this network yet
+    sampling_activation_daa_score: u64::MAX,
+    difficulty_sample_rate: 4,
+    past_median_time_sample_rate: 10,
+    mergeset_size_limit: (DEFAULT_GHOSTDAG_K as u64) * 10,
+    merge_depth: 3600,
+    finality_depth: 86400,
+    pruning_depth: 185798,
+    coinbase_payload_script_public_key_max_len: 150,
+    max_coinbase_payload_len: 204,
+
+    // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
+    // check these rules, but in practice it's encorced by the network layer that limits the message
+    // size to 1 GB.
+    // These values should be lowered to more reasonable amounts on the next planned HF/SF.
+    max_tx_inputs: 1_000_000_000,
+    max_tx_outputs: 1_000_000_000,
+    max_signature_script_len: 1_000_000_000,
+    max_script_public_key_len: 1_000_000_000,
+
+    mass_per_tx_byte: 1,
+    mass_per_script_pub_key_byte: 10,
+    mass_per_sig_op: 1000,
+    // Storage mass is not enforced on this network yet
+    storage_mass_per_utxo: 0,
+    max_block_mass: 500_000,
+
+    // deflationary_phase_daa_score is the DAA score after which the pre-deflationary period
+    // switches to the deflationary period. This number is calculated as follows:
+    // We define a year as 365.25 days
+    // Half a year in seconds = 365.25 / 2 * 24 * 60 * 60 = 15778800
+    // The network was down for three days shortly after launch
+    // Three days in seconds = 3 * 24 * 60 * 60 = 259200
+    deflationary_phase_daa_score: 15778800 - 259200,
+    pre_deflationary_phase_base_subsidy: 50000000000,
+    coinbase_maturity: 100,
+    skip_proof_of_work: false,
+    max_block_level: 250,
+};
+
+pub const TESTNET_PARAMS: Params = Params { network_name: "kaspa-testnet-10", genesis: TESTNET_GENESIS, ..MAINNET_PARAMS };
+
+/// A network for local simulations and tests, whose genesis difficulty is low enough for mining on a CPU
+pub const SIMNET_PARAMS: Params = Params { network_name: "kaspa-simnet", genesis: SIMNET_GENESIS, ..MAINNET_PARAMS };
+
+/// The networks with preset params
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NetworkType {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Simnet,
+}
+
+impl NetworkType {
+    pub const ALL: [NetworkType; 4] = [NetworkType::Mainnet, NetworkType::Testnet, NetworkType::Devnet, NetworkType::Simnet];
+
+    pub fn params(self) -> Params {
+        match self {
+            NetworkType::Mainnet => MAINNET_PARAMS,
+            NetworkType::Testnet => TESTNET_PARAMS,
+            NetworkType::Devnet => DEVNET_PARAMS,
+            NetworkType::Simnet => SIMNET_PARAMS,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            NetworkType::Mainnet => "mainnet",
+            NetworkType::Testnet => "testnet",
+            NetworkType::Devnet => "devnet",
+            NetworkType::Simnet => "simnet",
+        }
+    }
+}
+
+impl Display for NetworkType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for NetworkType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NetworkType::ALL.into_iter().find(|network| network.name() == s).ok_or_else(|| format!("unknown network {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_presets() {
+        for network in NetworkType::ALL {
+            assert_eq!(network.to_string().parse::<NetworkType>().unwrap(), network);
+            assert!(network.params().network_name.starts_with(&format!("kaspa-{}", network)));
+        }
+        assert!("regtest".parse::<NetworkType>().is_err());
+    }
+}
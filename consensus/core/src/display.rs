@@ -0,0 +1,78 @@
+//! Uniform formatting of hashes, transaction ids and raw bytes in logs and errors.
+//!
+//! Hex is always written lowercase, and a short hash is a prefix of the full hex string, so that log lines, errors
+//! and block explorer data can be correlated with a plain text search.
+
+use hashes::Hash;
+use std::{
+    fmt::{Display, Formatter},
+    str,
+};
+
+/// The number of hex characters of a short hash
+pub const SHORT_HASH_LEN: usize = 16;
+
+/// Displays the first [`SHORT_HASH_LEN`] hex characters of a hash, for high volume logs
+#[derive(Clone, Copy)]
+pub struct ShortHash(pub Hash);
+
+impl Display for ShortHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut hex = [0u8; SHORT_HASH_LEN];
+        faster_hex::hex_encode(&self.0.as_bytes()[..SHORT_HASH_LEN / 2], &mut hex)
+            .expect("The output is exactly twice the size of the input");
+        f.write_str(str::from_utf8(&hex).expect("hex is always valid UTF-8"))
+    }
+}
+
+pub trait HashDisplayExt {
+    fn short(&self) -> ShortHash;
+}
+
+impl HashDisplayExt for Hash {
+    fn short(&self) -> ShortHash {
+        ShortHash(*self)
+    }
+}
+
+/// Displays raw bytes as lowercase hex
+pub struct Hex<'a>(pub &'a [u8]);
+
+impl Display for Hex<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&faster_hex::hex_string(self.0))
+    }
+}
+
+/// Displays a list of hashes as `[hash, hash, ...]`
+pub struct HashList<'a>(pub &'a [Hash]);
+
+impl Display for HashList<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[")?;
+        for (i, hash) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            Display::fmt(hash, f)?;
+        }
+        f.write_str("]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_display_formats() {
+        let hash_str = "8e40af02265360d59f4ecf9ae9ebf8f00a3118408f5a9cdcbcc9c0f93642f3af";
+        let hash = Hash::from_str(&hash_str.to_uppercase()).unwrap();
+        assert_eq!(hash.to_string(), hash_str);
+        assert_eq!(hash.short().to_string(), &hash_str[..SHORT_HASH_LEN]);
+        assert_eq!(Hex(&[0xab, 0xcd, 0x01]).to_string(), "abcd01");
+        assert_eq!(HashList(&[]).to_string(), "[]");
+        assert_eq!(HashList(&[hash, 1.into()]).to_string(), format!("[{}, {}]", hash_str, Hash::from(1)));
+    }
+}
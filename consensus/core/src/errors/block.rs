@@ -2,7 +2,9 @@ use std::fmt::Display;
 
 use crate::{
     constants,
+    display::HashList,
     errors::{coinbase::CoinbaseError, tx::TxRuleError},
+    time::TimestampMs,
     tx::{TransactionId, TransactionOutpoint},
     BlueWorkType,
 };
@@ -26,13 +28,33 @@ impl<T: Display + Clone> Display for TwoDimVecDisplay<T> {
     }
 }
 
+/// Pinpoints where a header blue score or blue work diverges from the GHOSTDAG data computed locally
+#[derive(Clone, Debug)]
+pub struct MergesetDivergence {
+    /// The first mergeset blue, in GHOSTDAG order starting with the selected parent, at which the locally
+    /// accumulated value exceeds the header value. `None` if the header value exceeds the whole mergeset.
+    pub first_divergent_blue: Option<Hash>,
+    pub computed_blue_score: u64,
+    pub computed_blue_work: BlueWorkType,
+}
+
+impl Display for MergesetDivergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "computed blue score {} and blue work {}, ", self.computed_blue_score, self.computed_blue_work)?;
+        match self.first_divergent_blue {
+            Some(hash) => write!(f, "first divergent mergeset blue {}", hash),
+            None => write!(f, "the header value exceeds the whole mergeset"),
+        }
+    }
+}
+
 #[derive(Error, Debug, Clone)]
 pub enum RuleError {
     #[error("wrong block version: got {0} but expected {}", constants::BLOCK_VERSION)]
     WrongBlockVersion(u16),
 
     #[error("the block timestamp is too far into the future: block timestamp is {0} but maximum timestamp allowed is {1}")]
-    TimeTooFarIntoTheFuture(u64, u64),
+    TimeTooFarIntoTheFuture(TimestampMs, TimestampMs),
 
     #[error("block has no parents")]
     NoParents,
@@ -49,7 +71,7 @@ pub enum RuleError {
     #[error("parent {0} is invalid")]
     InvalidParent(Hash),
 
-    #[error("block has missing parents: {0:?}")]
+    #[error("block has missing parents: {}", HashList(.0))]
     MissingParents(Vec<Hash>),
 
     #[error("pruning point {0} is not in the past of this block")]
@@ -58,17 +80,17 @@ pub enum RuleError {
     #[error("expected header daa score {0} but got {1}")]
     UnexpectedHeaderDaaScore(u64, u64),
 
-    #[error("expected header blue score {0} but got {1}")]
-    UnexpectedHeaderBlueScore(u64, u64),
+    #[error("expected header blue score {0} but got {1} ({2})")]
+    UnexpectedHeaderBlueScore(u64, u64, MergesetDivergence),
 
-    #[error("expected header blue work {0} but got {1}")]
-    UnexpectedHeaderBlueWork(BlueWorkType, BlueWorkType),
+    #[error("expected header blue work {0} but got {1} ({2})")]
+    UnexpectedHeaderBlueWork(BlueWorkType, BlueWorkType, MergesetDivergence),
 
     #[error("block difficulty of {0} is not the expected value of {1}")]
     UnexpectedDifficulty(u32, u32),
 
     #[error("block timestamp of {0} is not after expected {1}")]
-    TimeTooOld(u64, u64),
+    TimeTooOld(TimestampMs, TimestampMs),
 
     #[error("block is known to be invalid")]
     KnownInvalid,
@@ -141,6 +163,27 @@ pub enum RuleError {
 
     #[error("invalid transactions in new block template")]
     InvalidTransactionsInNewBlock(Vec<(TransactionId, TxRuleError)>),
+
+    #[error("recomputed GHOSTDAG data of block {0} differs from the stored data")]
+    StoredGhostdagDataMismatch(Hash),
+
+    #[error("trusted block {0} was submitted while no trusted sync is in progress")]
+    TrustedBlockOutsideSync(Hash),
+
+    #[error("supplied GHOSTDAG data of trusted block {0} has blue score {1} and blue work {2}, which mismatch its header")]
+    TrustedGhostdagDataMismatch(Hash, u64, BlueWorkType),
+
+    #[error("a trusted sync can only begin on a node holding no blocks but genesis")]
+    TrustedSyncOfNonEmptyDag,
+
+    #[error("a pruning point UTXO set was imported while no trusted sync is in progress")]
+    UtxoSetImportOutsideSync,
+
+    #[error("imported pruning point {0} has no block body")]
+    MissingPruningPointBody(Hash),
+
+    #[error("past pruning point of index {0} is {1} but {2} is known")]
+    PastPruningPointMismatch(u64, Hash, Hash),
 }
 
 pub type BlockProcessResult<T> = std::result::Result<T, RuleError>;
@@ -1,3 +1,4 @@
 pub mod block;
 pub mod coinbase;
+pub mod sync;
 pub mod tx;
@@ -0,0 +1,25 @@
+use hashes::Hash;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum SyncManagerError {
+    #[error("block {0} is not known")]
+    BlockNotFound(Hash),
+
+    #[error("block {0} is not in the selected chain of block {1}")]
+    NotInSelectedChain(Hash, Hash),
+
+    #[error("block {0} is not in the past of block {1}")]
+    NotInPast(Hash, Hash),
+
+    #[error("the region between blocks {0} and {1} holds more than {2} blocks")]
+    RegionTooLarge(Hash, Hash, usize),
+
+    #[error("the data of the selected chain of block {0} was garbage collected")]
+    DataPruned(Hash),
+
+    #[error("the UTXO set of {0} is not available, since it is not the pruning point")]
+    PruningPointUtxoSetUnavailable(Hash),
+}
+
+pub type SyncManagerResult<T> = std::result::Result<T, SyncManagerError>;
@@ -70,6 +70,9 @@ pub enum TxRuleError {
     #[error("transaction tries to spend {0} while its total inputs amount is {1}")]
     SpendTooHigh(u64, u64),
 
+    #[error("transaction mass of {0} is higher than the max allowed block mass of {1}")]
+    MassTooHigh(u64, u64),
+
     #[error("one of the transaction sequence locks conditions was not met")]
     SequenceLockConditionsAreNotMet,
 
@@ -0,0 +1,15 @@
+use crate::BlueWorkType;
+use hashes::Hash;
+
+pub type KType = u8; // This type must be increased to u16 if we ever set GHOSTDAG K > 255
+
+/// The GHOSTDAG data of a block, as exposed to consumers external to consensus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalGhostdagData {
+    pub blue_score: u64,
+    pub blue_work: BlueWorkType,
+    pub selected_parent: Hash,
+    /// The blue blocks of the mergeset in consensus order, starting with the selected parent
+    pub mergeset_blues: Vec<Hash>,
+    pub mergeset_reds: Vec<Hash>,
+}
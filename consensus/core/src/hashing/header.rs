@@ -1,10 +1,10 @@
 use super::HasherExtensions;
-use crate::header::Header;
+use crate::{header::Header, time::TimestampMs};
 use hashes::{Hash, HasherBase};
 
 /// Returns the header hash using the provided nonce+timestamp instead of those in the header.
 #[inline]
-pub fn hash_override_nonce_time(header: &Header, nonce: u64, timestamp: u64) -> Hash {
+pub fn hash_override_nonce_time(header: &Header, nonce: u64, timestamp: TimestampMs) -> Hash {
     let mut hasher = hashes::BlockHash::new();
     hasher.update(header.version.to_le_bytes()).write_len(header.parents_by_level.len()); // Write the number of parent levels
 
@@ -18,7 +18,7 @@ pub fn hash_override_nonce_time(header: &Header, nonce: u64, timestamp: u64) ->
         .update(header.hash_merkle_root)
         .update(header.accepted_id_merkle_root)
         .update(header.utxo_commitment)
-        .update(timestamp.to_le_bytes())
+        .update(timestamp.as_millis().to_le_bytes())
         .update(header.bits.to_le_bytes())
         .update(nonce.to_le_bytes())
         .update(header.daa_score.to_le_bytes())
@@ -29,6 +29,14 @@ pub fn hash_override_nonce_time(header: &Header, nonce: u64, timestamp: u64) ->
     hasher.finalize()
 }
 
+/// Returns the pre-PoW hash of the header, i.e., the header hash with zeroed nonce and timestamp.
+/// This value is independent of the nonce and timestamp, so external miners can compute it once per
+/// template and roll nonces and timestamps locally.
+#[inline]
+pub fn pre_pow_hash(header: &Header) -> Hash {
+    hash_override_nonce_time(header, 0, TimestampMs::default())
+}
+
 /// Returns the header hash.
 pub fn hash(header: &Header) -> Hash {
     hash_override_nonce_time(header, header.nonce, header.timestamp)
@@ -47,7 +55,7 @@ mod tests {
             Default::default(),
             Default::default(),
             Default::default(),
-            234,
+            TimestampMs::from_millis(234),
             23,
             567,
             0,
@@ -58,6 +66,31 @@ mod tests {
         assert_ne!(blockhash::NONE, header.hash);
     }
 
+    #[test]
+    fn test_pre_pow_hash_ignores_nonce_and_time() {
+        let mut header = Header::new(
+            1,
+            vec![vec![1.into()]],
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            TimestampMs::from_millis(234),
+            23,
+            567,
+            0,
+            0.into(),
+            0,
+            Default::default(),
+        );
+        let pre_pow_hash = pre_pow_hash(&header);
+        let hash = header.hash;
+        header.nonce = 568;
+        header.timestamp = TimestampMs::from_millis(235);
+        header.finalize();
+        assert_eq!(pre_pow_hash, super::pre_pow_hash(&header));
+        assert_ne!(hash, header.hash);
+    }
+
     #[test]
     fn test_hash_blue_work() {
         let tests: Vec<(BlueWorkType, Vec<u8>)> =
@@ -16,6 +16,11 @@ pub fn hash(tx: &Transaction) -> Hash {
     hasher.finalize()
 }
 
+/// Returns the content hash of a transaction payload, by which large payloads are stored and retrieved
+pub fn payload_hash(payload: &[u8]) -> Hash {
+    hashes::PayloadHash::hash(payload)
+}
+
 /// Not intended for direct use by clients. Instead use `tx.id()`
 pub(crate) fn id(tx: &Transaction) -> TransactionId {
     // Encode the transaction, replace signature script with zeroes, cut off
@@ -1,4 +1,4 @@
-use crate::{hashing, BlueWorkType};
+use crate::{hashing, time::TimestampMs, BlueWorkType};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use hashes::Hash;
 use serde::{Deserialize, Serialize};
@@ -12,7 +12,7 @@ pub struct Header {
     pub hash_merkle_root: Hash,
     pub accepted_id_merkle_root: Hash,
     pub utxo_commitment: Hash,
-    pub timestamp: u64, // Timestamp is in milliseconds
+    pub timestamp: TimestampMs,
     pub bits: u32,
     pub nonce: u64,
     pub daa_score: u64,
@@ -29,7 +29,7 @@ impl Header {
         hash_merkle_root: Hash,
         accepted_id_merkle_root: Hash,
         utxo_commitment: Hash,
-        timestamp: u64,
+        timestamp: TimestampMs,
         bits: u32,
         nonce: u64,
         daa_score: u64,
@@ -61,7 +61,11 @@ impl Header {
         self.hash = hashing::header::hash(self);
     }
 
-    pub fn direct_parents(&self) -> &Vec<Hash> {
-        &self.parents_by_level[0]
+    /// Returns the direct parents of the block, which are empty for a genesis block
+    pub fn direct_parents(&self) -> &[Hash] {
+        match self.parents_by_level.first() {
+            Some(parents) => parents,
+            None => &[],
+        }
     }
 }
@@ -7,16 +7,24 @@ pub mod api;
 pub mod block;
 pub mod blockhash;
 pub mod blockstatus;
+pub mod chain;
 pub mod coinbase;
+pub mod config;
 pub mod constants;
+pub mod display;
 pub mod errors;
+pub mod ghostdag;
 pub mod hashing;
 pub mod header;
 pub mod merkle;
 pub mod muhash;
 pub mod notify;
+pub mod revalidation;
 pub mod sign;
+pub mod stats;
 pub mod subnets;
+pub mod time;
+pub mod trusted;
 pub mod tx;
 pub mod utxo;
 
@@ -1,14 +1,34 @@
-use crate::{hashing, tx::Transaction};
+use crate::{
+    hashing,
+    tx::{Transaction, TransactionId},
+};
 use hashes::Hash;
-use merkle::calc_merkle_root;
+use merkle::{calc_merkle_proof, calc_merkle_root};
 
+pub use merkle::MerkleProof;
+
+/// Calculates the block `hash_merkle_root` over the full transaction hashes, which (unlike transaction ids)
+/// also commit to the signature scripts (i.e., the transaction witness data)
 pub fn calc_hash_merkle_root<'a>(txs: impl ExactSizeIterator<Item = &'a Transaction>) -> Hash {
     calc_merkle_root(txs.map(hashing::tx::hash))
 }
 
+/// Calculates the `accepted_id_merkle_root` over the ids of the transactions accepted by a chain block.
+/// The ids are expected to be sorted by the caller
+pub fn calc_accepted_id_merkle_root(accepted_tx_ids: impl ExactSizeIterator<Item = TransactionId>) -> Hash {
+    calc_merkle_root(accepted_tx_ids)
+}
+
+/// Builds an SPV proof of inclusion of the transaction at `index` in the block `hash_merkle_root`.
+/// The proof should be verified against the full transaction hash (see [`hashing::tx::hash`])
+pub fn calc_hash_merkle_proof<'a>(txs: impl ExactSizeIterator<Item = &'a Transaction>, index: usize) -> Option<MerkleProof> {
+    calc_merkle_proof(txs.map(hashing::tx::hash), index)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::merkle::calc_hash_merkle_root;
+    use crate::hashing;
+    use crate::merkle::{calc_hash_merkle_proof, calc_hash_merkle_root};
     use crate::{
         subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
         tx::{scriptvec, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput},
@@ -244,5 +264,11 @@ mod tests {
                 0x38, 0x97, 0x4d, 0xa5, 0x0f, 0xd6, 0xef, 0xb4, 0xd2, 0x66, 0xbc, 0x8d, 0x21,
             ])
         );
+
+        let root = calc_hash_merkle_root(txs.iter());
+        for (i, tx) in txs.iter().enumerate() {
+            let proof = calc_hash_merkle_proof(txs.iter(), i).unwrap();
+            assert!(proof.verify(hashing::tx::hash(tx), root));
+        }
     }
 }
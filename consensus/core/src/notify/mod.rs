@@ -1,9 +1,33 @@
-use crate::block::Block;
+use crate::{
+    block::Block,
+    chain::ChainBlockAcceptanceData,
+    tx::{ScriptPublicKey, TransactionId},
+    utxo::utxo_diff::UtxoDiff,
+};
+use hashes::Hash;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub enum Notification {
     BlockAdded(BlockAddedNotification),
+    VirtualChainChanged(VirtualChainChangedNotification),
+    UtxosChanged(UtxosChangedNotification),
     NewBlockTemplate(NewBlockTemplateNotification),
+    TransactionsAccepted(TransactionsAcceptedNotification),
+    TransactionsReverted(TransactionsRevertedNotification),
+}
+
+impl Notification {
+    pub fn notification_type(&self) -> NotificationType {
+        match self {
+            Notification::BlockAdded(_) => NotificationType::BlockAdded,
+            Notification::VirtualChainChanged(_) => NotificationType::VirtualChainChanged,
+            Notification::UtxosChanged(_) => NotificationType::UtxosChanged,
+            Notification::NewBlockTemplate(_) => NotificationType::NewBlockTemplate,
+            Notification::TransactionsAccepted(_) => NotificationType::TransactionsAccepted,
+            Notification::TransactionsReverted(_) => NotificationType::TransactionsReverted,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -11,5 +35,90 @@ pub struct BlockAddedNotification {
     pub block: Block,
 }
 
+/// Sent whenever the virtual selected parent chain changed, after the block added notifications of the added chain blocks
+#[derive(Debug, Clone)]
+pub struct VirtualChainChangedNotification {
+    /// The chain blocks that were removed, in high-to-low order
+    pub removed_chain_block_hashes: Vec<Hash>,
+    /// The chain blocks that were added, in low-to-high order
+    pub added_chain_block_hashes: Vec<Hash>,
+    /// The acceptance data of each added chain block
+    pub added_chain_blocks_acceptance_data: Vec<ChainBlockAcceptanceData>,
+}
+
+/// Sent whenever the virtual UTXO set changed, along with the virtual chain changed notification
+#[derive(Debug, Clone)]
+pub struct UtxosChangedNotification {
+    /// The diff which was applied to the virtual UTXO set
+    pub accumulated_utxo_diff: Arc<UtxoDiff>,
+    /// The virtual parents once the diff was applied, identifying the resulting virtual UTXO set
+    pub virtual_parents: Vec<Hash>,
+}
+
 #[derive(Debug, Clone)]
 pub struct NewBlockTemplateNotification {}
+
+/// A transaction accepted by a virtual selected parent chain block, along with the script public keys its outputs pay to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionAcceptance {
+    pub transaction_id: TransactionId,
+    pub accepting_block_hash: Hash,
+    pub output_script_public_keys: Vec<ScriptPublicKey>,
+}
+
+/// Sent whenever the virtual chain changed, listing the transactions accepted by the added chain blocks in chain order.
+/// A transaction moving to another accepting block during a reorg is accepted again, without being reverted first.
+#[derive(Debug, Clone)]
+pub struct TransactionsAcceptedNotification {
+    pub accepted: Vec<TransactionAcceptance>,
+}
+
+/// Sent whenever a reorg removed chain blocks, before the accepted transactions notification, listing the transactions
+/// the removed chain blocks accepted and which are no longer accepted by the virtual chain
+#[derive(Debug, Clone)]
+pub struct TransactionsRevertedNotification {
+    /// The reverted transactions along with the removed chain block which accepted them
+    pub reverted: Vec<TransactionAcceptance>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationType {
+    BlockAdded,
+    VirtualChainChanged,
+    UtxosChanged,
+    NewBlockTemplate,
+    TransactionsAccepted,
+    TransactionsReverted,
+}
+
+impl NotificationType {
+    pub const ALL: [NotificationType; 6] = [
+        NotificationType::BlockAdded,
+        NotificationType::VirtualChainChanged,
+        NotificationType::UtxosChanged,
+        NotificationType::NewBlockTemplate,
+        NotificationType::TransactionsAccepted,
+        NotificationType::TransactionsReverted,
+    ];
+}
+
+/// A consumer of consensus notifications, registered on the consensus notification root.
+///
+/// Each consumer is fed by a dedicated thread, so `consume` may block without affecting
+/// consensus processing beyond what the consumer [`BackpressurePolicy`] allows.
+pub trait NotificationConsumer: Send + Sync {
+    fn consume(&self, notification: Arc<Notification>);
+}
+
+/// Defines how notifications are queued for a consumer which does not keep up with consensus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// Queue all notifications, with no bound on memory usage
+    Unbounded,
+    /// Queue up to the given number of notifications, then block consensus until the consumer catches up
+    Block(usize),
+    /// Queue up to the given number of notifications, then drop incoming ones
+    DropNewest(usize),
+    /// Queue up to the given number of notifications, then drop the oldest queued ones
+    DropOldest(usize),
+}
@@ -0,0 +1,57 @@
+use crate::{blockstatus::BlockStatus, errors::block::RuleError};
+
+/// The outcome of re-running the validations of a stored block, compared with its stored status
+#[derive(Debug, Clone)]
+pub struct BlockRevalidationReport {
+    /// The status stored for the block
+    pub stored_status: BlockStatus,
+    /// The error returned by header revalidation, if any
+    pub header_error: Option<RuleError>,
+    /// Whether the block body is stored and was revalidated
+    pub body_revalidated: bool,
+    /// The error returned by body revalidation, if any
+    pub body_error: Option<RuleError>,
+}
+
+impl BlockRevalidationReport {
+    /// Returns descriptions of all discrepancies between the revalidation outcome and the stored status.
+    ///
+    /// Blocks stored as invalid never report discrepancies, since the data which failed validation
+    /// (an invalid body for instance) is not stored and hence cannot be revalidated.
+    pub fn discrepancies(&self) -> Vec<String> {
+        if self.stored_status == BlockStatus::StatusInvalid {
+            return vec![];
+        }
+        let mut discrepancies = Vec::new();
+        if let Some(err) = &self.header_error {
+            discrepancies.push(format!("header is stored as valid but fails revalidation: {}", err));
+        }
+        if let Some(err) = &self.body_error {
+            discrepancies.push(format!("body is stored as valid but fails revalidation: {}", err));
+        }
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discrepancies_test() {
+        let report = |stored_status, header_error, body_error| BlockRevalidationReport {
+            stored_status,
+            header_error,
+            body_revalidated: true,
+            body_error,
+        };
+        assert!(report(BlockStatus::StatusUTXOValid, None, None).discrepancies().is_empty());
+        assert!(report(BlockStatus::StatusInvalid, Some(RuleError::InvalidPoW), None).discrepancies().is_empty());
+        assert_eq!(
+            report(BlockStatus::StatusUTXOPendingVerification, Some(RuleError::InvalidPoW), Some(RuleError::BadCoinbaseTransaction))
+                .discrepancies()
+                .len(),
+            2
+        );
+    }
+}
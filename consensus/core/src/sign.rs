@@ -5,6 +5,14 @@ use crate::{
     },
     tx::MutableTransaction,
 };
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SignError {
+    #[error("no key was provided for the script public key spent by input {0}")]
+    MissingKey(usize),
+}
 
 /// Sign a transaction using schnorr
 pub fn sign(mut mutable_tx: MutableTransaction, privkey: [u8; 32]) -> MutableTransaction {
@@ -21,6 +29,31 @@ pub fn sign(mut mutable_tx: MutableTransaction, privkey: [u8; 32]) -> MutableTra
     mutable_tx
 }
 
+/// Sign a transaction using schnorr, each input being signed by the key among `privkeys` whose public key is paid to
+/// by the UTXO entry it spends
+pub fn sign_with_multiple(mut mutable_tx: MutableTransaction, privkeys: &[[u8; 32]]) -> Result<MutableTransaction, SignError> {
+    let schnorr_keys: HashMap<[u8; 32], secp256k1::KeyPair> = privkeys
+        .iter()
+        .map(|privkey| {
+            let schnorr_key = secp256k1::KeyPair::from_seckey_slice(secp256k1::SECP256K1, privkey).unwrap();
+            (schnorr_key.x_only_public_key().0.serialize(), schnorr_key)
+        })
+        .collect();
+    let mut reused_values = SigHashReusedValues::new();
+    for i in 0..mutable_tx.tx.inputs.len() {
+        let script = mutable_tx.entries[i].as_ref().expect("expected all UTXO entries to be populated").script_public_key.script();
+        let schnorr_key = match script.get(1..33).and_then(|pk| schnorr_keys.get(pk)) {
+            Some(schnorr_key) => schnorr_key,
+            None => return Err(SignError::MissingKey(i)),
+        };
+        let sig_hash = calc_schnorr_signature_hash(&mutable_tx.as_verifiable(), i, SIG_HASH_ALL, &mut reused_values);
+        let msg = secp256k1::Message::from_slice(sig_hash.as_bytes().as_slice()).unwrap();
+        let sig: [u8; 64] = *schnorr_key.sign_schnorr(msg).as_ref();
+        mutable_tx.tx.inputs[i].signature_script = std::iter::once(65u8).chain(sig).chain([SIG_HASH_ALL.to_u8()]).collect();
+    }
+    Ok(mutable_tx)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +137,28 @@ mod tests {
         let signed_tx = sign(MutableTransaction::with_entries(unsigned_tx, entries), secret_key.secret_bytes());
         assert!(verify(&signed_tx.as_verifiable()).is_ok());
     }
+
+    #[test]
+    fn test_sign_with_multiple() {
+        let secp = Secp256k1::new();
+        let keys: Vec<_> = (0..2).map(|_| secp.generate_keypair(&mut rand::thread_rng())).collect();
+        let prev_tx_id = TransactionId::from_str("880eb9819a31821d9d2399e2f35e2433b72637e393d71ecc9b8d0250f49153c3").unwrap();
+        let inputs = (0..2).map(|index| TransactionInput::new(TransactionOutpoint::new(prev_tx_id, index), vec![], 0, 1)).collect();
+        let outputs = vec![TransactionOutput::new(250, ScriptPublicKey::new(0, ScriptVec::from_slice(&keys[0].1.serialize())))];
+        let unsigned_tx = Transaction::new(0, inputs, outputs, 0, SubnetworkId::from_bytes([0; 20]), 0, vec![]);
+        // Each input spends an entry paying to a different key
+        let entries: Vec<_> = keys
+            .iter()
+            .map(|(_, public_key)| {
+                UtxoEntry::new(150, ScriptPublicKey::new(0, ScriptVec::from_slice(&public_key.serialize())), 0, false)
+            })
+            .collect();
+
+        let privkeys: Vec<_> = keys.iter().map(|(secret_key, _)| secret_key.secret_bytes()).rev().collect();
+        let signed_tx = sign_with_multiple(MutableTransaction::with_entries(unsigned_tx.clone(), entries.clone()), &privkeys).unwrap();
+        assert!(verify(&signed_tx.as_verifiable()).is_ok());
+
+        let result = sign_with_multiple(MutableTransaction::with_entries(unsigned_tx, entries), &privkeys[..1]);
+        assert_eq!(result.err(), Some(SignError::MissingKey(0)));
+    }
 }
@@ -0,0 +1,146 @@
+use crate::time::{DurationMs, TimestampMs};
+use hashes::Hash;
+use serde::{Deserialize, Serialize};
+
+/// The maximal number of chain blocks covered by [`ChainThroughputStats`], bounding the chain walk serving a request
+pub const MAX_CHAIN_THROUGHPUT_WINDOW_SIZE: u64 = 10_000;
+
+/// Throughput statistics aggregated over a window of selected chain blocks ending at the virtual selected parent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainThroughputStats {
+    /// The number of chain blocks actually covered by the window (can be smaller than the requested size near genesis)
+    pub chain_block_count: u64,
+    /// The overall number of blocks merged by the chain blocks in the window
+    pub merged_block_count: u64,
+    /// The number of non-coinbase transactions accepted by the chain blocks in the window
+    pub accepted_transaction_count: u64,
+    /// The total mass of accepted transactions
+    pub accepted_mass: u64,
+    /// The total fees paid by accepted transactions
+    pub total_fees: u64,
+    /// The time span (in milliseconds) between the first and last chain blocks of the window
+    pub time_span_millis: u64,
+}
+
+impl ChainThroughputStats {
+    /// The number of chain blocks actually walked for a requested window size, clamped to
+    /// [`MAX_CHAIN_THROUGHPUT_WINDOW_SIZE`]
+    pub fn clamp_window_size(window_size: u64) -> usize {
+        window_size.min(MAX_CHAIN_THROUGHPUT_WINDOW_SIZE) as usize
+    }
+
+    /// Average accepted transactions per second over the window time span
+    pub fn transactions_per_second(&self) -> f64 {
+        if self.time_span_millis == 0 {
+            return 0.0;
+        }
+        self.accepted_transaction_count as f64 * 1000.0 / self.time_span_millis as f64
+    }
+
+    /// The ratio between accepted mass and the maximal mass capacity of all merged blocks
+    pub fn mass_utilization(&self, max_block_mass: u64) -> f64 {
+        let capacity = self.merged_block_count.saturating_mul(max_block_mass);
+        if capacity == 0 {
+            return 0.0;
+        }
+        self.accepted_mass as f64 / capacity as f64
+    }
+}
+
+/// A snapshot of the DAG state and of the number of blocks processed so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockDagInfo {
+    pub network_name: String,
+    /// The number of processed headers, including the ones of blocks with a body
+    pub header_count: u64,
+    /// The number of processed blocks with a body
+    pub block_count: u64,
+    /// The tips of the DAG of blocks with a body
+    pub tip_hashes: Vec<Hash>,
+    pub virtual_parent_hashes: Vec<Hash>,
+    pub virtual_daa_score: u64,
+    /// The difficulty of the next block, relative to the maximal target
+    pub difficulty: f64,
+    pub past_median_time: TimestampMs,
+    pub pruning_point_hash: Hash,
+}
+
+/// The drift of the virtual DAA score from the wall clock, measured from the timestamp of the sink (the virtual selected parent)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DaaScoreDrift {
+    pub virtual_daa_score: u64,
+    pub sink_timestamp: TimestampMs,
+    /// The wall clock time the drift is measured at
+    pub now: TimestampMs,
+    pub target_time_per_block: DurationMs,
+}
+
+impl DaaScoreDrift {
+    /// The drift in units of target time per block, i.e., roughly the number of DAA scores the virtual lags behind
+    /// the wall clock. It grows continuously when the node stalls or when the network hashrate collapses, and is
+    /// negative when the sink is ahead of the local clock.
+    pub fn drift(&self) -> f64 {
+        if self.target_time_per_block.as_millis() == 0 {
+            return 0.0;
+        }
+        (self.now.as_millis() as f64 - self.sink_timestamp.as_millis() as f64) / self.target_time_per_block.as_millis() as f64
+    }
+
+    /// The DAA score the virtual would have reached by now, had blocks kept coming at the target rate since the sink
+    pub fn expected_daa_score(&self) -> u64 {
+        self.virtual_daa_score + self.drift().max(0.0) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throughput_stats_test() {
+        let stats = ChainThroughputStats {
+            chain_block_count: 10,
+            merged_block_count: 20,
+            accepted_transaction_count: 500,
+            accepted_mass: 2_500_000,
+            total_fees: 1000,
+            time_span_millis: 10_000,
+        };
+        assert_eq!(stats.transactions_per_second(), 50.0);
+        assert_eq!(stats.mass_utilization(500_000), 0.25);
+
+        let empty = ChainThroughputStats::default();
+        assert_eq!(empty.transactions_per_second(), 0.0);
+        assert_eq!(empty.mass_utilization(500_000), 0.0);
+    }
+
+    #[test]
+    fn clamp_window_size_test() {
+        assert_eq!(ChainThroughputStats::clamp_window_size(0), 0);
+        assert_eq!(ChainThroughputStats::clamp_window_size(100), 100);
+        assert_eq!(
+            ChainThroughputStats::clamp_window_size(MAX_CHAIN_THROUGHPUT_WINDOW_SIZE),
+            MAX_CHAIN_THROUGHPUT_WINDOW_SIZE as usize
+        );
+        assert_eq!(ChainThroughputStats::clamp_window_size(u64::MAX), MAX_CHAIN_THROUGHPUT_WINDOW_SIZE as usize);
+    }
+
+    #[test]
+    fn daa_score_drift_test() {
+        let mut drift = DaaScoreDrift {
+            virtual_daa_score: 100,
+            sink_timestamp: TimestampMs::from_millis(10_000),
+            now: TimestampMs::from_millis(15_500),
+            target_time_per_block: DurationMs::from_millis(1000),
+        };
+        assert_eq!(drift.drift(), 5.5);
+        assert_eq!(drift.expected_daa_score(), 105);
+
+        // A sink from the future of the local clock
+        drift.now = TimestampMs::from_millis(8000);
+        assert_eq!(drift.drift(), -2.0);
+        assert_eq!(drift.expected_daa_score(), 100);
+
+        assert_eq!(DaaScoreDrift::default().drift(), 0.0);
+    }
+}
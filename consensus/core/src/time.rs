@@ -0,0 +1,309 @@
+//! Typed consensus time values.
+//!
+//! Consensus timestamps and the durations derived from them are always expressed in milliseconds.
+//! Wrapping them in dedicated types turns unit mix-ups, such as comparing a timestamp to a duration
+//! in seconds, into compile errors. Both types serialize exactly like the underlying `u64`.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    ops::{Add, Mul, Sub},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TimeConversionError {
+    #[error("negative timestamp {0}")]
+    NegativeTimestamp(i64),
+
+    #[error("{0} milliseconds is out of range")]
+    OutOfRange(u128),
+}
+
+/// A unix timestamp in milliseconds
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+#[serde(transparent)]
+pub struct TimestampMs(u64);
+
+impl TimestampMs {
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    /// Returns `None` if the timestamp in milliseconds overflows
+    pub const fn from_secs(secs: u64) -> Option<Self> {
+        match secs.checked_mul(1000) {
+            Some(millis) => Some(Self(millis)),
+            None => None,
+        }
+    }
+
+    pub const fn as_millis(self) -> u64 {
+        self.0
+    }
+
+    /// The current system time
+    pub fn now() -> Self {
+        Self(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64)
+    }
+
+    pub fn checked_add(self, duration: DurationMs) -> Option<Self> {
+        self.0.checked_add(duration.0).map(Self)
+    }
+
+    pub fn saturating_add(self, duration: DurationMs) -> Self {
+        Self(self.0.saturating_add(duration.0))
+    }
+
+    pub fn checked_sub(self, duration: DurationMs) -> Option<Self> {
+        self.0.checked_sub(duration.0).map(Self)
+    }
+
+    pub fn saturating_sub(self, duration: DurationMs) -> Self {
+        Self(self.0.saturating_sub(duration.0))
+    }
+
+    /// Returns the duration elapsed from `earlier` to `self`, or zero if `earlier` is later than `self`
+    pub fn saturating_duration_since(self, earlier: Self) -> DurationMs {
+        DurationMs(self.0.saturating_sub(earlier.0))
+    }
+}
+
+impl Add<DurationMs> for TimestampMs {
+    type Output = Self;
+
+    fn add(self, rhs: DurationMs) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub<DurationMs> for TimestampMs {
+    type Output = Self;
+
+    fn sub(self, rhs: DurationMs) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+/// The duration elapsed between two timestamps. Panics (in debug) if `rhs` is later than `self`.
+impl Sub for TimestampMs {
+    type Output = DurationMs;
+
+    fn sub(self, rhs: Self) -> DurationMs {
+        DurationMs(self.0 - rhs.0)
+    }
+}
+
+impl Display for TimestampMs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<i64> for TimestampMs {
+    type Error = TimeConversionError;
+
+    fn try_from(millis: i64) -> Result<Self, Self::Error> {
+        u64::try_from(millis).map(Self).map_err(|_| TimeConversionError::NegativeTimestamp(millis))
+    }
+}
+
+impl TryFrom<TimestampMs> for i64 {
+    type Error = TimeConversionError;
+
+    fn try_from(timestamp: TimestampMs) -> Result<Self, Self::Error> {
+        i64::try_from(timestamp.0).map_err(|_| TimeConversionError::OutOfRange(timestamp.0.into()))
+    }
+}
+
+/// A duration in milliseconds between consensus timestamps, such as the target time per block
+/// or the time span of a difficulty window
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+#[serde(transparent)]
+pub struct DurationMs(u64);
+
+impl DurationMs {
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    /// Returns `None` if the duration in milliseconds overflows
+    pub const fn from_secs(secs: u64) -> Option<Self> {
+        match secs.checked_mul(1000) {
+            Some(millis) => Some(Self(millis)),
+            None => None,
+        }
+    }
+
+    pub const fn as_millis(self) -> u64 {
+        self.0
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<Self> {
+        self.0.checked_mul(rhs).map(Self)
+    }
+
+    pub fn saturating_mul(self, rhs: u64) -> Self {
+        Self(self.0.saturating_mul(rhs))
+    }
+}
+
+impl Add for DurationMs {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Mul<u64> for DurationMs {
+    type Output = Self;
+
+    fn mul(self, rhs: u64) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Display for DurationMs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ms", self.0)
+    }
+}
+
+impl From<DurationMs> for Duration {
+    fn from(duration: DurationMs) -> Self {
+        Duration::from_millis(duration.0)
+    }
+}
+
+impl TryFrom<Duration> for DurationMs {
+    type Error = TimeConversionError;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let millis = duration.as_millis();
+        u64::try_from(millis).map(Self).map_err(|_| TimeConversionError::OutOfRange(millis))
+    }
+}
+
+/// A source of the current time. Consensus reads the time through a clock, so that embedders can control it.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> TimestampMs;
+}
+
+pub type DynClock = Arc<dyn Clock>;
+
+/// The system clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> TimestampMs {
+        TimestampMs::now()
+    }
+}
+
+/// A clock which only moves when explicitly set or advanced, for deterministic tests
+#[derive(Debug, Default)]
+pub struct ManualClock(AtomicU64);
+
+impl ManualClock {
+    pub fn new(now: TimestampMs) -> Self {
+        Self(AtomicU64::new(now.0))
+    }
+
+    pub fn set(&self, now: TimestampMs) {
+        self.0.store(now.0, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, duration: DurationMs) {
+        self.0.fetch_add(duration.0, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> TimestampMs {
+        TimestampMs(self.0.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(TimestampMs::from_secs(2), Some(TimestampMs::from_millis(2000)));
+        assert_eq!(TimestampMs::from_secs(u64::MAX), None);
+        assert_eq!(DurationMs::from_secs(3).unwrap().as_millis(), 3000);
+        assert_eq!(Duration::from(DurationMs::from_millis(1500)), Duration::from_millis(1500));
+        assert_eq!(DurationMs::try_from(Duration::from_secs(1)), Ok(DurationMs::from_millis(1000)));
+        assert!(DurationMs::try_from(Duration::MAX).is_err());
+    }
+
+    #[test]
+    fn test_checked_conversions() {
+        assert_eq!(TimestampMs::try_from(5i64), Ok(TimestampMs::from_millis(5)));
+        assert_eq!(TimestampMs::try_from(-1i64), Err(TimeConversionError::NegativeTimestamp(-1)));
+        assert_eq!(i64::try_from(TimestampMs::from_millis(7)), Ok(7));
+        assert!(i64::try_from(TimestampMs::from_millis(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let timestamp = TimestampMs::from_millis(1000);
+        let duration = DurationMs::from_millis(300);
+        assert_eq!(timestamp + duration, TimestampMs::from_millis(1300));
+        assert_eq!(timestamp - duration, TimestampMs::from_millis(700));
+        assert_eq!((timestamp + duration) - timestamp, duration);
+        assert_eq!(timestamp.saturating_duration_since(timestamp + duration), DurationMs::default());
+        assert_eq!(timestamp.checked_sub(DurationMs::from_millis(1001)), None);
+        assert_eq!(TimestampMs::from_millis(u64::MAX).checked_add(duration), None);
+        assert_eq!(duration * 2, DurationMs::from_millis(600));
+    }
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new(TimestampMs::from_millis(1000));
+        assert_eq!(clock.now(), TimestampMs::from_millis(1000));
+        clock.advance(DurationMs::from_millis(500));
+        assert_eq!(clock.now(), TimestampMs::from_millis(1500));
+        clock.set(TimestampMs::from_millis(10));
+        assert_eq!(clock.now(), TimestampMs::from_millis(10));
+    }
+}
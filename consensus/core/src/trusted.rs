@@ -0,0 +1,46 @@
+use crate::{block::Block, ghostdag::KType, header::Header, BlueWorkType};
+use hashes::Hash;
+use std::sync::Arc;
+
+/// The GHOSTDAG data of a trusted block, as computed by the peer supplying the block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedGhostdagData {
+    pub blue_score: u64,
+    pub blue_work: BlueWorkType,
+    pub selected_parent: Hash,
+    /// The blue blocks of the mergeset in consensus order, starting with the selected parent
+    pub mergeset_blues: Vec<Hash>,
+    pub mergeset_reds: Vec<Hash>,
+    pub blues_anticone_sizes: Vec<(Hash, KType)>,
+}
+
+/// A block whose past is not available to the syncing node, such as the blocks accompanying the pruning point during
+/// the IBD of pruned data. It is inserted with the supplied GHOSTDAG and DAA data, skipping the validations which
+/// depend on its past
+#[derive(Debug, Clone)]
+pub struct TrustedBlock {
+    pub block: Block,
+    pub ghostdag_data: TrustedGhostdagData,
+    /// The blocks of the mergeset which are out of the DAA window of the block. They are only read when validating the
+    /// coinbase of a chain block, so they may be left empty for blocks which are never UTXO validated, such as the
+    /// blocks accompanying the pruning point
+    pub mergeset_non_daa: Vec<Hash>,
+}
+
+impl TrustedBlock {
+    pub fn new(block: Block, ghostdag_data: TrustedGhostdagData, mergeset_non_daa: Vec<Hash>) -> Self {
+        Self { block, ghostdag_data, mergeset_non_daa }
+    }
+}
+
+/// The data a node syncing from a peer with an unknown pruning point receives along with the UTXO set of the pruning
+/// point, which is what it needs in order to process the blocks above the pruning point
+#[derive(Debug, Clone)]
+pub struct PruningPointTrustedData {
+    /// The headers of the past pruning points by index, ending with the pruning point
+    pub pruning_points: Vec<Arc<Header>>,
+    /// The pruning point along with the blocks of its anticone merged by the selected chain, which come with their
+    /// bodies, and the header-only blocks within reach of the difficulty and past median time windows of the blocks
+    /// above the pruning point, all in topological order
+    pub blocks: Vec<TrustedBlock>,
+}
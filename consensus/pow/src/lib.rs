@@ -5,14 +5,15 @@ pub mod matrix;
 pub mod xoshiro;
 
 use crate::matrix::Matrix;
-use consensus_core::{hashing, header::Header};
-use hashes::PowHash;
+use consensus_core::{hashing, header::Header, time::TimestampMs};
+use hashes::{Hash, PowHash};
 use math::Uint256;
 
 /// State is an intermediate data structure with pre-computed values to speed up mining.
 pub struct State {
     matrix: Matrix,
     target: Uint256,
+    pre_pow_hash: Hash,
     // PRE_POW_HASH || TIME || 32 zero byte padding; without NONCE
     hasher: PowHash,
 }
@@ -20,14 +21,28 @@ pub struct State {
 impl State {
     #[inline]
     pub fn new(header: &Header) -> Self {
-        let target = Uint256::from_compact_target_bits(header.bits);
         // Zero out the time and nonce.
-        let pre_pow_hash = hashing::header::hash_override_nonce_time(header, 0, 0);
+        let pre_pow_hash = hashing::header::pre_pow_hash(header);
+        Self::from_pre_pow_hash(pre_pow_hash, header.timestamp, header.bits)
+    }
+
+    /// Builds the mining state directly from a pre-computed pre-PoW hash (see [`hashing::header::pre_pow_hash`]).
+    /// Used by external miners which receive the pre-PoW hash rather than the full header.
+    #[inline]
+    pub fn from_pre_pow_hash(pre_pow_hash: Hash, timestamp: TimestampMs, bits: u32) -> Self {
+        let target = Uint256::from_compact_target_bits(bits);
         // PRE_POW_HASH || TIME || 32 zero byte padding || NONCE
-        let hasher = PowHash::new(pre_pow_hash, header.timestamp);
+        let hasher = PowHash::new(pre_pow_hash, timestamp.as_millis());
         let matrix = Matrix::generate(pre_pow_hash);
 
-        Self { matrix, target, hasher }
+        Self { matrix, target, pre_pow_hash, hasher }
+    }
+
+    /// Updates the timestamp used for PoW calculation. Only the hasher prefix is recomputed since
+    /// the matrix depends solely on the pre-PoW hash.
+    #[inline]
+    pub fn update_timestamp(&mut self, timestamp: TimestampMs) {
+        self.hasher = PowHash::new(self.pre_pow_hash, timestamp.as_millis());
     }
 
     #[inline]
@@ -48,3 +63,43 @@ impl State {
         (pow <= self.target, pow)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::State;
+    use consensus_core::{hashing, header::Header, time::TimestampMs};
+
+    #[test]
+    fn test_state_from_pre_pow_hash() {
+        let header = Header::new(
+            1,
+            vec![vec![1.into()]],
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            TimestampMs::from_millis(234),
+            0x207fffff,
+            567,
+            0,
+            0.into(),
+            0,
+            Default::default(),
+        );
+        let state = State::new(&header);
+        let mut from_pre_pow_hash = State::from_pre_pow_hash(hashing::header::pre_pow_hash(&header), header.timestamp, header.bits);
+        for nonce in [0, 1, header.nonce, u64::MAX] {
+            assert_eq!(state.check_pow(nonce), from_pre_pow_hash.check_pow(nonce));
+        }
+
+        // Rolling the timestamp matches building the state out of a header with the new timestamp
+        let mut rolled_header = header.clone();
+        rolled_header.timestamp = TimestampMs::from_millis(235);
+        rolled_header.finalize();
+        let rolled_state = State::new(&rolled_header);
+        from_pre_pow_hash.update_timestamp(rolled_header.timestamp);
+        for nonce in [0, 1, header.nonce, u64::MAX] {
+            assert_eq!(rolled_state.check_pow(nonce), from_pre_pow_hash.check_pow(nonce));
+            assert_ne!(rolled_state.calculate_pow(nonce), state.calculate_pow(nonce));
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Startup integrity check of the consensus stores.
+//!
+//! Blocks are committed in atomic batches, yet some operations span several batches (see [`StagedOperation`]) and
+//! the stores reference each other across batches. A node killed at the wrong moment or a damaged disk may thus
+//! leave a DB the processors cannot work with. The check runs before consensus is created, repairs what can be
+//! repaired, and otherwise fails with an error calling for a resync rather than letting a processor panic later.
+
+use crate::model::stores::{
+    acceptance_data::{AcceptanceDataStoreReader, DbAcceptanceDataStore},
+    block_transactions::{BlockTransactionsStoreReader, DbBlockTransactionsStore},
+    database::prelude::CachePolicy,
+    errors::{StoreError, StoreResult, StoreResultExtensions},
+    ghostdag::{DbGhostdagStore, GhostdagStoreReader},
+    headers::{DbHeadersStore, HeaderStoreReader},
+    headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
+    metadata::{DbMetadataStore, MetadataStoreReader, StagedOperation, SCHEMA_VERSION_KEY},
+    past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStore, PastPruningPointsStoreReader},
+    payloads::DbPayloadsStore,
+    pruning::{DbPruningStore, PruningStoreReader},
+    relations::{DbRelationsStore, RelationsStoreReader},
+    statuses::{DbStatusesStore, StatusesStoreReader},
+    tips::{DbTipsStore, TipsStoreReader},
+    utxo_diffs::{DbUtxoDiffsStore, UtxoDiffsStoreReader},
+    utxo_multisets::{DbUtxoMultisetsStore, UtxoMultisetsStoreReader},
+    virtual_state::{DbVirtualStateStore, VirtualStateStoreReader},
+    DB,
+};
+use consensus_core::{blockstatus::BlockStatus, config::params::Params};
+use hashes::Hash;
+use parking_lot::RwLock;
+use rocksdb::{IteratorMode, WriteBatch};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// The default number of blocks checked down the selected chains of the headers selected tip and of the sink
+pub const DEFAULT_CHECK_DEPTH: u64 = 1000;
+
+#[derive(Error, Debug)]
+pub enum IntegrityError {
+    #[error("the database is inconsistent and cannot be repaired: {0}. Delete the data directory and resync")]
+    Inconsistent(String),
+
+    #[error("store error: {0}")]
+    Store(#[from] StoreError),
+}
+
+pub type IntegrityResult<T> = Result<T, IntegrityError>;
+
+#[derive(Clone, Debug, Default)]
+pub struct IntegrityReport {
+    /// The number of blocks whose data was checked
+    pub checked_blocks: u64,
+    /// A description of each repair made
+    pub repairs: Vec<String>,
+}
+
+/// Returns the stored value, failing with an inconsistency if `what` is missing
+fn require<T>(result: StoreResult<T>, what: impl FnOnce() -> String) -> IntegrityResult<T> {
+    match result.optional()? {
+        Some(value) => Ok(value),
+        None => Err(IntegrityError::Inconsistent(format!("{} is missing", what()))),
+    }
+}
+
+/// Checks the cross-consistency of the stores from the tips down to a bounded depth, i.e., that the statuses of
+/// blocks agree with their headers, GHOSTDAG data, bodies and UTXO data, and repairs interrupted staged operations.
+pub struct IntegrityChecker {
+    db: Arc<DB>,
+    genesis_hash: Hash,
+    depth: u64,
+}
+
+impl IntegrityChecker {
+    pub fn new(db: Arc<DB>, params: &Params) -> Self {
+        Self { db, genesis_hash: params.genesis.hash, depth: DEFAULT_CHECK_DEPTH }
+    }
+
+    pub fn with_depth(self, depth: u64) -> Self {
+        Self { depth, ..self }
+    }
+
+    pub fn run(&self) -> IntegrityResult<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+        if let Some(operation) = DbMetadataStore::new(self.db.clone()).get_staged_operation().optional()? {
+            match operation {
+                // The DB holds nothing but the partial genesis data, so it is reset for the initialization to rerun
+                StagedOperation::GenesisInit => self.reset()?,
+                // Trusted blocks cannot be told apart from the blocks inserted before the sync
+                StagedOperation::TrustedSync => {
+                    return Err(IntegrityError::Inconsistent(format!("the {} was interrupted", operation)));
+                }
+            }
+            report.repairs.push(format!("rolled back the interrupted {}", operation));
+            return Ok(report);
+        }
+
+        let statuses_store = DbStatusesStore::new(self.db.clone(), CachePolicy::Count(0));
+        match statuses_store.get(self.genesis_hash).optional()? {
+            // Consensus was never initialized, so there is nothing to check
+            None => return Ok(report),
+            Some(BlockStatus::StatusUTXOValid) => {}
+            Some(status) => return Err(IntegrityError::Inconsistent(format!("genesis was initialized with status {:?}", status))),
+        }
+
+        // Genesis used to be initialized without staging, which could leave the first pruning point unwritten
+        let past_pruning_points_store = DbPastPruningPointsStore::new(self.db.clone(), CachePolicy::Count(0));
+        if past_pruning_points_store.get(0).optional()?.is_none() {
+            past_pruning_points_store.insert(0, self.genesis_hash)?;
+            report.repairs.push("restored the genesis as the first past pruning point".to_string());
+        }
+
+        let pruning_point = require(DbPruningStore::new(self.db.clone()).pruning_point(), || "the pruning point".to_string())?;
+        let headers_selected_tip =
+            require(DbHeadersSelectedTipStore::new(self.db.clone()).get(), || "the headers selected tip".to_string())?.hash;
+        let body_tips = require(DbTipsStore::new(self.db.clone()).get(), || "the body tips".to_string())?;
+        let sink = require(DbVirtualStateStore::new(self.db.clone()).get(), || "the virtual state".to_string())?
+            .ghostdag_data
+            .selected_parent;
+
+        let checker = BlockChecker::new(self.db.clone(), statuses_store);
+        checker.check_header(pruning_point)?;
+        report.checked_blocks += self.walk_selected_chain(&checker, headers_selected_tip, pruning_point, |_, _| Ok(()))?;
+        for &tip in body_tips.iter() {
+            let status = checker.check_header(tip)?;
+            if !status.has_block_body() {
+                return Err(IntegrityError::Inconsistent(format!("body tip {} has status {:?}", tip, status)));
+            }
+            checker.check_body(tip, status)?;
+            report.checked_blocks += 1;
+        }
+        report.checked_blocks += self.walk_selected_chain(&checker, sink, pruning_point, |hash, status| {
+            // Blocks of the virtual selected chain are UTXO valid, and their UTXO data is required for reorgs
+            if status != BlockStatus::StatusUTXOValid {
+                return Err(IntegrityError::Inconsistent(format!("chain block {} has status {:?}", hash, status)));
+            }
+            checker.check_body(hash, status)?;
+            checker.check_utxo_data(hash)
+        })?;
+        Ok(report)
+    }
+
+    /// Checks the headers of the selected chain of `tip` down to the depth, the pruning point or genesis, calling
+    /// `check` on each block along with its status, and returns the number of checked blocks
+    fn walk_selected_chain(
+        &self,
+        checker: &BlockChecker,
+        tip: Hash,
+        pruning_point: Hash,
+        mut check: impl FnMut(Hash, BlockStatus) -> IntegrityResult<()>,
+    ) -> IntegrityResult<u64> {
+        let mut current = tip;
+        let mut count = 0;
+        loop {
+            let status = checker.check_header(current)?;
+            check(current, status)?;
+            count += 1;
+            if current == pruning_point || current == self.genesis_hash || count >= self.depth {
+                return Ok(count);
+            }
+            current = checker.ghostdag_store.get_selected_parent(current)?;
+        }
+    }
+
+    /// Deletes all data but the schema version, including the staged operation
+    fn reset(&self) -> StoreResult<()> {
+        let mut batch = WriteBatch::default();
+        for item in self.db.iterator(IteratorMode::Start) {
+            let (key, _) = item?;
+            if key.as_ref() != SCHEMA_VERSION_KEY {
+                batch.delete(key);
+            }
+        }
+        Ok(self.db.write(batch)?)
+    }
+}
+
+/// Uncached readers of the per-block stores
+struct BlockChecker {
+    statuses_store: DbStatusesStore,
+    headers_store: DbHeadersStore,
+    ghostdag_store: DbGhostdagStore,
+    relations_store: DbRelationsStore,
+    block_transactions_store: DbBlockTransactionsStore,
+    utxo_diffs_store: DbUtxoDiffsStore,
+    utxo_multisets_store: DbUtxoMultisetsStore,
+    acceptance_data_store: DbAcceptanceDataStore,
+}
+
+impl BlockChecker {
+    fn new(db: Arc<DB>, statuses_store: DbStatusesStore) -> Self {
+        let policy = CachePolicy::Count(0);
+        let payloads_store = Arc::new(RwLock::new(DbPayloadsStore::new(db.clone(), policy)));
+        Self {
+            statuses_store,
+            headers_store: DbHeadersStore::new(db.clone(), policy),
+            ghostdag_store: DbGhostdagStore::new(db.clone(), policy),
+            relations_store: DbRelationsStore::new(db.clone(), policy),
+            block_transactions_store: DbBlockTransactionsStore::new(db.clone(), policy, payloads_store),
+            utxo_diffs_store: DbUtxoDiffsStore::new(db.clone(), policy),
+            utxo_multisets_store: DbUtxoMultisetsStore::new(db.clone(), policy),
+            acceptance_data_store: DbAcceptanceDataStore::new(db, policy),
+        }
+    }
+
+    /// Checks that the block has a status along with the data written with its header, and returns the status
+    fn check_header(&self, hash: Hash) -> IntegrityResult<BlockStatus> {
+        let status = require(self.statuses_store.get(hash), || format!("the status of block {}", hash))?;
+        require(self.headers_store.get_compact_header_data(hash), || format!("the header of block {}", hash))?;
+        require(self.ghostdag_store.get_compact_data(hash), || format!("the GHOSTDAG data of block {}", hash))?;
+        require(self.relations_store.get_parents(hash), || format!("the parents of block {}", hash))?;
+        Ok(status)
+    }
+
+    fn check_body(&self, hash: Hash, status: BlockStatus) -> IntegrityResult<()> {
+        if status.has_block_body() {
+            require(self.block_transactions_store.get(hash), || format!("the body of block {} with status {:?}", hash, status))?;
+        }
+        Ok(())
+    }
+
+    fn check_utxo_data(&self, hash: Hash) -> IntegrityResult<()> {
+        require(self.utxo_diffs_store.get(hash), || format!("the UTXO diff of block {}", hash))?;
+        require(self.utxo_multisets_store.get(hash), || format!("the UTXO multiset of block {}", hash))?;
+        require(self.acceptance_data_store.get(hash), || format!("the acceptance data of block {}", hash))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        consensus::test_consensus::{create_temp_db, TestConsensus},
+        model::stores::{database::prelude::DbKey, metadata::MetadataStore},
+    };
+    use consensus_core::config::params::MAINNET_PARAMS;
+
+    #[tokio::test]
+    async fn test_integrity_check() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let (_lifetime, db) = create_temp_db();
+        assert!(IntegrityChecker::new(db.clone(), &params).run().unwrap().repairs.is_empty());
+
+        let consensus = TestConsensus::new(db.clone(), &params);
+        let wait_handles = consensus.init();
+        let mut parents = vec![params.genesis.hash];
+        for i in 1..=10u64 {
+            let block = consensus.build_block_with_parents_and_transactions(i.into(), parents, vec![]);
+            consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            parents = vec![i.into()];
+        }
+        consensus.shutdown(wait_handles);
+        drop(consensus);
+
+        let report = IntegrityChecker::new(db.clone(), &params).run().unwrap();
+        assert!(report.repairs.is_empty());
+        assert!(report.checked_blocks >= 20);
+
+        // A missing first pruning point is restored
+        db.delete(DbKey::new(b"past-pruning-points", 0u64.to_le_bytes())).unwrap();
+        let report = IntegrityChecker::new(db.clone(), &params).run().unwrap();
+        assert_eq!(report.repairs.len(), 1);
+        assert_eq!(DbPastPruningPointsStore::new(db.clone(), CachePolicy::Count(0)).get(0).unwrap(), params.genesis.hash);
+
+        // Data missing down the checked depth is detected
+        db.delete(DbKey::new(b"utxo-multisets", Hash::from(5u64))).unwrap();
+        assert!(IntegrityChecker::new(db.clone(), &params).with_depth(3).run().is_ok());
+        assert!(matches!(IntegrityChecker::new(db.clone(), &params).run(), Err(IntegrityError::Inconsistent(_))));
+
+        // An interrupted trusted sync calls for a resync
+        DbMetadataStore::new(db.clone()).stage(StagedOperation::TrustedSync).unwrap();
+        assert!(matches!(IntegrityChecker::new(db.clone(), &params).run(), Err(IntegrityError::Inconsistent(_))));
+
+        // An interrupted genesis initialization is rolled back, so that consensus initializes again
+        DbMetadataStore::new(db.clone()).stage(StagedOperation::GenesisInit).unwrap();
+        assert_eq!(IntegrityChecker::new(db.clone(), &params).run().unwrap().repairs.len(), 1);
+        let consensus = TestConsensus::new(db.clone(), &params);
+        let wait_handles = consensus.init();
+        assert_eq!(consensus.block_status(params.genesis.hash), BlockStatus::StatusUTXOValid);
+        consensus.shutdown(wait_handles);
+        assert!(IntegrityChecker::new(db, &params).run().unwrap().repairs.is_empty());
+    }
+}
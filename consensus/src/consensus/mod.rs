@@ -1,4 +1,6 @@
+pub mod integrity;
 pub mod test_consensus;
+pub mod transfer;
 
 use crate::{
     constants::{
@@ -7,64 +9,101 @@ use crate::{
     },
     errors::{BlockProcessResult, RuleError},
     model::{
-        services::{reachability::MTReachabilityService, relations::MTRelationsService, statuses::MTStatusesService},
+        services::{
+            reachability::{MTReachabilityService, ReachabilityService},
+            relations::MTRelationsService,
+            statuses::MTStatusesService,
+        },
         stores::{
             acceptance_data::DbAcceptanceDataStore,
-            block_transactions::DbBlockTransactionsStore,
+            block_counts::{BlockCountsStoreReader, DbBlockCountsStore},
+            block_transactions::{BlockTransactionsStoreReader, DbBlockTransactionsStore},
             block_window_cache::BlockWindowCacheStore,
             daa::DbDaaStore,
+            database::prelude::{CachePolicy, CachePolicyBuilder},
             depth::DbDepthStore,
-            ghostdag::DbGhostdagStore,
-            headers::DbHeadersStore,
-            headers_selected_tip::DbHeadersSelectedTipStore,
-            past_pruning_points::DbPastPruningPointsStore,
-            pruning::DbPruningStore,
+            errors::StoreResultExtensions,
+            ghostdag::{DbGhostdagStore, GhostdagStoreReader},
+            headers::{DbHeadersStore, HeaderStoreReader},
+            headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
+            metadata::{DbMetadataStore, MetadataStore, MetadataStoreReader, StagedOperation},
+            past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStoreReader},
+            payloads::DbPayloadsStore,
+            pinned_blocks::{DbPinnedBlocksStore, PinnedBlocksStore, PinnedBlocksStoreReader},
+            pruning::{DbPruningStore, PruningStoreReader},
+            pruning_utxoset_position::{DbPruningUtxosetPositionStore, PruningUtxosetPositionStoreReader},
             reachability::DbReachabilityStore,
-            relations::DbRelationsStore,
-            statuses::{DbStatusesStore, StatusesStoreReader},
+            relations::{DbRelationsStore, RelationsStoreReader},
+            retention_root::DbRetentionRootStore,
+            statuses::{ShardedStatusesStore, StatusesStoreReader},
             tips::{DbTipsStore, TipsStoreReader},
             utxo_diffs::DbUtxoDiffsStore,
             utxo_multisets::DbUtxoMultisetsStore,
-            utxo_set::DbUtxoSetStore,
+            utxo_set::{DbUtxoSetStore, UtxoSetStore},
             virtual_state::{DbVirtualStateStore, VirtualStateStoreReader},
             DB,
         },
     },
-    params::Params,
+    notify::root::ConsensusNotificationRoot,
     pipeline::{
         body_processor::BlockBodyProcessor,
         deps_manager::{BlockResultSender, BlockTask},
         header_processor::HeaderProcessor,
+        pruning_processor::PruningProcessor,
         virtual_processor::VirtualStateProcessor,
-        ProcessingCounters,
+        BlockTaskSubmitter, PipelineQueueLengths, ProcessingCounters,
     },
     processes::{
-        block_depth::BlockDepthManager, coinbase::CoinbaseManager, difficulty::DifficultyManager, ghostdag::protocol::GhostdagManager,
-        mass::MassCalculator, parents_builder::ParentsManager, past_median_time::PastMedianTimeManager, pruning::PruningManager,
-        reachability::inquirer as reachability, transaction_validator::TransactionValidator, traversal_manager::DagTraversalManager,
+        block_depth::BlockDepthManager,
+        coinbase::CoinbaseManager,
+        difficulty::{calc_difficulty, DifficultyManager},
+        ghostdag::protocol::GhostdagManager,
+        mass::MassCalculator,
+        parents_builder::ParentsManager,
+        past_median_time::PastMedianTimeManager,
+        pruning::PruningManager,
+        reachability::inquirer as reachability,
+        sync::SyncManager,
+        transaction_validator::{sig_cache::SigCache, TransactionValidator},
+        traversal_manager::DagTraversalManager,
     },
 };
 use consensus_core::{
-    api::ConsensusApi,
-    block::{Block, BlockTemplate},
+    api::{session::SessionLock, ConsensusApi},
+    block::{Block, BlockDagData, BlockTemplate, VirtualInfo},
     blockstatus::BlockStatus,
-    coinbase::MinerData,
-    errors::tx::TxResult,
-    tx::{MutableTransaction, Transaction},
+    chain::{ChainBlocksBatch, VirtualChainBatch},
+    coinbase::{CoinbasePreview, MinerData},
+    config::params::Params,
+    errors::{
+        coinbase::CoinbaseResult,
+        sync::{SyncManagerError, SyncManagerResult},
+        tx::TxResult,
+    },
+    ghostdag::ExternalGhostdagData,
+    header::Header,
+    revalidation::BlockRevalidationReport,
+    stats::{BlockDagInfo, ChainThroughputStats, DaaScoreDrift},
+    time::{DurationMs, DynClock, SystemClock},
+    trusted::{PruningPointTrustedData, TrustedBlock},
+    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    utxo::utxo_diff::UtxoDiff,
     BlockHashSet,
 };
-use crossbeam_channel::{unbounded, Receiver, Sender};
-use futures_util::future::BoxFuture;
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use futures::channel::oneshot;
+use futures_util::future::{join_all, BoxFuture};
 use hashes::Hash;
+#[cfg(feature = "service")]
 use kaspa_core::{core::Core, service::Service};
 use parking_lot::RwLock;
-use std::{future::Future, sync::atomic::Ordering};
+use rocksdb::WriteBatch;
+use std::{future::Future, iter::once, sync::atomic::Ordering};
 use std::{
     ops::DerefMut,
     sync::Arc,
     thread::{self, JoinHandle},
 };
-use tokio::sync::oneshot;
 
 pub type DbGhostdagManager =
     GhostdagManager<DbGhostdagStore, MTRelationsService<DbRelationsStore>, MTReachabilityService<DbReachabilityStore>, DbHeadersStore>;
@@ -81,25 +120,51 @@ impl VirtualStores {
     }
 }
 
+/// Used in order to group the stores of the pruning point UTXO set under a single lock
+pub struct PruningUtxosetStores {
+    pub utxo_set: DbUtxoSetStore,
+    pub position: DbPruningUtxosetPositionStore,
+}
+
+impl PruningUtxosetStores {
+    pub fn new(utxo_set: DbUtxoSetStore, position: DbPruningUtxosetPositionStore) -> Self {
+        Self { utxo_set, position }
+    }
+}
+
 pub struct Consensus {
     // DB
     db: Arc<DB>,
 
+    // Params
+    network_name: &'static str,
+    genesis_hash: Hash,
+    // Whether block data is kept rather than pruned, as recorded in the DB by the node
+    is_archival: bool,
+    // The period for which block data is kept below the pruning point, or `None` if it is not garbage collected
+    retention_period: Option<DurationMs>,
+
     // Channels
-    block_sender: Sender<BlockTask>,
+    block_submitter: BlockTaskSubmitter,
+    pruning_exit_sender: Sender<()>,
 
     // Processors
     header_processor: Arc<HeaderProcessor>,
     pub(super) body_processor: Arc<BlockBodyProcessor>,
     pub virtual_processor: Arc<VirtualStateProcessor>,
+    pub(crate) pruning_processor: Arc<PruningProcessor>,
 
     // Stores
-    statuses_store: Arc<RwLock<DbStatusesStore>>,
+    statuses_store: Arc<ShardedStatusesStore>,
     pub relations_store: Arc<RwLock<DbRelationsStore>>,
     reachability_store: Arc<RwLock<DbReachabilityStore>>,
     pruning_store: Arc<RwLock<DbPruningStore>>,
+    past_pruning_points_store: Arc<DbPastPruningPointsStore>,
+    pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
     headers_selected_tip_store: Arc<RwLock<DbHeadersSelectedTipStore>>,
     body_tips_store: Arc<RwLock<DbTipsStore>>,
+    block_counts_store: Arc<RwLock<DbBlockCountsStore>>,
+    pinned_blocks_store: Arc<RwLock<DbPinnedBlocksStore>>,
     pub headers_store: Arc<DbHeadersStore>,
     pub block_transactions_store: Arc<DbBlockTransactionsStore>,
     // TODO: remove all pub from stores and processors when StoreManager is implemented
@@ -108,18 +173,28 @@ pub struct Consensus {
     pub ghostdag_store: Arc<DbGhostdagStore>,
 
     // Services and managers
-    statuses_service: MTStatusesService<DbStatusesStore>,
+    statuses_service: MTStatusesService<ShardedStatusesStore>,
     relations_service: MTRelationsService<DbRelationsStore>,
     reachability_service: MTReachabilityService<DbReachabilityStore>,
     pub(super) difficulty_manager: DifficultyManager<DbHeadersStore>,
-    pub(super) dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore>,
+    pub(super) dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore, DbHeadersStore>,
     pub(super) ghostdag_manager: DbGhostdagManager,
     pub(super) past_median_time_manager: PastMedianTimeManager<DbHeadersStore, DbGhostdagStore, BlockWindowCacheStore>,
     pub(super) coinbase_manager: CoinbaseManager,
     pub(super) pruning_manager: PruningManager<DbGhostdagStore, DbReachabilityStore, DbHeadersStore, DbPastPruningPointsStore>,
+    pub(super) sync_manager: SyncManager<DbGhostdagStore, DbReachabilityStore, ShardedStatusesStore>,
+
+    // Notifications
+    notification_root: Arc<ConsensusNotificationRoot>,
+
+    // Excludes external sessions from the commits of the virtual state
+    session_lock: SessionLock,
 
     // Counters
     pub counters: Arc<ProcessingCounters>,
+
+    // The signature cache shared by mempool and block validation, exposed for its metrics
+    sig_cache: SigCache,
 }
 
 impl Consensus {
@@ -128,40 +203,58 @@ impl Consensus {
     }
 
     pub fn with_perf_params(db: Arc<DB>, params: &Params, perf_params: &PerfParams) -> Self {
+        Self::with_clock(db, params, perf_params, Arc::new(SystemClock))
+    }
+
+    /// Creates a consensus reading the current time from `clock`, which affects the future timestamp
+    /// validation and the timestamps of built block templates
+    pub fn with_clock(db: Arc<DB>, params: &Params, perf_params: &PerfParams, clock: DynClock) -> Self {
         //
         // Stores
         //
 
-        let pruning_size_for_caches = params.pruning_depth;
-        let pruning_plus_finality_size_for_caches = params.pruning_depth + params.finality_depth;
+        // The cache memory budget is shared in thousandths. Stores with two cached accesses bound each access
+        // by their share, which is thus counted twice, so that all shares sum up to the whole budget.
+        let cache_budget = CachePolicyBuilder::new(perf_params.cache_memory_budget);
 
         // Headers
-        let statuses_store = Arc::new(RwLock::new(DbStatusesStore::new(db.clone(), pruning_plus_finality_size_for_caches)));
-        let relations_store = Arc::new(RwLock::new(DbRelationsStore::new(db.clone(), pruning_plus_finality_size_for_caches)));
-        let reachability_store =
-            Arc::new(RwLock::new(DbReachabilityStore::new(db.clone(), pruning_plus_finality_size_for_caches * 2)));
-        let ghostdag_store = Arc::new(DbGhostdagStore::new(db.clone(), pruning_plus_finality_size_for_caches));
-        let daa_excluded_store = Arc::new(DbDaaStore::new(db.clone(), pruning_size_for_caches));
-        let headers_store = Arc::new(DbHeadersStore::new(db.clone(), perf_params.header_data_cache_size));
-        let depth_store = Arc::new(DbDepthStore::new(db.clone(), perf_params.header_data_cache_size));
+        let statuses_store = Arc::new(ShardedStatusesStore::new(db.clone(), cache_budget.share(10)));
+        let relations_store = Arc::new(RwLock::new(DbRelationsStore::new(db.clone(), cache_budget.share(50))));
+        let reachability_store = Arc::new(RwLock::new(DbReachabilityStore::new(db.clone(), cache_budget.share(150))));
+        let ghostdag_store = Arc::new(DbGhostdagStore::new(db.clone(), cache_budget.share(100)));
+        let daa_excluded_store = Arc::new(DbDaaStore::new(db.clone(), cache_budget.share(30)));
+        let headers_store = Arc::new(DbHeadersStore::new(db.clone(), cache_budget.share(100)));
+        let depth_store = Arc::new(DbDepthStore::new(db.clone(), cache_budget.share(10)));
         // Pruning
         let pruning_store = Arc::new(RwLock::new(DbPruningStore::new(db.clone())));
-        let past_pruning_points_store = Arc::new(DbPastPruningPointsStore::new(db.clone(), 4));
+        let past_pruning_points_store = Arc::new(DbPastPruningPointsStore::new(db.clone(), CachePolicy::Count(4)));
         // Block data
-        let block_transactions_store = Arc::new(DbBlockTransactionsStore::new(db.clone(), perf_params.block_data_cache_size));
-        let utxo_diffs_store = Arc::new(DbUtxoDiffsStore::new(db.clone(), perf_params.block_data_cache_size));
-        let utxo_multisets_store = Arc::new(DbUtxoMultisetsStore::new(db.clone(), perf_params.block_data_cache_size));
-        let acceptance_data_store = Arc::new(DbAcceptanceDataStore::new(db.clone(), perf_params.block_data_cache_size));
+        let payloads_store = Arc::new(RwLock::new(DbPayloadsStore::new(db.clone(), cache_budget.share(50))));
+        let block_transactions_store = Arc::new(DbBlockTransactionsStore::new(db.clone(), cache_budget.share(100), payloads_store));
+        let utxo_diffs_store = Arc::new(DbUtxoDiffsStore::new(db.clone(), cache_budget.share(50)));
+        let utxo_multisets_store = Arc::new(DbUtxoMultisetsStore::new(db.clone(), cache_budget.share(10)));
+        let acceptance_data_store = Arc::new(DbAcceptanceDataStore::new(db.clone(), cache_budget.share(50)));
+        let retention_root_store = Arc::new(RwLock::new(DbRetentionRootStore::new(db.clone())));
         // Tips
         let headers_selected_tip_store = Arc::new(RwLock::new(DbHeadersSelectedTipStore::new(db.clone())));
         let body_tips_store = Arc::new(RwLock::new(DbTipsStore::new(db.clone())));
+        // Counters
+        let block_counts_store = Arc::new(RwLock::new(DbBlockCountsStore::new(db.clone())));
+        // Pinned blocks
+        let pinned_blocks_store = Arc::new(RwLock::new(DbPinnedBlocksStore::new(db.clone())));
         // Block windows
-        let block_window_cache_for_difficulty = Arc::new(BlockWindowCacheStore::new(perf_params.block_window_cache_size));
-        let block_window_cache_for_past_median_time = Arc::new(BlockWindowCacheStore::new(perf_params.block_window_cache_size));
+        let block_window_cache_policy = CachePolicy::Count(perf_params.block_window_cache_size as usize);
+        let block_window_cache_for_difficulty = Arc::new(BlockWindowCacheStore::new(block_window_cache_policy));
+        let block_window_cache_for_past_median_time = Arc::new(BlockWindowCacheStore::new(block_window_cache_policy));
         // Virtual stores
         let virtual_stores = Arc::new(RwLock::new(VirtualStores::new(
             DbVirtualStateStore::new(db.clone()),
-            DbUtxoSetStore::new(db.clone(), perf_params.utxo_set_cache_size, store_names::VIRTUAL_UTXO_SET),
+            DbUtxoSetStore::new(db.clone(), cache_budget.share(40), store_names::VIRTUAL_UTXO_SET),
+        )));
+        // The pruning point UTXO set is only read in chunks, which bypass the cache
+        let pruning_utxoset_stores = Arc::new(RwLock::new(PruningUtxosetStores::new(
+            DbUtxoSetStore::new(db.clone(), CachePolicy::Count(0), store_names::PRUNING_UTXO_SET),
+            DbPruningUtxosetPositionStore::new(db.clone()),
         )));
 
         //
@@ -172,35 +265,33 @@ impl Consensus {
         let relations_service = MTRelationsService::new(relations_store.clone());
         let reachability_service = MTReachabilityService::new(reachability_store.clone());
         let dag_traversal_manager = DagTraversalManager::new(
-            params.genesis_hash,
+            params.genesis.hash,
             ghostdag_store.clone(),
+            headers_store.clone(),
             block_window_cache_for_difficulty.clone(),
             block_window_cache_for_past_median_time.clone(),
-            params.difficulty_window_size,
-            (2 * params.timestamp_deviation_tolerance - 1) as usize, // TODO: incorporate target_time_per_block to this calculation
-        );
-        let past_median_time_manager = PastMedianTimeManager::new(
-            headers_store.clone(),
-            dag_traversal_manager.clone(),
-            params.timestamp_deviation_tolerance as usize,
-            params.genesis_timestamp,
+            params.difficulty_window(),
+            params.past_median_time_window(),
+            params.sampling_activation_daa_score,
         );
+        let past_median_time_manager =
+            PastMedianTimeManager::new(headers_store.clone(), dag_traversal_manager.clone(), params.genesis.timestamp);
         let difficulty_manager = DifficultyManager::new(
             headers_store.clone(),
-            params.genesis_bits,
-            params.difficulty_window_size,
+            params.genesis.bits,
+            params.difficulty_window(),
             params.target_time_per_block,
         );
         let depth_manager = BlockDepthManager::new(
             params.merge_depth,
             params.finality_depth,
-            params.genesis_hash,
+            params.genesis.hash,
             depth_store.clone(),
             reachability_service.clone(),
             ghostdag_store.clone(),
         );
         let ghostdag_manager = GhostdagManager::new(
-            params.genesis_hash,
+            params.genesis.hash,
             params.ghostdag_k,
             ghostdag_store.clone(),
             relations_service.clone(),
@@ -215,8 +306,12 @@ impl Consensus {
             params.pre_deflationary_phase_base_subsidy,
         );
 
-        let mass_calculator =
-            MassCalculator::new(params.mass_per_tx_byte, params.mass_per_script_pub_key_byte, params.mass_per_sig_op);
+        let mass_calculator = MassCalculator::new(
+            params.mass_per_tx_byte,
+            params.mass_per_script_pub_key_byte,
+            params.mass_per_sig_op,
+            params.storage_mass_per_utxo,
+        );
 
         let transaction_validator = TransactionValidator::new(
             params.max_tx_inputs,
@@ -227,11 +322,12 @@ impl Consensus {
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
         );
+        let sig_cache = transaction_validator.sig_cache().clone();
 
         let pruning_manager = PruningManager::new(
             params.pruning_depth,
             params.finality_depth,
-            params.genesis_hash,
+            params.genesis.hash,
             reachability_service.clone(),
             ghostdag_store.clone(),
             headers_store.clone(),
@@ -240,17 +336,24 @@ impl Consensus {
 
         let parents_manager = ParentsManager::new(
             params.max_block_level,
-            params.genesis_hash,
+            params.genesis.hash,
             headers_store.clone(),
             reachability_service.clone(),
             relations_store.clone(),
         );
 
-        let (sender, receiver): (Sender<BlockTask>, Receiver<BlockTask>) = unbounded();
-        let (body_sender, body_receiver): (Sender<BlockTask>, Receiver<BlockTask>) = unbounded();
-        let (virtual_sender, virtual_receiver): (Sender<BlockTask>, Receiver<BlockTask>) = unbounded();
+        let sync_manager = SyncManager::new(ghostdag_store.clone(), reachability_service.clone(), statuses_store.clone());
+
+        // The pipeline queues are bounded, so that a flood of blocks is held back by the submitters rather than queued
+        let (sender, receiver): (Sender<BlockTask>, Receiver<BlockTask>) = bounded(perf_params.header_queue_capacity);
+        let (body_sender, body_receiver): (Sender<BlockTask>, Receiver<BlockTask>) = bounded(perf_params.body_queue_capacity);
+        let (virtual_sender, virtual_receiver): (Sender<BlockTask>, Receiver<BlockTask>) = bounded(perf_params.virtual_queue_capacity);
+        let (pruning_exit_sender, pruning_exit_receiver): (Sender<()>, Receiver<()>) = unbounded();
 
         let counters = Arc::new(ProcessingCounters::default());
+        let is_archival = DbMetadataStore::new(db.clone()).get_archival().unwrap_option().unwrap_or_default();
+        let notification_root = Arc::new(ConsensusNotificationRoot::new());
+        let session_lock = SessionLock::default();
 
         //
         // Thread-pools
@@ -284,6 +387,7 @@ impl Consensus {
             body_sender,
             block_processors_pool.clone(),
             params,
+            clock.clone(),
             db.clone(),
             relations_store.clone(),
             reachability_store.clone(),
@@ -294,6 +398,7 @@ impl Consensus {
             pruning_store.clone(),
             depth_store,
             headers_selected_tip_store.clone(),
+            block_counts_store.clone(),
             block_window_cache_for_difficulty,
             block_window_cache_for_past_median_time,
             reachability_service.clone(),
@@ -311,25 +416,27 @@ impl Consensus {
             body_receiver,
             virtual_sender,
             block_processors_pool,
+            params,
             db.clone(),
             statuses_store.clone(),
             ghostdag_store.clone(),
             headers_store.clone(),
             block_transactions_store.clone(),
             body_tips_store.clone(),
+            block_counts_store.clone(),
             reachability_service.clone(),
             coinbase_manager.clone(),
-            mass_calculator,
+            mass_calculator.clone(),
             transaction_validator.clone(),
             past_median_time_manager.clone(),
-            params.max_block_mass,
-            params.genesis_hash,
+            counters.clone(),
         ));
 
         let virtual_processor = Arc::new(VirtualStateProcessor::new(
             virtual_receiver,
             virtual_pool,
             params,
+            clock,
             db.clone(),
             statuses_store.clone(),
             ghostdag_store.clone(),
@@ -337,38 +444,68 @@ impl Consensus {
             daa_excluded_store,
             block_transactions_store.clone(),
             pruning_store.clone(),
-            past_pruning_points_store,
+            past_pruning_points_store.clone(),
             body_tips_store.clone(),
             utxo_diffs_store,
             utxo_multisets_store,
-            acceptance_data_store,
+            acceptance_data_store.clone(),
             virtual_stores,
+            pruning_utxoset_stores.clone(),
             ghostdag_manager.clone(),
             reachability_service.clone(),
             relations_service.clone(),
             dag_traversal_manager.clone(),
             difficulty_manager.clone(),
             coinbase_manager.clone(),
+            mass_calculator,
             transaction_validator,
             past_median_time_manager.clone(),
             pruning_manager.clone(),
             parents_manager,
             depth_manager,
+            notification_root.clone(),
+            session_lock.clone(),
+            counters.clone(),
+        ));
+
+        let pruning_processor = Arc::new(PruningProcessor::new(
+            pruning_exit_receiver,
+            params,
+            db.clone(),
+            ghostdag_store.clone(),
+            headers_store.clone(),
+            block_transactions_store.clone(),
+            acceptance_data_store,
+            pruning_store.clone(),
+            past_pruning_points_store.clone(),
+            pinned_blocks_store.clone(),
+            retention_root_store,
+            reachability_service.clone(),
         ));
 
         Self {
             db,
-            block_sender: sender,
+            network_name: params.network_name,
+            genesis_hash: params.genesis.hash,
+            is_archival,
+            retention_period: None,
+            block_submitter: BlockTaskSubmitter::new(sender),
+            pruning_exit_sender,
             header_processor,
             body_processor,
             virtual_processor,
+            pruning_processor,
             statuses_store,
             relations_store,
             reachability_store,
             ghostdag_store,
             pruning_store,
+            past_pruning_points_store,
+            pruning_utxoset_stores,
             headers_selected_tip_store,
             body_tips_store,
+            block_counts_store,
+            pinned_blocks_store,
             headers_store,
             block_transactions_store,
 
@@ -381,37 +518,228 @@ impl Consensus {
             past_median_time_manager,
             coinbase_manager,
             pruning_manager,
+            sync_manager,
+
+            notification_root,
+            session_lock,
 
             counters,
+            sig_cache,
         }
     }
 
+    /// Garbage collects the data of blocks which are older than the pruning point by more than `retention_period`, as
+    /// determined by the retention policies of the stores. Archival nodes keep all data regardless
+    pub fn with_retention_period(mut self, retention_period: DurationMs) -> Self {
+        self.retention_period = Some(retention_period);
+        self
+    }
+
     pub fn init(&self) -> Vec<JoinHandle<()>> {
         // Ensure that reachability store is initialized
         reachability::init(self.reachability_store.write().deref_mut()).unwrap();
 
-        // Ensure that genesis was processed
+        // Ensure that genesis was processed. The processors initialize genesis in several batches, so the
+        // initialization is staged in order for an interruption to be detected and repaired on the next startup
+        let genesis_pending = self.statuses_store.get(self.genesis_hash).unwrap_option() != Some(BlockStatus::StatusUTXOValid);
+        let mut metadata_store = DbMetadataStore::new(self.db.clone());
+        if genesis_pending {
+            metadata_store.stage(StagedOperation::GenesisInit).unwrap();
+        }
         self.header_processor.process_genesis_if_needed();
         self.body_processor.process_genesis_if_needed();
         self.virtual_processor.process_genesis_if_needed();
+        if genesis_pending {
+            metadata_store.unstage().unwrap();
+        }
 
         // Spawn the asynchronous processors.
         let header_processor = self.header_processor.clone();
         let body_processor = self.body_processor.clone();
         let virtual_processor = self.virtual_processor.clone();
 
-        vec![
+        let mut handles = vec![
             thread::Builder::new().name("header-processor".to_string()).spawn(move || header_processor.worker()).unwrap(),
             thread::Builder::new().name("body-processor".to_string()).spawn(move || body_processor.worker()).unwrap(),
             thread::Builder::new().name("virtual-processor".to_string()).spawn(move || virtual_processor.worker()).unwrap(),
-        ]
+        ];
+        // Archival nodes keep all block data
+        if let Some(retention_period) = self.retention_period.filter(|_| !self.is_archival) {
+            let pruning_processor = self.pruning_processor.clone();
+            handles.push(
+                thread::Builder::new()
+                    .name("pruning-processor".to_string())
+                    .spawn(move || pruning_processor.worker(retention_period))
+                    .unwrap(),
+            );
+        }
+        handles
     }
 
+    /// Submits a block for processing. While the header queue is full, the block waits in submission order for room
+    /// in it, without blocking the calling thread if it runs on a tokio runtime. There, the returned future waits for
+    /// room in the bounded overflow queue of the header queue as well, once it is full
     pub fn validate_and_insert_block(&self, block: Block) -> impl Future<Output = BlockProcessResult<BlockStatus>> {
         let (tx, rx): (BlockResultSender, _) = oneshot::channel();
-        self.block_sender.send(BlockTask::Process(block, vec![tx])).unwrap();
+        let submission = self.block_submitter.submit(BlockTask::Process(block, vec![tx]), &self.counters.header_backpressure_counts);
+        self.counters.blocks_submitted.fetch_add(1, Ordering::SeqCst);
+        async {
+            submission.await;
+            rx.await.unwrap()
+        }
+    }
+
+    /// Submits a batch of blocks in topological order for processing as a group, which shares the locks and DB write
+    /// batches of each pipeline stage. Results are returned in the order of the batch. The batch is submitted as
+    /// [`Self::validate_and_insert_block`] submits a block
+    pub fn validate_and_insert_blocks(&self, blocks: Vec<Block>) -> impl Future<Output = Vec<BlockProcessResult<BlockStatus>>> {
+        let count = blocks.len() as u64;
+        let (tasks, receivers): (Vec<_>, Vec<_>) = blocks
+            .into_iter()
+            .map(|block| {
+                let (tx, rx): (BlockResultSender, _) = oneshot::channel();
+                ((block, vec![tx]), rx)
+            })
+            .unzip();
+        let submission = if tasks.is_empty() {
+            None
+        } else {
+            Some(self.block_submitter.submit(BlockTask::ProcessBatch(tasks), &self.counters.header_backpressure_counts))
+        };
+        self.counters.blocks_submitted.fetch_add(count, Ordering::SeqCst);
+        async {
+            if let Some(submission) = submission {
+                submission.await;
+            }
+            join_all(receivers).await.into_iter().map(|res| res.unwrap()).collect()
+        }
+    }
+
+    /// Begins a trusted sync. The sync is staged, since trusted blocks leave the DAG without their past until it completes.
+    /// A sync is only begun by a node holding no blocks but genesis, since the imported pruning point replaces its DAG
+    pub fn begin_trusted_sync(&self) -> BlockProcessResult<()> {
+        if self.is_trusted_sync() {
+            return Ok(());
+        }
+        if self.headers_selected_tip_store.read().get().unwrap().hash != self.genesis_hash {
+            return Err(RuleError::TrustedSyncOfNonEmptyDag);
+        }
+        DbMetadataStore::new(self.db.clone()).stage(StagedOperation::TrustedSync).unwrap();
+        Ok(())
+    }
+
+    pub fn end_trusted_sync(&self) {
+        if self.is_trusted_sync() {
+            DbMetadataStore::new(self.db.clone()).unstage().unwrap();
+        }
+    }
+
+    fn is_trusted_sync(&self) -> bool {
+        DbMetadataStore::new(self.db.clone()).get_staged_operation().unwrap_option() == Some(StagedOperation::TrustedSync)
+    }
+
+    /// Inserts a trusted block synchronously, bypassing the pipeline queues. Trusted blocks are expected in topological
+    /// order, and are not passed to the virtual processor since their past lacks the data required for UTXO validation
+    pub fn validate_and_insert_trusted_block(&self, trusted_block: TrustedBlock) -> BlockProcessResult<BlockStatus> {
+        let TrustedBlock { block, ghostdag_data, mergeset_non_daa } = trusted_block;
+        if !self.is_trusted_sync() {
+            return Err(RuleError::TrustedBlockOutsideSync(block.hash()));
+        }
         self.counters.blocks_submitted.fetch_add(1, Ordering::SeqCst);
-        async { rx.await.unwrap() }
+        let status = self.header_processor.process_trusted_header(
+            &block.header,
+            Arc::new((&ghostdag_data).into()),
+            mergeset_non_daa.into_iter().collect(),
+        )?;
+        if block.is_header_only() {
+            return Ok(status);
+        }
+        self.body_processor.process_trusted_body(&block)
+    }
+
+    pub fn clear_imported_pruning_point_utxos(&self) -> BlockProcessResult<()> {
+        if !self.is_trusted_sync() {
+            return Err(RuleError::UtxoSetImportOutsideSync);
+        }
+        self.pruning_utxoset_stores.write().utxo_set.clear().unwrap();
+        Ok(())
+    }
+
+    pub fn append_imported_pruning_point_utxos(&self, utxos: &[(TransactionOutpoint, UtxoEntry)]) -> BlockProcessResult<()> {
+        if !self.is_trusted_sync() {
+            return Err(RuleError::UtxoSetImportOutsideSync);
+        }
+        let utxo_diff = UtxoDiff::new(utxos.iter().cloned().collect(), Default::default());
+        self.pruning_utxoset_stores.write().utxo_set.write_diff(&utxo_diff).unwrap();
+        Ok(())
+    }
+
+    /// Imports the pruning point, the last of `past_pruning_points`, along with its UTXO set appended so far, see
+    /// [`VirtualStateProcessor::import_pruning_point_utxo_set`]
+    pub fn import_pruning_point_utxo_set(&self, past_pruning_points: Vec<Hash>) -> BlockProcessResult<()> {
+        if !self.is_trusted_sync() {
+            return Err(RuleError::UtxoSetImportOutsideSync);
+        }
+        self.virtual_processor.import_pruning_point_utxo_set(&past_pruning_points)?;
+        self.pruning_processor.init_imported_retention_root(*past_pruning_points.last().unwrap());
+        Ok(())
+    }
+
+    /// Returns the trusted data of the pruning point, see [`PruningPointTrustedData`]. The header-only blocks are the
+    /// past pruning points and the blocks merged by the selected chain of the pruning point from its windows root, see
+    /// [`PruningProcessor::windows_root`]. The DAA data of the blocks is left out, since the syncing node never UTXO
+    /// validates them
+    pub fn get_pruning_point_trusted_data(&self) -> SyncManagerResult<PruningPointTrustedData> {
+        let pruning_info = self.pruning_store.read().get().unwrap();
+        let pruning_point = pruning_info.pruning_point;
+        let past_pruning_points: Vec<Hash> =
+            (0..=pruning_info.index).map(|index| self.past_pruning_points_store.get(index).unwrap()).collect();
+
+        let windows_root = self.pruning_processor.windows_root(pruning_point);
+        let (window_hashes, _) = self.sync_manager.get_hashes_between(windows_root, pruning_point, usize::MAX)?;
+        let mut header_only: BlockHashSet =
+            past_pruning_points.iter().copied().chain(once(windows_root)).chain(window_hashes).collect();
+        header_only.remove(&pruning_point);
+
+        // The blocks of the anticone of the pruning point which are merged by the selected chain above it
+        let sink = self.virtual_processor.virtual_stores.read().state.get().unwrap().ghostdag_data.selected_parent;
+        let (future_hashes, _) = self.sync_manager.get_hashes_between(pruning_point, sink, usize::MAX)?;
+        let with_body = once(pruning_point)
+            .chain(future_hashes.into_iter().filter(|&hash| !self.reachability_service.is_dag_ancestor_of(pruning_point, hash)));
+
+        let trusted_block = |hash: Hash, has_body: bool| -> SyncManagerResult<TrustedBlock> {
+            let header = match self.headers_store.get_header(hash).unwrap_option() {
+                Some(header) => header,
+                None => return Err(SyncManagerError::DataPruned(hash)),
+            };
+            let transactions = if has_body {
+                match self.block_transactions_store.get(hash).unwrap_option() {
+                    Some(transactions) => transactions,
+                    None => return Err(SyncManagerError::DataPruned(hash)),
+                }
+            } else {
+                Default::default()
+            };
+            let block = Block::from_arcs(header, transactions);
+            let ghostdag_data = self.ghostdag_store.get_data(hash).unwrap();
+            Ok(TrustedBlock::new(block, ghostdag_data.as_ref().into(), Vec::new()))
+        };
+        let mut blocks = header_only
+            .into_iter()
+            .map(|hash| trusted_block(hash, false))
+            .chain(with_body.map(|hash| trusted_block(hash, true)))
+            .collect::<SyncManagerResult<Vec<_>>>()?;
+        // Blue work is strictly increasing along the edges of the DAG, hence sorting by it yields a topological order
+        blocks.sort_by_key(|trusted_block| (trusted_block.ghostdag_data.blue_work, trusted_block.block.hash()));
+
+        let pruning_points = past_pruning_points
+            .into_iter()
+            .map(|hash| match self.headers_store.get_header(hash).unwrap_option() {
+                Some(header) => Ok(header),
+                None => Err(SyncManagerError::DataPruned(hash)),
+            })
+            .collect::<SyncManagerResult<Vec<_>>>()?;
+        Ok(PruningPointTrustedData { pruning_points, blocks })
     }
 
     pub fn build_block_template(&self, miner_data: MinerData, txs: Vec<Transaction>) -> Result<BlockTemplate, RuleError> {
@@ -423,15 +751,191 @@ impl Consensus {
     }
 
     pub fn block_status(&self, hash: Hash) -> BlockStatus {
-        self.statuses_store.read().get(hash).unwrap()
+        self.statuses_store.get(hash).unwrap()
+    }
+
+    /// Re-runs header and body validations for a stored block and reports the outcome along with the
+    /// stored status. Returns `None` if the block header is not stored.
+    pub fn revalidate_block(&self, hash: Hash) -> Option<BlockRevalidationReport> {
+        let stored_status = self.statuses_store.get(hash).unwrap_option()?;
+        let header = self.headers_store.get_header(hash).unwrap_option()?;
+        let header_error = self.header_processor.revalidate_header(&header).err();
+        let (body_revalidated, body_error) = match self.block_transactions_store.get(hash).unwrap_option() {
+            Some(transactions) => (true, self.body_processor.revalidate_body(&Block::from_arcs(header, transactions)).err()),
+            None => (false, None),
+        };
+        Some(BlockRevalidationReport { stored_status, header_error, body_revalidated, body_error })
+    }
+
+    /// Returns the block with the given hash, or `None` if its header or body is not stored
+    pub fn get_block(&self, hash: Hash) -> Option<Block> {
+        let header = self.headers_store.get_header(hash).unwrap_option()?;
+        let transactions = self.block_transactions_store.get(hash).unwrap_option()?;
+        Some(Block::from_arcs(header, transactions))
+    }
+
+    /// Returns the number of confirmations of a block, i.e., the blue score of the sink minus the blue score of the
+    /// selected chain block merging the block, plus one. Returns `None` if the block is unknown, invalid or not yet
+    /// merged by the virtual selected parent chain
+    pub fn get_confirmations(&self, hash: Hash) -> Option<u64> {
+        match self.statuses_store.get(hash).unwrap_option() {
+            None | Some(BlockStatus::StatusInvalid) => return None,
+            Some(_) => {}
+        }
+        let sink = self.virtual_processor.virtual_stores.read().state.get().unwrap().ghostdag_data.selected_parent;
+        if !self.reachability_service.is_dag_ancestor_of(hash, sink) {
+            return None;
+        }
+        // The chain blocks in the future of the block form a suffix of the selected chain, the lowest of which merges it.
+        // A chain block merges itself
+        let mut merging = sink;
+        if self.reachability_service.is_chain_ancestor_of(hash, sink) {
+            merging = hash;
+        } else {
+            loop {
+                let selected_parent = self.ghostdag_store.get_selected_parent(merging).unwrap();
+                if !self.reachability_service.is_dag_ancestor_of(hash, selected_parent) {
+                    break;
+                }
+                merging = selected_parent;
+            }
+        }
+        let sink_blue_score = self.ghostdag_store.get_blue_score(sink).unwrap();
+        Some(sink_blue_score - self.ghostdag_store.get_blue_score(merging).unwrap() + 1)
+    }
+
+    /// Returns whether `low` is in the selected chain of `high`. Fails if one of the blocks is unknown or invalid
+    pub fn is_chain_ancestor_of(&self, low: Hash, high: Hash) -> SyncManagerResult<bool> {
+        for hash in [low, high] {
+            match self.statuses_store.get(hash).unwrap_option() {
+                None | Some(BlockStatus::StatusInvalid) => return Err(SyncManagerError::BlockNotFound(hash)),
+                Some(_) => {}
+            }
+        }
+        Ok(self.reachability_service.is_chain_ancestor_of(low, high))
+    }
+
+    /// Pins blocks, so that pruning keeps them along with their data. Fails without pinning any block if one of them
+    /// is not known
+    pub fn pin_blocks(&self, hashes: &[Hash]) -> SyncManagerResult<()> {
+        if let Some(&unknown) = hashes.iter().find(|&&hash| self.statuses_store.get(hash).unwrap_option().is_none()) {
+            return Err(SyncManagerError::BlockNotFound(unknown));
+        }
+        self.pinned_blocks_store.write().pin(hashes).unwrap();
+        Ok(())
+    }
+
+    /// Returns whether the block is pinned, in which case pruning must keep it along with its data
+    pub fn is_block_pinned(&self, hash: Hash) -> bool {
+        self.pinned_blocks_store.read().is_pinned(hash).unwrap()
+    }
+
+    /// Returns the hashes of the blocks in the future of `low` and in the past of `high`, both included, in topological
+    /// order. The region is collected by traversing the parents from `high` down to the blocks which are not in the
+    /// future of `low`
+    pub fn get_block_region(&self, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<Vec<Hash>> {
+        for hash in [low, high] {
+            match self.statuses_store.get(hash).unwrap_option() {
+                None | Some(BlockStatus::StatusInvalid) => return Err(SyncManagerError::BlockNotFound(hash)),
+                Some(_) => {}
+            }
+        }
+        if !self.reachability_service.is_dag_ancestor_of(low, high) {
+            return Err(SyncManagerError::NotInPast(low, high));
+        }
+        let relations = self.relations_store.read();
+        let mut visited = BlockHashSet::from_iter([high]);
+        let mut queue = std::collections::VecDeque::from([high]);
+        let mut blocks = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            if blocks.len() == max_blocks {
+                return Err(SyncManagerError::RegionTooLarge(low, high, max_blocks));
+            }
+            blocks.push((self.ghostdag_store.get_blue_work(current).unwrap(), current));
+            for &parent in relations.get_parents(current).unwrap().iter() {
+                if visited.insert(parent) && self.reachability_service.is_dag_ancestor_of(low, parent) {
+                    queue.push_back(parent);
+                }
+            }
+        }
+        // Blue work strictly increases along DAG edges, hence sorting by it results in a topological order
+        blocks.sort();
+        Ok(blocks.into_iter().map(|(_, hash)| hash).collect())
+    }
+
+    /// Returns the hashes of all blocks with a body in the future of the pruning point, in topological order
+    pub fn pruning_point_future_blocks(&self) -> Vec<Hash> {
+        let pruning_point = self.pruning_store.read().pruning_point().unwrap();
+        let relations = self.relations_store.read();
+        let mut visited = BlockHashSet::new();
+        let mut queue = std::collections::VecDeque::from([pruning_point]);
+        let mut blocks = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for &child in relations.get_children(current).unwrap().iter() {
+                if visited.insert(child) {
+                    queue.push_back(child);
+                    if self.statuses_store.get(child).unwrap().has_block_body() {
+                        blocks.push((self.ghostdag_store.get_blue_work(child).unwrap(), child));
+                    }
+                }
+            }
+        }
+        // Blue work strictly increases along DAG edges, hence sorting by it results in a topological order
+        blocks.sort();
+        blocks.into_iter().map(|(_, hash)| hash).collect()
+    }
+
+    /// Returns the retention root, i.e., the selected chain block below which block bodies, acceptance data and headers
+    /// may have been garbage collected
+    pub fn retention_root(&self) -> Hash {
+        self.pruning_processor.retention_root()
+    }
+
+    /// Fails if the data of the blocks merged above `low` may have been garbage collected, i.e., if
+    /// `low` is known and the retention root is not in its selected chain
+    fn check_retained(&self, low: Hash) -> SyncManagerResult<()> {
+        if self.ghostdag_store.has(low).unwrap() && !self.reachability_service.is_chain_ancestor_of(self.retention_root(), low) {
+            return Err(SyncManagerError::DataPruned(low));
+        }
+        Ok(())
     }
 
     pub fn processing_counters(&self) -> &Arc<ProcessingCounters> {
         &self.counters
     }
 
+    pub fn sig_cache(&self) -> &SigCache {
+        &self.sig_cache
+    }
+
+    /// Returns the number of tasks queued at each pipeline stage. The header queue holds the submitted blocks, including
+    /// the ones waiting for room in it
+    pub fn pipeline_queue_lengths(&self) -> PipelineQueueLengths {
+        PipelineQueueLengths {
+            header: self.block_submitter.len(),
+            body: self.body_processor.queue_len(),
+            virtual_state: self.virtual_processor.queue_len(),
+        }
+    }
+
+    /// Returns the capacity of the queue of each pipeline stage
+    pub fn pipeline_queue_capacities(&self) -> PipelineQueueLengths {
+        PipelineQueueLengths {
+            header: self.block_submitter.capacity(),
+            body: self.body_processor.queue_capacity(),
+            virtual_state: self.virtual_processor.queue_capacity(),
+        }
+    }
+
+    /// Returns the notification root, on which external consumers of consensus notifications can be registered
+    pub fn notification_root(&self) -> &Arc<ConsensusNotificationRoot> {
+        &self.notification_root
+    }
+
     pub fn signal_exit(&self) {
-        self.block_sender.send(BlockTask::Exit).unwrap();
+        // Submitted after all pending blocks, so that the header processor handles them before exiting
+        self.block_submitter.submit_exit(&self.counters.header_backpressure_counts);
+        self.pruning_exit_sender.send(()).unwrap();
     }
 
     pub fn shutdown(&self, wait_handles: Vec<JoinHandle<()>>) {
@@ -456,20 +960,282 @@ impl ConsensusApi for Consensus {
         Box::pin(self.as_ref().validate_and_insert_block(block))
     }
 
-    fn validate_mempool_transaction_and_populate(self: Arc<Self>, transaction: &mut MutableTransaction) -> TxResult<()> {
-        self.virtual_processor.validate_mempool_transaction_and_populate(transaction)?;
+    fn validate_and_insert_blocks(
+        self: Arc<Self>,
+        blocks: Vec<Block>,
+        _update_virtual: bool,
+    ) -> BoxFuture<'static, Vec<BlockProcessResult<BlockStatus>>> {
+        Box::pin(self.as_ref().validate_and_insert_blocks(blocks))
+    }
+
+    fn begin_trusted_sync(self: Arc<Self>) -> BlockProcessResult<()> {
+        self.as_ref().begin_trusted_sync()
+    }
+
+    fn end_trusted_sync(self: Arc<Self>) {
+        self.as_ref().end_trusted_sync()
+    }
+
+    fn validate_and_insert_trusted_block(self: Arc<Self>, block: TrustedBlock) -> BlockProcessResult<BlockStatus> {
+        self.as_ref().validate_and_insert_trusted_block(block)
+    }
+
+    fn clear_imported_pruning_point_utxos(self: Arc<Self>) -> BlockProcessResult<()> {
+        self.as_ref().clear_imported_pruning_point_utxos()
+    }
+
+    fn append_imported_pruning_point_utxos(self: Arc<Self>, utxos: &[(TransactionOutpoint, UtxoEntry)]) -> BlockProcessResult<()> {
+        self.as_ref().append_imported_pruning_point_utxos(utxos)
+    }
+
+    fn import_pruning_point_utxo_set(self: Arc<Self>, past_pruning_points: Vec<Hash>) -> BlockProcessResult<()> {
+        self.as_ref().import_pruning_point_utxo_set(past_pruning_points)
+    }
+
+    fn validate_mempool_transaction(self: Arc<Self>, transaction: &mut MutableTransaction) -> TxResult<()> {
+        self.virtual_processor.validate_mempool_transaction(transaction)?;
         Ok(())
     }
 
     fn calculate_transaction_mass(self: Arc<Self>, transaction: &Transaction) -> u64 {
-        self.body_processor.mass_calculator.calc_tx_mass(transaction)
+        self.body_processor.mass_calculator.calc_tx_mass(transaction).combined()
     }
 
     fn get_virtual_daa_score(self: Arc<Self>) -> u64 {
         self.virtual_processor.virtual_stores.read().state.get().unwrap().daa_score
     }
+
+    fn get_virtual_parents(self: Arc<Self>) -> Vec<Hash> {
+        self.virtual_processor.virtual_stores.read().state.get().unwrap().parents.clone()
+    }
+
+    fn get_virtual_info(self: Arc<Self>) -> VirtualInfo {
+        let virtual_state = self.virtual_processor.virtual_stores.read().state.get().unwrap();
+        VirtualInfo {
+            parents: virtual_state.parents.clone(),
+            ghostdag_data: (&virtual_state.ghostdag_data).into(),
+            daa_score: virtual_state.daa_score,
+            bits: virtual_state.bits,
+            past_median_time: virtual_state.past_median_time,
+        }
+    }
+
+    fn session_lock(self: Arc<Self>) -> SessionLock {
+        self.session_lock.clone()
+    }
+
+    fn get_virtual_utxos(
+        self: Arc<Self>,
+        from_outpoint: Option<TransactionOutpoint>,
+        chunk_size: usize,
+    ) -> Vec<(TransactionOutpoint, UtxoEntry)> {
+        self.virtual_processor.virtual_stores.read().utxo_set.get_chunk(from_outpoint, chunk_size).unwrap()
+    }
+
+    fn revalidate_block(self: Arc<Self>, hash: Hash) -> Option<BlockRevalidationReport> {
+        self.as_ref().revalidate_block(hash)
+    }
+
+    fn get_coinbase_preview(self: Arc<Self>, miner_data: MinerData) -> CoinbaseResult<CoinbasePreview> {
+        self.virtual_processor.coinbase_preview(miner_data)
+    }
+
+    fn get_chain_throughput_stats(self: Arc<Self>, window_size: u64) -> ChainThroughputStats {
+        self.virtual_processor.chain_throughput_stats(window_size)
+    }
+
+    fn get_block_dag_info(self: Arc<Self>) -> BlockDagInfo {
+        let counts = self.block_counts_store.read().get().unwrap();
+        let tip_hashes = self.body_tips_store.read().get().unwrap().iter().copied().collect();
+        let virtual_state = self.virtual_processor.virtual_stores.read().state.get().unwrap();
+        BlockDagInfo {
+            network_name: self.network_name.to_string(),
+            header_count: counts.header_count,
+            block_count: counts.block_count,
+            tip_hashes,
+            virtual_parent_hashes: virtual_state.parents.clone(),
+            virtual_daa_score: virtual_state.daa_score,
+            difficulty: calc_difficulty(virtual_state.bits),
+            past_median_time: virtual_state.past_median_time,
+            pruning_point_hash: self.pruning_store.read().pruning_point().unwrap(),
+        }
+    }
+
+    fn get_daa_score_drift(self: Arc<Self>) -> DaaScoreDrift {
+        self.virtual_processor.daa_score_drift()
+    }
+
+    fn max_block_mass(self: Arc<Self>) -> u64 {
+        self.body_processor.max_block_mass
+    }
+
+    fn get_block_status(self: Arc<Self>, hash: Hash) -> Option<BlockStatus> {
+        self.statuses_store.get(hash).unwrap_option()
+    }
+
+    fn get_header(self: Arc<Self>, hash: Hash) -> Option<Arc<Header>> {
+        self.headers_store.get_header(hash).unwrap_option()
+    }
+
+    fn get_block(self: Arc<Self>, hash: Hash) -> Option<Block> {
+        self.as_ref().get_block(hash)
+    }
+
+    fn get_payload(self: Arc<Self>, payload_hash: Hash) -> Option<Arc<Vec<u8>>> {
+        self.block_transactions_store.get_payload(payload_hash).unwrap_option()
+    }
+
+    fn get_block_dag_data(self: Arc<Self>, hash: Hash) -> Option<BlockDagData> {
+        let status = self.statuses_store.get(hash).unwrap_option()?;
+        let bits = self.headers_store.get_bits(hash).unwrap_option()?;
+        let ghostdag_data = self.ghostdag_store.get_data(hash).unwrap();
+        let children = self.relations_store.read().get_children(hash).unwrap().iter().copied().collect();
+        let sink = self.clone().get_sink();
+        Some(BlockDagData {
+            is_header_only: !status.has_block_body(),
+            difficulty: calc_difficulty(bits),
+            selected_parent: ghostdag_data.selected_parent,
+            blue_score: ghostdag_data.blue_score,
+            mergeset_blues: ghostdag_data.mergeset_blues.iter().copied().collect(),
+            mergeset_reds: ghostdag_data.mergeset_reds.iter().copied().collect(),
+            children,
+            is_chain_block: self.reachability_service.is_chain_ancestor_of(hash, sink),
+        })
+    }
+
+    fn get_ghostdag_data(self: Arc<Self>, hash: Hash) -> Option<ExternalGhostdagData> {
+        self.ghostdag_store.get_data(hash).unwrap_option().map(|data| data.as_ref().into())
+    }
+
+    fn is_chain_ancestor_of(self: Arc<Self>, low: Hash, high: Hash) -> SyncManagerResult<bool> {
+        self.as_ref().is_chain_ancestor_of(low, high)
+    }
+
+    fn get_confirmations(self: Arc<Self>, hash: Hash) -> Option<u64> {
+        self.as_ref().get_confirmations(hash)
+    }
+
+    fn get_headers_selected_tip(self: Arc<Self>) -> Hash {
+        self.headers_selected_tip_store.read().get().unwrap().hash
+    }
+
+    fn get_sink(self: Arc<Self>) -> Hash {
+        self.virtual_processor.virtual_stores.read().state.get().unwrap().ghostdag_data.selected_parent
+    }
+
+    fn hint_virtual_selected_parent(self: Arc<Self>, hint: Hash) {
+        let mut batch = WriteBatch::default();
+        match self.reachability_service.hint_virtual_selected_parent(&mut batch, hint) {
+            Ok(reachability_write_guard) => {
+                self.db.write(batch).unwrap();
+                drop(reachability_write_guard);
+            }
+            Err(err) if err.is_key_not_found() => {}
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn get_pruning_point(self: Arc<Self>) -> Hash {
+        self.pruning_store.read().pruning_point().unwrap()
+    }
+
+    fn is_archival(self: Arc<Self>) -> bool {
+        self.is_archival
+    }
+
+    fn get_history_root(self: Arc<Self>) -> Hash {
+        if self.is_archival {
+            self.genesis_hash
+        } else {
+            self.get_pruning_point()
+        }
+    }
+
+    fn pin_blocks(self: Arc<Self>, hashes: &[Hash]) -> SyncManagerResult<()> {
+        self.as_ref().pin_blocks(hashes)
+    }
+
+    fn unpin_blocks(self: Arc<Self>, hashes: &[Hash]) {
+        self.pinned_blocks_store.write().unpin(hashes).unwrap();
+    }
+
+    fn get_pinned_blocks(self: Arc<Self>) -> Vec<Hash> {
+        self.pinned_blocks_store.read().get().unwrap().iter().copied().collect()
+    }
+
+    fn get_block_region(self: Arc<Self>, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<Vec<Hash>> {
+        self.as_ref().get_block_region(low, high, max_blocks)
+    }
+
+    fn create_headers_selected_chain_block_locator(
+        self: Arc<Self>,
+        low: Option<Hash>,
+        high: Option<Hash>,
+    ) -> SyncManagerResult<Vec<Hash>> {
+        let low = low.unwrap_or_else(|| self.clone().get_pruning_point());
+        let high = high.unwrap_or_else(|| self.clone().get_headers_selected_tip());
+        self.sync_manager.create_headers_selected_chain_block_locator(low, high)
+    }
+
+    fn get_hashes_between(self: Arc<Self>, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<(Vec<Hash>, Hash)> {
+        // The headers of the blocks merged below the retention root may have been garbage collected
+        self.check_retained(low)?;
+        self.sync_manager.get_hashes_between(low, high, max_blocks)
+    }
+
+    fn get_missing_block_body_hashes(self: Arc<Self>, high: Hash) -> SyncManagerResult<Vec<Hash>> {
+        let pruning_point = self.clone().get_pruning_point();
+        self.sync_manager.get_missing_block_body_hashes(pruning_point, high)
+    }
+
+    fn get_pruning_point_trusted_data(self: Arc<Self>) -> SyncManagerResult<PruningPointTrustedData> {
+        self.as_ref().get_pruning_point_trusted_data()
+    }
+
+    fn get_pruning_point_utxos(
+        self: Arc<Self>,
+        pruning_point: Hash,
+        from_outpoint: Option<TransactionOutpoint>,
+        chunk_size: usize,
+    ) -> SyncManagerResult<Vec<(TransactionOutpoint, UtxoEntry)>> {
+        let pruning_utxoset_read = self.pruning_utxoset_stores.read();
+        let position = pruning_utxoset_read.position.get().unwrap_option().unwrap_or(self.genesis_hash);
+        if position != pruning_point || self.pruning_store.read().pruning_point().unwrap() != pruning_point {
+            return Err(SyncManagerError::PruningPointUtxoSetUnavailable(pruning_point));
+        }
+        Ok(pruning_utxoset_read.utxo_set.get_chunk(from_outpoint, chunk_size).unwrap())
+    }
+
+    fn get_virtual_chain_from_block(
+        self: Arc<Self>,
+        start: Hash,
+        include_accepted_transaction_ids: bool,
+        max_added_blocks: usize,
+        max_accepted_transaction_ids: usize,
+    ) -> SyncManagerResult<VirtualChainBatch> {
+        if include_accepted_transaction_ids {
+            self.check_retained(start)?;
+        }
+        self.virtual_processor.virtual_chain_batch(
+            start,
+            include_accepted_transaction_ids,
+            max_added_blocks,
+            max_accepted_transaction_ids,
+        )
+    }
+
+    fn get_chain_blocks_with_acceptance(
+        self: Arc<Self>,
+        low: Hash,
+        high: Option<Hash>,
+        max_blocks: usize,
+    ) -> SyncManagerResult<ChainBlocksBatch> {
+        self.check_retained(low)?;
+        self.virtual_processor.chain_blocks_with_acceptance(low, high, max_blocks)
+    }
 }
 
+#[cfg(feature = "service")]
 impl Service for Consensus {
     fn ident(self: Arc<Consensus>) -> &'static str {
         "consensus"
@@ -6,19 +6,26 @@ use std::{
 };
 
 use consensus_core::{
-    api::ConsensusApi,
-    block::{Block, BlockTemplate, MutableBlock},
+    api::{session::SessionLock, ConsensusApi},
+    block::{Block, BlockDagData, BlockTemplate, MutableBlock, VirtualInfo},
     blockstatus::BlockStatus,
-    coinbase::MinerData,
-    errors::{block::RuleError, tx::TxResult},
+    chain::{ChainBlocksBatch, VirtualChainBatch},
+    coinbase::{CoinbasePreview, MinerData},
+    errors::{block::RuleError, coinbase::CoinbaseResult, sync::SyncManagerResult, tx::TxResult},
+    ghostdag::ExternalGhostdagData,
     header::Header,
     merkle::calc_hash_merkle_root,
+    revalidation::BlockRevalidationReport,
+    stats::{BlockDagInfo, ChainThroughputStats, DaaScoreDrift},
     subnets::SUBNETWORK_ID_COINBASE,
-    tx::{MutableTransaction, Transaction},
+    time::DurationMs,
+    trusted::{PruningPointTrustedData, TrustedBlock},
+    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
     BlockHashSet,
 };
 use futures_util::future::BoxFuture;
 use hashes::Hash;
+#[cfg(feature = "service")]
 use kaspa_core::{core::Core, service::Service};
 use parking_lot::RwLock;
 use std::future::Future;
@@ -34,11 +41,15 @@ use crate::{
         reachability::DbReachabilityStore,
         DB,
     },
-    params::Params,
+    notify::root::ConsensusNotificationRoot,
     pipeline::{body_processor::BlockBodyProcessor, ProcessingCounters},
-    processes::{past_median_time::PastMedianTimeManager, traversal_manager::DagTraversalManager},
+    processes::{
+        past_median_time::PastMedianTimeManager,
+        traversal_manager::{DagTraversalManager, WindowType},
+    },
     test_helpers::header_from_precomputed_hash,
 };
+use consensus_core::config::params::Params;
 
 use super::{Consensus, DbGhostdagManager};
 
@@ -65,14 +76,12 @@ impl TestConsensus {
             .consensus
             .pruning_manager
             .expected_header_pruning_point(ghostdag_data.to_compact(), self.consensus.pruning_store.read().get().unwrap());
-        let window = self.consensus.dag_traversal_manager.block_window(&ghostdag_data, self.params.difficulty_window_size);
-        let (daa_score, _) = self
-            .consensus
-            .difficulty_manager
-            .calc_daa_score_and_non_daa_mergeset_blocks(&mut window.iter().map(|item| item.0.hash), &ghostdag_data);
+        let window = self.consensus.dag_traversal_manager.window(&ghostdag_data, WindowType::DifficultyWindow);
+        let (daa_score, _) = self.consensus.difficulty_manager.calc_daa_score_and_non_daa_mergeset_blocks(&window, &ghostdag_data);
         header.bits = self.consensus.difficulty_manager.calculate_difficulty_bits(&window);
         header.daa_score = daa_score;
-        header.timestamp = self.consensus.past_median_time_manager.calc_past_median_time(&ghostdag_data).0 + 1;
+        header.timestamp =
+            self.consensus.past_median_time_manager.calc_past_median_time(&ghostdag_data).0 + DurationMs::from_millis(1);
         header.blue_score = ghostdag_data.blue_score;
         header.blue_work = ghostdag_data.blue_work;
 
@@ -110,6 +119,14 @@ impl TestConsensus {
         self.consensus.as_ref().validate_and_insert_block(block)
     }
 
+    pub fn validate_and_insert_blocks(&self, blocks: Vec<Block>) -> impl Future<Output = Vec<BlockProcessResult<BlockStatus>>> {
+        self.consensus.as_ref().validate_and_insert_blocks(blocks)
+    }
+
+    pub fn revalidate_block(&self, hash: Hash) -> Option<BlockRevalidationReport> {
+        self.consensus.as_ref().revalidate_block(hash)
+    }
+
     pub fn init(&self) -> Vec<JoinHandle<()>> {
         self.consensus.init()
     }
@@ -118,7 +135,11 @@ impl TestConsensus {
         self.consensus.shutdown(wait_handles)
     }
 
-    pub fn dag_traversal_manager(&self) -> &DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore> {
+    pub fn consensus(&self) -> &Arc<Consensus> {
+        &self.consensus
+    }
+
+    pub fn dag_traversal_manager(&self) -> &DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore, DbHeadersStore> {
         &self.consensus.dag_traversal_manager
     }
 
@@ -134,10 +155,22 @@ impl TestConsensus {
         self.consensus.headers_store.clone()
     }
 
+    pub fn get_block(&self, hash: Hash) -> Option<Block> {
+        self.consensus.get_block(hash)
+    }
+
+    pub fn pruning_point_future_blocks(&self) -> Vec<Hash> {
+        self.consensus.pruning_point_future_blocks()
+    }
+
     pub fn processing_counters(&self) -> &Arc<ProcessingCounters> {
         &self.consensus.counters
     }
 
+    pub fn notification_root(&self) -> &Arc<ConsensusNotificationRoot> {
+        self.consensus.notification_root()
+    }
+
     pub fn block_body_processor(&self) -> &Arc<BlockBodyProcessor> {
         &self.consensus.body_processor
     }
@@ -172,8 +205,40 @@ impl ConsensusApi for TestConsensus {
         self.consensus.clone().validate_and_insert_block(block, update_virtual)
     }
 
-    fn validate_mempool_transaction_and_populate(self: Arc<Self>, transaction: &mut MutableTransaction) -> TxResult<()> {
-        self.consensus.clone().validate_mempool_transaction_and_populate(transaction)
+    fn validate_and_insert_blocks(
+        self: Arc<Self>,
+        blocks: Vec<Block>,
+        update_virtual: bool,
+    ) -> BoxFuture<'static, Vec<BlockProcessResult<BlockStatus>>> {
+        self.consensus.clone().validate_and_insert_blocks(blocks, update_virtual)
+    }
+
+    fn begin_trusted_sync(self: Arc<Self>) -> BlockProcessResult<()> {
+        self.consensus.clone().begin_trusted_sync()
+    }
+
+    fn end_trusted_sync(self: Arc<Self>) {
+        self.consensus.clone().end_trusted_sync()
+    }
+
+    fn validate_and_insert_trusted_block(self: Arc<Self>, block: TrustedBlock) -> BlockProcessResult<BlockStatus> {
+        self.consensus.clone().validate_and_insert_trusted_block(block)
+    }
+
+    fn clear_imported_pruning_point_utxos(self: Arc<Self>) -> BlockProcessResult<()> {
+        self.consensus.clone().clear_imported_pruning_point_utxos()
+    }
+
+    fn append_imported_pruning_point_utxos(self: Arc<Self>, utxos: &[(TransactionOutpoint, UtxoEntry)]) -> BlockProcessResult<()> {
+        self.consensus.clone().append_imported_pruning_point_utxos(utxos)
+    }
+
+    fn import_pruning_point_utxo_set(self: Arc<Self>, past_pruning_points: Vec<Hash>) -> BlockProcessResult<()> {
+        self.consensus.clone().import_pruning_point_utxo_set(past_pruning_points)
+    }
+
+    fn validate_mempool_transaction(self: Arc<Self>, transaction: &mut MutableTransaction) -> TxResult<()> {
+        self.consensus.clone().validate_mempool_transaction(transaction)
     }
 
     fn calculate_transaction_mass(self: Arc<Self>, transaction: &Transaction) -> u64 {
@@ -183,8 +248,178 @@ impl ConsensusApi for TestConsensus {
     fn get_virtual_daa_score(self: Arc<Self>) -> u64 {
         self.consensus.clone().get_virtual_daa_score()
     }
+
+    fn get_virtual_parents(self: Arc<Self>) -> Vec<Hash> {
+        self.consensus.clone().get_virtual_parents()
+    }
+
+    fn get_virtual_info(self: Arc<Self>) -> VirtualInfo {
+        self.consensus.clone().get_virtual_info()
+    }
+
+    fn session_lock(self: Arc<Self>) -> SessionLock {
+        self.consensus.clone().session_lock()
+    }
+
+    fn get_virtual_utxos(
+        self: Arc<Self>,
+        from_outpoint: Option<TransactionOutpoint>,
+        chunk_size: usize,
+    ) -> Vec<(TransactionOutpoint, UtxoEntry)> {
+        self.consensus.clone().get_virtual_utxos(from_outpoint, chunk_size)
+    }
+
+    fn revalidate_block(self: Arc<Self>, hash: Hash) -> Option<BlockRevalidationReport> {
+        self.consensus.clone().revalidate_block(hash)
+    }
+
+    fn get_coinbase_preview(self: Arc<Self>, miner_data: MinerData) -> CoinbaseResult<CoinbasePreview> {
+        self.consensus.clone().get_coinbase_preview(miner_data)
+    }
+
+    fn get_chain_throughput_stats(self: Arc<Self>, window_size: u64) -> ChainThroughputStats {
+        self.consensus.clone().get_chain_throughput_stats(window_size)
+    }
+
+    fn get_block_dag_info(self: Arc<Self>) -> BlockDagInfo {
+        self.consensus.clone().get_block_dag_info()
+    }
+
+    fn get_daa_score_drift(self: Arc<Self>) -> DaaScoreDrift {
+        self.consensus.clone().get_daa_score_drift()
+    }
+
+    fn max_block_mass(self: Arc<Self>) -> u64 {
+        self.consensus.clone().max_block_mass()
+    }
+
+    fn get_block_status(self: Arc<Self>, hash: Hash) -> Option<BlockStatus> {
+        self.consensus.clone().get_block_status(hash)
+    }
+
+    fn get_header(self: Arc<Self>, hash: Hash) -> Option<Arc<Header>> {
+        self.consensus.clone().get_header(hash)
+    }
+
+    fn get_block(self: Arc<Self>, hash: Hash) -> Option<Block> {
+        self.consensus.as_ref().get_block(hash)
+    }
+
+    fn get_payload(self: Arc<Self>, payload_hash: Hash) -> Option<Arc<Vec<u8>>> {
+        self.consensus.clone().get_payload(payload_hash)
+    }
+
+    fn get_block_dag_data(self: Arc<Self>, hash: Hash) -> Option<BlockDagData> {
+        self.consensus.clone().get_block_dag_data(hash)
+    }
+
+    fn get_ghostdag_data(self: Arc<Self>, hash: Hash) -> Option<ExternalGhostdagData> {
+        self.consensus.clone().get_ghostdag_data(hash)
+    }
+
+    fn is_chain_ancestor_of(self: Arc<Self>, low: Hash, high: Hash) -> SyncManagerResult<bool> {
+        self.consensus.clone().is_chain_ancestor_of(low, high)
+    }
+
+    fn get_confirmations(self: Arc<Self>, hash: Hash) -> Option<u64> {
+        self.consensus.clone().get_confirmations(hash)
+    }
+
+    fn get_headers_selected_tip(self: Arc<Self>) -> Hash {
+        self.consensus.clone().get_headers_selected_tip()
+    }
+
+    fn get_sink(self: Arc<Self>) -> Hash {
+        self.consensus.clone().get_sink()
+    }
+
+    fn hint_virtual_selected_parent(self: Arc<Self>, hint: Hash) {
+        self.consensus.clone().hint_virtual_selected_parent(hint)
+    }
+
+    fn get_pruning_point(self: Arc<Self>) -> Hash {
+        self.consensus.clone().get_pruning_point()
+    }
+
+    fn pin_blocks(self: Arc<Self>, hashes: &[Hash]) -> SyncManagerResult<()> {
+        self.consensus.clone().pin_blocks(hashes)
+    }
+
+    fn unpin_blocks(self: Arc<Self>, hashes: &[Hash]) {
+        self.consensus.clone().unpin_blocks(hashes)
+    }
+
+    fn is_archival(self: Arc<Self>) -> bool {
+        self.consensus.clone().is_archival()
+    }
+
+    fn get_history_root(self: Arc<Self>) -> Hash {
+        self.consensus.clone().get_history_root()
+    }
+
+    fn get_pinned_blocks(self: Arc<Self>) -> Vec<Hash> {
+        self.consensus.clone().get_pinned_blocks()
+    }
+
+    fn get_block_region(self: Arc<Self>, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<Vec<Hash>> {
+        self.consensus.clone().get_block_region(low, high, max_blocks)
+    }
+
+    fn create_headers_selected_chain_block_locator(
+        self: Arc<Self>,
+        low: Option<Hash>,
+        high: Option<Hash>,
+    ) -> SyncManagerResult<Vec<Hash>> {
+        self.consensus.clone().create_headers_selected_chain_block_locator(low, high)
+    }
+
+    fn get_hashes_between(self: Arc<Self>, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<(Vec<Hash>, Hash)> {
+        self.consensus.clone().get_hashes_between(low, high, max_blocks)
+    }
+
+    fn get_missing_block_body_hashes(self: Arc<Self>, high: Hash) -> SyncManagerResult<Vec<Hash>> {
+        self.consensus.clone().get_missing_block_body_hashes(high)
+    }
+
+    fn get_pruning_point_trusted_data(self: Arc<Self>) -> SyncManagerResult<PruningPointTrustedData> {
+        self.consensus.clone().get_pruning_point_trusted_data()
+    }
+
+    fn get_pruning_point_utxos(
+        self: Arc<Self>,
+        pruning_point: Hash,
+        from_outpoint: Option<TransactionOutpoint>,
+        chunk_size: usize,
+    ) -> SyncManagerResult<Vec<(TransactionOutpoint, UtxoEntry)>> {
+        self.consensus.clone().get_pruning_point_utxos(pruning_point, from_outpoint, chunk_size)
+    }
+
+    fn get_virtual_chain_from_block(
+        self: Arc<Self>,
+        start: Hash,
+        include_accepted_transaction_ids: bool,
+        max_added_blocks: usize,
+        max_accepted_transaction_ids: usize,
+    ) -> SyncManagerResult<VirtualChainBatch> {
+        self.consensus.clone().get_virtual_chain_from_block(
+            start,
+            include_accepted_transaction_ids,
+            max_added_blocks,
+            max_accepted_transaction_ids,
+        )
+    }
+
+    fn get_chain_blocks_with_acceptance(
+        self: Arc<Self>,
+        low: Hash,
+        high: Option<Hash>,
+        max_blocks: usize,
+    ) -> SyncManagerResult<ChainBlocksBatch> {
+        self.consensus.clone().get_chain_blocks_with_acceptance(low, high, max_blocks)
+    }
 }
 
+#[cfg(feature = "service")]
 impl Service for TestConsensus {
     fn ident(self: Arc<TestConsensus>) -> &'static str {
         "test-consensus"
@@ -249,6 +484,15 @@ pub fn create_temp_db() -> (TempDbLifetime, Arc<DB>) {
     (TempDbLifetime::new(db_tempdir, Arc::downgrade(&db)), db)
 }
 
+/// Creates a DB whose files are held in memory rather than on disk. The DB content is gone once its last
+/// reference is dropped, so no lifetime guard is needed.
+pub fn create_memory_db() -> Arc<DB> {
+    let mut options = rocksdb::Options::default();
+    options.create_if_missing(true);
+    options.set_env(&rocksdb::Env::mem_env().unwrap());
+    Arc::new(DB::open(&options, "kaspa-memory-db").unwrap())
+}
+
 /// Creates a DB within the provided directory path.
 /// Callers must keep the `TempDbLifetime` guard for as long as they wish the DB instance to exist.
 pub fn create_permanent_db(db_path: String) -> (TempDbLifetime, Arc<DB>) {
@@ -0,0 +1,119 @@
+//! Transfer of blocks between two local consensus instances.
+//!
+//! Used for rebuilding a consensus into a fresh DB, e.g., after a DB schema reset, without downloading the DAG
+//! from the network again. Blocks are fully validated by the target consensus.
+
+use super::Consensus;
+use crate::model::stores::{errors::StoreResultExtensions, pruning::PruningStoreReader, statuses::StatusesStoreReader};
+use consensus_core::errors::block::RuleError;
+use futures_util::future::join_all;
+use hashes::Hash;
+use thiserror::Error;
+
+/// The number of blocks submitted concurrently to the target consensus
+const TRANSFER_BATCH_SIZE: usize = 256;
+
+#[derive(Error, Debug)]
+pub enum TransferError {
+    #[error("the source pruning point {0} differs from the target pruning point {1}")]
+    PruningPointMismatch(Hash, Hash),
+
+    #[error("block {0} was rejected by the target consensus: {1}")]
+    RejectedBlock(Hash, RuleError),
+}
+
+pub type TransferResult<T> = Result<T, TransferError>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// The number of source blocks handled so far, including skipped ones
+    pub processed: u64,
+    /// The number of source blocks the target already held, e.g., from an interrupted transfer
+    pub skipped: u64,
+    /// The number of source blocks to handle
+    pub total: u64,
+}
+
+/// Transfers the blocks of `source` in the future of its pruning point into `target`, which must share the
+/// same pruning point, e.g., a fresh consensus when the source did not prune yet.
+///
+/// Blocks are read in topological order and submitted in concurrent batches, after each of which `progress`
+/// is reported. Blocks the target already holds with a body are skipped, hence an interrupted transfer
+/// resumes when called again.
+pub async fn transfer_blocks(
+    source: &Consensus,
+    target: &Consensus,
+    mut progress: impl FnMut(TransferProgress),
+) -> TransferResult<TransferProgress> {
+    let source_pruning_point = source.pruning_store.read().pruning_point().unwrap();
+    let target_pruning_point = target.pruning_store.read().pruning_point().unwrap();
+    if source_pruning_point != target_pruning_point {
+        return Err(TransferError::PruningPointMismatch(source_pruning_point, target_pruning_point));
+    }
+
+    let hashes = source.pruning_point_future_blocks();
+    let mut state = TransferProgress { total: hashes.len() as u64, ..Default::default() };
+    for chunk in hashes.chunks(TRANSFER_BATCH_SIZE) {
+        let mut batch = Vec::with_capacity(chunk.len());
+        for &hash in chunk {
+            if matches!(target.statuses_store.get(hash).unwrap_option(), Some(status) if status.has_block_body()) {
+                state.skipped += 1;
+            } else {
+                // Blocks returned by the traversal are guaranteed to have a body
+                batch.push(source.get_block(hash).unwrap());
+            }
+        }
+        let batch_hashes: Vec<Hash> = batch.iter().map(|block| block.hash()).collect();
+        let results = join_all(batch.into_iter().map(|block| target.validate_and_insert_block(block))).await;
+        for (hash, result) in batch_hashes.into_iter().zip(results) {
+            result.map_err(|err| TransferError::RejectedBlock(hash, err))?;
+        }
+        state.processed += chunk.len() as u64;
+        progress(state);
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::TestConsensus;
+    use consensus_core::config::params::MAINNET_PARAMS;
+
+    async fn add_chain(consensus: &TestConsensus, first: u64, count: u64, mut parents: Vec<Hash>) -> Hash {
+        let mut tip = Hash::default();
+        for i in first..first + count {
+            tip = i.into();
+            let block = consensus.build_block_with_parents_and_transactions(tip, parents, vec![]);
+            consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            parents = vec![tip];
+        }
+        tip
+    }
+
+    #[tokio::test]
+    async fn test_transfer_blocks() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let source = TestConsensus::create_from_temp_db(&params);
+        let source_wait_handles = source.init();
+        let target = TestConsensus::create_from_temp_db(&params);
+        let target_wait_handles = target.init();
+
+        let tip = add_chain(&source, 1, 300, vec![params.genesis.hash]).await;
+        let mut reports = Vec::new();
+        let state = transfer_blocks(source.consensus(), target.consensus(), |progress| reports.push(progress)).await.unwrap();
+        assert_eq!(state, TransferProgress { processed: 300, skipped: 0, total: 300 });
+        assert_eq!(reports.len(), 2);
+        assert!(target.consensus().block_status(tip).has_block_body());
+
+        // A repeated transfer only submits the blocks missing from the target
+        let tip = add_chain(&source, 1000, 10, vec![tip]).await;
+        let state = transfer_blocks(source.consensus(), target.consensus(), |_| {}).await.unwrap();
+        assert_eq!(state, TransferProgress { processed: 310, skipped: 300, total: 310 });
+        assert!(target.consensus().block_status(tip).has_block_body());
+        assert_eq!(target.consensus().body_tips().iter().copied().collect::<Vec<_>>(), vec![tip]);
+
+        source.shutdown(source_wait_handles);
+        target.shutdown(target_wait_handles);
+    }
+}
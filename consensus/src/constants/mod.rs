@@ -16,16 +16,9 @@ pub mod perf {
         //
         // Cache sizes
         //
-        /// Preferred cache size for header-related data
-        pub header_data_cache_size: u64,
-
-        /// Preferred cache size for block-body-related data which
-        /// is typically orders-of magnitude larger than header data
-        /// (Note this cannot be set to high due to severe memory consumption)
-        pub block_data_cache_size: u64,
-
-        /// Preferred cache size for UTXO-related data
-        pub utxo_set_cache_size: u64,
+        /// The estimated memory in bytes shared by the caches of the DB stores,
+        /// each store being given a fixed share of it
+        pub cache_memory_budget: usize,
 
         /// Preferred cache size for block-window-related data
         pub block_window_cache_size: u64,
@@ -40,15 +33,31 @@ pub mod perf {
         /// Defaults to 0 which indicates using system default
         /// which is typically the number of logical CPU cores
         pub virtual_processor_num_threads: usize,
+
+        //
+        // Pipeline queues
+        //
+        /// The number of submitted blocks which may wait for header processing, beyond which
+        /// submitting a block blocks until the pipeline catches up
+        pub header_queue_capacity: usize,
+
+        /// The number of blocks which may wait for body processing, beyond which header
+        /// processing blocks until body processing catches up
+        pub body_queue_capacity: usize,
+
+        /// The number of blocks which may wait for virtual processing, beyond which body
+        /// processing blocks until virtual processing catches up
+        pub virtual_queue_capacity: usize,
     }
 
     pub const PERF_PARAMS: PerfParams = PerfParams {
-        header_data_cache_size: 10_000,
-        block_data_cache_size: 200,
-        utxo_set_cache_size: 10_000,
+        cache_memory_budget: 1 << 30,
         block_window_cache_size: 2000,
         block_processors_num_threads: 0,
         virtual_processor_num_threads: 0,
+        header_queue_capacity: 4096,
+        body_queue_capacity: 1024,
+        virtual_queue_capacity: 1024,
     };
 }
 
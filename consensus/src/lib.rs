@@ -5,8 +5,9 @@
 pub mod consensus;
 pub mod constants;
 pub mod errors;
+pub mod migrations;
 pub mod model;
-pub mod params;
+pub mod notify;
 pub mod pipeline;
 pub mod processes;
 pub mod test_helpers;
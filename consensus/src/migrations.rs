@@ -0,0 +1,169 @@
+//! Upgrades of the persisted data to the format of the current node version.
+//!
+//! The DB records the version of its schema in the metadata store. Each change to the format of persisted data,
+//! e.g., to the serialization of `GhostdagData`, bumps [`SCHEMA_VERSION`] and registers a [`Migration`] in
+//! [`MIGRATIONS`] converting the data of the previous version, so that existing nodes upgrade on startup
+//! rather than resync.
+
+use crate::model::stores::{
+    errors::{StoreError, StoreResult, StoreResultExtensions},
+    metadata::{DbMetadataStore, MetadataStore, MetadataStoreReader, SchemaVersion},
+    DB,
+};
+use consensus_core::time::TimestampMs;
+use kaspa_core::info;
+use rocksdb::{checkpoint::Checkpoint, IteratorMode};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// The schema version of DBs created before schema versions were recorded
+pub const BASE_SCHEMA_VERSION: SchemaVersion = 1;
+
+/// The schema version of the data written by this node version
+pub const SCHEMA_VERSION: SchemaVersion = BASE_SCHEMA_VERSION;
+
+/// The migrations upgrading the DB from [`BASE_SCHEMA_VERSION`] to [`SCHEMA_VERSION`], in version order
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// A conversion of the DB from the previous schema version to `version`
+pub struct Migration {
+    pub version: SchemaVersion,
+    pub description: &'static str,
+    /// Converts the data. The new version is only recorded once the migration returns, so an interrupted
+    /// migration is run again on the next startup and must thus be idempotent
+    pub migrate: fn(&Arc<DB>) -> StoreResult<()>,
+}
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("the database schema version {0} is newer than the version {1} supported by this node")]
+    UnsupportedVersion(SchemaVersion, SchemaVersion),
+
+    #[error("no migration upgrades the database schema from version {0}")]
+    MissingMigration(SchemaVersion),
+
+    #[error("cannot back up the database to {0}: {1}")]
+    BackupFailed(String, rocksdb::Error),
+
+    #[error("migration to schema version {0} ({1}) failed: {2}")]
+    MigrationFailed(SchemaVersion, &'static str, StoreError),
+
+    #[error("store error: {0}")]
+    Store(#[from] StoreError),
+}
+
+pub type MigrationResult<T> = Result<T, MigrationError>;
+
+/// Brings the DB schema up to date by running the pending migrations in order, each recording its version once done
+pub struct MigrationRunner<'a> {
+    db: Arc<DB>,
+    migrations: &'a [Migration],
+    latest_version: SchemaVersion,
+    backup_dir: Option<PathBuf>,
+}
+
+impl MigrationRunner<'static> {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self::with_migrations(db, MIGRATIONS, SCHEMA_VERSION)
+    }
+}
+
+impl<'a> MigrationRunner<'a> {
+    pub fn with_migrations(db: Arc<DB>, migrations: &'a [Migration], latest_version: SchemaVersion) -> Self {
+        Self { db, migrations, latest_version, backup_dir: None }
+    }
+
+    /// Checkpoints the DB into a new directory within `backup_dir` before migrating it. The checkpoint hard links
+    /// the DB files where possible, so `backup_dir` is best kept on the same file system as the DB.
+    pub fn with_backup(self, backup_dir: PathBuf) -> Self {
+        Self { backup_dir: Some(backup_dir), ..self }
+    }
+
+    /// Runs the pending migrations and returns the resulting schema version. A DB holding no data yet is
+    /// stamped with the latest version at once.
+    pub fn run(&self) -> MigrationResult<SchemaVersion> {
+        let mut store = DbMetadataStore::new(self.db.clone());
+        let mut version = match store.get_schema_version().unwrap_option() {
+            Some(version) => version,
+            None if self.db.iterator(IteratorMode::Start).next().is_none() => {
+                store.set_schema_version(self.latest_version)?;
+                return Ok(self.latest_version);
+            }
+            None => BASE_SCHEMA_VERSION,
+        };
+        if version > self.latest_version {
+            return Err(MigrationError::UnsupportedVersion(version, self.latest_version));
+        }
+        if version < self.latest_version {
+            info!("Database schema version {} is outdated, migrating to version {}", version, self.latest_version);
+            if let Some(backup_dir) = self.backup_dir.as_ref() {
+                self.backup(version, backup_dir)?;
+            }
+        }
+        while version < self.latest_version {
+            let migration = match self.migrations.iter().find(|migration| migration.version == version + 1) {
+                Some(migration) => migration,
+                None => return Err(MigrationError::MissingMigration(version)),
+            };
+            info!("Migrating the database to schema version {}: {}", migration.version, migration.description);
+            (migration.migrate)(&self.db)
+                .map_err(|err| MigrationError::MigrationFailed(migration.version, migration.description, err))?;
+            store.set_schema_version(migration.version)?;
+            version = migration.version;
+        }
+        Ok(version)
+    }
+
+    fn backup(&self, version: SchemaVersion, backup_dir: &Path) -> MigrationResult<()> {
+        let path = backup_dir.join(format!("pre-migration-v{}-{}", version, TimestampMs::now()));
+        let path_str = path.to_string_lossy().to_string();
+        info!("Backing up the database to {}", path_str);
+        Checkpoint::new(&self.db)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(&path))
+            .map_err(|err| MigrationError::BackupFailed(path_str, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::create_temp_db;
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration { version: 2, description: "write v2", migrate: |db| Ok(db.put(b"v2", b"")?) },
+        Migration { version: 3, description: "write v3", migrate: |db| Ok(db.put(b"v3", b"")?) },
+    ];
+
+    #[test]
+    fn test_migration_runner() {
+        // A fresh DB is stamped with the latest version without migrating
+        let (_lifetime, db) = create_temp_db();
+        assert_eq!(MigrationRunner::with_migrations(db.clone(), TEST_MIGRATIONS, 3).run().unwrap(), 3);
+        assert!(db.get(b"v2").unwrap().is_none());
+
+        // A DB predating schema versions is migrated from the base version, after being backed up
+        let (_lifetime, db) = create_temp_db();
+        db.put(b"existing-data", b"").unwrap();
+        let backup_dir = tempfile::tempdir().unwrap();
+        let runner = MigrationRunner::with_migrations(db.clone(), &TEST_MIGRATIONS[..1], 2).with_backup(backup_dir.path().to_owned());
+        assert_eq!(runner.run().unwrap(), 2);
+        assert!(db.get(b"v2").unwrap().is_some());
+        assert_eq!(std::fs::read_dir(backup_dir.path()).unwrap().count(), 1);
+
+        // Only the pending migrations are run
+        db.delete(b"v2").unwrap();
+        assert_eq!(MigrationRunner::with_migrations(db.clone(), TEST_MIGRATIONS, 3).run().unwrap(), 3);
+        assert!(db.get(b"v2").unwrap().is_none() && db.get(b"v3").unwrap().is_some());
+        assert_eq!(DbMetadataStore::new(db.clone()).get_schema_version().unwrap(), 3);
+
+        // A DB written by a newer node version is rejected, as is a DB no migration upgrades
+        assert!(matches!(
+            MigrationRunner::with_migrations(db.clone(), TEST_MIGRATIONS, 2).run(),
+            Err(MigrationError::UnsupportedVersion(3, 2))
+        ));
+        assert!(matches!(MigrationRunner::with_migrations(db, &[], 4).run(), Err(MigrationError::MissingMigration(3))));
+    }
+}
@@ -2,9 +2,10 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use consensus_core::blockhash;
-use parking_lot::RwLock;
+use parking_lot::{RwLock, RwLockWriteGuard};
+use rocksdb::WriteBatch;
 
-use crate::model::stores::reachability::ReachabilityStoreReader;
+use crate::model::stores::reachability::{DbReachabilityStore, ReachabilityStore, ReachabilityStoreReader, StagingReachabilityStore};
 use crate::processes::reachability::{inquirer, Result};
 use hashes::Hash;
 
@@ -72,6 +73,25 @@ impl<T: ReachabilityStoreReader + ?Sized> ReachabilityService for MTReachability
     }
 }
 
+impl MTReachabilityService<DbReachabilityStore> {
+    /// Returns the current reindex root, the chain block whose subtree absorbs the reindexing of intervals
+    pub fn get_reindex_root(&self) -> Hash {
+        self.store.read().get_reindex_root().unwrap()
+    }
+
+    /// Hints that `hint` is a candidate to become the virtual selected parent, moving the reindex root towards it
+    /// once it is deep enough below `hint`. Keeping the root close to the tips of the chain being extended keeps
+    /// reindexing of new blocks amortized O(1).
+    ///
+    /// The changes are staged into `batch`, and the returned write guard must be held until the batch is written.
+    /// Fails if `hint` was not added to reachability.
+    pub fn hint_virtual_selected_parent(&self, batch: &mut WriteBatch, hint: Hash) -> Result<RwLockWriteGuard<DbReachabilityStore>> {
+        let mut staging = StagingReachabilityStore::new(self.store.upgradable_read());
+        inquirer::hint_virtual_selected_parent(&mut staging, hint)?;
+        Ok(staging.commit(batch)?)
+    }
+}
+
 impl<T: ReachabilityStoreReader + ?Sized> MTReachabilityService<T> {
     /// Returns a forward iterator walking up the chain-selection tree from `from_ancestor`
     /// to `to_descendant`, where `to_descendant` is included if `inclusive` is set to true.
@@ -1,27 +1,26 @@
 use crate::model::stores::statuses::StatusesStoreReader;
 use consensus_core::blockstatus::BlockStatus;
 use hashes::Hash;
-use parking_lot::RwLock;
 use std::sync::Arc;
 
 /// Multi-threaded block-statuses service imp
 #[derive(Clone)]
 pub struct MTStatusesService<T: StatusesStoreReader> {
-    store: Arc<RwLock<T>>,
+    store: Arc<T>,
 }
 
 impl<T: StatusesStoreReader> MTStatusesService<T> {
-    pub fn new(store: Arc<RwLock<T>>) -> Self {
+    pub fn new(store: Arc<T>) -> Self {
         Self { store }
     }
 }
 
 impl<T: StatusesStoreReader> StatusesStoreReader for MTStatusesService<T> {
     fn get(&self, hash: Hash) -> Result<BlockStatus, crate::model::stores::errors::StoreError> {
-        self.store.read().get(hash)
+        self.store.get(hash)
     }
 
     fn has(&self, hash: Hash) -> Result<bool, crate::model::stores::errors::StoreError> {
-        self.store.read().has(hash)
+        self.store.has(hash)
     }
 }
@@ -1,17 +1,38 @@
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
     errors::StoreError,
     DB,
 };
-use consensus_core::BlockHasher;
+use consensus_core::{chain::MergesetBlockAcceptanceData, tx::TransactionId, BlockHasher};
 use hashes::Hash;
 use rocksdb::WriteBatch;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Aggregate statistics of the transactions accepted by a chain block, i.e., by the mergeset of the block
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcceptanceStats {
+    /// The number of blocks in the mergeset (including the selected parent)
+    pub mergeset_size: u64,
+    /// The number of accepted non-coinbase transactions
+    pub accepted_tx_count: u64,
+    /// The total (combined) mass of accepted transactions
+    pub accepted_mass: u64,
+    /// The total fees paid by accepted transactions
+    pub total_fees: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AcceptanceData {
-    // TODO
+    pub stats: AcceptanceStats,
+    /// The accepted transactions of each mergeset block, in consensus order starting with the selected parent
+    pub mergeset: Vec<MergesetBlockAcceptanceData>,
+}
+
+impl AcceptanceData {
+    pub fn new(stats: AcceptanceStats, mergeset: Vec<MergesetBlockAcceptanceData>) -> Self {
+        Self { stats, mergeset }
+    }
 }
 
 pub trait AcceptanceDataStoreReader {
@@ -31,13 +52,27 @@ pub struct DbAcceptanceDataStore {
     access: CachedDbAccess<Hash, Arc<AcceptanceData>, BlockHasher>,
 }
 
+/// Estimates the memory owned by cached acceptance data, see [`CachePolicy::Bytes`]
+fn acceptance_data_heap_size(data: &Arc<AcceptanceData>) -> usize {
+    size_of::<AcceptanceData>()
+        + data
+            .mergeset
+            .iter()
+            .map(|block| size_of::<MergesetBlockAcceptanceData>() + block.accepted_transaction_ids.len() * size_of::<TransactionId>())
+            .sum::<usize>()
+}
+
 impl DbAcceptanceDataStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(Arc::clone(&db), cache_size, STORE_PREFIX) }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(Arc::clone(&db), cache_policy, STORE_PREFIX)
+                .with_heap_size_estimator(acceptance_data_heap_size),
+        }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     pub fn insert_batch(&self, batch: &mut WriteBatch, hash: Hash, acceptance_data: Arc<AcceptanceData>) -> Result<(), StoreError> {
@@ -47,6 +82,10 @@ impl DbAcceptanceDataStore {
         self.access.write(BatchDbWriter::new(batch), hash, acceptance_data)?;
         Ok(())
     }
+
+    pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
+        self.access.delete(BatchDbWriter::new(batch), hash)
+    }
 }
 
 impl AcceptanceDataStoreReader for DbAcceptanceDataStore {
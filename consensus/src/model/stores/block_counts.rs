@@ -0,0 +1,63 @@
+use super::{
+    database::prelude::{BatchDbWriter, CachedDbItem},
+    errors::{StoreResult, StoreResultExtensions},
+    DB,
+};
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The number of blocks processed by consensus
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockCounts {
+    /// The number of processed headers, including the ones of blocks with a body
+    pub header_count: u64,
+    /// The number of processed blocks with a body
+    pub block_count: u64,
+}
+
+/// Reader API for `BlockCountsStore`.
+pub trait BlockCountsStoreReader {
+    fn get(&self) -> StoreResult<BlockCounts>;
+}
+
+pub const STORE_NAME: &[u8] = b"block-counts";
+
+/// A DB + cache implementation of the block counts store. Counts are only updated within the batches of the
+/// processors, which must hold a write lock over the store until the batch is written
+#[derive(Clone)]
+pub struct DbBlockCountsStore {
+    db: Arc<DB>,
+    access: CachedDbItem<BlockCounts>,
+}
+
+impl DbBlockCountsStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db.clone(), STORE_NAME) }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+
+    pub fn increment_headers_batch(&mut self, batch: &mut WriteBatch) -> StoreResult<BlockCounts> {
+        self.update_batch(batch, |counts| BlockCounts { header_count: counts.header_count + 1, ..counts })
+    }
+
+    pub fn increment_blocks_batch(&mut self, batch: &mut WriteBatch) -> StoreResult<BlockCounts> {
+        self.update_batch(batch, |counts| BlockCounts { block_count: counts.block_count + 1, ..counts })
+    }
+
+    fn update_batch(&mut self, batch: &mut WriteBatch, op: impl Fn(BlockCounts) -> BlockCounts) -> StoreResult<BlockCounts> {
+        // Counts start from zero, also for DBs created before the store was introduced
+        let counts = op(self.get()?);
+        self.access.write(BatchDbWriter::new(batch), &counts)?;
+        Ok(counts)
+    }
+}
+
+impl BlockCountsStoreReader for DbBlockCountsStore {
+    fn get(&self) -> StoreResult<BlockCounts> {
+        Ok(self.access.read().unwrap_option().unwrap_or_default())
+    }
+}
@@ -1,13 +1,23 @@
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
 
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess},
     errors::StoreError,
+    payloads::{DbPayloadsStore, PayloadsStoreReader},
     DB,
 };
-use consensus_core::{tx::Transaction, BlockHasher};
+use consensus_core::{
+    tx::{Transaction, TransactionInput, TransactionOutput},
+    BlockHasher,
+};
 use hashes::Hash;
+use parking_lot::{RwLock, RwLockWriteGuard};
 use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+
+/// Payloads of at least this size are moved to the payloads store, so that a payload repeated across
+/// blocks is stored once
+pub const PAYLOAD_DEDUP_THRESHOLD: usize = 1024;
 
 pub trait BlockTransactionsStoreReader {
     fn get(&self, hash: Hash) -> Result<Arc<Vec<Transaction>>, StoreError>;
@@ -20,43 +30,208 @@ pub trait BlockTransactionsStore: BlockTransactionsStoreReader {
 
 const STORE_PREFIX: &[u8] = b"block-transactions";
 
-/// A DB + cache implementation of `BlockTransactionsStore` trait, with concurrency support.
+/// The stored transactions of a block, where the payloads moved to the payloads store are left empty
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredTransactions {
+    transactions: Arc<Vec<Transaction>>,
+    /// The indexes of the transactions whose payload was moved, along with the payload hash
+    payload_refs: Vec<(u32, Hash)>,
+}
+
+/// A DB + cache implementation of `BlockTransactionsStore` trait, with concurrency support. Large payloads are
+/// deduplicated through a shared [`DbPayloadsStore`].
 #[derive(Clone)]
 pub struct DbBlockTransactionsStore {
     db: Arc<DB>,
-    access: CachedDbAccess<Hash, Arc<Vec<Transaction>>, BlockHasher>,
+    access: CachedDbAccess<Hash, StoredTransactions, BlockHasher>,
+    payloads_store: Arc<RwLock<DbPayloadsStore>>,
+}
+
+/// Estimates the memory owned by cached block transactions, see [`CachePolicy::Bytes`]
+fn transactions_heap_size(data: &StoredTransactions) -> usize {
+    let transactions_size = data
+        .transactions
+        .iter()
+        .map(|tx| {
+            size_of::<Transaction>()
+                + tx.payload.len()
+                + tx.inputs.iter().map(|input| size_of::<TransactionInput>() + input.signature_script.len()).sum::<usize>()
+                + tx.outputs
+                    .iter()
+                    .map(|output| size_of::<TransactionOutput>() + output.script_public_key.script().len())
+                    .sum::<usize>()
+        })
+        .sum::<usize>();
+    size_of::<Vec<Transaction>>() + transactions_size + data.payload_refs.len() * size_of::<(u32, Hash)>()
 }
 
 impl DbBlockTransactionsStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(Arc::clone(&db), cache_size, STORE_PREFIX) }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy, payloads_store: Arc<RwLock<DbPayloadsStore>>) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(Arc::clone(&db), cache_policy, STORE_PREFIX).with_heap_size_estimator(transactions_heap_size),
+            payloads_store,
+        }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy, self.payloads_store.clone())
     }
 
-    pub fn insert_batch(&self, batch: &mut WriteBatch, hash: Hash, transactions: Arc<Vec<Transaction>>) -> Result<(), StoreError> {
+    /// Writes the transactions to the batch, adding a payloads store reference for each large payload, and returns
+    /// the write guard of the payloads store. The guard is expected to be held until the batch is committed
+    pub fn insert_batch(
+        &self,
+        batch: &mut WriteBatch,
+        hash: Hash,
+        transactions: Arc<Vec<Transaction>>,
+    ) -> Result<RwLockWriteGuard<DbPayloadsStore>, StoreError> {
+        self.insert_many_batch(batch, [(hash, transactions)])
+    }
+
+    /// Writes the transactions of several blocks to the batch under a single write lock of the payloads store, see
+    /// [`Self::insert_batch`]
+    pub fn insert_many_batch(
+        &self,
+        batch: &mut WriteBatch,
+        blocks: impl IntoIterator<Item = (Hash, Arc<Vec<Transaction>>)>,
+    ) -> Result<RwLockWriteGuard<DbPayloadsStore>, StoreError> {
+        let mut payloads_write_guard = self.payloads_store.write();
+        for (hash, transactions) in blocks {
+            self.insert_locked(batch, &mut payloads_write_guard, hash, transactions)?;
+        }
+        Ok(payloads_write_guard)
+    }
+
+    fn insert_locked(
+        &self,
+        batch: &mut WriteBatch,
+        payloads_write_guard: &mut DbPayloadsStore,
+        hash: Hash,
+        transactions: Arc<Vec<Transaction>>,
+    ) -> Result<(), StoreError> {
         if self.access.has(hash)? {
             return Err(StoreError::KeyAlreadyExists(hash.to_string()));
         }
-        self.access.write(BatchDbWriter::new(batch), hash, transactions)?;
-        Ok(())
+        let mut payload_refs = Vec::new();
+        for (index, tx) in transactions.iter().enumerate().filter(|(_, tx)| tx.payload.len() >= PAYLOAD_DEDUP_THRESHOLD) {
+            payload_refs.push((index as u32, payloads_write_guard.add_ref_batch(batch, &tx.payload)?));
+        }
+        let transactions = if payload_refs.is_empty() {
+            transactions
+        } else {
+            let mut stripped = (*transactions).clone();
+            for &(index, _) in payload_refs.iter() {
+                stripped[index as usize].payload = vec![];
+            }
+            Arc::new(stripped)
+        };
+        self.access.write(BatchDbWriter::new(batch), hash, StoredTransactions { transactions, payload_refs })
+    }
+
+    /// Deletes the transactions of the block from the batch, releasing their payloads store references, and returns
+    /// the write guard of the payloads store. The guard is expected to be held until the batch is committed
+    pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<RwLockWriteGuard<DbPayloadsStore>, StoreError> {
+        let stored = self.access.read(hash)?;
+        let mut payloads_write_guard = self.payloads_store.write();
+        for &(_, payload_hash) in stored.payload_refs.iter() {
+            payloads_write_guard.remove_ref_batch(batch, payload_hash)?;
+        }
+        self.access.delete(BatchDbWriter::new(batch), hash)?;
+        Ok(payloads_write_guard)
+    }
+
+    pub fn has(&self, hash: Hash) -> Result<bool, StoreError> {
+        self.access.has(hash)
+    }
+
+    /// Returns the payload of `payload_hash` if it is referenced by a stored transaction
+    pub fn get_payload(&self, payload_hash: Hash) -> Result<Arc<Vec<u8>>, StoreError> {
+        self.payloads_store.read().get(payload_hash)
     }
 }
 
 impl BlockTransactionsStoreReader for DbBlockTransactionsStore {
     fn get(&self, hash: Hash) -> Result<Arc<Vec<Transaction>>, StoreError> {
-        self.access.read(hash)
+        let stored = self.access.read(hash)?;
+        if stored.payload_refs.is_empty() {
+            return Ok(stored.transactions);
+        }
+        let payloads_read_guard = self.payloads_store.read();
+        let mut transactions = (*stored.transactions).clone();
+        for &(index, payload_hash) in stored.payload_refs.iter() {
+            transactions[index as usize].payload = payloads_read_guard.get(payload_hash)?.to_vec();
+        }
+        Ok(Arc::new(transactions))
     }
 }
 
 impl BlockTransactionsStore for DbBlockTransactionsStore {
     fn insert(&self, hash: Hash, transactions: Arc<Vec<Transaction>>) -> Result<(), StoreError> {
-        if self.access.has(hash)? {
-            return Err(StoreError::KeyAlreadyExists(hash.to_string()));
-        }
-        self.access.write(DirectDbWriter::new(&self.db), hash, transactions)?;
+        let mut batch = WriteBatch::default();
+        let payloads_write_guard = self.insert_batch(&mut batch, hash, transactions)?;
+        self.db.write(batch)?;
+        drop(payloads_write_guard);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::create_temp_db;
+    use consensus_core::{hashing::tx::payload_hash, subnets::SubnetworkId};
+
+    fn transaction(payload: Vec<u8>) -> Transaction {
+        Transaction::new(0, vec![], vec![], 0, SubnetworkId::from_byte(3), 0, payload)
+    }
+
+    #[test]
+    fn test_payload_deduplication() {
+        let (_lifetime, db) = create_temp_db();
+        let payloads_store = Arc::new(RwLock::new(DbPayloadsStore::new(db.clone(), CachePolicy::Count(10))));
+        let store = DbBlockTransactionsStore::new(db.clone(), CachePolicy::Count(10), payloads_store.clone());
+
+        let large_payload = vec![7u8; PAYLOAD_DEDUP_THRESHOLD];
+        let large_hash = payload_hash(&large_payload);
+        let block_a = Arc::new(vec![transaction(vec![1, 2, 3]), transaction(large_payload.clone())]);
+        let block_b = Arc::new(vec![transaction(large_payload.clone())]);
+        let (hash_a, hash_b) = (Hash::from_u64_word(1), Hash::from_u64_word(2));
+        store.insert(hash_a, block_a.clone()).unwrap();
+        store.insert(hash_b, block_b.clone()).unwrap();
+        assert_eq!(payloads_store.read().ref_count(large_hash).unwrap(), 2);
+        assert!(payloads_store.read().get(payload_hash(&[1, 2, 3])).is_err());
+
+        // Transactions are restored in full, also when read from the DB rather than from the cache
+        let reloaded = DbBlockTransactionsStore::new(
+            db.clone(),
+            CachePolicy::Count(10),
+            Arc::new(RwLock::new(DbPayloadsStore::new(db.clone(), CachePolicy::Count(10)))),
+        );
+        for store in [&store, &reloaded] {
+            let transactions = store.get(hash_a).unwrap();
+            assert_eq!(transactions[0].payload, vec![1, 2, 3]);
+            assert_eq!(transactions[1].payload, large_payload);
+            assert_eq!(transactions[1].id(), block_a[1].id());
+        }
+        assert_eq!(*store.get_payload(large_hash).unwrap(), large_payload);
+
+        // Blocks inserted within a single batch reference the payload once each
+        let (hash_c, hash_d) = (Hash::from_u64_word(3), Hash::from_u64_word(4));
+        let mut batch = WriteBatch::default();
+        let payloads_write_guard = store.insert_many_batch(&mut batch, [(hash_c, block_b.clone()), (hash_d, block_b)]).unwrap();
+        db.write(batch).unwrap();
+        drop(payloads_write_guard);
+        assert_eq!(payloads_store.read().ref_count(large_hash).unwrap(), 4);
+
+        // The payload is deleted along with its last reference
+        for (hash, expected_ref_count) in [(hash_a, Some(3)), (hash_b, Some(2)), (hash_c, Some(1)), (hash_d, None)] {
+            let mut batch = WriteBatch::default();
+            let payloads_write_guard = store.delete_batch(&mut batch, hash).unwrap();
+            db.write(batch).unwrap();
+            drop(payloads_write_guard);
+            assert!(store.get(hash).is_err());
+            assert_eq!(payloads_store.read().ref_count(large_hash).ok(), expected_ref_count);
+        }
+    }
+}
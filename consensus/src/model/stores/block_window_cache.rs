@@ -2,20 +2,50 @@ use super::database::prelude::Cache;
 use crate::processes::ghostdag::ordering::SortableBlock;
 use consensus_core::BlockHasher;
 use hashes::Hash;
-use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+use std::{
+    cmp::Reverse,
+    collections::{binary_heap, BinaryHeap},
+    sync::Arc,
+};
 
 pub type BlockWindowHeap = BinaryHeap<Reverse<SortableBlock>>;
 
+/// The blocks of a window, which are either all the blocks of the window or, once sampling is active, only the
+/// sampled ones. A window only extends the window of a selected parent of the same kind
+#[derive(Clone, Default)]
+pub struct BlockWindow {
+    pub blocks: BlockWindowHeap,
+    pub is_sampled: bool,
+}
+
+impl BlockWindow {
+    pub fn new(blocks: BlockWindowHeap, is_sampled: bool) -> Self {
+        Self { blocks, is_sampled }
+    }
+
+    pub fn iter(&self) -> binary_heap::Iter<'_, Reverse<SortableBlock>> {
+        self.blocks.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
 /// Reader API for `BlockWindowCacheStore`.
 pub trait BlockWindowCacheReader {
-    fn get(&self, hash: &Hash) -> Option<Arc<BlockWindowHeap>>;
+    fn get(&self, hash: &Hash) -> Option<Arc<BlockWindow>>;
 }
 
-pub type BlockWindowCacheStore = Cache<Hash, Arc<BlockWindowHeap>, BlockHasher>;
+pub type BlockWindowCacheStore = Cache<Hash, Arc<BlockWindow>, BlockHasher>;
 
 impl BlockWindowCacheReader for BlockWindowCacheStore {
     #[inline(always)]
-    fn get(&self, hash: &Hash) -> Option<Arc<BlockWindowHeap>> {
+    fn get(&self, hash: &Hash) -> Option<Arc<BlockWindow>> {
         self.get(hash)
     }
 }
@@ -1,7 +1,7 @@
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
 
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
     errors::StoreError,
     DB,
 };
@@ -27,13 +27,21 @@ pub struct DbDaaStore {
     access: CachedDbAccess<Hash, Arc<BlockHashSet>, BlockHasher>,
 }
 
+/// Estimates the memory owned by a cached hash set, see [`CachePolicy::Bytes`]
+fn hash_set_heap_size(data: &Arc<BlockHashSet>) -> usize {
+    size_of::<BlockHashSet>() + data.capacity() * size_of::<Hash>()
+}
+
 impl DbDaaStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_size, STORE_PREFIX) }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db, cache_policy, STORE_PREFIX).with_heap_size_estimator(hash_set_heap_size),
+        }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     pub fn insert_batch(&self, batch: &mut WriteBatch, hash: Hash, mergeset_non_daa: Arc<BlockHashSet>) -> Result<(), StoreError> {
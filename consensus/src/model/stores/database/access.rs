@@ -1,7 +1,12 @@
-use super::prelude::{Cache, DbKey, DbWriter};
+use super::prelude::{Cache, CachePolicy, DbKey, DbWriter, CACHE_COUNTERS};
 use crate::model::stores::{errors::StoreError, DB};
+use rocksdb::{Direction, IteratorMode};
 use serde::{de::DeserializeOwned, Serialize};
-use std::{collections::hash_map::RandomState, hash::BuildHasher, sync::Arc};
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::{atomic::Ordering, Arc},
+};
 
 /// A concurrent DB store access with typed caching.
 #[derive(Clone)]
@@ -25,8 +30,13 @@ where
     TData: Clone + Send + Sync,
     S: BuildHasher + Default,
 {
-    pub fn new(db: Arc<DB>, cache_size: u64, prefix: &'static [u8]) -> Self {
-        Self { db, cache: Cache::new(cache_size), prefix }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy, prefix: &'static [u8]) -> Self {
+        Self { db, cache: Cache::new(cache_policy), prefix }
+    }
+
+    /// Sets the estimator of the memory owned by cached data on top of its inline size, see [`Cache::with_heap_size_estimator`]
+    pub fn with_heap_size_estimator(self, heap_size: fn(&TData) -> usize) -> Self {
+        Self { cache: self.cache.with_heap_size_estimator(heap_size), ..self }
     }
 
     pub fn read_from_cache(&self, key: TKey) -> Option<TData>
@@ -49,8 +59,10 @@ where
         TData: DeserializeOwned, // We need `DeserializeOwned` since the slice coming from `db.get_pinned` has short lifetime
     {
         if let Some(data) = self.cache.get(&key) {
+            CACHE_COUNTERS.hits.fetch_add(1, Ordering::Relaxed);
             Ok(data)
         } else {
+            CACHE_COUNTERS.misses.fetch_add(1, Ordering::Relaxed);
             let db_key = DbKey::new(self.prefix, key);
             if let Some(slice) = self.db.get_pinned(&db_key)? {
                 let data: TData = bincode::deserialize(&slice)?;
@@ -111,4 +123,108 @@ where
         }
         Ok(())
     }
+
+    /// Deletes all entries whose key starts with `key_prefix` and returns their number. The keys are read from
+    /// the DB, hence entries written to a pending batch of `writer` are not deleted.
+    pub fn delete_prefix(&self, mut writer: impl DbWriter, key_prefix: &[u8]) -> Result<usize, StoreError>
+    where
+        TKey: Copy + AsRef<[u8]>,
+    {
+        self.cache.remove_matching(|key| key.as_ref().starts_with(key_prefix));
+        let scan_prefix = DbKey::new(self.prefix, key_prefix);
+        let mut deleted = 0;
+        for item in self.db.iterator(IteratorMode::From(scan_prefix.as_ref(), Direction::Forward)) {
+            let (key, _) = item?;
+            if !key.starts_with(scan_prefix.as_ref()) {
+                break;
+            }
+            writer.delete(key)?;
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+
+    /// Iterates over all entries of the store bucket in key order, reading directly from the DB. Yielded keys
+    /// are stripped of the bucket prefix. The cache is bypassed, so the iteration does not pollute it.
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(Box<[u8]>, TData), StoreError>> + '_
+    where
+        TKey: Copy + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        self.iter_prefix(&[])
+    }
+
+    /// Like [`Self::iterator`], but only iterates over the entries whose key starts with `key_prefix`
+    pub fn iter_prefix(&self, key_prefix: &[u8]) -> impl Iterator<Item = Result<(Box<[u8]>, TData), StoreError>> + '_
+    where
+        TKey: Copy + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        self.seek_iterator(key_prefix, None)
+    }
+
+    /// Like [`Self::iter_prefix`], but starts from the key `seek_from` if specified
+    pub fn seek_iterator(
+        &self,
+        key_prefix: &[u8],
+        seek_from: Option<TKey>,
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, TData), StoreError>> + '_
+    where
+        TKey: Copy + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        let bucket_len = DbKey::prefix_only(self.prefix).as_ref().len();
+        let scan_prefix = DbKey::new(self.prefix, key_prefix);
+        let seek_key = match seek_from {
+            Some(key) => DbKey::new(self.prefix, key),
+            None => scan_prefix.clone(),
+        };
+        self.db
+            .iterator(IteratorMode::From(seek_key.as_ref(), Direction::Forward))
+            .take_while(move |item| match item {
+                Ok((key, _)) => key.starts_with(scan_prefix.as_ref()),
+                Err(_) => true,
+            })
+            .map(move |item| {
+                let (key, value) = item?;
+                let data: TData = bincode::deserialize(&value)?;
+                Ok((key[bucket_len..].into(), data))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        consensus::test_consensus::create_memory_db,
+        model::stores::database::prelude::{BatchDbWriter, DirectDbWriter},
+    };
+    use rocksdb::WriteBatch;
+
+    #[test]
+    fn test_delete_prefix() {
+        let db = create_memory_db();
+        let access = CachedDbAccess::<[u8; 2], u64>::new(db.clone(), CachePolicy::Count(10), b"test-prefix");
+        let keys = [[1, 1], [1, 2], [2, 1], [2, 2]];
+        for (i, key) in keys.into_iter().enumerate() {
+            access.write(DirectDbWriter::new(&db), key, i as u64).unwrap();
+        }
+        let other = CachedDbAccess::<[u8; 2], u64>::new(db.clone(), CachePolicy::Count(10), b"other-prefix");
+        other.write(DirectDbWriter::new(&db), [1, 1], 10).unwrap();
+
+        let entries = |key_prefix: &[u8]| access.iter_prefix(key_prefix).map(|item| item.unwrap().1).collect::<Vec<_>>();
+        assert_eq!(entries(&[1]), vec![0, 1]);
+        assert_eq!(entries(&[]), vec![0, 1, 2, 3]);
+
+        // Deletions are applied once the batch is written, while the cache is invalidated right away
+        let mut batch = WriteBatch::default();
+        assert_eq!(access.delete_prefix(BatchDbWriter::new(&mut batch), &[1]).unwrap(), 2);
+        assert!(access.read_from_cache([1, 2]).is_none());
+        assert_eq!(access.read_from_cache([2, 2]), Some(3));
+        db.write(batch).unwrap();
+        assert_eq!(entries(&[]), vec![2, 3]);
+        assert!(!access.has([1, 1]).unwrap());
+        assert_eq!(other.iterator().map(|item| item.unwrap().1).collect::<Vec<_>>(), vec![10]);
+    }
 }
@@ -1,67 +1,287 @@
 use indexmap::IndexMap;
 use parking_lot::RwLock;
 use rand::Rng;
-use std::{collections::hash_map::RandomState, hash::BuildHasher, sync::Arc};
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    mem::size_of,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
+/// The hit and miss counts of the reads of all cached DB stores of the process
+pub struct CacheCounters {
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+}
+
+pub static CACHE_COUNTERS: CacheCounters = CacheCounters { hits: AtomicU64::new(0), misses: AtomicU64::new(0) };
+
+/// The number of entries sampled on eviction, the least recently used of which is evicted
+const EVICTION_SAMPLES: usize = 8;
+
+/// The bound of a cache
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Holds up to this number of entries
+    Count(usize),
+    /// Holds entries up to this estimated memory size in bytes, see [`Cache::with_heap_size_estimator`]
+    Bytes(usize),
+}
+
+impl CachePolicy {
+    /// Splits the bound evenly across `parts` caches
+    pub fn split(self, parts: usize) -> Self {
+        match self {
+            Self::Count(count) => Self::Count(count / parts),
+            Self::Bytes(bytes) => Self::Bytes(bytes / parts),
+        }
+    }
+
+    fn is_disabled(self) -> bool {
+        matches!(self, Self::Count(0) | Self::Bytes(0))
+    }
+}
+
+/// Distributes a global memory budget across caches, each cache being given a share of the budget
+#[derive(Clone, Copy, Debug)]
+pub struct CachePolicyBuilder {
+    budget: usize,
+}
+
+impl CachePolicyBuilder {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self { budget: budget_bytes }
+    }
+
+    /// A policy bounding a cache to `permille` thousandths of the budget
+    pub fn share(&self, permille: usize) -> CachePolicy {
+        CachePolicy::Bytes(self.budget / 1000 * permille)
+    }
+}
+
+struct Entry<TData> {
+    data: TData,
+    /// The estimated memory size of the entry
+    size: usize,
+    /// The logical time of the latest access
+    last_access: AtomicU64,
+}
+
+struct CacheState<TKey, TData, S> {
+    // We use IndexMap and not HashMap, because it makes it cheaper to sample random elements when the cache is full.
+    map: IndexMap<TKey, Entry<TData>, S>,
+    /// The total estimated memory size of the entries
+    size: usize,
+}
+
+impl<TKey: std::hash::Hash + Eq, TData, S: BuildHasher> CacheState<TKey, TData, S> {
+    fn remove(&mut self, key: &TKey) {
+        if let Some(entry) = self.map.swap_remove(key) {
+            self.size -= entry.size;
+        }
+    }
+}
+
+/// A concurrent cache evicting the approximately least recently used entries. Each eviction removes the least
+/// recently used of a few randomly sampled entries, which avoids ordering the entries on every read, so that
+/// reads only require a shared lock.
 #[derive(Clone)]
 pub struct Cache<TKey: Clone + std::hash::Hash + Eq + Send + Sync, TData: Clone + Send + Sync, S = RandomState> {
-    // We use IndexMap and not HashMap, because it makes it cheaper to remove a random element when the cache is full.
-    map: Arc<RwLock<IndexMap<TKey, TData, S>>>,
-    size: usize,
+    state: Arc<RwLock<CacheState<TKey, TData, S>>>,
+    /// The logical clock ordering the accesses to the entries
+    clock: Arc<AtomicU64>,
+    policy: CachePolicy,
+    heap_size: fn(&TData) -> usize,
 }
 
 impl<TKey: Clone + std::hash::Hash + Eq + Send + Sync, TData: Clone + Send + Sync, S: BuildHasher + Default> Cache<TKey, TData, S> {
-    pub fn new(size: u64) -> Self {
-        Self { map: Arc::new(RwLock::new(IndexMap::with_capacity_and_hasher(size as usize, S::default()))), size: size as usize }
+    pub fn new(policy: CachePolicy) -> Self {
+        let capacity = match policy {
+            CachePolicy::Count(count) => count,
+            CachePolicy::Bytes(_) => 0,
+        };
+        Self {
+            state: Arc::new(RwLock::new(CacheState { map: IndexMap::with_capacity_and_hasher(capacity, S::default()), size: 0 })),
+            clock: Default::default(),
+            policy,
+            heap_size: |_| 0,
+        }
+    }
+
+    /// Sets the estimator of the memory owned by the cached data on top of its inline size, e.g., by the vectors
+    /// it holds. Only affects caches bound by [`CachePolicy::Bytes`].
+    pub fn with_heap_size_estimator(self, heap_size: fn(&TData) -> usize) -> Self {
+        Self { heap_size, ..self }
     }
 
     pub fn get(&self, key: &TKey) -> Option<TData> {
-        self.map.read().get(key).cloned()
+        self.state.read().map.get(key).map(|entry| {
+            entry.last_access.store(self.tick(), Ordering::Relaxed);
+            entry.data.clone()
+        })
     }
 
     pub fn contains_key(&self, key: &TKey) -> bool {
-        self.map.read().contains_key(key)
+        self.state.read().map.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.read().map.is_empty()
+    }
+
+    /// The total estimated memory size of the cached entries
+    pub fn size(&self) -> usize {
+        self.state.read().size
     }
 
     pub fn insert(&self, key: TKey, data: TData) {
-        if self.size == 0 {
+        if self.policy.is_disabled() {
             return;
         }
-        let mut write_guard = self.map.write();
-        if write_guard.len() == self.size {
-            write_guard.swap_remove_index(rand::thread_rng().gen_range(0..self.size));
-        }
-        write_guard.insert(key, data);
+        let mut write_guard = self.state.write();
+        self.insert_locked(&mut write_guard, key, data);
     }
 
     pub fn insert_many(&self, iter: &mut impl Iterator<Item = (TKey, TData)>) {
-        if self.size == 0 {
+        if self.policy.is_disabled() {
             return;
         }
-        let mut write_guard = self.map.write();
+        let mut write_guard = self.state.write();
         for (key, data) in iter {
-            if write_guard.len() == self.size {
-                write_guard.swap_remove_index(rand::thread_rng().gen_range(0..self.size));
-            }
-            write_guard.insert(key, data);
+            self.insert_locked(&mut write_guard, key, data);
         }
     }
 
     pub fn remove(&self, key: &TKey) {
-        if self.size == 0 {
+        if self.policy.is_disabled() {
             return;
         }
-        let mut write_guard = self.map.write();
-        write_guard.swap_remove(key);
+        self.state.write().remove(key);
     }
 
     pub fn remove_many(&self, key_iter: &mut impl Iterator<Item = TKey>) {
-        if self.size == 0 {
+        if self.policy.is_disabled() {
             return;
         }
-        let mut write_guard = self.map.write();
+        let mut write_guard = self.state.write();
         for key in key_iter {
-            write_guard.swap_remove(&key);
+            write_guard.remove(&key);
+        }
+    }
+
+    /// Removes all entries whose key matches `predicate`
+    pub fn remove_matching(&self, predicate: impl Fn(&TKey) -> bool) {
+        if self.policy.is_disabled() {
+            return;
+        }
+        let mut write_guard = self.state.write();
+        let mut removed_size = 0;
+        write_guard.map.retain(|key, entry| {
+            let matches = predicate(key);
+            if matches {
+                removed_size += entry.size;
+            }
+            !matches
+        });
+        write_guard.size -= removed_size;
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn insert_locked(&self, state: &mut CacheState<TKey, TData, S>, key: TKey, data: TData) {
+        // A previous entry is removed first, so that stale data is never kept even if the new data is not cached
+        state.remove(&key);
+        let size = size_of::<TKey>() + size_of::<Entry<TData>>() + (self.heap_size)(&data);
+        if matches!(self.policy, CachePolicy::Bytes(max_size) if size > max_size) {
+            return;
         }
+        self.evict(state, size);
+        state.map.insert(key, Entry { data, size, last_access: AtomicU64::new(self.tick()) });
+        state.size += size;
+    }
+
+    /// Evicts entries until an entry of `incoming_size` fits
+    fn evict(&self, state: &mut CacheState<TKey, TData, S>, incoming_size: usize) {
+        let mut rng = rand::thread_rng();
+        loop {
+            let len = state.map.len();
+            let fits = match self.policy {
+                CachePolicy::Count(max_count) => len < max_count,
+                CachePolicy::Bytes(max_size) => state.size + incoming_size <= max_size,
+            };
+            if fits || len == 0 {
+                return;
+            }
+            let last_access = |index: &usize| state.map[*index].last_access.load(Ordering::Relaxed);
+            // Small caches are scanned entirely, making their eviction exactly LRU
+            let victim = if len <= EVICTION_SAMPLES {
+                (0..len).min_by_key(last_access)
+            } else {
+                (0..EVICTION_SAMPLES).map(|_| rng.gen_range(0..len)).min_by_key(last_access)
+            };
+            if let Some((_, entry)) = victim.and_then(|index| state.map.swap_remove_index(index)) {
+                state.size -= entry.size;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = Cache::<u64, u64>::new(CachePolicy::Count(3));
+        for i in 0..3 {
+            cache.insert(i, i);
+        }
+        // Reading the oldest entry makes the second one the least recently used
+        assert_eq!(cache.get(&0), Some(0));
+        cache.insert(3, 3);
+        assert!(cache.contains_key(&0) && !cache.contains_key(&1));
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_bytes_policy() {
+        let cache = Cache::<u64, Vec<u8>>::new(CachePolicy::Bytes(1000)).with_heap_size_estimator(|data| data.len());
+        let entry_size = size_of::<u64>() + size_of::<Entry<Vec<u8>>>() + 400;
+        cache.insert(0, vec![0; 400]);
+        cache.insert(1, vec![0; 400]);
+        assert_eq!(cache.size(), 2 * entry_size);
+
+        // Large entries evict more entries than small ones
+        cache.insert(2, vec![0; 400]);
+        assert!(!cache.contains_key(&0) && cache.contains_key(&1));
+        cache.insert(3, vec![0; 900]);
+        assert_eq!(cache.len(), 1);
+
+        // Entries larger than the whole budget are not cached, nor is the data they replace kept
+        cache.insert(3, vec![0; 2000]);
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+
+        cache.insert(4, vec![0; 100]);
+        cache.insert(5, vec![0; 100]);
+        cache.remove_matching(|key| key % 2 == 0);
+        assert_eq!(cache.size(), size_of::<u64>() + size_of::<Entry<Vec<u8>>>() + 100);
+    }
+
+    #[test]
+    fn test_budget_shares() {
+        let builder = CachePolicyBuilder::new(1_000_000);
+        assert_eq!(builder.share(150), CachePolicy::Bytes(150_000));
+        assert_eq!(builder.share(150).split(3), CachePolicy::Bytes(50_000));
+        assert_eq!(CachePolicy::Count(10).split(3), CachePolicy::Count(3));
     }
 }
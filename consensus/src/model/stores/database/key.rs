@@ -1,3 +1,4 @@
+use consensus_core::display::Hex;
 use std::{
     fmt::{Debug, Display},
     str,
@@ -38,11 +39,11 @@ impl Display for DbKey {
             f.write_str(s)?;
         } else {
             // Otherwise we fallback to hex parsing
-            f.write_str(&faster_hex::hex_string(&prefix[..prefix.len() - 1]))?; // Drop `SEP`
+            write!(f, "{}", Hex(&prefix[..prefix.len() - 1]))?; // Drop `SEP`
             f.write_str("/")?;
         }
         // We expect that key is usually more readable as hex
-        f.write_str(&faster_hex::hex_string(key))
+        write!(f, "{}", Hex(key))
     }
 }
 
@@ -6,7 +6,7 @@ mod writer;
 
 pub mod prelude {
     pub use super::access::CachedDbAccess;
-    pub use super::cache::Cache;
+    pub use super::cache::{Cache, CacheCounters, CachePolicy, CachePolicyBuilder, CACHE_COUNTERS};
     pub use super::item::CachedDbItem;
     pub use super::key::DbKey;
     pub use super::writer::{BatchDbWriter, DbWriter, DirectDbWriter};
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
     errors::StoreError,
     DB,
 };
@@ -23,9 +23,9 @@ pub trait DepthStore: DepthStoreReader {
 const STORE_PREFIX: &[u8] = b"block-at-depth";
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
-struct BlockDepthInfo {
-    merge_depth_root: Hash,
-    finality_point: Hash,
+pub struct BlockDepthInfo {
+    pub merge_depth_root: Hash,
+    pub finality_point: Hash,
 }
 
 /// A DB + cache implementation of `DepthStore` trait, with concurrency support.
@@ -36,12 +36,12 @@ pub struct DbDepthStore {
 }
 
 impl DbDepthStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_size, STORE_PREFIX) }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, STORE_PREFIX) }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     pub fn insert_batch(
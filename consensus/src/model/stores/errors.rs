@@ -20,6 +20,9 @@ pub type StoreResult<T> = std::result::Result<T, StoreError>;
 
 pub trait StoreResultExtensions<T> {
     fn unwrap_option(self) -> Option<T>;
+
+    /// Like [`Self::unwrap_option`], but returns any error other than a missing key rather than panicking
+    fn optional(self) -> StoreResult<Option<T>>;
 }
 
 impl<T> StoreResultExtensions<T> for StoreResult<T> {
@@ -30,4 +33,12 @@ impl<T> StoreResultExtensions<T> for StoreResult<T> {
             Err(err) => panic!("Unexpected store error: {:?}", err),
         }
     }
+
+    fn optional(self) -> StoreResult<Option<T>> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
 }
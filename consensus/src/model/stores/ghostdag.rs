@@ -1,7 +1,8 @@
-use super::database::prelude::{BatchDbWriter, CachedDbAccess, DbKey, DirectDbWriter};
+use super::database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DbKey, DirectDbWriter};
 use super::{errors::StoreError, DB};
 use crate::processes::ghostdag::ordering::SortableBlock;
-use consensus_core::{blockhash::BlockHashes, BlueWorkType};
+pub use consensus_core::ghostdag::KType;
+use consensus_core::{blockhash::BlockHashes, ghostdag::ExternalGhostdagData, trusted::TrustedGhostdagData, BlueWorkType};
 use consensus_core::{BlockHashMap, BlockHasher, HashMapCustomHasher};
 use hashes::Hash;
 
@@ -10,9 +11,8 @@ use itertools::Itertools;
 use rocksdb::WriteBatch;
 use serde::{Deserialize, Serialize};
 use std::iter::once;
-use std::{cell::RefCell, sync::Arc};
+use std::{cell::RefCell, mem::size_of, sync::Arc};
 
-pub type KType = u8; // This type must be increased to u16 if we ever set GHOSTDAG K > 255
 pub type HashKTypeMap = Arc<BlockHashMap<KType>>;
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -175,6 +175,44 @@ impl GhostdagData {
     }
 }
 
+impl From<&GhostdagData> for ExternalGhostdagData {
+    fn from(data: &GhostdagData) -> Self {
+        Self {
+            blue_score: data.blue_score,
+            blue_work: data.blue_work,
+            selected_parent: data.selected_parent,
+            mergeset_blues: data.mergeset_blues.iter().copied().collect(),
+            mergeset_reds: data.mergeset_reds.iter().copied().collect(),
+        }
+    }
+}
+
+impl From<&TrustedGhostdagData> for GhostdagData {
+    fn from(data: &TrustedGhostdagData) -> Self {
+        Self {
+            blue_score: data.blue_score,
+            blue_work: data.blue_work,
+            selected_parent: data.selected_parent,
+            mergeset_blues: BlockHashes::new(data.mergeset_blues.clone()),
+            mergeset_reds: BlockHashes::new(data.mergeset_reds.clone()),
+            blues_anticone_sizes: HashKTypeMap::new(data.blues_anticone_sizes.iter().copied().collect()),
+        }
+    }
+}
+
+impl From<&GhostdagData> for TrustedGhostdagData {
+    fn from(data: &GhostdagData) -> Self {
+        Self {
+            blue_score: data.blue_score,
+            blue_work: data.blue_work,
+            selected_parent: data.selected_parent,
+            mergeset_blues: data.mergeset_blues.as_ref().clone(),
+            mergeset_reds: data.mergeset_reds.as_ref().clone(),
+            blues_anticone_sizes: data.blues_anticone_sizes.iter().map(|(&hash, &size)| (hash, size)).collect(),
+        }
+    }
+}
+
 pub trait GhostdagStoreReader {
     fn get_blue_score(&self, hash: Hash) -> Result<u64, StoreError>;
     fn get_blue_work(&self, hash: Hash) -> Result<BlueWorkType, StoreError>;
@@ -211,17 +249,24 @@ pub struct DbGhostdagStore {
     compact_access: CachedDbAccess<Hash, CompactGhostdagData, BlockHasher>,
 }
 
+/// Estimates the memory owned by cached GHOSTDAG data, see [`CachePolicy::Bytes`]
+fn ghostdag_heap_size(data: &Arc<GhostdagData>) -> usize {
+    size_of::<GhostdagData>()
+        + (data.mergeset_blues.len() + data.mergeset_reds.len()) * size_of::<Hash>()
+        + data.blues_anticone_sizes.len() * (size_of::<Hash>() + size_of::<KType>())
+}
+
 impl DbGhostdagStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
         Self {
             db: Arc::clone(&db),
-            access: CachedDbAccess::new(db.clone(), cache_size, STORE_PREFIX),
-            compact_access: CachedDbAccess::new(db, cache_size, COMPACT_STORE_PREFIX),
+            access: CachedDbAccess::new(db.clone(), cache_policy, STORE_PREFIX).with_heap_size_estimator(ghostdag_heap_size),
+            compact_access: CachedDbAccess::new(db, cache_policy, COMPACT_STORE_PREFIX),
         }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     pub fn insert_batch(&self, batch: &mut WriteBatch, hash: Hash, data: &Arc<GhostdagData>) -> Result<(), StoreError> {
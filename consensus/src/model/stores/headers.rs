@@ -1,11 +1,11 @@
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
 
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
     errors::StoreError,
     DB,
 };
-use consensus_core::{header::Header, BlockHasher};
+use consensus_core::{header::Header, time::TimestampMs, BlockHasher};
 use hashes::Hash;
 use rocksdb::WriteBatch;
 use serde::{Deserialize, Serialize};
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 pub trait HeaderStoreReader {
     fn get_daa_score(&self, hash: Hash) -> Result<u64, StoreError>;
     fn get_blue_score(&self, hash: Hash) -> Result<u64, StoreError>;
-    fn get_timestamp(&self, hash: Hash) -> Result<u64, StoreError>;
+    fn get_timestamp(&self, hash: Hash) -> Result<TimestampMs, StoreError>;
     fn get_bits(&self, hash: Hash) -> Result<u32, StoreError>;
     fn get_header(&self, hash: Hash) -> Result<Arc<Header>, StoreError>;
     fn get_header_with_block_level(&self, hash: Hash) -> Result<HeaderWithBlockLevel, StoreError>;
@@ -37,7 +37,7 @@ const COMPACT_HEADER_DATA_STORE_PREFIX: &[u8] = b"compact-header-data";
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct CompactHeaderData {
     pub daa_score: u64,
-    pub timestamp: u64,
+    pub timestamp: TimestampMs,
     pub bits: u32,
     pub blue_score: u64,
 }
@@ -50,17 +50,23 @@ pub struct DbHeadersStore {
     headers_access: CachedDbAccess<Hash, HeaderWithBlockLevel, BlockHasher>,
 }
 
+/// Estimates the memory owned by a cached header, see [`CachePolicy::Bytes`]
+fn header_heap_size(data: &HeaderWithBlockLevel) -> usize {
+    size_of::<Header>()
+        + data.header.parents_by_level.iter().map(|parents| size_of::<Vec<Hash>>() + parents.len() * size_of::<Hash>()).sum::<usize>()
+}
+
 impl DbHeadersStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
         Self {
             db: Arc::clone(&db),
-            compact_headers_access: CachedDbAccess::new(Arc::clone(&db), cache_size, COMPACT_HEADER_DATA_STORE_PREFIX),
-            headers_access: CachedDbAccess::new(db, cache_size, HEADERS_STORE_PREFIX),
+            compact_headers_access: CachedDbAccess::new(Arc::clone(&db), cache_policy, COMPACT_HEADER_DATA_STORE_PREFIX),
+            headers_access: CachedDbAccess::new(db, cache_policy, HEADERS_STORE_PREFIX).with_heap_size_estimator(header_heap_size),
         }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     pub fn insert_batch(&self, batch: &mut WriteBatch, hash: Hash, header: Arc<Header>, block_level: u8) -> Result<(), StoreError> {
@@ -80,6 +86,16 @@ impl DbHeadersStore {
         )?;
         Ok(())
     }
+
+    /// Deletes the full header of the block, keeping its compact data
+    pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
+        self.headers_access.delete(BatchDbWriter::new(batch), hash)
+    }
+
+    /// Deletes the compact header data of the block. The full header is expected to be deleted already
+    pub fn delete_compact_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
+        self.compact_headers_access.delete(BatchDbWriter::new(batch), hash)
+    }
 }
 
 impl HeaderStoreReader for DbHeadersStore {
@@ -97,7 +113,7 @@ impl HeaderStoreReader for DbHeadersStore {
         Ok(self.compact_headers_access.read(hash)?.blue_score)
     }
 
-    fn get_timestamp(&self, hash: Hash) -> Result<u64, StoreError> {
+    fn get_timestamp(&self, hash: Hash) -> Result<TimestampMs, StoreError> {
         if let Some(header_with_block_level) = self.headers_access.read_from_cache(hash) {
             return Ok(header_with_block_level.header.timestamp);
         }
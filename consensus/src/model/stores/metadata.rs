@@ -0,0 +1,111 @@
+use super::{
+    database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter},
+    errors::StoreResult,
+    DB,
+};
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, sync::Arc};
+
+/// The version of the format of the data persisted by the node
+pub type SchemaVersion = u32;
+
+/// An operation writing several batches, which leaves the DB partially written if interrupted in between. Such
+/// operations are staged before their first batch and unstaged after their last one, so that an interruption is
+/// detected on the next startup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StagedOperation {
+    /// The initialization of the stores with the genesis block
+    GenesisInit,
+    /// The insertion of trusted blocks during the IBD of pruned data, which leaves the DAG without the past of the
+    /// blocks until the sync completes
+    TrustedSync,
+}
+
+impl Display for StagedOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GenesisInit => f.write_str("genesis initialization"),
+            Self::TrustedSync => f.write_str("trusted sync"),
+        }
+    }
+}
+
+/// Reader API for `MetadataStore`.
+pub trait MetadataStoreReader {
+    fn get_schema_version(&self) -> StoreResult<SchemaVersion>;
+    fn get_staged_operation(&self) -> StoreResult<StagedOperation>;
+    fn get_archival(&self) -> StoreResult<bool>;
+}
+
+pub trait MetadataStore: MetadataStoreReader {
+    fn set_schema_version(&mut self, version: SchemaVersion) -> StoreResult<()>;
+    fn stage(&mut self, operation: StagedOperation) -> StoreResult<()>;
+    fn unstage(&mut self) -> StoreResult<()>;
+    fn set_archival(&mut self, archival: bool) -> StoreResult<()>;
+}
+
+pub const SCHEMA_VERSION_KEY: &[u8] = b"metadata-schema-version";
+pub const STAGED_OPERATION_KEY: &[u8] = b"metadata-staged-operation";
+pub const ARCHIVAL_KEY: &[u8] = b"metadata-archival";
+
+/// A DB + cache implementation of `MetadataStore` trait, holding data describing the DB itself
+#[derive(Clone)]
+pub struct DbMetadataStore {
+    db: Arc<DB>,
+    schema_version: CachedDbItem<SchemaVersion>,
+    staged_operation: CachedDbItem<StagedOperation>,
+    /// Whether the node keeps the data of all blocks rather than pruning it
+    archival: CachedDbItem<bool>,
+}
+
+impl DbMetadataStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            schema_version: CachedDbItem::new(db.clone(), SCHEMA_VERSION_KEY),
+            staged_operation: CachedDbItem::new(db.clone(), STAGED_OPERATION_KEY),
+            archival: CachedDbItem::new(db, ARCHIVAL_KEY),
+        }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+
+    pub fn set_schema_version_batch(&mut self, batch: &mut WriteBatch, version: SchemaVersion) -> StoreResult<()> {
+        self.schema_version.write(BatchDbWriter::new(batch), &version)
+    }
+}
+
+impl MetadataStoreReader for DbMetadataStore {
+    fn get_schema_version(&self) -> StoreResult<SchemaVersion> {
+        self.schema_version.read()
+    }
+
+    fn get_staged_operation(&self) -> StoreResult<StagedOperation> {
+        self.staged_operation.read()
+    }
+
+    fn get_archival(&self) -> StoreResult<bool> {
+        self.archival.read()
+    }
+}
+
+impl MetadataStore for DbMetadataStore {
+    fn set_schema_version(&mut self, version: SchemaVersion) -> StoreResult<()> {
+        self.schema_version.write(DirectDbWriter::new(&self.db), &version)
+    }
+
+    fn stage(&mut self, operation: StagedOperation) -> StoreResult<()> {
+        self.staged_operation.write(DirectDbWriter::new(&self.db), &operation)
+    }
+
+    fn unstage(&mut self) -> StoreResult<()> {
+        self.staged_operation.remove(DirectDbWriter::new(&self.db))
+    }
+
+    fn set_archival(&mut self, archival: bool) -> StoreResult<()> {
+        self.archival.write(DirectDbWriter::new(&self.db), &archival)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod acceptance_data;
+pub mod block_counts;
 pub mod block_transactions;
 pub mod block_window_cache;
 pub mod daa;
@@ -8,10 +9,15 @@ pub mod errors;
 pub mod ghostdag;
 pub mod headers;
 pub mod headers_selected_tip;
+pub mod metadata;
 pub mod past_pruning_points;
+pub mod payloads;
+pub mod pinned_blocks;
 pub mod pruning;
+pub mod pruning_utxoset_position;
 pub mod reachability;
 pub mod relations;
+pub mod retention_root;
 pub mod statuses;
 pub mod tips;
 pub mod utxo_diffs;
@@ -20,4 +26,50 @@ pub mod utxo_set;
 pub mod virtual_state;
 
 use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::{path::Path, sync::Arc};
 pub type DB = DBWithThreadMode<MultiThreaded>;
+
+/// Opens the DB at `db_path` read-only, as a secondary instance of the DB possibly held open by a running node.
+/// The secondary instance never writes to `db_path`, so it can inspect a live DB without risking its corruption,
+/// and keeps its own info logs in `secondary_path`. It sees the data as of opening, and is brought up to date with
+/// the writes of the node by calling `try_catch_up_with_primary`, which does not refresh store caches, hence stores
+/// over a secondary DB are best created with [`CachePolicy::Count(0)`](database::prelude::CachePolicy). Writes
+/// through the stores fail with `StoreError::DbError`.
+pub fn open_secondary_db(db_path: &Path, secondary_path: &Path) -> Result<Arc<DB>, rocksdb::Error> {
+    let mut options = rocksdb::Options::default();
+    // Required by secondary instances, which otherwise miss files the primary opened after them
+    options.set_max_open_files(-1);
+    DB::open_as_secondary(&options, db_path, secondary_path).map(Arc::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::create_temp_db;
+    use database::prelude::{CachePolicy, DbKey};
+    use hashes::Hash;
+    use past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStore, PastPruningPointsStoreReader};
+
+    #[test]
+    fn test_secondary_db() {
+        let (_lifetime, db) = create_temp_db();
+        let db_path = db.path().to_owned();
+        let secondary_dir = tempfile::tempdir().unwrap();
+        let primary_store = DbPastPruningPointsStore::new(db.clone(), CachePolicy::Count(0));
+        primary_store.insert(0, Hash::from(1u64)).unwrap();
+        db.flush().unwrap();
+
+        let secondary = open_secondary_db(&db_path, secondary_dir.path()).unwrap();
+        let secondary_store = DbPastPruningPointsStore::new(secondary.clone(), CachePolicy::Count(0));
+        assert_eq!(secondary_store.get(0).unwrap(), Hash::from(1u64));
+
+        // Writes of the primary are seen once caught up with
+        primary_store.insert(1, Hash::from(2u64)).unwrap();
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(secondary_store.get(1).unwrap(), Hash::from(2u64));
+
+        // The secondary instance is read-only
+        assert!(secondary_store.insert(2, Hash::from(3u64)).is_err());
+        assert!(secondary.put(DbKey::new(b"test", Hash::from(4u64)), b"").is_err());
+    }
+}
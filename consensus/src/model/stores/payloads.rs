@@ -0,0 +1,83 @@
+use super::{
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess},
+    errors::{StoreResult, StoreResultExtensions},
+    DB,
+};
+use consensus_core::{hashing::tx::payload_hash, BlockHasher};
+use hashes::Hash;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use std::{mem::size_of, sync::Arc};
+
+/// A payload along with the number of block transactions referencing it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PayloadEntry {
+    payload: Arc<Vec<u8>>,
+    ref_count: u64,
+}
+
+/// Reader API for `PayloadsStore`.
+pub trait PayloadsStoreReader {
+    fn get(&self, hash: Hash) -> StoreResult<Arc<Vec<u8>>>;
+    fn ref_count(&self, hash: Hash) -> StoreResult<u64>;
+}
+
+pub const STORE_PREFIX: &[u8] = b"payloads";
+
+/// A DB + cache implementation of a content-addressed payloads store, where each payload is stored once under
+/// its payload hash along with a reference count. References are only updated within the batches of the block
+/// transactions store, which must hold a write lock over the store until the batch is written
+#[derive(Clone)]
+pub struct DbPayloadsStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<Hash, PayloadEntry, BlockHasher>,
+}
+
+/// Estimates the memory owned by a cached payload, see [`CachePolicy::Bytes`]
+fn payload_heap_size(entry: &PayloadEntry) -> usize {
+    size_of::<Vec<u8>>() + entry.payload.len()
+}
+
+impl DbPayloadsStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db, cache_policy, STORE_PREFIX).with_heap_size_estimator(payload_heap_size),
+        }
+    }
+
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
+    }
+
+    /// Adds a reference to `payload`, storing it if it was not referenced yet. Returns the payload hash
+    pub fn add_ref_batch(&mut self, batch: &mut WriteBatch, payload: &[u8]) -> StoreResult<Hash> {
+        let hash = payload_hash(payload);
+        let entry = match self.access.read(hash).unwrap_option() {
+            Some(entry) => PayloadEntry { ref_count: entry.ref_count + 1, ..entry },
+            None => PayloadEntry { payload: Arc::new(payload.to_vec()), ref_count: 1 },
+        };
+        self.access.write(BatchDbWriter::new(batch), hash, entry)?;
+        Ok(hash)
+    }
+
+    /// Removes a reference to the payload of `hash`, deleting the payload once it is no longer referenced
+    pub fn remove_ref_batch(&mut self, batch: &mut WriteBatch, hash: Hash) -> StoreResult<()> {
+        let entry = self.access.read(hash)?;
+        if entry.ref_count > 1 {
+            self.access.write(BatchDbWriter::new(batch), hash, PayloadEntry { ref_count: entry.ref_count - 1, ..entry })
+        } else {
+            self.access.delete(BatchDbWriter::new(batch), hash)
+        }
+    }
+}
+
+impl PayloadsStoreReader for DbPayloadsStore {
+    fn get(&self, hash: Hash) -> StoreResult<Arc<Vec<u8>>> {
+        Ok(self.access.read(hash)?.payload)
+    }
+
+    fn ref_count(&self, hash: Hash) -> StoreResult<u64> {
+        Ok(self.access.read(hash)?.ref_count)
+    }
+}
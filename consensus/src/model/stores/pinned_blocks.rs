@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use super::{
+    database::prelude::{CachedDbItem, DirectDbWriter},
+    errors::{StoreError, StoreResult},
+    DB,
+};
+use consensus_core::BlockHashSet;
+use hashes::Hash;
+
+/// Reader API for `PinnedBlocksStore`.
+pub trait PinnedBlocksStoreReader {
+    /// Returns the pinned blocks, which is an empty set if no block was ever pinned
+    fn get(&self) -> StoreResult<Arc<BlockHashSet>>;
+}
+
+/// Pinned blocks are kept along with their data when pruning, so that DAG regions of interest can be preserved
+/// by node operators. Pruning is expected to skip the data of every block of the set.
+pub trait PinnedBlocksStore: PinnedBlocksStoreReader {
+    fn pin(&mut self, hashes: &[Hash]) -> StoreResult<Arc<BlockHashSet>>;
+    fn unpin(&mut self, hashes: &[Hash]) -> StoreResult<Arc<BlockHashSet>>;
+}
+
+pub const STORE_NAME: &[u8] = b"pinned-blocks";
+
+/// A DB + cache implementation of `PinnedBlocksStore` trait
+#[derive(Clone)]
+pub struct DbPinnedBlocksStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Arc<BlockHashSet>>,
+}
+
+impl DbPinnedBlocksStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db.clone(), STORE_NAME) }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+
+    pub fn is_pinned(&self, hash: Hash) -> StoreResult<bool> {
+        Ok(self.get()?.contains(&hash))
+    }
+
+    fn update(&mut self, op: impl Fn(&mut BlockHashSet)) -> StoreResult<Arc<BlockHashSet>> {
+        let mut pinned = self.get()?;
+        op(Arc::make_mut(&mut pinned));
+        self.access.write(DirectDbWriter::new(&self.db), &pinned)?;
+        Ok(pinned)
+    }
+}
+
+impl PinnedBlocksStoreReader for DbPinnedBlocksStore {
+    fn get(&self) -> StoreResult<Arc<BlockHashSet>> {
+        match self.access.read() {
+            Err(StoreError::KeyNotFound(_)) => Ok(Default::default()),
+            result => result,
+        }
+    }
+}
+
+impl PinnedBlocksStore for DbPinnedBlocksStore {
+    fn pin(&mut self, hashes: &[Hash]) -> StoreResult<Arc<BlockHashSet>> {
+        self.update(|pinned| pinned.extend(hashes.iter().copied()))
+    }
+
+    fn unpin(&mut self, hashes: &[Hash]) -> StoreResult<Arc<BlockHashSet>> {
+        self.update(|pinned| {
+            for hash in hashes {
+                pinned.remove(hash);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::create_temp_db;
+
+    #[test]
+    fn test_pin_and_unpin() {
+        let (_lifetime, db) = create_temp_db();
+        let mut store = DbPinnedBlocksStore::new(db);
+        assert!(store.get().unwrap().is_empty());
+
+        store.pin(&[1.into(), 2.into(), 3.into()]).unwrap();
+        let pinned = store.unpin(&[2.into(), 4.into()]).unwrap();
+        assert_eq!(*pinned, BlockHashSet::from_iter([1.into(), 3.into()]));
+
+        // The set is persisted, hence read back by a store with a fresh cache
+        let store = store.clone_with_new_cache();
+        assert!(store.is_pinned(1.into()).unwrap());
+        assert!(!store.is_pinned(2.into()).unwrap());
+    }
+}
@@ -0,0 +1,55 @@
+use super::{
+    database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter},
+    errors::StoreResult,
+    DB,
+};
+use hashes::Hash;
+use rocksdb::WriteBatch;
+use std::sync::Arc;
+
+/// Reader API for `PruningUtxosetPositionStore`.
+pub trait PruningUtxosetPositionStoreReader {
+    fn get(&self) -> StoreResult<Hash>;
+}
+
+/// The position of the pruning point UTXO set is the selected chain block whose UTXO set it currently holds. It
+/// follows the selected chain of the pruning point, reaching it soon after each pruning point movement. The store
+/// is unset as long as the set was never advanced, in which case the position is genesis and the set is empty.
+pub trait PruningUtxosetPositionStore: PruningUtxosetPositionStoreReader {
+    fn set(&mut self, hash: Hash) -> StoreResult<()>;
+}
+
+pub const STORE_NAME: &[u8] = b"pruning-utxoset-position";
+
+/// A DB + cache implementation of `PruningUtxosetPositionStore` trait
+#[derive(Clone)]
+pub struct DbPruningUtxosetPositionStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Hash>,
+}
+
+impl DbPruningUtxosetPositionStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db.clone(), STORE_NAME) }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+
+    pub fn set_batch(&mut self, batch: &mut WriteBatch, hash: Hash) -> StoreResult<()> {
+        self.access.write(BatchDbWriter::new(batch), &hash)
+    }
+}
+
+impl PruningUtxosetPositionStoreReader for DbPruningUtxosetPositionStore {
+    fn get(&self) -> StoreResult<Hash> {
+        self.access.read()
+    }
+}
+
+impl PruningUtxosetPositionStore for DbPruningUtxosetPositionStore {
+    fn set(&mut self, hash: Hash) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), &hash)
+    }
+}
@@ -1,5 +1,5 @@
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, CachedDbItem, DbKey, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, CachedDbItem, DbKey, DirectDbWriter},
     errors::StoreError,
     DB,
 };
@@ -13,7 +13,7 @@ use hashes::Hash;
 use parking_lot::{RwLockUpgradableReadGuard, RwLockWriteGuard};
 use rocksdb::WriteBatch;
 use serde::{Deserialize, Serialize};
-use std::{collections::hash_map::Entry::Vacant, sync::Arc};
+use std::{collections::hash_map::Entry::Vacant, mem::size_of, sync::Arc};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ReachabilityData {
@@ -64,17 +64,22 @@ pub struct DbReachabilityStore {
     reindex_root: CachedDbItem<Hash>,
 }
 
+/// Estimates the memory owned by cached reachability data, see [`CachePolicy::Bytes`]
+fn reachability_heap_size(data: &Arc<ReachabilityData>) -> usize {
+    size_of::<ReachabilityData>() + (data.children.len() + data.future_covering_set.len()) * size_of::<Hash>()
+}
+
 impl DbReachabilityStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
         Self {
             db: Arc::clone(&db),
-            access: CachedDbAccess::new(Arc::clone(&db), cache_size, STORE_PREFIX),
+            access: CachedDbAccess::new(Arc::clone(&db), cache_policy, STORE_PREFIX).with_heap_size_estimator(reachability_heap_size),
             reindex_root: CachedDbItem::new(db, REINDEX_ROOT_KEY),
         }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 }
 
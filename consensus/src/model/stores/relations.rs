@@ -1,5 +1,5 @@
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DbKey, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DbKey, DirectDbWriter},
     errors::StoreError,
     DB,
 };
@@ -7,7 +7,7 @@ use consensus_core::{blockhash::BlockHashes, BlockHashMap, BlockHasher, HashMapC
 use hashes::Hash;
 use parking_lot::{RwLock, RwLockWriteGuard};
 use rocksdb::WriteBatch;
-use std::{collections::hash_map::Entry::Vacant, sync::Arc};
+use std::{collections::hash_map::Entry::Vacant, mem::size_of, sync::Arc};
 
 /// Reader API for `RelationsStore`.
 pub trait RelationsStoreReader {
@@ -35,17 +35,23 @@ pub struct DbRelationsStore {
     children_access: CachedDbAccess<Hash, Arc<Vec<Hash>>, BlockHasher>,
 }
 
+/// Estimates the memory owned by a cached hash list, see [`CachePolicy::Bytes`]
+fn hashes_heap_size(data: &Arc<Vec<Hash>>) -> usize {
+    size_of::<Vec<Hash>>() + data.len() * size_of::<Hash>()
+}
+
 impl DbRelationsStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
         Self {
             db: Arc::clone(&db),
-            parents_access: CachedDbAccess::new(Arc::clone(&db), cache_size, PARENTS_PREFIX),
-            children_access: CachedDbAccess::new(db, cache_size, CHILDREN_PREFIX),
+            parents_access: CachedDbAccess::new(Arc::clone(&db), cache_policy, PARENTS_PREFIX)
+                .with_heap_size_estimator(hashes_heap_size),
+            children_access: CachedDbAccess::new(db, cache_policy, CHILDREN_PREFIX).with_heap_size_estimator(hashes_heap_size),
         }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     // Should be kept private and used only through `RelationsStoreBatchExtensions.insert_batch`
@@ -209,7 +215,7 @@ mod tests {
     fn test_db_relations_store() {
         let db_tempdir = tempfile::tempdir().unwrap();
         let db = Arc::new(DB::open_default(db_tempdir.path().to_owned().to_str().unwrap()).unwrap());
-        test_relations_store(DbRelationsStore::new(db, 2));
+        test_relations_store(DbRelationsStore::new(db, CachePolicy::Count(2)));
     }
 
     fn test_relations_store<T: RelationsStore>(mut store: T) {
@@ -0,0 +1,55 @@
+use super::{
+    database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter},
+    errors::StoreResult,
+    DB,
+};
+use hashes::Hash;
+use rocksdb::WriteBatch;
+use std::sync::Arc;
+
+/// Reader API for `RetentionRootStore`.
+pub trait RetentionRootStoreReader {
+    fn get(&self) -> StoreResult<Hash>;
+}
+
+/// The retention root is the selected chain block below which the bodies and acceptance data of blocks were
+/// garbage collected. It follows the selected chain of the pruning point, lagging behind it by the retention
+/// period. The store is unset as long as no data was collected, in which case the root is genesis.
+pub trait RetentionRootStore: RetentionRootStoreReader {
+    fn set(&mut self, hash: Hash) -> StoreResult<()>;
+}
+
+pub const STORE_NAME: &[u8] = b"retention-root";
+
+/// A DB + cache implementation of `RetentionRootStore` trait
+#[derive(Clone)]
+pub struct DbRetentionRootStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Hash>,
+}
+
+impl DbRetentionRootStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db.clone(), STORE_NAME) }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+
+    pub fn set_batch(&mut self, batch: &mut WriteBatch, hash: Hash) -> StoreResult<()> {
+        self.access.write(BatchDbWriter::new(batch), &hash)
+    }
+}
+
+impl RetentionRootStoreReader for DbRetentionRootStore {
+    fn get(&self) -> StoreResult<Hash> {
+        self.access.read()
+    }
+}
+
+impl RetentionRootStore for DbRetentionRootStore {
+    fn set(&mut self, hash: Hash) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), &hash)
+    }
+}
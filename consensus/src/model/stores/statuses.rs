@@ -1,10 +1,10 @@
 use consensus_core::{blockstatus::BlockStatus, BlockHasher};
-use parking_lot::{RwLock, RwLockWriteGuard};
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use rocksdb::WriteBatch;
 use std::sync::Arc;
 
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
     errors::{StoreError, StoreResult},
     DB,
 };
@@ -18,13 +18,16 @@ pub trait StatusesStoreReader {
 
 /// Write API for `StatusesStore`. The set function is deliberately `mut`
 /// since status is not append-only and thus needs to be guarded.
-/// TODO: can be optimized to avoid the locking if needed.
+/// See [`ShardedStatusesStore`] for the guarded implementation.
 pub trait StatusesStore: StatusesStoreReader {
     fn set(&mut self, hash: Hash, status: BlockStatus) -> StoreResult<()>;
 }
 
 const STORE_PREFIX: &[u8] = b"block-statuses";
 
+/// The number of shards of [`ShardedStatusesStore`]
+pub const STATUSES_STORE_SHARDS: usize = 16;
+
 /// A DB + cache implementation of `StatusesStore` trait, with concurrent readers support.
 #[derive(Clone)]
 pub struct DbStatusesStore {
@@ -33,12 +36,12 @@ pub struct DbStatusesStore {
 }
 
 impl DbStatusesStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_size, STORE_PREFIX) }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, STORE_PREFIX) }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 }
 
@@ -49,19 +52,96 @@ pub trait StatusesStoreBatchExtensions {
         hash: Hash,
         status: BlockStatus,
     ) -> Result<RwLockWriteGuard<DbStatusesStore>, StoreError>;
+
+    fn set_many_batch(
+        &self,
+        batch: &mut WriteBatch,
+        hashes: &[Hash],
+        status: BlockStatus,
+    ) -> Result<Vec<RwLockWriteGuard<DbStatusesStore>>, StoreError>;
 }
 
-impl StatusesStoreBatchExtensions for Arc<RwLock<DbStatusesStore>> {
+impl StatusesStoreBatchExtensions for Arc<ShardedStatusesStore> {
+    /// Writes the status to the batch and returns the write guard of the shard holding `hash`. The guard is
+    /// expected to be held until the batch is committed, so that readers never observe an uncommitted status
     fn set_batch(
         &self,
         batch: &mut WriteBatch,
         hash: Hash,
         status: BlockStatus,
     ) -> Result<RwLockWriteGuard<DbStatusesStore>, StoreError> {
-        let write_guard = self.write();
+        let write_guard = self.write(hash);
         write_guard.access.write(BatchDbWriter::new(batch), hash, status)?;
         Ok(write_guard)
     }
+
+    /// Writes the status of all `hashes` to the batch and returns the write guards of the shards holding them,
+    /// see [`Self::set_batch`]. Each shard is locked once, in shard order
+    fn set_many_batch(
+        &self,
+        batch: &mut WriteBatch,
+        hashes: &[Hash],
+        status: BlockStatus,
+    ) -> Result<Vec<RwLockWriteGuard<DbStatusesStore>>, StoreError> {
+        let mut shard_indices: Vec<usize> = hashes.iter().map(|&hash| shard_index(hash)).collect();
+        shard_indices.sort_unstable();
+        shard_indices.dedup();
+        let write_guards: Vec<_> = shard_indices.iter().map(|&index| (index, self.shards[index].write())).collect();
+        for &hash in hashes {
+            let (_, write_guard) = write_guards.iter().find(|(index, _)| *index == shard_index(hash)).unwrap();
+            write_guard.access.write(BatchDbWriter::new(batch), hash, status)?;
+        }
+        Ok(write_guards.into_iter().map(|(_, write_guard)| write_guard).collect())
+    }
+}
+
+fn shard_index(hash: Hash) -> usize {
+    hash.as_bytes()[0] as usize % STATUSES_STORE_SHARDS
+}
+
+/// A statuses store split by hash prefix into [`STATUSES_STORE_SHARDS`] shards, each a [`DbStatusesStore`]
+/// guarded by its own lock. Statuses are accessed per block by all processors concurrently, so sharding
+/// limits contention to blocks falling in the same shard. All shards persist to the same DB prefix and
+/// each holds a proportional part of the cache.
+pub struct ShardedStatusesStore {
+    shards: Vec<RwLock<DbStatusesStore>>,
+}
+
+impl ShardedStatusesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        let shard_cache_policy = cache_policy.split(STATUSES_STORE_SHARDS);
+        Self {
+            shards: (0..STATUSES_STORE_SHARDS).map(|_| RwLock::new(DbStatusesStore::new(db.clone(), shard_cache_policy))).collect(),
+        }
+    }
+
+    fn shard(&self, hash: Hash) -> &RwLock<DbStatusesStore> {
+        &self.shards[shard_index(hash)]
+    }
+
+    /// Acquires a read lock on the shard holding `hash`
+    pub fn read(&self, hash: Hash) -> RwLockReadGuard<DbStatusesStore> {
+        self.shard(hash).read()
+    }
+
+    /// Acquires a write lock on the shard holding `hash`
+    pub fn write(&self, hash: Hash) -> RwLockWriteGuard<DbStatusesStore> {
+        self.shard(hash).write()
+    }
+
+    pub fn set(&self, hash: Hash, status: BlockStatus) -> StoreResult<()> {
+        self.write(hash).set(hash, status)
+    }
+}
+
+impl StatusesStoreReader for ShardedStatusesStore {
+    fn get(&self, hash: Hash) -> StoreResult<BlockStatus> {
+        self.read(hash).get(hash)
+    }
+
+    fn has(&self, hash: Hash) -> StoreResult<bool> {
+        self.read(hash).has(hash)
+    }
 }
 
 impl StatusesStoreReader for DbStatusesStore {
@@ -79,3 +159,47 @@ impl StatusesStore for DbStatusesStore {
         self.access.write(DirectDbWriter::new(&self.db), hash, status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::create_temp_db;
+
+    #[test]
+    fn test_sharded_statuses_store() {
+        let (_lifetime, db) = create_temp_db();
+        let store = Arc::new(ShardedStatusesStore::new(db.clone(), CachePolicy::Count(100)));
+        let hashes: Vec<Hash> = (0..=u8::MAX).map(|i| Hash::from_bytes([i; 32])).collect();
+
+        for hash in hashes.iter().copied() {
+            assert!(!store.has(hash).unwrap());
+            store.set(hash, BlockStatus::StatusHeaderOnly).unwrap();
+        }
+
+        let mut batch = WriteBatch::default();
+        let write_guard = store.set_batch(&mut batch, hashes[1], BlockStatus::StatusUTXOValid).unwrap();
+        // Other shards remain accessible while a shard is write-locked
+        assert_eq!(store.get(hashes[2]).unwrap(), BlockStatus::StatusHeaderOnly);
+        db.write(batch).unwrap();
+        drop(write_guard);
+
+        assert_eq!(store.get(hashes[1]).unwrap(), BlockStatus::StatusUTXOValid);
+        // Statuses are persisted to the shared prefix regardless of the shard
+        let reloaded = ShardedStatusesStore::new(db, CachePolicy::Count(100));
+        for hash in hashes.iter().copied().filter(|&h| h != hashes[1]) {
+            assert_eq!(reloaded.get(hash).unwrap(), BlockStatus::StatusHeaderOnly);
+        }
+        assert_eq!(reloaded.get(hashes[1]).unwrap(), BlockStatus::StatusUTXOValid);
+
+        // A batch spanning several shards, some of them more than once
+        let mut batch = WriteBatch::default();
+        let many = [hashes[3], hashes[20], hashes[4], hashes[19]];
+        let write_guards = store.set_many_batch(&mut batch, &many, BlockStatus::StatusInvalid).unwrap();
+        assert_eq!(write_guards.len(), 2);
+        db.write(batch).unwrap();
+        drop(write_guards);
+        for hash in many {
+            assert_eq!(store.get(hash).unwrap(), BlockStatus::StatusInvalid);
+        }
+    }
+}
@@ -1,11 +1,15 @@
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
 
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
     errors::StoreError,
     DB,
 };
-use consensus_core::{utxo::utxo_diff::UtxoDiff, BlockHasher};
+use consensus_core::{
+    tx::{TransactionOutpoint, UtxoEntry},
+    utxo::utxo_diff::UtxoDiff,
+    BlockHasher,
+};
 use hashes::Hash;
 use rocksdb::WriteBatch;
 
@@ -32,13 +36,27 @@ pub struct DbUtxoDiffsStore {
     access: CachedDbAccess<Hash, Arc<UtxoDiff>, BlockHasher>,
 }
 
+/// Estimates the memory owned by a cached UTXO diff, see [`CachePolicy::Bytes`]
+fn utxo_diff_heap_size(data: &Arc<UtxoDiff>) -> usize {
+    size_of::<UtxoDiff>()
+        + data
+            .add
+            .values()
+            .chain(data.remove.values())
+            .map(|entry| size_of::<TransactionOutpoint>() + size_of::<UtxoEntry>() + entry.script_public_key.script().len())
+            .sum::<usize>()
+}
+
 impl DbUtxoDiffsStore {
-    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(Arc::clone(&db), cache_size, STORE_PREFIX) }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(Arc::clone(&db), cache_policy, STORE_PREFIX).with_heap_size_estimator(utxo_diff_heap_size),
+        }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
     }
 
     pub fn insert_batch(&self, batch: &mut WriteBatch, hash: Hash, utxo_diff: Arc<UtxoDiff>) -> Result<(), StoreError> {
@@ -1,6 +1,6 @@
 use super::{
-    database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter},
-    errors::{StoreError, StoreResultExtensions},
+    database::prelude::{BatchDbWriter, CachePolicy, CachedDbAccess, DirectDbWriter},
+    errors::{StoreError, StoreResult, StoreResultExtensions},
     DB,
 };
 use consensus_core::{
@@ -12,7 +12,7 @@ use consensus_core::{
 };
 use hashes::Hash;
 use rocksdb::WriteBatch;
-use std::{fmt::Display, sync::Arc};
+use std::{fmt::Display, mem::size_of, sync::Arc};
 
 pub trait UtxoSetStoreReader {
     fn get(&self, outpoint: &TransactionOutpoint) -> Result<Arc<UtxoEntry>, StoreError>;
@@ -70,13 +70,22 @@ pub struct DbUtxoSetStore {
     access: CachedDbAccess<UtxoKey, Arc<UtxoEntry>>,
 }
 
+/// Estimates the memory owned by a cached UTXO entry, see [`CachePolicy::Bytes`]
+fn utxo_entry_heap_size(entry: &Arc<UtxoEntry>) -> usize {
+    size_of::<UtxoEntry>() + entry.script_public_key.script().len()
+}
+
 impl DbUtxoSetStore {
-    pub fn new(db: Arc<DB>, cache_size: u64, prefix: &'static [u8]) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(Arc::clone(&db), cache_size, prefix), prefix }
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy, prefix: &'static [u8]) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(Arc::clone(&db), cache_policy, prefix).with_heap_size_estimator(utxo_entry_heap_size),
+            prefix,
+        }
     }
 
-    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size, self.prefix)
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy, self.prefix)
     }
 
     /// See comment at [`UtxoSetStore::write_diff`]
@@ -86,6 +95,32 @@ impl DbUtxoSetStore {
         self.access.write_many(&mut writer, &mut utxo_diff.added().iter().map(|(o, e)| ((*o).into(), Arc::new(e.clone()))))?;
         Ok(())
     }
+
+    /// Removes all entries of the store
+    pub fn clear(&mut self) -> Result<(), StoreError> {
+        self.access.delete_prefix(DirectDbWriter::new(&self.db), &[])?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` entries in the store key order, starting right after `from_outpoint`, or from the first
+    /// entry if `None`. `from_outpoint` does not need to be in the store anymore.
+    pub fn get_chunk(
+        &self,
+        from_outpoint: Option<TransactionOutpoint>,
+        limit: usize,
+    ) -> StoreResult<Vec<(TransactionOutpoint, UtxoEntry)>> {
+        let seek_from = from_outpoint.map(UtxoKey::from);
+        self.access
+            .seek_iterator(&[], seek_from)
+            .skip_while(|item| matches!((item, seek_from), (Ok((key, _)), Some(from)) if &key[..] == from.as_ref()))
+            .take(limit)
+            .map(|item| {
+                let (key, entry) = item?;
+                let outpoint = UtxoKey(key.as_ref().try_into().expect("expecting UTXO key size")).into();
+                Ok((outpoint, entry.as_ref().clone()))
+            })
+            .collect()
+    }
 }
 
 impl UtxoView for DbUtxoSetStore {
@@ -113,6 +148,7 @@ impl UtxoSetStore for DbUtxoSetStore {
 mod tests {
     use super::*;
     use itertools::Itertools;
+    use std::collections::HashSet;
 
     #[test]
     fn test_utxo_key_conversion() {
@@ -122,4 +158,29 @@ mod tests {
         assert_eq!(outpoint, key.into());
         assert_eq!(key.0.to_vec(), id.as_bytes().iter().copied().chain([44, 1, 0, 0].iter().copied()).collect_vec());
     }
+
+    #[test]
+    fn test_get_chunk() {
+        let (_lifetime, db) = crate::consensus::test_consensus::create_temp_db();
+        let mut store = DbUtxoSetStore::new(db, CachePolicy::Count(10), b"test-utxo-set");
+        let mut diff = UtxoDiff::default();
+        let outpoints = (1..=5).map(|i| TransactionOutpoint::new(i.into(), 0)).collect_vec();
+        for (i, outpoint) in outpoints.iter().enumerate() {
+            diff.add.insert(*outpoint, UtxoEntry::new(i as u64, Default::default(), 0, false));
+        }
+        store.write_diff(&diff).unwrap();
+
+        let first = store.get_chunk(None, 3).unwrap();
+        assert_eq!(first.len(), 3);
+        let rest = store.get_chunk(Some(first[2].0), 3).unwrap();
+        assert_eq!(rest.len(), 2);
+        let chunked: HashSet<_> = first.iter().chain(rest.iter()).map(|(outpoint, _)| *outpoint).collect();
+        assert_eq!(chunked, outpoints.iter().copied().collect());
+
+        // Chunks resume from the position of a removed outpoint
+        let mut diff = UtxoDiff::default();
+        diff.remove.insert(first[2].0, first[2].1.clone());
+        store.write_diff(&diff).unwrap();
+        assert_eq!(store.get_chunk(Some(first[2].0), 3).unwrap(), rest);
+    }
 }
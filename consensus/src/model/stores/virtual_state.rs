@@ -7,7 +7,8 @@ use super::{
     DB,
 };
 use consensus_core::{
-    coinbase::BlockRewardData, tx::TransactionId, utxo::utxo_diff::UtxoDiff, BlockHashMap, BlockHashSet, HashMapCustomHasher,
+    coinbase::BlockRewardData, config::genesis::GenesisBlock, time::TimestampMs, tx::TransactionId, utxo::utxo_diff::UtxoDiff,
+    BlockHashMap, BlockHashSet, HashMapCustomHasher,
 };
 use hashes::Hash;
 use muhash::MuHash;
@@ -20,7 +21,7 @@ pub struct VirtualState {
     pub ghostdag_data: GhostdagData,
     pub daa_score: u64,
     pub bits: u32,
-    pub past_median_time: u64,
+    pub past_median_time: TimestampMs,
     pub multiset: MuHash,
     pub utxo_diff: UtxoDiff,
     pub accepted_tx_ids: Vec<TransactionId>, // TODO: consider saving `accepted_id_merkle_root` directly
@@ -33,7 +34,7 @@ impl VirtualState {
         parents: Vec<Hash>,
         daa_score: u64,
         bits: u32,
-        past_median_time: u64,
+        past_median_time: TimestampMs,
         multiset: MuHash,
         utxo_diff: UtxoDiff,
         accepted_tx_ids: Vec<TransactionId>,
@@ -55,24 +56,18 @@ impl VirtualState {
         }
     }
 
-    pub fn from_genesis(
-        genesis_hash: Hash,
-        genesis_bits: u32,
-        past_median_time: u64,
-        accepted_tx_ids: Vec<TransactionId>,
-        initial_ghostdag_data: GhostdagData,
-    ) -> Self {
+    pub fn from_genesis(genesis: &GenesisBlock, accepted_tx_ids: Vec<TransactionId>, initial_ghostdag_data: GhostdagData) -> Self {
         Self {
-            parents: vec![genesis_hash],
+            parents: vec![genesis.hash],
             ghostdag_data: initial_ghostdag_data,
-            daa_score: 0,
-            bits: genesis_bits,
-            past_median_time,
+            daa_score: genesis.daa_score,
+            bits: genesis.bits,
+            past_median_time: genesis.timestamp,
             multiset: MuHash::new(),
             utxo_diff: UtxoDiff::default(), // Virtual diff is initially empty since genesis receives no reward
             accepted_tx_ids,
             mergeset_rewards: BlockHashMap::new(),
-            mergeset_non_daa: BlockHashSet::from_iter(std::iter::once(genesis_hash)),
+            mergeset_non_daa: BlockHashSet::from_iter(std::iter::once(genesis.hash)),
         }
     }
 }
@@ -0,0 +1,235 @@
+use consensus_core::notify::{BackpressurePolicy, Notification, NotificationConsumer, NotificationType};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender, TrySendError};
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+pub type ConsumerId = u64;
+
+struct Registration {
+    notification_types: Vec<NotificationType>,
+    policy: BackpressurePolicy,
+    sender: Sender<Arc<Notification>>,
+    /// A receiver end kept for evicting the oldest notifications under [`BackpressurePolicy::DropOldest`]
+    receiver: Receiver<Arc<Notification>>,
+    dropped: AtomicU64,
+}
+
+impl Registration {
+    fn send(&self, mut notification: Arc<Notification>) {
+        match self.policy {
+            BackpressurePolicy::Unbounded | BackpressurePolicy::Block(_) => {
+                // The consumer thread only exits once the registration is dropped, so failing is not expected
+                let _ = self.sender.send(notification);
+            }
+            BackpressurePolicy::DropNewest(_) => {
+                if let Err(TrySendError::Full(_)) = self.sender.try_send(notification) {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            BackpressurePolicy::DropOldest(_) => loop {
+                match self.sender.try_send(notification) {
+                    Ok(()) | Err(TrySendError::Disconnected(_)) => break,
+                    Err(TrySendError::Full(returned)) => {
+                        // The consumer might have drained the queue concurrently, in which case nothing is evicted
+                        if self.receiver.try_recv().is_ok() {
+                            self.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        notification = returned;
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// The consensus notification root dispatches consensus notifications to registered consumers. It is the
+/// extension point for external DAG data consumers (indexers, analytics pipelines, archival exporters)
+/// which can attach to a node without patching it.
+///
+/// Every consumer gets its own queue, drained by a dedicated thread, and its own [`BackpressurePolicy`]
+/// deciding what happens when the queue is full.
+#[derive(Default)]
+pub struct ConsensusNotificationRoot {
+    next_id: AtomicU64,
+    registrations: RwLock<HashMap<ConsumerId, Arc<Registration>>>,
+}
+
+impl ConsensusNotificationRoot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `consumer` for notifications of the given types and returns its id
+    ///
+    /// # Panics
+    /// Panics if a bounded policy has a zero capacity
+    pub fn register(
+        &self,
+        notification_types: &[NotificationType],
+        policy: BackpressurePolicy,
+        consumer: Arc<dyn NotificationConsumer>,
+    ) -> ConsumerId {
+        let (sender, receiver) = match policy {
+            BackpressurePolicy::Unbounded => unbounded(),
+            BackpressurePolicy::Block(capacity)
+            | BackpressurePolicy::DropNewest(capacity)
+            | BackpressurePolicy::DropOldest(capacity) => {
+                assert!(capacity > 0, "a bounded backpressure policy requires a positive capacity");
+                bounded(capacity)
+            }
+        };
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let consumer_receiver = receiver.clone();
+        thread::Builder::new()
+            .name(format!("notification-consumer-{}", id))
+            .spawn(move || {
+                while let Ok(notification) = consumer_receiver.recv() {
+                    consumer.consume(notification);
+                }
+            })
+            .unwrap();
+        let registration =
+            Registration { notification_types: notification_types.to_vec(), policy, sender, receiver, dropped: AtomicU64::new(0) };
+        self.registrations.write().insert(id, Arc::new(registration));
+        id
+    }
+
+    /// Unregisters a consumer. Notifications already queued are still delivered before its thread exits.
+    /// Returns `false` if no such consumer is registered
+    pub fn unregister(&self, id: ConsumerId) -> bool {
+        self.registrations.write().remove(&id).is_some()
+    }
+
+    /// Returns the number of notifications dropped for the consumer due to its backpressure policy
+    pub fn dropped_count(&self, id: ConsumerId) -> Option<u64> {
+        self.registrations.read().get(&id).map(|registration| registration.dropped.load(Ordering::Relaxed))
+    }
+
+    /// Returns whether any consumer is registered for notifications of the given type, so that
+    /// notifications costly to build can be skipped
+    pub fn has_consumers(&self, notification_type: NotificationType) -> bool {
+        self.registrations.read().values().any(|registration| registration.notification_types.contains(&notification_type))
+    }
+
+    pub fn notify(&self, notification: Notification) {
+        self.relay(Arc::new(notification));
+    }
+
+    /// Dispatches a notification received from an upstream root, e.g., by a consumer relaying the notifications
+    /// it processed to consumers of its own
+    pub fn relay(&self, notification: Arc<Notification>) {
+        let notification_type = notification.notification_type();
+        // Registrations are collected so that no lock is held while possibly blocking on a full queue
+        let registrations: Vec<Arc<Registration>> = self
+            .registrations
+            .read()
+            .values()
+            .filter(|registration| registration.notification_types.contains(&notification_type))
+            .cloned()
+            .collect();
+        for registration in registrations {
+            registration.send(notification.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::notify::NewBlockTemplateNotification;
+    use crossbeam_channel::Receiver;
+    use std::time::Duration;
+
+    /// A consumer forwarding notifications to a channel, waiting for a release signal before each one
+    struct GatedConsumer {
+        gate: Receiver<()>,
+        sender: Sender<Arc<Notification>>,
+    }
+
+    impl NotificationConsumer for GatedConsumer {
+        fn consume(&self, notification: Arc<Notification>) {
+            self.gate.recv().unwrap();
+            self.sender.send(notification).unwrap();
+        }
+    }
+
+    fn gated_consumer() -> (Arc<GatedConsumer>, Sender<()>, Receiver<Arc<Notification>>) {
+        let (gate_sender, gate) = unbounded();
+        let (sender, receiver) = unbounded();
+        (Arc::new(GatedConsumer { gate, sender }), gate_sender, receiver)
+    }
+
+    fn new_block_template() -> Notification {
+        Notification::NewBlockTemplate(NewBlockTemplateNotification {})
+    }
+
+    #[test]
+    fn test_notification_types_filter() {
+        let root = ConsensusNotificationRoot::new();
+        let (consumer, gate, received) = gated_consumer();
+        let id = root.register(&[NotificationType::BlockAdded], BackpressurePolicy::Unbounded, consumer);
+        gate.send(()).unwrap();
+        // Only block added notifications are delivered to the consumer
+        root.notify(new_block_template());
+        assert!(received.recv_timeout(Duration::from_millis(100)).is_err());
+        assert_eq!(root.dropped_count(id), Some(0));
+        assert!(root.unregister(id));
+        assert!(!root.unregister(id));
+        assert_eq!(root.dropped_count(id), None);
+    }
+
+    #[test]
+    fn test_drop_policies() {
+        for policy in [BackpressurePolicy::DropNewest(2), BackpressurePolicy::DropOldest(2)] {
+            let root = ConsensusNotificationRoot::new();
+            let (consumer, gate, received) = gated_consumer();
+            let id = root.register(&[NotificationType::NewBlockTemplate], policy, consumer);
+
+            // The consumer thread takes the first notification and waits at the gate, two are
+            // then queued and the remaining ones overflow
+            root.notify(new_block_template());
+            thread::sleep(Duration::from_millis(100));
+            for _ in 0..5 {
+                root.notify(new_block_template());
+            }
+            assert_eq!(root.dropped_count(id), Some(3), "policy {:?}", policy);
+
+            for _ in 0..3 {
+                gate.send(()).unwrap();
+            }
+            for _ in 0..3 {
+                received.recv_timeout(Duration::from_secs(1)).unwrap();
+            }
+            assert!(received.recv_timeout(Duration::from_millis(100)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_block_policy() {
+        let root = Arc::new(ConsensusNotificationRoot::new());
+        let (consumer, gate, received) = gated_consumer();
+        let id = root.register(&[NotificationType::NewBlockTemplate], BackpressurePolicy::Block(1), consumer);
+
+        let notifier = root.clone();
+        let handle = thread::spawn(move || {
+            for _ in 0..4 {
+                notifier.notify(new_block_template());
+            }
+        });
+        for _ in 0..4 {
+            gate.send(()).unwrap();
+        }
+        handle.join().unwrap();
+        for _ in 0..4 {
+            received.recv_timeout(Duration::from_secs(1)).unwrap();
+        }
+        assert_eq!(root.dropped_count(id), Some(0));
+    }
+}
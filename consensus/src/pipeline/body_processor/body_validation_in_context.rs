@@ -3,13 +3,15 @@ use crate::{
     errors::{BlockProcessResult, RuleError},
     model::stores::{errors::StoreResultExtensions, ghostdag::GhostdagStoreReader, statuses::StatusesStoreReader},
 };
-use consensus_core::block::Block;
+use consensus_core::{block::Block, BlockHashSet};
 use hashes::Hash;
 use std::sync::Arc;
 
 impl BlockBodyProcessor {
-    pub fn validate_body_in_context(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
-        self.check_parent_bodies_exist(block)?;
+    /// Validates the body of `block` in the context of the DAG, where the blocks of `staged_bodies` are considered
+    /// as having a body although not committed yet
+    pub fn validate_body_in_context(self: &Arc<Self>, block: &Block, staged_bodies: &BlockHashSet) -> BlockProcessResult<()> {
+        self.check_parent_bodies_exist(block, staged_bodies)?;
         self.check_coinbase_blue_score_and_subsidy(block)?;
         self.check_block_transactions_in_context(block)?;
         self.check_block_is_not_pruned(block)
@@ -24,7 +26,7 @@ impl BlockBodyProcessor {
     fn check_block_transactions_in_context(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
         let (pmt, _) = self.past_median_time_manager.calc_past_median_time(&self.ghostdag_store.get_data(block.hash()).unwrap());
         for tx in block.transactions.iter() {
-            if let Err(e) = self.transaction_validator.utxo_free_tx_validation(tx, block.header.daa_score, pmt) {
+            if let Err(e) = self.transaction_validator.utxo_free_tx_validation(tx, block.header.daa_score, pmt.as_millis()) {
                 return Err(RuleError::TxInContextFailed(tx.id(), e));
             }
         }
@@ -32,21 +34,21 @@ impl BlockBodyProcessor {
         Ok(())
     }
 
-    fn check_parent_bodies_exist(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
+    fn check_parent_bodies_exist(self: &Arc<Self>, block: &Block, staged_bodies: &BlockHashSet) -> BlockProcessResult<()> {
         // TODO: Skip this check for blocks in PP anticone that comes as part of the pruning proof.
 
         if block.header.direct_parents().len() == 1 && block.header.direct_parents()[0] == self.genesis_hash {
             return Ok(());
         }
 
-        let statuses_read_guard = self.statuses_store.read();
         let missing: Vec<Hash> = block
             .header
             .direct_parents()
             .iter()
             .cloned()
+            .filter(|parent| !staged_bodies.contains(parent))
             .filter(|parent| {
-                let status_option = statuses_read_guard.get(*parent).unwrap_option();
+                let status_option = self.statuses_store.get(*parent).unwrap_option();
                 status_option.is_none() || !status_option.unwrap().has_block_body()
             })
             .collect();
@@ -82,9 +84,10 @@ mod tests {
 
     use crate::{
         consensus::test_consensus::TestConsensus, constants::TX_VERSION, errors::RuleError,
-        model::stores::ghostdag::GhostdagStoreReader, params::MAINNET_PARAMS, processes::transaction_validator::errors::TxRuleError,
+        model::stores::ghostdag::GhostdagStoreReader, processes::transaction_validator::errors::TxRuleError,
     };
     use consensus_core::{
+        config::params::MAINNET_PARAMS,
         merkle::calc_hash_merkle_root,
         subnets::SUBNETWORK_ID_NATIVE,
         tx::{Transaction, TransactionInput, TransactionOutpoint},
@@ -101,7 +104,7 @@ mod tests {
 
         let body_processor = consensus.block_body_processor();
 
-        consensus.add_block_with_parents(1.into(), vec![params.genesis_hash]).await.unwrap();
+        consensus.add_block_with_parents(1.into(), vec![params.genesis.hash]).await.unwrap();
 
         {
             let block = consensus.build_block_with_parents_and_transactions(2.into(), vec![1.into()], vec![]);
@@ -109,7 +112,7 @@ mod tests {
             assert_match!(body_processor.validate_body_in_context(&block.to_immutable()), Err(RuleError::MissingParents(_)));
         }
 
-        let valid_block = consensus.build_block_with_parents_and_transactions(3.into(), vec![params.genesis_hash], vec![]);
+        let valid_block = consensus.build_block_with_parents_and_transactions(3.into(), vec![params.genesis.hash], vec![]);
         consensus.validate_and_insert_block(valid_block.to_immutable()).await.unwrap();
         {
             let mut block = consensus.build_block_with_parents_and_transactions(2.into(), vec![3.into()], vec![]);
@@ -161,7 +164,7 @@ mod tests {
 
             let valid_block_child_gd = consensus.ghostdag_store().get_data(valid_block_child.header.hash).unwrap();
             let (valid_block_child_gd_pmt, _) = consensus.past_median_time_manager().calc_past_median_time(&valid_block_child_gd);
-            let past_median_time = valid_block_child_gd_pmt + 1;
+            let past_median_time = valid_block_child_gd_pmt.as_millis() + 1;
 
             // Check that the same past median time as the block's or higher fails, but lower passes.
             let tip_daa_score = valid_block_child.header.daa_score + 1;
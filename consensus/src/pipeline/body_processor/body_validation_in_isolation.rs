@@ -1,8 +1,12 @@
 use std::{collections::HashSet, sync::Arc};
 
 use super::BlockBodyProcessor;
-use crate::errors::{BlockProcessResult, RuleError};
+use crate::{
+    errors::{BlockProcessResult, RuleError},
+    processes::mass::Mass,
+};
 use consensus_core::{block::Block, merkle::calc_hash_merkle_root, tx::TransactionOutpoint};
+use rayon::prelude::*;
 
 impl BlockBodyProcessor {
     pub fn validate_body_in_isolation(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
@@ -45,20 +49,25 @@ impl BlockBodyProcessor {
         Ok(())
     }
 
+    /// Transactions are validated independently of each other, hence in parallel on the processor pool, since the
+    /// signature script checks dominate the cost of body validation. Once a transaction fails, the remaining ones
+    /// are skipped and the error of one of the failing transactions is returned
     fn check_transactions_in_isolation(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
-        for tx in block.transactions.iter() {
-            if let Err(e) = self.transaction_validator.validate_tx_in_isolation(tx) {
-                return Err(RuleError::TxInIsolationValidationFailed(tx.id(), e));
-            }
-        }
-        Ok(())
+        self.thread_pool.install(|| {
+            block.transactions.par_iter().try_for_each(|tx| {
+                self.transaction_validator
+                    .validate_tx_in_isolation(tx)
+                    .map_err(|e| RuleError::TxInIsolationValidationFailed(tx.id(), e))
+            })
+        })
     }
 
     fn check_block_mass(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
-        let mut total_mass: u64 = 0;
+        // The compute and storage components are accumulated separately and each is bounded by the block mass limit
+        let mut total_mass = Mass::default();
         for tx in block.transactions.iter() {
             total_mass += self.mass_calculator.calc_tx_mass(tx);
-            if total_mass > self.max_block_mass {
+            if total_mass.combined() > self.max_block_mass {
                 return Err(RuleError::ExceedsMassLimit(self.max_block_mass));
             }
         }
@@ -105,12 +114,14 @@ impl BlockBodyProcessor {
 
 #[cfg(test)]
 mod tests {
-    use crate::{consensus::test_consensus::TestConsensus, errors::RuleError, params::MAINNET_PARAMS};
+    use crate::{consensus::test_consensus::TestConsensus, errors::RuleError};
     use consensus_core::{
         block::MutableBlock,
+        config::params::MAINNET_PARAMS,
         header::Header,
         merkle::calc_hash_merkle_root,
         subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+        time::TimestampMs,
         tx::{scriptvec, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput},
     };
     use hashes::Hash;
@@ -142,7 +153,7 @@ mod tests {
                 ]),
                 Default::default(),
                 Default::default(),
-                0x17305aa654a,
+                TimestampMs::from_millis(0x17305aa654a),
                 0x207fffff,
                 1,
                 0,
@@ -419,6 +430,18 @@ mod tests {
             Err(RuleError::TxInIsolationValidationFailed(_, _))
         );
 
+        // With several invalid transactions, validation stops at one of them
+        let mut block = example_block.clone();
+        let txs = &mut block.transactions;
+        txs[1].inputs = vec![];
+        txs[3].inputs = vec![];
+        block.header.hash_merkle_root = calc_hash_merkle_root(txs.iter());
+        let invalid_ids = [txs[1].id(), txs[3].id()];
+        match body_processor.validate_body_in_isolation(&block.to_immutable()) {
+            Err(RuleError::TxInIsolationValidationFailed(id, _)) => assert!(invalid_ids.contains(&id)),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
         let mut block = example_block;
         let txs = &mut block.transactions;
         txs[3].inputs[0].previous_outpoint = TransactionOutpoint { transaction_id: txs[2].id(), index: 0 };
@@ -434,7 +457,7 @@ mod tests {
         let consensus = TestConsensus::create_from_temp_db(&params);
         let wait_handles = consensus.init();
 
-        let mut block = consensus.build_block_with_parents_and_transactions(1.into(), vec![params.genesis_hash], vec![]);
+        let mut block = consensus.build_block_with_parents_and_transactions(1.into(), vec![params.genesis.hash], vec![]);
         block.transactions[0].version += 1;
 
         assert_match!(consensus.validate_and_insert_block(block.clone().to_immutable()).await, Err(RuleError::BadMerkleRoot(_, _)));
@@ -442,7 +465,7 @@ mod tests {
         // BadMerkleRoot shouldn't mark the block as known invalid
         assert_match!(consensus.validate_and_insert_block(block.to_immutable()).await, Err(RuleError::BadMerkleRoot(_, _)));
 
-        let mut block = consensus.build_block_with_parents_and_transactions(1.into(), vec![params.genesis_hash], vec![]);
+        let mut block = consensus.build_block_with_parents_and_transactions(1.into(), vec![params.genesis.hash], vec![]);
         block.header.parents_by_level[0][0] = 0.into();
 
         assert_match!(consensus.validate_and_insert_block(block.clone().to_immutable()).await, Err(RuleError::MissingParents(_)));
@@ -1,20 +1,23 @@
 use crate::{
-    constants::TX_VERSION,
     errors::{BlockProcessResult, RuleError},
     model::{
         services::reachability::MTReachabilityService,
         stores::{
+            block_counts::DbBlockCountsStore,
             block_transactions::DbBlockTransactionsStore,
             block_window_cache::BlockWindowCacheStore,
             ghostdag::DbGhostdagStore,
             headers::DbHeadersStore,
             reachability::DbReachabilityStore,
-            statuses::{DbStatusesStore, StatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
+            statuses::{ShardedStatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
             tips::DbTipsStore,
             DB,
         },
     },
-    pipeline::deps_manager::{BlockTask, BlockTaskDependencyManager},
+    pipeline::{
+        deps_manager::{BlockTask, BlockTaskDependencyManager},
+        ProcessingCounters,
+    },
     processes::{
         coinbase::CoinbaseManager, mass::MassCalculator, past_median_time::PastMedianTimeManager,
         transaction_validator::TransactionValidator,
@@ -23,15 +26,20 @@ use crate::{
 use consensus_core::{
     block::Block,
     blockstatus::BlockStatus::{self, StatusHeaderOnly, StatusInvalid},
-    subnets::SUBNETWORK_ID_COINBASE,
+    config::{genesis::GenesisBlock, params::Params},
     tx::Transaction,
+    BlockHashSet,
 };
 use crossbeam_channel::{Receiver, Sender};
 use hashes::Hash;
 use parking_lot::RwLock;
-use rayon::ThreadPool;
+use rayon::{prelude::*, ThreadPool};
 use rocksdb::WriteBatch;
-use std::sync::Arc;
+use std::{
+    slice,
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
+};
 
 pub struct BlockBodyProcessor {
     // Channels
@@ -45,15 +53,17 @@ pub struct BlockBodyProcessor {
     db: Arc<DB>,
 
     // Config
-    pub(super) max_block_mass: u64,
+    pub(crate) max_block_mass: u64,
     pub(super) genesis_hash: Hash,
+    genesis: GenesisBlock,
 
     // Stores
-    pub(super) statuses_store: Arc<RwLock<DbStatusesStore>>,
+    pub(super) statuses_store: Arc<ShardedStatusesStore>,
     pub(super) ghostdag_store: Arc<DbGhostdagStore>,
     pub(super) headers_store: Arc<DbHeadersStore>,
     pub(super) block_transactions_store: Arc<DbBlockTransactionsStore>,
     pub(super) body_tips_store: Arc<RwLock<DbTipsStore>>,
+    block_counts_store: Arc<RwLock<DbBlockCountsStore>>,
 
     // Managers and services
     pub(super) reachability_service: MTReachabilityService<DbReachabilityStore>,
@@ -64,6 +74,9 @@ pub struct BlockBodyProcessor {
 
     // Dependency manager
     task_manager: BlockTaskDependencyManager,
+
+    // Counters
+    counters: Arc<ProcessingCounters>,
 }
 
 impl BlockBodyProcessor {
@@ -72,19 +85,20 @@ impl BlockBodyProcessor {
         receiver: Receiver<BlockTask>,
         sender: Sender<BlockTask>,
         thread_pool: Arc<ThreadPool>,
+        params: &Params,
         db: Arc<DB>,
-        statuses_store: Arc<RwLock<DbStatusesStore>>,
+        statuses_store: Arc<ShardedStatusesStore>,
         ghostdag_store: Arc<DbGhostdagStore>,
         headers_store: Arc<DbHeadersStore>,
         block_transactions_store: Arc<DbBlockTransactionsStore>,
         body_tips_store: Arc<RwLock<DbTipsStore>>,
+        block_counts_store: Arc<RwLock<DbBlockCountsStore>>,
         reachability_service: MTReachabilityService<DbReachabilityStore>,
         coinbase_manager: CoinbaseManager,
         mass_calculator: MassCalculator,
         transaction_validator: TransactionValidator,
         past_median_time_manager: PastMedianTimeManager<DbHeadersStore, DbGhostdagStore, BlockWindowCacheStore>,
-        max_block_mass: u64,
-        genesis_hash: Hash,
+        counters: Arc<ProcessingCounters>,
     ) -> Self {
         Self {
             receiver,
@@ -97,13 +111,16 @@ impl BlockBodyProcessor {
             headers_store,
             block_transactions_store,
             body_tips_store,
+            block_counts_store,
             coinbase_manager,
             mass_calculator,
             transaction_validator,
             past_median_time_manager,
-            max_block_mass,
-            genesis_hash,
+            max_block_mass: params.max_block_mass,
+            genesis_hash: params.genesis.hash,
+            genesis: params.genesis.clone(),
             task_manager: BlockTaskDependencyManager::new(),
+            counters,
         }
     }
 
@@ -120,6 +137,15 @@ impl BlockBodyProcessor {
                         });
                     }
                 }
+                BlockTask::ProcessBatch(tasks) => {
+                    let hashes = self.task_manager.register_batch(tasks);
+                    if !hashes.is_empty() {
+                        let processor = self.clone();
+                        self.thread_pool.spawn(move || {
+                            processor.queue_batch(hashes);
+                        });
+                    }
+                }
             };
         }
 
@@ -130,9 +156,21 @@ impl BlockBodyProcessor {
         self.sender.send(BlockTask::Exit).unwrap();
     }
 
+    /// The number of tasks waiting to be received by the worker
+    pub fn queue_len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// The number of tasks the queue holds at most
+    pub fn queue_capacity(&self) -> usize {
+        self.receiver.capacity().unwrap_or(usize::MAX)
+    }
+
     fn queue_block(self: &Arc<BlockBodyProcessor>, hash: Hash) {
         if let Some(block) = self.task_manager.try_begin(hash) {
+            let start = Instant::now();
             let res = self.process_block_body(&block);
+            ProcessingCounters::record_elapsed(&self.counters.body_micros, start);
 
             let dependent_tasks = self.task_manager.end(hash, |block, result_transmitters| {
                 if res.is_err() {
@@ -141,7 +179,11 @@ impl BlockBodyProcessor {
                         let _ = transmitter.send(res.clone());
                     }
                 } else {
-                    self.sender.send(BlockTask::Process(block, result_transmitters)).unwrap();
+                    ProcessingCounters::send_with_backpressure(
+                        &self.sender,
+                        BlockTask::Process(block, result_transmitters),
+                        &self.counters.virtual_backpressure_counts,
+                    );
                 }
             });
 
@@ -152,8 +194,101 @@ impl BlockBodyProcessor {
         }
     }
 
+    /// Processes the bodies of a batch within a single pool task and commits them in a single write batch, then
+    /// forwards the valid blocks to the virtual processor as a single batch. Blocks whose parents are pending
+    /// outside the batch are held back as regular dependent tasks
+    fn queue_batch(self: &Arc<BlockBodyProcessor>, hashes: Vec<Hash>) {
+        let mut began = BlockHashSet::with_capacity(hashes.len());
+        let mut processed = Vec::with_capacity(hashes.len());
+        let mut blocks = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            if let Some(block) = self.task_manager.try_begin_in_group(hash, &began) {
+                began.insert(hash);
+                processed.push(hash);
+                blocks.push(block);
+            }
+        }
+
+        let start = Instant::now();
+        let results = self.process_block_bodies(&blocks);
+        ProcessingCounters::record_elapsed(&self.counters.body_micros, start);
+
+        let dependent_tasks = self.task_manager.end_batch(&processed, |tasks| {
+            let mut virtual_tasks = Vec::with_capacity(tasks.len());
+            for ((block, result_transmitters), res) in tasks.into_iter().zip(results) {
+                if res.is_err() {
+                    for transmitter in result_transmitters {
+                        // We don't care if receivers were dropped
+                        let _ = transmitter.send(res.clone());
+                    }
+                } else {
+                    virtual_tasks.push((block, result_transmitters));
+                }
+            }
+            if !virtual_tasks.is_empty() {
+                ProcessingCounters::send_with_backpressure(
+                    &self.sender,
+                    BlockTask::ProcessBatch(virtual_tasks),
+                    &self.counters.virtual_backpressure_counts,
+                );
+            }
+        });
+
+        for dep in dependent_tasks {
+            let processor = self.clone();
+            self.thread_pool.spawn(move || processor.queue_block(dep));
+        }
+    }
+
     fn process_block_body(self: &Arc<BlockBodyProcessor>, block: &Block) -> BlockProcessResult<BlockStatus> {
-        let status = self.statuses_store.read().get(block.hash()).unwrap();
+        self.process_block_bodies(slice::from_ref(block)).pop().unwrap()
+    }
+
+    /// Processes the bodies of blocks given in topological order, returning a result per block. Blocks are
+    /// validated in isolation in parallel, and the valid blocks are committed together in a single write batch,
+    /// where a block may have parents earlier in the slice
+    fn process_block_bodies(self: &Arc<BlockBodyProcessor>, blocks: &[Block]) -> Vec<BlockProcessResult<BlockStatus>> {
+        let isolation_results: Vec<_> = if blocks.len() > 1 {
+            self.thread_pool.install(|| blocks.par_iter().map(|block| self.validate_body_in_isolation(block)).collect())
+        } else {
+            blocks.iter().map(|block| self.validate_body_in_isolation(block)).collect()
+        };
+
+        let mut staged = BlockHashSet::with_capacity(blocks.len());
+        let mut staged_blocks = Vec::with_capacity(blocks.len());
+        let mut results = Vec::with_capacity(blocks.len());
+        for (block, isolation_result) in blocks.iter().zip(isolation_results) {
+            let status = self.statuses_store.get(block.hash()).unwrap();
+            match status {
+                StatusInvalid => results.push(Err(RuleError::KnownInvalid)),
+                StatusHeaderOnly => {
+                    // Parents staged earlier in the group count as having a body
+                    match isolation_result.and_then(|_| self.validate_body_in_context(block, &staged)) {
+                        Ok(()) => {
+                            staged.insert(block.hash());
+                            staged_blocks.push(block);
+                            results.push(Ok(BlockStatus::StatusUTXOPendingVerification));
+                        }
+                        Err(e) => results.push(Err(self.reject_body(block, e))),
+                    }
+                }
+                _ if status.has_block_body() => results.push(Ok(status)),
+                _ => panic!("unexpected block status {:?}", status),
+            }
+        }
+
+        if !staged_blocks.is_empty() {
+            self.commit_bodies(&staged_blocks);
+            self.counters.body_counts.fetch_add(staged_blocks.len() as u64, Ordering::Relaxed);
+        }
+        results
+    }
+
+    /// Inserts the body of a trusted block, whose header was inserted along with its supplied data. The body is only
+    /// validated in isolation, since its context is the past of the block. Trusted blocks do not become body tips, so
+    /// that virtual resolution does not reach them before the sync completes
+    pub fn process_trusted_body(self: &Arc<BlockBodyProcessor>, block: &Block) -> BlockProcessResult<BlockStatus> {
+        let status = self.statuses_store.get(block.hash()).unwrap();
         match status {
             StatusInvalid => return Err(RuleError::KnownInvalid),
             StatusHeaderOnly => {} // Proceed to body processing
@@ -161,53 +296,113 @@ impl BlockBodyProcessor {
             _ => panic!("unexpected block status {:?}", status),
         }
 
-        if let Err(e) = self.validate_body(block) {
-            // We mark invalid blocks with status StatusInvalid except in the
-            // case of the following errors:
-            // MissingParents - If we got MissingParents the block shouldn't be
-            // considered as invalid because it could be added later on when its
-            // parents are present.
-            // BadMerkleRoot - if we get BadMerkleRoot we shouldn't mark the
-            // block as invalid because later on we can get the block with
-            // transactions that fits the merkle root.
-            // PrunedBlock - PrunedBlock is an error that rejects a block body and
-            // not the block as a whole, so we shouldn't mark it as invalid.
-            // TODO: implement the last part.
-            if !matches!(e, RuleError::BadMerkleRoot(_, _) | RuleError::MissingParents(_)) {
-                self.statuses_store.write().set(block.hash(), BlockStatus::StatusInvalid).unwrap();
-            }
-            return Err(e);
+        if let Err(e) = self.validate_body_in_isolation(block) {
+            return Err(self.reject_body(block, e));
         }
 
-        self.commit_body(block.hash(), block.header.direct_parents(), block.transactions.clone());
+        let mut batch = WriteBatch::default();
+        let payloads_write_guard =
+            self.block_transactions_store.insert_batch(&mut batch, block.hash(), block.transactions.clone()).unwrap();
+        let statuses_write_guard =
+            self.statuses_store.set_batch(&mut batch, block.hash(), BlockStatus::StatusUTXOPendingVerification).unwrap();
+        let mut counts_write_guard = self.block_counts_store.write();
+        counts_write_guard.increment_blocks_batch(&mut batch).unwrap();
+        self.db.write(batch).unwrap();
+        drop(counts_write_guard);
+        drop(statuses_write_guard);
+        drop(payloads_write_guard);
+
+        self.counters.body_counts.fetch_add(1, Ordering::Relaxed);
         Ok(BlockStatus::StatusUTXOPendingVerification)
     }
 
+    /// Marks the block as invalid if the error invalidates it, and returns the error
+    fn reject_body(self: &Arc<BlockBodyProcessor>, block: &Block, e: RuleError) -> RuleError {
+        // We mark invalid blocks with status StatusInvalid except in the
+        // case of the following errors:
+        // MissingParents - If we got MissingParents the block shouldn't be
+        // considered as invalid because it could be added later on when its
+        // parents are present.
+        // BadMerkleRoot - if we get BadMerkleRoot we shouldn't mark the
+        // block as invalid because later on we can get the block with
+        // transactions that fits the merkle root.
+        // PrunedBlock - PrunedBlock is an error that rejects a block body and
+        // not the block as a whole, so we shouldn't mark it as invalid.
+        // TODO: implement the last part.
+        if !matches!(e, RuleError::BadMerkleRoot(_, _) | RuleError::MissingParents(_)) {
+            self.statuses_store.set(block.hash(), BlockStatus::StatusInvalid).unwrap();
+        }
+        e
+    }
+
+    /// Re-runs the body validations of an already processed block. The genesis body is not validated
+    /// upon insertion, hence it is skipped here as well
+    pub fn revalidate_body(self: &Arc<BlockBodyProcessor>, block: &Block) -> BlockProcessResult<()> {
+        if block.hash() == self.genesis_hash {
+            return Ok(());
+        }
+        self.validate_body(block)
+    }
+
     fn validate_body(self: &Arc<BlockBodyProcessor>, block: &Block) -> BlockProcessResult<()> {
         self.validate_body_in_isolation(block)?;
-        self.validate_body_in_context(block)
+        self.validate_body_in_context(block, &BlockHashSet::new())
+    }
+
+    /// Commits the bodies of the blocks in a single write batch, taking each store lock once
+    fn commit_bodies(self: &Arc<BlockBodyProcessor>, blocks: &[&Block]) {
+        let mut batch = WriteBatch::default();
+
+        let payloads_write_guard = self
+            .block_transactions_store
+            .insert_many_batch(&mut batch, blocks.iter().map(|block| (block.hash(), block.transactions.clone())))
+            .unwrap();
+
+        let mut body_tips_write_guard = self.body_tips_store.write();
+        for block in blocks {
+            body_tips_write_guard.add_tip_batch(&mut batch, block.hash(), block.header.direct_parents()).unwrap();
+        }
+        let hashes: Vec<Hash> = blocks.iter().map(|block| block.hash()).collect();
+        let statuses_write_guards =
+            self.statuses_store.set_many_batch(&mut batch, &hashes, BlockStatus::StatusUTXOPendingVerification).unwrap();
+        let mut counts_write_guard = self.block_counts_store.write();
+        for _ in blocks {
+            counts_write_guard.increment_blocks_batch(&mut batch).unwrap();
+        }
+
+        self.db.write(batch).unwrap();
+
+        // Calling the drops explicitly after the batch is written in order to avoid possible errors.
+        drop(counts_write_guard);
+        drop(statuses_write_guards);
+        drop(body_tips_write_guard);
+        drop(payloads_write_guard);
     }
 
     fn commit_body(self: &Arc<BlockBodyProcessor>, hash: Hash, parents: &[Hash], transactions: Arc<Vec<Transaction>>) {
         let mut batch = WriteBatch::default();
 
-        // This is an append only store so it requires no lock.
-        self.block_transactions_store.insert_batch(&mut batch, hash, transactions).unwrap();
+        // This is an append only store, yet its deduplicated payloads are reference counted under a lock
+        let payloads_write_guard = self.block_transactions_store.insert_batch(&mut batch, hash, transactions).unwrap();
 
         let mut body_tips_write_guard = self.body_tips_store.write();
         body_tips_write_guard.add_tip_batch(&mut batch, hash, parents).unwrap();
         let statuses_write_guard =
             self.statuses_store.set_batch(&mut batch, hash, BlockStatus::StatusUTXOPendingVerification).unwrap();
+        let mut counts_write_guard = self.block_counts_store.write();
+        counts_write_guard.increment_blocks_batch(&mut batch).unwrap();
 
         self.db.write(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
+        drop(counts_write_guard);
         drop(statuses_write_guard);
         drop(body_tips_write_guard);
+        drop(payloads_write_guard);
     }
 
     pub fn process_genesis_if_needed(self: &Arc<BlockBodyProcessor>) {
-        let status = self.statuses_store.read().get(self.genesis_hash).unwrap();
+        let status = self.statuses_store.get(self.genesis_hash).unwrap();
         match status {
             StatusHeaderOnly => {
                 let mut batch = WriteBatch::default();
@@ -216,24 +411,7 @@ impl BlockBodyProcessor {
                 self.db.write(batch).unwrap();
                 drop(body_tips_write_guard);
 
-                let genesis_coinbase = Transaction::new(
-                    TX_VERSION,
-                    vec![],
-                    vec![],
-                    0,
-                    SUBNETWORK_ID_COINBASE,
-                    0,
-                    vec![
-                        // Kaspad devnet coinbase payload
-                        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Blue score
-                        0x00, 0xE1, 0xF5, 0x05, 0x00, 0x00, 0x00, 0x00, // Subsidy
-                        0x00, 0x00, // Script version
-                        0x01, // Varint
-                        0x00, // OP-FALSE
-                        0x6b, 0x61, 0x73, 0x70, 0x61, 0x2d, 0x64, 0x65, 0x76, 0x6e, 0x65, 0x74, // kaspa-devnet
-                    ],
-                );
-                self.commit_body(self.genesis_hash, &[], Arc::new(vec![genesis_coinbase]))
+                self.commit_body(self.genesis_hash, &[], Arc::new(self.genesis.build_genesis_transactions()))
             }
             _ if status.has_block_body() => (),
             _ => panic!("unexpected genesis status {:?}", status),
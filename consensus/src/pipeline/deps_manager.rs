@@ -1,15 +1,17 @@
 use crate::errors::BlockProcessResult;
-use consensus_core::{block::Block, blockstatus::BlockStatus, BlockHashMap, HashMapCustomHasher};
+use consensus_core::{block::Block, blockstatus::BlockStatus, BlockHashMap, BlockHashSet, HashMapCustomHasher};
+use futures::channel::oneshot;
 use hashes::Hash;
 use parking_lot::{Condvar, Mutex};
 use std::collections::hash_map::Entry::Vacant;
-use tokio::sync::oneshot;
 
 pub type BlockResultSender = oneshot::Sender<BlockProcessResult<BlockStatus>>;
 
 pub enum BlockTask {
     Exit,
     Process(Block, Vec<BlockResultSender>),
+    /// A batch of blocks in topological order, processed as a group which shares locks and DB write batches
+    ProcessBatch(Vec<(Block, Vec<BlockResultSender>)>),
 }
 
 /// An internal struct used to manage a block processing task
@@ -49,8 +51,29 @@ impl BlockTaskDependencyManager {
     /// result transmitters and the function returns `false` indicating that the task shall
     /// not be queued for processing. The function is expected to be called by a worker
     /// controlling the reception of block processing tasks.
-    pub fn register(&self, block: Block, mut result_transmitters: Vec<BlockResultSender>) -> bool {
+    pub fn register(&self, block: Block, result_transmitters: Vec<BlockResultSender>) -> bool {
+        Self::register_locked(&mut self.pending.lock(), block, result_transmitters)
+    }
+
+    /// Registers a batch of tasks under a single lock, see [`Self::register`]. Returns the hashes of the
+    /// tasks which shall be queued for processing, in the order of the batch
+    pub fn register_batch(&self, tasks: Vec<(Block, Vec<BlockResultSender>)>) -> Vec<Hash> {
         let mut pending = self.pending.lock();
+        let mut registered = Vec::with_capacity(tasks.len());
+        for (block, result_transmitters) in tasks {
+            let hash = block.header.hash;
+            if Self::register_locked(&mut pending, block, result_transmitters) {
+                registered.push(hash);
+            }
+        }
+        registered
+    }
+
+    fn register_locked(
+        pending: &mut BlockHashMap<BlockTaskInternal>,
+        block: Block,
+        mut result_transmitters: Vec<BlockResultSender>,
+    ) -> bool {
         match pending.entry(block.header.hash) {
             Vacant(e) => {
                 e.insert(BlockTaskInternal::new(block, result_transmitters));
@@ -74,11 +97,17 @@ impl BlockTaskDependencyManager {
     /// this hash are in `pending` state, the task is queued as a dependency to the `parent` task
     /// and wil be re-evaluated once that task completes -- in which case the function will return `None`.
     pub fn try_begin(&self, hash: Hash) -> Option<Block> {
+        self.try_begin_in_group(hash, &BlockHashSet::new())
+    }
+
+    /// Like [`Self::try_begin`], where pending parents within `group` do not hold the task back. Used by workers
+    /// processing a group of tasks together, which begin all tasks of the group before ending them at once
+    pub fn try_begin_in_group(&self, hash: Hash, group: &BlockHashSet) -> Option<Block> {
         // Lock the pending map. The contention around the lock is
         // expected to be negligible in header processing time
         let mut pending = self.pending.lock();
         let block = pending.get(&hash).unwrap().block.clone();
-        for parent in block.header.direct_parents().iter() {
+        for parent in block.header.direct_parents().iter().filter(|parent| !group.contains(parent)) {
             if let Some(task) = pending.get_mut(parent) {
                 task.dependent_tasks.push(hash);
                 return None; // The block will be reprocessed once the pending parent completes processing
@@ -109,6 +138,32 @@ impl BlockTaskDependencyManager {
         task.dependent_tasks
     }
 
+    /// Reports the completion of a group of processing tasks, see [`Self::end`]. The `callback` is called once
+    /// with all the blocks and their result transmitters, in the order of `hashes`, and the dependent tasks of
+    /// all the group are returned
+    pub fn end_batch<F>(&self, hashes: &[Hash], callback: F) -> Vec<Hash>
+    where
+        F: FnOnce(Vec<(Block, Vec<BlockResultSender>)>),
+    {
+        let mut pending = self.pending.lock();
+        let mut tasks = Vec::with_capacity(hashes.len());
+        let mut dependent_tasks = Vec::new();
+        for hash in hashes {
+            let mut task = pending.remove(hash).expect("processed block is expected to be in pending map");
+            dependent_tasks.append(&mut task.dependent_tasks);
+            tasks.push((task.block, task.result_transmitters));
+        }
+
+        // Callback within the lock
+        callback(tasks);
+
+        if pending.is_empty() {
+            self.idle_signal.notify_one();
+        }
+
+        dependent_tasks
+    }
+
     /// Wait until all pending tasks are completed and workers are idle.
     pub fn wait_for_idle(&self) {
         let mut pending = self.pending.lock();
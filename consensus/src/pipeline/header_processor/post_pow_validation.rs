@@ -1,8 +1,16 @@
 use super::{HeaderProcessingContext, HeaderProcessor};
-use crate::errors::{BlockProcessResult, RuleError, TwoDimVecDisplay};
-use crate::model::services::reachability::ReachabilityService;
-use consensus_core::header::Header;
+use crate::errors::{BlockProcessResult, MergesetDivergence, RuleError, TwoDimVecDisplay};
+use crate::model::{
+    services::reachability::ReachabilityService,
+    stores::{
+        ghostdag::{GhostdagData, GhostdagStoreReader},
+        headers::HeaderStoreReader,
+    },
+};
+use crate::processes::difficulty::calc_work;
+use consensus_core::{header::Header, BlueWorkType};
 use hashes::Hash;
+use kaspa_core::debug;
 use std::collections::HashSet;
 use std::sync::Arc;
 
@@ -45,22 +53,80 @@ impl HeaderProcessor {
         Ok(())
     }
 
-    fn check_blue_score(self: &Arc<HeaderProcessor>, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
-        let gd_blue_score = ctx.ghostdag_data.as_ref().unwrap().blue_score;
-        if gd_blue_score != header.blue_score {
-            return Err(RuleError::UnexpectedHeaderBlueScore(gd_blue_score, header.blue_score));
+    pub(super) fn check_blue_score(
+        self: &Arc<HeaderProcessor>,
+        ctx: &mut HeaderProcessingContext,
+        header: &Header,
+    ) -> BlockProcessResult<()> {
+        let ghostdag_data = ctx.ghostdag_data.as_ref().unwrap();
+        if ghostdag_data.blue_score != header.blue_score {
+            let divergence = self.mergeset_divergence(header, ghostdag_data, |blue_score, _| blue_score > header.blue_score);
+            return Err(RuleError::UnexpectedHeaderBlueScore(ghostdag_data.blue_score, header.blue_score, divergence));
         }
         Ok(())
     }
 
-    fn check_blue_work(self: &Arc<HeaderProcessor>, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
-        let gd_blue_work = ctx.ghostdag_data.as_ref().unwrap().blue_work;
-        if gd_blue_work != header.blue_work {
-            return Err(RuleError::UnexpectedHeaderBlueWork(gd_blue_work, header.blue_work));
+    pub(super) fn check_blue_work(
+        self: &Arc<HeaderProcessor>,
+        ctx: &mut HeaderProcessingContext,
+        header: &Header,
+    ) -> BlockProcessResult<()> {
+        let ghostdag_data = ctx.ghostdag_data.as_ref().unwrap();
+        if ghostdag_data.blue_work != header.blue_work {
+            let divergence = self.mergeset_divergence(header, ghostdag_data, |_, blue_work| blue_work > header.blue_work);
+            return Err(RuleError::UnexpectedHeaderBlueWork(ghostdag_data.blue_work, header.blue_work, divergence));
         }
         Ok(())
     }
 
+    /// Accumulates the blue score and blue work over the mergeset blues, in GHOSTDAG order, and locates the first
+    /// blue at which `exceeds_header` holds for the accumulated values.
+    ///
+    /// With debug logging enabled, the mergeset blues along with their accumulated values and the mergeset reds
+    /// are dumped, so that they can be compared with those of the node which produced the header.
+    fn mergeset_divergence(
+        self: &Arc<HeaderProcessor>,
+        header: &Header,
+        ghostdag_data: &GhostdagData,
+        exceeds_header: impl Fn(u64, BlueWorkType) -> bool,
+    ) -> MergesetDivergence {
+        let dump = log::log_enabled!(log::Level::Debug);
+        if dump {
+            debug!(
+                "GHOSTDAG mismatch of header {}: declared blue score {} and blue work {}, selected parent {}",
+                header.hash, header.blue_score, header.blue_work, ghostdag_data.selected_parent
+            );
+        }
+
+        let mut blue_score = self.ghostdag_store.get_blue_score(ghostdag_data.selected_parent).unwrap();
+        let mut blue_work = self.ghostdag_store.get_blue_work(ghostdag_data.selected_parent).unwrap();
+        let mut first_divergent_blue = None;
+        for &blue in ghostdag_data.mergeset_blues.iter() {
+            blue_score += 1;
+            blue_work = blue_work + calc_work(self.headers_store.get_bits(blue).unwrap());
+            if first_divergent_blue.is_none() && exceeds_header(blue_score, blue_work) {
+                first_divergent_blue = Some(blue);
+                if !dump {
+                    break;
+                }
+            }
+            if dump {
+                debug!("  blue {}: accumulated blue score {} and blue work {}", blue, blue_score, blue_work);
+            }
+        }
+        if dump {
+            for red in ghostdag_data.mergeset_reds.iter() {
+                debug!("  red {}", red);
+            }
+        }
+
+        MergesetDivergence {
+            first_divergent_blue,
+            computed_blue_score: ghostdag_data.blue_score,
+            computed_blue_work: ghostdag_data.blue_work,
+        }
+    }
+
     pub fn check_indirect_parents(
         self: &Arc<HeaderProcessor>,
         ctx: &mut HeaderProcessingContext,
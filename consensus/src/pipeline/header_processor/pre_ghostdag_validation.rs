@@ -7,10 +7,7 @@ use crate::model::stores::statuses::StatusesStoreReader;
 use consensus_core::blockhash::BlockHashExtensions;
 use consensus_core::blockstatus::BlockStatus::StatusInvalid;
 use consensus_core::header::Header;
-use std::{
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::sync::Arc;
 
 impl HeaderProcessor {
     pub(super) fn pre_ghostdag_validation(
@@ -28,7 +25,7 @@ impl HeaderProcessor {
         Ok(())
     }
 
-    fn validate_header_in_isolation(self: &Arc<HeaderProcessor>, header: &Header) -> BlockProcessResult<()> {
+    pub(super) fn validate_header_in_isolation(self: &Arc<HeaderProcessor>, header: &Header) -> BlockProcessResult<()> {
         if header.hash == self.genesis_hash {
             return Ok(());
         }
@@ -48,8 +45,7 @@ impl HeaderProcessor {
     }
 
     fn check_block_timestamp_in_isolation(self: &Arc<HeaderProcessor>, header: &Header) -> BlockProcessResult<()> {
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
-        let max_block_time = now + self.timestamp_deviation_tolerance * self.target_time_per_block;
+        let max_block_time = self.clock.now() + self.target_time_per_block * self.timestamp_deviation_tolerance;
         if header.timestamp > max_block_time {
             return Err(RuleError::TimeTooFarIntoTheFuture(header.timestamp, max_block_time));
         }
@@ -79,7 +75,7 @@ impl HeaderProcessor {
     fn check_parents_exist(self: &Arc<HeaderProcessor>, header: &Header) -> BlockProcessResult<()> {
         let mut missing_parents = Vec::new();
         for parent in header.direct_parents() {
-            match self.statuses_store.read().get(*parent).unwrap_option() {
+            match self.statuses_store.get(*parent).unwrap_option() {
                 None => missing_parents.push(*parent),
                 Some(StatusInvalid) => {
                     return Err(RuleError::InvalidParent(*parent));
@@ -1,6 +1,7 @@
 use super::*;
 use crate::errors::{BlockProcessResult, RuleError};
 use crate::model::services::reachability::ReachabilityService;
+use crate::processes::traversal_manager::WindowType;
 use consensus_core::header::Header;
 use std::cmp::max;
 use std::sync::Arc;
@@ -36,7 +37,7 @@ impl HeaderProcessor {
         Ok(())
     }
 
-    fn check_pow_and_calc_block_level(
+    pub(super) fn check_pow_and_calc_block_level(
         self: &Arc<HeaderProcessor>,
         ctx: &mut HeaderProcessingContext,
         header: &Header,
@@ -52,17 +53,16 @@ impl HeaderProcessor {
         }
     }
 
-    fn check_difficulty_and_daa_score(
+    pub(super) fn check_difficulty_and_daa_score(
         self: &Arc<HeaderProcessor>,
         ctx: &mut HeaderProcessingContext,
         header: &Header,
     ) -> BlockProcessResult<()> {
         let ghostdag_data = ctx.ghostdag_data.clone().unwrap();
-        let window = self.dag_traversal_manager.block_window(&ghostdag_data, self.difficulty_window_size);
+        let window = self.dag_traversal_manager.window(&ghostdag_data, WindowType::DifficultyWindow);
 
-        let (daa_score, mergeset_non_daa) = self
-            .difficulty_manager
-            .calc_daa_score_and_non_daa_mergeset_blocks(&mut window.iter().map(|item| item.0.hash), &ghostdag_data);
+        let (daa_score, mergeset_non_daa) =
+            self.difficulty_manager.calc_daa_score_and_non_daa_mergeset_blocks(&window, &ghostdag_data);
 
         if daa_score != header.daa_score {
             return Err(RuleError::UnexpectedHeaderDaaScore(daa_score, header.daa_score));
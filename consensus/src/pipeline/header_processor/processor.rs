@@ -3,22 +3,22 @@ use crate::{
     model::{
         services::{reachability::MTReachabilityService, relations::MTRelationsService},
         stores::{
-            block_window_cache::{BlockWindowCacheStore, BlockWindowHeap},
+            block_counts::DbBlockCountsStore,
+            block_window_cache::{BlockWindow, BlockWindowCacheStore},
             daa::DbDaaStore,
             depth::DbDepthStore,
             errors::StoreResultExtensions,
-            ghostdag::{DbGhostdagStore, GhostdagData},
+            ghostdag::{DbGhostdagStore, GhostdagData, GhostdagStoreReader},
             headers::DbHeadersStore,
             headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
             past_pruning_points::DbPastPruningPointsStore,
             pruning::{DbPruningStore, PruningPointInfo, PruningStore, PruningStoreReader},
             reachability::{DbReachabilityStore, StagingReachabilityStore},
             relations::{DbRelationsStore, RelationsStoreBatchExtensions},
-            statuses::{DbStatusesStore, StatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
+            statuses::{ShardedStatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
             DB,
         },
     },
-    params::Params,
     pipeline::deps_manager::{BlockTask, BlockTaskDependencyManager},
     processes::{
         block_depth::BlockDepthManager,
@@ -30,12 +30,13 @@ use crate::{
         reachability::inquirer as reachability,
         traversal_manager::DagTraversalManager,
     },
-    test_helpers::header_from_precomputed_hash,
 };
 use consensus_core::{
     blockhash::{BlockHashes, ORIGIN},
     blockstatus::BlockStatus::{self, StatusHeaderOnly, StatusInvalid},
+    config::{genesis::GenesisBlock, params::Params},
     header::Header,
+    time::{DurationMs, DynClock},
     BlockHashSet,
 };
 use crossbeam_channel::{Receiver, Sender};
@@ -43,7 +44,10 @@ use hashes::Hash;
 use parking_lot::RwLock;
 use rayon::ThreadPool;
 use rocksdb::WriteBatch;
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
+};
 
 use super::super::ProcessingCounters;
 
@@ -54,13 +58,16 @@ pub struct HeaderProcessingContext<'a> {
 
     // Staging data
     pub ghostdag_data: Option<Arc<GhostdagData>>,
-    pub block_window_for_difficulty: Option<BlockWindowHeap>,
-    pub block_window_for_past_median_time: Option<BlockWindowHeap>,
+    pub block_window_for_difficulty: Option<BlockWindow>,
+    pub block_window_for_past_median_time: Option<BlockWindow>,
     pub mergeset_non_daa: Option<BlockHashSet>,
     pub merge_depth_root: Option<Hash>,
     pub finality_point: Option<Hash>,
     pub block_level: Option<u8>,
 
+    /// Whether the block is trusted, i.e., inserted with supplied GHOSTDAG and DAA data while its past may be missing
+    pub is_trusted: bool,
+
     // Cache
     non_pruned_parents: Option<BlockHashes>,
 }
@@ -79,6 +86,7 @@ impl<'a> HeaderProcessingContext<'a> {
             merge_depth_root: None,
             finality_point: None,
             block_level: None,
+            is_trusted: false,
         }
     }
 
@@ -87,7 +95,7 @@ impl<'a> HeaderProcessingContext<'a> {
             return parents;
         }
 
-        let non_pruned_parents = Arc::new(self.header.direct_parents().clone()); // TODO: Exclude pruned parents
+        let non_pruned_parents = Arc::new(self.header.direct_parents().to_vec()); // TODO: Exclude pruned parents
         self.non_pruned_parents = Some(non_pruned_parents.clone());
         non_pruned_parents
     }
@@ -107,15 +115,14 @@ pub struct HeaderProcessor {
 
     // Config
     pub(super) genesis_hash: Hash,
-    pub(super) genesis_timestamp: u64,
-    pub(super) genesis_bits: u32,
+    pub(super) genesis: GenesisBlock,
     pub(super) timestamp_deviation_tolerance: u64,
-    pub(super) target_time_per_block: u64,
+    pub(super) target_time_per_block: DurationMs,
     pub(super) max_block_parents: u8,
-    pub(super) difficulty_window_size: usize,
     pub(super) mergeset_size_limit: u64,
     pub(super) skip_proof_of_work: bool,
     pub(super) max_block_level: u8,
+    pub(super) clock: DynClock,
 
     // DB
     db: Arc<DB>,
@@ -123,8 +130,8 @@ pub struct HeaderProcessor {
     // Stores
     relations_store: Arc<RwLock<DbRelationsStore>>,
     reachability_store: Arc<RwLock<DbReachabilityStore>>,
-    ghostdag_store: Arc<DbGhostdagStore>,
-    pub(super) statuses_store: Arc<RwLock<DbStatusesStore>>,
+    pub(super) ghostdag_store: Arc<DbGhostdagStore>,
+    pub(super) statuses_store: Arc<ShardedStatusesStore>,
     pub(super) pruning_store: Arc<RwLock<DbPruningStore>>,
     pub(super) block_window_cache_for_difficulty: Arc<BlockWindowCacheStore>,
     pub(super) block_window_cache_for_past_median_time: Arc<BlockWindowCacheStore>,
@@ -132,6 +139,7 @@ pub struct HeaderProcessor {
     pub(super) headers_store: Arc<DbHeadersStore>,
     pub(super) headers_selected_tip_store: Arc<RwLock<DbHeadersSelectedTipStore>>,
     depth_store: Arc<DbDepthStore>,
+    block_counts_store: Arc<RwLock<DbBlockCountsStore>>,
 
     // Managers and services
     ghostdag_manager: GhostdagManager<
@@ -140,7 +148,7 @@ pub struct HeaderProcessor {
         MTReachabilityService<DbReachabilityStore>,
         DbHeadersStore,
     >,
-    pub(super) dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore>,
+    pub(super) dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore, DbHeadersStore>,
     pub(super) difficulty_manager: DifficultyManager<DbHeadersStore>,
     pub(super) past_median_time_manager: PastMedianTimeManager<DbHeadersStore, DbGhostdagStore, BlockWindowCacheStore>,
     pub(super) depth_manager: BlockDepthManager<DbDepthStore, DbReachabilityStore, DbGhostdagStore>,
@@ -162,22 +170,24 @@ impl HeaderProcessor {
         body_sender: Sender<BlockTask>,
         thread_pool: Arc<ThreadPool>,
         params: &Params,
+        clock: DynClock,
         db: Arc<DB>,
         relations_store: Arc<RwLock<DbRelationsStore>>,
         reachability_store: Arc<RwLock<DbReachabilityStore>>,
         ghostdag_store: Arc<DbGhostdagStore>,
         headers_store: Arc<DbHeadersStore>,
         daa_store: Arc<DbDaaStore>,
-        statuses_store: Arc<RwLock<DbStatusesStore>>,
+        statuses_store: Arc<ShardedStatusesStore>,
         pruning_store: Arc<RwLock<DbPruningStore>>,
         depth_store: Arc<DbDepthStore>,
         headers_selected_tip_store: Arc<RwLock<DbHeadersSelectedTipStore>>,
+        block_counts_store: Arc<RwLock<DbBlockCountsStore>>,
         block_window_cache_for_difficulty: Arc<BlockWindowCacheStore>,
         block_window_cache_for_past_median_time: Arc<BlockWindowCacheStore>,
         reachability_service: MTReachabilityService<DbReachabilityStore>,
         relations_service: MTRelationsService<DbRelationsStore>,
         past_median_time_manager: PastMedianTimeManager<DbHeadersStore, DbGhostdagStore, BlockWindowCacheStore>,
-        dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore>,
+        dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore, DbHeadersStore>,
         difficulty_manager: DifficultyManager<DbHeadersStore>,
         depth_manager: BlockDepthManager<DbDepthStore, DbReachabilityStore, DbGhostdagStore>,
         pruning_manager: PruningManager<DbGhostdagStore, DbReachabilityStore, DbHeadersStore, DbPastPruningPointsStore>,
@@ -188,9 +198,8 @@ impl HeaderProcessor {
             receiver,
             body_sender,
             thread_pool,
-            genesis_hash: params.genesis_hash,
-            genesis_timestamp: params.genesis_timestamp,
-            difficulty_window_size: params.difficulty_window_size,
+            genesis_hash: params.genesis.hash,
+            genesis: params.genesis.clone(),
             db,
             relations_store,
             reachability_store,
@@ -201,10 +210,11 @@ impl HeaderProcessor {
             headers_store: headers_store.clone(),
             depth_store,
             headers_selected_tip_store,
+            block_counts_store,
             block_window_cache_for_difficulty,
             block_window_cache_for_past_median_time,
             ghostdag_manager: GhostdagManager::new(
-                params.genesis_hash,
+                params.genesis.hash,
                 params.ghostdag_k,
                 ghostdag_store,
                 relations_service,
@@ -224,9 +234,9 @@ impl HeaderProcessor {
             target_time_per_block: params.target_time_per_block,
             max_block_parents: params.max_block_parents,
             mergeset_size_limit: params.mergeset_size_limit,
-            genesis_bits: params.genesis_bits,
             skip_proof_of_work: params.skip_proof_of_work,
             max_block_level: params.max_block_level,
+            clock,
         }
     }
 
@@ -243,6 +253,15 @@ impl HeaderProcessor {
                         });
                     }
                 }
+                BlockTask::ProcessBatch(tasks) => {
+                    let hashes = self.task_manager.register_batch(tasks);
+                    if !hashes.is_empty() {
+                        let processor = self.clone();
+                        self.thread_pool.spawn(move || {
+                            processor.queue_batch(hashes);
+                        });
+                    }
+                }
             };
         }
 
@@ -255,7 +274,9 @@ impl HeaderProcessor {
 
     fn queue_block(self: &Arc<HeaderProcessor>, hash: Hash) {
         if let Some(block) = self.task_manager.try_begin(hash) {
+            let start = Instant::now();
             let res = self.process_header(&block.header);
+            ProcessingCounters::record_elapsed(&self.counters.header_micros, start);
 
             let dependent_tasks = self.task_manager.end(hash, |block, result_transmitters| {
                 if res.is_err() || block.is_header_only() {
@@ -264,7 +285,13 @@ impl HeaderProcessor {
                         let _ = transmitter.send(res.clone());
                     }
                 } else {
-                    self.body_sender.send(BlockTask::Process(block, result_transmitters)).unwrap();
+                    // Waiting within the task manager lock holds back the worker as well, so that the backpressure
+                    // propagates to the submitters
+                    ProcessingCounters::send_with_backpressure(
+                        &self.body_sender,
+                        BlockTask::Process(block, result_transmitters),
+                        &self.counters.body_backpressure_counts,
+                    );
                 }
             });
 
@@ -275,12 +302,57 @@ impl HeaderProcessor {
         }
     }
 
+    /// Processes the headers of a batch sequentially within a single pool task, and forwards the blocks with a body
+    /// to the body processor as a single batch. Headers whose parents are pending outside the batch are held back
+    /// as regular dependent tasks
+    fn queue_batch(self: &Arc<HeaderProcessor>, hashes: Vec<Hash>) {
+        let mut began = BlockHashSet::with_capacity(hashes.len());
+        let mut processed = Vec::with_capacity(hashes.len());
+        let mut results = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            if let Some(block) = self.task_manager.try_begin_in_group(hash, &began) {
+                let start = Instant::now();
+                results.push(self.process_header(&block.header));
+                ProcessingCounters::record_elapsed(&self.counters.header_micros, start);
+                began.insert(hash);
+                processed.push(hash);
+            }
+        }
+
+        let dependent_tasks = self.task_manager.end_batch(&processed, |tasks| {
+            let mut body_tasks = Vec::with_capacity(tasks.len());
+            for ((block, result_transmitters), res) in tasks.into_iter().zip(results) {
+                if res.is_err() || block.is_header_only() {
+                    for transmitter in result_transmitters {
+                        // We don't care if receivers were dropped
+                        let _ = transmitter.send(res.clone());
+                    }
+                } else {
+                    body_tasks.push((block, result_transmitters));
+                }
+            }
+            if !body_tasks.is_empty() {
+                // See `queue_block` for waiting within the task manager lock
+                ProcessingCounters::send_with_backpressure(
+                    &self.body_sender,
+                    BlockTask::ProcessBatch(body_tasks),
+                    &self.counters.body_backpressure_counts,
+                );
+            }
+        });
+
+        for dep in dependent_tasks {
+            let processor = self.clone();
+            self.thread_pool.spawn(move || processor.queue_block(dep));
+        }
+    }
+
     fn header_was_processed(self: &Arc<HeaderProcessor>, hash: Hash) -> bool {
-        self.statuses_store.read().has(hash).unwrap()
+        self.statuses_store.has(hash).unwrap()
     }
 
     fn process_header(self: &Arc<HeaderProcessor>, header: &Arc<Header>) -> BlockProcessResult<BlockStatus> {
-        let status_option = self.statuses_store.read().get(header.hash).unwrap_option();
+        let status_option = self.statuses_store.get(header.hash).unwrap_option();
 
         match status_option {
             Some(StatusInvalid) => return Err(RuleError::KnownInvalid),
@@ -296,7 +368,7 @@ impl HeaderProcessor {
         ctx.ghostdag_data = Some(Arc::new(self.ghostdag_manager.ghostdag(header.direct_parents()))); // TODO: Run GHOSTDAG for all block levels
         self.pre_pow_validation(&mut ctx, header)?;
         if let Err(e) = self.post_pow_validation(&mut ctx, header) {
-            self.statuses_store.write().set(ctx.hash, StatusInvalid).unwrap();
+            self.statuses_store.set(ctx.hash, StatusInvalid).unwrap();
             return Err(e);
         }
 
@@ -308,6 +380,71 @@ impl HeaderProcessor {
         Ok(StatusHeaderOnly)
     }
 
+    /// Inserts the header of a trusted block along with its supplied GHOSTDAG and DAA data. Only the validations which
+    /// do not depend on the past of the block are run, and the merge depth root and finality point are left unset
+    pub fn process_trusted_header(
+        self: &Arc<HeaderProcessor>,
+        header: &Arc<Header>,
+        ghostdag_data: Arc<GhostdagData>,
+        mergeset_non_daa: BlockHashSet,
+    ) -> BlockProcessResult<BlockStatus> {
+        match self.statuses_store.get(header.hash).unwrap_option() {
+            Some(StatusInvalid) => return Err(RuleError::KnownInvalid),
+            Some(status) => return Ok(status),
+            None => {}
+        }
+
+        let mut ctx = HeaderProcessingContext::new(header.hash, header, self.pruning_store.read().get().unwrap());
+        ctx.is_trusted = true;
+        self.validate_header_in_isolation(header)?;
+        self.check_pow_and_calc_block_level(&mut ctx, header)?;
+        // The supplied data cannot be verified without the past of the block, but it must at least agree with the header
+        if ghostdag_data.blue_score != header.blue_score || ghostdag_data.blue_work != header.blue_work {
+            return Err(RuleError::TrustedGhostdagDataMismatch(header.hash, ghostdag_data.blue_score, ghostdag_data.blue_work));
+        }
+        ctx.ghostdag_data = Some(ghostdag_data);
+        ctx.mergeset_non_daa = Some(mergeset_non_daa);
+        ctx.merge_depth_root = Some(ORIGIN);
+        ctx.finality_point = Some(ORIGIN);
+
+        self.commit_header(ctx, header);
+
+        self.counters.header_counts.fetch_add(1, Ordering::Relaxed);
+        Ok(StatusHeaderOnly)
+    }
+
+    /// Re-runs the validations of an already processed header, and recomputes its GHOSTDAG data in order to compare
+    /// it with the stored data. Validations which depend on the current pruning state (pruning violation, bounded
+    /// merge depth, header pruning point and indirect parents) are skipped, since they might legitimately fail for
+    /// headers processed before the pruning point advanced.
+    pub fn revalidate_header(self: &Arc<HeaderProcessor>, header: &Arc<Header>) -> BlockProcessResult<()> {
+        if header.hash == self.genesis_hash {
+            return Ok(());
+        }
+
+        let mut ctx = HeaderProcessingContext::new(header.hash, header, self.pruning_store.read().get().unwrap());
+        self.pre_ghostdag_validation(&mut ctx, header)?;
+
+        let ghostdag_data = Arc::new(self.ghostdag_manager.ghostdag(header.direct_parents()));
+        match self.ghostdag_store.get_data(header.hash).unwrap_option() {
+            Some(stored)
+                if stored.blue_score == ghostdag_data.blue_score
+                    && stored.blue_work == ghostdag_data.blue_work
+                    && stored.selected_parent == ghostdag_data.selected_parent
+                    && stored.mergeset_blues == ghostdag_data.mergeset_blues
+                    && stored.mergeset_reds == ghostdag_data.mergeset_reds => {}
+            _ => return Err(RuleError::StoredGhostdagDataMismatch(header.hash)),
+        }
+        ctx.ghostdag_data = Some(ghostdag_data);
+
+        self.check_pow_and_calc_block_level(&mut ctx, header)?;
+        self.check_difficulty_and_daa_score(&mut ctx, header)?;
+        self.check_blue_score(&mut ctx, header)?;
+        self.check_blue_work(&mut ctx, header)?;
+        self.check_median_timestamp(&mut ctx, header)?;
+        self.check_merge_size_limit(&mut ctx)
+    }
+
     fn commit_header(self: &Arc<HeaderProcessor>, ctx: HeaderProcessingContext, header: &Arc<Header>) {
         let ghostdag_data = ctx.ghostdag_data.unwrap();
 
@@ -316,8 +453,13 @@ impl HeaderProcessor {
 
         // Write to append only stores: this requires no lock and hence done first
         self.ghostdag_store.insert_batch(&mut batch, ctx.hash, &ghostdag_data).unwrap();
-        self.block_window_cache_for_difficulty.insert(ctx.hash, Arc::new(ctx.block_window_for_difficulty.unwrap()));
-        self.block_window_cache_for_past_median_time.insert(ctx.hash, Arc::new(ctx.block_window_for_past_median_time.unwrap()));
+        // Windows are not computed for trusted blocks, and are computed on demand should they be needed
+        if let Some(window) = ctx.block_window_for_difficulty {
+            self.block_window_cache_for_difficulty.insert(ctx.hash, Arc::new(window));
+        }
+        if let Some(window) = ctx.block_window_for_past_median_time {
+            self.block_window_cache_for_past_median_time.insert(ctx.hash, Arc::new(window));
+        }
         self.daa_store.insert_batch(&mut batch, ctx.hash, Arc::new(ctx.mergeset_non_daa.unwrap())).unwrap();
         self.headers_store.insert_batch(&mut batch, ctx.hash, ctx.header.clone(), ctx.block_level.unwrap()).unwrap();
         self.depth_store.insert_batch(&mut batch, ctx.hash, ctx.merge_depth_root.unwrap(), ctx.finality_point.unwrap()).unwrap();
@@ -328,14 +470,29 @@ impl HeaderProcessor {
         // alternative is to create a separate ReachabilityProcessor and to manage things more tightly.
         let mut staging = StagingReachabilityStore::new(self.reachability_store.upgradable_read());
 
-        // Add block to staging reachability
-        reachability::add_block(
-            &mut staging,
-            ctx.hash,
-            ghostdag_data.selected_parent,
-            &mut ghostdag_data.unordered_mergeset_without_selected_parent(),
-        )
-        .unwrap();
+        // Add block to staging reachability. The parents of trusted blocks may be in their pruned past, in which case
+        // the block is linked to the known part of its mergeset, or else to the origin
+        let parents: Vec<Hash> = if ctx.is_trusted {
+            let is_known = |hash: &Hash| self.statuses_store.has(*hash).unwrap();
+            let selected_parent = Some(ghostdag_data.selected_parent).filter(is_known).unwrap_or(ORIGIN);
+            reachability::add_block(
+                &mut staging,
+                ctx.hash,
+                selected_parent,
+                &mut ghostdag_data.unordered_mergeset_without_selected_parent().filter(is_known),
+            )
+            .unwrap();
+            header.direct_parents().iter().copied().filter(is_known).collect()
+        } else {
+            reachability::add_block(
+                &mut staging,
+                ctx.hash,
+                ghostdag_data.selected_parent,
+                &mut ghostdag_data.unordered_mergeset_without_selected_parent(),
+            )
+            .unwrap();
+            header.direct_parents().to_vec()
+        };
 
         // Non-append only stores need to use write locks.
         // Note we need to keep the lock write guards until the batch is written.
@@ -347,14 +504,17 @@ impl HeaderProcessor {
             hst_write_guard.set_batch(&mut batch, SortableBlock::new(ctx.hash, header.blue_work)).unwrap();
         }
 
-        let relations_write_guard = if header.direct_parents().is_empty() {
+        let relations_write_guard = if parents.is_empty() {
             self.relations_store.insert_batch(&mut batch, header.hash, BlockHashes::new(vec![ORIGIN])).unwrap()
         } else {
-            self.relations_store.insert_batch(&mut batch, header.hash, BlockHashes::new(header.direct_parents().clone())).unwrap()
+            self.relations_store.insert_batch(&mut batch, header.hash, BlockHashes::new(parents)).unwrap()
         };
 
         let statuses_write_guard = self.statuses_store.set_batch(&mut batch, ctx.hash, StatusHeaderOnly).unwrap();
 
+        let mut counts_write_guard = self.block_counts_store.write();
+        counts_write_guard.increment_headers_batch(&mut batch).unwrap();
+
         // Write reachability data. Only at this brief moment the reachability store is locked for reads.
         // We take special care for this since reachability read queries are used throughout the system frequently.
         // Note we hold the lock until the batch is written
@@ -365,6 +525,7 @@ impl HeaderProcessor {
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(reachability_write_guard);
+        drop(counts_write_guard);
         drop(statuses_write_guard);
         drop(relations_write_guard);
         drop(hst_write_guard);
@@ -386,10 +547,7 @@ impl HeaderProcessor {
         }
 
         self.pruning_store.write().set(self.genesis_hash, self.genesis_hash, 0).unwrap();
-        let mut header = header_from_precomputed_hash(self.genesis_hash, vec![]); // TODO
-        header.bits = self.genesis_bits;
-        header.timestamp = self.genesis_timestamp;
-        let header = Arc::new(header);
+        let header = Arc::new(Header::from(&self.genesis));
         let mut ctx = HeaderProcessingContext::new(self.genesis_hash, &header, PruningPointInfo::from_genesis(self.genesis_hash));
         ctx.ghostdag_data = Some(Arc::new(self.ghostdag_manager.genesis_ghostdag_data()));
         ctx.block_window_for_difficulty = Some(Default::default());
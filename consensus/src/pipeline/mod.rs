@@ -1,15 +1,47 @@
 pub mod body_processor;
 pub mod deps_manager;
 pub mod header_processor;
+pub mod pruning_processor;
 pub mod virtual_processor;
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use crossbeam_channel::{Sender, TrySendError};
+use deps_manager::BlockTask;
+use futures_util::future::{ready, Either, Ready};
+use parking_lot::{Mutex, MutexGuard};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+#[cfg(feature = "service")]
+use tokio::{
+    runtime::Handle,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+
+// Minimal builds run no tokio runtime, so their tasks never hold a permit of the overflow queue
+#[cfg(not(feature = "service"))]
+enum OwnedSemaphorePermit {}
 
 #[derive(Default)]
 pub struct ProcessingCounters {
     pub blocks_submitted: AtomicU64,
     pub header_counts: AtomicU64,
     pub dep_counts: AtomicU64,
+    pub body_counts: AtomicU64,
+    pub virtual_counts: AtomicU64,
+    // The cumulative processing time of each pipeline stage, in microseconds
+    pub header_micros: AtomicU64,
+    pub body_micros: AtomicU64,
+    pub virtual_micros: AtomicU64,
+    // The number of blocks sent to each pipeline stage while its input queue was full, which had to wait
+    pub header_backpressure_counts: AtomicU64,
+    pub body_backpressure_counts: AtomicU64,
+    pub virtual_backpressure_counts: AtomicU64,
     // pub max_pending_headers: AtomicU64,
     // pub avg_pending_headers: AtomicU64,
 }
@@ -20,16 +52,316 @@ impl ProcessingCounters {
             blocks_submitted: self.blocks_submitted.load(Ordering::SeqCst),
             header_counts: self.header_counts.load(Ordering::SeqCst),
             dep_counts: self.dep_counts.load(Ordering::SeqCst),
+            body_counts: self.body_counts.load(Ordering::SeqCst),
+            virtual_counts: self.virtual_counts.load(Ordering::SeqCst),
+            header_micros: self.header_micros.load(Ordering::SeqCst),
+            body_micros: self.body_micros.load(Ordering::SeqCst),
+            virtual_micros: self.virtual_micros.load(Ordering::SeqCst),
+            header_backpressure_counts: self.header_backpressure_counts.load(Ordering::SeqCst),
+            body_backpressure_counts: self.body_backpressure_counts.load(Ordering::SeqCst),
+            virtual_backpressure_counts: self.virtual_backpressure_counts.load(Ordering::SeqCst),
             // max_pending_headers: self.max_pending_headers.load(Ordering::SeqCst),
             // avg_pending_headers: self.avg_pending_headers.load(Ordering::SeqCst),
         }
     }
+
+    /// Adds the time elapsed since `start` to a cumulative processing time counter
+    pub(crate) fn record_elapsed(counter: &AtomicU64, start: Instant) {
+        counter.fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Sends a task to the bounded input queue of a pipeline stage, waiting for room if the queue is full, in which
+    /// case the wait is counted by `backpressure_counter`
+    pub(crate) fn send_with_backpressure(sender: &Sender<BlockTask>, task: BlockTask, backpressure_counter: &AtomicU64) {
+        match sender.try_send(task) {
+            Err(TrySendError::Full(task)) => {
+                backpressure_counter.fetch_add(1, Ordering::Relaxed);
+                sender.send(task).unwrap();
+            }
+            result => result.unwrap(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct OverflowQueue {
+    // Tasks submitted from a tokio runtime hold a permit of the overflow queue until they are sent
+    tasks: VecDeque<(BlockTask, Option<OwnedSemaphorePermit>)>,
+    // Whether a drainer is moving the tasks to the header queue, in which case new tasks must queue behind them
+    draining: bool,
+    // The number of async submitters waiting for room in the overflow queue, which new tasks must queue behind as well
+    waiting: usize,
+}
+
+impl OverflowQueue {
+    fn is_idle(&self) -> bool {
+        !self.draining && self.waiting == 0
+    }
+}
+
+// Counts an async submitter as waiting for room in the overflow queue for as long as it lives
+#[cfg(feature = "service")]
+struct WaitingSubmitter(Arc<Mutex<OverflowQueue>>);
+
+#[cfg(feature = "service")]
+impl Drop for WaitingSubmitter {
+    fn drop(&mut self) {
+        self.0.lock().waiting -= 1;
+    }
+}
+
+/// Submits tasks to the input queue of the header processor without blocking async callers.
+///
+/// While the header queue is full, tasks are held in an overflow queue in submission order, which is drained into the
+/// header queue by a blocking task of the tokio runtime of the caller. The overflow queue holds at most as many tasks
+/// of async callers as the header queue does: once it is full as well, async callers wait for room in it before waiting
+/// for the results of their blocks. Callers outside of a tokio runtime drain the overflow queue themselves, blocking as
+/// [`ProcessingCounters::send_with_backpressure`] does.
+pub(crate) struct BlockTaskSubmitter {
+    sender: Sender<BlockTask>,
+    overflow: Arc<Mutex<OverflowQueue>>,
+    // The room left in the overflow queue for the tasks of async callers
+    #[cfg(feature = "service")]
+    permits: Arc<Semaphore>,
+}
+
+impl BlockTaskSubmitter {
+    pub fn new(sender: Sender<BlockTask>) -> Self {
+        // An unbounded header queue never overflows, so its overflow queue is given a single permit
+        #[cfg(feature = "service")]
+        let permits = Arc::new(Semaphore::new(sender.capacity().unwrap_or(0).clamp(1, Semaphore::MAX_PERMITS)));
+        Self {
+            sender,
+            overflow: Default::default(),
+            #[cfg(feature = "service")]
+            permits,
+        }
+    }
+
+    /// The number of submitted tasks not yet handled by the header processor, including the overflowing ones
+    pub fn len(&self) -> usize {
+        self.sender.len() + self.overflow.lock().tasks.len()
+    }
+
+    /// The capacity of the header queue
+    pub fn capacity(&self) -> usize {
+        self.sender.capacity().unwrap_or(usize::MAX)
+    }
+
+    /// Submits a task, keeping the submission order. A task overflowing the header queue is counted by
+    /// `backpressure_counter`. The returned future resolves once the task is queued, which async callers must await
+    /// while the overflow queue is full. Callers outside of a tokio runtime are blocked until the task is queued instead
+    pub fn submit(&self, task: BlockTask, backpressure_counter: &AtomicU64) -> impl Future<Output = ()> {
+        let overflow = self.overflow.lock();
+        match self.try_send(&overflow, task, backpressure_counter) {
+            Some(task) => Either::Right(self.overflow(overflow, task)),
+            None => Either::Left(ready(())),
+        }
+    }
+
+    /// Submits the exit task behind all submitted tasks. Unlike [`Self::submit`], it never waits for room in the
+    /// overflow queue, so that sync callers on a tokio runtime can submit it as well
+    pub fn submit_exit(&self, backpressure_counter: &AtomicU64) {
+        let overflow = self.overflow.lock();
+        if let Some(task) = self.try_send(&overflow, BlockTask::Exit, backpressure_counter) {
+            self.push_and_drain(overflow, task, None);
+        }
+    }
+
+    /// Sends a task to the header queue unless it is full or earlier tasks are overflowing it, in which case the task is
+    /// counted by `backpressure_counter` and returned
+    fn try_send(&self, overflow: &OverflowQueue, task: BlockTask, backpressure_counter: &AtomicU64) -> Option<BlockTask> {
+        let task = if overflow.is_idle() {
+            match self.sender.try_send(task) {
+                Err(TrySendError::Full(task)) => task,
+                result => {
+                    result.unwrap();
+                    return None;
+                }
+            }
+        } else {
+            task
+        };
+        backpressure_counter.fetch_add(1, Ordering::Relaxed);
+        Some(task)
+    }
+
+    /// Adds an overflowing task to the overflow queue. On a tokio runtime, the returned future waits for room in the
+    /// overflow queue first if it is full
+    #[cfg(feature = "service")]
+    fn overflow(&self, mut overflow: MutexGuard<OverflowQueue>, task: BlockTask) -> Either<Ready<()>, impl Future<Output = ()>> {
+        if Handle::try_current().is_err() {
+            self.push_and_drain(overflow, task, None);
+            return Either::Left(ready(()));
+        }
+        if overflow.waiting == 0 {
+            if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+                self.push_and_drain(overflow, task, Some(permit));
+                return Either::Left(ready(()));
+            }
+        }
+
+        // The overflow queue is full as well, so the task waits for room in it
+        overflow.waiting += 1;
+        drop(overflow);
+        let waiting = WaitingSubmitter(self.overflow.clone());
+        let (sender, overflow, permits) = (self.sender.clone(), self.overflow.clone(), self.permits.clone());
+        Either::Right(async move {
+            let permit = permits.acquire_owned().await.unwrap();
+            if Self::push(overflow.lock(), task, Some(permit)) {
+                Self::start_draining(&sender, &overflow);
+            }
+            drop(waiting);
+        })
+    }
+
+    /// Adds an overflowing task to the overflow queue
+    #[cfg(not(feature = "service"))]
+    fn overflow(&self, overflow: MutexGuard<OverflowQueue>, task: BlockTask) -> Ready<()> {
+        self.push_and_drain(overflow, task, None);
+        ready(())
+    }
+
+    fn push_and_drain(&self, overflow: MutexGuard<OverflowQueue>, task: BlockTask, permit: Option<OwnedSemaphorePermit>) {
+        if Self::push(overflow, task, permit) {
+            Self::start_draining(&self.sender, &self.overflow);
+        }
+    }
+
+    /// Adds a task to the overflow queue. Returns whether the caller must start draining it, since no drainer is running
+    fn push(mut overflow: MutexGuard<OverflowQueue>, task: BlockTask, permit: Option<OwnedSemaphorePermit>) -> bool {
+        overflow.tasks.push_back((task, permit));
+        !std::mem::replace(&mut overflow.draining, true)
+    }
+
+    /// Drains the overflow queue by a blocking task of the current tokio runtime, or by the calling thread outside of one
+    fn start_draining(sender: &Sender<BlockTask>, overflow: &Arc<Mutex<OverflowQueue>>) {
+        #[cfg(feature = "service")]
+        if let Ok(handle) = Handle::try_current() {
+            let (sender, overflow) = (sender.clone(), overflow.clone());
+            handle.spawn_blocking(move || Self::drain(&sender, &overflow));
+            return;
+        }
+        Self::drain(sender, overflow)
+    }
+
+    fn drain(sender: &Sender<BlockTask>, overflow: &Mutex<OverflowQueue>) {
+        loop {
+            // The drainer stops only once its last task was sent, so that no later task overtakes it
+            let (task, _permit) = {
+                let mut overflow = overflow.lock();
+                match overflow.tasks.pop_front() {
+                    Some(task) => task,
+                    None => {
+                        overflow.draining = false;
+                        return;
+                    }
+                }
+            };
+            // The header processor might have exited meanwhile, in which case the task is dropped along with the
+            // tasks left in the header queue. The permit of the task is released once it is sent
+            let _ = sender.send(task);
+        }
+    }
+}
+
+/// The number of tasks waiting in the input queue of each pipeline stage, or the capacities of these queues
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PipelineQueueLengths {
+    pub header: usize,
+    pub body: usize,
+    pub virtual_state: usize,
 }
 
 pub struct ProcessingCountersSnapshot {
     pub blocks_submitted: u64,
     pub header_counts: u64,
     pub dep_counts: u64,
+    pub body_counts: u64,
+    pub virtual_counts: u64,
+    pub header_micros: u64,
+    pub body_micros: u64,
+    pub virtual_micros: u64,
+    pub header_backpressure_counts: u64,
+    pub body_backpressure_counts: u64,
+    pub virtual_backpressure_counts: u64,
     // pub max_pending_headers: u64,
     // pub avg_pending_headers: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::bounded;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn test_send_with_backpressure() {
+        let counters = ProcessingCounters::default();
+        let (sender, receiver) = bounded(1);
+        ProcessingCounters::send_with_backpressure(&sender, BlockTask::Exit, &counters.body_backpressure_counts);
+        assert_eq!(counters.body_backpressure_counts.load(Ordering::SeqCst), 0);
+
+        // The queue is full, so the next send waits for the receiver
+        let consumer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            (0..2).filter_map(|_| receiver.recv().ok()).count()
+        });
+        ProcessingCounters::send_with_backpressure(&sender, BlockTask::Exit, &counters.body_backpressure_counts);
+        assert_eq!(consumer.join().unwrap(), 2);
+        assert_eq!(counters.body_backpressure_counts.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "service")]
+    #[test]
+    fn test_block_task_submitter() {
+        use consensus_core::{block::Block, header::Header};
+        use futures_util::future::join_all;
+
+        let counters = ProcessingCounters::default();
+        let (sender, receiver) = bounded(1);
+        let submitter = BlockTaskSubmitter::new(sender);
+        let block = |nonce: u64| {
+            let header = Header::new(
+                0,
+                vec![],
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                0,
+                nonce,
+                0,
+                Default::default(),
+                0,
+                Default::default(),
+            );
+            BlockTask::Process(Block::from_header(header), vec![])
+        };
+
+        // Submitting to a full queue from a single threaded runtime must not block the runtime
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let submissions: Vec<_> =
+                (0..4).map(|nonce| submitter.submit(block(nonce), &counters.header_backpressure_counts)).collect();
+            assert_eq!(counters.header_backpressure_counts.load(Ordering::SeqCst), 3);
+
+            // The overflow queue holds no more tasks than the header queue, so the last two wait for room in it
+            assert_eq!(submitter.len(), 2);
+            let consumer = thread::spawn(move || {
+                (0..4)
+                    .map(|_| match receiver.recv_timeout(Duration::from_secs(10)).unwrap() {
+                        BlockTask::Process(block, _) => block.header.nonce,
+                        _ => panic!("unexpected task"),
+                    })
+                    .collect::<Vec<_>>()
+            });
+            join_all(submissions).await;
+
+            // The overflowing tasks reach the header queue in submission order
+            assert_eq!(consumer.join().unwrap(), vec![0, 1, 2, 3]);
+        });
+        drop(runtime);
+        assert_eq!(submitter.len(), 0);
+    }
+}
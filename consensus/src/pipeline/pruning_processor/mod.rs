@@ -0,0 +1,4 @@
+mod processor;
+mod retention;
+pub use processor::*;
+pub use retention::*;
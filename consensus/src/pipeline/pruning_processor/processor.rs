@@ -0,0 +1,430 @@
+use super::{RetentionPolicies, RetentionPolicy};
+use crate::model::{
+    services::reachability::{MTReachabilityService, ReachabilityService},
+    stores::{
+        acceptance_data::DbAcceptanceDataStore,
+        block_transactions::DbBlockTransactionsStore,
+        errors::StoreResultExtensions,
+        ghostdag::{DbGhostdagStore, GhostdagStoreReader},
+        headers::{DbHeadersStore, HeaderStoreReader},
+        past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStoreReader},
+        pinned_blocks::{DbPinnedBlocksStore, PinnedBlocksStoreReader},
+        pruning::{DbPruningStore, PruningStoreReader},
+        reachability::DbReachabilityStore,
+        retention_root::{DbRetentionRootStore, RetentionRootStore, RetentionRootStoreReader},
+        DB,
+    },
+};
+use consensus_core::{
+    config::params::Params,
+    time::{DurationMs, TimestampMs},
+    BlockHashSet,
+};
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use hashes::Hash;
+use kaspa_core::info;
+use parking_lot::RwLock;
+use rocksdb::WriteBatch;
+use std::{sync::Arc, time::Duration};
+
+/// The interval between garbage collection rounds
+const PRUNING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of chain blocks the retention root advances by per round, so that a node catching up on a
+/// long backlog (e.g. when the retention period was shortened) collects it incrementally
+const MAX_STEPS_PER_ROUND: usize = 256;
+
+/// A store garbage collected by the pruning processor according to its [`RetentionPolicy`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PrunedStore {
+    BlockTransactions,
+    AcceptanceData,
+    Headers,
+    CompactHeaders,
+}
+
+/// Garbage collects the block data below the retention root, which is advanced in the background along the selected
+/// chain of the pruning point as long as its successor is older than the pruning point by at least the retention
+/// period. The data kept by each store below the root is determined by its [`RetentionPolicy`], see
+/// [`RetentionPolicies`]. GHOSTDAG and reachability data are kept.
+pub struct PruningProcessor {
+    // Channels
+    exit_receiver: Receiver<()>,
+
+    // DB
+    db: Arc<DB>,
+
+    // Config
+    genesis_hash: Hash,
+    retention_policies: RetentionPolicies,
+
+    // Stores
+    ghostdag_store: Arc<DbGhostdagStore>,
+    headers_store: Arc<DbHeadersStore>,
+    block_transactions_store: Arc<DbBlockTransactionsStore>,
+    acceptance_data_store: Arc<DbAcceptanceDataStore>,
+    pruning_store: Arc<RwLock<DbPruningStore>>,
+    past_pruning_points_store: Arc<DbPastPruningPointsStore>,
+    pinned_blocks_store: Arc<RwLock<DbPinnedBlocksStore>>,
+    retention_root_store: Arc<RwLock<DbRetentionRootStore>>,
+
+    // Services
+    reachability_service: MTReachabilityService<DbReachabilityStore>,
+}
+
+impl PruningProcessor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        exit_receiver: Receiver<()>,
+        params: &Params,
+        db: Arc<DB>,
+        ghostdag_store: Arc<DbGhostdagStore>,
+        headers_store: Arc<DbHeadersStore>,
+        block_transactions_store: Arc<DbBlockTransactionsStore>,
+        acceptance_data_store: Arc<DbAcceptanceDataStore>,
+        pruning_store: Arc<RwLock<DbPruningStore>>,
+        past_pruning_points_store: Arc<DbPastPruningPointsStore>,
+        pinned_blocks_store: Arc<RwLock<DbPinnedBlocksStore>>,
+        retention_root_store: Arc<RwLock<DbRetentionRootStore>>,
+        reachability_service: MTReachabilityService<DbReachabilityStore>,
+    ) -> Self {
+        Self {
+            exit_receiver,
+            db,
+            genesis_hash: params.genesis.hash,
+            retention_policies: RetentionPolicies::new(params),
+            ghostdag_store,
+            headers_store,
+            block_transactions_store,
+            acceptance_data_store,
+            pruning_store,
+            past_pruning_points_store,
+            pinned_blocks_store,
+            retention_root_store,
+            reachability_service,
+        }
+    }
+
+    pub fn worker(self: &Arc<PruningProcessor>, retention_period: DurationMs) {
+        loop {
+            match self.exit_receiver.recv_timeout(PRUNING_INTERVAL) {
+                Err(RecvTimeoutError::Timeout) => {
+                    let pruning_point = self.pruning_store.read().pruning_point().unwrap();
+                    let cutoff = self.headers_store.get_timestamp(pruning_point).unwrap().saturating_sub(retention_period);
+                    let steps = self.advance_retention_root(pruning_point, cutoff, MAX_STEPS_PER_ROUND);
+                    if steps > 0 {
+                        info!("Garbage collected the block data below retention root {}", self.retention_root());
+                    }
+                }
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    /// Returns the retention root, which is genesis as long as no data was collected
+    pub fn retention_root(&self) -> Hash {
+        self.retention_root_store.read().get().unwrap_option().unwrap_or(self.genesis_hash)
+    }
+
+    /// Advances the retention root along the selected chain of `target` by up to `max_steps` chain blocks, stopping
+    /// below the first chain block with a timestamp above `cutoff`, and returns the number of steps taken. `target`
+    /// must be in the selected chain of the pruning point
+    pub fn advance_retention_root(&self, target: Hash, cutoff: TimestampMs, max_steps: usize) -> usize {
+        let root = self.retention_root();
+        let pruning_points = self.past_pruning_points();
+        let stores = self.pruned_stores();
+        // For each store, the highest chain block whose mergeset data was collected
+        let mut collected: Vec<Hash> = stores.iter().map(|(_, policy)| self.collected_chain_block(root, policy.depth)).collect();
+        let mut steps = 0;
+        for next in self.reachability_service.forward_chain_iterator(root, target, true).skip(1).take(max_steps) {
+            if self.headers_store.get_timestamp(next).unwrap() > cutoff {
+                break;
+            }
+            let next_blue_score = self.ghostdag_store.get_blue_score(next).unwrap();
+            let pinned_blocks = self.pinned_blocks_store.read().get().unwrap();
+            for ((store, policy), collected) in stores.iter().zip(collected.iter_mut()) {
+                for chain_block in self.reachability_service.forward_chain_iterator(*collected, next, true).skip(1) {
+                    if self.ghostdag_store.get_blue_score(chain_block).unwrap() + policy.depth > next_blue_score {
+                        break;
+                    }
+                    let ghostdag_data = self.ghostdag_store.get_data(chain_block).unwrap();
+                    for hash in ghostdag_data.unordered_mergeset() {
+                        let is_kept = pinned_blocks.contains(&hash) || (policy.keep_pruning_points && pruning_points.contains(&hash));
+                        if !is_kept {
+                            self.delete_block_data(*store, hash);
+                        }
+                    }
+                    *collected = chain_block;
+                }
+            }
+            // The root is only advanced once the data below it is deleted, so that an interrupted step is repeated
+            self.retention_root_store.write().set(next).unwrap();
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Sets the retention root of a node which imported `pruning_point` from a peer, since it holds no block data below it
+    pub fn init_imported_retention_root(&self, pruning_point: Hash) {
+        self.retention_root_store.write().set(pruning_point).unwrap();
+    }
+
+    /// Returns the lowest chain block of `pruning_point` whose header is kept once the retention root reaches the pruning
+    /// point. The headers of the blocks merged by the chain blocks above it are kept as well, and cover the difficulty
+    /// and past median time windows of the blocks above the pruning point
+    pub fn windows_root(&self, pruning_point: Hash) -> Hash {
+        self.collected_chain_block(pruning_point, self.retention_policies.headers.depth)
+    }
+
+    fn pruned_stores(&self) -> [(PrunedStore, RetentionPolicy); 4] {
+        let policies = self.retention_policies;
+        [
+            (PrunedStore::BlockTransactions, policies.block_transactions),
+            (PrunedStore::AcceptanceData, policies.acceptance_data),
+            (PrunedStore::Headers, policies.headers),
+            (PrunedStore::CompactHeaders, policies.compact_headers),
+        ]
+    }
+
+    /// Returns the highest chain block of `root` which is at least `depth` blue score below it, i.e., the chain block
+    /// up to which the mergeset data of a store with such a retention depth was collected
+    fn collected_chain_block(&self, root: Hash, depth: u64) -> Hash {
+        let root_blue_score = self.ghostdag_store.get_blue_score(root).unwrap();
+        self.reachability_service
+            .default_backward_chain_iterator(root)
+            .find(|&chain_block| self.ghostdag_store.get_blue_score(chain_block).unwrap() + depth <= root_blue_score)
+            .unwrap_or(self.genesis_hash)
+    }
+
+    fn past_pruning_points(&self) -> BlockHashSet {
+        let index = self.pruning_store.read().pruning_point_index().unwrap();
+        (0..=index).map(|index| self.past_pruning_points_store.get(index).unwrap()).collect()
+    }
+
+    /// Deletes the data of the block from the store. Each deletion is written in its own batch, since the deletion
+    /// of a body releases payload references which are read back from the payloads store
+    fn delete_block_data(&self, store: PrunedStore, hash: Hash) {
+        let mut batch = WriteBatch::default();
+        match store {
+            PrunedStore::BlockTransactions => {
+                if self.block_transactions_store.has(hash).unwrap() {
+                    let payloads_write_guard = self.block_transactions_store.delete_batch(&mut batch, hash).unwrap();
+                    self.db.write(batch).unwrap();
+                    drop(payloads_write_guard);
+                }
+                return;
+            }
+            PrunedStore::AcceptanceData => self.acceptance_data_store.delete_batch(&mut batch, hash).unwrap(),
+            PrunedStore::Headers => self.headers_store.delete_batch(&mut batch, hash).unwrap(),
+            PrunedStore::CompactHeaders => self.headers_store.delete_compact_batch(&mut batch, hash).unwrap(),
+        }
+        self.db.write(batch).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        consensus::{
+            test_consensus::{create_temp_db, TestConsensus},
+            Consensus,
+        },
+        model::stores::{
+            acceptance_data::AcceptanceDataStoreReader,
+            block_transactions::BlockTransactionsStoreReader,
+            database::prelude::CachePolicy,
+            metadata::{DbMetadataStore, MetadataStore},
+            utxo_diffs::{DbUtxoDiffsStore, UtxoDiffsStoreReader},
+        },
+    };
+    use consensus_core::{
+        api::ConsensusApi, blockstatus::BlockStatus, coinbase::MinerData, config::params::MAINNET_PARAMS,
+        errors::sync::SyncManagerError, tx::ScriptPublicKey,
+    };
+
+    #[tokio::test]
+    async fn test_retention_root_advance() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let (_lifetime, db) = create_temp_db();
+        let consensus = TestConsensus::new(db, &params);
+        let wait_handles = consensus.init();
+        let mut parents = vec![params.genesis.hash];
+        for i in 1..=10u64 {
+            let block = consensus.build_block_with_parents_and_transactions(i.into(), parents, vec![]);
+            consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            parents = vec![i.into()];
+        }
+        consensus.consensus().as_ref().pin_blocks(&[Hash::from(2u64)]).unwrap();
+
+        let processor = consensus.consensus().pruning_processor.clone();
+        assert_eq!(processor.retention_root(), params.genesis.hash);
+        // No chain block is older than the cutoff
+        assert_eq!(processor.advance_retention_root(Hash::from(5u64), TimestampMs::from_millis(0), 10), 0);
+        // The root advances by at most the number of steps, and up to the target
+        let cutoff = TimestampMs::from_millis(u64::MAX);
+        assert_eq!(processor.advance_retention_root(Hash::from(5u64), cutoff, 2), 2);
+        assert_eq!(processor.retention_root(), Hash::from(2u64));
+        assert_eq!(processor.advance_retention_root(Hash::from(5u64), cutoff, 10), 3);
+        assert_eq!(processor.retention_root(), Hash::from(5u64));
+
+        for hash in [params.genesis.hash, Hash::from(1u64), Hash::from(3u64), Hash::from(4u64)] {
+            assert!(processor.block_transactions_store.get(hash).is_err());
+            assert!(processor.acceptance_data_store.get(hash).is_err());
+        }
+        // Pinned blocks and the blocks from the root upwards keep their data
+        for i in [2u64, 5, 6, 10] {
+            assert!(processor.block_transactions_store.get(Hash::from(i)).is_ok());
+            assert!(processor.acceptance_data_store.get(Hash::from(i)).is_ok());
+        }
+
+        let api = consensus.consensus().clone();
+        assert!(matches!(
+            api.clone().get_chain_blocks_with_acceptance(Hash::from(3u64), None, 10),
+            Err(SyncManagerError::DataPruned(_))
+        ));
+        assert_eq!(api.clone().get_chain_blocks_with_acceptance(Hash::from(5u64), None, 10).unwrap().blocks.len(), 5);
+        assert!(api.clone().get_virtual_chain_from_block(Hash::from(3u64), false, 10, 100).is_ok());
+        assert!(api.get_virtual_chain_from_block(Hash::from(3u64), true, 10, 100).is_err());
+        consensus.shutdown(wait_handles);
+    }
+
+    #[tokio::test]
+    async fn test_pruning_cycle() {
+        let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+        params.finality_depth = 10;
+        params.merge_depth = 5;
+        params.pruning_depth = 25;
+        params.difficulty_window_size = 8;
+        params.timestamp_deviation_tolerance = 3;
+        params.sampling_activation_daa_score = 30;
+        params.difficulty_sample_rate = 2;
+        params.past_median_time_sample_rate = 2;
+        let consensus = TestConsensus::create_from_temp_db(&params);
+        let wait_handles = consensus.init();
+        // A node which never collects data, validating the blocks built by the pruned one
+        let reference = TestConsensus::create_from_temp_db(&params);
+        let reference_wait_handles = reference.init();
+
+        let mut tip = params.genesis.hash;
+        let mut add_chain_blocks = |range: std::ops::RangeInclusive<u64>| {
+            range
+                .map(|i| {
+                    let block = consensus.build_block_with_parents_and_transactions(i.into(), vec![tip], vec![]).to_immutable();
+                    tip = i.into();
+                    block
+                })
+                .collect::<Vec<_>>()
+        };
+        for block in add_chain_blocks(1..=100) {
+            consensus.validate_and_insert_block(block.clone()).await.unwrap();
+            reference.validate_and_insert_block(block).await.unwrap();
+        }
+
+        let api = consensus.consensus().clone();
+        let pruning_point = api.clone().get_pruning_point();
+        let processor = consensus.consensus().pruning_processor.clone();
+        let pruning_points = processor.past_pruning_points();
+        assert!(pruning_points.len() > 1, "the pruning point should have moved");
+        assert!(processor.advance_retention_root(pruning_point, TimestampMs::from_millis(u64::MAX), usize::MAX) > 0);
+        assert_eq!(processor.retention_root(), pruning_point);
+
+        // Along a chain, the blue score of a block is its height, and its data is collected along with its chain child
+        let root_blue_score = processor.ghostdag_store.get_blue_score(pruning_point).unwrap();
+        let window_depth = processor.retention_policies.compact_headers.depth;
+        for i in 0..=100u64 {
+            let hash = if i == 0 { params.genesis.hash } else { Hash::from(i) };
+            let is_pruning_point = pruning_points.contains(&hash);
+            assert_eq!(processor.block_transactions_store.get(hash).is_ok(), i >= root_blue_score, "body of block {}", i);
+            assert_eq!(
+                processor.headers_store.get_header(hash).is_ok(),
+                i + 1 + window_depth > root_blue_score || is_pruning_point,
+                "header of block {}",
+                i
+            );
+            assert_eq!(
+                processor.headers_store.get_compact_header_data(hash).is_ok(),
+                i + 1 + window_depth > root_blue_score || is_pruning_point,
+                "compact header data of block {}",
+                i
+            );
+        }
+
+        // The difficulty and past median time windows of a child of the root are still available
+        consensus.build_header_with_parents(1000.into(), vec![pruning_point]);
+
+        // Blocks built on top of the collected data are valid for a node which kept all data
+        for block in add_chain_blocks(101..=140) {
+            consensus.validate_and_insert_block(block.clone()).await.unwrap();
+            assert_eq!(reference.validate_and_insert_block(block).await.unwrap(), BlockStatus::StatusUTXOValid);
+        }
+
+        // The headers of the blocks merged below the retention root are not served
+        assert!(matches!(api.clone().get_hashes_between(Hash::from(3u64), tip, 10), Err(SyncManagerError::DataPruned(_))));
+        assert!(api.get_hashes_between(pruning_point, tip, 10).is_ok());
+
+        consensus.shutdown(wait_handles);
+        reference.shutdown(reference_wait_handles);
+    }
+
+    #[tokio::test]
+    async fn test_archival_mode() {
+        let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+        params.finality_depth = 10;
+        params.merge_depth = 5;
+        params.pruning_depth = 25;
+        params.difficulty_window_size = 8;
+        params.timestamp_deviation_tolerance = 3;
+        params.sampling_activation_daa_score = 30;
+        params.difficulty_sample_rate = 2;
+        params.past_median_time_sample_rate = 2;
+        // Both nodes collect all data below the pruning point, unless archival
+        let (_archival_lifetime, archival_db) = create_temp_db();
+        DbMetadataStore::new(archival_db.clone()).set_archival(true).unwrap();
+        let archival = Arc::new(Consensus::new(archival_db.clone(), &params).with_retention_period(DurationMs::from_millis(0)));
+        let archival_wait_handles = archival.init();
+        let (_pruned_lifetime, pruned_db) = create_temp_db();
+        let pruned = Arc::new(Consensus::new(pruned_db, &params).with_retention_period(DurationMs::from_millis(0)));
+        let pruned_wait_handles = pruned.init();
+        assert!(archival.clone().is_archival());
+        assert!(!pruned.clone().is_archival());
+
+        // Blocks are built out of templates, so that the chain blocks are UTXO valid and have UTXO diffs
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![1; 32]), vec![]);
+        let mut chain = vec![];
+        for _ in 0..100 {
+            let block = archival.as_ref().build_block_template(miner_data.clone(), vec![]).unwrap().block.to_immutable();
+            assert_eq!(archival.as_ref().validate_and_insert_block(block.clone()).await.unwrap(), BlockStatus::StatusUTXOValid);
+            assert_eq!(pruned.as_ref().validate_and_insert_block(block.clone()).await.unwrap(), BlockStatus::StatusUTXOValid);
+            chain.push(block.hash());
+        }
+        let pruning_point = pruned.clone().get_pruning_point();
+        assert_eq!(archival.clone().get_pruning_point(), pruning_point);
+        assert_eq!(archival.clone().get_history_root(), params.genesis.hash);
+        assert_eq!(pruned.clone().get_history_root(), pruning_point);
+
+        // Only the non archival node runs the pruning processor
+        for _ in 0..100 {
+            if pruned.pruning_processor.retention_root() == pruning_point {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(pruned.pruning_processor.retention_root(), pruning_point);
+        assert_eq!(archival.pruning_processor.retention_root(), params.genesis.hash);
+
+        let archival_utxo_diffs_store = DbUtxoDiffsStore::new(archival_db, CachePolicy::Count(0));
+        let below_pruning_point = &chain[..chain.iter().position(|&hash| hash == pruning_point).unwrap()];
+        assert!(!below_pruning_point.is_empty());
+        for &hash in below_pruning_point {
+            assert!(pruned.pruning_processor.block_transactions_store.get(hash).is_err());
+            assert!(pruned.pruning_processor.acceptance_data_store.get(hash).is_err());
+            assert!(archival.pruning_processor.block_transactions_store.get(hash).is_ok());
+            assert!(archival.pruning_processor.acceptance_data_store.get(hash).is_ok());
+            assert!(archival_utxo_diffs_store.get(hash).is_ok());
+        }
+
+        archival.shutdown(archival_wait_handles);
+        pruned.shutdown(pruned_wait_handles);
+    }
+}
@@ -0,0 +1,75 @@
+use consensus_core::config::params::{Params, WindowParams};
+
+/// Compensates for red blocks, whose blue score lags behind the blue score of the blocks of similar blue work, when
+/// bounding the blue score span of a window by its number of blocks
+const WINDOW_DEPTH_MARGIN: u64 = 2;
+
+/// Determines which data of a store the pruning processor keeps once the retention root advanced past it.
+///
+/// The data of a block is garbage collected along with the mergeset of the selected chain block merging it, once that
+/// chain block is at least `depth` blue score below the retention root. Pinned blocks are always kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// The blue score depth below the retention root down to which data is kept
+    pub depth: u64,
+    /// Whether the data of past pruning points, which make up the pruning proof, is kept regardless of depth
+    pub keep_pruning_points: bool,
+}
+
+impl RetentionPolicy {
+    /// Keeps the data of the retention root and above only
+    pub const BELOW_ROOT: Self = Self { depth: 0, keep_pruning_points: false };
+
+    /// Keeps the data of the retention root and above, along with the data of past pruning points
+    pub const PRUNING_PROOF: Self = Self { depth: 0, keep_pruning_points: true };
+
+    /// Keeps the data read while building the difficulty and past median time windows of the blocks above the
+    /// retention root, whether the windows are sampled or not. The data of past pruning points is kept as well, since
+    /// the headers above the retention root refer to them
+    pub fn windows(params: &Params) -> Self {
+        let span = |window: WindowParams| (window.size as u64).max(window.sampled_size as u64 * window.sample_rate);
+        let depth = span(params.difficulty_window()).max(span(params.past_median_time_window())) * WINDOW_DEPTH_MARGIN;
+        Self { depth, keep_pruning_points: true }
+    }
+}
+
+/// The retention policies of the stores garbage collected by the pruning processor. Stores which are not listed,
+/// e.g., GHOSTDAG, reachability and relations, keep the data of all blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetentionPolicies {
+    pub block_transactions: RetentionPolicy,
+    pub acceptance_data: RetentionPolicy,
+    /// Full headers are served to peers, both as part of the pruning proof and as the windows of the blocks above the
+    /// pruning point, which are sent to nodes syncing from the pruning point
+    pub headers: RetentionPolicy,
+    /// The compact header data, i.e., the DAA score, timestamp and bits, of the blocks within window reach
+    pub compact_headers: RetentionPolicy,
+}
+
+impl RetentionPolicies {
+    pub fn new(params: &Params) -> Self {
+        Self {
+            block_transactions: RetentionPolicy::BELOW_ROOT,
+            acceptance_data: RetentionPolicy::BELOW_ROOT,
+            headers: RetentionPolicy::windows(params),
+            compact_headers: RetentionPolicy::windows(params),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::config::params::MAINNET_PARAMS;
+
+    #[test]
+    fn test_window_policy_covers_windows() {
+        let params = &MAINNET_PARAMS;
+        let policy = RetentionPolicy::windows(params);
+        for window in [params.difficulty_window(), params.past_median_time_window()] {
+            assert!(policy.depth >= window.size as u64);
+            assert!(policy.depth >= window.sampled_size as u64 * window.sample_rate);
+        }
+        assert!(policy.keep_pruning_points);
+    }
+}
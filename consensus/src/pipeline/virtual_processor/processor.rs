@@ -1,54 +1,78 @@
 use crate::{
-    consensus::{DbGhostdagManager, VirtualStores},
+    consensus::{DbGhostdagManager, PruningUtxosetStores, VirtualStores},
     constants::BLOCK_VERSION,
-    errors::RuleError,
+    errors::{BlockProcessResult, RuleError},
     model::{
         services::{
             reachability::{MTReachabilityService, ReachabilityService},
             relations::MTRelationsService,
         },
         stores::{
-            acceptance_data::{AcceptanceData, DbAcceptanceDataStore},
+            acceptance_data::{AcceptanceData, AcceptanceDataStoreReader, DbAcceptanceDataStore},
             block_transactions::{BlockTransactionsStoreReader, DbBlockTransactionsStore},
             block_window_cache::BlockWindowCacheStore,
             daa::DbDaaStore,
             depth::DbDepthStore,
-            errors::StoreError,
+            errors::{StoreError, StoreResultExtensions},
             ghostdag::{DbGhostdagStore, GhostdagData, GhostdagStoreReader},
             headers::{DbHeadersStore, HeaderStoreReader},
             past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStore, PastPruningPointsStoreReader},
             pruning::{DbPruningStore, PruningStore, PruningStoreReader},
+            pruning_utxoset_position::{PruningUtxosetPositionStore, PruningUtxosetPositionStoreReader},
             reachability::DbReachabilityStore,
             relations::{DbRelationsStore, RelationsStoreReader},
-            statuses::{DbStatusesStore, StatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
+            statuses::{ShardedStatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
             tips::{DbTipsStore, TipsStoreReader},
             utxo_diffs::{DbUtxoDiffsStore, UtxoDiffsStoreReader},
             utxo_multisets::{DbUtxoMultisetsStore, UtxoMultisetsStoreReader},
+            utxo_set::{DbUtxoSetStore, UtxoSetStore},
             virtual_state::{VirtualState, VirtualStateStore, VirtualStateStoreReader},
             DB,
         },
     },
-    params::Params,
-    pipeline::{deps_manager::BlockTask, virtual_processor::utxo_validation::UtxoProcessingContext},
+    notify::root::ConsensusNotificationRoot,
+    pipeline::{
+        deps_manager::{BlockResultSender, BlockTask},
+        virtual_processor::utxo_validation::UtxoProcessingContext,
+        ProcessingCounters,
+    },
     processes::{
         block_depth::BlockDepthManager,
         coinbase::CoinbaseManager,
         difficulty::DifficultyManager,
         ghostdag::ordering::SortableBlock,
+        mass::MassCalculator,
         parents_builder::ParentsManager,
         past_median_time::PastMedianTimeManager,
         pruning::PruningManager,
-        transaction_validator::{errors::TxResult, TransactionValidator},
-        traversal_manager::DagTraversalManager,
+        transaction_validator::{
+            errors::{TxResult, TxRuleError},
+            TransactionValidator,
+        },
+        traversal_manager::{DagTraversalManager, WindowType},
     },
 };
 use consensus_core::{
-    block::{BlockTemplate, MutableBlock},
+    api::session::SessionLock,
+    block::{Block, BlockTemplate, MutableBlock},
     blockstatus::BlockStatus::{self, StatusDisqualifiedFromChain, StatusUTXOPendingVerification, StatusUTXOValid},
-    coinbase::MinerData,
+    chain::{ChainBlockAcceptanceData, ChainBlockWithAcceptance, ChainBlocksBatch, VirtualChainBatch},
+    coinbase::{CoinbasePreview, MinerData},
+    config::{genesis::GenesisBlock, params::Params},
+    errors::{
+        coinbase::CoinbaseResult,
+        sync::{SyncManagerError, SyncManagerResult},
+    },
     header::Header,
-    merkle::calc_hash_merkle_root,
-    tx::{MutableTransaction, Transaction},
+    merkle::{calc_accepted_id_merkle_root, calc_hash_merkle_root},
+    muhash::MuHashExtensions,
+    notify::{
+        BlockAddedNotification, Notification, NotificationType, TransactionAcceptance, TransactionsAcceptedNotification,
+        TransactionsRevertedNotification, UtxosChangedNotification, VirtualChainChangedNotification,
+    },
+    stats::{ChainThroughputStats, DaaScoreDrift},
+    time::{DurationMs, DynClock, TimestampMs},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
     utxo::{
         utxo_diff::UtxoDiff,
         utxo_view::{UtxoView, UtxoViewComposition},
@@ -66,12 +90,15 @@ use rayon::ThreadPool;
 use rocksdb::WriteBatch;
 use std::{
     cmp::{min, Reverse},
-    collections::VecDeque,
+    collections::{HashSet, VecDeque},
     ops::Deref,
-    sync::Arc,
-    time::SystemTime,
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
 };
 
+/// The number of entries read at once when importing the pruning point UTXO set
+const UTXO_SET_IMPORT_CHUNK_SIZE: usize = 1000;
+
 pub struct VirtualStateProcessor {
     // Channels
     receiver: Receiver<BlockTask>,
@@ -84,15 +111,16 @@ pub struct VirtualStateProcessor {
 
     // Config
     pub(super) genesis_hash: Hash,
-    pub(super) genesis_bits: u32,
-    pub(super) genesis_timestamp: u64,
+    pub(super) genesis: GenesisBlock,
     pub(super) max_block_parents: u8,
-    pub(super) difficulty_window_size: usize,
     pub(super) mergeset_size_limit: u64,
     pub(super) pruning_depth: u64,
+    pub(super) target_time_per_block: DurationMs,
+    pub(super) max_block_mass: u64,
+    pub(super) clock: DynClock,
 
     // Stores
-    pub(super) statuses_store: Arc<RwLock<DbStatusesStore>>,
+    pub(super) statuses_store: Arc<ShardedStatusesStore>,
     pub(super) ghostdag_store: Arc<DbGhostdagStore>,
     pub(super) headers_store: Arc<DbHeadersStore>,
     pub(super) daa_store: Arc<DbDaaStore>,
@@ -106,20 +134,31 @@ pub struct VirtualStateProcessor {
     pub(super) utxo_multisets_store: Arc<DbUtxoMultisetsStore>,
     pub(super) acceptance_data_store: Arc<DbAcceptanceDataStore>,
     pub virtual_stores: Arc<RwLock<VirtualStores>>,
+    pub(super) pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
     // TODO: remove all pub from stores when StoreManager is implemented
 
     // Managers and services
     pub(super) ghostdag_manager: DbGhostdagManager,
     pub(super) reachability_service: MTReachabilityService<DbReachabilityStore>,
     pub(super) relations_service: MTRelationsService<DbRelationsStore>,
-    pub(super) dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore>,
+    pub(super) dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore, DbHeadersStore>,
     pub(super) difficulty_manager: DifficultyManager<DbHeadersStore>,
     pub(super) coinbase_manager: CoinbaseManager,
+    pub(super) mass_calculator: MassCalculator,
     pub(super) transaction_validator: TransactionValidator,
     pub(super) past_median_time_manager: PastMedianTimeManager<DbHeadersStore, DbGhostdagStore, BlockWindowCacheStore>,
     pub(super) pruning_manager: PruningManager<DbGhostdagStore, DbReachabilityStore, DbHeadersStore, DbPastPruningPointsStore>,
     pub(super) parents_manager: ParentsManager<DbHeadersStore, DbReachabilityStore, DbRelationsStore>,
     pub(super) depth_manager: BlockDepthManager<DbDepthStore, DbReachabilityStore, DbGhostdagStore>,
+
+    // Notifications
+    notification_root: Arc<ConsensusNotificationRoot>,
+
+    // Excludes external sessions from the commits of the virtual state
+    session_lock: SessionLock,
+
+    // Counters
+    counters: Arc<ProcessingCounters>,
 }
 
 impl VirtualStateProcessor {
@@ -128,9 +167,10 @@ impl VirtualStateProcessor {
         receiver: Receiver<BlockTask>,
         thread_pool: Arc<ThreadPool>,
         params: &Params,
+        clock: DynClock,
         db: Arc<DB>,
         // Stores
-        statuses_store: Arc<RwLock<DbStatusesStore>>,
+        statuses_store: Arc<ShardedStatusesStore>,
         ghostdag_store: Arc<DbGhostdagStore>,
         headers_store: Arc<DbHeadersStore>,
         daa_store: Arc<DbDaaStore>,
@@ -144,30 +184,37 @@ impl VirtualStateProcessor {
         acceptance_data_store: Arc<DbAcceptanceDataStore>,
         // Virtual-related stores
         virtual_stores: Arc<RwLock<VirtualStores>>,
+        pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
         // Managers
         ghostdag_manager: DbGhostdagManager,
         reachability_service: MTReachabilityService<DbReachabilityStore>,
         relations_service: MTRelationsService<DbRelationsStore>,
-        dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore>,
+        dag_traversal_manager: DagTraversalManager<DbGhostdagStore, BlockWindowCacheStore, DbHeadersStore>,
         difficulty_manager: DifficultyManager<DbHeadersStore>,
         coinbase_manager: CoinbaseManager,
+        mass_calculator: MassCalculator,
         transaction_validator: TransactionValidator,
         past_median_time_manager: PastMedianTimeManager<DbHeadersStore, DbGhostdagStore, BlockWindowCacheStore>,
         pruning_manager: PruningManager<DbGhostdagStore, DbReachabilityStore, DbHeadersStore, DbPastPruningPointsStore>,
         parents_manager: ParentsManager<DbHeadersStore, DbReachabilityStore, DbRelationsStore>,
         depth_manager: BlockDepthManager<DbDepthStore, DbReachabilityStore, DbGhostdagStore>,
+        // Notifications
+        notification_root: Arc<ConsensusNotificationRoot>,
+        session_lock: SessionLock,
+        counters: Arc<ProcessingCounters>,
     ) -> Self {
         Self {
             receiver,
             thread_pool,
 
-            genesis_hash: params.genesis_hash,
-            genesis_bits: params.genesis_bits,
-            genesis_timestamp: params.genesis_timestamp,
+            genesis_hash: params.genesis.hash,
+            genesis: params.genesis.clone(),
             max_block_parents: params.max_block_parents,
-            difficulty_window_size: params.difficulty_window_size,
             mergeset_size_limit: params.mergeset_size_limit,
             pruning_depth: params.pruning_depth,
+            target_time_per_block: params.target_time_per_block,
+            max_block_mass: params.max_block_mass,
+            clock,
 
             db,
             statuses_store,
@@ -182,17 +229,22 @@ impl VirtualStateProcessor {
             utxo_multisets_store,
             acceptance_data_store,
             virtual_stores,
+            pruning_utxoset_stores,
             ghostdag_manager,
             reachability_service,
             relations_service,
             dag_traversal_manager,
             difficulty_manager,
             coinbase_manager,
+            mass_calculator,
             transaction_validator,
             past_median_time_manager,
             pruning_manager,
             parents_manager,
             depth_manager,
+            notification_root,
+            session_lock,
+            counters,
         }
     }
 
@@ -204,24 +256,55 @@ impl VirtualStateProcessor {
             let tasks: Vec<BlockTask> = std::iter::once(first_task).chain(self.receiver.try_iter()).collect();
             trace!("virtual processor received {} tasks", tasks.len());
 
-            self.resolve_virtual();
+            let start = Instant::now();
+            let virtual_notifications = self.resolve_virtual();
+            ProcessingCounters::record_elapsed(&self.counters.virtual_micros, start);
+            self.counters.virtual_counts.fetch_add(1, Ordering::Relaxed);
 
-            let statuses_read = self.statuses_store.read();
             for task in tasks {
                 match task {
                     BlockTask::Exit => break 'outer,
-                    BlockTask::Process(block, result_transmitters) => {
-                        for transmitter in result_transmitters {
-                            // We don't care if receivers were dropped
-                            let _ = transmitter.send(Ok(statuses_read.get(block.hash()).unwrap()));
+                    BlockTask::Process(block, result_transmitters) => self.report_processed(block, result_transmitters),
+                    BlockTask::ProcessBatch(tasks) => {
+                        for (block, result_transmitters) in tasks {
+                            self.report_processed(block, result_transmitters);
                         }
                     }
                 };
             }
+
+            // Notified after the added blocks, so that consumers already know all added chain blocks
+            for notification in virtual_notifications {
+                self.notification_root.notify(notification);
+            }
         }
     }
 
-    fn resolve_virtual(self: &Arc<Self>) {
+    /// Notifies the addition of a block with a body and sends its status to the result transmitters
+    fn report_processed(&self, block: Block, result_transmitters: Vec<BlockResultSender>) {
+        let status = self.statuses_store.get(block.hash()).unwrap();
+        if status.has_block_body() {
+            self.notification_root.notify(Notification::BlockAdded(BlockAddedNotification { block }));
+        }
+        for transmitter in result_transmitters {
+            // We don't care if receivers were dropped
+            let _ = transmitter.send(Ok(status));
+        }
+    }
+
+    /// The number of tasks waiting to be received by the worker
+    pub fn queue_len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// The number of tasks the queue holds at most
+    pub fn queue_capacity(&self) -> usize {
+        self.receiver.capacity().unwrap_or(usize::MAX)
+    }
+
+    /// Resolves the virtual state and returns the notifications of the virtual chain and UTXO set changes, built only
+    /// if such notifications are consumed
+    fn resolve_virtual(self: &Arc<Self>) -> Vec<Notification> {
         // TODO: check finality violation
         // TODO: handle disqualified chain loop
         // TODO: acceptance data format
@@ -235,6 +318,8 @@ impl VirtualStateProcessor {
 
         let mut split_point: Option<Hash> = None;
         let mut accumulated_diff = prev_state.utxo_diff.clone().to_reversed();
+        let mut removed_chain_block_hashes = Vec::new();
+        let mut added_chain_block_hashes = Vec::new();
 
         // Walk down to the reorg split point
         for current in self.reachability_service.default_backward_chain_iterator(prev_selected) {
@@ -242,6 +327,7 @@ impl VirtualStateProcessor {
                 split_point = Some(current);
                 break;
             }
+            removed_chain_block_hashes.push(current);
 
             let mergeset_diff = self.utxo_diffs_store.get(current).unwrap();
             // Apply the diff in reverse
@@ -254,13 +340,14 @@ impl VirtualStateProcessor {
         for (selected_parent, current) in
             self.reachability_service.forward_chain_iterator(split_point, new_selected, true).tuple_windows()
         {
+            added_chain_block_hashes.push(current);
             match self.utxo_diffs_store.get(current) {
                 Ok(mergeset_diff) => {
                     accumulated_diff.with_diff_in_place(mergeset_diff.deref()).unwrap();
                 }
                 Err(StoreError::KeyNotFound(_)) => {
-                    if self.statuses_store.read().get(selected_parent).unwrap() == StatusDisqualifiedFromChain {
-                        self.statuses_store.write().set(current, StatusDisqualifiedFromChain).unwrap();
+                    if self.statuses_store.get(selected_parent).unwrap() == StatusDisqualifiedFromChain {
+                        self.statuses_store.set(current, StatusDisqualifiedFromChain).unwrap();
                         continue; // TODO: optimize
                     }
 
@@ -278,13 +365,17 @@ impl VirtualStateProcessor {
 
                     if let Err(rule_error) = res {
                         info!("{:?}", rule_error);
-                        self.statuses_store.write().set(current, StatusDisqualifiedFromChain).unwrap();
+                        self.statuses_store.set(current, StatusDisqualifiedFromChain).unwrap();
                     } else {
                         // Accumulate
                         accumulated_diff.with_diff_in_place(&ctx.mergeset_diff).unwrap();
                         // Commit UTXO data for current chain block
-                        self.commit_utxo_state(current, ctx.mergeset_diff, ctx.multiset_hash, AcceptanceData {});
-                        // TODO: AcceptanceData
+                        self.commit_utxo_state(
+                            current,
+                            ctx.mergeset_diff,
+                            ctx.multiset_hash,
+                            AcceptanceData::new(ctx.acceptance_stats, ctx.mergeset_acceptance_data),
+                        );
                     }
                 }
                 Err(err) => panic!("unexpected error {}", err),
@@ -293,7 +384,8 @@ impl VirtualStateProcessor {
 
         // NOTE: inlining this within the match captures the statuses store lock and should be avoided.
         // TODO: wrap statuses store lock within a service
-        let new_selected_status = self.statuses_store.read().get(new_selected).unwrap();
+        let new_selected_status = self.statuses_store.get(new_selected).unwrap();
+        let mut virtual_notifications = Vec::new();
         match new_selected_status {
             BlockStatus::StatusUTXOValid => {
                 let (virtual_parents, virtual_ghostdag_data) = self.pick_virtual_parents(new_selected, tips);
@@ -305,10 +397,9 @@ impl VirtualStateProcessor {
                 let mut ctx = UtxoProcessingContext::new((&virtual_ghostdag_data).into(), selected_parent_multiset_hash);
 
                 // Calc virtual DAA score, difficulty bits and past median time
-                let window = self.dag_traversal_manager.block_window(&virtual_ghostdag_data, self.difficulty_window_size);
-                let (virtual_daa_score, mergeset_non_daa) = self
-                    .difficulty_manager
-                    .calc_daa_score_and_non_daa_mergeset_blocks(&mut window.iter().map(|item| item.0.hash), &virtual_ghostdag_data);
+                let window = self.dag_traversal_manager.window(&virtual_ghostdag_data, WindowType::DifficultyWindow);
+                let (virtual_daa_score, mergeset_non_daa) =
+                    self.difficulty_manager.calc_daa_score_and_non_daa_mergeset_blocks(&window, &virtual_ghostdag_data);
                 let virtual_bits = self.difficulty_manager.calculate_difficulty_bits(&window);
                 let virtual_past_median_time = self.past_median_time_manager.calc_past_median_time(&virtual_ghostdag_data).0;
 
@@ -320,7 +411,7 @@ impl VirtualStateProcessor {
 
                 // Build the new virtual state
                 let new_virtual_state = VirtualState::new(
-                    virtual_parents,
+                    virtual_parents.clone(),
                     virtual_daa_score,
                     virtual_bits,
                     virtual_past_median_time,
@@ -332,6 +423,9 @@ impl VirtualStateProcessor {
                     virtual_ghostdag_data,
                 );
 
+                // Active sessions are waited for, so that they observe the virtual state and chain either before or
+                // after the commit but never in between
+                let session_write = self.session_lock.write();
                 let mut batch = WriteBatch::default();
                 let mut virtual_write = RwLockUpgradableReadGuard::upgrade(virtual_read);
 
@@ -346,6 +440,32 @@ impl VirtualStateProcessor {
 
                 // Calling the drops explicitly after the batch is written in order to avoid possible errors.
                 drop(virtual_write);
+
+                // Advance the reindex root with the new sink. This is usually a no-op since the headers selected
+                // tip leads the root ahead of the sink, unless the sink moved to another chain
+                if !added_chain_block_hashes.is_empty() {
+                    let mut batch = WriteBatch::default();
+                    let reachability_write_guard =
+                        self.reachability_service.hint_virtual_selected_parent(&mut batch, new_selected).unwrap();
+                    self.db.write(batch).unwrap();
+                    drop(reachability_write_guard);
+                }
+                drop(session_write);
+
+                let acceptance_notifications =
+                    self.transactions_acceptance_changed(&removed_chain_block_hashes, &added_chain_block_hashes);
+                if let Some(notification) = self.virtual_chain_changed(removed_chain_block_hashes, added_chain_block_hashes) {
+                    virtual_notifications.push(Notification::VirtualChainChanged(notification));
+                }
+                if self.notification_root.has_consumers(NotificationType::UtxosChanged)
+                    && !(accumulated_diff.add.is_empty() && accumulated_diff.remove.is_empty())
+                {
+                    virtual_notifications.push(Notification::UtxosChanged(UtxosChangedNotification {
+                        accumulated_utxo_diff: Arc::new(accumulated_diff),
+                        virtual_parents,
+                    }));
+                }
+                virtual_notifications.extend(acceptance_notifications);
             }
             BlockStatus::StatusDisqualifiedFromChain => {
                 // TODO: this means another chain needs to be checked
@@ -354,7 +474,82 @@ impl VirtualStateProcessor {
         }
 
         // TODO: Make a separate pruning processor and send to its channel here
-        self.advance_pruning_point_and_candidate_if_possible()
+        self.advance_pruning_point_and_candidate_if_possible();
+
+        virtual_notifications
+    }
+
+    fn virtual_chain_changed(
+        &self,
+        removed_chain_block_hashes: Vec<Hash>,
+        added_chain_block_hashes: Vec<Hash>,
+    ) -> Option<VirtualChainChangedNotification> {
+        // A removed chain block implies an added one, since the new selected chain is at least as heavy
+        if added_chain_block_hashes.is_empty() || !self.notification_root.has_consumers(NotificationType::VirtualChainChanged) {
+            return None;
+        }
+        let added_chain_blocks_acceptance_data = added_chain_block_hashes
+            .iter()
+            .map(|&hash| ChainBlockAcceptanceData {
+                accepting_block_hash: hash,
+                mergeset: self.acceptance_data_store.get(hash).unwrap().mergeset.clone(),
+            })
+            .collect();
+        Some(VirtualChainChangedNotification {
+            removed_chain_block_hashes,
+            added_chain_block_hashes,
+            added_chain_blocks_acceptance_data,
+        })
+    }
+
+    /// Returns the notifications of the transactions reverted and accepted by a virtual chain change, in this order.
+    /// A transaction accepted by a removed chain block is only reverted if no added chain block accepts it again.
+    fn transactions_acceptance_changed(
+        &self,
+        removed_chain_block_hashes: &[Hash],
+        added_chain_block_hashes: &[Hash],
+    ) -> Vec<Notification> {
+        let notify_accepted = self.notification_root.has_consumers(NotificationType::TransactionsAccepted);
+        let notify_reverted = self.notification_root.has_consumers(NotificationType::TransactionsReverted);
+        let mut notifications = Vec::new();
+        if !notify_accepted && !notify_reverted {
+            return notifications;
+        }
+        let accepted = self.accepted_transactions(added_chain_block_hashes);
+        if notify_reverted && !removed_chain_block_hashes.is_empty() {
+            let accepted_ids: HashSet<TransactionId> = accepted.iter().map(|acceptance| acceptance.transaction_id).collect();
+            let reverted = self
+                .accepted_transactions(removed_chain_block_hashes)
+                .into_iter()
+                .filter(|acceptance| !accepted_ids.contains(&acceptance.transaction_id))
+                .collect_vec();
+            if !reverted.is_empty() {
+                notifications.push(Notification::TransactionsReverted(TransactionsRevertedNotification { reverted }));
+            }
+        }
+        if notify_accepted && !accepted.is_empty() {
+            notifications.push(Notification::TransactionsAccepted(TransactionsAcceptedNotification { accepted }));
+        }
+        notifications
+    }
+
+    /// Returns the transactions accepted by the chain blocks, following the order of the chain blocks and of their mergesets
+    fn accepted_transactions(&self, chain_block_hashes: &[Hash]) -> Vec<TransactionAcceptance> {
+        let mut accepted = Vec::new();
+        for &accepting_block_hash in chain_block_hashes {
+            for merged_block in self.acceptance_data_store.get(accepting_block_hash).unwrap().mergeset.iter() {
+                let accepted_ids: HashSet<TransactionId> = merged_block.accepted_transaction_ids.iter().copied().collect();
+                let transactions = self.block_transactions_store.get(merged_block.block_hash).unwrap();
+                accepted.extend(transactions.iter().map(|tx| (tx.id(), tx)).filter(|(id, _)| accepted_ids.contains(id)).map(
+                    |(transaction_id, tx)| TransactionAcceptance {
+                        transaction_id,
+                        accepting_block_hash,
+                        output_script_public_keys: tx.outputs.iter().map(|output| output.script_public_key.clone()).collect(),
+                    },
+                ));
+            }
+        }
+        accepted
     }
 
     fn commit_utxo_state(self: &Arc<Self>, current: Hash, mergeset_diff: UtxoDiff, multiset: MuHash, acceptance_data: AcceptanceData) {
@@ -493,7 +688,7 @@ impl VirtualStateProcessor {
         (virtual_parents, ghostdag_data)
     }
 
-    pub fn validate_mempool_transaction_and_populate(&self, mutable_tx: &mut MutableTransaction) -> TxResult<()> {
+    pub fn validate_mempool_transaction(&self, mutable_tx: &mut MutableTransaction) -> TxResult<()> {
         self.transaction_validator.validate_tx_in_isolation(&mutable_tx.tx)?;
 
         let virtual_read = self.virtual_stores.read();
@@ -502,9 +697,16 @@ impl VirtualStateProcessor {
         let virtual_daa_score = virtual_state.daa_score;
         let virtual_past_median_time = virtual_state.past_median_time;
 
-        self.transaction_validator.utxo_free_tx_validation(&mutable_tx.tx, virtual_daa_score, virtual_past_median_time)?;
+        self.transaction_validator.utxo_free_tx_validation(&mutable_tx.tx, virtual_daa_score, virtual_past_median_time.as_millis())?;
         self.validate_mempool_transaction_in_utxo_context(mutable_tx, virtual_utxo_view, virtual_daa_score)?;
 
+        // A transaction which cannot fit in a block could never be mined
+        let mass = self.mass_calculator.calc_tx_mass(&mutable_tx.tx).combined();
+        if mass > self.max_block_mass {
+            return Err(TxRuleError::MassTooHigh(mass, self.max_block_mass));
+        }
+        mutable_tx.calculated_mass = Some(mass);
+
         Ok(())
     }
 
@@ -515,13 +717,158 @@ impl VirtualStateProcessor {
         utxo_view: &impl UtxoView,
     ) -> TxResult<()> {
         // No need to validate the transaction in isolation since we rely on the mining manager to submit transactions
-        // which were previously validated through `validate_mempool_transaction_and_populate`, hence we only perform
+        // which were previously validated through `validate_mempool_transaction`, hence we only perform
         // in-context validations
-        self.transaction_validator.utxo_free_tx_validation(tx, virtual_state.daa_score, virtual_state.past_median_time)?;
+        self.transaction_validator.utxo_free_tx_validation(tx, virtual_state.daa_score, virtual_state.past_median_time.as_millis())?;
         self.validate_transaction_in_utxo_context(tx, utxo_view, virtual_state.daa_score)?;
         Ok(())
     }
 
+    /// Aggregates the acceptance statistics of the last `window_size` chain blocks, walking down from the virtual selected parent.
+    /// The window is clamped to [`consensus_core::stats::MAX_CHAIN_THROUGHPUT_WINDOW_SIZE`] chain blocks
+    pub fn chain_throughput_stats(&self, window_size: u64) -> ChainThroughputStats {
+        let sink = self.virtual_stores.read().state.get().unwrap().ghostdag_data.selected_parent;
+        let mut stats = ChainThroughputStats::default();
+        let mut oldest = sink;
+        let window_size = ChainThroughputStats::clamp_window_size(window_size);
+        for current in self.reachability_service.default_backward_chain_iterator(sink).take(window_size) {
+            // The window is cut short at blocks whose acceptance data was garbage collected
+            let acceptance_stats = match self.acceptance_data_store.get(current).unwrap_option() {
+                Some(acceptance_data) => acceptance_data.stats,
+                None => break,
+            };
+            stats.chain_block_count += 1;
+            stats.merged_block_count += acceptance_stats.mergeset_size;
+            stats.accepted_transaction_count += acceptance_stats.accepted_tx_count;
+            stats.accepted_mass += acceptance_stats.accepted_mass;
+            stats.total_fees += acceptance_stats.total_fees;
+            oldest = current;
+        }
+        stats.time_span_millis = self
+            .headers_store
+            .get_timestamp(sink)
+            .unwrap()
+            .saturating_duration_since(self.headers_store.get_timestamp(oldest).unwrap())
+            .as_millis();
+        stats
+    }
+
+    /// Measures the drift of the virtual DAA score from the clock, based on the timestamp of the virtual selected parent
+    pub fn daa_score_drift(&self) -> DaaScoreDrift {
+        let (virtual_daa_score, sink) = {
+            let virtual_state = self.virtual_stores.read().state.get().unwrap();
+            (virtual_state.daa_score, virtual_state.ghostdag_data.selected_parent)
+        };
+        DaaScoreDrift {
+            virtual_daa_score,
+            sink_timestamp: self.headers_store.get_timestamp(sink).unwrap(),
+            now: self.clock.now(),
+            target_time_per_block: self.target_time_per_block,
+        }
+    }
+
+    /// Returns the changes turning the selected chain of `start` into the virtual selected parent chain. Added chain
+    /// blocks are limited to `max_added_blocks` and, if acceptance data is included, to about `max_accepted_transaction_ids`
+    /// accepted transaction ids (at least one chain block is always added if available)
+    pub fn virtual_chain_batch(
+        &self,
+        start: Hash,
+        include_accepted_transaction_ids: bool,
+        max_added_blocks: usize,
+        max_accepted_transaction_ids: usize,
+    ) -> SyncManagerResult<VirtualChainBatch> {
+        if !self.ghostdag_store.has(start).unwrap() {
+            return Err(SyncManagerError::BlockNotFound(start));
+        }
+        let sink = self.virtual_stores.read().state.get().unwrap().ghostdag_data.selected_parent;
+        let mut batch = VirtualChainBatch::default();
+
+        // Walk down the selected chain of `start` until reaching the virtual chain. Genesis is always reached at worst
+        let mut split_point = start;
+        while !self.reachability_service.is_chain_ancestor_of(split_point, sink) {
+            batch.removed_chain_block_hashes.push(split_point);
+            split_point = self.ghostdag_store.get_selected_parent(split_point).unwrap();
+        }
+
+        let mut accepted_transaction_count = 0;
+        for chain_block in self.reachability_service.forward_chain_iterator(split_point, sink, true).skip(1) {
+            if batch.added_chain_block_hashes.len() == max_added_blocks {
+                batch.has_more = true;
+                break;
+            }
+            if include_accepted_transaction_ids {
+                let mergeset = self.acceptance_data_store.get(chain_block).unwrap().mergeset.clone();
+                let count: usize = mergeset.iter().map(|block| block.accepted_transaction_ids.len()).sum();
+                if !batch.added_chain_block_hashes.is_empty() && accepted_transaction_count + count > max_accepted_transaction_ids {
+                    batch.has_more = true;
+                    break;
+                }
+                accepted_transaction_count += count;
+                batch.accepted_transaction_ids.push(ChainBlockAcceptanceData { accepting_block_hash: chain_block, mergeset });
+            }
+            batch.added_chain_block_hashes.push(chain_block);
+        }
+        Ok(batch)
+    }
+
+    /// Returns the selected chain blocks of `high` above `low`, `high` defaulting to the virtual selected parent, along
+    /// with their GHOSTDAG and acceptance data. Blocks are limited to `max_blocks`
+    pub fn chain_blocks_with_acceptance(
+        &self,
+        low: Hash,
+        high: Option<Hash>,
+        max_blocks: usize,
+    ) -> SyncManagerResult<ChainBlocksBatch> {
+        let sink = self.virtual_stores.read().state.get().unwrap().ghostdag_data.selected_parent;
+        let high = high.unwrap_or(sink);
+        for hash in [low, high] {
+            if !self.ghostdag_store.has(hash).unwrap() {
+                return Err(SyncManagerError::BlockNotFound(hash));
+            }
+        }
+        // Only virtual chain blocks are guaranteed to hold acceptance data
+        if !self.reachability_service.is_chain_ancestor_of(high, sink) {
+            return Err(SyncManagerError::NotInSelectedChain(high, sink));
+        }
+        if !self.reachability_service.is_chain_ancestor_of(low, high) {
+            return Err(SyncManagerError::NotInSelectedChain(low, high));
+        }
+
+        let mut batch = ChainBlocksBatch::default();
+        for chain_block in self.reachability_service.forward_chain_iterator(low, high, true).skip(1) {
+            if batch.blocks.len() == max_blocks {
+                batch.has_more = true;
+                break;
+            }
+            // Virtual chain blocks always have a body
+            let block = Block::from_arcs(
+                self.headers_store.get_header(chain_block).unwrap(),
+                self.block_transactions_store.get(chain_block).unwrap(),
+            );
+            let ghostdag_data = self.ghostdag_store.get_compact_data(chain_block).unwrap();
+            batch.blocks.push(ChainBlockWithAcceptance {
+                block,
+                blue_score: ghostdag_data.blue_score,
+                blue_work: ghostdag_data.blue_work,
+                selected_parent: ghostdag_data.selected_parent,
+                mergeset: self.acceptance_data_store.get(chain_block).unwrap().mergeset.clone(),
+            });
+        }
+        Ok(batch)
+    }
+
+    /// Returns the coinbase a block built right now on top of the virtual would carry, paying to `miner_data`
+    pub fn coinbase_preview(&self, miner_data: MinerData) -> CoinbaseResult<CoinbasePreview> {
+        let virtual_state = self.virtual_stores.read().state.get().unwrap();
+        self.coinbase_manager.coinbase_preview(
+            virtual_state.daa_score,
+            miner_data,
+            &virtual_state.ghostdag_data,
+            &virtual_state.mergeset_rewards,
+            &virtual_state.mergeset_non_daa,
+        )
+    }
+
     pub fn build_block_template(&self, miner_data: MinerData, mut txs: Vec<Transaction>) -> Result<BlockTemplate, RuleError> {
         // TODO: tests
         let virtual_read = self.virtual_stores.read();
@@ -558,18 +905,17 @@ impl VirtualStateProcessor {
         let version = BLOCK_VERSION;
         let parents_by_level = self.parents_manager.calc_block_parents(pruning_point, &virtual_state.parents);
         let hash_merkle_root = calc_hash_merkle_root(txs.iter());
-        let accepted_id_merkle_root = merkle::calc_merkle_root(virtual_state.accepted_tx_ids.iter().copied());
+        let accepted_id_merkle_root = calc_accepted_id_merkle_root(virtual_state.accepted_tx_ids.iter().copied());
         let utxo_commitment = virtual_state.multiset.clone().finalize();
         // Past median time is the exclusive lower bound for valid block time, so we increase by 1 to get the valid min
-        let min_block_time = virtual_state.past_median_time + 1;
-        let now = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as u64;
+        let min_block_time = virtual_state.past_median_time + DurationMs::from_millis(1);
         let header = Header::new(
             version,
             parents_by_level,
             hash_merkle_root,
             accepted_id_merkle_root,
             utxo_commitment,
-            u64::max(min_block_time, now),
+            TimestampMs::max(min_block_time, self.clock.now()),
             virtual_state.bits,
             0,
             virtual_state.daa_score,
@@ -606,15 +952,114 @@ impl VirtualStateProcessor {
             let new_pp_index = current_pruning_info.index + new_pruning_points.len() as u64;
             write_guard.set_batch(&mut batch, *new_pruning_points.last().unwrap(), new_candidate, new_pp_index).unwrap();
             self.db.write(batch).unwrap();
-            // TODO: Move PP UTXO etc
+            drop(write_guard);
+            self.advance_pruning_utxoset(*new_pruning_points.last().unwrap());
         } else if new_candidate != current_pruning_info.candidate {
             let mut write_guard = RwLockUpgradableReadGuard::upgrade(pruning_read_guard);
             write_guard.set(current_pruning_info.pruning_point, new_candidate, current_pruning_info.index).unwrap();
         }
     }
 
+    /// Advances the pruning point UTXO set along the selected chain up to `pruning_point`, by applying the UTXO diffs
+    /// of the chain blocks above its current position. Each chain block is applied in its own batch along with the new
+    /// position, so that an interrupted advance resumes from where it stopped on the next pruning point movement
+    fn advance_pruning_utxoset(&self, pruning_point: Hash) {
+        let mut pruning_utxoset_write = self.pruning_utxoset_stores.write();
+        let position = pruning_utxoset_write.position.get().unwrap_option().unwrap_or(self.genesis_hash);
+        for chain_block in self.reachability_service.forward_chain_iterator(position, pruning_point, true).skip(1) {
+            let mut batch = WriteBatch::default();
+            let utxo_diff = self.utxo_diffs_store.get(chain_block).unwrap();
+            pruning_utxoset_write.utxo_set.write_diff_batch(&mut batch, utxo_diff.as_ref()).unwrap();
+            pruning_utxoset_write.position.set_batch(&mut batch, chain_block).unwrap();
+            self.db.write(batch).unwrap();
+        }
+    }
+
+    /// Imports the UTXO set of the pruning point, i.e., the last of `past_pruning_points`, which was appended to the
+    /// pruning point UTXO set during a trusted sync along with the insertion of the trusted blocks. Once the set is
+    /// verified against the UTXO commitment of the pruning point, it becomes the virtual UTXO set, and the pruning
+    /// point becomes the pruning point of this node and its only body tip, on top of which the virtual is resolved
+    pub fn import_pruning_point_utxo_set(self: &Arc<Self>, past_pruning_points: &[Hash]) -> BlockProcessResult<()> {
+        let pruning_point = *past_pruning_points.last().expect("the past pruning points start with genesis");
+        match self.statuses_store.get(pruning_point).unwrap_option() {
+            Some(status) if status.has_block_body() => {}
+            _ => return Err(RuleError::MissingPruningPointBody(pruning_point)),
+        }
+        for (index, &hash) in past_pruning_points.iter().enumerate() {
+            match self.past_pruning_points_store.get(index as u64).unwrap_option() {
+                Some(known) if known != hash => return Err(RuleError::PastPruningPointMismatch(index as u64, hash, known)),
+                _ => {}
+            }
+        }
+
+        // Verify the imported set against the UTXO commitment of the pruning point
+        let mut pruning_utxoset_write = self.pruning_utxoset_stores.write();
+        let mut multiset = MuHash::new();
+        for_each_utxo_chunk(&pruning_utxoset_write.utxo_set, |chunk| {
+            for (outpoint, entry) in chunk.iter() {
+                multiset.add_utxo(outpoint, entry);
+            }
+        });
+        let header = self.headers_store.get_header(pruning_point).unwrap();
+        let imported_commitment = multiset.finalize();
+        if imported_commitment != header.utxo_commitment {
+            return Err(RuleError::BadUTXOCommitment(pruning_point, header.utxo_commitment, imported_commitment));
+        }
+
+        // The virtual UTXO set is replaced by the imported set
+        let mut virtual_write = self.virtual_stores.write();
+        virtual_write.utxo_set.clear().unwrap();
+        for_each_utxo_chunk(&pruning_utxoset_write.utxo_set, |chunk| {
+            virtual_write.utxo_set.write_diff(&UtxoDiff::new(chunk.into_iter().collect(), Default::default())).unwrap();
+        });
+
+        // The virtual state is a placeholder pointing at the pruning point, which is replaced once the virtual is resolved
+        let placeholder_state = VirtualState::new(
+            vec![pruning_point],
+            header.daa_score,
+            header.bits,
+            header.timestamp,
+            multiset.clone(),
+            UtxoDiff::default(),
+            Vec::new(),
+            Default::default(),
+            Default::default(),
+            self.ghostdag_manager.ghostdag(&[pruning_point]),
+        );
+
+        let mut batch = WriteBatch::default();
+        for (index, &hash) in past_pruning_points.iter().enumerate() {
+            match self.past_pruning_points_store.insert_batch(&mut batch, index as u64, hash) {
+                Ok(()) | Err(StoreError::KeyAlreadyExists(_)) => {}
+                Err(err) => panic!("unexpected store error {}", err),
+            }
+        }
+        let mut pruning_write = self.pruning_store.write();
+        pruning_write.set_batch(&mut batch, pruning_point, pruning_point, past_pruning_points.len() as u64 - 1).unwrap();
+        pruning_utxoset_write.position.set_batch(&mut batch, pruning_point).unwrap();
+        match self.utxo_multisets_store.insert_batch(&mut batch, pruning_point, multiset) {
+            Ok(()) | Err(StoreError::KeyAlreadyExists(_)) => {}
+            Err(err) => panic!("unexpected store error {}", err),
+        }
+        let mut body_tips_write = self.body_tips_store.write();
+        body_tips_write.init_batch(&mut batch, &[pruning_point]).unwrap();
+        virtual_write.state.set_batch(&mut batch, placeholder_state).unwrap();
+        let statuses_write_guard = self.statuses_store.set_batch(&mut batch, pruning_point, StatusUTXOValid).unwrap();
+        self.db.write(batch).unwrap();
+        drop(statuses_write_guard);
+        drop(body_tips_write);
+        drop(pruning_write);
+        drop(virtual_write);
+        drop(pruning_utxoset_write);
+
+        for notification in self.resolve_virtual() {
+            self.notification_root.notify(notification);
+        }
+        Ok(())
+    }
+
     pub fn process_genesis_if_needed(self: &Arc<Self>) {
-        let status = self.statuses_store.read().get(self.genesis_hash).unwrap();
+        let status = self.statuses_store.get(self.genesis_hash).unwrap();
         match status {
             StatusUTXOPendingVerification => {
                 let txs = self.block_transactions_store.get(self.genesis_hash).unwrap();
@@ -622,14 +1067,12 @@ impl VirtualStateProcessor {
                     .write()
                     .state
                     .set(VirtualState::from_genesis(
-                        self.genesis_hash,
-                        self.genesis_bits,
-                        self.genesis_timestamp,
+                        &self.genesis,
                         vec![txs[0].id()],
                         self.ghostdag_manager.ghostdag(&[self.genesis_hash]),
                     ))
                     .unwrap();
-                self.commit_utxo_state(self.genesis_hash, UtxoDiff::default(), MuHash::new(), AcceptanceData {});
+                self.commit_utxo_state(self.genesis_hash, UtxoDiff::default(), MuHash::new(), AcceptanceData::default());
                 match self.past_pruning_points_store.insert(0, self.genesis_hash) {
                     Ok(()) => {}
                     Err(StoreError::KeyAlreadyExists(_)) => {
@@ -648,6 +1091,20 @@ impl VirtualStateProcessor {
     }
 }
 
+/// Calls `f` with the consecutive chunks of `utxo_set`, in the UTXO set order
+fn for_each_utxo_chunk(utxo_set: &DbUtxoSetStore, mut f: impl FnMut(Vec<(TransactionOutpoint, UtxoEntry)>)) {
+    let mut from_outpoint = None;
+    loop {
+        let chunk = utxo_set.get_chunk(from_outpoint, UTXO_SET_IMPORT_CHUNK_SIZE).unwrap();
+        let is_last = chunk.len() < UTXO_SET_IMPORT_CHUNK_SIZE;
+        from_outpoint = chunk.last().map(|(outpoint, _)| *outpoint);
+        f(chunk);
+        if is_last {
+            break;
+        }
+    }
+}
+
 enum MergesetIncreaseResult {
     Accepted { increase_size: u64 },
     Rejected { new_candidate: Hash },
@@ -4,13 +4,18 @@ use crate::{
         BlockProcessResult,
         RuleError::{BadAcceptedIDMerkleRoot, BadCoinbaseTransaction, BadUTXOCommitment, InvalidTransactionsInUtxoContext},
     },
-    model::stores::{block_transactions::BlockTransactionsStoreReader, daa::DaaStoreReader, ghostdag::GhostdagData},
+    model::stores::{
+        acceptance_data::AcceptanceStats, block_transactions::BlockTransactionsStoreReader, daa::DaaStoreReader,
+        ghostdag::GhostdagData,
+    },
     processes::transaction_validator::errors::{TxResult, TxRuleError},
 };
 use consensus_core::{
+    chain::MergesetBlockAcceptanceData,
     coinbase::*,
     hashing,
     header::Header,
+    merkle::calc_accepted_id_merkle_root,
     muhash::MuHashExtensions,
     tx::{MutableTransaction, PopulatedTransaction, Transaction, TransactionId, ValidatedTransaction, VerifiableTransaction},
     utxo::{
@@ -35,6 +40,8 @@ pub(super) struct UtxoProcessingContext<'a> {
     pub mergeset_diff: UtxoDiff,
     pub accepted_tx_ids: Vec<TransactionId>,
     pub mergeset_rewards: BlockHashMap<BlockRewardData>,
+    pub acceptance_stats: AcceptanceStats,
+    pub mergeset_acceptance_data: Vec<MergesetBlockAcceptanceData>,
 }
 
 impl<'a> UtxoProcessingContext<'a> {
@@ -46,6 +53,8 @@ impl<'a> UtxoProcessingContext<'a> {
             mergeset_diff: UtxoDiff::default(),
             accepted_tx_ids: Vec::with_capacity(1), // We expect at least the selected parent coinbase tx
             mergeset_rewards: BlockHashMap::with_capacity(mergeset_size),
+            acceptance_stats: AcceptanceStats::default(),
+            mergeset_acceptance_data: Vec::with_capacity(mergeset_size),
         }
     }
 
@@ -68,6 +77,7 @@ impl VirtualStateProcessor {
         ctx.mergeset_diff.add_transaction(&validated_coinbase, pov_daa_score).unwrap();
         ctx.multiset_hash.add_transaction(&validated_coinbase, pov_daa_score);
         ctx.accepted_tx_ids.push(validated_coinbase.id());
+        let (selected_parent, selected_parent_coinbase_id) = (ctx.selected_parent(), validated_coinbase.id());
 
         // TODO: no need to validate selected parent transactions, but only to populate and add,
         // since selected parent txs were already validated as part of selected parent utxo state verification.
@@ -84,12 +94,22 @@ impl VirtualStateProcessor {
             let validated_transactions = self.validate_transactions_in_parallel(&txs, &composed_view, pov_daa_score);
 
             let mut block_fee = 0u64;
+            // The selected parent coinbase is accepted by the chain block along with the selected parent transactions
+            let mut block_accepted_tx_ids =
+                if merged_block == selected_parent { vec![selected_parent_coinbase_id] } else { Vec::new() };
             for validated_tx in validated_transactions {
                 ctx.mergeset_diff.add_transaction(&validated_tx, pov_daa_score).unwrap();
                 ctx.multiset_hash.add_transaction(&validated_tx, pov_daa_score);
                 ctx.accepted_tx_ids.push(validated_tx.id());
+                block_accepted_tx_ids.push(validated_tx.id());
                 block_fee += validated_tx.calculated_fee;
+                ctx.acceptance_stats.accepted_tx_count += 1;
+                ctx.acceptance_stats.accepted_mass += self.mass_calculator.calc_tx_mass(validated_tx.tx).combined();
             }
+            ctx.acceptance_stats.mergeset_size += 1;
+            ctx.acceptance_stats.total_fees += block_fee;
+            ctx.mergeset_acceptance_data
+                .push(MergesetBlockAcceptanceData { block_hash: merged_block, accepted_transaction_ids: block_accepted_tx_ids });
 
             let coinbase_data = self.coinbase_manager.deserialize_coinbase_payload(&txs[0].payload).unwrap();
             ctx.mergeset_rewards.insert(
@@ -123,7 +143,7 @@ impl VirtualStateProcessor {
         trace!("correct commitment: {}, {}", header.hash, expected_commitment);
 
         // Verify header accepted_id_merkle_root
-        let expected_accepted_id_merkle_root = merkle::calc_merkle_root(ctx.accepted_tx_ids.iter().copied());
+        let expected_accepted_id_merkle_root = calc_accepted_id_merkle_root(ctx.accepted_tx_ids.iter().copied());
         if expected_accepted_id_merkle_root != header.accepted_id_merkle_root {
             return Err(BadAcceptedIDMerkleRoot(header.hash, header.accepted_id_merkle_root, expected_accepted_id_merkle_root));
         }
@@ -17,6 +17,14 @@ const LENGTH_OF_SCRIPT_PUB_KEY_LENGTH: usize = size_of::<u8>();
 const MIN_PAYLOAD_LENGTH: usize =
     LENGTH_OF_BLUE_SCORE + LENGTH_OF_SUBSIDY + LENGTH_OF_SCRIPT_PUB_KEY_VERSION + LENGTH_OF_SCRIPT_PUB_KEY_LENGTH;
 
+// We define a year as 365.25 days and a month as 365.25 / 12 = 30.4375
+// SECONDS_PER_MONTH = 30.4375 * 24 * 60 * 60
+const SECONDS_PER_MONTH: u64 = 2629800;
+
+/// The number of months in which the deflationary subsidy halves. The subsidy table is "chromatic":
+/// each month the subsidy is reduced by a factor of 2^(1/12), so that it halves exactly every 12 months
+const MONTHS_PER_HALVING: usize = 12;
+
 #[derive(Clone)]
 pub struct CoinbaseManager {
     coinbase_payload_script_public_key_max_len: u8,
@@ -99,6 +107,45 @@ impl CoinbaseManager {
         })
     }
 
+    /// Computes the coinbase transaction expected for a block with the given GHOSTDAG data along with the reward each
+    /// merged block contributes to it. Blocks outside the DAA window do not contribute and are omitted
+    pub fn coinbase_preview<T: AsRef<[u8]>>(
+        &self,
+        daa_score: u64,
+        miner_data: MinerData<T>,
+        ghostdag_data: &GhostdagData,
+        mergeset_rewards: &BlockHashMap<BlockRewardData>,
+        mergeset_non_daa: &BlockHashSet,
+    ) -> CoinbaseResult<CoinbasePreview> {
+        let miner_script_public_key = miner_data.script_public_key.clone();
+        let coinbase = self.expected_coinbase_transaction(daa_score, miner_data, ghostdag_data, mergeset_rewards, mergeset_non_daa)?;
+
+        let blues = ghostdag_data.mergeset_blues.iter().map(|h| (h, true));
+        let reds = ghostdag_data.mergeset_reds.iter().map(|h| (h, false));
+        let merged_block_rewards = blues
+            .chain(reds)
+            .filter(|(h, _)| !mergeset_non_daa.contains(h))
+            .map(|(&hash, is_blue)| {
+                let reward_data = mergeset_rewards.get(&hash).unwrap();
+                MergesetBlockReward {
+                    hash,
+                    is_blue,
+                    subsidy: reward_data.subsidy,
+                    total_fees: reward_data.total_fees,
+                    script_public_key: if is_blue { reward_data.script_public_key.clone() } else { miner_script_public_key.clone() },
+                }
+            })
+            .collect();
+
+        Ok(CoinbasePreview {
+            daa_score,
+            blue_score: ghostdag_data.blue_score,
+            subsidy: self.calc_block_subsidy(daa_score),
+            merged_block_rewards,
+            outputs: coinbase.tx.outputs,
+        })
+    }
+
     pub fn serialize_coinbase_payload<T: AsRef<[u8]>>(&self, data: &CoinbaseData<T>) -> CoinbaseResult<Vec<u8>> {
         let script_pub_key_len = data.miner_data.script_public_key.script().len();
         if script_pub_key_len > self.coinbase_payload_script_public_key_max_len as usize {
@@ -182,18 +229,19 @@ impl CoinbaseManager {
             return self.pre_deflationary_phase_base_subsidy;
         }
 
-        // We define a year as 365.25 days and a month as 365.25 / 12 = 30.4375
-        // SECONDS_PER_MONTH = 30.4375 * 24 * 60 * 60
-        const SECONDS_PER_MONTH: u64 = 2629800;
-
         // Note that this calculation implicitly assumes that block per second = 1 (by assuming daa score diff is in second units).
         let months_since_deflationary_phase_started = (daa_score - self.deflationary_phase_daa_score) / SECONDS_PER_MONTH;
         assert!(months_since_deflationary_phase_started <= usize::MAX as u64);
         let months_since_deflationary_phase_started: usize = months_since_deflationary_phase_started as usize;
-        if months_since_deflationary_phase_started >= SUBSIDY_BY_MONTH_TABLE.len() {
+        Self::subsidy_by_month(months_since_deflationary_phase_started)
+    }
+
+    /// Returns the deflationary-phase block subsidy for the given month index (counted from the start of the deflationary phase)
+    fn subsidy_by_month(month: usize) -> u64 {
+        if month >= SUBSIDY_BY_MONTH_TABLE.len() {
             *SUBSIDY_BY_MONTH_TABLE.last().unwrap()
         } else {
-            SUBSIDY_BY_MONTH_TABLE[months_since_deflationary_phase_started]
+            SUBSIDY_BY_MONTH_TABLE[month]
         }
     }
 }
@@ -227,8 +275,10 @@ const SUBSIDY_BY_MONTH_TABLE: [u64; 426] = [
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::params::MAINNET_PARAMS;
+    use consensus_core::config::params::MAINNET_PARAMS;
     use consensus_core::tx::scriptvec;
+    use hashes::Hash;
+    use std::sync::Arc;
 
     #[test]
     fn subsidy_test() {
@@ -241,8 +291,7 @@ mod tests {
         );
 
         const DEFLATIONARY_PHASE_INITIAL_SUBSIDY: u64 = 44000000000;
-        const SECONDS_PER_MONTH: u64 = 2629800;
-        const SECONDS_PER_HALVING: u64 = SECONDS_PER_MONTH * 12;
+        const SECONDS_PER_HALVING: u64 = SECONDS_PER_MONTH * MONTHS_PER_HALVING as u64;
 
         struct Test {
             name: &'static str,
@@ -261,6 +310,22 @@ mod tests {
                 daa_score: params.deflationary_phase_daa_score,
                 expected: DEFLATIONARY_PHASE_INITIAL_SUBSIDY,
             },
+            Test {
+                name: "end of first deflationary month",
+                daa_score: params.deflationary_phase_daa_score + SECONDS_PER_MONTH - 1,
+                expected: DEFLATIONARY_PHASE_INITIAL_SUBSIDY,
+            },
+            Test {
+                name: "start of second deflationary month",
+                daa_score: params.deflationary_phase_daa_score + SECONDS_PER_MONTH,
+                expected: 41530469757,
+            },
+            Test {
+                // Value observed in an actual mainnet coinbase payload (see `payload_serialization_test`)
+                name: "after 6 months (mainnet value)",
+                daa_score: params.deflationary_phase_daa_score + 6 * SECONDS_PER_MONTH,
+                expected: 31112698372,
+            },
             Test {
                 name: "after one halving",
                 daa_score: params.deflationary_phase_daa_score + SECONDS_PER_HALVING,
@@ -298,6 +363,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn subsidy_table_test() {
+        // Every 12 months the subsidy halves (rounded down)
+        for month in 0..SUBSIDY_BY_MONTH_TABLE.len() - MONTHS_PER_HALVING {
+            assert_eq!(
+                SUBSIDY_BY_MONTH_TABLE[month + MONTHS_PER_HALVING],
+                SUBSIDY_BY_MONTH_TABLE[month] / 2,
+                "subsidy at month {} is not half the subsidy at month {}",
+                month + MONTHS_PER_HALVING,
+                month
+            );
+        }
+
+        // The subsidy is monotonically non-increasing and eventually depleted
+        assert!(SUBSIDY_BY_MONTH_TABLE.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(*SUBSIDY_BY_MONTH_TABLE.last().unwrap(), 0);
+        assert_eq!(CoinbaseManager::subsidy_by_month(SUBSIDY_BY_MONTH_TABLE.len() + 100), 0);
+    }
+
     #[test]
     fn payload_serialization_test() {
         let params = &MAINNET_PARAMS;
@@ -385,4 +469,50 @@ mod tests {
 
         assert_eq!(data2, deserialized_data);
     }
+
+    #[test]
+    fn coinbase_preview_test() {
+        let params = &MAINNET_PARAMS;
+        let cbm = CoinbaseManager::new(
+            params.coinbase_payload_script_public_key_max_len,
+            params.max_coinbase_payload_len,
+            params.deflationary_phase_daa_score,
+            params.pre_deflationary_phase_base_subsidy,
+        );
+
+        let script = |b: u8| ScriptPublicKey::new(0, ScriptVec::from_slice(&[b, 172]));
+        let (blue1, blue2, red, non_daa) =
+            (Hash::from_u64_word(1), Hash::from_u64_word(2), Hash::from_u64_word(3), Hash::from_u64_word(4));
+        let ghostdag_data = GhostdagData::new(
+            90,
+            Default::default(),
+            blue1,
+            Arc::new(vec![blue1, blue2]),
+            Arc::new(vec![red, non_daa]),
+            Default::default(),
+        );
+        let mergeset_rewards = BlockHashMap::from_iter([
+            (blue1, BlockRewardData::new(500, 20, script(1))),
+            (blue2, BlockRewardData::new(500, 0, script(2))),
+            (red, BlockRewardData::new(500, 7, script(3))),
+            (non_daa, BlockRewardData::new(500, 9, script(4))),
+        ]);
+        let mergeset_non_daa = BlockHashSet::from_iter([non_daa]);
+        let miner_data = MinerData::new(script(5), vec![]);
+
+        let preview = cbm.coinbase_preview(1000, miner_data, &ghostdag_data, &mergeset_rewards, &mergeset_non_daa).unwrap();
+
+        assert_eq!(preview.blue_score, 90);
+        assert_eq!(preview.subsidy, cbm.calc_block_subsidy(1000));
+        assert_eq!(
+            preview.merged_block_rewards.iter().map(|r| (r.hash, r.is_blue)).collect::<Vec<_>>(),
+            vec![(blue1, true), (blue2, true), (red, false)]
+        );
+        // The red reward is paid to the merging block miner
+        assert_eq!(preview.merged_block_rewards[2].script_public_key, script(5));
+        assert_eq!(
+            preview.outputs.iter().map(|o| (o.value, o.script_public_key.clone())).collect::<Vec<_>>(),
+            vec![(520, script(1)), (500, script(2)), (507, script(5))]
+        );
+    }
 }
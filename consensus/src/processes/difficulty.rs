@@ -1,6 +1,9 @@
-use crate::model::stores::{block_window_cache::BlockWindowHeap, ghostdag::GhostdagData, headers::HeaderStoreReader};
-use consensus_core::{BlockHashSet, BlueWorkType};
-use hashes::Hash;
+use crate::model::stores::{block_window_cache::BlockWindow, ghostdag::GhostdagData, headers::HeaderStoreReader};
+use consensus_core::{
+    config::params::WindowParams,
+    time::{DurationMs, TimestampMs},
+    BlockHashSet, BlueWorkType,
+};
 use math::{Uint256, Uint320};
 use std::{
     cmp::{max, Ordering},
@@ -14,34 +17,42 @@ use itertools::Itertools;
 pub struct DifficultyManager<T: HeaderStoreReader> {
     headers_store: Arc<T>,
     genesis_bits: u32,
-    difficulty_adjustment_window_size: usize,
-    target_time_per_block: u64,
+    difficulty_adjustment_window: WindowParams,
+    target_time_per_block: DurationMs,
 }
 
 impl<T: HeaderStoreReader> DifficultyManager<T> {
     pub fn new(
         headers_store: Arc<T>,
         genesis_bits: u32,
-        difficulty_adjustment_window_size: usize,
-        target_time_per_block: u64,
+        difficulty_adjustment_window: WindowParams,
+        target_time_per_block: DurationMs,
     ) -> Self {
-        Self { headers_store, difficulty_adjustment_window_size, genesis_bits, target_time_per_block }
+        Self { headers_store, difficulty_adjustment_window, genesis_bits, target_time_per_block }
     }
 
     pub fn calc_daa_score_and_non_daa_mergeset_blocks(
         &self,
-        window_hashes: &mut impl ExactSizeIterator<Item = Hash>,
+        window: &BlockWindow,
         ghostdag_data: &GhostdagData,
     ) -> (u64, BlockHashSet) {
         let mergeset: BlockHashSet = ghostdag_data.unordered_mergeset().collect();
-        let mergeset_daa: BlockHashSet = window_hashes.filter(|h| mergeset.contains(h)).take(mergeset.len()).collect();
-        let mergeset_non_daa: BlockHashSet = mergeset.difference(&mergeset_daa).copied().collect();
         let sp_daa_score = self.headers_store.get_daa_score(ghostdag_data.selected_parent).unwrap();
-
-        (sp_daa_score + mergeset_daa.len() as u64, mergeset_non_daa)
+        let mergeset_non_daa: BlockHashSet = if window.is_sampled {
+            // A sampled window misses most mergeset blocks, so the DAA blocks are rather the ones within a full window
+            // worth of DAA score below the selected parent
+            let daa_score_lower_bound = sp_daa_score.saturating_sub(self.difficulty_adjustment_window.size as u64);
+            mergeset.iter().copied().filter(|&h| self.headers_store.get_daa_score(h).unwrap() < daa_score_lower_bound).collect()
+        } else {
+            let mergeset_daa: BlockHashSet =
+                window.iter().map(|item| item.0.hash).filter(|h| mergeset.contains(h)).take(mergeset.len()).collect();
+            mergeset.difference(&mergeset_daa).copied().collect()
+        };
+
+        (sp_daa_score + (mergeset.len() - mergeset_non_daa.len()) as u64, mergeset_non_daa)
     }
 
-    pub fn calculate_difficulty_bits(&self, window: &BlockWindowHeap) -> u32 {
+    pub fn calculate_difficulty_bits(&self, window: &BlockWindow) -> u32 {
         let mut difficulty_blocks: Vec<DifficultyBlock> = window
             .iter()
             .map(|item| {
@@ -50,8 +61,15 @@ impl<T: HeaderStoreReader> DifficultyManager<T> {
             })
             .collect();
 
+        // A sampled block stands for the `sample_rate` blocks of the window between consecutive samples
+        let (window_size, sample_rate) = if window.is_sampled {
+            (self.difficulty_adjustment_window.sampled_size, self.difficulty_adjustment_window.sample_rate)
+        } else {
+            (self.difficulty_adjustment_window.size, 1)
+        };
+
         // Until there are enough blocks for a full block window the difficulty should remain constant.
-        if difficulty_blocks.len() < self.difficulty_adjustment_window_size {
+        if difficulty_blocks.len() < window_size {
             return self.genesis_bits;
         }
 
@@ -69,7 +87,10 @@ impl<T: HeaderStoreReader> DifficultyManager<T> {
         let targets_sum: Uint320 =
             difficulty_blocks.into_iter().map(|diff_block| Uint320::from(Uint256::from_compact_target_bits(diff_block.bits))).sum();
         let average_target = targets_sum / (difficulty_blocks_len as u64);
-        let new_target = average_target * max(max_ts - min_ts, 1) / self.target_time_per_block / difficulty_blocks_len as u64;
+        let window_duration = max(max_ts - min_ts, DurationMs::from_millis(1));
+        let new_target = average_target * window_duration.as_millis()
+            / self.target_time_per_block.as_millis()
+            / (difficulty_blocks_len as u64 * sample_rate);
         Uint256::try_from(new_target).expect("Expected target should be less than 2^256").compact_target_bits()
     }
 }
@@ -86,9 +107,20 @@ pub fn calc_work(bits: u32) -> BlueWorkType {
     res.try_into().expect("Work should not exceed 2**192")
 }
 
+/// Returns the difficulty of `bits` as the ratio between the maximal target, 2^255 - 1, and the target of `bits`
+pub fn calc_difficulty(bits: u32) -> f64 {
+    let target = Uint256::from_compact_target_bits(bits);
+    if target.is_zero() {
+        return 0.0;
+    }
+    // The precision of f64 suffices for reporting purposes
+    let as_f64 = |value: Uint256| value.0.iter().rev().fold(0.0, |acc, &limb| acc * 18446744073709551616.0 + limb as f64);
+    as_f64(Uint256::MAX >> 1) / as_f64(target)
+}
+
 #[derive(Eq)]
 struct DifficultyBlock {
-    timestamp: u64,
+    timestamp: TimestampMs,
     bits: u32,
     sortable_block: SortableBlock,
 }
@@ -3,19 +3,55 @@ use consensus_core::{
     tx::{Transaction, TransactionInput, TransactionOutput},
 };
 use hashes::HASH_SIZE;
+use std::ops::AddAssign;
 
+/// The mass of a transaction (or of a set of transactions), split into its compute and storage components
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mass {
+    /// Mass reflecting the processing cost: serialized size, script public key bytes and sig ops
+    pub compute: u64,
+    /// Mass reflecting the persistent storage cost, i.e., the growth of the UTXO set
+    pub storage: u64,
+}
+
+impl Mass {
+    pub fn new(compute: u64, storage: u64) -> Self {
+        Self { compute, storage }
+    }
+
+    /// The combined mass used for limit enforcement. Both components are bounded by the same
+    /// limit, hence the combined mass is the maximum of the two (and not their sum)
+    pub fn combined(&self) -> u64 {
+        self.compute.max(self.storage)
+    }
+}
+
+impl AddAssign for Mass {
+    fn add_assign(&mut self, rhs: Self) {
+        self.compute += rhs.compute;
+        self.storage += rhs.storage;
+    }
+}
+
+#[derive(Clone)]
 pub struct MassCalculator {
     mass_per_tx_byte: u64,
     mass_per_script_pub_key_byte: u64,
     mass_per_sig_op: u64,
+    storage_mass_per_utxo: u64,
 }
 
 impl MassCalculator {
-    pub fn new(mass_per_tx_byte: u64, mass_per_script_pub_key_byte: u64, mass_per_sig_op: u64) -> Self {
-        Self { mass_per_tx_byte, mass_per_script_pub_key_byte, mass_per_sig_op }
+    pub fn new(mass_per_tx_byte: u64, mass_per_script_pub_key_byte: u64, mass_per_sig_op: u64, storage_mass_per_utxo: u64) -> Self {
+        Self { mass_per_tx_byte, mass_per_script_pub_key_byte, mass_per_sig_op, storage_mass_per_utxo }
+    }
+
+    /// Calculates the full mass of the transaction, see [`Mass`]
+    pub fn calc_tx_mass(&self, tx: &Transaction) -> Mass {
+        Mass::new(self.calc_tx_compute_mass(tx), self.calc_tx_storage_mass(tx))
     }
 
-    pub fn calc_tx_mass(&self, tx: &Transaction) -> u64 {
+    pub fn calc_tx_compute_mass(&self, tx: &Transaction) -> u64 {
         if tx.is_coinbase() {
             return 0;
         }
@@ -34,6 +70,18 @@ impl MassCalculator {
 
         mass_for_size + total_script_public_key_mass + total_sigops_mass
     }
+
+    /// Calculates the storage mass of the transaction. The storage mass is bounded by the UTXO set growth
+    /// caused by the transaction, that is, only outputs exceeding the number of spent inputs are charged.
+    /// Transactions which do not increase the UTXO set (e.g. compounding transactions) have zero storage mass
+    pub fn calc_tx_storage_mass(&self, tx: &Transaction) -> u64 {
+        if tx.is_coinbase() {
+            return 0;
+        }
+
+        let utxo_set_growth = tx.outputs.len().saturating_sub(tx.inputs.len()) as u64;
+        utxo_set_growth * self.storage_mass_per_utxo
+    }
 }
 
 // transaction_estimated_serialized_size is the estimated size of a transaction in some
@@ -87,3 +135,59 @@ fn transaction_output_estimated_serialized_size(output: &TransactionOutput) -> u
     size += output.script_public_key.script().len() as u64;
     size
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::{
+        subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+        tx::{ScriptPublicKey, TransactionOutpoint},
+    };
+
+    fn test_tx(num_inputs: usize, num_outputs: usize) -> Transaction {
+        Transaction::new(
+            0,
+            (0..num_inputs)
+                .map(|i| TransactionInput::new(TransactionOutpoint::new((i as u64).into(), 0), vec![0; 66], 0, 1))
+                .collect(),
+            (0..num_outputs).map(|_| TransactionOutput::new(1000, ScriptPublicKey::from_vec(0, vec![0; 34]))).collect(),
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_storage_mass() {
+        let calculator = MassCalculator::new(1, 10, 1000, 5000);
+
+        // Compounding and balanced transactions do not grow the UTXO set
+        assert_eq!(calculator.calc_tx_storage_mass(&test_tx(3, 1)), 0);
+        assert_eq!(calculator.calc_tx_storage_mass(&test_tx(2, 2)), 0);
+
+        // Each UTXO created beyond the spent inputs is charged
+        assert_eq!(calculator.calc_tx_storage_mass(&test_tx(1, 2)), 5000);
+        assert_eq!(calculator.calc_tx_storage_mass(&test_tx(1, 10)), 9 * 5000);
+
+        // Coinbase transactions are massless
+        let mut coinbase = test_tx(0, 10);
+        coinbase.subnetwork_id = SUBNETWORK_ID_COINBASE;
+        assert_eq!(calculator.calc_tx_mass(&coinbase), Mass::default());
+    }
+
+    #[test]
+    fn test_combined_mass() {
+        let calculator = MassCalculator::new(1, 10, 1000, 5000);
+
+        let tx = test_tx(1, 2);
+        let mass = calculator.calc_tx_mass(&tx);
+        assert_eq!(mass.compute, calculator.calc_tx_compute_mass(&tx));
+        assert_eq!(mass.combined(), mass.compute.max(5000));
+
+        let tx = test_tx(1, 20);
+        let mass = calculator.calc_tx_mass(&tx);
+        assert_eq!(mass.storage, 19 * 5000);
+        assert_eq!(mass.combined(), mass.storage);
+    }
+}
@@ -7,5 +7,6 @@ pub mod parents_builder;
 pub mod past_median_time;
 pub mod pruning;
 pub mod reachability;
+pub mod sync;
 pub mod transaction_validator;
 pub mod traversal_manager;
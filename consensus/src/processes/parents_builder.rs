@@ -198,6 +198,7 @@ mod tests {
     use consensus_core::{
         blockhash::{BlockHashes, ORIGIN},
         header::Header,
+        time::TimestampMs,
         BlockHashSet, HashMapCustomHasher,
     };
     use hashes::Hash;
@@ -220,7 +221,7 @@ mod tests {
             todo!()
         }
 
-        fn get_timestamp(&self, hash: hashes::Hash) -> Result<u64, StoreError> {
+        fn get_timestamp(&self, hash: hashes::Hash) -> Result<TimestampMs, StoreError> {
             todo!()
         }
 
@@ -294,7 +295,7 @@ mod tests {
                     hash_merkle_root: 1.into(),
                     accepted_id_merkle_root: 1.into(),
                     utxo_commitment: 1.into(),
-                    timestamp: 0,
+                    timestamp: Default::default(),
                     bits: 0,
                     nonce: 0,
                     daa_score: 0,
@@ -323,7 +324,7 @@ mod tests {
                     hash_merkle_root: 1.into(),
                     accepted_id_merkle_root: 1.into(),
                     utxo_commitment: 1.into(),
-                    timestamp: 0,
+                    timestamp: Default::default(),
                     bits: 0,
                     nonce: 0,
                     daa_score: 0,
@@ -352,7 +353,7 @@ mod tests {
                     hash_merkle_root: 1.into(),
                     accepted_id_merkle_root: 1.into(),
                     utxo_commitment: 1.into(),
-                    timestamp: 0,
+                    timestamp: Default::default(),
                     bits: 0,
                     nonce: 0,
                     daa_score: 0,
@@ -454,7 +455,7 @@ mod tests {
                         hash_merkle_root: 1.into(),
                         accepted_id_merkle_root: 1.into(),
                         utxo_commitment: 1.into(),
-                        timestamp: 0,
+                        timestamp: Default::default(),
                         bits: 0,
                         nonce: 0,
                         daa_score: 0,
@@ -514,7 +515,7 @@ mod tests {
                     hash_merkle_root: 1.into(),
                     accepted_id_merkle_root: 1.into(),
                     utxo_commitment: 1.into(),
-                    timestamp: 0,
+                    timestamp: Default::default(),
                     bits: 0,
                     nonce: 0,
                     daa_score: 0,
@@ -555,7 +556,7 @@ mod tests {
                         hash_merkle_root: 1.into(),
                         accepted_id_merkle_root: 1.into(),
                         utxo_commitment: 1.into(),
-                        timestamp: 0,
+                        timestamp: Default::default(),
                         bits: 0,
                         nonce: 0,
                         daa_score: 0,
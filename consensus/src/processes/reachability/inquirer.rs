@@ -185,7 +185,35 @@ fn assert_hashes_ordered(store: &(impl ReachabilityStoreReader + ?Sized), ordere
 mod tests {
     use super::super::tests::*;
     use super::*;
-    use crate::{model::stores::reachability::MemoryReachabilityStore, processes::reachability::interval::Interval};
+    use crate::{
+        constants::perf::{DEFAULT_REINDEX_DEPTH, DEFAULT_REINDEX_SLACK},
+        model::stores::reachability::MemoryReachabilityStore,
+        processes::reachability::interval::Interval,
+    };
+
+    /// The number of blocks added between reachability hints, as with the header batches of IBD
+    const HINT_INTERVAL: u64 = 1 << 10;
+
+    /// Adds the chain of blocks `first..first + count` above `parent`, hinting reachability every `HINT_INTERVAL`
+    /// blocks or after every block if `hint_each` is set. Returns the chain tip.
+    fn add_chain(store: &mut MemoryReachabilityStore, first: u64, count: u64, mut parent: Hash, hint_each: bool) -> Hash {
+        for i in first..first + count {
+            let hash: Hash = i.into();
+            add_block(store, hash, parent, &mut std::iter::empty::<Hash>()).unwrap();
+            if hint_each || (i - first + 1) % HINT_INTERVAL == 0 {
+                hint_virtual_selected_parent(store, hash).unwrap();
+            }
+            parent = hash;
+        }
+        parent
+    }
+
+    /// Asserts that the reindex root is a chain ancestor of `tip` at most `DEFAULT_REINDEX_DEPTH` below it
+    fn assert_reindex_root_near(store: &MemoryReachabilityStore, tip: Hash) {
+        let root = store.get_reindex_root().unwrap();
+        assert!(is_chain_ancestor_of(store, root, tip).unwrap());
+        assert!(store.get_height(tip).unwrap() - store.get_height(root).unwrap() <= DEFAULT_REINDEX_DEPTH);
+    }
 
     #[test]
     fn test_add_tree_blocks() {
@@ -278,4 +306,36 @@ mod tests {
         assert!(store.are_anticone(11, 6));
         assert!(store.are_anticone(11, 9));
     }
+
+    #[test]
+    fn test_reindex_root_follows_hinted_chain() {
+        let mut store = MemoryReachabilityStore::new();
+        init(&mut store).unwrap();
+        let genesis: Hash = 1.into();
+        add_block(&mut store, genesis, blockhash::ORIGIN, &mut std::iter::empty::<Hash>()).unwrap();
+
+        let local_tip = add_chain(&mut store, 2, 2 * DEFAULT_REINDEX_SLACK, genesis, true);
+        assert_reindex_root_near(&store, local_tip);
+
+        // A chain synced from genesis takes over the reindex root once higher than the local chain by the slack
+        let synced_tip = add_chain(&mut store, 1 << 32, 4 * DEFAULT_REINDEX_SLACK, genesis, false);
+        assert_reindex_root_near(&store, synced_tip);
+        store.validate_intervals(blockhash::ORIGIN).unwrap();
+    }
+
+    #[test]
+    #[ignore]
+    fn test_deep_chain_stress() {
+        let mut store = MemoryReachabilityStore::new();
+        init(&mut store).unwrap();
+        let genesis: Hash = 1.into();
+        add_block(&mut store, genesis, blockhash::ORIGIN, &mut std::iter::empty::<Hash>()).unwrap();
+
+        // Insertion stays amortized O(1) only if the reindex root keeps up with the chain
+        let local_tip = add_chain(&mut store, 2, 1 << 20, genesis, false);
+        assert_reindex_root_near(&store, local_tip);
+        let synced_tip = add_chain(&mut store, 1 << 32, 3 << 19, genesis, false);
+        assert_reindex_root_near(&store, synced_tip);
+        assert!(!store.get_interval(synced_tip).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::model::{
+    services::reachability::{MTReachabilityService, ReachabilityService},
+    stores::{ghostdag::GhostdagStoreReader, reachability::ReachabilityStoreReader, statuses::StatusesStoreReader},
+};
+use consensus_core::{
+    blockstatus::BlockStatus,
+    errors::sync::{SyncManagerError, SyncManagerResult},
+};
+use hashes::Hash;
+
+/// Provides the DAG queries required for syncing peers: selected chain block locators,
+/// topologically ordered block ranges and missing block bodies
+#[derive(Clone)]
+pub struct SyncManager<S: GhostdagStoreReader, T: ReachabilityStoreReader, U: StatusesStoreReader> {
+    ghostdag_store: Arc<S>,
+    reachability_service: MTReachabilityService<T>,
+    statuses_store: Arc<U>,
+}
+
+impl<S: GhostdagStoreReader, T: ReachabilityStoreReader, U: StatusesStoreReader> SyncManager<S, T, U> {
+    pub fn new(ghostdag_store: Arc<S>, reachability_service: MTReachabilityService<T>, statuses_store: Arc<U>) -> Self {
+        Self { ghostdag_store, reachability_service, statuses_store }
+    }
+
+    /// Returns a locator of the selected chain of `high` down to `low`. The locator starts at `high` and
+    /// samples the chain with exponentially growing blue score gaps, always ending with `low`.
+    pub fn create_headers_selected_chain_block_locator(&self, low: Hash, high: Hash) -> SyncManagerResult<Vec<Hash>> {
+        self.ensure_in_selected_chain(low, high)?;
+        if low == high {
+            return Ok(vec![low]);
+        }
+
+        let mut locator = Vec::new();
+        let mut step = 1u64;
+        let mut next_blue_score = self.ghostdag_store.get_blue_score(high).unwrap();
+        for current in self.reachability_service.backward_chain_iterator(high, low, true) {
+            let blue_score = self.ghostdag_store.get_blue_score(current).unwrap();
+            if current == low || blue_score <= next_blue_score {
+                locator.push(current);
+                next_blue_score = blue_score.saturating_sub(step);
+                step = step.saturating_mul(2);
+            }
+        }
+        Ok(locator)
+    }
+
+    /// Returns the hashes of blocks in the past of `high` and not in the past of `low`, in topological order.
+    ///
+    /// The selected chain is walked up from `low`, adding each chain block along with its mergeset, until adding
+    /// the next chain block would exceed `max_blocks` hashes (at least one chain block is always added). Returns
+    /// the hashes along with the highest chain block reached, which is `low` if no hashes were added.
+    pub fn get_hashes_between(&self, low: Hash, high: Hash, max_blocks: usize) -> SyncManagerResult<(Vec<Hash>, Hash)> {
+        self.ensure_in_selected_chain(low, high)?;
+
+        let mut hashes = Vec::new();
+        let mut highest_reached = low;
+        for chain_block in self.reachability_service.forward_chain_iterator(low, high, true).skip(1) {
+            let ghostdag_data = self.ghostdag_store.get_data(chain_block).unwrap();
+            let mut chunk: Vec<Hash> = ghostdag_data
+                .consensus_ordered_mergeset_without_selected_parent(self.ghostdag_store.as_ref())
+                .filter(|&hash| !self.reachability_service.is_dag_ancestor_of(hash, low))
+                .collect();
+            chunk.push(chain_block);
+            if !hashes.is_empty() && hashes.len() + chunk.len() > max_blocks {
+                break;
+            }
+            hashes.extend(chunk);
+            highest_reached = chain_block;
+        }
+        Ok((hashes, highest_reached))
+    }
+
+    /// Returns the hashes of header-only blocks in the past of `high` and not in the past of `low`, in topological order
+    pub fn get_missing_block_body_hashes(&self, low: Hash, high: Hash) -> SyncManagerResult<Vec<Hash>> {
+        let (hashes, _) = self.get_hashes_between(low, high, usize::MAX)?;
+        Ok(hashes.into_iter().filter(|&hash| self.statuses_store.get(hash).unwrap() == BlockStatus::StatusHeaderOnly).collect())
+    }
+
+    fn ensure_in_selected_chain(&self, low: Hash, high: Hash) -> SyncManagerResult<()> {
+        for hash in [low, high] {
+            if !self.ghostdag_store.has(hash).unwrap() {
+                return Err(SyncManagerError::BlockNotFound(hash));
+            }
+        }
+        if !self.reachability_service.is_chain_ancestor_of(low, high) {
+            return Err(SyncManagerError::NotInSelectedChain(low, high));
+        }
+        Ok(())
+    }
+}
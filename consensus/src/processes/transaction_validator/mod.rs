@@ -1,19 +1,13 @@
 pub mod errors;
+pub mod sig_cache;
 pub mod transaction_validator_populated;
 mod tx_validation_in_isolation;
 pub mod tx_validation_not_utxo_related;
-use crate::model::stores::{database::prelude::Cache, ghostdag};
+use crate::model::stores::ghostdag;
+use sig_cache::{SigCache, SIG_CACHE_SIZE};
 
 pub use tx_validation_in_isolation::*;
 
-// TODO: Move it to the script engine once it's ready
-#[derive(Clone, Hash, PartialEq, Eq)]
-pub(crate) struct SigCacheKey {
-    signature: secp256k1::schnorr::Signature,
-    pub_key: secp256k1::XOnlyPublicKey,
-    message: secp256k1::Message,
-}
-
 #[derive(Clone)]
 pub struct TransactionValidator {
     max_tx_inputs: usize,
@@ -23,7 +17,10 @@ pub struct TransactionValidator {
     ghostdag_k: ghostdag::KType,
     coinbase_payload_script_public_key_max_len: u8,
     coinbase_maturity: u64,
-    sig_cache: Cache<SigCacheKey, bool>, // TODO: Move sig_cache to the script engine once it's ready
+
+    // Shared by all clones of the validator, so signatures verified during mempool
+    // validation are not verified again when the containing block is UTXO-verified
+    sig_cache: SigCache, // TODO: Move sig_cache to the script engine once it's ready
 }
 
 impl TransactionValidator {
@@ -44,7 +41,11 @@ impl TransactionValidator {
             ghostdag_k,
             coinbase_payload_script_public_key_max_len,
             coinbase_maturity,
-            sig_cache: Cache::new(10_000),
+            sig_cache: SigCache::new(SIG_CACHE_SIZE),
         }
     }
+
+    pub fn sig_cache(&self) -> &SigCache {
+        &self.sig_cache
+    }
 }
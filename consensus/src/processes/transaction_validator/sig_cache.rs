@@ -0,0 +1,67 @@
+use crate::model::stores::database::prelude::{Cache, CachePolicy};
+use consensus_core::tx::TransactionId;
+use secp256k1::{schnorr::Signature, Message, XOnlyPublicKey};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// The number of successful signature verifications kept by the cache
+pub const SIG_CACHE_SIZE: usize = 10_000;
+
+/// Identifies a successful verification of the signature of a transaction input. The signed message is part of
+/// the key, since it commits to the spent UTXO entry, which the transaction id alone does not.
+// TODO: Move it to the script engine once it's ready
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub(crate) struct SigCacheKey {
+    pub tx_id: TransactionId,
+    pub signature: Signature,
+    pub pub_key: XOnlyPublicKey,
+    pub message: Message,
+}
+
+/// The hit and miss counts of the lookups of a [`SigCache`]
+#[derive(Default)]
+pub struct SigCacheCounters {
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+}
+
+/// A concurrent cache of successful signature verifications, shared by all clones so that signatures verified
+/// during mempool acceptance are not verified again when the containing block is validated
+#[derive(Clone)]
+pub struct SigCache {
+    cache: Cache<SigCacheKey, ()>,
+    counters: Arc<SigCacheCounters>,
+}
+
+impl SigCache {
+    pub fn new(size: usize) -> Self {
+        Self { cache: Cache::new(CachePolicy::Count(size)), counters: Default::default() }
+    }
+
+    /// Returns whether the signature was already verified successfully, counting the lookup
+    pub(crate) fn contains(&self, key: &SigCacheKey) -> bool {
+        let found = self.cache.contains_key(key);
+        let counter = if found { &self.counters.hits } else { &self.counters.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Records a successful verification. Failed verifications are never cached
+    pub(crate) fn insert(&self, key: SigCacheKey) {
+        self.cache.insert(key, ());
+    }
+
+    pub fn counters(&self) -> &Arc<SigCacheCounters> {
+        &self.counters
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
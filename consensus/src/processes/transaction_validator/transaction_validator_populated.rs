@@ -9,7 +9,8 @@ use consensus_core::{
 
 use super::{
     errors::{TxResult, TxRuleError},
-    SigCacheKey, TransactionValidator,
+    sig_cache::SigCacheKey,
+    TransactionValidator,
 };
 
 impl TransactionValidator {
@@ -104,27 +105,97 @@ impl TransactionValidator {
     fn check_scripts(&self, tx: &impl VerifiableTransaction) -> TxResult<()> {
         let mut reused_values = SigHashReusedValues::new();
         for (i, (input, entry)) in tx.populated_inputs().enumerate() {
+            let sig_hash = calc_schnorr_signature_hash(tx, i, SIG_HASH_ALL, &mut reused_values);
+
             // TODO: this is a temporary implementation and not ready for consensus since any invalid signature
             // will crash the node. We need to replace it with a proper script engine once it's ready.
             let pk = &entry.script_public_key.script()[1..33];
             let pk = secp256k1::XOnlyPublicKey::from_slice(pk).unwrap();
             let sig = secp256k1::schnorr::Signature::from_slice(&input.signature_script[1..65]).unwrap();
-            let sig_hash = calc_schnorr_signature_hash(tx, i, SIG_HASH_ALL, &mut reused_values);
             let msg = secp256k1::Message::from_slice(sig_hash.as_bytes().as_slice()).unwrap();
-            let sig_cache_key = SigCacheKey { signature: sig, pub_key: pk, message: msg };
-            match self.sig_cache.get(&sig_cache_key) {
-                Some(valid) => {
-                    assert!(valid, "invalid signature in sig cache");
-                }
-                None => {
-                    // TODO: Find a way to parallelize this part. This will be less trivial
-                    // once this code is inside the script engine.
-                    sig.verify(&msg, &pk).unwrap();
-                    self.sig_cache.insert(sig_cache_key, true);
-                }
+            let sig_cache_key = SigCacheKey { tx_id: tx.tx().id(), signature: sig, pub_key: pk, message: msg };
+            // A hit means the signature was already verified against this exact UTXO entry, e.g., during mempool validation
+            if !self.sig_cache.contains(&sig_cache_key) {
+                // TODO: Find a way to parallelize this part. This will be less trivial
+                // once this code is inside the script engine.
+                sig.verify(&msg, &pk).unwrap();
+                self.sig_cache.insert(sig_cache_key);
             }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::{
+        config::params::MAINNET_PARAMS,
+        sign::sign,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{
+            MutableTransaction, ScriptPublicKey, ScriptVec, Transaction, TransactionId, TransactionInput, TransactionOutpoint,
+            TransactionOutput, UtxoEntry,
+        },
+    };
+    use secp256k1::{rand, Secp256k1};
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn sig_cache_test() {
+        let params = MAINNET_PARAMS.clone();
+        let tv = TransactionValidator::new(
+            params.max_tx_inputs,
+            params.max_tx_outputs,
+            params.max_signature_script_len,
+            params.max_script_public_key_len,
+            params.ghostdag_k,
+            params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_maturity,
+        );
+
+        let (secret_key, public_key) = Secp256k1::new().generate_keypair(&mut rand::thread_rng());
+        let script_public_key = ScriptPublicKey::new(0, ScriptVec::from_slice(&public_key.serialize()));
+        let outpoint = TransactionOutpoint::new(TransactionId::from_slice(&[1; 32]), 0);
+        let unsigned_tx = Transaction::new(
+            0,
+            vec![TransactionInput::new(outpoint, vec![], 0, 0)],
+            vec![TransactionOutput::new(50, script_public_key.clone())],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        );
+        let entries = vec![UtxoEntry::new(100, script_public_key.clone(), 0, false)];
+        let signed_tx = sign(MutableTransaction::with_entries(unsigned_tx, entries), secret_key.secret_bytes());
+
+        let key_for = |tx: &MutableTransaction| SigCacheKey {
+            tx_id: tx.tx.id(),
+            signature: secp256k1::schnorr::Signature::from_slice(&tx.tx.inputs[0].signature_script[1..65]).unwrap(),
+            pub_key: secp256k1::XOnlyPublicKey::from_slice(&public_key.serialize()[1..33]).unwrap(),
+            message: secp256k1::Message::from_slice(
+                calc_schnorr_signature_hash(&tx.as_verifiable(), 0, SIG_HASH_ALL, &mut SigHashReusedValues::new())
+                    .as_bytes()
+                    .as_slice(),
+            )
+            .unwrap(),
+        };
+
+        // A successful verification populates the cache, which is shared with clones of the validator
+        let shared = tv.clone();
+        let counters = shared.sig_cache().counters();
+        assert!(shared.sig_cache().is_empty());
+        tv.check_scripts(&signed_tx.as_verifiable()).unwrap();
+        assert_eq!(shared.sig_cache().len(), 1);
+        assert_eq!((counters.hits.load(Ordering::Relaxed), counters.misses.load(Ordering::Relaxed)), (0, 1));
+        shared.check_scripts(&signed_tx.as_verifiable()).unwrap();
+        assert_eq!((counters.hits.load(Ordering::Relaxed), counters.misses.load(Ordering::Relaxed)), (1, 1));
+
+        // A change to the spent UTXO entry results in a different key
+        let modified_tx =
+            MutableTransaction::with_entries(signed_tx.tx.clone(), vec![UtxoEntry::new(101, script_public_key, 0, false)]);
+        assert!(key_for(&modified_tx) != key_for(&signed_tx));
+        assert!(!shared.sig_cache().contains(&key_for(&modified_tx)));
+    }
+}
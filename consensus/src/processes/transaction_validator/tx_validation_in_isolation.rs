@@ -156,9 +156,9 @@ mod tests {
 
     use crate::{
         constants::TX_VERSION,
-        params::MAINNET_PARAMS,
         processes::transaction_validator::{errors::TxRuleError, TransactionValidator},
     };
+    use consensus_core::config::params::MAINNET_PARAMS;
 
     #[test]
     fn validate_tx_in_isolation_test() {
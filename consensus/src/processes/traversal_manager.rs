@@ -2,68 +2,112 @@ use std::{cmp::Reverse, collections::BinaryHeap, ops::Deref, sync::Arc};
 
 use crate::{
     model::stores::{
-        block_window_cache::{BlockWindowCacheReader, BlockWindowHeap},
+        block_window_cache::{BlockWindow, BlockWindowCacheReader, BlockWindowHeap},
         ghostdag::{GhostdagData, GhostdagStoreReader},
+        headers::HeaderStoreReader,
     },
     processes::ghostdag::ordering::SortableBlock,
 };
+use consensus_core::config::params::WindowParams;
 use consensus_core::{blockhash::BlockHashExtensions, BlueWorkType};
 use hashes::Hash;
 use kaspa_utils::refs::Refs;
 
+/// The type of a block window, determining its sizes, sample rate and cache
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowType {
+    DifficultyWindow,
+    MedianTimeWindow,
+}
+
 #[derive(Clone)]
-pub struct DagTraversalManager<T: GhostdagStoreReader, U: BlockWindowCacheReader> {
+pub struct DagTraversalManager<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> {
     genesis_hash: Hash,
     ghostdag_store: Arc<T>,
+    headers_store: Arc<V>,
     block_window_cache_for_difficulty: Arc<U>,
     block_window_cache_for_past_median_time: Arc<U>,
-    difficulty_window_size: usize,
-    past_median_time_window_size: usize,
+    difficulty_window: WindowParams,
+    past_median_time_window: WindowParams,
+    sampling_activation_daa_score: u64,
 }
 
-impl<T: GhostdagStoreReader, U: BlockWindowCacheReader> DagTraversalManager<T, U> {
+impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> DagTraversalManager<T, U, V> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         genesis_hash: Hash,
         ghostdag_store: Arc<T>,
+        headers_store: Arc<V>,
         block_window_cache_for_difficulty: Arc<U>,
         block_window_cache_for_past_median_time: Arc<U>,
-        difficulty_window_size: usize,
-        past_median_time_window_size: usize,
+        difficulty_window: WindowParams,
+        past_median_time_window: WindowParams,
+        sampling_activation_daa_score: u64,
     ) -> Self {
         Self {
             genesis_hash,
             ghostdag_store,
+            headers_store,
             block_window_cache_for_difficulty,
-            difficulty_window_size,
             block_window_cache_for_past_median_time,
-            past_median_time_window_size,
+            difficulty_window,
+            past_median_time_window,
+            sampling_activation_daa_score,
         }
     }
+
+    /// Returns whether the windows of a block with `ghostdag_data` are sampled, i.e., whether its selected parent
+    /// reached the sampling activation DAA score
+    pub fn is_sampling_active(&self, ghostdag_data: &GhostdagData) -> bool {
+        self.headers_store.get_daa_score(ghostdag_data.selected_parent).unwrap() >= self.sampling_activation_daa_score
+    }
+
+    /// Returns the window of `window_type` for a block with `high_ghostdag_data`. Once sampling is active, the window
+    /// holds only the blocks whose DAA score is a multiple of the sample rate, and thus far fewer blocks than a full
+    /// window spanning about the same part of the DAG
+    pub fn window(&self, high_ghostdag_data: &GhostdagData, window_type: WindowType) -> BlockWindow {
+        let (cache, window) = match window_type {
+            WindowType::DifficultyWindow => (&self.block_window_cache_for_difficulty, self.difficulty_window),
+            WindowType::MedianTimeWindow => (&self.block_window_cache_for_past_median_time, self.past_median_time_window),
+        };
+        let is_sampled = self.is_sampling_active(high_ghostdag_data);
+        let (window_size, sample_rate) = if is_sampled { (window.sampled_size, window.sample_rate) } else { (window.size, 1) };
+        BlockWindow::new(self.build_block_window(high_ghostdag_data, window_size, sample_rate, Some((cache, is_sampled))), is_sampled)
+    }
+
+    /// Returns the full window of the `window_size` blocks with the highest blue work in the past of a block with
+    /// `high_ghostdag_data`
     pub fn block_window(&self, high_ghostdag_data: &GhostdagData, window_size: usize) -> BlockWindowHeap {
+        self.build_block_window(high_ghostdag_data, window_size, 1, None)
+    }
+
+    fn build_block_window(
+        &self,
+        high_ghostdag_data: &GhostdagData,
+        window_size: usize,
+        sample_rate: u64,
+        cache: Option<(&Arc<U>, bool)>,
+    ) -> BlockWindowHeap {
         if window_size == 0 {
             return BlockWindowHeap::new();
         }
 
-        let cache = if window_size == self.difficulty_window_size {
-            Some(&self.block_window_cache_for_difficulty)
-        } else if window_size == self.past_median_time_window_size {
-            Some(&self.block_window_cache_for_past_median_time)
-        } else {
-            None
-        };
+        if let Some((cache, is_sampled)) = cache {
+            if let Some(selected_parent_window) = cache.get(&high_ghostdag_data.selected_parent) {
+                // Around the sampling activation, the cached window of the selected parent might be of the other kind
+                if selected_parent_window.is_sampled == is_sampled {
+                    let mut window_heap = BoundedSizeBlockHeap::from_binary_heap(window_size, selected_parent_window.blocks.clone());
+                    if high_ghostdag_data.selected_parent != self.genesis_hash {
+                        self.try_push_mergeset(
+                            &mut window_heap,
+                            high_ghostdag_data,
+                            self.ghostdag_store.get_blue_work(high_ghostdag_data.selected_parent).unwrap(),
+                            sample_rate,
+                        );
+                    }
 
-        if let Some(cache) = cache {
-            if let Some(selected_parent_binary_heap) = cache.get(&high_ghostdag_data.selected_parent) {
-                let mut window_heap = BoundedSizeBlockHeap::from_binary_heap(window_size, (*selected_parent_binary_heap).clone());
-                if high_ghostdag_data.selected_parent != self.genesis_hash {
-                    self.try_push_mergeset(
-                        &mut window_heap,
-                        high_ghostdag_data,
-                        self.ghostdag_store.get_blue_work(high_ghostdag_data.selected_parent).unwrap(),
-                    );
+                    return window_heap.binary_heap;
                 }
-
-                return window_heap.binary_heap;
             }
         }
 
@@ -78,7 +122,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader> DagTraversalManager<T, U
             }
             let parent_ghostdag = self.ghostdag_store.get_data(current_ghostdag.selected_parent).unwrap();
             let selected_parent_blue_work_too_low =
-                self.try_push_mergeset(&mut window_heap, &current_ghostdag, parent_ghostdag.blue_work);
+                self.try_push_mergeset(&mut window_heap, &current_ghostdag, parent_ghostdag.blue_work, sample_rate);
             // No need to further iterate since past of selected parent has even lower blue work
             if selected_parent_blue_work_too_low {
                 break;
@@ -94,20 +138,33 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader> DagTraversalManager<T, U
         heap: &mut BoundedSizeBlockHeap,
         ghostdag_data: &GhostdagData,
         selected_parent_blue_work: BlueWorkType,
+        sample_rate: u64,
     ) -> bool {
         // If the window is full and the selected parent is less than the minimum then we break
         // because this means that there cannot be any more blocks in the past with higher blue work
-        if !heap.try_push(ghostdag_data.selected_parent, selected_parent_blue_work) {
+        if !heap.can_push(ghostdag_data.selected_parent, selected_parent_blue_work) {
             return true;
         }
+        if self.is_in_sample(ghostdag_data.selected_parent, sample_rate) {
+            heap.try_push(ghostdag_data.selected_parent, selected_parent_blue_work);
+        }
         for block in ghostdag_data.descending_mergeset_without_selected_parent(self.ghostdag_store.deref()) {
             // If it's smaller than minimum then we won't be able to add the rest because we iterate in descending blue work order.
-            if !heap.try_push(block.hash, block.blue_work) {
+            if !heap.can_push(block.hash, block.blue_work) {
                 break;
             }
+            if self.is_in_sample(block.hash, sample_rate) {
+                heap.try_push(block.hash, block.blue_work);
+            }
         }
         false
     }
+
+    /// Returns whether a block belongs to a window sampled at `sample_rate`. A DAA score cannot be ground by miners,
+    /// unlike the hash of a block
+    fn is_in_sample(&self, hash: Hash, sample_rate: u64) -> bool {
+        sample_rate == 1 || self.headers_store.get_daa_score(hash).unwrap() % sample_rate == 0
+    }
 }
 
 struct BoundedSizeBlockHeap {
@@ -124,7 +181,7 @@ impl BoundedSizeBlockHeap {
         Self { size, binary_heap }
     }
 
-    fn try_push(&mut self, hash: Hash, blue_work: BlueWorkType) -> bool {
+    fn can_push(&self, hash: Hash, blue_work: BlueWorkType) -> bool {
         let r_sortable_block = Reverse(SortableBlock { hash, blue_work });
         if self.binary_heap.len() == self.size {
             if let Some(max) = self.binary_heap.peek() {
@@ -132,9 +189,18 @@ impl BoundedSizeBlockHeap {
                     return false; // Heap is full and the suggested block is greater than the max
                 }
             }
+        }
+        true
+    }
+
+    fn try_push(&mut self, hash: Hash, blue_work: BlueWorkType) -> bool {
+        if !self.can_push(hash, blue_work) {
+            return false;
+        }
+        if self.binary_heap.len() == self.size {
             self.binary_heap.pop(); // Remove the max block (because it's reverse, it'll be the block with the least blue work)
         }
-        self.binary_heap.push(r_sortable_block);
+        self.binary_heap.push(Reverse(SortableBlock { hash, blue_work }));
         true
     }
 }
@@ -11,7 +11,7 @@ pub fn header_from_precomputed_hash(hash: Hash, parents: Vec<Hash>) -> Header {
         accepted_id_merkle_root: Default::default(),
         utxo_commitment: Default::default(),
         nonce: 0,
-        timestamp: 0,
+        timestamp: Default::default(),
         daa_score: 0,
         bits: 0,
         blue_work: 0.into(),
@@ -0,0 +1,52 @@
+//!
+//! Feature matrix build tests
+//!
+//! Verifies that consensus and consensus-core can be built for minimal consumers (no RPC/P2P and no
+//! node runtime) as well as with all features enabled. These tests invoke cargo and are therefore
+//! ignored by default. They are run by CI with `cargo test -p consensus --test feature_matrix_tests -- --ignored`.
+//!
+
+use std::{path::Path, process::Command};
+
+fn cargo_check(package: &str, extra_args: &[&str]) {
+    let workspace_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let status = Command::new(env!("CARGO"))
+        .current_dir(workspace_dir)
+        .args(["check", "--package", package])
+        .args(extra_args)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "cargo check -p {} {:?} failed", package, extra_args);
+}
+
+/// Returns the list of (non-dev) dependency package names of `package` for the given feature args
+fn dependency_tree(package: &str, extra_args: &[&str]) -> String {
+    let workspace_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let output = Command::new(env!("CARGO"))
+        .current_dir(workspace_dir)
+        .args(["tree", "--package", package, "--edges", "normal", "--prefix", "none"])
+        .args(extra_args)
+        .output()
+        .expect("failed to run cargo");
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+#[ignore]
+fn test_minimal_consensus_build() {
+    cargo_check("consensus-core", &[]);
+    cargo_check("consensus", &["--no-default-features"]);
+
+    let tree = dependency_tree("consensus", &["--no-default-features"]);
+    for forbidden in ["rpc-core", "rpc-grpc", "tonic", "prost", "tokio", "ctrlc", "intertrait", "env_logger"] {
+        assert!(!tree.lines().any(|l| l.starts_with(&format!("{} ", forbidden))), "minimal consensus build depends on {}", forbidden);
+    }
+}
+
+#[test]
+#[ignore]
+fn test_full_consensus_build() {
+    cargo_check("consensus", &[]);
+    cargo_check("consensus", &["--all-features"]);
+}
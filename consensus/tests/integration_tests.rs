@@ -3,20 +3,32 @@
 //!
 
 use consensus::consensus::test_consensus::{create_temp_db, TestConsensus};
+use consensus::model::stores::database::prelude::CachePolicy;
 use consensus::model::stores::ghostdag::{GhostdagStoreReader, KType as GhostdagKType};
 use consensus::model::stores::headers::HeaderStoreReader;
 use consensus::model::stores::reachability::DbReachabilityStore;
-use consensus::params::{Params, DEVNET_PARAMS, MAINNET_PARAMS};
+use consensus::processes::reachability::inquirer::is_dag_ancestor_of;
 use consensus::processes::reachability::tests::{DagBlock, DagBuilder, StoreValidationExtensions};
+use consensus::processes::traversal_manager::WindowType;
+use consensus_core::api::{session::ConsensusSession, ConsensusApi, DynConsensus};
 use consensus_core::block::Block;
 use consensus_core::blockhash::new_unique;
 use consensus_core::blockstatus::BlockStatus;
+use consensus_core::coinbase::MinerData;
+use consensus_core::config::genesis::{GenesisBlock, DEVNET_GENESIS};
+use consensus_core::config::params::{Params, WindowParams, DEVNET_PARAMS, MAINNET_PARAMS};
 use consensus_core::constants::BLOCK_VERSION;
 use consensus_core::errors::block::{BlockProcessResult, RuleError};
+use consensus_core::errors::sync::SyncManagerError;
+use consensus_core::errors::tx::TxRuleError;
 use consensus_core::header::Header;
-use consensus_core::subnets::SubnetworkId;
-use consensus_core::tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput};
-use consensus_core::{blockhash, hashing, BlueWorkType};
+use consensus_core::notify::{BackpressurePolicy, Notification, NotificationConsumer, NotificationType, TransactionAcceptance};
+use consensus_core::sign::sign;
+use consensus_core::subnets::{SubnetworkId, SUBNETWORK_ID_NATIVE};
+use consensus_core::time::{DurationMs, TimestampMs};
+use consensus_core::trusted::{TrustedBlock, TrustedGhostdagData};
+use consensus_core::tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput};
+use consensus_core::{blockhash, hashing, BlockHashSet, BlueWorkType};
 use hashes::Hash;
 
 use flate2::read::GzDecoder;
@@ -31,7 +43,8 @@ use std::{
     future::Future,
     io::{self, BufRead, BufReader},
     str::{from_utf8, FromStr},
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
 mod common;
@@ -80,7 +93,7 @@ fn reachability_stretch_test(use_attack_json: bool) {
 
     // Act
     let (_temp_db_lifetime, db) = create_temp_db();
-    let mut store = DbReachabilityStore::new(db, 100000);
+    let mut store = DbReachabilityStore::new(db, CachePolicy::Count(100000));
     let mut builder = DagBuilder::new(&mut store);
 
     builder.init();
@@ -154,7 +167,7 @@ async fn consensus_sanity_test() {
     let wait_handles = consensus.init();
 
     consensus
-        .validate_and_insert_block(consensus.build_block_with_parents(genesis_child, vec![MAINNET_PARAMS.genesis_hash]).to_immutable())
+        .validate_and_insert_block(consensus.build_block_with_parents(genesis_child, vec![MAINNET_PARAMS.genesis.hash]).to_immutable())
         .await
         .unwrap();
 
@@ -207,7 +220,7 @@ async fn ghostdag_test() {
         let test: GhostdagTestDag = serde_json::from_reader(reader).unwrap();
 
         let mut params = MAINNET_PARAMS.clone_with_skip_pow();
-        params.genesis_hash = string_to_hash(&test.genesis_id);
+        params.genesis.hash = string_to_hash(&test.genesis_id);
         params.ghostdag_k = test.k;
 
         let consensus = TestConsensus::create_from_temp_db(&params);
@@ -223,7 +236,7 @@ async fn ghostdag_test() {
         }
 
         // Clone with a new cache in order to verify correct writes to the DB itself
-        let ghostdag_store = consensus.ghostdag_store().clone_with_new_cache(10000);
+        let ghostdag_store = consensus.ghostdag_store().clone_with_new_cache(CachePolicy::Count(10000));
 
         // Assert GHOSTDAG output data
         for block in test.blocks {
@@ -279,7 +292,7 @@ async fn block_window_test() {
     let (_temp_db_lifetime, db) = create_temp_db();
 
     let mut params = MAINNET_PARAMS.clone_with_skip_pow();
-    params.genesis_hash = string_to_hash("A");
+    params.genesis.hash = string_to_hash("A");
     params.ghostdag_k = 1;
 
     let consensus = TestConsensus::new(db, &params);
@@ -337,12 +350,61 @@ async fn block_window_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn sampled_block_window_test() {
+    const ACTIVATION_DAA_SCORE: u64 = 20;
+
+    let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+    params.difficulty_window_size = 40;
+    params.difficulty_sample_rate = 4;
+    params.past_median_time_sample_rate = 10;
+    params.sampling_activation_daa_score = ACTIVATION_DAA_SCORE;
+
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+    let headers_store = consensus.headers_store();
+
+    // Returns the hashes of the window blocks expected out of the chain blocks, oldest first
+    fn expected_window(chain: &[(Hash, u64)], window: WindowParams, is_sampled: bool) -> BlockHashSet {
+        let (window_size, sample_rate) = if is_sampled { (window.sampled_size, window.sample_rate) } else { (window.size, 1) };
+        chain.iter().rev().filter(|(_, daa_score)| daa_score % sample_rate == 0).take(window_size).map(|&(hash, _)| hash).collect()
+    }
+
+    let mut chain = Vec::new();
+    let mut selected_parent = params.genesis.hash;
+    for i in 1..=100u64 {
+        let hash: Hash = i.into();
+        consensus.add_block_with_parents(hash, vec![selected_parent]).await.unwrap();
+        let ghostdag_data = consensus.ghostdag_store().get_data(hash).unwrap();
+        let selected_parent_daa_score = headers_store.get_daa_score(selected_parent).unwrap();
+
+        // All chain blocks are DAA blocks, also once windows are sampled
+        assert_eq!(headers_store.get_daa_score(hash).unwrap(), selected_parent_daa_score + 1);
+
+        let is_sampled = selected_parent_daa_score >= ACTIVATION_DAA_SCORE;
+        for (window_type, window_params) in [
+            (WindowType::DifficultyWindow, params.difficulty_window()),
+            (WindowType::MedianTimeWindow, params.past_median_time_window()),
+        ] {
+            let window = consensus.dag_traversal_manager().window(&ghostdag_data, window_type);
+            assert_eq!(window.is_sampled, is_sampled);
+            let window_hashes: BlockHashSet = window.iter().map(|item| item.0.hash).collect();
+            assert_eq!(window_hashes, expected_window(&chain, window_params, is_sampled), "block {} {:?}", i, window_type);
+        }
+
+        chain.push((hash, headers_store.get_daa_score(hash).unwrap()));
+        selected_parent = hash;
+    }
+
+    consensus.shutdown(wait_handles);
+}
+
 #[tokio::test]
 async fn header_in_isolation_validation_test() {
     let params = &MAINNET_PARAMS;
     let consensus = TestConsensus::create_from_temp_db(params);
     let wait_handles = consensus.init();
-    let block = consensus.build_block_with_parents(1.into(), vec![params.genesis_hash]);
+    let block = consensus.build_block_with_parents(1.into(), vec![params.genesis.hash]);
 
     {
         let mut block = block.clone();
@@ -362,8 +424,8 @@ async fn header_in_isolation_validation_test() {
         let mut block = block.clone();
         block.header.hash = 2.into();
 
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
-        let block_ts = now + params.timestamp_deviation_tolerance * params.target_time_per_block + 2000;
+        let block_ts =
+            TimestampMs::now() + params.target_time_per_block * params.timestamp_deviation_tolerance + DurationMs::from_millis(2000);
         block.header.timestamp = block_ts;
         match consensus.validate_and_insert_block(block.to_immutable()).await {
             Err(RuleError::TimeTooFarIntoTheFuture(ts, _)) => {
@@ -410,14 +472,14 @@ async fn incest_test() {
     let params = MAINNET_PARAMS.clone_with_skip_pow();
     let consensus = TestConsensus::create_from_temp_db(&params);
     let wait_handles = consensus.init();
-    let block = consensus.build_block_with_parents(1.into(), vec![params.genesis_hash]);
+    let block = consensus.build_block_with_parents(1.into(), vec![params.genesis.hash]);
     consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
 
-    let mut block = consensus.build_block_with_parents(2.into(), vec![params.genesis_hash]);
-    block.header.parents_by_level[0] = vec![1.into(), params.genesis_hash];
+    let mut block = consensus.build_block_with_parents(2.into(), vec![params.genesis.hash]);
+    block.header.parents_by_level[0] = vec![1.into(), params.genesis.hash];
     match consensus.validate_and_insert_block(block.to_immutable()).await {
         Err(RuleError::InvalidParentsRelation(a, b)) => {
-            assert_eq!(a, params.genesis_hash);
+            assert_eq!(a, params.genesis.hash);
             assert_eq!(b, 1.into());
         }
         res => {
@@ -433,7 +495,7 @@ async fn missing_parents_test() {
     let params = &MAINNET_PARAMS;
     let consensus = TestConsensus::create_from_temp_db(params);
     let wait_handles = consensus.init();
-    let mut block = consensus.build_block_with_parents(1.into(), vec![params.genesis_hash]);
+    let mut block = consensus.build_block_with_parents(1.into(), vec![params.genesis.hash]);
     block.header.parents_by_level[0] = vec![0.into()];
     match consensus.validate_and_insert_block(block.to_immutable()).await {
         Err(RuleError::MissingParents(missing)) => {
@@ -454,8 +516,8 @@ async fn known_invalid_test() {
     let params = MAINNET_PARAMS.clone_with_skip_pow();
     let consensus = TestConsensus::create_from_temp_db(&params);
     let wait_handles = consensus.init();
-    let mut block = consensus.build_block_with_parents(1.into(), vec![params.genesis_hash]);
-    block.header.timestamp -= 1;
+    let mut block = consensus.build_block_with_parents(1.into(), vec![params.genesis.hash]);
+    block.header.timestamp = block.header.timestamp - DurationMs::from_millis(1);
 
     match consensus.validate_and_insert_block(block.clone().to_immutable()).await {
         Err(RuleError::TimeTooOld(_, _)) => {}
@@ -474,6 +536,206 @@ async fn known_invalid_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn trusted_block_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    // A block with a parent in the pruned past of the syncing node
+    let missing_parent: Hash = 100.into();
+    let mut block = consensus.build_block_with_parents_and_transactions(1.into(), vec![params.genesis.hash], vec![]);
+    block.header.parents_by_level[0] = vec![missing_parent, params.genesis.hash];
+    let ghostdag_data = TrustedGhostdagData {
+        blue_score: block.header.blue_score,
+        blue_work: block.header.blue_work,
+        selected_parent: missing_parent,
+        mergeset_blues: vec![missing_parent, params.genesis.hash],
+        mergeset_reds: vec![],
+        blues_anticone_sizes: vec![(missing_parent, 0), (params.genesis.hash, 1)],
+    };
+    let trusted_block = TrustedBlock::new(block.to_immutable(), ghostdag_data, vec![]);
+
+    let inner = consensus.consensus().as_ref();
+    assert!(matches!(inner.validate_and_insert_trusted_block(trusted_block.clone()), Err(RuleError::TrustedBlockOutsideSync(_))));
+
+    inner.begin_trusted_sync().unwrap();
+    // The supplied GHOSTDAG data must agree with the blue score and blue work of the header
+    let mut mismatching_blue_score = trusted_block.clone();
+    mismatching_blue_score.ghostdag_data.blue_score += 1;
+    assert!(matches!(
+        inner.validate_and_insert_trusted_block(mismatching_blue_score),
+        Err(RuleError::TrustedGhostdagDataMismatch(..))
+    ));
+    let mut mismatching_blue_work = trusted_block.clone();
+    mismatching_blue_work.ghostdag_data.blue_work = trusted_block.ghostdag_data.blue_work + 1;
+    assert!(matches!(inner.validate_and_insert_trusted_block(mismatching_blue_work), Err(RuleError::TrustedGhostdagDataMismatch(..))));
+    assert_eq!(consensus.consensus().clone().get_block_status(1.into()), None);
+
+    assert_eq!(inner.validate_and_insert_trusted_block(trusted_block.clone()).unwrap(), BlockStatus::StatusUTXOPendingVerification);
+    assert_eq!(consensus.block_status(1.into()), BlockStatus::StatusUTXOPendingVerification);
+    // The supplied GHOSTDAG data is stored as is, while the DAG links only the known parent
+    let stored = consensus.consensus().clone().get_ghostdag_data(1.into()).unwrap();
+    assert_eq!(stored.selected_parent, missing_parent);
+    assert!(is_dag_ancestor_of(&*consensus.reachability_store().read(), params.genesis.hash, 1.into()).unwrap());
+    inner.end_trusted_sync();
+
+    assert!(matches!(inner.validate_and_insert_trusted_block(trusted_block), Err(RuleError::TrustedBlockOutsideSync(_))));
+    // A node holding blocks other than genesis does not begin a sync
+    assert!(matches!(inner.begin_trusted_sync(), Err(RuleError::TrustedSyncOfNonEmptyDag)));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn pruning_point_import_test() {
+    let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+    params.finality_depth = 10;
+    params.merge_depth = 5;
+    params.pruning_depth = 25;
+    params.difficulty_window_size = 8;
+    params.timestamp_deviation_tolerance = 3;
+    params.sampling_activation_daa_score = 30;
+    params.difficulty_sample_rate = 2;
+    params.past_median_time_sample_rate = 2;
+    let syncer = TestConsensus::create_from_temp_db(&params);
+    let syncer_wait_handles = syncer.init();
+    let syncee = TestConsensus::create_from_temp_db(&params);
+    let syncee_wait_handles = syncee.init();
+
+    // Blocks are built out of templates, so that they are UTXO valid and their coinbase outputs populate the UTXO set
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![1; 32]), vec![]);
+    let mut blocks = Vec::new();
+    for _ in 0..100 {
+        let block = syncer.consensus().build_block_template(miner_data.clone(), vec![]).unwrap().block.to_immutable();
+        assert_eq!(syncer.validate_and_insert_block(block.clone()).await.unwrap(), BlockStatus::StatusUTXOValid);
+        blocks.push(block);
+    }
+    let tip = blocks.last().unwrap().hash();
+    let syncer_api = syncer.consensus().clone();
+    let pruning_point = syncer_api.clone().get_pruning_point();
+    assert_ne!(pruning_point, params.genesis.hash);
+
+    let trusted_data = syncer_api.clone().get_pruning_point_trusted_data().unwrap();
+    assert_eq!(trusted_data.pruning_points.first().unwrap().hash, params.genesis.hash);
+    assert_eq!(trusted_data.pruning_points.last().unwrap().hash, pruning_point);
+    let mut utxos = Vec::new();
+    let mut from_outpoint = None;
+    loop {
+        let chunk = syncer_api.clone().get_pruning_point_utxos(pruning_point, from_outpoint, 10).unwrap();
+        from_outpoint = chunk.last().map(|(outpoint, _)| *outpoint);
+        let is_last = chunk.len() < 10;
+        utxos.extend(chunk);
+        if is_last {
+            break;
+        }
+    }
+    assert!(!utxos.is_empty());
+    assert!(matches!(
+        syncer_api.clone().get_pruning_point_utxos(tip, None, 10),
+        Err(SyncManagerError::PruningPointUtxoSetUnavailable(_))
+    ));
+
+    let syncee_api = syncee.consensus().clone();
+    let past_pruning_points = trusted_data.pruning_points.iter().map(|header| header.hash).collect_vec();
+    assert!(matches!(syncee_api.clone().append_imported_pruning_point_utxos(&utxos), Err(RuleError::UtxoSetImportOutsideSync)));
+    syncee_api.clone().begin_trusted_sync().unwrap();
+    for trusted_block in trusted_data.blocks {
+        syncee_api.clone().validate_and_insert_trusted_block(trusted_block).unwrap();
+    }
+
+    // A UTXO set missing an entry mismatches the UTXO commitment of the pruning point
+    syncee_api.clone().clear_imported_pruning_point_utxos().unwrap();
+    syncee_api.clone().append_imported_pruning_point_utxos(&utxos[1..]).unwrap();
+    assert!(matches!(
+        syncee_api.clone().import_pruning_point_utxo_set(past_pruning_points.clone()),
+        Err(RuleError::BadUTXOCommitment(..))
+    ));
+    syncee_api.clone().append_imported_pruning_point_utxos(&utxos[..1]).unwrap();
+    syncee_api.clone().import_pruning_point_utxo_set(past_pruning_points).unwrap();
+    syncee_api.clone().end_trusted_sync();
+    assert_eq!(syncee_api.clone().get_pruning_point(), pruning_point);
+    assert_eq!(syncee_api.clone().get_sink(), pruning_point);
+
+    // The blocks above the pruning point are validated on top of the imported UTXO set
+    let pruning_point_blue_score = syncee_api.clone().get_ghostdag_data(pruning_point).unwrap().blue_score;
+    for block in blocks.into_iter().filter(|block| block.header.blue_score > pruning_point_blue_score) {
+        assert_eq!(syncee.validate_and_insert_block(block).await.unwrap(), BlockStatus::StatusUTXOValid);
+    }
+    assert_eq!(syncee_api.clone().get_sink(), tip);
+    assert_eq!(syncee_api.get_virtual_utxos(None, usize::MAX), syncer_api.get_virtual_utxos(None, usize::MAX));
+
+    syncer.shutdown(syncer_wait_handles);
+    syncee.shutdown(syncee_wait_handles);
+}
+
+#[tokio::test]
+async fn validate_mempool_transaction_test() {
+    let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+    params.coinbase_maturity = 0;
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    // Mine a few blocks paying to a key of ours, so that their coinbase outputs can be spent
+    let (secret_key, public_key) = secp256k1::generate_keypair(&mut rand::thread_rng());
+    let mut script = vec![0x20];
+    script.extend_from_slice(&public_key.x_only_public_key().0.serialize());
+    script.push(0xac);
+    let script_public_key = ScriptPublicKey::from_vec(0, script);
+    let miner_data = MinerData::new(script_public_key.clone(), vec![]);
+    for _ in 0..3 {
+        let block = consensus.consensus().build_block_template(miner_data.clone(), vec![]).unwrap().block.to_immutable();
+        assert_eq!(consensus.validate_and_insert_block(block).await.unwrap(), BlockStatus::StatusUTXOValid);
+    }
+    let api = consensus.consensus().clone();
+    let (outpoint, entry) = api
+        .clone()
+        .get_virtual_utxos(None, usize::MAX)
+        .into_iter()
+        .find(|(_, entry)| entry.script_public_key == script_public_key)
+        .unwrap();
+
+    let build_tx = |outpoint: TransactionOutpoint, output_values: Vec<u64>| {
+        let inputs = vec![TransactionInput::new(outpoint, vec![], 0, 1)];
+        let outputs = output_values.into_iter().map(|value| TransactionOutput::new(value, script_public_key.clone())).collect();
+        Transaction::new(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![])
+    };
+    let sign_tx = |tx: Transaction| sign(MutableTransaction::with_entries(tx, vec![entry.clone()]), secret_key.secret_bytes()).tx;
+
+    // A valid transaction gets its entries, fee and mass populated
+    let fee = 1000;
+    let tx = sign_tx(build_tx(outpoint, vec![entry.amount - fee]));
+    let mut mtx = MutableTransaction::new(tx.clone());
+    api.clone().validate_mempool_transaction(&mut mtx).unwrap();
+    assert!(mtx.is_fully_populated());
+    assert_eq!(mtx.calculated_fee, Some(fee));
+    assert_eq!(mtx.calculated_mass, Some(api.clone().calculate_transaction_mass(&tx)));
+
+    // Spending more than the inputs amount, i.e., a negative fee
+    let mut mtx = MutableTransaction::new(build_tx(outpoint, vec![entry.amount + 1]));
+    assert!(matches!(api.clone().validate_mempool_transaction(&mut mtx), Err(TxRuleError::SpendTooHigh(_, _))));
+    assert_eq!(mtx.calculated_fee, None);
+
+    // Spending an unknown outpoint
+    let unknown_outpoint = TransactionOutpoint::new(1.into(), 0);
+    let mut mtx = MutableTransaction::new(build_tx(unknown_outpoint, vec![entry.amount - fee]));
+    assert!(matches!(api.clone().validate_mempool_transaction(&mut mtx), Err(TxRuleError::MissingTxOutpoints)));
+
+    // A transaction with too many outputs to fit in a block
+    let output_count = 2000;
+    let tx = sign_tx(build_tx(outpoint, vec![(entry.amount - fee) / output_count; output_count as usize]));
+    let mass = api.clone().calculate_transaction_mass(&tx);
+    assert!(mass > params.max_block_mass);
+    let mut mtx = MutableTransaction::new(tx);
+    match api.clone().validate_mempool_transaction(&mut mtx) {
+        Err(TxRuleError::MassTooHigh(tx_mass, max_mass)) => assert_eq!((tx_mass, max_mass), (mass, params.max_block_mass)),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    assert_eq!(mtx.calculated_mass, None);
+
+    consensus.shutdown(wait_handles);
+}
+
 #[tokio::test]
 async fn median_time_test() {
     let params = MAINNET_PARAMS.clone_with_skip_pow();
@@ -482,15 +744,15 @@ async fn median_time_test() {
 
     let num_blocks = 2 * params.timestamp_deviation_tolerance - 1;
     for i in 1..(num_blocks + 1) {
-        let parent = if i == 1 { params.genesis_hash } else { (i - 1).into() };
+        let parent = if i == 1 { params.genesis.hash } else { (i - 1).into() };
         let mut block = consensus.build_block_with_parents(i.into(), vec![parent]);
-        block.header.timestamp = params.genesis_timestamp + i;
+        block.header.timestamp = params.genesis.timestamp + DurationMs::from_millis(i);
         consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
     }
 
     let mut block = consensus.build_block_with_parents((num_blocks + 2).into(), vec![num_blocks.into()]);
     // We set the timestamp to be less than the median time and expect the block to be rejected
-    block.header.timestamp = params.genesis_timestamp + num_blocks - params.timestamp_deviation_tolerance - 1;
+    block.header.timestamp = params.genesis.timestamp + DurationMs::from_millis(num_blocks - params.timestamp_deviation_tolerance - 1);
     match consensus.validate_and_insert_block(block.to_immutable()).await {
         Err(RuleError::TimeTooOld(_, _)) => {}
         res => {
@@ -500,7 +762,7 @@ async fn median_time_test() {
 
     let mut block = consensus.build_block_with_parents((num_blocks + 3).into(), vec![num_blocks.into()]);
     // We set the timestamp to be the exact median time and expect the block to be rejected
-    block.header.timestamp = params.genesis_timestamp + num_blocks - params.timestamp_deviation_tolerance;
+    block.header.timestamp = params.genesis.timestamp + DurationMs::from_millis(num_blocks - params.timestamp_deviation_tolerance);
     match consensus.validate_and_insert_block(block.to_immutable()).await {
         Err(RuleError::TimeTooOld(_, _)) => {}
         res => {
@@ -510,7 +772,7 @@ async fn median_time_test() {
 
     let mut block = consensus.build_block_with_parents((num_blocks + 4).into(), vec![(num_blocks).into()]);
     // We set the timestamp to be bigger than the median time and expect the block to be inserted successfully.
-    block.header.timestamp = params.genesis_timestamp + params.timestamp_deviation_tolerance + 1;
+    block.header.timestamp = params.genesis.timestamp + DurationMs::from_millis(params.timestamp_deviation_tolerance + 1);
     consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
 
     consensus.shutdown(wait_handles);
@@ -524,14 +786,14 @@ async fn mergeset_size_limit_test() {
 
     let num_blocks_per_chain = params.mergeset_size_limit + 1;
 
-    let mut tip1_hash = params.genesis_hash;
+    let mut tip1_hash = params.genesis.hash;
     for i in 1..(num_blocks_per_chain + 1) {
         let block = consensus.build_block_with_parents(i.into(), vec![tip1_hash]);
         tip1_hash = block.header.hash;
         consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
     }
 
-    let mut tip2_hash = params.genesis_hash;
+    let mut tip2_hash = params.genesis.hash;
     for i in (num_blocks_per_chain + 2)..(2 * num_blocks_per_chain + 1) {
         let block = consensus.build_block_with_parents(i.into(), vec![tip2_hash]);
         tip2_hash = block.header.hash;
@@ -552,6 +814,48 @@ async fn mergeset_size_limit_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn blue_work_mismatch_diagnostics_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in [(1u64, vec![params.genesis.hash]), (2, vec![1.into()]), (3, vec![1.into()])] {
+        let block = consensus.build_block_with_parents(hash.into(), parents);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    // The mergeset blues of a block merging 2 and 3 are its selected parent followed by the other parent, which
+    // is the first blue exceeding a blue work lower by one
+    let mut block = consensus.build_block_with_parents(4.into(), vec![2.into(), 3.into()]);
+    let computed_blue_work = block.header.blue_work;
+    block.header.blue_work = computed_blue_work - BlueWorkType::from_u64(1);
+    match consensus.validate_and_insert_block(block.to_immutable()).await {
+        Err(RuleError::UnexpectedHeaderBlueWork(expected, _, divergence)) => {
+            assert_eq!(expected, computed_blue_work);
+            assert_eq!(divergence.computed_blue_work, computed_blue_work);
+            let parents: [Hash; 2] = [2.into(), 3.into()];
+            let selected_parent = consensus.ghostdag_manager().find_selected_parent(&mut parents.into_iter());
+            let other_parent = if selected_parent == parents[0] { parents[1] } else { parents[0] };
+            assert_eq!(divergence.first_divergent_blue, Some(other_parent));
+        }
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // A blue score higher than the whole mergeset allows has no divergent blue
+    let mut block = consensus.build_block_with_parents(5.into(), vec![2.into(), 3.into()]);
+    block.header.blue_score += 1;
+    match consensus.validate_and_insert_block(block.to_immutable()).await {
+        Err(RuleError::UnexpectedHeaderBlueScore(expected, _, divergence)) => {
+            assert_eq!(divergence.computed_blue_score, expected);
+            assert_eq!(divergence.first_divergent_blue, None);
+        }
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    consensus.shutdown(wait_handles);
+}
+
 #[allow(non_snake_case)]
 #[derive(Deserialize, Debug)]
 struct RPCBlock {
@@ -658,14 +962,16 @@ impl KaspadGoParams {
     fn into_params(self, genesis_header: &Header) -> Params {
         let finality_depth = self.FinalityDuration / self.TargetTimePerBlock;
         Params {
-            genesis_hash: genesis_header.hash,
+            network_name: MAINNET_PARAMS.network_name,
+            genesis: genesis_from_header(genesis_header),
             ghostdag_k: self.K,
             timestamp_deviation_tolerance: self.TimestampDeviationTolerance,
-            target_time_per_block: self.TargetTimePerBlock / 1_000_000,
+            target_time_per_block: DurationMs::from_millis(self.TargetTimePerBlock / 1_000_000),
             max_block_parents: self.MaxBlockParents,
             difficulty_window_size: self.DifficultyAdjustmentWindowSize,
-            genesis_timestamp: genesis_header.timestamp,
-            genesis_bits: genesis_header.bits,
+            sampling_activation_daa_score: MAINNET_PARAMS.sampling_activation_daa_score,
+            difficulty_sample_rate: MAINNET_PARAMS.difficulty_sample_rate,
+            past_median_time_sample_rate: MAINNET_PARAMS.past_median_time_sample_rate,
             mergeset_size_limit: self.MergeSetSizeLimit,
             merge_depth: self.MergeDepth,
             finality_depth,
@@ -679,6 +985,7 @@ impl KaspadGoParams {
             mass_per_tx_byte: self.MassPerTxByte,
             mass_per_script_pub_key_byte: self.MassPerScriptPubKeyByte,
             mass_per_sig_op: self.MassPerSigOp,
+            storage_mass_per_utxo: MAINNET_PARAMS.storage_mass_per_utxo,
             max_block_mass: self.MaxBlockMass,
             deflationary_phase_daa_score: self.DeflationaryPhaseDaaScore,
             pre_deflationary_phase_base_subsidy: self.PreDeflationaryPhaseBaseSubsidy,
@@ -689,6 +996,21 @@ impl KaspadGoParams {
     }
 }
 
+/// The genesis of a recorded DAG, whose coinbase is assumed to carry the devnet payload
+fn genesis_from_header(header: &Header) -> GenesisBlock {
+    GenesisBlock {
+        hash: header.hash,
+        version: header.version,
+        hash_merkle_root: header.hash_merkle_root,
+        utxo_commitment: header.utxo_commitment,
+        timestamp: header.timestamp,
+        bits: header.bits,
+        nonce: header.nonce,
+        daa_score: header.daa_score,
+        coinbase_payload: DEVNET_GENESIS.coinbase_payload,
+    }
+}
+
 #[tokio::test]
 async fn goref_custom_pruning_depth() {
     json_test("tests/testdata/goref_custom_pruning_depth.json.gz").await
@@ -740,11 +1062,7 @@ async fn json_test(file_path: &str) {
         go_params.into_params(&genesis.header)
     } else {
         let genesis = json_line_to_block(first_line);
-        let mut params = DEVNET_PARAMS;
-        params.genesis_bits = genesis.header.bits;
-        params.genesis_hash = genesis.header.hash;
-        params.genesis_timestamp = genesis.header.timestamp;
-        params
+        Params { genesis: genesis_from_header(&genesis.header), ..DEVNET_PARAMS }
     };
 
     let consensus = TestConsensus::create_from_temp_db(&params);
@@ -780,10 +1098,7 @@ async fn json_concurrency_test(file_path: &str) {
     let mut lines = io::BufReader::new(decoder).lines();
     let first_line = lines.next().unwrap();
     let genesis = json_line_to_block(first_line.unwrap());
-    let mut params = DEVNET_PARAMS;
-    params.genesis_bits = genesis.header.bits;
-    params.genesis_hash = genesis.header.hash;
-    params.genesis_timestamp = genesis.header.timestamp;
+    let params = Params { genesis: genesis_from_header(&genesis.header), ..DEVNET_PARAMS };
 
     let consensus = TestConsensus::create_from_temp_db(&params);
     let wait_handles = consensus.init();
@@ -836,7 +1151,7 @@ fn json_line_to_block(line: String) -> Block {
             hash_merkle_root: Hash::from_str(&rpc_block.Header.HashMerkleRoot).unwrap(),
             accepted_id_merkle_root: Hash::from_str(&rpc_block.Header.AcceptedIDMerkleRoot).unwrap(),
             utxo_commitment: Hash::from_str(&rpc_block.Header.UTXOCommitment).unwrap(),
-            timestamp: rpc_block.Header.Timestamp,
+            timestamp: TimestampMs::from_millis(rpc_block.Header.Timestamp),
             bits: rpc_block.Header.Bits,
             nonce: rpc_block.Header.Nonce,
             daa_score: rpc_block.Header.DAAScore,
@@ -902,7 +1217,7 @@ async fn bounded_merge_depth_test() {
     let consensus = TestConsensus::create_from_temp_db(&params);
     let wait_handles = consensus.init();
 
-    let mut selected_chain = vec![params.genesis_hash];
+    let mut selected_chain = vec![params.genesis.hash];
     for i in 1..(params.merge_depth + 3) {
         let hash: Hash = (i + 1).into();
         consensus.add_block_with_parents(hash, vec![*selected_chain.last().unwrap()]).await.unwrap();
@@ -910,7 +1225,7 @@ async fn bounded_merge_depth_test() {
     }
 
     // The length of block_chain_2 is shorter by one than selected_chain, so selected_chain will remain the selected chain.
-    let mut block_chain_2 = vec![params.genesis_hash];
+    let mut block_chain_2 = vec![params.genesis.hash];
     for i in 1..(params.merge_depth + 2) {
         let hash: Hash = (i + params.merge_depth + 3).into();
         consensus.add_block_with_parents(hash, vec![*block_chain_2.last().unwrap()]).await.unwrap();
@@ -968,7 +1283,7 @@ async fn bounded_merge_depth_test() {
 
 #[tokio::test]
 async fn difficulty_test() {
-    async fn add_block(consensus: &TestConsensus, block_time: Option<u64>, parents: Vec<Hash>) -> Header {
+    async fn add_block(consensus: &TestConsensus, block_time: Option<TimestampMs>, parents: Vec<Hash>) -> Header {
         let selected_parent = consensus.ghostdag_manager().find_selected_parent(&mut parents.iter().copied());
         let block_time = block_time.unwrap_or_else(|| {
             consensus.headers_store().get_timestamp(selected_parent).unwrap() + consensus.params.target_time_per_block
@@ -982,7 +1297,7 @@ async fn difficulty_test() {
     async fn add_block_with_min_time(consensus: &TestConsensus, parents: Vec<Hash>) -> Header {
         let ghostdag_data = consensus.ghostdag_manager().ghostdag(&parents[..]);
         let (pmt, _) = consensus.past_median_time_manager().calc_past_median_time(&ghostdag_data);
-        add_block(consensus, Some(pmt + 1), parents).await
+        add_block(consensus, Some(pmt + DurationMs::from_millis(1)), parents).await
     }
 
     fn compare_bits(a: u32, b: u32) -> Ordering {
@@ -997,13 +1312,13 @@ async fn difficulty_test() {
     let wait_handles = consensus.init();
 
     let fake_genesis = Header {
-        hash: params.genesis_hash,
+        hash: params.genesis.hash,
         version: 0,
         parents_by_level: vec![],
         hash_merkle_root: 0.into(),
         accepted_id_merkle_root: 0.into(),
         utxo_commitment: 0.into(),
-        timestamp: 0,
+        timestamp: TimestampMs::from_millis(0),
         bits: 0,
         nonce: 0,
         daa_score: 0,
@@ -1015,17 +1330,17 @@ async fn difficulty_test() {
     let mut tip = fake_genesis;
     for _ in 0..params.difficulty_window_size {
         tip = add_block(&consensus, None, vec![tip.hash]).await;
-        assert_eq!(tip.bits, params.genesis_bits, "until first DAA window is created difficulty should remains unchanged");
+        assert_eq!(tip.bits, params.genesis.bits, "until first DAA window is created difficulty should remains unchanged");
     }
 
     for _ in 0..params.difficulty_window_size + 10 {
         tip = add_block(&consensus, None, vec![tip.hash]).await;
-        assert_eq!(tip.bits, params.genesis_bits, "block rate wasn't changed so difficulty is not expected to change");
+        assert_eq!(tip.bits, params.genesis.bits, "block rate wasn't changed so difficulty is not expected to change");
     }
 
     let block_in_the_past = add_block_with_min_time(&consensus, vec![tip.hash]).await;
     assert_eq!(
-        block_in_the_past.bits, params.genesis_bits,
+        block_in_the_past.bits, params.genesis.bits,
         "block_in_the_past shouldn't affect its own difficulty, but only its future"
     );
     tip = block_in_the_past;
@@ -1054,7 +1369,7 @@ async fn difficulty_test() {
         }
     }
 
-    let slow_block_time = tip.timestamp + params.target_time_per_block + 1000;
+    let slow_block_time = tip.timestamp + params.target_time_per_block + DurationMs::from_millis(1000);
     let slow_block = add_block(&consensus, Some(slow_block_time), vec![tip.hash]).await;
     let slow_block_bits = slow_block.bits;
     assert_eq!(slow_block.bits, tip.bits, "The difficulty should change only when slow_block is in the past");
@@ -1115,3 +1430,445 @@ async fn difficulty_test() {
 
     consensus.shutdown(wait_handles);
 }
+
+#[tokio::test]
+async fn revalidate_block_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    let block = consensus.build_block_with_parents_and_transactions(2.into(), vec![params.genesis.hash], vec![]);
+    consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![2.into()]).await.unwrap();
+
+    for hash in [params.genesis.hash, 2.into()] {
+        let report = consensus.revalidate_block(hash).unwrap();
+        assert!(report.body_revalidated);
+        assert!(report.header_error.is_none() && report.body_error.is_none());
+        assert!(report.discrepancies().is_empty());
+    }
+
+    // Header only blocks are revalidated without a body
+    let report = consensus.revalidate_block(3.into()).unwrap();
+    assert_eq!(report.stored_status, BlockStatus::StatusHeaderOnly);
+    assert!(!report.body_revalidated);
+    assert!(report.discrepancies().is_empty());
+
+    assert!(consensus.revalidate_block(100.into()).is_none());
+
+    consensus.shutdown(wait_handles);
+}
+
+struct ChannelConsumer(std::sync::Mutex<std::sync::mpsc::Sender<Arc<Notification>>>);
+
+impl NotificationConsumer for ChannelConsumer {
+    fn consume(&self, notification: Arc<Notification>) {
+        self.0.lock().unwrap().send(notification).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn notification_root_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    consensus.notification_root().register(
+        &[NotificationType::BlockAdded, NotificationType::VirtualChainChanged],
+        BackpressurePolicy::Unbounded,
+        Arc::new(ChannelConsumer(std::sync::Mutex::new(sender))),
+    );
+    let recv = || receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    let block = consensus.build_block_with_parents_and_transactions(2.into(), vec![params.genesis.hash], vec![]);
+    consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    // The chain change is notified after the added block
+    assert!(matches!(*recv(), Notification::BlockAdded(ref added) if added.block.hash() == 2.into()));
+    match *recv() {
+        Notification::VirtualChainChanged(ref changed) => {
+            assert!(changed.removed_chain_block_hashes.is_empty());
+            assert_eq!(changed.added_chain_block_hashes, vec![2.into()]);
+            assert_eq!(changed.added_chain_blocks_acceptance_data.len(), 1);
+            assert_eq!(changed.added_chain_blocks_acceptance_data[0].accepting_block_hash, 2.into());
+        }
+        ref notification => panic!("unexpected notification {:?}", notification),
+    }
+
+    // A heavier side chain reorgs the virtual chain, possibly through several changes depending on the tie break of 2 and 3
+    let (mut removed, mut added): (Vec<Hash>, Vec<Hash>) = (Vec::new(), Vec::new());
+    for (hash, parent) in [(3u64, params.genesis.hash), (4, 3.into())] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), vec![parent], vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+    while !added.contains(&4.into()) {
+        if let Notification::VirtualChainChanged(ref changed) = *recv() {
+            removed.extend(changed.removed_chain_block_hashes.iter().copied());
+            added.extend(changed.added_chain_block_hashes.iter().copied());
+        }
+    }
+    assert_eq!(removed, vec![2.into()]);
+    assert_eq!(added, vec![3.into(), 4.into()]);
+
+    // Header only blocks are not notified
+    consensus.add_block_with_parents(5.into(), vec![4.into()]).await.unwrap();
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn transactions_acceptance_notifications_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    consensus.notification_root().register(
+        &[NotificationType::TransactionsAccepted, NotificationType::TransactionsReverted],
+        BackpressurePolicy::Unbounded,
+        Arc::new(ChannelConsumer(std::sync::Mutex::new(sender))),
+    );
+
+    // The coinbase transaction of 2 is accepted by its chain child 6
+    let mut coinbase_id = None;
+    for (hash, parent) in [(2u64, params.genesis.hash), (6, 2.into())] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), vec![parent], vec![]);
+        coinbase_id.get_or_insert(block.transactions[0].id());
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+    let coinbase_id = coinbase_id.unwrap();
+    let mut accepted = Vec::new();
+    while !accepted.iter().any(|acceptance: &TransactionAcceptance| acceptance.transaction_id == coinbase_id) {
+        if let Notification::TransactionsAccepted(ref notification) = *receiver.recv_timeout(Duration::from_secs(10)).unwrap() {
+            accepted.extend(notification.accepted.iter().cloned());
+        }
+    }
+    let acceptance = accepted.iter().find(|acceptance| acceptance.transaction_id == coinbase_id).unwrap();
+    assert_eq!(acceptance.accepting_block_hash, 6.into());
+    assert_eq!(acceptance.output_script_public_keys.len(), 1);
+
+    // A heavier side chain not merging 2 reverts its coinbase transaction, while the genesis coinbase transaction
+    // is accepted again by 3 without being reverted
+    for (hash, parent) in [(3u64, params.genesis.hash), (4, 3.into()), (5, 4.into())] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), vec![parent], vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+    let mut reverted = Vec::new();
+    while let Ok(notification) = receiver.recv_timeout(Duration::from_millis(500)) {
+        if let Notification::TransactionsReverted(ref notification) = *notification {
+            reverted
+                .extend(notification.reverted.iter().map(|acceptance| (acceptance.transaction_id, acceptance.accepting_block_hash)));
+        }
+    }
+    assert_eq!(reverted, vec![(coinbase_id, 6.into())]);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn pruning_point_future_blocks_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = TestConsensus::create_from_temp_db(&params);
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in [(2u64, vec![params.genesis.hash]), (3, vec![params.genesis.hash]), (4, vec![2.into(), 3.into()])] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+    // Header only blocks are not included
+    consensus.add_block_with_parents(5.into(), vec![4.into()]).await.unwrap();
+
+    let blocks = consensus.pruning_point_future_blocks();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[2], 4.into());
+    assert!(consensus.get_block(5.into()).is_none());
+
+    // Feeding the blocks in order into a fresh consensus should fully reconstruct the DAG
+    let other_consensus = TestConsensus::create_from_temp_db(&params);
+    let other_wait_handles = other_consensus.init();
+    for hash in blocks {
+        let status = other_consensus.validate_and_insert_block(consensus.get_block(hash).unwrap()).await.unwrap();
+        assert_eq!(status, consensus.block_status(hash));
+    }
+    assert_eq!(other_consensus.body_tips(), consensus.body_tips());
+
+    consensus.shutdown(wait_handles);
+    other_consensus.shutdown(other_wait_handles);
+}
+
+#[tokio::test]
+async fn sync_manager_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in
+        [(2u64, vec![params.genesis.hash]), (3, vec![params.genesis.hash]), (4, vec![2.into(), 3.into()]), (5, vec![4.into()])]
+    {
+        consensus.add_block_with_parents(hash.into(), parents).await.unwrap();
+    }
+    assert_eq!(consensus.clone().get_headers_selected_tip(), 5.into());
+    assert_eq!(consensus.clone().get_pruning_point(), params.genesis.hash);
+
+    let locator = consensus.clone().create_headers_selected_chain_block_locator(None, None).unwrap();
+    assert_eq!(locator.first(), Some(&5.into()));
+    assert_eq!(locator.last(), Some(&params.genesis.hash));
+
+    // The chain block merging 2 and 3 is preceded by its mergeset
+    let (hashes, highest) = consensus.clone().get_hashes_between(params.genesis.hash, 5.into(), usize::MAX).unwrap();
+    assert_eq!(hashes.len(), 4);
+    assert_eq!(hashes[2..], [4.into(), 5.into()]);
+    assert_eq!(highest, 5.into());
+
+    // A chain block along with its mergeset is never split across chunks
+    let (hashes, highest) = consensus.clone().get_hashes_between(params.genesis.hash, 5.into(), 2).unwrap();
+    assert_eq!(hashes.len(), 1);
+    assert_eq!(highest, hashes[0]);
+
+    assert_eq!(consensus.clone().get_missing_block_body_hashes(5.into()).unwrap().len(), 4);
+
+    assert!(matches!(
+        consensus.clone().get_hashes_between(2.into(), 3.into(), usize::MAX),
+        Err(SyncManagerError::NotInSelectedChain(_, _))
+    ));
+    assert!(matches!(
+        consensus.clone().create_headers_selected_chain_block_locator(Some(100.into()), None),
+        Err(SyncManagerError::BlockNotFound(_))
+    ));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn virtual_chain_from_block_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in
+        [(2u64, vec![params.genesis.hash]), (3, vec![2.into()]), (4, vec![3.into()]), (10, vec![params.genesis.hash])]
+    {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    let batch = consensus.clone().get_virtual_chain_from_block(params.genesis.hash, true, 2, usize::MAX).unwrap();
+    assert!(batch.removed_chain_block_hashes.is_empty());
+    assert_eq!(batch.added_chain_block_hashes, vec![2.into(), 3.into()]);
+    assert!(batch.has_more);
+    // Each chain block accepts the coinbase of its selected parent
+    let acceptance = &batch.accepted_transaction_ids[1];
+    assert_eq!(acceptance.accepting_block_hash, 3.into());
+    assert_eq!(acceptance.mergeset[0].block_hash, 2.into());
+    assert_eq!(
+        acceptance.mergeset[0].accepted_transaction_ids,
+        vec![consensus.clone().get_block(2.into()).unwrap().transactions[0].id()]
+    );
+
+    // Continuing from the last added chain block reaches the virtual selected parent
+    let batch = consensus.clone().get_virtual_chain_from_block(3.into(), false, 2, usize::MAX).unwrap();
+    assert_eq!(batch.added_chain_block_hashes, vec![4.into()]);
+    assert!(batch.accepted_transaction_ids.is_empty());
+    assert!(!batch.has_more);
+
+    // Starting from a block out of the virtual chain removes it first
+    let batch = consensus.clone().get_virtual_chain_from_block(10.into(), false, 10, usize::MAX).unwrap();
+    assert_eq!(batch.removed_chain_block_hashes, vec![10.into()]);
+    assert_eq!(batch.added_chain_block_hashes, vec![2.into(), 3.into(), 4.into()]);
+
+    // The accepted transaction ids limit bounds the batch, but at least one chain block is added
+    let batch = consensus.clone().get_virtual_chain_from_block(params.genesis.hash, true, 10, 1).unwrap();
+    assert_eq!(batch.added_chain_block_hashes, vec![2.into()]);
+    assert!(batch.has_more);
+
+    assert!(matches!(
+        consensus.clone().get_virtual_chain_from_block(100.into(), false, 10, usize::MAX),
+        Err(SyncManagerError::BlockNotFound(_))
+    ));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn chain_blocks_with_acceptance_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in
+        [(2u64, vec![params.genesis.hash]), (3, vec![2.into()]), (4, vec![3.into()]), (10, vec![params.genesis.hash])]
+    {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    let batch = consensus.clone().get_chain_blocks_with_acceptance(params.genesis.hash, None, 2).unwrap();
+    assert_eq!(batch.blocks.iter().map(|block| block.block.hash()).collect::<Vec<_>>(), vec![2.into(), 3.into()]);
+    assert!(batch.has_more);
+    let chain_block = &batch.blocks[1];
+    assert_eq!(chain_block.selected_parent, 2.into());
+    assert_eq!(chain_block.blue_score, 2);
+    // Each chain block accepts the coinbase of its selected parent
+    assert_eq!(chain_block.mergeset[0].block_hash, 2.into());
+    assert_eq!(chain_block.mergeset[0].accepted_transaction_ids, vec![batch.blocks[0].block.transactions[0].id()]);
+
+    // Continuing from the last returned block reaches the virtual selected parent
+    let batch = consensus.clone().get_chain_blocks_with_acceptance(3.into(), None, 2).unwrap();
+    assert_eq!(batch.blocks.iter().map(|block| block.block.hash()).collect::<Vec<_>>(), vec![4.into()]);
+    assert!(!batch.has_more);
+
+    // The range is bounded by the given high block
+    let batch = consensus.clone().get_chain_blocks_with_acceptance(params.genesis.hash, Some(3.into()), 10).unwrap();
+    assert_eq!(batch.blocks.len(), 2);
+    assert!(!batch.has_more);
+
+    assert!(matches!(
+        consensus.clone().get_chain_blocks_with_acceptance(10.into(), None, 10),
+        Err(SyncManagerError::NotInSelectedChain(_, _))
+    ));
+    assert!(matches!(
+        consensus.clone().get_chain_blocks_with_acceptance(params.genesis.hash, Some(10.into()), 10),
+        Err(SyncManagerError::NotInSelectedChain(_, _))
+    ));
+    assert!(matches!(
+        consensus.clone().get_chain_blocks_with_acceptance(100.into(), None, 10),
+        Err(SyncManagerError::BlockNotFound(_))
+    ));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn block_dag_info_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in [(2u64, vec![params.genesis.hash]), (3, vec![2.into()]), (10, vec![params.genesis.hash])] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    let info = consensus.clone().get_block_dag_info();
+    assert_eq!(info.network_name, params.network_name);
+    // Genesis is counted as well
+    assert_eq!(info.header_count, 4);
+    assert_eq!(info.block_count, 4);
+    assert_eq!(info.tip_hashes.into_iter().collect::<BlockHashSet>(), BlockHashSet::from_iter([3.into(), 10.into()]));
+    assert_eq!(info.virtual_parent_hashes.into_iter().collect::<BlockHashSet>(), BlockHashSet::from_iter([3.into(), 10.into()]));
+    assert_eq!(info.virtual_daa_score, consensus.clone().get_virtual_daa_score());
+    assert_eq!(info.pruning_point_hash, params.genesis.hash);
+    assert!(info.difficulty > 0.0);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn confirmations_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in
+        [(2u64, vec![params.genesis.hash]), (3, vec![2.into()]), (4, vec![3.into()]), (10, vec![params.genesis.hash])]
+    {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    // The virtual selected parent has a single confirmation, while a block merged only by the virtual has none
+    assert_eq!(consensus.clone().get_confirmations(4.into()), Some(1));
+    assert_eq!(consensus.clone().get_confirmations(2.into()), Some(3));
+    assert_eq!(consensus.clone().get_confirmations(10.into()), None);
+    assert_eq!(consensus.clone().get_confirmations(100.into()), None);
+
+    let block = consensus.build_block_with_parents_and_transactions(5.into(), vec![4.into(), 10.into()], vec![]);
+    consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+
+    // A merged block has the confirmations of the chain block merging it
+    assert_eq!(consensus.clone().get_confirmations(10.into()), Some(1));
+    assert_eq!(consensus.clone().get_confirmations(5.into()), Some(1));
+    assert_eq!(consensus.clone().get_confirmations(4.into()), Some(3));
+    assert_eq!(consensus.clone().get_confirmations(params.genesis.hash), Some(6));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn block_region_and_pinning_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+
+    for (hash, parents) in [
+        (2u64, vec![params.genesis.hash]),
+        (3, vec![2.into()]),
+        (4, vec![3.into()]),
+        (10, vec![params.genesis.hash]),
+        (5, vec![4.into(), 10.into()]),
+    ] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    // The region excludes the blocks merged by `high` which are not in the future of `low`
+    let region = consensus.clone().get_block_region(2.into(), 5.into(), 10).unwrap();
+    assert_eq!(region, vec![2.into(), 3.into(), 4.into(), 5.into()]);
+    assert_eq!(consensus.clone().get_block_region(10.into(), 10.into(), 10).unwrap(), vec![10.into()]);
+    let region = consensus.clone().get_block_region(params.genesis.hash, 5.into(), 6).unwrap();
+    assert_eq!(region.first(), Some(&params.genesis.hash));
+    assert_eq!(region.iter().copied().collect::<BlockHashSet>().len(), 6);
+    assert!(matches!(
+        consensus.clone().get_block_region(params.genesis.hash, 5.into(), 5),
+        Err(SyncManagerError::RegionTooLarge(_, _, 5))
+    ));
+    assert!(matches!(consensus.clone().get_block_region(10.into(), 4.into(), 10), Err(SyncManagerError::NotInPast(_, _))));
+    assert!(matches!(consensus.clone().get_block_region(100.into(), 5.into(), 10), Err(SyncManagerError::BlockNotFound(_))));
+
+    // Pinning fails as a whole if a block is unknown
+    consensus.clone().pin_blocks(&[2.into(), 3.into()]).unwrap();
+    assert!(
+        matches!(consensus.clone().pin_blocks(&[4.into(), 100.into()]), Err(SyncManagerError::BlockNotFound(hash)) if hash == Hash::from(100))
+    );
+    consensus.clone().unpin_blocks(&[2.into(), 10.into()]);
+    assert_eq!(consensus.clone().get_pinned_blocks(), vec![3.into()]);
+    assert!(consensus.consensus().is_block_pinned(3.into()));
+    assert!(!consensus.consensus().is_block_pinned(4.into()));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn consensus_session_test() {
+    let params = MAINNET_PARAMS.clone_with_skip_pow();
+    let consensus = Arc::new(TestConsensus::create_from_temp_db(&params));
+    let wait_handles = consensus.init();
+    let dyn_consensus: DynConsensus = consensus.clone();
+
+    for (hash, parents) in [(2u64, vec![params.genesis.hash]), (3, vec![2.into()])] {
+        let block = consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+    }
+
+    let session = ConsensusSession::acquire(&dyn_consensus);
+    assert_eq!(session.get_sink(), 3.into());
+    assert_eq!(session.get_virtual_info().ghostdag_data.selected_parent, 3.into());
+    assert_eq!(session.get_ghostdag_data(3.into()).unwrap().selected_parent, 2.into());
+    assert!(session.get_ghostdag_data(100.into()).is_none());
+    assert!(session.is_chain_ancestor_of(2.into(), 3.into()).unwrap());
+    assert!(!session.is_chain_ancestor_of(3.into(), 2.into()).unwrap());
+    assert!(matches!(session.is_chain_ancestor_of(100.into(), 3.into()), Err(SyncManagerError::BlockNotFound(_))));
+
+    // The virtual state is not committed while the session is held
+    let block = consensus.build_block_with_parents_and_transactions(4.into(), vec![3.into()], vec![]);
+    let mut processing = tokio::spawn(dyn_consensus.clone().validate_and_insert_block(block.to_immutable(), true));
+    assert!(tokio::time::timeout(Duration::from_millis(500), &mut processing).await.is_err());
+    assert_eq!(session.get_sink(), 3.into());
+
+    drop(session);
+    processing.await.unwrap().unwrap();
+    assert_eq!(ConsensusSession::acquire(&dyn_consensus).get_sink(), 4.into());
+
+    consensus.shutdown(wait_handles);
+}
@@ -1,9 +1,12 @@
 use consensus::{
     consensus::test_consensus::{create_temp_db, TestConsensus},
-    model::stores::reachability::{DbReachabilityStore, StagingReachabilityStore},
-    params::MAINNET_PARAMS,
+    model::stores::{
+        database::prelude::CachePolicy,
+        reachability::{DbReachabilityStore, StagingReachabilityStore},
+    },
     processes::reachability::tests::{DagBlock, DagBuilder, StoreValidationExtensions},
 };
+use consensus_core::config::params::MAINNET_PARAMS;
 use consensus_core::{blockhash, blockstatus::BlockStatus, errors::block::RuleError};
 use futures_util::future::join_all;
 use hashes::Hash;
@@ -19,7 +22,7 @@ mod common;
 fn test_reachability_staging() {
     // Arrange
     let (_temp_db_lifetime, db) = create_temp_db();
-    let store = RwLock::new(DbReachabilityStore::new(db.clone(), 10000));
+    let store = RwLock::new(DbReachabilityStore::new(db.clone(), CachePolicy::Count(10000)));
     let mut staging = StagingReachabilityStore::new(store.upgradable_read());
 
     // Act
@@ -46,7 +49,7 @@ fn test_reachability_staging() {
     }
 
     // Clone with a new cache in order to verify correct writes to the DB itself
-    let store = store.read().clone_with_new_cache(10000);
+    let store = store.read().clone_with_new_cache(CachePolicy::Count(10000));
 
     // Assert intervals
     store.validate_intervals(blockhash::ORIGIN).unwrap();
@@ -82,7 +85,7 @@ async fn test_concurrent_pipeline() {
     let (_temp_db_lifetime, db) = create_temp_db();
 
     let mut params = MAINNET_PARAMS.clone_with_skip_pow();
-    params.genesis_hash = 1.into();
+    params.genesis.hash = 1.into();
 
     let consensus = TestConsensus::new(db, &params);
     let wait_handles = consensus.init();
@@ -110,7 +113,7 @@ async fn test_concurrent_pipeline() {
     }
 
     // Clone with a new cache in order to verify correct writes to the DB itself
-    let store = consensus.reachability_store().read().clone_with_new_cache(10000);
+    let store = consensus.reachability_store().read().clone_with_new_cache(CachePolicy::Count(10000));
 
     // Assert intervals
     store.validate_intervals(blockhash::ORIGIN).unwrap();
@@ -143,6 +146,64 @@ async fn test_concurrent_pipeline() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn test_batch_pipeline() {
+    let (_temp_db_lifetime, db) = create_temp_db();
+
+    let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+    params.genesis.hash = 1.into();
+
+    let consensus = TestConsensus::new(db, &params);
+    let wait_handles = consensus.init();
+
+    let blocks: Vec<(u64, Vec<u64>)> = vec![
+        (2, vec![1]),
+        (3, vec![1]),
+        (4, vec![2, 3]),
+        (5, vec![4]),
+        (6, vec![1]),
+        (7, vec![5, 6]),
+        (8, vec![1]),
+        (9, vec![1]),
+        (10, vec![7, 8, 9]),
+        (11, vec![1]),
+        (12, vec![11, 10]),
+    ];
+    let batch: Vec<_> = blocks
+        .into_iter()
+        .map(|(hash, parents)| {
+            let parents = parents.into_iter().map(Hash::from).collect();
+            consensus.build_block_with_parents_and_transactions(hash.into(), parents, vec![]).to_immutable()
+        })
+        .collect();
+
+    // Submit the first half twice, once within the batch, to make sure duplicates are handled
+    let duplicates = batch[..5].to_vec();
+    let (results, duplicate_results) =
+        join!(consensus.validate_and_insert_blocks(batch), consensus.validate_and_insert_blocks(duplicates));
+    assert_eq!(results.len(), 11);
+    assert_eq!(duplicate_results.len(), 5);
+    for status in results.into_iter().chain(duplicate_results) {
+        assert!(status.unwrap().has_block_body());
+    }
+    assert_eq!(consensus.consensus().body_tips().iter().copied().collect::<Vec<_>>(), vec![Hash::from(12u64)]);
+
+    // A block with a missing parent fails along with its descendant within the batch
+    let orphan = consensus.build_block_with_parents_and_transactions(13.into(), vec![100.into()], vec![]).to_immutable();
+    let child = consensus.build_block_with_parents_and_transactions(14.into(), vec![13.into()], vec![]).to_immutable();
+    let results = consensus.validate_and_insert_blocks(vec![orphan, child]).await;
+    assert!(matches!(results[0], Err(RuleError::MissingParents(_))));
+    assert!(results[1].is_err());
+
+    // Clone with a new cache in order to verify correct writes to the DB itself
+    let store = consensus.reachability_store().read().clone_with_new_cache(CachePolicy::Count(10000));
+    store.validate_intervals(blockhash::ORIGIN).unwrap();
+    assert!(store.in_past_of(5, 10));
+    assert!(store.are_anticone(11, 9));
+
+    consensus.shutdown(wait_handles);
+}
+
 #[tokio::test]
 async fn test_concurrent_pipeline_random() {
     let genesis: Hash = blockhash::new_unique();
@@ -155,7 +216,7 @@ async fn test_concurrent_pipeline_random() {
     let (_temp_db_lifetime, db) = create_temp_db();
 
     let mut params = MAINNET_PARAMS.clone_with_skip_pow();
-    params.genesis_hash = genesis;
+    params.genesis.hash = genesis;
 
     let consensus = TestConsensus::new(db, &params);
     let wait_handles = consensus.init();
@@ -186,7 +247,7 @@ async fn test_concurrent_pipeline_random() {
     }
 
     // Clone with a new cache in order to verify correct writes to the DB itself
-    let store = consensus.reachability_store().read().clone_with_new_cache(10000);
+    let store = consensus.reachability_store().read().clone_with_new_cache(CachePolicy::Count(10000));
 
     // Assert intervals
     store.validate_intervals(blockhash::ORIGIN).unwrap();
@@ -0,0 +1,119 @@
+//! Golden-bytes tests of the types persisted by the consensus stores.
+//!
+//! Each type is encoded and compared against a committed fixture under `testdata/serialization`, and the
+//! fixture is decoded and re-encoded to the same bytes. A failure means that existing databases can no longer
+//! be read correctly, e.g., due to reordered or retyped fields. Fixtures should only be updated along with a
+//! deliberate DB format change.
+//!
+//! Map-typed fields hold a single entry since the encoding order of several entries is not defined.
+
+use consensus::model::stores::{
+    acceptance_data::{AcceptanceData, AcceptanceStats},
+    depth::BlockDepthInfo,
+    ghostdag::{CompactGhostdagData, GhostdagData},
+    headers::{CompactHeaderData, HeaderWithBlockLevel},
+};
+use consensus_core::{
+    chain::MergesetBlockAcceptanceData,
+    header::Header,
+    time::TimestampMs,
+    tx::{ScriptPublicKey, ScriptVec, UtxoEntry},
+    BlockHashMap, BlueWorkType, HashMapCustomHasher,
+};
+use hashes::Hash;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{io::Read, sync::Arc};
+
+mod common;
+
+fn hash(byte: u8) -> Hash {
+    Hash::from_bytes([byte; 32])
+}
+
+fn blue_work() -> BlueWorkType {
+    // Distinct limbs so that a change of the limb order is detected
+    BlueWorkType([1, 2, 3])
+}
+
+fn assert_golden<T: Serialize + DeserializeOwned>(name: &str, value: &T) {
+    let mut fixture = String::new();
+    common::open_file(&format!("tests/testdata/serialization/{}.hex", name)).read_to_string(&mut fixture).unwrap();
+    let fixture = fixture.trim();
+
+    let encoded = faster_hex::hex_string(&bincode::serialize(value).unwrap());
+    assert_eq!(encoded, fixture, "the encoding of {} does not match its golden fixture", name);
+
+    let mut fixture_bytes = vec![0u8; fixture.len() / 2];
+    faster_hex::hex_decode(fixture.as_bytes(), &mut fixture_bytes).unwrap();
+    let decoded: T = bincode::deserialize(&fixture_bytes).unwrap();
+    assert_eq!(bincode::serialize(&decoded).unwrap(), fixture_bytes, "the golden fixture of {} does not round-trip", name);
+}
+
+#[test]
+fn ghostdag_data_serialization_test() {
+    let mut blues_anticone_sizes = BlockHashMap::new();
+    blues_anticone_sizes.insert(hash(2), 5);
+    let data = GhostdagData::new(
+        1000,
+        blue_work(),
+        hash(1),
+        Arc::new(vec![hash(1), hash(2)]),
+        Arc::new(vec![hash(3)]),
+        Arc::new(blues_anticone_sizes),
+    );
+    assert_golden("ghostdag_data", &data);
+    assert_golden(
+        "compact_ghostdag_data",
+        &CompactGhostdagData { blue_score: 1000, blue_work: blue_work(), selected_parent: hash(1) },
+    );
+}
+
+#[test]
+fn block_depth_info_serialization_test() {
+    assert_golden("block_depth_info", &BlockDepthInfo { merge_depth_root: hash(4), finality_point: hash(5) });
+}
+
+#[test]
+fn header_serialization_test() {
+    let header = Header {
+        hash: hash(6),
+        version: 1,
+        parents_by_level: vec![vec![hash(1), hash(2)], vec![hash(1)]],
+        hash_merkle_root: hash(7),
+        accepted_id_merkle_root: hash(8),
+        utxo_commitment: hash(9),
+        timestamp: TimestampMs::from_millis(1_700_000_000_000),
+        bits: 0x1e7fffff,
+        nonce: 42,
+        daa_score: 1234,
+        blue_work: blue_work(),
+        blue_score: 1000,
+        pruning_point: hash(10),
+    };
+    assert_golden("header_with_block_level", &HeaderWithBlockLevel { header: Arc::new(header), block_level: 2 });
+    assert_golden(
+        "compact_header_data",
+        &CompactHeaderData {
+            daa_score: 1234,
+            timestamp: TimestampMs::from_millis(1_700_000_000_000),
+            bits: 0x1e7fffff,
+            blue_score: 1000,
+        },
+    );
+}
+
+#[test]
+fn utxo_entry_serialization_test() {
+    let script_public_key = ScriptPublicKey::new(0, ScriptVec::from_slice(&[0x51, 0x52, 0x53]));
+    assert_golden("utxo_entry", &UtxoEntry::new(5_000_000_000, script_public_key, 1234, true));
+}
+
+#[test]
+fn acceptance_data_serialization_test() {
+    let stats = AcceptanceStats { mergeset_size: 2, accepted_tx_count: 3, accepted_mass: 2000, total_fees: 300 };
+    let mergeset = vec![
+        MergesetBlockAcceptanceData { block_hash: hash(1), accepted_transaction_ids: vec![hash(11)] },
+        MergesetBlockAcceptanceData { block_hash: hash(2), accepted_transaction_ids: vec![hash(12), hash(13)] },
+    ];
+    assert_golden("acceptance_data", &AcceptanceData::new(stats, mergeset));
+}
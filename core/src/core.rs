@@ -1,12 +1,45 @@
 use crate::service::Service;
 use crate::signals::Shutdown;
-use crate::trace;
+use crate::{error, trace, warn};
+use std::any::Any;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle, Thread};
+use std::time::{Duration, Instant};
+
+/// The default duration a service is given to stop its workers before they are detached
+pub const DEFAULT_SERVICE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The interval at which the run thread checks whether the workers exited
+const WORKERS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A bound service along with its running workers
+struct ServiceWorkers {
+    service: Arc<dyn Service>,
+    workers: Vec<JoinHandle<()>>,
+    restarts: usize,
+}
+
+impl ServiceWorkers {
+    /// Joins the workers which exited, returning the panic payload of the first one which panicked if any
+    fn reap(&mut self) -> Option<Box<dyn Any + Send>> {
+        let (finished, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.workers).into_iter().partition(JoinHandle::is_finished);
+        self.workers = running;
+        let mut panic = None;
+        for worker in finished {
+            if let Err(err) = worker.join() {
+                panic.get_or_insert(err);
+            }
+        }
+        panic
+    }
+}
 
 pub struct Core {
     pub keep_running: AtomicBool,
     services: Mutex<Vec<Arc<dyn Service>>>,
+    service_shutdown_timeout: Duration,
+    run_thread: Mutex<Option<Thread>>,
 }
 
 impl Default for Core {
@@ -17,9 +50,21 @@ impl Default for Core {
 
 impl Core {
     pub fn new() -> Core {
-        Core { keep_running: AtomicBool::new(true), services: Mutex::new(Vec::new()) }
+        Self::with_shutdown_timeout(DEFAULT_SERVICE_SHUTDOWN_TIMEOUT)
     }
 
+    /// Creates a core giving each service `service_shutdown_timeout` to stop its workers on shutdown
+    pub fn with_shutdown_timeout(service_shutdown_timeout: Duration) -> Core {
+        Core {
+            keep_running: AtomicBool::new(true),
+            services: Mutex::new(Vec::new()),
+            service_shutdown_timeout,
+            run_thread: Mutex::new(None),
+        }
+    }
+
+    /// Binds a service to the core. Services are started in bind order and stopped in reverse order, so a service
+    /// should be bound after the services it depends on.
     pub fn bind<T>(&self, service: Arc<T>)
     where
         T: Service,
@@ -27,28 +72,107 @@ impl Core {
         self.services.lock().unwrap().push(service);
     }
 
+    /// Starts all services and blocks until shutdown is requested or all workers exited, then stops the services
+    /// in reverse bind order. A panicking worker shuts the core down, unless its service has a restart policy.
     pub fn run(self: &Arc<Core>) {
-        let mut workers = Vec::new();
-        for service in self.services.lock().unwrap().iter() {
-            workers.append(&mut service.clone().start(self.clone()));
-        }
-        trace!("core is starting {} workers", workers.len());
+        *self.run_thread.lock().unwrap() = Some(thread::current());
+        let services = self.services.lock().unwrap().clone();
+        let mut services: Vec<ServiceWorkers> = services
+            .into_iter()
+            .map(|service| ServiceWorkers { workers: service.clone().start(self.clone()), service, restarts: 0 })
+            .collect();
+        trace!("core is starting {} workers", services.iter().map(|service| service.workers.len()).sum::<usize>());
 
-        // println!("starting termination...");
-        for worker in workers {
-            match worker.join() {
-                Ok(()) => {}
-                Err(err) => {
-                    trace!("thread join failure: {:?}", err);
+        while self.keep_running.load(Ordering::SeqCst) {
+            for service in services.iter_mut() {
+                if let Some(panic) = service.reap() {
+                    self.handle_panic(service, panic);
                 }
             }
+            if services.iter().all(|service| service.workers.is_empty()) {
+                break;
+            }
+            thread::park_timeout(WORKERS_POLL_INTERVAL);
+        }
+        self.keep_running.store(false, Ordering::SeqCst);
+
+        trace!("core is shutting down...");
+        for ServiceWorkers { service, workers, .. } in services.into_iter().rev() {
+            let ident = service.clone().ident();
+            trace!("shutting down: {}", ident);
+            service.stop();
+            self.join_workers(ident, workers);
         }
 
         trace!("... core is shut down");
     }
+
+    /// Restarts the service of a panicked worker according to its restart policy, or else shuts the core down
+    fn handle_panic(self: &Arc<Core>, service: &mut ServiceWorkers, panic: Box<dyn Any + Send>) {
+        let ident = service.service.clone().ident();
+        let policy = match service.service.clone().restart_policy() {
+            Some(policy) if service.restarts < policy.max_restarts => policy,
+            _ => {
+                error!("{} panicked: {}, shutting down", ident, panic_message(panic.as_ref()));
+                self.keep_running.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+        let backoff = policy.backoff(service.restarts);
+        service.restarts += 1;
+        warn!(
+            "{} panicked: {}, restarting it in {:?} ({}/{})",
+            ident,
+            panic_message(panic.as_ref()),
+            backoff,
+            service.restarts,
+            policy.max_restarts
+        );
+        service.service.clone().stop();
+        self.join_workers(ident, std::mem::take(&mut service.workers));
+
+        // The backoff is cut short by a shutdown request, in which case the service is not restarted
+        let deadline = Instant::now() + backoff;
+        while self.keep_running.load(Ordering::SeqCst) {
+            let now = Instant::now();
+            if now >= deadline {
+                service.workers = service.service.clone().start(self.clone());
+                return;
+            }
+            thread::park_timeout(deadline - now);
+        }
+    }
+
+    /// Joins the workers of a service, detaching them if they do not all exit within the service shutdown timeout
+    fn join_workers(&self, ident: &str, workers: Vec<JoinHandle<()>>) {
+        let deadline = Instant::now() + self.service_shutdown_timeout;
+        while !workers.iter().all(JoinHandle::is_finished) {
+            let now = Instant::now();
+            if now >= deadline {
+                let hanging = workers.iter().filter(|worker| !worker.is_finished()).count();
+                warn!("{} did not stop within {:?}, detaching its {} hanging workers", ident, self.service_shutdown_timeout, hanging);
+                return;
+            }
+            thread::sleep(WORKERS_POLL_INTERVAL.min(deadline - now));
+        }
+        for worker in workers {
+            if let Err(panic) = worker.join() {
+                warn!("{} worker panicked while stopping: {}", ident, panic_message(panic.as_ref()));
+            }
+        }
+    }
+}
+
+/// Extracts the message of a panic payload, which is a string unless the panic was raised with a custom payload
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    match panic.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => panic.downcast_ref::<String>().map_or("(non-string payload)", String::as_str),
+    }
 }
 
 impl Shutdown for Core {
+    /// Requests the shutdown of the core, which is run by the thread running [`Core::run`]
     fn shutdown(self: &Arc<Core>) {
         let keep_running = self.keep_running.load(Ordering::SeqCst);
         if !keep_running {
@@ -57,15 +181,165 @@ impl Shutdown for Core {
 
         trace!("signaling core shutdown...");
         self.keep_running.store(false, Ordering::SeqCst);
+        if let Some(run_thread) = self.run_thread.lock().unwrap().as_ref() {
+            run_thread.unpark();
+        }
+    }
+}
 
-        {
-            for service in self.services.lock().unwrap().iter() {
-                let ident = service.clone().ident();
-                trace!("shutting down: {}", ident);
-                service.clone().stop();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::RestartPolicy;
+    use std::sync::atomic::AtomicUsize;
+
+    /// A service with a single worker, which runs until the service is stopped unless set to panic right away
+    struct TestService {
+        ident: &'static str,
+        panics: bool,
+        /// Whether the worker keeps running for a second regardless of the service being stopped
+        hangs: bool,
+        restart_policy: Option<RestartPolicy>,
+        running: AtomicBool,
+        starts: AtomicUsize,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TestService {
+        fn new(ident: &'static str, events: &Arc<Mutex<Vec<String>>>) -> Self {
+            Self {
+                ident,
+                panics: false,
+                hangs: false,
+                restart_policy: None,
+                running: AtomicBool::new(false),
+                starts: AtomicUsize::new(0),
+                events: events.clone(),
             }
         }
+    }
 
-        trace!("core is shutting down...");
+    impl Service for TestService {
+        fn ident(self: Arc<Self>) -> &'static str {
+            self.ident
+        }
+
+        fn start(self: Arc<Self>, _core: Arc<Core>) -> Vec<JoinHandle<()>> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            self.running.store(true, Ordering::SeqCst);
+            self.events.lock().unwrap().push(format!("start {}", self.ident));
+            let service = self.clone();
+            vec![thread::spawn(move || {
+                if service.panics {
+                    panic!("{} failed", service.ident);
+                }
+                if service.hangs {
+                    thread::sleep(Duration::from_secs(1));
+                }
+                while service.running.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            })]
+        }
+
+        fn stop(self: Arc<Self>) {
+            self.running.store(false, Ordering::SeqCst);
+            self.events.lock().unwrap().push(format!("stop {}", self.ident));
+        }
+
+        fn restart_policy(self: Arc<Self>) -> Option<RestartPolicy> {
+            self.restart_policy
+        }
+    }
+
+    #[test]
+    fn test_panic_shuts_down() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let core = Arc::new(Core::new());
+        let healthy = Arc::new(TestService::new("healthy", &events));
+        let mut failing = TestService::new("failing", &events);
+        failing.panics = true;
+        core.bind(healthy.clone());
+        core.bind(Arc::new(failing));
+
+        // Returns on its own once the panic is detected, having stopped all services
+        core.run();
+        assert!(!core.keep_running.load(Ordering::SeqCst));
+        assert!(!healthy.running.load(Ordering::SeqCst));
+        assert_eq!(healthy.starts.load(Ordering::SeqCst), 1);
+        assert_eq!(*events.lock().unwrap(), vec!["start healthy", "start failing", "stop failing", "stop healthy"]);
+    }
+
+    #[test]
+    fn test_restart_policy() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let core = Arc::new(Core::new());
+        let healthy = Arc::new(TestService::new("healthy", &events));
+        let mut failing = TestService::new("failing", &events);
+        failing.panics = true;
+        failing.restart_policy = Some(RestartPolicy::new(2, Duration::from_millis(1)));
+        let failing = Arc::new(failing);
+        core.bind(healthy.clone());
+        core.bind(failing.clone());
+
+        // The service is restarted twice, after which its next panic shuts the core down
+        core.run();
+        assert_eq!(failing.starts.load(Ordering::SeqCst), 3);
+        assert_eq!(healthy.starts.load(Ordering::SeqCst), 1);
+        let events = events.lock().unwrap();
+        assert_eq!(events.iter().filter(|event| *event == "stop failing").count(), 3);
+        assert_eq!(events.iter().filter(|event| *event == "stop healthy").count(), 1);
+        assert_eq!(events.last().unwrap(), "stop healthy");
+
+        let policy = RestartPolicy::new(5, Duration::from_millis(10));
+        assert_eq!(
+            (policy.backoff(0), policy.backoff(1), policy.backoff(3)),
+            (policy.initial_backoff, Duration::from_millis(20), Duration::from_millis(80))
+        );
+    }
+
+    #[test]
+    fn test_shutdown_order() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let core = Arc::new(Core::new());
+        let services: Vec<_> = ["a", "b", "c"].into_iter().map(|ident| Arc::new(TestService::new(ident, &events))).collect();
+        for service in services.iter() {
+            core.bind(service.clone());
+        }
+        let run_core = core.clone();
+        let run = thread::spawn(move || run_core.run());
+        while events.lock().unwrap().len() < 3 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // Services are started in bind order and stopped in reverse order, each once even if shutdown is requested again
+        core.shutdown();
+        core.shutdown();
+        run.join().unwrap();
+        core.shutdown();
+        assert_eq!(*events.lock().unwrap(), vec!["start a", "start b", "start c", "stop c", "stop b", "stop a"]);
+        assert!(services.iter().all(|service| !service.running.load(Ordering::SeqCst)));
+    }
+
+    #[test]
+    fn test_shutdown_timeout() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let core = Arc::new(Core::with_shutdown_timeout(Duration::from_millis(50)));
+        let mut hanging = TestService::new("hanging", &events);
+        hanging.hangs = true;
+        core.bind(Arc::new(TestService::new("db", &events)));
+        core.bind(Arc::new(hanging));
+        let run_core = core.clone();
+        let run = thread::spawn(move || run_core.run());
+        while events.lock().unwrap().len() < 2 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // The hanging worker is detached after the timeout, so that the services bound before it are still stopped
+        let start = Instant::now();
+        core.shutdown();
+        run.join().unwrap();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(*events.lock().unwrap(), vec!["start db", "start hanging", "stop hanging", "stop db"]);
     }
 }
@@ -5,7 +5,7 @@ pub mod console;
 pub mod log;
 
 cfg_if::cfg_if! {
-    if #[cfg(not(target_arch = "wasm32"))] {
+    if #[cfg(all(not(target_arch = "wasm32"), feature = "runtime"))] {
         pub mod core;
         pub mod service;
         pub mod signals;
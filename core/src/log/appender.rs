@@ -0,0 +1,121 @@
+//! Log outputs beyond the console: a size based rolling log file, and a logger writing to several outputs
+
+use log::{Log, Metadata, Record};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// A log file which is rolled once it exceeds a maximum size. The full file `<name>` is renamed `<name>.1`, the previous
+/// `<name>.1` is renamed `<name>.2` and so on, up to `max_rolls` files.
+pub struct RollingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_rolls: usize,
+    file: File,
+    size: u64,
+}
+
+impl RollingFile {
+    pub fn new(dir: &Path, name: &str, max_size: u64, max_rolls: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(name);
+        let (file, size) = Self::open(&path)?;
+        Ok(Self { path, max_size, max_rolls, file, size })
+    }
+
+    fn open(path: &Path) -> io::Result<(File, u64)> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    }
+
+    fn rolled_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let oldest = self.rolled_path(self.max_rolls);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+        for index in (1..self.max_rolls).rev() {
+            let rolled = self.rolled_path(index);
+            if rolled.exists() {
+                fs::rename(rolled, self.rolled_path(index + 1))?;
+            }
+        }
+        if self.max_rolls > 0 {
+            fs::rename(&self.path, self.rolled_path(1))?;
+        } else {
+            fs::remove_file(&self.path)?;
+        }
+        (self.file, self.size) = Self::open(&self.path)?;
+        Ok(())
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A record is written at once, so it never spans two files
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.roll()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Writes each record to all loggers enabled for it
+pub struct MultiLogger {
+    loggers: Vec<env_logger::Logger>,
+}
+
+impl MultiLogger {
+    pub fn new(loggers: Vec<env_logger::Logger>) -> Self {
+        Self { loggers }
+    }
+}
+
+impl Log for MultiLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.loggers.iter().any(|logger| logger.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        self.loggers.iter().for_each(|logger| logger.log(record));
+    }
+
+    fn flush(&self) {
+        self.loggers.iter().for_each(|logger| logger.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_file() {
+        let dir = std::env::temp_dir().join(format!("kaspa-rolling-file-{}", std::process::id()));
+        let mut file = RollingFile::new(&dir, "test.log", 10, 2).unwrap();
+        for record in ["aaaaaa", "bbbbbb", "cccccc", "dddddd"] {
+            file.write_all(record.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+        assert_eq!(fs::read_to_string(dir.join("test.log")).unwrap(), "dddddd");
+        assert_eq!(fs::read_to_string(dir.join("test.log.1")).unwrap(), "cccccc");
+        assert_eq!(fs::read_to_string(dir.join("test.log.2")).unwrap(), "bbbbbb");
+        assert!(!dir.join("test.log.3").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -19,17 +19,55 @@ cfg_if::cfg_if! {
     }
 }
 
-// TODO: enhance logger with parallel output to file, rotation, compression
+#[cfg(all(not(target_arch = "wasm32"), feature = "logger"))]
+mod appender;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn init_logger(filters: &str) {
-    env_logger::Builder::new()
-        .format_target(false)
-        .format_timestamp_secs()
-        .filter_level(log::LevelFilter::Info)
-        .parse_default_env()
-        .parse_filters(filters)
-        .init();
+/// The name of the log file, rolled into `<name>.1`, `<name>.2`, ... once full
+#[cfg(all(not(target_arch = "wasm32"), feature = "logger"))]
+pub const LOG_FILE_NAME: &str = "rusty-kaspa.log";
+
+/// The size of the log file above which it is rolled
+#[cfg(all(not(target_arch = "wasm32"), feature = "logger"))]
+pub const LOG_FILE_MAX_SIZE: u64 = 100 * 1024 * 1024;
+
+/// The number of rolled log files kept, the oldest being deleted
+#[cfg(all(not(target_arch = "wasm32"), feature = "logger"))]
+pub const LOG_FILE_MAX_ROLLS: usize = 8;
+
+/// Initializes the logger, writing to the console with colors when it is a terminal and, if `log_dir` is set, to a
+/// rolling log file in that directory.
+///
+/// `filters` sets the level of all modules and optionally of individual ones, as in `info,p2p=debug,consensus=trace`.
+/// The `RUST_LOG` environment variable is applied first, so the filters override it.
+#[cfg(all(not(target_arch = "wasm32"), feature = "logger"))]
+pub fn init_logger(log_dir: Option<&str>, filters: &str) {
+    let builder = || {
+        let mut builder = env_logger::Builder::new();
+        builder
+            .format_target(false)
+            .format_timestamp_secs()
+            .filter_level(LevelFilter::Info)
+            .parse_default_env()
+            .parse_filters(filters);
+        builder
+    };
+    let mut loggers = vec![builder().write_style(env_logger::WriteStyle::Auto).build()];
+    let file_error = log_dir.and_then(|log_dir| {
+        match appender::RollingFile::new(std::path::Path::new(log_dir), LOG_FILE_NAME, LOG_FILE_MAX_SIZE, LOG_FILE_MAX_ROLLS) {
+            Ok(file) => {
+                let target = env_logger::Target::Pipe(Box::new(file));
+                loggers.push(builder().target(target).write_style(env_logger::WriteStyle::Never).build());
+                None
+            }
+            Err(err) => Some(err),
+        }
+    });
+
+    log::set_max_level(loggers.iter().map(|logger| logger.filter()).max().unwrap());
+    log::set_boxed_logger(Box::new(appender::MultiLogger::new(loggers))).expect("the logger can only be initialized once");
+    if let Some(err) = file_error {
+        log::warn!("Logging to the console only, as the log file cannot be opened in {}: {}", log_dir.unwrap(), err);
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
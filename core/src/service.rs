@@ -1,9 +1,35 @@
 use crate::core::Core;
 use intertrait::CastFromSync;
-use std::{sync::Arc, thread::JoinHandle};
+use std::{sync::Arc, thread::JoinHandle, time::Duration};
 
+/// A service bound to the [`Core`], running on its own worker threads. By default, a panic of one of its workers shuts
+/// the node down.
 pub trait Service: CastFromSync {
     fn ident(self: Arc<Self>) -> &'static str;
     fn start(self: Arc<Self>, core: Arc<Core>) -> Vec<JoinHandle<()>>;
     fn stop(self: Arc<Self>);
+
+    /// The restart policy of a non-critical service, which is then stopped and started again when one of its workers
+    /// panics, rather than shutting the node down. Such a service must support being started after being stopped.
+    fn restart_policy(self: Arc<Self>) -> Option<RestartPolicy> {
+        None
+    }
+}
+
+/// Restarts a service up to `max_restarts` times, waiting an exponentially growing backoff before each restart
+#[derive(Clone, Copy, Debug)]
+pub struct RestartPolicy {
+    pub max_restarts: usize,
+    pub initial_backoff: Duration,
+}
+
+impl RestartPolicy {
+    pub const fn new(max_restarts: usize, initial_backoff: Duration) -> Self {
+        Self { max_restarts, initial_backoff }
+    }
+
+    /// The backoff before the restart following `restarts` previous restarts
+    pub fn backoff(&self, restarts: usize) -> Duration {
+        self.initial_backoff.saturating_mul(1u32.checked_shl(restarts as u32).unwrap_or(u32::MAX))
+    }
 }
@@ -1,6 +1,14 @@
+//! OS signal handling. The first termination signal (ctrl-c, SIGINT, SIGTERM or SIGHUP on Unix, a console control event
+//! on Windows) shuts the target down gracefully, and a second one forces the process to quit at once, so that a node
+//! stopped by a container runtime or a service manager flushes its state before exiting.
+
+use crate::warn;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
 
+/// The exit code of a process forced to quit by a second signal
+pub const FORCE_QUIT_EXIT_CODE: i32 = 1;
+
 pub trait Shutdown {
     fn shutdown(self: &Arc<Self>);
 }
@@ -16,20 +24,40 @@ impl<T: Shutdown + Send + Sync> Signals<T> {
     }
 
     pub fn init(self: &Arc<Signals<T>>) {
-        let core = self.target.clone();
         let signals = self.clone();
-        ctrlc::set_handler(move || {
-            let v = signals.iterations.fetch_add(1, Ordering::SeqCst);
-            if v > 1 {
-                println!("^SIGNAL - halting");
-                std::process::exit(1);
-            }
-
-            println!("^SIGNAL - shutting down... (CTRL+C again to halt)");
-            if let Some(actual_target) = core.upgrade() {
-                actual_target.shutdown();
-            }
-        })
-        .expect("Error setting signal handler");
+        ctrlc::set_handler(move || signals.on_signal()).expect("Error setting signal handler");
+    }
+
+    fn on_signal(&self) {
+        if self.iterations.fetch_add(1, Ordering::SeqCst) > 0 {
+            warn!("^SIGNAL - halting");
+            std::process::exit(FORCE_QUIT_EXIT_CODE);
+        }
+
+        warn!("^SIGNAL - shutting down... (signal again to halt)");
+        match self.target.upgrade() {
+            Some(target) => target.shutdown(),
+            // The target was already dropped, so there is nothing left to shut down gracefully
+            None => std::process::exit(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Core;
+
+    #[test]
+    fn test_signal_shuts_down() {
+        let core = Arc::new(Core::new());
+        let signals = Signals::new(&core);
+        assert!(core.keep_running.load(Ordering::SeqCst));
+
+        // The first signal requests a graceful shutdown of the target, which is only weakly held
+        signals.on_signal();
+        assert!(!core.keep_running.load(Ordering::SeqCst));
+        assert_eq!(signals.iterations.load(Ordering::SeqCst), 1);
+        assert_eq!(Arc::strong_count(&core), 1);
     }
 }
@@ -13,8 +13,13 @@ const ASYNC_RUNTIME: &str = "async-runtime";
 
 /// AsyncRuntime registers async services and provides
 /// a tokio Runtime to run them.
+///
+/// The runtime is itself a [`Service`] bound to [`Core`], through which the async services take part in the
+/// startup and shutdown of the node. Services are started in registration order and stopped in reverse order,
+/// so a service should be registered after the services it depends on.
 pub struct AsyncRuntime {
     services: Mutex<Vec<Arc<dyn AsyncService>>>,
+    worker_threads: usize,
 }
 
 impl Default for AsyncRuntime {
@@ -24,9 +29,14 @@ impl Default for AsyncRuntime {
 }
 
 impl AsyncRuntime {
+    /// Creates a runtime with a worker thread per available core
     pub fn new() -> Self {
-        trace!("Creating the async-runtime service");
-        Self { services: Mutex::new(Vec::new()) }
+        Self::with_worker_threads(thread::available_parallelism().map_or(2, |threads| threads.get()))
+    }
+
+    pub fn with_worker_threads(worker_threads: usize) -> Self {
+        trace!("Creating the async-runtime service with {} worker threads", worker_threads);
+        Self { services: Mutex::new(Vec::new()), worker_threads }
     }
 
     pub fn register<T>(&self, service: Arc<T>)
@@ -42,30 +52,42 @@ impl AsyncRuntime {
     }
 
     /// Launch a tokio Runtime and run the top-level async objects
-    #[tokio::main(worker_threads = 2)]
-    // TODO: increase the number of threads if needed
-    // TODO: build the runtime explicitly and dedicate a number of threads based on the host specs
-    pub async fn worker(self: &Arc<AsyncRuntime>) {
+    pub fn worker(self: &Arc<AsyncRuntime>) {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(self.worker_threads)
+            .enable_all()
+            .build()
+            .expect("failed building the tokio runtime")
+            .block_on(self.run())
+    }
+
+    async fn run(self: &Arc<AsyncRuntime>) {
         // Start all async services
         // All services futures are spawned as tokio tasks to enable parallelism
         trace!("async-runtime worker starting");
-        let futures =
-            self.services.lock().unwrap().iter().map(|x| tokio::spawn(x.clone().start())).collect::<Vec<TaskJoinHandle<()>>>();
+        let services = self.services.lock().unwrap().clone();
+        let futures = services
+            .iter()
+            .map(|service| {
+                trace!("async-runtime starting {}", service.clone().ident());
+                tokio::spawn(service.clone().start())
+            })
+            .collect::<Vec<TaskJoinHandle<()>>>();
         join_all(futures).await.into_iter().collect::<Result<Vec<()>, JoinError>>().unwrap();
 
-        // Stop all async services
-        // All services futures are spawned as tokio tasks to enable parallelism
+        // Stop all async services in reverse order, each one once the services depending on it are stopped
         trace!("async-runtime worker stopping");
-        let futures =
-            self.services.lock().unwrap().iter().map(|x| tokio::spawn(x.clone().stop())).collect::<Vec<TaskJoinHandle<()>>>();
-        join_all(futures).await.into_iter().collect::<Result<Vec<()>, JoinError>>().unwrap();
+        for service in services.iter().rev() {
+            trace!("async-runtime stopping {}", service.clone().ident());
+            tokio::spawn(service.clone().stop()).await.unwrap();
+        }
 
         trace!("async-runtime worker exiting");
     }
 
     pub fn signal_exit(self: Arc<AsyncRuntime>) {
         trace!("Sending an exit signal to all async-runtime services");
-        for service in self.services.lock().unwrap().iter() {
+        for service in self.services.lock().unwrap().iter().rev() {
             service.clone().signal_exit();
         }
     }
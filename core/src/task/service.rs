@@ -5,9 +5,17 @@ use std::sync::Arc;
 
 pub type AsyncServiceFuture = BoxFuture<'static, ()>;
 
+/// A service running on the tokio runtime of an [`AsyncRuntime`](super::runtime::AsyncRuntime), the async
+/// counterpart of [`Service`](crate::service::Service).
 pub trait AsyncService: CastFromSync {
     fn ident(self: Arc<Self>) -> &'static str;
+
+    /// Returns a future running the service, which resolves once the service exits after [`AsyncService::signal_exit`]
     fn start(self: Arc<Self>) -> AsyncServiceFuture;
+
+    /// Signals the service to exit, without waiting
     fn signal_exit(self: Arc<Self>);
+
+    /// Returns a future releasing the resources of the service, polled once all start futures resolved
     fn stop(self: Arc<Self>) -> AsyncServiceFuture;
 }
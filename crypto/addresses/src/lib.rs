@@ -1,5 +1,5 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 
 mod bech32;
@@ -77,22 +77,29 @@ impl TryFrom<&str> for Prefix {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(PartialEq, Eq, Clone, Debug, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct Address {
     pub prefix: Prefix,
     pub payload: Vec<u8>,
     pub version: u8,
 }
 
+/// Displays the address as encoded for users, i.e., prefixed and checksummed
+impl Display for Address {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.prefix, self.encode_payload())
+    }
+}
+
 impl From<Address> for String {
     fn from(address: Address) -> Self {
-        (&address).into()
+        address.to_string()
     }
 }
 
 impl From<&Address> for String {
     fn from(address: &Address) -> Self {
-        format!("{}:{}", address.prefix, address.encode_payload())
+        address.to_string()
     }
 }
 
@@ -107,6 +114,37 @@ impl TryFrom<String> for Address {
     }
 }
 
+/// The serde representation of an address in binary formats
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Address")]
+struct AddressFields {
+    prefix: Prefix,
+    payload: Vec<u8>,
+    version: u8,
+}
+
+/// Human readable formats, such as JSON, get the checksummed string of [`Display`]. Binary formats keep the fields.
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            AddressFields { prefix: self.prefix, payload: self.payload.clone(), version: self.version }.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            Address::try_from(<String as Deserialize>::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+        } else {
+            let AddressFields { prefix, payload, version } = AddressFields::deserialize(deserializer)?;
+            Ok(Address { prefix, payload, version })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -147,6 +185,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_display_and_serde() {
+        for (address, address_str) in cases() {
+            assert_eq!(address.to_string(), address_str);
+            let json = serde_json::to_string(&address).unwrap();
+            assert_eq!(json, format!("\"{}\"", address_str));
+            assert_eq!(serde_json::from_str::<Address>(&json).unwrap(), address);
+        }
+        let (address, address_str) = cases().pop().unwrap();
+        let bad_checksum = format!("\"{}x\"", &address_str[..address_str.len() - 1]);
+        assert!(serde_json::from_str::<Address>(&bad_checksum).is_err());
+        assert_eq!(bincode::deserialize::<Address>(&bincode::serialize(&address).unwrap()).unwrap(), address);
+    }
+
     #[test]
     fn test_errors() {
         let address_str: String = "kaspa:qqqqqqqqqqqqq1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e".to_string();
@@ -0,0 +1,83 @@
+use crate::{MnemonicError, MnemonicResult};
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// The number of words of a wordlist, each word encoding 11 bits
+pub const WORDLIST_SIZE: usize = 2048;
+
+/// A wordlist parsed from its BIP-39 file, along with the index of each word for reverse lookups
+struct Wordlist {
+    words: Vec<&'static str>,
+    indices: HashMap<&'static str, u16>,
+}
+
+impl Wordlist {
+    /// Parses a wordlist file, one word per line. The files are kept byte identical to the BIP-39 reference
+    /// wordlists, whose words are NFKD normalized
+    fn parse(file: &'static str) -> Self {
+        let words: Vec<_> = file.lines().collect();
+        assert_eq!(words.len(), WORDLIST_SIZE);
+        let indices = words.iter().enumerate().map(|(i, &word)| (word, i as u16)).collect();
+        Self { words, indices }
+    }
+}
+
+static ENGLISH: Lazy<Wordlist> = Lazy::new(|| Wordlist::parse(include_str!("wordlists/english.txt")));
+static FRENCH: Lazy<Wordlist> = Lazy::new(|| Wordlist::parse(include_str!("wordlists/french.txt")));
+static SPANISH: Lazy<Wordlist> = Lazy::new(|| Wordlist::parse(include_str!("wordlists/spanish.txt")));
+
+/// The language of a mnemonic, i.e., the wordlist encoding its entropy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::French, Language::Spanish];
+
+    fn wordlist(self) -> &'static Wordlist {
+        match self {
+            Language::English => &ENGLISH,
+            Language::French => &FRENCH,
+            Language::Spanish => &SPANISH,
+        }
+    }
+
+    /// Returns the word at `index` of the wordlist. Panics if `index` is beyond [`WORDLIST_SIZE`]
+    pub fn word(self, index: u16) -> &'static str {
+        self.wordlist().words[index as usize]
+    }
+
+    /// Returns the index of an NFKD normalized word in the wordlist, if found
+    pub fn find_word(self, word: &str) -> Option<u16> {
+        self.wordlist().indices.get(word).copied()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::French => "french",
+            Language::Spanish => "spanish",
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Language {
+    type Err = MnemonicError;
+
+    fn from_str(s: &str) -> MnemonicResult<Self> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| MnemonicError::UnknownLanguage(s.to_string()))
+    }
+}
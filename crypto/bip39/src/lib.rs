@@ -0,0 +1,284 @@
+//! BIP-39 mnemonics.
+//!
+//! A [`Mnemonic`] encodes 128 to 256 bits of entropy, along with a checksum, as a sentence of 12 to 24 words of a
+//! [`Language`] wordlist. The binary seed wallets derive their keys from is obtained from the sentence and an
+//! optional passphrase. Sentences and passphrases are NFKD normalized, so that a sentence typed in any Unicode form
+//! yields the same seed.
+
+mod language;
+mod pbkdf2;
+
+pub use language::{Language, WORDLIST_SIZE};
+pub use pbkdf2::{hmac_sha512, pbkdf2_hmac_sha512};
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::fmt::Display;
+use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+
+/// The number of PBKDF2 rounds deriving a seed out of a mnemonic
+const SEED_ROUNDS: u32 = 2048;
+
+/// The size of a seed derived out of a mnemonic
+pub const SEED_SIZE: usize = pbkdf2::OUTPUT_SIZE;
+
+/// The word counts of valid mnemonics, i.e., 128 to 256 bits of entropy in steps of 32 bits, each step adding a
+/// checksum bit
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// The number of bits encoded by a word
+const BITS_PER_WORD: usize = 11;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MnemonicError {
+    #[error("invalid mnemonic word count {0}, expected 12, 15, 18, 21 or 24 words")]
+    InvalidWordCount(usize),
+
+    #[error("invalid entropy length of {0} bytes, expected 16 to 32 bytes in steps of 4")]
+    InvalidEntropyLength(usize),
+
+    #[error("word {0} is not in the {1} wordlist")]
+    UnknownWord(String, Language),
+
+    #[error("invalid mnemonic checksum")]
+    InvalidChecksum,
+
+    #[error("unknown mnemonic language {0}")]
+    UnknownLanguage(String),
+}
+
+pub type MnemonicResult<T> = Result<T, MnemonicError>;
+
+/// A BIP-39 mnemonic, held as its entropy along with the language of its words
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mnemonic {
+    entropy: Vec<u8>,
+    language: Language,
+}
+
+impl Mnemonic {
+    /// Builds the mnemonic encoding `entropy`, which must be 16 to 32 bytes long in steps of 4
+    pub fn from_entropy(entropy: &[u8], language: Language) -> MnemonicResult<Self> {
+        if !(16..=32).contains(&entropy.len()) || entropy.len() % 4 != 0 {
+            return Err(MnemonicError::InvalidEntropyLength(entropy.len()));
+        }
+        Ok(Self { entropy: entropy.to_vec(), language })
+    }
+
+    /// Generates a mnemonic of `word_count` words out of random entropy
+    pub fn random(word_count: usize, language: Language) -> MnemonicResult<Self> {
+        if !WORD_COUNTS.contains(&word_count) {
+            return Err(MnemonicError::InvalidWordCount(word_count));
+        }
+        let mut entropy = vec![0u8; entropy_len(word_count)];
+        rand::thread_rng().fill_bytes(&mut entropy);
+        Self::from_entropy(&entropy, language)
+    }
+
+    /// Parses a mnemonic sentence of the given language, validating its checksum. Words may be separated by any
+    /// whitespace
+    pub fn parse(phrase: &str, language: Language) -> MnemonicResult<Self> {
+        let phrase: String = phrase.nfkd().collect();
+        let words: Vec<_> = phrase.split_whitespace().collect();
+        if !WORD_COUNTS.contains(&words.len()) {
+            return Err(MnemonicError::InvalidWordCount(words.len()));
+        }
+
+        // Concatenate the 11 bits of each word, the entropy bits being followed by the checksum bits
+        let mut bits = vec![0u8; (words.len() * BITS_PER_WORD + 7) / 8];
+        for (i, word) in words.iter().enumerate() {
+            let index = language.find_word(word).ok_or_else(|| MnemonicError::UnknownWord(word.to_string(), language))?;
+            for bit in 0..BITS_PER_WORD {
+                if index & (1 << (BITS_PER_WORD - 1 - bit)) != 0 {
+                    let position = i * BITS_PER_WORD + bit;
+                    bits[position / 8] |= 1 << (7 - position % 8);
+                }
+            }
+        }
+
+        let mnemonic = Self::from_entropy(&bits[..entropy_len(words.len())], language)?;
+        if mnemonic.word_indices().ne(words.iter().map(|word| language.find_word(word).unwrap())) {
+            return Err(MnemonicError::InvalidChecksum);
+        }
+        Ok(mnemonic)
+    }
+
+    /// Parses a mnemonic sentence of any supported language, the language being that of its first word
+    pub fn parse_in_any_language(phrase: &str) -> MnemonicResult<Self> {
+        let phrase: String = phrase.nfkd().collect();
+        let first_word = phrase.split_whitespace().next().unwrap_or_default();
+        // Words may be shared across wordlists, in which case the checksum settles the language
+        let mut result = Err(MnemonicError::UnknownWord(first_word.to_string(), Language::default()));
+        for language in Language::ALL.into_iter().filter(|language| language.find_word(first_word).is_some()) {
+            result = Self::parse(&phrase, language);
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    pub fn entropy(&self) -> &[u8] {
+        &self.entropy
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.entropy.len() * 8 * 33 / 32 / BITS_PER_WORD
+    }
+
+    /// Returns the wordlist indices of the words, i.e., the entropy bits followed by the checksum bits, in groups
+    /// of 11 bits
+    fn word_indices(&self) -> impl Iterator<Item = u16> + '_ {
+        let checksum = Sha256::digest(&self.entropy);
+        let bit = move |position: usize| {
+            let byte = if position < self.entropy.len() * 8 {
+                self.entropy[position / 8]
+            } else {
+                checksum[position / 8 - self.entropy.len()]
+            };
+            (byte >> (7 - position % 8)) & 1
+        };
+        (0..self.word_count()).map(move |i| (0..BITS_PER_WORD).fold(0u16, |index, b| (index << 1) | bit(i * BITS_PER_WORD + b) as u16))
+    }
+
+    /// Returns the words of the mnemonic, NFKD normalized
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.word_indices().map(|index| self.language.word(index))
+    }
+
+    /// Returns the mnemonic sentence, i.e., its words separated by spaces
+    pub fn phrase(&self) -> String {
+        self.words().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Derives the binary seed of the mnemonic protected by `passphrase`, which may be empty
+    pub fn to_seed(&self, passphrase: &str) -> [u8; SEED_SIZE] {
+        let salt: String = "mnemonic".chars().chain(passphrase.nfkd()).collect();
+        pbkdf2::pbkdf2_hmac_sha512(self.phrase().as_bytes(), salt.as_bytes(), SEED_ROUNDS)
+    }
+}
+
+impl Display for Mnemonic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.phrase())
+    }
+}
+
+/// The entropy length in bytes of a mnemonic of `word_count` words, i.e., all but one bit out of 33 bits
+fn entropy_len(word_count: usize) -> usize {
+    word_count * BITS_PER_WORD * 32 / 33 / 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faster_hex::{hex_decode, hex_string};
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; hex.len() / 2];
+        hex_decode(hex.as_bytes(), &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_vectors() {
+        // Test vectors of the BIP-39 reference implementation, with the TREZOR passphrase, along with vectors of the
+        // other wordlists, whose sentences are typed below in NFC form
+        let tests = [
+            (
+                Language::English,
+                "00000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                Language::English,
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                Language::English,
+                "80808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+                "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+            ),
+            (
+                Language::English,
+                "ffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+                "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+            ),
+            (
+                Language::English,
+                "ffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo when",
+                "0cd6e5d827bb62eb8fc1e262254223817fd068a74b5b449cc2f667c3f1f985a76379b43348d952e2265b4cd129090758b3e3c2c49103b5051aac2eaeb890a528",
+            ),
+            (
+                Language::English,
+                "f585c11aec520db57dd353c69554b21a89b20fb0650966fa0a9d6f74fd989d8f",
+                "void come effort suffer camp survey warrior heavy shoot primary clutch crush open amazing screen patrol group space point ten exist slush involve unfold",
+                "01f5bced59dec48e362f2c45b5de68b9fd6c92c6634f44d6d40aab69056506f0e35524a518034ddc1192e1dacd32c1ed3eaa3c3b131c88ed8e7e54c49a5d0998",
+            ),
+            (
+                Language::French,
+                "9e885d952ad362caeb4efe34a8e91bd2",
+                "monument dépenser féroce entasser comédie ferveur optique sonnette codifier discuter dioxyde nerveux",
+                "d322acd69a849cce8719674eeb7cd76520de01ea35210012a44a5dcc19faf285202c3fb3c749a46d338ad54ddd398029ee308ee352a89f65180dbd3ff750dd50",
+            ),
+            (
+                Language::Spanish,
+                "9e885d952ad362caeb4efe34a8e91bd2",
+                "obra diadema gorila farmacia colgar gorra pausa talar cocina duda dragón optar",
+                "fcf6ebfc7d9eebab56ca868cbd2d5d05a6f2142ba903c52855dad4ab8c0c2cf6b4e047a2dd97cf382ae717dc18d155a45fc798e6f0a0b89971a4224e2a285701",
+            ),
+        ];
+        for (language, entropy, phrase, seed) in tests {
+            let mnemonic = Mnemonic::from_entropy(&from_hex(entropy), language).unwrap();
+            assert_eq!(mnemonic.phrase(), phrase.nfkd().collect::<String>());
+            assert_eq!(mnemonic.word_count(), phrase.split(' ').count());
+            assert_eq!(hex_string(&mnemonic.to_seed("TREZOR")), seed);
+            assert_eq!(Mnemonic::parse(phrase, language).unwrap(), mnemonic);
+            assert_eq!(Mnemonic::parse_in_any_language(phrase).unwrap(), mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |phrase: &str| Mnemonic::parse(phrase, Language::English);
+        assert_eq!(parse("abandon abandon abandon"), Err(MnemonicError::InvalidWordCount(3)));
+        assert_eq!(
+            parse("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon kaspa"),
+            Err(MnemonicError::UnknownWord("kaspa".to_string(), Language::English))
+        );
+        assert_eq!(
+            parse("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"),
+            Err(MnemonicError::InvalidChecksum)
+        );
+        // Extra whitespace is ignored
+        assert!(parse(" abandon abandon abandon abandon abandon abandon\tabandon abandon abandon abandon abandon\nabout ").is_ok());
+        assert_eq!(Mnemonic::from_entropy(&[0; 15], Language::English), Err(MnemonicError::InvalidEntropyLength(15)));
+    }
+
+    #[test]
+    fn test_random() {
+        for word_count in WORD_COUNTS {
+            let mnemonic = Mnemonic::random(word_count, Language::French).unwrap();
+            assert_eq!(mnemonic.word_count(), word_count);
+            assert_eq!(Mnemonic::parse(&mnemonic.phrase(), Language::French).unwrap(), mnemonic);
+        }
+        assert_eq!(Mnemonic::random(13, Language::English), Err(MnemonicError::InvalidWordCount(13)));
+    }
+
+    #[test]
+    fn test_language_from_str() {
+        assert_eq!("Spanish".parse::<Language>().unwrap(), Language::Spanish);
+        assert_eq!("klingon".parse::<Language>(), Err(MnemonicError::UnknownLanguage("klingon".to_string())));
+    }
+}
@@ -0,0 +1,89 @@
+use sha2::{Digest, Sha512};
+
+/// The SHA-512 input block size, to which HMAC keys are padded
+const BLOCK_SIZE: usize = 128;
+
+pub const OUTPUT_SIZE: usize = 64;
+
+/// HMAC-SHA512 keyed once, the padded key blocks being hashed ahead of all messages
+struct HmacSha512 {
+    inner: Sha512,
+    outer: Sha512,
+}
+
+impl HmacSha512 {
+    fn new(key: &[u8]) -> Self {
+        let mut padded_key = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            padded_key[..OUTPUT_SIZE].copy_from_slice(Sha512::digest(key).as_slice());
+        } else {
+            padded_key[..key.len()].copy_from_slice(key);
+        }
+        Self {
+            inner: Sha512::new().chain_update(padded_key.map(|b| b ^ 0x36)),
+            outer: Sha512::new().chain_update(padded_key.map(|b| b ^ 0x5c)),
+        }
+    }
+
+    fn mac(&self, message: &[&[u8]]) -> [u8; OUTPUT_SIZE] {
+        let mut inner = self.inner.clone();
+        message.iter().for_each(|part| inner.update(part));
+        let mut out = [0u8; OUTPUT_SIZE];
+        out.copy_from_slice(self.outer.clone().chain_update(inner.finalize()).finalize().as_slice());
+        out
+    }
+}
+
+/// HMAC-SHA512 of the concatenation of the `message` parts, which BIP-32 key derivation is built upon as well
+pub fn hmac_sha512(key: &[u8], message: &[&[u8]]) -> [u8; OUTPUT_SIZE] {
+    HmacSha512::new(key).mac(message)
+}
+
+/// PBKDF2 with HMAC-SHA512, deriving a single output block, which is all BIP-39 seeds require
+pub fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], rounds: u32) -> [u8; OUTPUT_SIZE] {
+    let hmac = HmacSha512::new(password);
+    let mut block = hmac.mac(&[salt, &1u32.to_be_bytes()]);
+    let mut out = block;
+    for _ in 1..rounds {
+        block = hmac.mac(&[block.as_slice()]);
+        out.iter_mut().zip(block.iter()).for_each(|(out, b)| *out ^= b);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faster_hex::hex_string;
+
+    #[test]
+    fn test_hmac_sha512() {
+        // RFC 4231 test case 2, with the message split into parts
+        let mac = hmac_sha512(b"Jefe", &[b"what do ya ", b"want for nothing?"]);
+        assert_eq!(
+            hex_string(&mac),
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_hmac_sha512() {
+        let long_password = [b'x'; 200];
+        let tests = [
+            (
+                b"password".as_slice(),
+                1,
+                "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce",
+            ),
+            // A password longer than the block size is hashed into the key
+            (
+                long_password.as_slice(),
+                2,
+                "87c9db0b7c12b94bec5039ae603028ec5076234596401c3a2899f16dfe93479f2c3471672af152683f243d595ed1a7f3430ec13fda297c99e3989ea03d53f1cd",
+            ),
+        ];
+        for (password, rounds, expected) in tests {
+            assert_eq!(hex_string(&pbkdf2_hmac_sha512(password, b"salt", rounds)), expected);
+        }
+    }
+}
@@ -29,6 +29,9 @@ blake2b_hasher! {
     struct MerkleBranchHash => b"MerkleBranchHash",
     struct MuHashElementHash => b"MuHashElement",
     struct MuHashFinalizeHash => b"MuHashFinalize",
+    struct SigScriptHash => b"SigScriptHash",
+    struct PayloadHash => b"PayloadHash",
+    struct ScriptPublicKeyHash => b"ScriptPublicKeyHash",
 }
 
 sha256_hasher! {
@@ -207,6 +210,18 @@ mod tests {
             ],
         );
 
+        run_test_vector(
+            &input_data,
+            SigScriptHash::new,
+            &[
+                "07a81abb636fa4237691f962f83f37d508cf81495685a53e22ad5da0f5f0d250",
+                "514efe553f23be3133e82115162fbf6a8561dff2323716f72438b6347ceaaadb",
+                "675ec96906d61163f9c561107c9af31e9341f1ca25299f6bf020fc513c866de2",
+                "e2f1faa4fba5f7e149bbc3ed9fe5f7cb33adbc8f573454a64815bac772091bdf",
+                "b21659f08b152ccfcc1db0687f28fc424de8630a8e0eb6063eb003416578154b",
+            ],
+        );
+
         run_test_vector(
             &input_data,
             BlockHash::new,
@@ -2,7 +2,7 @@ mod hashers;
 mod pow_hashers;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash as StdHash, Hasher as StdHasher};
 use std::str::{self, FromStr};
@@ -12,7 +12,7 @@ pub const HASH_SIZE: usize = 32;
 pub use hashers::*;
 
 // TODO: Check if we use hash more as an array of u64 or of bytes and change the default accordingly
-#[derive(Eq, Clone, Copy, Default, PartialOrd, Ord, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Eq, Clone, Copy, Default, PartialOrd, Ord, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct Hash([u8; HASH_SIZE]);
 
 impl Hash {
@@ -102,6 +102,34 @@ impl FromStr for Hash {
     }
 }
 
+/// The serde representation of a hash in binary formats, such as the bincode encoding of the database
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Hash")]
+struct HashBytes([u8; HASH_SIZE]);
+
+/// Human readable formats, such as JSON, get the same lowercase hex string as [`Display`], so that serialized
+/// hashes match the logs and block explorers. Binary formats keep the raw bytes.
+impl Serialize for Hash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            HashBytes(self.0).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let hash_str = <String as Deserialize>::deserialize(deserializer)?;
+            Hash::from_str(&hash_str).map_err(serde::de::Error::custom)
+        } else {
+            HashBytes::deserialize(deserializer).map(|bytes| Hash(bytes.0))
+        }
+    }
+}
+
 impl From<u64> for Hash {
     #[inline(always)]
     fn from(word: u64) -> Self {
@@ -140,4 +168,22 @@ mod tests {
         assert!(matches!(dbg!(Hash::from_str(odd_str)), Err(faster_hex::Error::InvalidLength(len)) if len == 64));
         assert!(matches!(dbg!(Hash::from_str(short_str)), Err(faster_hex::Error::InvalidLength(len)) if len == 64));
     }
+
+    #[test]
+    fn test_hash_serde() {
+        let hash_str = "8e40af02265360d59f4ecf9ae9ebf8f00a3118408f5a9cdcbcc9c0f93642f3af";
+        let hash = Hash::from_str(hash_str).unwrap();
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{}\"", hash_str));
+        assert_eq!(serde_json::from_str::<Hash>(&json).unwrap(), hash);
+        // Uppercase hex is accepted, while hashes are always written lowercase
+        assert_eq!(serde_json::from_str::<Hash>(&json.to_uppercase()).unwrap(), hash);
+        assert!(serde_json::from_str::<Hash>("\"8e40af\"").is_err());
+
+        // Binary formats keep the raw bytes, as stored in the database
+        let bytes = bincode::serialize(&hash).unwrap();
+        assert_eq!(bytes, hash.as_bytes());
+        assert_eq!(bincode::deserialize::<Hash>(&bytes).unwrap(), hash);
+    }
 }
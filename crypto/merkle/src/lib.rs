@@ -22,8 +22,72 @@ pub fn calc_merkle_root(hashes: impl ExactSizeIterator<Item = Hash>) -> Hash {
     merkles.last().unwrap().unwrap()
 }
 
+/// A proof of inclusion of a single leaf in a merkle tree built by [`calc_merkle_root`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// The index of the proven leaf
+    pub index: usize,
+    /// The sibling hashes along the path from the leaf up to the root (exclusive)
+    pub siblings: Vec<Hash>,
+}
+
+impl MerkleProof {
+    /// Calculates the root implied by this proof for the given `leaf`
+    pub fn calc_root(&self, leaf: Hash) -> Hash {
+        let mut index = self.index;
+        let mut current = leaf;
+        for sibling in self.siblings.iter().copied() {
+            current = if index % 2 == 0 { merkle_hash(current, sibling) } else { merkle_hash(sibling, current) };
+            index /= 2;
+        }
+        current
+    }
+
+    /// Verifies that `leaf` is included at `self.index` in the tree with the given `root`
+    pub fn verify(&self, leaf: Hash, root: Hash) -> bool {
+        self.calc_root(leaf) == root
+    }
+}
+
+/// Builds a proof of inclusion for the leaf at `index`. Returns `None` if `index` is out of range
+pub fn calc_merkle_proof(hashes: impl ExactSizeIterator<Item = Hash>, index: usize) -> Option<MerkleProof> {
+    if index >= hashes.len() {
+        return None;
+    }
+    let mut level: Vec<Option<Hash>> = hashes.map(Some).collect();
+    level.resize(level.len().next_power_of_two(), None);
+    let mut siblings = Vec::new();
+    let mut position = index;
+    while level.len() > 1 {
+        // Missing siblings are padded with the zero hash, in accordance with `calc_merkle_root`
+        siblings.push(level[position ^ 1].unwrap_or(ZERO_HASH));
+        level = level.chunks(2).map(|pair| pair[0].map(|left| merkle_hash(left, pair[1].unwrap_or(ZERO_HASH)))).collect();
+        position /= 2;
+    }
+    Some(MerkleProof { index, siblings })
+}
+
 fn merkle_hash(left: Hash, right: Hash) -> Hash {
     let mut hasher = MerkleBranchHash::new();
     hasher.update(left).update(right);
     hasher.finalize()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merkle_proof_test() {
+        for len in 1..=17u64 {
+            let leaves: Vec<Hash> = (0..len).map(|i| Hash::from_u64_word(i + 1)).collect();
+            let root = calc_merkle_root(leaves.iter().copied());
+            for (index, leaf) in leaves.iter().copied().enumerate() {
+                let proof = calc_merkle_proof(leaves.iter().copied(), index).unwrap();
+                assert!(proof.verify(leaf, root), "proof failed for leaf {} out of {}", index, len);
+                assert!(!proof.verify(Hash::from_u64_word(u64::MAX), root));
+            }
+            assert!(calc_merkle_proof(leaves.iter().copied(), len as usize).is_none());
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! The kaspa DB archive format, a portable dump of the raw entries of the node database.
+//!
+//! An archive starts with an 8 bytes magic followed by a little-endian u32 format version and the u32 schema
+//! version of the dumped DB. Entries follow in key order, each as a little-endian u32 key length, the key, a u32
+//! value length and the value. The archive ends with a u32::MAX marker followed by the u64 number of entries, so
+//! that a truncated archive is detected.
+
+use consensus::{
+    migrations::{BASE_SCHEMA_VERSION, SCHEMA_VERSION},
+    model::stores::{
+        errors::StoreResultExtensions,
+        metadata::{DbMetadataStore, MetadataStoreReader, SchemaVersion},
+        DB,
+    },
+};
+use rocksdb::{IteratorMode, WriteBatch};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::Arc,
+};
+use thiserror::Error;
+
+const MAGIC: &[u8; 8] = b"KASDBAR\0";
+const FORMAT_VERSION: u32 = 1;
+
+/// Marks the end of the entries in place of a key length
+const END_MARKER: u32 = u32::MAX;
+
+/// An upper bound on the length of a single key or value, protecting against corrupted length prefixes
+const MAX_FIELD_SIZE: u32 = 1 << 28;
+
+/// The number of entries written to the DB per batch during import
+const IMPORT_BATCH_SIZE: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Db(#[from] rocksdb::Error),
+
+    #[error("invalid archive: {0}")]
+    InvalidFormat(String),
+
+    #[error("the archive has schema version {0} while this tool supports up to version {1}")]
+    UnsupportedSchemaVersion(SchemaVersion, SchemaVersion),
+
+    #[error("the target database is not empty")]
+    TargetNotEmpty,
+}
+
+pub type ArchiveResult<T> = Result<T, ArchiveError>;
+
+/// The entries dumped into an archive
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Content {
+    /// All entries, which restores into a datadir a node can run from
+    All,
+    /// Block headers
+    Headers,
+    /// Block headers and bodies
+    Blocks,
+    /// The UTXO set of the virtual state
+    UtxoSet,
+}
+
+const HEADERS_PREFIXES: &[&[u8]] = &[b"headers", b"compact-header-data"];
+const BLOCKS_PREFIXES: &[&[u8]] = &[b"headers", b"compact-header-data", b"block-transactions", b"payloads"];
+const UTXO_SET_PREFIXES: &[&[u8]] = &[b"virtual-utxo-set"];
+
+impl Content {
+    /// The prefixes of the stores holding the content, or `None` for all stores
+    fn prefixes(self) -> Option<&'static [&'static [u8]]> {
+        match self {
+            Self::All => None,
+            Self::Headers => Some(HEADERS_PREFIXES),
+            Self::Blocks => Some(BLOCKS_PREFIXES),
+            Self::UtxoSet => Some(UTXO_SET_PREFIXES),
+        }
+    }
+
+    fn includes(self, key: &[u8]) -> bool {
+        match self.prefixes() {
+            Some(prefixes) => prefixes.iter().any(|prefix| store_prefix(key) == *prefix),
+            None => true,
+        }
+    }
+}
+
+/// The prefix of the store holding `key`, i.e., the key up to the prefix separator, or the whole key for the
+/// stores holding a single item
+pub fn store_prefix(key: &[u8]) -> &[u8] {
+    match key.iter().position(|&byte| byte == b'/') {
+        Some(position) => &key[..position],
+        None => key,
+    }
+}
+
+/// Writes the entries of `db` matching `content` into an archive at `path`, returning the number of entries
+pub fn export(db: &Arc<DB>, content: Content, path: &Path) -> ArchiveResult<u64> {
+    let schema_version = match DbMetadataStore::new(db.clone()).get_schema_version().unwrap_option() {
+        Some(version) => version,
+        None => BASE_SCHEMA_VERSION,
+    };
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&schema_version.to_le_bytes())?;
+    let mut count = 0u64;
+    // A DB iterator reads from an implicit snapshot, so the archive is consistent even if the DB is being written
+    for item in db.iterator(IteratorMode::Start) {
+        let (key, value) = item?;
+        if content.includes(&key) {
+            write_field(&mut writer, &key)?;
+            write_field(&mut writer, &value)?;
+            count += 1;
+        }
+    }
+    writer.write_all(&END_MARKER.to_le_bytes())?;
+    writer.write_all(&count.to_le_bytes())?;
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Writes the entries of the archive at `path` into `db`, which must be empty, returning the number of entries
+/// A failed import may leave part of the entries written, in which case the target DB is to be deleted
+pub fn import(db: &Arc<DB>, path: &Path) -> ArchiveResult<u64> {
+    if db.iterator(IteratorMode::Start).next().is_some() {
+        return Err(ArchiveError::TargetNotEmpty);
+    }
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(ArchiveError::InvalidFormat("unknown magic".to_string()));
+    }
+    let version = read_u32(&mut reader)?;
+    if version != FORMAT_VERSION {
+        return Err(ArchiveError::InvalidFormat(format!("unsupported format version {0}", version)));
+    }
+    // Older schemas are migrated by the node on startup
+    let schema_version = read_u32(&mut reader)?;
+    if schema_version > SCHEMA_VERSION {
+        return Err(ArchiveError::UnsupportedSchemaVersion(schema_version, SCHEMA_VERSION));
+    }
+
+    let mut batch = WriteBatch::default();
+    let mut count = 0u64;
+    loop {
+        let key_len = read_u32(&mut reader)?;
+        if key_len == END_MARKER {
+            break;
+        }
+        let key = read_field(&mut reader, key_len)?;
+        let value_len = read_u32(&mut reader)?;
+        let value = read_field(&mut reader, value_len)?;
+        batch.put(key, value);
+        count += 1;
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            db.write(std::mem::take(&mut batch))?;
+        }
+    }
+    let mut expected = [0u8; 8];
+    reader.read_exact(&mut expected)?;
+    let expected = u64::from_le_bytes(expected);
+    if expected != count {
+        return Err(ArchiveError::InvalidFormat(format!("read {0} entries while the archive holds {1}", count, expected)));
+    }
+    db.write(batch)?;
+    db.flush()?;
+    Ok(count)
+}
+
+fn write_field(writer: &mut impl Write, field: &[u8]) -> ArchiveResult<()> {
+    let len = u32::try_from(field.len())
+        .ok()
+        .filter(|&len| len <= MAX_FIELD_SIZE)
+        .ok_or_else(|| ArchiveError::InvalidFormat(format!("an entry of {0} bytes exceeds the maximum size", field.len())))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(field)?;
+    Ok(())
+}
+
+fn read_u32(reader: &mut impl Read) -> ArchiveResult<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_field(reader: &mut impl Read, len: u32) -> ArchiveResult<Vec<u8>> {
+    if len > MAX_FIELD_SIZE {
+        return Err(ArchiveError::InvalidFormat(format!("entry size {0} exceeds the maximum", len)));
+    }
+    let mut field = vec![0u8; len as usize];
+    reader.read_exact(&mut field)?;
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::create_temp_db;
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let (_lifetime, source) = create_temp_db();
+        source.put(b"headers/\x01", b"header").unwrap();
+        source.put(b"block-transactions/\x01", b"transactions").unwrap();
+        source.put(b"pruning-point", b"pruning point").unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let path = archive_dir.path().join("headers.kasdb");
+        assert_eq!(export(&source, Content::Headers, &path).unwrap(), 1);
+        let path = archive_dir.path().join("all.kasdb");
+        assert_eq!(export(&source, Content::All, &path).unwrap(), 3);
+
+        let (_lifetime, target) = create_temp_db();
+        assert_eq!(import(&target, &path).unwrap(), 3);
+        assert_eq!(target.get(b"pruning-point").unwrap().unwrap(), b"pruning point");
+        assert!(matches!(import(&target, &path), Err(ArchiveError::TargetNotEmpty)));
+
+        // A truncated archive is rejected
+        let data = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &data[..data.len() - 8]).unwrap();
+        let (_lifetime, target) = create_temp_db();
+        assert!(import(&target, &path).is_err());
+    }
+}
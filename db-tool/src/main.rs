@@ -0,0 +1,131 @@
+use archive::{store_prefix, Content};
+use clap::{Parser, Subcommand};
+use consensus::model::stores::{open_secondary_db, DB};
+use consensus_core::display::Hex;
+use rocksdb::IteratorMode;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::exit,
+    str,
+    sync::Arc,
+};
+
+mod archive;
+
+/// Kaspa DB tool: exports the node database into a portable archive, imports such an archive into a fresh
+/// datadir, and inspects the stores. Reading commands open the DB as a secondary instance, so the node may
+/// keep running meanwhile
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// The database directory, i.e., the `datadir` directory within the network directory of the node
+    #[arg(long)]
+    datadir: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Dumps the database entries into an archive
+    Export {
+        /// The path of the archive to create
+        path: PathBuf,
+
+        /// The entries to dump
+        #[arg(long, value_enum, default_value_t = Content::All)]
+        content: Content,
+    },
+
+    /// Restores the entries of an archive into the database, which must not exist yet or be empty
+    Import {
+        /// The path of the archive
+        path: PathBuf,
+    },
+
+    /// Prints the number of entries and their total size per store
+    Stats,
+
+    /// Prints the value of a single entry
+    Get {
+        /// The store prefix, e.g. block-ghostdag-data, or the key of a single item store, e.g. pruning-point
+        store: String,
+
+        /// The key within the store, in hex. Omitted for single item stores
+        key: Option<String>,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("{0}", err);
+        exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    match args.command {
+        Command::Export { path, content } => {
+            let db = open_read_only(&args.datadir)?;
+            let count = archive::export(&db.db, content, &path)?;
+            println!("Exported {0} entries to {1}", count, path.display());
+        }
+        Command::Import { path } => {
+            let db = Arc::new(DB::open_default(&args.datadir)?);
+            let count = archive::import(&db, &path)?;
+            println!("Imported {0} entries into {1}", count, args.datadir.display());
+        }
+        Command::Stats => {
+            let db = open_read_only(&args.datadir)?;
+            let mut stats = BTreeMap::<Vec<u8>, (u64, u64)>::new();
+            for item in db.db.iterator(IteratorMode::Start) {
+                let (key, value) = item?;
+                let entry = stats.entry(store_prefix(&key).to_vec()).or_default();
+                entry.0 += 1;
+                entry.1 += (key.len() + value.len()) as u64;
+            }
+            println!("{0:<40} {1:>12} {2:>16}", "store", "entries", "bytes");
+            for (prefix, (entries, bytes)) in stats {
+                println!("{0:<40} {1:>12} {2:>16}", format_prefix(&prefix), entries, bytes);
+            }
+        }
+        Command::Get { store, key } => {
+            let db = open_read_only(&args.datadir)?;
+            let mut db_key = store.into_bytes();
+            if let Some(key) = key {
+                let mut key_bytes = vec![0u8; key.len() / 2];
+                faster_hex::hex_decode(key.as_bytes(), &mut key_bytes).map_err(|err| format!("invalid hex key: {0}", err))?;
+                db_key.push(b'/');
+                db_key.extend(key_bytes);
+            }
+            match db.db.get_pinned(&db_key)? {
+                Some(value) => println!("{0}", Hex(&value)),
+                None => return Err("key not found".into()),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A secondary instance of the DB along with the directory holding its info logs
+struct ReadOnlyDb {
+    db: Arc<DB>,
+    _secondary_dir: tempfile::TempDir,
+}
+
+fn open_read_only(datadir: &Path) -> Result<ReadOnlyDb, Box<dyn std::error::Error>> {
+    let secondary_dir = tempfile::tempdir()?;
+    let db = open_secondary_db(datadir, secondary_dir.path())?;
+    Ok(ReadOnlyDb { db, _secondary_dir: secondary_dir })
+}
+
+/// Store prefixes are expected to be human readable, falling back to hex otherwise
+fn format_prefix(prefix: &[u8]) -> String {
+    match str::from_utf8(prefix) {
+        Ok(prefix) if prefix.chars().all(|c| c.is_ascii_graphic()) => prefix.to_string(),
+        _ => Hex(prefix).to_string(),
+    }
+}
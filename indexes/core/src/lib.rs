@@ -0,0 +1,7 @@
+//! The APIs of the optional node indexes.
+//!
+//! Index implementations hold their data in the node DB, while their consumers, such as the RPC service, only
+//! depend on the APIs defined here.
+
+pub mod txindex;
+pub mod utxoindex;
@@ -0,0 +1,53 @@
+use consensus_core::tx::TransactionId;
+use hashes::Hash;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The virtual chain block which accepted a transaction, along with the block including it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxAcceptance {
+    pub accepting_block_hash: Hash,
+    pub accepting_daa_score: u64,
+    /// The mergeset block of the accepting block whose transaction was accepted. Other blocks might include the
+    /// transaction as well.
+    pub including_block_hash: Hash,
+}
+
+/// The outcome of looking a transaction up in the transaction index
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxIndexLookup {
+    /// The transaction was accepted by a virtual chain block within the retention window
+    Accepted(TxAcceptance),
+    /// The transaction was not accepted by any virtual chain block indexed so far
+    NotFound,
+    /// The transaction was not found within the retention window. Records below the window were trimmed,
+    /// so the transaction might have been accepted before it.
+    BeyondRetention,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TxIndexStats {
+    /// The number of retained transaction acceptance records
+    pub transaction_count: u64,
+    /// The number of retained accepting chain blocks
+    pub chain_block_count: u64,
+    /// The DAA score the index started indexing from
+    pub indexed_from_daa_score: u64,
+    /// The lowest DAA score of the retained accepting chain blocks. Equals `indexed_from_daa_score` until
+    /// records are first trimmed.
+    pub retention_root_daa_score: u64,
+    /// An estimate of the DB size of the retained records, in bytes
+    pub estimated_size: u64,
+}
+
+/// Abstracts the transaction index API
+pub trait TxIndexApi: Send + Sync {
+    fn get_transaction_acceptance(&self, transaction_id: TransactionId) -> TxIndexLookup;
+
+    fn stats(&self) -> TxIndexStats;
+
+    /// Returns the retention window in DAA score units, or `None` if records are never trimmed
+    fn retention_daa_score(&self) -> Option<u64>;
+}
+
+pub type DynTxIndex = Arc<dyn TxIndexApi>;
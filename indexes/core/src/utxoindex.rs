@@ -0,0 +1,18 @@
+use consensus_core::tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry};
+use std::sync::Arc;
+
+/// Abstracts the UTXO index API. The index tracks the virtual UTXO set, keyed by script public key.
+pub trait UtxoIndexApi: Send + Sync {
+    /// Returns the UTXOs of the virtual UTXO set paying to `script_public_key`
+    fn get_utxos_by_script_public_key(&self, script_public_key: &ScriptPublicKey) -> Vec<(TransactionOutpoint, UtxoEntry)>;
+
+    /// Returns the total amount of the UTXOs of the virtual UTXO set paying to `script_public_key`
+    fn get_balance_by_script_public_key(&self, script_public_key: &ScriptPublicKey) -> u64 {
+        self.get_utxos_by_script_public_key(script_public_key).iter().map(|(_, entry)| entry.amount).sum()
+    }
+
+    /// Returns the total amount of the UTXOs of the virtual UTXO set
+    fn get_circulating_supply(&self) -> u64;
+}
+
+pub type DynUtxoIndex = Arc<dyn UtxoIndexApi>;
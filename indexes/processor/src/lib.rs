@@ -0,0 +1,12 @@
+//! Index notification processor.
+//!
+//! The [`IndexProcessor`] consumes the consensus notifications on a queue of its own, applies them to the enabled
+//! indexes and then relays them to the consumers registered on its [`ConsensusNotificationRoot`]. Downstream
+//! consumers, such as the RPC notifier, thus observe indexes already reflecting each notification, while neither
+//! the indexes nor a slow downstream consumer run on the consensus processing threads.
+//!
+//! [`ConsensusNotificationRoot`]: consensus::notify::root::ConsensusNotificationRoot
+
+mod processor;
+
+pub use processor::IndexProcessor;
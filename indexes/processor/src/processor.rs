@@ -0,0 +1,119 @@
+use consensus::notify::root::ConsensusNotificationRoot;
+use consensus_core::notify::{Notification, NotificationConsumer};
+use kaspa_core::error;
+use std::sync::Arc;
+use txindex::TxIndex;
+use utxoindex::UtxoIndex;
+
+/// Applies consensus notifications to the enabled indexes before relaying them downstream
+pub struct IndexProcessor {
+    utxo_index: Option<Arc<UtxoIndex>>,
+    tx_index: Option<Arc<TxIndex>>,
+    notification_root: Arc<ConsensusNotificationRoot>,
+}
+
+impl IndexProcessor {
+    pub fn new(utxo_index: Option<Arc<UtxoIndex>>, tx_index: Option<Arc<TxIndex>>) -> Self {
+        Self { utxo_index, tx_index, notification_root: Arc::new(ConsensusNotificationRoot::new()) }
+    }
+
+    /// The root relaying the processed notifications, on which downstream consumers register
+    pub fn notification_root(&self) -> &Arc<ConsensusNotificationRoot> {
+        &self.notification_root
+    }
+
+    fn process(&self, notification: &Notification) {
+        match notification {
+            Notification::UtxosChanged(notification) => {
+                if let Some(utxo_index) = self.utxo_index.as_ref() {
+                    if let Err(err) = utxo_index.update(&notification.accumulated_utxo_diff, &notification.virtual_parents) {
+                        error!("Failed updating the UTXO index: {}", err);
+                    }
+                }
+            }
+            Notification::VirtualChainChanged(_) => {
+                // The index follows the virtual chain by itself, so syncing it covers the notified changes
+                if let Some(tx_index) = self.tx_index.as_ref() {
+                    if let Err(err) = tx_index.sync() {
+                        error!("Failed syncing the transaction index: {}", err);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl NotificationConsumer for IndexProcessor {
+    fn consume(&self, notification: Arc<Notification>) {
+        self.process(&notification);
+        self.notification_root.relay(notification);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::{create_temp_db, TestConsensus};
+    use consensus_core::config::params::MAINNET_PARAMS;
+    use consensus_core::notify::{BackpressurePolicy, NotificationType};
+    use hashes::Hash;
+    use index_core::utxoindex::UtxoIndexApi;
+    use std::{
+        sync::{
+            mpsc::{channel, Sender},
+            Mutex,
+        },
+        time::Duration,
+    };
+
+    /// A downstream consumer reporting whether the UTXOs added by each diff are already indexed once notified
+    struct IndexedUtxosChecker {
+        utxo_index: Arc<UtxoIndex>,
+        sender: Mutex<Sender<bool>>,
+    }
+
+    impl NotificationConsumer for IndexedUtxosChecker {
+        fn consume(&self, notification: Arc<Notification>) {
+            if let Notification::UtxosChanged(ref notification) = *notification {
+                let indexed = notification.accumulated_utxo_diff.add.iter().all(|(outpoint, entry)| {
+                    self.utxo_index
+                        .get_utxos_by_script_public_key(&entry.script_public_key)
+                        .iter()
+                        .any(|(indexed_outpoint, _)| indexed_outpoint == outpoint)
+                });
+                self.sender.lock().unwrap().send(indexed).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_indexes_are_updated_before_relaying() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let consensus = TestConsensus::create_from_temp_db(&params);
+        let wait_handles = consensus.init();
+        let (_lifetime, db) = create_temp_db();
+
+        let utxo_index = Arc::new(UtxoIndex::new(consensus.consensus().clone(), db));
+        utxo_index.resync_if_needed().unwrap();
+        let processor = Arc::new(IndexProcessor::new(Some(utxo_index.clone()), None));
+        let (sender, receiver) = channel();
+        processor.notification_root().register(
+            &[NotificationType::UtxosChanged],
+            BackpressurePolicy::Unbounded,
+            Arc::new(IndexedUtxosChecker { utxo_index, sender: Mutex::new(sender) }),
+        );
+        consensus.notification_root().register(&NotificationType::ALL, BackpressurePolicy::Block(10), processor);
+
+        let mut parents = vec![params.genesis.hash];
+        for i in 1..=5u64 {
+            let hash: Hash = i.into();
+            let block = consensus.build_block_with_parents_and_transactions(hash, parents, vec![]);
+            consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            parents = vec![hash];
+            assert!(receiver.recv_timeout(Duration::from_secs(5)).unwrap());
+        }
+
+        consensus.shutdown(wait_handles);
+    }
+}
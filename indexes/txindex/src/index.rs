@@ -0,0 +1,349 @@
+use crate::stores::{
+    AcceptanceStore, AcceptanceStoreReader, ChainBlockKey, ChainBlocksStore, ChainBlocksStoreReader, DbAcceptanceStore,
+    DbChainBlocksStore, DbTxIndexStateStore, IndexedChainBlock, TxIndexState,
+};
+use consensus::model::stores::{
+    database::prelude::{BatchDbWriter, CachePolicy, DirectDbWriter},
+    errors::{StoreResult, StoreResultExtensions},
+    DB,
+};
+use consensus_core::{api::DynConsensus, chain::VirtualChainBatch, time::DurationMs, tx::TransactionId};
+use index_core::txindex::{TxAcceptance, TxIndexApi, TxIndexLookup, TxIndexStats};
+use kaspa_core::{debug, warn};
+use parking_lot::RwLock;
+use rocksdb::WriteBatch;
+use std::sync::Arc;
+
+/// The maximum number of chain blocks indexed per DB batch
+const SYNC_BATCH_CHAIN_BLOCKS: usize = 1_000;
+
+/// The approximate maximum number of transactions indexed per DB batch
+const SYNC_BATCH_TRANSACTION_IDS: usize = 100_000;
+
+/// The maximum number of chain blocks trimmed per DB batch
+const TRIM_BATCH_CHAIN_BLOCKS: usize = 1_000;
+
+const ACCEPTANCE_CACHE_POLICY: CachePolicy = CachePolicy::Count(10_000);
+const CHAIN_BLOCKS_CACHE_POLICY: CachePolicy = CachePolicy::Count(1_000);
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Config {
+    /// The number of DAA scores below the virtual for which acceptance records are retained. If `None`, records
+    /// are never trimmed.
+    pub retention_daa_score: Option<u64>,
+}
+
+impl Config {
+    pub fn new(retention_daa_score: Option<u64>) -> Self {
+        Self { retention_daa_score }
+    }
+
+    /// Sets the retention window to about `days` days of blocks produced every `target_time_per_block`
+    pub fn with_retention_days(days: u64, target_time_per_block: DurationMs) -> Self {
+        let day_millis = 24 * 60 * 60 * 1000;
+        Self::new(Some(days.saturating_mul(day_millis) / target_time_per_block.as_millis().max(1)))
+    }
+}
+
+struct Stores {
+    acceptance: DbAcceptanceStore,
+    chain_blocks: DbChainBlocksStore,
+    state: DbTxIndexStateStore,
+}
+
+/// Indexes the virtual chain block accepting each transaction, and the mergeset block including it.
+///
+/// The index follows the virtual chain from the pruning point at the time it was first synced, and keeps its
+/// records when consensus prunes, so that acceptances below the pruning point remain queryable. Records of chain
+/// blocks older than the configured retention window are trimmed independently of pruning. Lookups missing a
+/// transaction report whether it might be older than the window.
+pub struct TxIndex {
+    consensus: DynConsensus,
+    db: Arc<DB>,
+    config: Config,
+    stores: RwLock<Stores>,
+}
+
+impl TxIndex {
+    pub fn new(consensus: DynConsensus, db: Arc<DB>, config: Config) -> Self {
+        let stores = Stores {
+            acceptance: DbAcceptanceStore::new(db.clone(), ACCEPTANCE_CACHE_POLICY),
+            chain_blocks: DbChainBlocksStore::new(db.clone(), CHAIN_BLOCKS_CACHE_POLICY),
+            state: DbTxIndexStateStore::new(db.clone()),
+        };
+        Self { consensus, db, config, stores: RwLock::new(stores) }
+    }
+
+    /// Indexes the virtual chain changes since the last sync. Returns the number of indexed chain blocks.
+    pub fn sync(&self) -> StoreResult<u64> {
+        let mut indexed = 0;
+        loop {
+            let mut stores = self.stores.write();
+            let mut state = match stores.state.get().unwrap_option() {
+                Some(state) => state,
+                None => self.pruning_point_state(),
+            };
+            let batch = match self.consensus.clone().get_virtual_chain_from_block(
+                state.sink,
+                true,
+                SYNC_BATCH_CHAIN_BLOCKS,
+                SYNC_BATCH_TRANSACTION_IDS,
+            ) {
+                Ok(batch) => batch,
+                Err(err) => {
+                    warn!("Resyncing the transaction index from the pruning point: {}", err);
+                    self.resync_from_pruning_point(&mut stores, state)?;
+                    continue;
+                }
+            };
+            if batch.removed_chain_block_hashes.is_empty() && batch.added_chain_block_hashes.is_empty() {
+                if stores.state.get().unwrap_option().is_none() {
+                    stores.state.set(DirectDbWriter::new(&self.db), &state)?;
+                }
+                return Ok(indexed);
+            }
+
+            let mut write_batch = WriteBatch::default();
+            if !self.apply_removed(&mut stores, &mut write_batch, &mut state, &batch)? {
+                warn!("Resyncing the transaction index from the pruning point: reorged chain blocks are no longer indexed");
+                self.resync_from_pruning_point(&mut stores, state)?;
+                continue;
+            }
+            for acceptance in batch.accepted_transaction_ids.iter() {
+                let daa_score = self.consensus.clone().get_header(acceptance.accepting_block_hash).unwrap().daa_score;
+                let mut accepted_transaction_ids = Vec::new();
+                for block in acceptance.mergeset.iter() {
+                    let record = TxAcceptance {
+                        accepting_block_hash: acceptance.accepting_block_hash,
+                        accepting_daa_score: daa_score,
+                        including_block_hash: block.block_hash,
+                    };
+                    for &transaction_id in block.accepted_transaction_ids.iter() {
+                        stores.acceptance.set(BatchDbWriter::new(&mut write_batch), transaction_id, record)?;
+                    }
+                    accepted_transaction_ids.extend(block.accepted_transaction_ids.iter().copied());
+                }
+                state.transaction_count += accepted_transaction_ids.len() as u64;
+                state.chain_block_count += 1;
+                let chain_block = IndexedChainBlock { selected_parent: state.sink, accepted_transaction_ids };
+                stores.chain_blocks.set(
+                    BatchDbWriter::new(&mut write_batch),
+                    ChainBlockKey::new(daa_score, acceptance.accepting_block_hash),
+                    chain_block,
+                )?;
+                state.sink = acceptance.accepting_block_hash;
+            }
+            stores.state.set(BatchDbWriter::new(&mut write_batch), &state)?;
+            self.db.write(write_batch)?;
+            indexed += batch.added_chain_block_hashes.len() as u64;
+
+            if !batch.has_more {
+                return Ok(indexed);
+            }
+        }
+    }
+
+    /// Removes the records of the reorged chain blocks and moves the sink down to the chain split point. Returns
+    /// `false` if some reorged chain block is no longer indexed, in which case the split point is unknown
+    fn apply_removed(
+        &self,
+        stores: &mut Stores,
+        write_batch: &mut WriteBatch,
+        state: &mut TxIndexState,
+        batch: &VirtualChainBatch,
+    ) -> StoreResult<bool> {
+        for &hash in batch.removed_chain_block_hashes.iter() {
+            let key = match self.consensus.clone().get_header(hash) {
+                Some(header) => ChainBlockKey::new(header.daa_score, hash),
+                None => return Ok(false),
+            };
+            let chain_block = match stores.chain_blocks.get(key).unwrap_option() {
+                Some(chain_block) => chain_block,
+                None => return Ok(false),
+            };
+            for &transaction_id in chain_block.accepted_transaction_ids.iter() {
+                stores.acceptance.remove(BatchDbWriter::new(write_batch), transaction_id)?;
+            }
+            stores.chain_blocks.remove(BatchDbWriter::new(write_batch), key)?;
+            state.transaction_count -= chain_block.accepted_transaction_ids.len() as u64;
+            state.chain_block_count -= 1;
+            state.sink = chain_block.selected_parent;
+        }
+        Ok(true)
+    }
+
+    /// Returns the initial state of an index following the virtual chain from the current pruning point
+    fn pruning_point_state(&self) -> TxIndexState {
+        let pruning_point = self.consensus.clone().get_pruning_point();
+        TxIndexState::new(pruning_point, self.consensus.clone().get_header(pruning_point).unwrap().daa_score)
+    }
+
+    /// Drops the records of the chain blocks above the pruning point, which are final below it, and restarts
+    /// following the virtual chain from the pruning point
+    fn resync_from_pruning_point(&self, stores: &mut Stores, mut state: TxIndexState) -> StoreResult<()> {
+        let pruning_point_state = self.pruning_point_state();
+        let pruning_point_daa_score = pruning_point_state.indexed_from_daa_score;
+        let mut write_batch = WriteBatch::default();
+        for (key, chain_block) in stores.chain_blocks.get_range(pruning_point_daa_score + 1, u64::MAX, usize::MAX)? {
+            for &transaction_id in chain_block.accepted_transaction_ids.iter() {
+                stores.acceptance.remove(BatchDbWriter::new(&mut write_batch), transaction_id)?;
+            }
+            stores.chain_blocks.remove(BatchDbWriter::new(&mut write_batch), key)?;
+            state.transaction_count -= chain_block.accepted_transaction_ids.len() as u64;
+            state.chain_block_count -= 1;
+        }
+        state.sink = pruning_point_state.sink;
+        state.indexed_from_daa_score = state.indexed_from_daa_score.min(pruning_point_daa_score);
+        state.retention_root_daa_score = state.retention_root_daa_score.min(pruning_point_daa_score);
+        stores.state.set(BatchDbWriter::new(&mut write_batch), &state)?;
+        self.db.write(write_batch)?;
+        Ok(())
+    }
+
+    /// Trims the records of the chain blocks below the retention window. Returns the number of trimmed chain blocks.
+    pub fn trim(&self) -> StoreResult<u64> {
+        let retention_daa_score = match self.config.retention_daa_score {
+            Some(retention_daa_score) => retention_daa_score,
+            None => return Ok(0),
+        };
+        let window_start = self.consensus.clone().get_virtual_daa_score().saturating_sub(retention_daa_score);
+        let mut trimmed = 0;
+        loop {
+            let mut stores = self.stores.write();
+            let mut state = match stores.state.get().unwrap_option() {
+                Some(state) if state.retention_root_daa_score < window_start => state,
+                _ => return Ok(trimmed),
+            };
+            let expired = stores.chain_blocks.get_range(0, window_start, TRIM_BATCH_CHAIN_BLOCKS)?;
+            let is_last_batch = expired.len() < TRIM_BATCH_CHAIN_BLOCKS;
+            let mut write_batch = WriteBatch::default();
+            for (key, chain_block) in expired.iter() {
+                for &transaction_id in chain_block.accepted_transaction_ids.iter() {
+                    stores.acceptance.remove(BatchDbWriter::new(&mut write_batch), transaction_id)?;
+                }
+                stores.chain_blocks.remove(BatchDbWriter::new(&mut write_batch), *key)?;
+                state.transaction_count -= chain_block.accepted_transaction_ids.len() as u64;
+                state.chain_block_count -= 1;
+            }
+            trimmed += expired.len() as u64;
+            if is_last_batch {
+                state.retention_root_daa_score = window_start;
+            }
+            stores.state.set(BatchDbWriter::new(&mut write_batch), &state)?;
+            self.db.write(write_batch)?;
+            if is_last_batch {
+                drop(stores);
+                debug!("Trimmed {} chain blocks from the transaction index, retaining {:?}", trimmed, self.stats());
+                return Ok(trimmed);
+            }
+        }
+    }
+}
+
+impl TxIndexApi for TxIndex {
+    fn get_transaction_acceptance(&self, transaction_id: TransactionId) -> TxIndexLookup {
+        let stores = self.stores.read();
+        if let Some(acceptance) = stores.acceptance.get(transaction_id).unwrap_option() {
+            return TxIndexLookup::Accepted(acceptance);
+        }
+        match stores.state.get().unwrap_option() {
+            Some(state) if state.retention_root_daa_score > state.indexed_from_daa_score => TxIndexLookup::BeyondRetention,
+            _ => TxIndexLookup::NotFound,
+        }
+    }
+
+    fn stats(&self) -> TxIndexStats {
+        match self.stores.read().state.get().unwrap_option() {
+            Some(state) => TxIndexStats {
+                transaction_count: state.transaction_count,
+                chain_block_count: state.chain_block_count,
+                indexed_from_daa_score: state.indexed_from_daa_score,
+                retention_root_daa_score: state.retention_root_daa_score,
+                estimated_size: state.estimated_size(),
+            },
+            None => TxIndexStats::default(),
+        }
+    }
+
+    fn retention_daa_score(&self) -> Option<u64> {
+        self.config.retention_daa_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::{create_temp_db, TestConsensus};
+    use consensus_core::api::ConsensusApi;
+    use consensus_core::config::params::MAINNET_PARAMS;
+    use hashes::Hash;
+
+    async fn add_chain(consensus: &TestConsensus, first: u64, count: u64, mut parents: Vec<Hash>) -> Vec<Hash> {
+        let mut chain = Vec::new();
+        for i in first..first + count {
+            let hash: Hash = i.into();
+            let block = consensus.build_block_with_parents_and_transactions(hash, parents, vec![]);
+            consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            parents = vec![hash];
+            chain.push(hash);
+        }
+        chain
+    }
+
+    fn coinbase_id(consensus: &TestConsensus, hash: Hash) -> TransactionId {
+        consensus.get_block(hash).unwrap().transactions[0].id()
+    }
+
+    fn accepting_block(index: &TxIndex, transaction_id: TransactionId) -> Option<Hash> {
+        match index.get_transaction_acceptance(transaction_id) {
+            TxIndexLookup::Accepted(acceptance) => Some(acceptance.accepting_block_hash),
+            _ => None,
+        }
+    }
+
+    fn including_block(index: &TxIndex, transaction_id: TransactionId) -> Option<Hash> {
+        match index.get_transaction_acceptance(transaction_id) {
+            TxIndexLookup::Accepted(acceptance) => Some(acceptance.including_block_hash),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tx_index_sync_and_trim() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let consensus = TestConsensus::create_from_temp_db(&params);
+        let wait_handles = consensus.init();
+        let (_lifetime, db) = create_temp_db();
+
+        // The coinbase of each chain block is accepted by its chain child
+        let chain = add_chain(&consensus, 1, 10, vec![params.genesis.hash]).await;
+        let index = TxIndex::new(consensus.consensus().clone(), db.clone(), Config::default());
+        assert_eq!(index.sync().unwrap(), 10);
+        assert_eq!(index.stats().chain_block_count, 10);
+        assert_eq!(accepting_block(&index, coinbase_id(&consensus, chain[0])), Some(chain[1]));
+        assert_eq!(including_block(&index, coinbase_id(&consensus, chain[0])), Some(chain[0]));
+        assert_eq!(index.sync().unwrap(), 0);
+
+        // A heavier side chain reorgs the indexed chain out
+        let side_chain = add_chain(&consensus, 100, 12, vec![params.genesis.hash]).await;
+        assert_eq!(index.sync().unwrap(), 12);
+        assert_eq!(index.stats().chain_block_count, 12);
+        assert_eq!(index.get_transaction_acceptance(coinbase_id(&consensus, chain[0])), TxIndexLookup::NotFound);
+        assert_eq!(accepting_block(&index, coinbase_id(&consensus, side_chain[0])), Some(side_chain[1]));
+        assert_eq!(including_block(&index, coinbase_id(&consensus, side_chain[0])), Some(side_chain[0]));
+
+        // Records below the retention window are trimmed, and lookups of missing transactions report it
+        let index = TxIndex::new(consensus.consensus().clone(), db, Config::new(Some(5)));
+        let window_start = consensus.consensus().clone().get_virtual_daa_score() - 5;
+        assert!(index.trim().unwrap() > 0);
+        let stats = index.stats();
+        assert_eq!(stats.retention_root_daa_score, window_start);
+        assert!(stats.chain_block_count < 12);
+        assert!(stats.estimated_size > 0);
+        assert_eq!(index.get_transaction_acceptance(coinbase_id(&consensus, side_chain[0])), TxIndexLookup::BeyondRetention);
+        assert_eq!(accepting_block(&index, coinbase_id(&consensus, side_chain[10])), Some(side_chain[11]));
+        assert_eq!(index.trim().unwrap(), 0);
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,18 @@
+//! Transaction index.
+//!
+//! The [`TxIndex`] records the virtual chain block accepting each transaction along with the block including it,
+//! following virtual chain changes, reorgs included. Records are kept when consensus prunes, and are trimmed once their accepting chain block falls
+//! out of the configured retention window, expressed in DAA scores below the virtual. The [`TxIndexService`]
+//! syncs and trims the index in the background.
+
+use std::time::Duration;
+
+mod index;
+mod service;
+mod stores;
+
+pub use index::{Config, TxIndex};
+pub use service::TxIndexService;
+
+/// The interval between syncs of the index with the virtual chain
+pub const SYNC_INTERVAL: Duration = Duration::from_secs(1);
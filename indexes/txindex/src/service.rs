@@ -0,0 +1,82 @@
+use crate::{index::TxIndex, SYNC_INTERVAL};
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::DuplexTrigger;
+use std::sync::Arc;
+
+const TX_INDEX_SERVICE: &str = "tx-index";
+
+/// Keeps the [`TxIndex`] in sync with the virtual chain and trims the records falling out of its retention window
+/// every [`SYNC_INTERVAL`]
+pub struct TxIndexService {
+    index: Arc<TxIndex>,
+    shutdown: DuplexTrigger,
+}
+
+impl TxIndexService {
+    pub fn new(index: Arc<TxIndex>) -> Self {
+        Self { index, shutdown: DuplexTrigger::default() }
+    }
+
+    async fn sync_and_trim(&self) {
+        let index = self.index.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            index.sync()?;
+            index.trim()
+        })
+        .await
+        .unwrap();
+        if let Err(err) = result {
+            warn!("Failed updating the transaction index: {}", err);
+        }
+    }
+}
+
+impl AsyncService for TxIndexService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        TX_INDEX_SERVICE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", TX_INDEX_SERVICE);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            tokio::pin!(shutdown_signal);
+            let mut interval = tokio::time::interval(SYNC_INTERVAL);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = &mut shutdown_signal => break,
+                    _ = async {
+                        interval.tick().await;
+                        self.sync_and_trim().await
+                    } => {}
+                }
+            }
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", TX_INDEX_SERVICE);
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} stopping", TX_INDEX_SERVICE);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        Box::pin(async move {
+            // Wait for the pending update to complete
+            shutdown_executed_signal.await;
+            trace!("{} exiting", TX_INDEX_SERVICE);
+        })
+    }
+}
@@ -0,0 +1,217 @@
+use consensus::model::stores::{
+    database::prelude::{CachePolicy, CachedDbAccess, CachedDbItem, DbWriter},
+    errors::StoreResult,
+    DB,
+};
+use consensus_core::tx::TransactionId;
+use hashes::{Hash, HASH_SIZE};
+use index_core::txindex::TxAcceptance;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, sync::Arc};
+
+const CHAIN_BLOCK_KEY_LEN: usize = 8 + HASH_SIZE;
+
+/// The DB key of an indexed chain block, i.e., its big-endian DAA score followed by its hash, so that chain
+/// blocks are iterated from the lowest DAA score up
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChainBlockKey([u8; CHAIN_BLOCK_KEY_LEN]);
+
+impl ChainBlockKey {
+    pub fn new(daa_score: u64, hash: Hash) -> Self {
+        let mut bytes = [0u8; CHAIN_BLOCK_KEY_LEN];
+        bytes[..8].copy_from_slice(&daa_score.to_be_bytes());
+        bytes[8..].copy_from_slice(&hash.as_bytes());
+        Self(bytes)
+    }
+
+    fn from_slice(bytes: &[u8]) -> Self {
+        Self(bytes.try_into().unwrap())
+    }
+
+    pub fn daa_score(&self) -> u64 {
+        u64::from_be_bytes(self.0[..8].try_into().unwrap())
+    }
+
+    pub fn hash(&self) -> Hash {
+        Hash::from_slice(&self.0[8..])
+    }
+}
+
+impl AsRef<[u8]> for ChainBlockKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for ChainBlockKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.daa_score(), self.hash())
+    }
+}
+
+/// The transactions accepted by an indexed chain block
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedChainBlock {
+    /// The chain block indexed right before this one, i.e., its selected parent
+    pub selected_parent: Hash,
+    pub accepted_transaction_ids: Vec<TransactionId>,
+}
+
+/// The progress of the index along the virtual chain, along with record counts for size accounting
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxIndexState {
+    /// The last indexed virtual chain block
+    pub sink: Hash,
+    pub indexed_from_daa_score: u64,
+    /// Records of chain blocks below this DAA score were trimmed
+    pub retention_root_daa_score: u64,
+    pub transaction_count: u64,
+    pub chain_block_count: u64,
+}
+
+impl TxIndexState {
+    pub fn new(sink: Hash, daa_score: u64) -> Self {
+        Self {
+            sink,
+            indexed_from_daa_score: daa_score,
+            retention_root_daa_score: daa_score,
+            transaction_count: 0,
+            chain_block_count: 0,
+        }
+    }
+
+    /// Estimates the DB size of the retained records, including the `/` key separators
+    pub fn estimated_size(&self) -> u64 {
+        let acceptance_record_size = (ACCEPTANCE_STORE_PREFIX.len() + 1 + HASH_SIZE + HASH_SIZE + 8 + HASH_SIZE) as u64;
+        let chain_block_record_size = (CHAIN_BLOCKS_STORE_PREFIX.len() + 1 + CHAIN_BLOCK_KEY_LEN + HASH_SIZE + 8) as u64;
+        // Each transaction id is also listed by its accepting chain block
+        self.transaction_count * (acceptance_record_size + HASH_SIZE as u64) + self.chain_block_count * chain_block_record_size
+    }
+}
+
+/// Reader API for `AcceptanceStore`.
+pub trait AcceptanceStoreReader {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<TxAcceptance>;
+}
+
+/// Write API for `AcceptanceStore`
+pub trait AcceptanceStore: AcceptanceStoreReader {
+    fn set(&mut self, writer: impl DbWriter, transaction_id: TransactionId, acceptance: TxAcceptance) -> StoreResult<()>;
+    fn remove(&mut self, writer: impl DbWriter, transaction_id: TransactionId) -> StoreResult<()>;
+}
+
+const ACCEPTANCE_STORE_PREFIX: &[u8] = b"txindex-acceptance";
+
+/// A DB + cache implementation of `AcceptanceStore` trait
+#[derive(Clone)]
+pub struct DbAcceptanceStore {
+    access: CachedDbAccess<TransactionId, TxAcceptance>,
+}
+
+impl DbAcceptanceStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { access: CachedDbAccess::new(db, cache_policy, ACCEPTANCE_STORE_PREFIX) }
+    }
+}
+
+impl AcceptanceStoreReader for DbAcceptanceStore {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<TxAcceptance> {
+        self.access.read(transaction_id)
+    }
+}
+
+impl AcceptanceStore for DbAcceptanceStore {
+    fn set(&mut self, writer: impl DbWriter, transaction_id: TransactionId, acceptance: TxAcceptance) -> StoreResult<()> {
+        self.access.write(writer, transaction_id, acceptance)
+    }
+
+    fn remove(&mut self, writer: impl DbWriter, transaction_id: TransactionId) -> StoreResult<()> {
+        self.access.delete(writer, transaction_id)
+    }
+}
+
+/// Reader API for `ChainBlocksStore`.
+pub trait ChainBlocksStoreReader {
+    fn get(&self, key: ChainBlockKey) -> StoreResult<IndexedChainBlock>;
+    /// Returns up to `limit` chain blocks with a DAA score in `[from_daa_score, until_daa_score)`, from the lowest
+    /// DAA score up
+    fn get_range(
+        &self,
+        from_daa_score: u64,
+        until_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<Vec<(ChainBlockKey, IndexedChainBlock)>>;
+}
+
+/// Write API for `ChainBlocksStore`
+pub trait ChainBlocksStore: ChainBlocksStoreReader {
+    fn set(&mut self, writer: impl DbWriter, key: ChainBlockKey, chain_block: IndexedChainBlock) -> StoreResult<()>;
+    fn remove(&mut self, writer: impl DbWriter, key: ChainBlockKey) -> StoreResult<()>;
+}
+
+const CHAIN_BLOCKS_STORE_PREFIX: &[u8] = b"txindex-chain-blocks";
+
+/// A DB + cache implementation of `ChainBlocksStore` trait
+#[derive(Clone)]
+pub struct DbChainBlocksStore {
+    access: CachedDbAccess<ChainBlockKey, IndexedChainBlock>,
+}
+
+impl DbChainBlocksStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { access: CachedDbAccess::new(db, cache_policy, CHAIN_BLOCKS_STORE_PREFIX) }
+    }
+}
+
+impl ChainBlocksStoreReader for DbChainBlocksStore {
+    fn get(&self, key: ChainBlockKey) -> StoreResult<IndexedChainBlock> {
+        self.access.read(key)
+    }
+
+    fn get_range(
+        &self,
+        from_daa_score: u64,
+        until_daa_score: u64,
+        limit: usize,
+    ) -> StoreResult<Vec<(ChainBlockKey, IndexedChainBlock)>> {
+        self.access
+            .iterator()
+            .map(|item| item.map(|(key, chain_block)| (ChainBlockKey::from_slice(&key), chain_block)))
+            .skip_while(|item| matches!(item, Ok((key, _)) if key.daa_score() < from_daa_score))
+            .take_while(|item| !matches!(item, Ok((key, _)) if key.daa_score() >= until_daa_score))
+            .take(limit)
+            .collect()
+    }
+}
+
+impl ChainBlocksStore for DbChainBlocksStore {
+    fn set(&mut self, writer: impl DbWriter, key: ChainBlockKey, chain_block: IndexedChainBlock) -> StoreResult<()> {
+        self.access.write(writer, key, chain_block)
+    }
+
+    fn remove(&mut self, writer: impl DbWriter, key: ChainBlockKey) -> StoreResult<()> {
+        self.access.delete(writer, key)
+    }
+}
+
+const STATE_KEY: &[u8] = b"txindex-state";
+
+/// A DB + cache implementation of the index state item
+#[derive(Clone)]
+pub struct DbTxIndexStateStore {
+    access: CachedDbItem<TxIndexState>,
+}
+
+impl DbTxIndexStateStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { access: CachedDbItem::new(db, STATE_KEY) }
+    }
+
+    pub fn get(&self) -> StoreResult<TxIndexState> {
+        self.access.read()
+    }
+
+    pub fn set(&mut self, writer: impl DbWriter, state: &TxIndexState) -> StoreResult<()> {
+        self.access.write(writer, state)
+    }
+}
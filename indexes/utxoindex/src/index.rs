@@ -0,0 +1,258 @@
+use crate::stores::{
+    DbUtxoEntriesStore, DbUtxoIndexStateStore, ScriptPublicKeyBucket, UtxoEntriesStore, UtxoEntriesStoreReader, UtxoEntryKey,
+    UtxoIndexState,
+};
+use consensus::model::stores::{
+    database::prelude::BatchDbWriter,
+    errors::{StoreResult, StoreResultExtensions},
+    DB,
+};
+use consensus_core::{
+    api::DynConsensus,
+    notify::{Notification, NotificationConsumer},
+    tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry},
+    utxo::utxo_diff::UtxoDiff,
+};
+use hashes::Hash;
+use index_core::utxoindex::UtxoIndexApi;
+use kaspa_core::{error, info};
+use parking_lot::RwLock;
+use rocksdb::WriteBatch;
+use std::{collections::HashSet, sync::Arc};
+
+/// The number of UTXOs copied from the virtual UTXO set, or deleted from the index, per DB batch during a resync
+const RESYNC_CHUNK_SIZE: usize = 10_000;
+
+struct Stores {
+    entries: DbUtxoEntriesStore,
+    state: DbUtxoIndexStateStore,
+}
+
+/// Indexes the UTXOs of the virtual UTXO set by script public key, and tracks their circulating supply.
+///
+/// The index consumes the UtxosChanged consensus notifications, and records the virtual parents of the last applied
+/// diff. A resync rebuilds the index from the virtual UTXO set in chunks while consensus keeps processing blocks.
+/// Diffs notified meanwhile are applied between chunks, and since removing a missing UTXO or adding an existing one
+/// is a no-op, every UTXO ends up in the state of the latest diff or chunk covering it.
+pub struct UtxoIndex {
+    consensus: DynConsensus,
+    db: Arc<DB>,
+    stores: RwLock<Stores>,
+}
+
+impl UtxoIndex {
+    pub fn new(consensus: DynConsensus, db: Arc<DB>) -> Self {
+        let stores = Stores { entries: DbUtxoEntriesStore::new(db.clone()), state: DbUtxoIndexStateStore::new(db.clone()) };
+        Self { consensus, db, stores: RwLock::new(stores) }
+    }
+
+    /// Resyncs the index unless it holds the virtual UTXO set of the current virtual parents. Returns whether the
+    /// index was resynced
+    pub fn resync_if_needed(&self) -> StoreResult<bool> {
+        let state = self.stores.read().state.get().unwrap_option();
+        match state {
+            Some(state) if state.is_synced && state.virtual_parents == self.consensus.clone().get_virtual_parents() => Ok(false),
+            _ => self.resync().map(|_| true),
+        }
+    }
+
+    /// Clears the index and rebuilds it from the virtual UTXO set
+    pub fn resync(&self) -> StoreResult<()> {
+        info!("Resyncing the UTXO index from the virtual UTXO set");
+        self.clear()?;
+        let mut from_outpoint = None;
+        let mut utxo_count = 0;
+        loop {
+            let mut stores = self.stores.write();
+            let chunk = self.consensus.clone().get_virtual_utxos(from_outpoint, RESYNC_CHUNK_SIZE);
+            let mut state = stores.state.get()?;
+            let mut write_batch = WriteBatch::default();
+            Self::write_utxos(
+                &mut stores,
+                &mut write_batch,
+                &mut state,
+                std::iter::empty(),
+                chunk.iter().map(|(outpoint, entry)| (outpoint, entry)),
+            )?;
+            let is_last_chunk = chunk.len() < RESYNC_CHUNK_SIZE;
+            if is_last_chunk {
+                // Diffs notified but not applied yet are applied on top, recording their own virtual parents
+                state.virtual_parents = self.consensus.clone().get_virtual_parents();
+                state.is_synced = true;
+            }
+            stores.state.set(BatchDbWriter::new(&mut write_batch), &state)?;
+            self.db.write(write_batch)?;
+            utxo_count += chunk.len();
+            if is_last_chunk {
+                info!("Resynced the UTXO index with {} UTXOs, circulating supply {}", utxo_count, state.circulating_supply);
+                return Ok(());
+            }
+            from_outpoint = chunk.last().map(|&(outpoint, _)| outpoint);
+        }
+    }
+
+    /// Deletes all indexed UTXOs and resets the index state. The write lock is held throughout, so that no diff is
+    /// applied to a partially cleared index
+    fn clear(&self) -> StoreResult<()> {
+        let mut stores = self.stores.write();
+        // The state is reset first, so that an interrupted clear is resumed by the next resync
+        let mut write_batch = WriteBatch::default();
+        stores.state.set(BatchDbWriter::new(&mut write_batch), &UtxoIndexState::default())?;
+        self.db.write(write_batch)?;
+        loop {
+            let keys = stores.entries.get_keys(RESYNC_CHUNK_SIZE)?;
+            if keys.is_empty() {
+                return Ok(());
+            }
+            let mut write_batch = WriteBatch::default();
+            for &key in keys.iter() {
+                stores.entries.remove(BatchDbWriter::new(&mut write_batch), key)?;
+            }
+            self.db.write(write_batch)?;
+        }
+    }
+
+    /// Applies a diff of the virtual UTXO set, recording the virtual parents it led to
+    pub fn update(&self, utxo_diff: &UtxoDiff, virtual_parents: &[Hash]) -> StoreResult<()> {
+        let mut stores = self.stores.write();
+        let mut state = stores.state.get().unwrap_option().unwrap_or_default();
+        let mut write_batch = WriteBatch::default();
+        Self::write_utxos(&mut stores, &mut write_batch, &mut state, utxo_diff.remove.iter(), utxo_diff.add.iter())?;
+        state.virtual_parents = virtual_parents.to_vec();
+        stores.state.set(BatchDbWriter::new(&mut write_batch), &state)?;
+        self.db.write(write_batch)?;
+        Ok(())
+    }
+
+    /// Writes the removal and then the addition of UTXOs to the batch, updating the circulating supply. Removing a
+    /// missing UTXO or adding an existing one only leaves the UTXO as is
+    fn write_utxos<'a>(
+        stores: &mut Stores,
+        write_batch: &mut WriteBatch,
+        state: &mut UtxoIndexState,
+        removed: impl Iterator<Item = (&'a TransactionOutpoint, &'a UtxoEntry)>,
+        added: impl Iterator<Item = (&'a TransactionOutpoint, &'a UtxoEntry)>,
+    ) -> StoreResult<()> {
+        let mut removed_keys = HashSet::new();
+        for (&outpoint, entry) in removed {
+            let key = UtxoEntryKey::new(ScriptPublicKeyBucket::new(&entry.script_public_key), outpoint);
+            if stores.entries.has(key)? {
+                stores.entries.remove(BatchDbWriter::new(write_batch), key)?;
+                state.circulating_supply -= entry.amount;
+                removed_keys.insert(key);
+            }
+        }
+        for (&outpoint, entry) in added {
+            let key = UtxoEntryKey::new(ScriptPublicKeyBucket::new(&entry.script_public_key), outpoint);
+            // Removals are pending in the batch, so the store still holds the UTXOs removed above
+            if removed_keys.contains(&key) || !stores.entries.has(key)? {
+                state.circulating_supply += entry.amount;
+            }
+            stores.entries.set(BatchDbWriter::new(write_batch), key, entry.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl UtxoIndexApi for UtxoIndex {
+    fn get_utxos_by_script_public_key(&self, script_public_key: &ScriptPublicKey) -> Vec<(TransactionOutpoint, UtxoEntry)> {
+        let mut utxos = self.stores.read().entries.get_bucket(ScriptPublicKeyBucket::new(script_public_key)).unwrap();
+        // A bucket might in theory be shared by colliding script public keys
+        utxos.retain(|(_, entry)| entry.script_public_key == *script_public_key);
+        utxos
+    }
+
+    fn get_circulating_supply(&self) -> u64 {
+        self.stores.read().state.get().unwrap_option().map_or(0, |state| state.circulating_supply)
+    }
+}
+
+impl NotificationConsumer for UtxoIndex {
+    fn consume(&self, notification: Arc<Notification>) {
+        if let Notification::UtxosChanged(ref notification) = *notification {
+            if let Err(err) = self.update(&notification.accumulated_utxo_diff, &notification.virtual_parents) {
+                error!("Failed updating the UTXO index: {}", err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::{create_temp_db, TestConsensus};
+    use consensus_core::{
+        api::ConsensusApi,
+        config::params::MAINNET_PARAMS,
+        notify::{BackpressurePolicy, NotificationType},
+    };
+    use std::time::Duration;
+
+    async fn add_chain(consensus: &TestConsensus, first: u64, count: u64, mut parents: Vec<Hash>) -> Vec<Hash> {
+        let mut chain = Vec::new();
+        for i in first..first + count {
+            let hash: Hash = i.into();
+            let block = consensus.build_block_with_parents_and_transactions(hash, parents, vec![]);
+            consensus.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            parents = vec![hash];
+            chain.push(hash);
+        }
+        chain
+    }
+
+    /// Waits for the index to apply the diffs notified up to the current virtual state
+    fn wait_for_virtual_parents(index: &UtxoIndex, consensus: &TestConsensus) {
+        let virtual_parents = consensus.consensus().clone().get_virtual_parents();
+        for _ in 0..100 {
+            if index.stores.read().state.get().unwrap().virtual_parents == virtual_parents {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("the UTXO index did not reach the virtual state");
+    }
+
+    fn assert_indexes_virtual_utxo_set(index: &UtxoIndex, consensus: &TestConsensus) {
+        let utxos = consensus.consensus().clone().get_virtual_utxos(None, usize::MAX);
+        assert!(!utxos.is_empty());
+        let script_public_keys: HashSet<_> = utxos.iter().map(|(_, entry)| entry.script_public_key.clone()).collect();
+        let indexed: HashSet<_> = script_public_keys
+            .iter()
+            .flat_map(|script_public_key| index.get_utxos_by_script_public_key(script_public_key))
+            .map(|(outpoint, _)| outpoint)
+            .collect();
+        assert_eq!(indexed, utxos.iter().map(|(outpoint, _)| *outpoint).collect());
+        assert_eq!(index.get_circulating_supply(), utxos.iter().map(|(_, entry)| entry.amount).sum::<u64>());
+    }
+
+    #[tokio::test]
+    async fn test_utxo_index_resync_and_update() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let consensus = TestConsensus::create_from_temp_db(&params);
+        let wait_handles = consensus.init();
+        let (_lifetime, db) = create_temp_db();
+
+        // The index is resynced from the virtual UTXO set when first enabled
+        add_chain(&consensus, 1, 10, vec![params.genesis.hash]).await;
+        let index = Arc::new(UtxoIndex::new(consensus.consensus().clone(), db));
+        assert!(index.resync_if_needed().unwrap());
+        assert!(!index.resync_if_needed().unwrap());
+        assert_indexes_virtual_utxo_set(&index, &consensus);
+
+        // Notified diffs are applied, including the diff of a reorg to a heavier side chain
+        let id =
+            consensus.notification_root().register(&[NotificationType::UtxosChanged], BackpressurePolicy::Unbounded, index.clone());
+        let side_chain = add_chain(&consensus, 100, 12, vec![params.genesis.hash]).await;
+        wait_for_virtual_parents(&index, &consensus);
+        assert_indexes_virtual_utxo_set(&index, &consensus);
+        assert!(!index.resync_if_needed().unwrap());
+
+        // An index which missed diffs is resynced
+        consensus.notification_root().unregister(id);
+        add_chain(&consensus, 200, 2, vec![*side_chain.last().unwrap()]).await;
+        assert!(index.resync_if_needed().unwrap());
+        assert_indexes_virtual_utxo_set(&index, &consensus);
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,12 @@
+//! UTXO index.
+//!
+//! The [`UtxoIndex`] holds the virtual UTXO set keyed by script public key, along with its circulating supply. It
+//! is updated from the UtxosChanged consensus notifications it consumes, and rebuilt from the virtual UTXO set when
+//! first enabled or found out of date. The [`UtxoIndexService`] runs this resync in the background on startup.
+
+mod index;
+mod service;
+mod stores;
+
+pub use index::UtxoIndex;
+pub use service::UtxoIndexService;
@@ -0,0 +1,67 @@
+use crate::index::UtxoIndex;
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::DuplexTrigger;
+use std::sync::Arc;
+
+const UTXO_INDEX_SERVICE: &str = "utxo-index";
+
+/// Resyncs the [`UtxoIndex`] on startup if it is out of date. The index is then kept up to date by the consensus
+/// notifications it consumes.
+pub struct UtxoIndexService {
+    index: Arc<UtxoIndex>,
+    shutdown: DuplexTrigger,
+}
+
+impl UtxoIndexService {
+    pub fn new(index: Arc<UtxoIndex>) -> Self {
+        Self { index, shutdown: DuplexTrigger::default() }
+    }
+
+    async fn resync_if_needed(&self) {
+        let index = self.index.clone();
+        if let Err(err) = tokio::task::spawn_blocking(move || index.resync_if_needed()).await.unwrap() {
+            warn!("Failed resyncing the UTXO index: {}", err);
+        }
+    }
+}
+
+impl AsyncService for UtxoIndexService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        UTXO_INDEX_SERVICE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", UTXO_INDEX_SERVICE);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            // A resync is not interrupted by an early shutdown request, since it cannot be resumed halfway
+            self.resync_if_needed().await;
+            shutdown_signal.await;
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", UTXO_INDEX_SERVICE);
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} stopping", UTXO_INDEX_SERVICE);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        Box::pin(async move {
+            // Wait for the pending resync to complete
+            shutdown_executed_signal.await;
+            trace!("{} exiting", UTXO_INDEX_SERVICE);
+        })
+    }
+}
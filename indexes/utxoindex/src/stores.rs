@@ -0,0 +1,156 @@
+use consensus::model::stores::{
+    database::prelude::{CachePolicy, CachedDbAccess, CachedDbItem, DbWriter},
+    errors::StoreResult,
+    DB,
+};
+use consensus_core::tx::{ScriptPublicKey, TransactionIndexType, TransactionOutpoint, UtxoEntry};
+use hashes::{Hash, HasherBase, ScriptPublicKeyHash, HASH_SIZE};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, mem::size_of, sync::Arc};
+
+/// The hash bucketing the UTXOs of a script public key. Script public keys have no bounded size, so their
+/// UTXOs are keyed by this hash rather than by the script itself
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScriptPublicKeyBucket(Hash);
+
+impl ScriptPublicKeyBucket {
+    pub fn new(script_public_key: &ScriptPublicKey) -> Self {
+        let mut hasher = ScriptPublicKeyHash::new();
+        hasher.update(script_public_key.version().to_le_bytes()).update(script_public_key.script());
+        Self(hasher.finalize())
+    }
+}
+
+impl AsRef<[u8]> for ScriptPublicKeyBucket {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+const UTXO_ENTRY_KEY_LEN: usize = HASH_SIZE + HASH_SIZE + size_of::<TransactionIndexType>();
+
+/// The DB key of an indexed UTXO, i.e., the bucket of its script public key followed by its outpoint, so that
+/// the UTXOs of a script public key are iterated by prefix
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UtxoEntryKey([u8; UTXO_ENTRY_KEY_LEN]);
+
+impl UtxoEntryKey {
+    pub fn new(bucket: ScriptPublicKeyBucket, outpoint: TransactionOutpoint) -> Self {
+        let mut bytes = [0u8; UTXO_ENTRY_KEY_LEN];
+        bytes[..HASH_SIZE].copy_from_slice(bucket.as_ref());
+        bytes[HASH_SIZE..2 * HASH_SIZE].copy_from_slice(&outpoint.transaction_id.as_bytes());
+        bytes[2 * HASH_SIZE..].copy_from_slice(&outpoint.index.to_le_bytes());
+        Self(bytes)
+    }
+
+    fn from_slice(bytes: &[u8]) -> Self {
+        Self(bytes.try_into().unwrap())
+    }
+
+    pub fn outpoint(&self) -> TransactionOutpoint {
+        let index = TransactionIndexType::from_le_bytes(self.0[2 * HASH_SIZE..].try_into().unwrap());
+        TransactionOutpoint::new(Hash::from_slice(&self.0[HASH_SIZE..2 * HASH_SIZE]), index)
+    }
+}
+
+impl AsRef<[u8]> for UtxoEntryKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for UtxoEntryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", Hash::from_slice(&self.0[..HASH_SIZE]), self.outpoint())
+    }
+}
+
+/// The sync status of the index along with its circulating supply
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtxoIndexState {
+    /// The virtual parents of the last indexed virtual UTXO set
+    pub virtual_parents: Vec<Hash>,
+    /// Whether the index holds the full virtual UTXO set, i.e., a resync was completed since the index was cleared
+    pub is_synced: bool,
+    /// The total amount of the indexed UTXOs
+    pub circulating_supply: u64,
+}
+
+/// Reader API for `UtxoEntriesStore`.
+pub trait UtxoEntriesStoreReader {
+    fn has(&self, key: UtxoEntryKey) -> StoreResult<bool>;
+    /// Returns the UTXOs of all script public keys hashed into `bucket`
+    fn get_bucket(&self, bucket: ScriptPublicKeyBucket) -> StoreResult<Vec<(TransactionOutpoint, UtxoEntry)>>;
+    /// Returns up to `limit` keys of the store, from the first one
+    fn get_keys(&self, limit: usize) -> StoreResult<Vec<UtxoEntryKey>>;
+}
+
+/// Write API for `UtxoEntriesStore`
+pub trait UtxoEntriesStore: UtxoEntriesStoreReader {
+    fn set(&mut self, writer: impl DbWriter, key: UtxoEntryKey, entry: UtxoEntry) -> StoreResult<()>;
+    fn remove(&mut self, writer: impl DbWriter, key: UtxoEntryKey) -> StoreResult<()>;
+}
+
+const UTXO_ENTRIES_STORE_PREFIX: &[u8] = b"utxoindex-entries";
+
+/// A DB implementation of `UtxoEntriesStore` trait. Entries are always read by prefix iteration, which bypasses
+/// the cache, so no cache is kept
+#[derive(Clone)]
+pub struct DbUtxoEntriesStore {
+    access: CachedDbAccess<UtxoEntryKey, UtxoEntry>,
+}
+
+impl DbUtxoEntriesStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { access: CachedDbAccess::new(db, CachePolicy::Count(0), UTXO_ENTRIES_STORE_PREFIX) }
+    }
+}
+
+impl UtxoEntriesStoreReader for DbUtxoEntriesStore {
+    fn has(&self, key: UtxoEntryKey) -> StoreResult<bool> {
+        self.access.has(key)
+    }
+
+    fn get_bucket(&self, bucket: ScriptPublicKeyBucket) -> StoreResult<Vec<(TransactionOutpoint, UtxoEntry)>> {
+        self.access
+            .iter_prefix(bucket.as_ref())
+            .map(|item| item.map(|(key, entry)| (UtxoEntryKey::from_slice(&key).outpoint(), entry)))
+            .collect()
+    }
+
+    fn get_keys(&self, limit: usize) -> StoreResult<Vec<UtxoEntryKey>> {
+        self.access.iterator().take(limit).map(|item| item.map(|(key, _)| UtxoEntryKey::from_slice(&key))).collect()
+    }
+}
+
+impl UtxoEntriesStore for DbUtxoEntriesStore {
+    fn set(&mut self, writer: impl DbWriter, key: UtxoEntryKey, entry: UtxoEntry) -> StoreResult<()> {
+        self.access.write(writer, key, entry)
+    }
+
+    fn remove(&mut self, writer: impl DbWriter, key: UtxoEntryKey) -> StoreResult<()> {
+        self.access.delete(writer, key)
+    }
+}
+
+const STATE_KEY: &[u8] = b"utxoindex-state";
+
+/// A DB + cache implementation of the index state item
+#[derive(Clone)]
+pub struct DbUtxoIndexStateStore {
+    access: CachedDbItem<UtxoIndexState>,
+}
+
+impl DbUtxoIndexStateStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { access: CachedDbItem::new(db, STATE_KEY) }
+    }
+
+    pub fn get(&self) -> StoreResult<UtxoIndexState> {
+        self.access.read()
+    }
+
+    pub fn set(&mut self, writer: impl DbWriter, state: &UtxoIndexState) -> StoreResult<()> {
+        self.access.write(writer, state)
+    }
+}
@@ -0,0 +1,150 @@
+//! The kaspa blocks file format, used for exporting and importing blocks for offline sync.
+//!
+//! A blocks file starts with an 8 bytes magic followed by a little-endian u32 format version. Blocks
+//! follow in topological order, each as a little-endian u32 length prefix followed by the bincode
+//! encoding of the block header and transactions.
+
+use consensus::consensus::Consensus;
+use consensus_core::{block::Block, errors::block::RuleError, header::Header, tx::Transaction};
+use hashes::Hash;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+
+const MAGIC: &[u8; 8] = b"KASBLKS\0";
+const FORMAT_VERSION: u32 = 1;
+
+/// An upper bound on the encoded size of a single block, protecting against corrupted length prefixes
+const MAX_BLOCK_RECORD_SIZE: u32 = 1 << 26;
+
+/// The number of blocks submitted concurrently to consensus during import
+const IMPORT_BATCH_SIZE: usize = 256;
+
+#[derive(Error, Debug)]
+pub enum BlocksFileError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("invalid blocks file: {0}")]
+    InvalidFormat(String),
+
+    #[error("block {0} was rejected: {1}")]
+    RejectedBlock(Hash, RuleError),
+}
+
+pub type BlocksFileResult<T> = Result<T, BlocksFileError>;
+
+pub struct BlocksFileWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> BlocksFileWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(Self { writer })
+    }
+
+    pub fn write_block(&mut self, block: &Block) -> BlocksFileResult<()> {
+        let record = bincode::serialize(&(&*block.header, &*block.transactions))
+            .map_err(|err| BlocksFileError::InvalidFormat(err.to_string()))?;
+        let len = u32::try_from(record.len())
+            .ok()
+            .filter(|&len| len <= MAX_BLOCK_RECORD_SIZE)
+            .ok_or_else(|| BlocksFileError::InvalidFormat(format!("block {} exceeds the maximum record size", block.hash())))?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&record)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+pub struct BlocksFileReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> BlocksFileReader<R> {
+    pub fn new(mut reader: R) -> BlocksFileResult<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BlocksFileError::InvalidFormat("unknown magic".to_string()));
+        }
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(BlocksFileError::InvalidFormat(format!("unsupported format version {}", version)));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Reads the next block, returning `None` at the end of the file
+    pub fn read_block(&mut self) -> BlocksFileResult<Option<Block>> {
+        let mut len = [0u8; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+        let len = u32::from_le_bytes(len);
+        if len > MAX_BLOCK_RECORD_SIZE {
+            return Err(BlocksFileError::InvalidFormat(format!("block record size {} exceeds the maximum", len)));
+        }
+        let mut record = vec![0u8; len as usize];
+        self.reader.read_exact(&mut record)?;
+        let (mut header, mut transactions): (Header, Vec<Transaction>) =
+            bincode::deserialize(&record).map_err(|err| BlocksFileError::InvalidFormat(err.to_string()))?;
+
+        // Cached hashes are never trusted
+        header.finalize();
+        transactions.iter_mut().for_each(|tx| tx.finalize());
+        Ok(Some(Block::new(header, transactions)))
+    }
+}
+
+/// Writes all blocks above the pruning point into a blocks file, returning the number of exported blocks
+pub fn export_blocks(consensus: &Consensus, path: &Path) -> BlocksFileResult<u64> {
+    let mut writer = BlocksFileWriter::new(BufWriter::new(File::create(path)?))?;
+    let mut count = 0;
+    for hash in consensus.pruning_point_future_blocks() {
+        // Blocks returned by the traversal are guaranteed to have a body
+        writer.write_block(&consensus.get_block(hash).unwrap())?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Feeds the blocks of a blocks file through the consensus pipeline, returning the number of imported blocks.
+///
+/// Blocks are submitted in batches processed as a group and are fully validated. The importing node is expected to share
+/// the pruning point of the exporting node.
+pub async fn import_blocks(consensus: &Consensus, path: &Path) -> BlocksFileResult<u64> {
+    let mut reader = BlocksFileReader::new(BufReader::new(File::open(path)?))?;
+    let mut count = 0;
+    loop {
+        let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+        while batch.len() < IMPORT_BATCH_SIZE {
+            match reader.read_block()? {
+                Some(block) => batch.push(block),
+                None => break,
+            }
+        }
+        if batch.is_empty() {
+            return Ok(count);
+        }
+        let hashes: Vec<Hash> = batch.iter().map(|block| block.hash()).collect();
+        let results = consensus.validate_and_insert_blocks(batch).await;
+        for (hash, result) in hashes.into_iter().zip(results) {
+            result.map_err(|err| BlocksFileError::RejectedBlock(hash, err))?;
+            count += 1;
+        }
+    }
+}
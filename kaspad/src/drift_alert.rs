@@ -0,0 +1,237 @@
+//! Alerting on the drift of the virtual DAA score from the wall clock, which catches both local stalls (the node
+//! stops processing blocks) and network-wide hashrate collapses (blocks stop coming).
+
+use consensus_core::{api::DynConsensus, stats::DaaScoreDrift};
+use kaspa_core::{
+    info,
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::DuplexTrigger;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+const DRIFT_ALERT: &str = "drift-alert";
+
+/// The interval between two drift measures
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// An alert level is only left once the drift falls below this ratio of its threshold, so that a drift hovering
+/// around a threshold does not raise an alert on every check
+const RECOVERY_RATIO: f64 = 0.5;
+
+pub const DEFAULT_WARNING_THRESHOLD: f64 = 60.0;
+pub const DEFAULT_CRITICAL_THRESHOLD: f64 = 600.0;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid webhook URL {0}, expecting http://<host>[:<port>][/<path>]")]
+pub struct WebhookUrlError(String);
+
+/// A plain HTTP endpoint receiving the alerts as JSON POST requests
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookUrl {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl FromStr for WebhookUrl {
+    type Err = WebhookUrlError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let invalid = || WebhookUrlError(url.to_string());
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self { host: host.to_string(), port, path: path.to_string() })
+    }
+}
+
+impl Display for WebhookUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "http://{0}:{1}{2}", self.host, self.port, self.path)
+    }
+}
+
+/// The drift thresholds, in units of target time per block, and the optional webhook
+#[derive(Clone, Debug)]
+pub struct DriftAlertConfig {
+    pub warning_threshold: f64,
+    pub critical_threshold: f64,
+    pub webhook: Option<WebhookUrl>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl AlertLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            AlertLevel::Normal => "normal",
+            AlertLevel::Warning => "warning",
+            AlertLevel::Critical => "critical",
+        }
+    }
+}
+
+impl DriftAlertConfig {
+    /// The level following `current` for a measured `drift`. Levels are raised as soon as the drift exceeds their
+    /// threshold and lowered only once it falls below [`RECOVERY_RATIO`] of the threshold.
+    pub fn next_level(&self, current: AlertLevel, drift: f64) -> AlertLevel {
+        let raised = if drift > self.critical_threshold {
+            AlertLevel::Critical
+        } else if drift > self.warning_threshold {
+            AlertLevel::Warning
+        } else {
+            AlertLevel::Normal
+        };
+        if raised >= current {
+            return raised;
+        }
+        match current {
+            AlertLevel::Critical if drift >= self.critical_threshold * RECOVERY_RATIO => AlertLevel::Critical,
+            AlertLevel::Critical | AlertLevel::Warning if drift >= self.warning_threshold * RECOVERY_RATIO => AlertLevel::Warning,
+            _ => AlertLevel::Normal,
+        }
+    }
+}
+
+/// Periodically measures the DAA score drift and alerts on every change of [`AlertLevel`], through the log and the
+/// webhook if any. Checks are skipped while IBD is running, since the node is then expected to lag behind.
+pub struct DriftAlertService {
+    consensus: DynConsensus,
+    network_name: &'static str,
+    config: DriftAlertConfig,
+    is_ibd_running: Arc<AtomicBool>,
+    shutdown: DuplexTrigger,
+}
+
+impl DriftAlertService {
+    pub fn new(
+        consensus: DynConsensus,
+        network_name: &'static str,
+        config: DriftAlertConfig,
+        is_ibd_running: Arc<AtomicBool>,
+    ) -> Self {
+        Self { consensus, network_name, config, is_ibd_running, shutdown: DuplexTrigger::default() }
+    }
+
+    async fn alert(&self, level: AlertLevel, drift: &DaaScoreDrift) {
+        match level {
+            AlertLevel::Normal => {
+                info!("DAA score drift recovered: {0:.1} blocks (virtual DAA score {1})", drift.drift(), drift.virtual_daa_score)
+            }
+            _ => warn!(
+                "DAA score drift is {0}: {1:.1} blocks behind the clock (virtual DAA score {2}, expected {3}, sink timestamp {4})",
+                level.as_str(),
+                drift.drift(),
+                drift.virtual_daa_score,
+                drift.expected_daa_score(),
+                drift.sink_timestamp
+            ),
+        }
+        if let Some(webhook) = self.config.webhook.as_ref() {
+            let body = format!(
+                "{{\"network\":\"{0}\",\"level\":\"{1}\",\"daaScoreDrift\":{2},\"virtualDaaScore\":{3},\"expectedDaaScore\":{4},\"sinkTimestamp\":{5}}}",
+                self.network_name,
+                level.as_str(),
+                drift.drift(),
+                drift.virtual_daa_score,
+                drift.expected_daa_score(),
+                drift.sink_timestamp
+            );
+            if let Err(err) = post(webhook, &body).await {
+                warn!("Failed posting the DAA score drift alert to {0}: {1}", webhook, err);
+            }
+        }
+    }
+}
+
+impl AsyncService for DriftAlertService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        DRIFT_ALERT
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", DRIFT_ALERT);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            tokio::pin!(shutdown_signal);
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            let mut level = AlertLevel::Normal;
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = &mut shutdown_signal => break,
+                    _ = interval.tick() => {
+                        if self.is_ibd_running.load(Ordering::SeqCst) {
+                            continue;
+                        }
+                        let drift = self.consensus.clone().get_daa_score_drift();
+                        let next_level = self.config.next_level(level, drift.drift());
+                        if next_level != level {
+                            level = next_level;
+                            self.alert(level, &drift).await;
+                        }
+                    }
+                }
+            }
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", DRIFT_ALERT);
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} stopping", DRIFT_ALERT);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        Box::pin(async move {
+            shutdown_executed_signal.await;
+            trace!("{} exiting", DRIFT_ALERT);
+        })
+    }
+}
+
+/// Posts a JSON body to the webhook, without waiting for the response
+async fn post(webhook: &WebhookUrl, body: &str) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect((webhook.host.as_str(), webhook.port)).await?;
+    let request = format!(
+        "POST {0} HTTP/1.1\r\nHost: {1}\r\nContent-Type: application/json\r\nContent-Length: {2}\r\nConnection: close\r\n\r\n{3}",
+        webhook.path,
+        webhook.host,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await
+}
@@ -2,46 +2,239 @@ extern crate consensus;
 extern crate core;
 extern crate hashes;
 
+use addressmanager::{AddressManager, BanManager, Config as AddressManagerConfig, DEFAULT_BAN_DURATION};
 use clap::Parser;
+use connectionmanager::{Config as ConnectionManagerConfig, ConnectionManager, DEFAULT_OUTBOUND_TARGET};
 use consensus::model::stores::DB;
+use flows::FlowContext;
+use index_core::{txindex::DynTxIndex, utxoindex::DynUtxoIndex};
+use index_processor::IndexProcessor;
 use kaspa_core::{core::Core, signals::Signals, task::runtime::AsyncRuntime};
+use p2p::{
+    handshake::{LocalVersion, SERVICE_ARCHIVAL},
+    Hub,
+};
+use parking_lot::Mutex;
 use std::fs;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::__private::PathAsDisplay;
+use txindex::{Config as TxIndexConfig, TxIndex, TxIndexService};
+use utxoindex::{UtxoIndex, UtxoIndexService};
+use uuid::Uuid;
 
+use crate::drift_alert::{DriftAlertConfig, DriftAlertService, WebhookUrl, DEFAULT_CRITICAL_THRESHOLD, DEFAULT_WARNING_THRESHOLD};
+use crate::metrics::{MetricsServer, MetricsSources};
 use crate::monitor::ConsensusMonitor;
+use crate::peer_manager::PeerManager;
+use crate::perf_monitor::PerfMonitor;
+use crate::probe::ProbeServer;
+use crate::self_test::run_self_test;
+use consensus::consensus::transfer::{transfer_blocks, TransferProgress};
 use consensus::consensus::Consensus;
-use consensus::params::DEVNET_PARAMS;
-use kaspa_core::{info, trace};
-use rpc_core::server::collector::ConsensusNotificationChannel;
-use rpc_core::server::RpcCoreServer;
+use consensus_core::config::{
+    params::{NetworkType, Params},
+    Config,
+};
+use consensus_core::notify::{BackpressurePolicy, NotificationType};
+use consensus_core::time::DurationMs;
+use kaspa_core::{error, info, trace};
+use kaspa_utils::networking::ContextualNetAddress;
+use mining::{block_template::cache::DEFAULT_BLOCK_TEMPLATE_CACHE_LIFETIME, manager::MiningManager, mempool::Config as MempoolConfig};
+use rpc_core::server::collector::{ConsensusNotificationChannel, ConsensusNotificationForwarder};
+use rpc_core::server::{peers::DynPeerManager, perf::DynPerfMonitor, RpcCoreServer};
 use rpc_grpc::server::GrpcServer;
+use rpc_wrpc::{codec::Encoding, server::WrpcServer};
 
+mod blocks_file;
+mod drift_alert;
+mod metrics;
 mod monitor;
+mod peer_manager;
+mod perf_monitor;
+mod probe;
+mod self_test;
 
 const DEFAULT_DATA_DIR: &str = "datadir";
+const DEFAULT_LOG_DIR: &str = "logs";
 
-// TODO: add a Config
-// TODO: apply Args to Config
-// TODO: log to file
+/// The number of consensus notifications queued for the index processor, beyond which consensus processing waits
+/// for the indexes to catch up
+const INDEX_PROCESSOR_QUEUE_SIZE: usize = 1_000;
+
+/// The number of processed notifications queued for the RPC notifier, beyond which the oldest ones are dropped so
+/// that slow RPC subscribers cannot hold back the indexes nor consensus
+const RPC_NOTIFICATION_QUEUE_SIZE: usize = 1_000;
 
 /// Kaspa Node launch arguments
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Directory to store data
+    /// Directory to store data. The data of each network is kept in a subdirectory named after the network
     #[arg(short = 'b', long = "appdir")]
     app_dir: Option<String>,
 
-    /// Interface/port to listen for RPC connections (default port: 16110, testnet: 16210)
+    /// Use the test network
+    #[arg(long = "testnet", group = "network")]
+    testnet: bool,
+
+    /// Use the development test network
+    #[arg(long = "devnet", group = "network")]
+    devnet: bool,
+
+    /// Use the simulation test network
+    #[arg(long = "simnet", group = "network")]
+    simnet: bool,
+
+    /// Interface/port to listen for RPC connections (default port: 16110, testnet: 16210, simnet: 16510, devnet: 16610)
     #[arg(long = "rpclisten")]
     rpc_listen: Option<String>,
 
+    /// Interface/port to listen for wRPC connections with Borsh encoding. If not set, wRPC Borsh is not served
+    #[arg(long = "rpclisten-borsh")]
+    rpc_listen_borsh: Option<String>,
+
+    /// Interface/port to listen for wRPC connections with JSON encoding. If not set, wRPC JSON is not served
+    #[arg(long = "rpclisten-json")]
+    rpc_listen_json: Option<String>,
+
+    /// Interface/port to serve the /healthy and /ready HTTP probes on. If not set, probes are not served
+    #[arg(long = "probelisten")]
+    probe_listen: Option<String>,
+
+    /// Interface/port to serve the /metrics HTTP endpoint on, in the Prometheus text format. If not set, metrics are not served
+    #[arg(long = "metricslisten")]
+    metrics_listen: Option<String>,
+
+    /// Add an interface/port to listen for connections (default all interfaces port: 16111, testnet: 16211, simnet: 16511,
+    /// devnet: 16611).
+    /// May be specified multiple times
+    #[arg(long = "listen")]
+    listen: Vec<ContextualNetAddress>,
+
+    /// Add an ip to the list of local addresses we claim to listen on to peers.
+    /// If not set, the first publicly routable listen address is advertised
+    #[arg(long = "externalip")]
+    external_ip: Option<ContextualNetAddress>,
+
+    /// Connect only to the specified peers at startup. May be specified multiple times
+    #[arg(long = "connect", conflicts_with = "add_peers")]
+    connect_peers: Vec<ContextualNetAddress>,
+
+    /// Add a peer to connect with at startup and reconnect to when dropped. May be specified multiple times
+    #[arg(long = "addpeer")]
+    add_peers: Vec<ContextualNetAddress>,
+
+    /// Target number of outbound peers
+    #[arg(long = "outpeers", default_value_t = DEFAULT_OUTBOUND_TARGET)]
+    outbound_target: usize,
+
+    /// Add a DNS seeder to query for peer addresses when none is known. May be specified multiple times
+    #[arg(long = "dnsseed")]
+    dns_seeders: Vec<String>,
+
+    /// Duration in seconds for which misbehaving peers are banned
+    #[arg(long = "banduration", default_value_t = DEFAULT_BAN_DURATION.as_millis() / 1000)]
+    ban_duration: u64,
+
+    /// Disable the mempool and block template lane reserved for transactions submitted to this node
+    #[arg(long = "nolocalprioritylane")]
+    no_local_priority_lane: bool,
+
+    /// Disable the periodic rebroadcasting of the transactions submitted to this node until they are accepted
+    #[arg(long = "nolocalrebroadcast")]
+    no_local_rebroadcast: bool,
+
+    /// Keep the data of all blocks rather than pruning it below the pruning point, and serve the whole DAG to
+    /// archival peers. Requires a node which never pruned, e.g., a new node
+    #[arg(long = "archival")]
+    archival: bool,
+
+    /// Number of days for which the bodies and acceptance data of blocks are kept below the pruning point before
+    /// being garbage collected. Defaults to 0, i.e., data is collected as soon as the pruning point moves past it
+    #[arg(long = "retention-period-days", conflicts_with = "archival")]
+    retention_period_days: Option<u64>,
+
+    /// Maintain an index of the chain blocks accepting each transaction
+    #[arg(long = "txindex")]
+    tx_index: bool,
+
+    /// Number of days for which the transaction index retains acceptances. If not set, acceptances are never trimmed
+    #[arg(long = "txindexretentiondays", requires = "tx_index")]
+    tx_index_retention_days: Option<u64>,
+
+    /// Maintain an index of the virtual UTXO set by address, enabling the UTXO and balance queries by address
+    #[arg(long = "utxoindex")]
+    utxo_index: bool,
+
+    /// Drift of the virtual DAA score from the clock, in target block times, above which a warning alert is raised
+    #[arg(long = "driftalertthreshold", default_value_t = DEFAULT_WARNING_THRESHOLD)]
+    drift_alert_threshold: f64,
+
+    /// Drift of the virtual DAA score from the clock, in target block times, above which a critical alert is raised
+    #[arg(long = "driftalertcriticalthreshold", default_value_t = DEFAULT_CRITICAL_THRESHOLD)]
+    drift_alert_critical_threshold: f64,
+
+    /// HTTP URL (http://<host>[:<port>][/<path>]) to POST DAA score drift alerts to as JSON. If not set, alerts are only logged
+    #[arg(long = "driftalertwebhook")]
+    drift_alert_webhook: Option<WebhookUrl>,
+
     /// Logging level for all subsystems {off, error, warn, info, debug, trace}
     ///  -- You may also specify <subsystem>=<level>,<subsystem2>=<level>,... to set the log level for individual subsystems
     #[arg(short = 'd', long = "loglevel", default_value = "info")]
     log_level: String,
+
+    /// Directory to write the rolling log files to (default: the logs subdirectory of the network data)
+    #[arg(long = "logdir")]
+    log_dir: Option<PathBuf>,
+
+    /// Disable logging to files, logging to the console only
+    #[arg(long = "nologfiles", conflicts_with = "log_dir")]
+    no_log_files: bool,
+
+    /// Export all blocks above the pruning point into the given blocks file and exit
+    #[arg(long = "export-blocks")]
+    export_blocks: Option<PathBuf>,
+
+    /// Import the blocks of a blocks file written with --export-blocks and exit
+    #[arg(long = "import-blocks")]
+    import_blocks: Option<PathBuf>,
+
+    /// Transfer all blocks of the data directory at the given path into the data directory and exit, fully
+    /// validating them. An interrupted transfer resumes when run again
+    #[arg(long = "transfer-blocks-from")]
+    transfer_blocks_from: Option<PathBuf>,
+
+    /// Back up the database into a new subdirectory of the given directory before migrating its schema to the
+    /// format of this node version. Best kept on the file system of the data directory, where the backup is cheap
+    #[arg(long = "migrationbackupdir")]
+    migration_backup_dir: Option<PathBuf>,
+}
+
+impl Args {
+    /// The network selected by the network flags, mainnet if none is set
+    fn network(&self) -> NetworkType {
+        if self.testnet {
+            NetworkType::Testnet
+        } else if self.devnet {
+            NetworkType::Devnet
+        } else if self.simnet {
+            NetworkType::Simnet
+        } else {
+            NetworkType::Mainnet
+        }
+    }
+}
+
+/// The default RPC and P2P ports of a network
+fn default_ports(network: NetworkType) -> (u16, u16) {
+    match network {
+        NetworkType::Mainnet => (16110, 16111),
+        NetworkType::Testnet => (16210, 16211),
+        NetworkType::Simnet => (16510, 16511),
+        NetworkType::Devnet => (16610, 16611),
+    }
 }
 
 fn get_home_dir() -> PathBuf {
@@ -62,10 +255,9 @@ pub fn main() {
     // Get CLI arguments
     let args = Args::parse();
 
-    // Initialize the logger
-    kaspa_core::log::init_logger(&args.log_level);
-
-    info!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    let network = args.network();
+    let config = Config::new(network.params());
+    let (default_rpc_port, default_p2p_port) = default_ports(network);
 
     // TODO: Refactor all this quick-and-dirty code
     let app_dir = args
@@ -73,30 +265,206 @@ pub fn main() {
         .unwrap_or_else(|| get_app_dir().as_path().to_str().unwrap().to_string())
         .replace('~', get_home_dir().as_path().to_str().unwrap());
     let app_dir = if app_dir.is_empty() { get_app_dir() } else { PathBuf::from(app_dir) };
-    let db_dir = app_dir.join(DEFAULT_DATA_DIR);
+    let db_dir = app_dir.join(config.network_name).join(DEFAULT_DATA_DIR);
     assert!(!db_dir.to_str().unwrap().is_empty());
+    let log_dir = if args.no_log_files {
+        None
+    } else {
+        Some(args.log_dir.unwrap_or_else(|| app_dir.join(config.network_name).join(DEFAULT_LOG_DIR)))
+    };
+
+    // Initialize the logger
+    kaspa_core::log::init_logger(log_dir.as_ref().map(|dir| dir.to_str().unwrap()), &args.log_level);
+
+    info!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    info!("Network: {}", config.network_name);
     info!("Application directory: {}", app_dir.as_display());
     info!("Data directory: {}", db_dir.as_display());
     fs::create_dir_all(db_dir.as_path()).unwrap();
-    let grpc_server_addr = args.rpc_listen.unwrap_or_else(|| "127.0.0.1:16610".to_string()).parse().unwrap();
+    let grpc_server_addr = args.rpc_listen.unwrap_or_else(|| format!("127.0.0.1:{}", default_rpc_port)).parse().unwrap();
+    let wrpc_server_addresses: Vec<(SocketAddr, Encoding)> =
+        [(args.rpc_listen_borsh, Encoding::Borsh), (args.rpc_listen_json, Encoding::SerdeJson)]
+            .into_iter()
+            .filter_map(|(address, encoding)| address.map(|address| (address.parse().unwrap(), encoding)))
+            .collect();
+    let probe_server_addr: Option<SocketAddr> = args.probe_listen.map(|address| address.parse().unwrap());
+    let metrics_server_addr: Option<SocketAddr> = args.metrics_listen.map(|address| address.parse().unwrap());
+
+    // The advertised address is sent to peers in the version message of the handshake
+    let p2p_listen_addresses = if args.listen.is_empty() { vec![ContextualNetAddress::unspecified()] } else { args.listen };
+    let advertised_address = args
+        .external_ip
+        .or_else(|| p2p_listen_addresses.iter().copied().find(|address| address.is_publicly_routable()))
+        .map(|address| address.normalize(default_p2p_port));
+    let p2p_listen_addresses: Vec<_> = p2p_listen_addresses.into_iter().map(|address| address.normalize(default_p2p_port)).collect();
+    for address in p2p_listen_addresses.iter() {
+        info!("P2P listen address: {}", address);
+    }
+    match advertised_address {
+        Some(address) => info!("P2P advertised address: {}", address),
+        None => info!("P2P advertised address: none (no external or publicly routable listen address)"),
+    }
 
     let core = Arc::new(Core::new());
 
     // ---
 
-    let params = DEVNET_PARAMS;
-    let db = Arc::new(DB::open_default(db_dir.to_str().unwrap()).unwrap());
-    let consensus = Arc::new(Consensus::new(db, &params));
+    let mut listen_addresses = vec![("gRPC", grpc_server_addr)];
+    listen_addresses.extend(wrpc_server_addresses.iter().map(|&(address, _)| ("wRPC", address)));
+    listen_addresses.extend(p2p_listen_addresses.iter().map(|&address| ("P2P", address.into())));
+    listen_addresses.extend(probe_server_addr.map(|address| ("probe", address)));
+    listen_addresses.extend(metrics_server_addr.map(|address| ("metrics", address)));
+    let db = match run_self_test(&db_dir, &config, &listen_addresses, args.migration_backup_dir, args.archival) {
+        Ok(db) => db,
+        Err(err) => {
+            error!("Startup self-test failed: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let mut consensus = Consensus::new(db.clone(), &config);
+    if !args.archival {
+        let retention_period_days = args.retention_period_days.unwrap_or_default();
+        consensus = consensus.with_retention_period(DurationMs::from_millis(retention_period_days.saturating_mul(86_400_000)));
+    }
+    let consensus = Arc::new(consensus);
+
+    if args.export_blocks.is_some() || args.import_blocks.is_some() {
+        run_blocks_file_commands(&consensus, args.export_blocks, args.import_blocks);
+        return;
+    }
+    if let Some(source_dir) = args.transfer_blocks_from {
+        run_transfer_blocks(&consensus, &config, &source_dir);
+        return;
+    }
+
     let monitor = Arc::new(ConsensusMonitor::new(consensus.processing_counters().clone()));
+    let perf_monitor = Arc::new(PerfMonitor::new(consensus.clone(), db.clone()));
+
+    let mempool_config = MempoolConfig::default()
+        .with_high_priority_lane(!args.no_local_priority_lane)
+        .with_high_priority_rebroadcast(!args.no_local_rebroadcast);
+    let mining_manager = Arc::new(MiningManager::new(consensus.clone(), mempool_config, DEFAULT_BLOCK_TEMPLATE_CACHE_LIFETIME));
+    let tx_index = if args.tx_index {
+        let tx_index_config = match args.tx_index_retention_days {
+            Some(days) => TxIndexConfig::with_retention_days(days, config.target_time_per_block),
+            None => TxIndexConfig::default(),
+        };
+        Some(Arc::new(TxIndex::new(consensus.clone(), db.clone(), tx_index_config)))
+    } else {
+        None
+    };
+    let utxo_index = if args.utxo_index { Some(Arc::new(UtxoIndex::new(consensus.clone(), db.clone()))) } else { None };
+
+    // Consensus notifications flow through the index processor to the RPC notifier, each stage on its own queue.
+    // The processor is registered before the UTXO index resync starts, so that the diffs notified during the
+    // resync are applied
+    let index_processor = Arc::new(IndexProcessor::new(utxo_index.clone(), tx_index.clone()));
+    let notification_channel = ConsensusNotificationChannel::bounded(RPC_NOTIFICATION_QUEUE_SIZE);
+    index_processor.notification_root().register(
+        &NotificationType::ALL,
+        BackpressurePolicy::DropOldest(RPC_NOTIFICATION_QUEUE_SIZE),
+        Arc::new(ConsensusNotificationForwarder::new(notification_channel.sender())),
+    );
+    consensus.notification_root().register(
+        &NotificationType::ALL,
+        BackpressurePolicy::Block(INDEX_PROCESSOR_QUEUE_SIZE),
+        index_processor,
+    );
+
+    // Devnet and simnet nodes usually run on local networks, hence unroutable addresses are accepted
+    let accept_unroutable = matches!(network, NetworkType::Devnet | NetworkType::Simnet);
+    let ban_manager = Arc::new(std::sync::Mutex::new(
+        BanManager::new(db.clone(), DurationMs::from_millis(args.ban_duration.saturating_mul(1000))).unwrap(),
+    ));
+    let address_manager = Arc::new(Mutex::new(
+        AddressManager::new(db, AddressManagerConfig::new(args.dns_seeders, default_p2p_port, accept_unroutable)).unwrap(),
+    ));
+    let hub = Hub::new();
+    let user_agent = format!("/{}:{}/", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    let mut local_version = LocalVersion::new(Uuid::new_v4(), config.network_name.to_string(), user_agent);
+    local_version.address = advertised_address;
+    if args.archival {
+        local_version.services |= SERVICE_ARCHIVAL;
+    }
+    let drift_alert_config = DriftAlertConfig {
+        warning_threshold: args.drift_alert_threshold,
+        critical_threshold: args.drift_alert_critical_threshold,
+        webhook: args.drift_alert_webhook,
+    };
+    let flow_context = Arc::new(FlowContext::new(
+        consensus.clone(),
+        mining_manager.clone(),
+        hub.clone(),
+        local_version,
+        ban_manager.clone(),
+        address_manager.clone(),
+    ));
+
+    // Drift alerts are suppressed while IBD is running
+    let drift_alert_service =
+        Arc::new(DriftAlertService::new(consensus.clone(), config.network_name, drift_alert_config, flow_context.ibd_running_flag()));
 
-    let notification_channel = ConsensusNotificationChannel::default();
-    let rpc_core_server = Arc::new(RpcCoreServer::new(consensus.clone(), notification_channel.receiver()));
+    let connection_manager_config = ConnectionManagerConfig::new(
+        args.outbound_target,
+        args.connect_peers.into_iter().map(|address| address.normalize(default_p2p_port)).collect(),
+        args.add_peers.into_iter().map(|address| address.normalize(default_p2p_port)).collect(),
+    );
+    let connection_manager = Arc::new(ConnectionManager::new(
+        p2p_listen_addresses,
+        hub.clone(),
+        flow_context.clone(),
+        address_manager.clone(),
+        connection_manager_config,
+    ));
+    let metrics_server = metrics_server_addr.map(|address| {
+        let sources = MetricsSources {
+            consensus: consensus.clone(),
+            mining_manager: mining_manager.clone(),
+            hub: hub.clone(),
+            perf_monitor: perf_monitor.clone(),
+        };
+        Arc::new(MetricsServer::new(address, sources))
+    });
+    let peer_manager = Arc::new(PeerManager::new(connection_manager.clone(), hub, address_manager, ban_manager));
+
+    // The RPC core service rejects submitted blocks while IBD is running
+    let rpc_core_server = Arc::new(RpcCoreServer::new(
+        consensus.clone(),
+        mining_manager,
+        tx_index.clone().map(|tx_index| tx_index as DynTxIndex),
+        utxo_index.clone().map(|utxo_index| utxo_index as DynUtxoIndex),
+        Some(peer_manager as DynPeerManager),
+        Some(perf_monitor.clone() as DynPerfMonitor),
+        flow_context.ibd_running_flag(),
+        notification_channel.receiver(),
+    ));
     let grpc_server = Arc::new(GrpcServer::new(grpc_server_addr, rpc_core_server.service()));
+    let wrpc_servers: Vec<_> = wrpc_server_addresses
+        .into_iter()
+        .map(|(address, encoding)| Arc::new(WrpcServer::new(address, encoding, rpc_core_server.service())))
+        .collect();
 
     // Create an async runtime and register the top-level async services
     let async_runtime = Arc::new(AsyncRuntime::new());
     async_runtime.register(rpc_core_server);
     async_runtime.register(grpc_server);
+    for wrpc_server in wrpc_servers {
+        async_runtime.register(wrpc_server);
+    }
+    async_runtime.register(connection_manager);
+    async_runtime.register(drift_alert_service);
+    if let Some(tx_index) = tx_index {
+        async_runtime.register(Arc::new(TxIndexService::new(tx_index)));
+    }
+    if let Some(utxo_index) = utxo_index {
+        async_runtime.register(Arc::new(UtxoIndexService::new(utxo_index)));
+    }
+    if let Some(address) = probe_server_addr {
+        async_runtime.register(Arc::new(ProbeServer::new(address)));
+    }
+    if let Some(metrics_server) = metrics_server {
+        async_runtime.register(metrics_server);
+    }
 
     // Bind the keyboard signal to the core
     Arc::new(Signals::new(&core)).init();
@@ -104,9 +472,52 @@ pub fn main() {
     // Consensus must start first in order to init genesis in stores
     core.bind(consensus);
     core.bind(monitor);
+    core.bind(perf_monitor);
     core.bind(async_runtime);
 
     core.run();
 
     trace!("Kaspad is finished...");
 }
+
+fn run_blocks_file_commands(consensus: &Consensus, export_path: Option<PathBuf>, import_path: Option<PathBuf>) {
+    let wait_handles = consensus.init();
+    if let Some(path) = export_path {
+        match blocks_file::export_blocks(consensus, &path) {
+            Ok(count) => info!("Exported {} blocks to {}", count, path.as_display()),
+            Err(err) => error!("Failed exporting blocks to {}: {}", path.as_display(), err),
+        }
+    }
+    if let Some(path) = import_path {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        match runtime.block_on(blocks_file::import_blocks(consensus, &path)) {
+            Ok(count) => info!("Imported {} blocks from {}", count, path.as_display()),
+            Err(err) => error!("Failed importing blocks from {}: {}", path.as_display(), err),
+        }
+    }
+    consensus.shutdown(wait_handles);
+}
+
+fn run_transfer_blocks(consensus: &Consensus, params: &Params, source_dir: &Path) {
+    info!("Transferring blocks from {}", source_dir.as_display());
+    let source = Consensus::new(Arc::new(DB::open_default(source_dir.to_str().unwrap()).unwrap()), params);
+    let source_wait_handles = source.init();
+    let wait_handles = consensus.init();
+
+    let mut next_report_percent = 0;
+    let report_progress = |progress: TransferProgress| {
+        let percent = progress.processed * 100 / progress.total;
+        if percent >= next_report_percent {
+            info!("Transferred {}/{} blocks ({}%, {} already present)", progress.processed, progress.total, percent, progress.skipped);
+            next_report_percent = percent + 10;
+        }
+    };
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    match runtime.block_on(transfer_blocks(&source, consensus, report_progress)) {
+        Ok(progress) => info!("Transferred {} blocks ({} were already present)", progress.processed, progress.skipped),
+        Err(err) => error!("Failed transferring blocks from {}: {}", source_dir.as_display(), err),
+    }
+
+    source.shutdown(source_wait_handles);
+    consensus.shutdown(wait_handles);
+}
@@ -0,0 +1,289 @@
+use crate::perf_monitor::PerfMonitor;
+use consensus::{consensus::Consensus, model::stores::database::prelude::CACHE_COUNTERS};
+use consensus_core::api::ConsensusApi;
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::DuplexTrigger;
+use mining::manager::MiningManager;
+use p2p::Hub;
+use std::{
+    fmt::{Display, Write},
+    net::SocketAddr,
+    sync::{atomic::Ordering, Arc},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+const METRICS_SERVER: &str = "metrics-server";
+
+/// The node components sampled by the metrics server
+pub struct MetricsSources {
+    pub consensus: Arc<Consensus>,
+    pub mining_manager: Arc<MiningManager>,
+    pub hub: Hub,
+    pub perf_monitor: Arc<PerfMonitor>,
+}
+
+impl MetricsSources {
+    /// Samples all metrics and renders them in the Prometheus text exposition format
+    fn render(&self) -> String {
+        let counters = self.consensus.processing_counters().snapshot();
+        let cache_hits = CACHE_COUNTERS.hits.load(Ordering::Relaxed);
+        let cache_misses = CACHE_COUNTERS.misses.load(Ordering::Relaxed);
+        let sig_cache = self.consensus.sig_cache();
+        let sig_cache_hits = sig_cache.counters().hits.load(Ordering::Relaxed);
+        let sig_cache_misses = sig_cache.counters().misses.load(Ordering::Relaxed);
+        let stages = [
+            ("header", counters.header_counts, counters.header_micros),
+            ("body", counters.body_counts, counters.body_micros),
+            ("virtual", counters.virtual_counts, counters.virtual_micros),
+        ];
+        let queue_lengths = self.consensus.pipeline_queue_lengths();
+        let queue_capacities = self.consensus.pipeline_queue_capacities();
+        let queues = [
+            ("header", queue_lengths.header, queue_capacities.header, counters.header_backpressure_counts),
+            ("body", queue_lengths.body, queue_capacities.body, counters.body_backpressure_counts),
+            ("virtual", queue_lengths.virtual_state, queue_capacities.virtual_state, counters.virtual_backpressure_counts),
+        ];
+
+        let mut out = String::new();
+        family(&mut out, "kaspa_blocks_submitted_total", "counter", "Blocks submitted to consensus");
+        sample(&mut out, "kaspa_blocks_submitted_total", None, counters.blocks_submitted);
+        family(
+            &mut out,
+            "kaspa_blocks_processed_total",
+            "counter",
+            "Blocks processed per pipeline stage, virtual processing counting resolutions of the virtual state",
+        );
+        for (stage, count, _) in stages {
+            sample(&mut out, "kaspa_blocks_processed_total", Some(("stage", stage)), count);
+        }
+        family(&mut out, "kaspa_block_dependencies_total", "counter", "Direct parents of the processed headers");
+        sample(&mut out, "kaspa_block_dependencies_total", None, counters.dep_counts);
+        family(&mut out, "kaspa_processing_seconds_total", "counter", "Cumulative processing time per pipeline stage");
+        for (stage, _, micros) in stages {
+            sample(&mut out, "kaspa_processing_seconds_total", Some(("stage", stage)), micros as f64 / 1_000_000.0);
+        }
+        family(&mut out, "kaspa_pipeline_queue_length", "gauge", "Blocks queued per pipeline stage");
+        for (stage, length, _, _) in queues {
+            sample(&mut out, "kaspa_pipeline_queue_length", Some(("stage", stage)), length);
+        }
+        family(&mut out, "kaspa_pipeline_queue_capacity", "gauge", "Blocks queued at most per pipeline stage");
+        for (stage, _, capacity, _) in queues {
+            sample(&mut out, "kaspa_pipeline_queue_capacity", Some(("stage", stage)), capacity);
+        }
+        family(
+            &mut out,
+            "kaspa_pipeline_backpressure_total",
+            "counter",
+            "Blocks sent to a pipeline stage while its queue was full, per stage",
+        );
+        for (stage, _, _, backpressure) in queues {
+            sample(&mut out, "kaspa_pipeline_backpressure_total", Some(("stage", stage)), backpressure);
+        }
+        family(&mut out, "kaspa_db_cache_lookups_total", "counter", "Reads of the cached DB stores, by cache result");
+        sample(&mut out, "kaspa_db_cache_lookups_total", Some(("result", "hit")), cache_hits);
+        sample(&mut out, "kaspa_db_cache_lookups_total", Some(("result", "miss")), cache_misses);
+        family(&mut out, "kaspa_sig_cache_lookups_total", "counter", "Lookups of verified signatures, by cache result");
+        sample(&mut out, "kaspa_sig_cache_lookups_total", Some(("result", "hit")), sig_cache_hits);
+        sample(&mut out, "kaspa_sig_cache_lookups_total", Some(("result", "miss")), sig_cache_misses);
+        family(&mut out, "kaspa_sig_cache_entries", "gauge", "Verified signatures held by the signature cache");
+        sample(&mut out, "kaspa_sig_cache_entries", None, sig_cache.len());
+        family(&mut out, "kaspa_mempool_transactions", "gauge", "Transactions in the mempool, orphans excluded");
+        sample(&mut out, "kaspa_mempool_transactions", None, self.mining_manager.mempool().len());
+        family(&mut out, "kaspa_peers", "gauge", "Connected peers");
+        sample(&mut out, "kaspa_peers", None, self.hub.active_peers_len());
+        family(&mut out, "kaspa_virtual_daa_score", "gauge", "DAA score of the virtual block");
+        sample(&mut out, "kaspa_virtual_daa_score", None, self.consensus.clone().get_virtual_daa_score());
+        if let Some(perf) = self.perf_monitor.last_sample() {
+            let gauges = [
+                ("kaspa_process_cpu_usage_percent", "CPU time used per unit of time, in percents of a single core", perf.cpu_usage),
+                ("kaspa_process_resident_memory_bytes", "Resident set size of the node process", perf.resident_set_size as f64),
+                ("kaspa_process_disk_read_bytes_per_second", "Bytes read from storage per second", perf.disk_read_rate),
+                ("kaspa_process_disk_write_bytes_per_second", "Bytes written to storage per second", perf.disk_write_rate),
+                ("kaspa_db_sst_files_bytes", "Total size of the database SST files", perf.db_sst_files_size as f64),
+                ("kaspa_db_memtables_bytes", "Size of the database memtables", perf.db_memtables_size as f64),
+                ("kaspa_db_pending_compaction_bytes", "Estimated bytes pending compaction", perf.db_pending_compaction_bytes as f64),
+                ("kaspa_db_running_compactions", "Running database compactions", perf.db_running_compactions as f64),
+            ];
+            for (name, help, value) in gauges {
+                family(&mut out, name, "gauge", help);
+                sample(&mut out, name, None, value);
+            }
+        }
+        out
+    }
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = write!(out, "# HELP {0} {1}\n# TYPE {0} {2}\n", name, help, kind);
+}
+
+fn sample(out: &mut String, name: &str, label: Option<(&str, &str)>, value: impl Display) {
+    let _ = match label {
+        Some((key, label_value)) => writeln!(out, "{0}{{{1}=\"{2}\"}} {3}", name, key, label_value, value),
+        None => writeln!(out, "{0} {1}", name, value),
+    };
+}
+
+/// Serves the node metrics on `/metrics`, in the Prometheus text exposition format.
+///
+/// Any other path answers `404 Not Found`.
+pub struct MetricsServer {
+    address: SocketAddr,
+    sources: Arc<MetricsSources>,
+    shutdown: DuplexTrigger,
+}
+
+impl MetricsServer {
+    pub fn new(address: SocketAddr, sources: MetricsSources) -> Self {
+        Self { address, sources: Arc::new(sources), shutdown: DuplexTrigger::default() }
+    }
+}
+
+impl AsyncService for MetricsServer {
+    fn ident(self: Arc<Self>) -> &'static str {
+        METRICS_SERVER
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", METRICS_SERVER);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            match TcpListener::bind(self.address).await {
+                Ok(listener) => {
+                    trace!("metrics server listening on: {}", self.address);
+                    tokio::pin!(shutdown_signal);
+                    loop {
+                        tokio::select! {
+                            biased;
+                            _ = &mut shutdown_signal => break,
+                            result = listener.accept() => match result {
+                                Ok((stream, _)) => {
+                                    tokio::spawn(respond(stream, self.sources.clone()));
+                                }
+                                Err(err) => trace!("metrics server failed accepting a connection: {0}", err),
+                            },
+                        }
+                    }
+                }
+                Err(err) => warn!("Metrics server failed binding {0}: {1}", self.address, err),
+            }
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", METRICS_SERVER);
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} stopping", METRICS_SERVER);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        Box::pin(async move {
+            shutdown_executed_signal.await;
+            trace!("{} exiting", METRICS_SERVER);
+        })
+    }
+}
+
+/// Answers a single metrics request and closes the connection. Only the request line is read.
+async fn respond(mut stream: TcpStream, sources: Arc<MetricsSources>) {
+    let mut buffer = [0u8; 1024];
+    let len = match stream.read(&mut buffer).await {
+        Ok(len) => len,
+        Err(_) => return,
+    };
+    let request = String::from_utf8_lossy(&buffer[..len]);
+    let path = request.lines().next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or_default();
+    let (status, content_type, body) = match path {
+        "/metrics" => ("200 OK", "text/plain; version=0.0.4", sources.render()),
+        _ => ("404 Not Found", "text/plain", "not found".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {0}\r\nContent-Type: {1}\r\nContent-Length: {2}\r\nConnection: close\r\n\r\n{3}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::create_memory_db;
+    use consensus_core::config::params::MAINNET_PARAMS;
+    use mining::mempool::Config as MempoolConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn test_exposition_format() {
+        let mut out = String::new();
+        family(&mut out, "kaspa_peers", "gauge", "Connected peers");
+        sample(&mut out, "kaspa_peers", None, 3);
+        family(&mut out, "kaspa_processing_seconds_total", "counter", "Cumulative processing time per pipeline stage");
+        sample(&mut out, "kaspa_processing_seconds_total", Some(("stage", "header")), 1.5);
+        sample(&mut out, "kaspa_processing_seconds_total", Some(("stage", "body")), 0.0);
+        assert_eq!(
+            out,
+            "# HELP kaspa_peers Connected peers\n\
+             # TYPE kaspa_peers gauge\n\
+             kaspa_peers 3\n\
+             # HELP kaspa_processing_seconds_total Cumulative processing time per pipeline stage\n\
+             # TYPE kaspa_processing_seconds_total counter\n\
+             kaspa_processing_seconds_total{stage=\"header\"} 1.5\n\
+             kaspa_processing_seconds_total{stage=\"body\"} 0\n"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let db = create_memory_db();
+        let consensus = Arc::new(Consensus::new(db.clone(), &MAINNET_PARAMS));
+        let wait_handles = consensus.init();
+        let sources = MetricsSources {
+            consensus: consensus.clone(),
+            mining_manager: Arc::new(MiningManager::new(consensus.clone(), MempoolConfig::default(), Duration::ZERO)),
+            hub: Hub::new(),
+            perf_monitor: Arc::new(PerfMonitor::new(consensus.clone(), db)),
+        };
+        let out = sources.render();
+
+        // Each family is described by a HELP and a TYPE line, followed by its samples
+        let mut current_family = None;
+        for line in out.lines() {
+            if let Some(help) = line.strip_prefix("# HELP ") {
+                current_family = Some(help.split(' ').next().unwrap());
+            } else if let Some(kind) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = kind.split_once(' ').unwrap();
+                assert_eq!(Some(name), current_family);
+                assert!(kind == "counter" || kind == "gauge", "{0}", line);
+            } else {
+                let (series, value) = line.rsplit_once(' ').unwrap();
+                assert_eq!(series.split('{').next(), current_family, "{0}", line);
+                assert!(value.parse::<f64>().is_ok(), "{0}", line);
+            }
+        }
+        assert!(out.contains("kaspa_blocks_processed_total{stage=\"virtual\"} "));
+        assert!(out.contains("\nkaspa_mempool_transactions 0\n"));
+        assert!(out.contains("\nkaspa_peers 0\n"));
+        assert!(out.contains(&format!("\nkaspa_virtual_daa_score {0}\n", consensus.clone().get_virtual_daa_score())));
+        // Resource usage is only exposed once sampled
+        assert!(!out.contains("kaspa_process_cpu_usage_percent"));
+
+        consensus.shutdown(wait_handles);
+    }
+}
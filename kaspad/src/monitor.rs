@@ -1,5 +1,10 @@
 use consensus::pipeline::ProcessingCounters;
-use kaspa_core::{core::Core, info, service::Service, trace};
+use kaspa_core::{
+    core::Core,
+    info,
+    service::{RestartPolicy, Service},
+    trace,
+};
 use num_format::{Locale, ToFormattedString};
 use std::{
     sync::{
@@ -62,10 +67,16 @@ impl Service for ConsensusMonitor {
     }
 
     fn start(self: Arc<ConsensusMonitor>, _core: Arc<Core>) -> Vec<JoinHandle<()>> {
+        self.terminate.store(false, Ordering::SeqCst);
         vec![spawn(move || self.worker())]
     }
 
     fn stop(self: Arc<ConsensusMonitor>) {
         self.terminate.store(true, Ordering::SeqCst);
     }
+
+    /// The monitor only logs, so the node keeps running if it fails
+    fn restart_policy(self: Arc<ConsensusMonitor>) -> Option<RestartPolicy> {
+        Some(RestartPolicy::new(3, Duration::from_secs(1)))
+    }
 }
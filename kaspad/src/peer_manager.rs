@@ -0,0 +1,84 @@
+use addressmanager::{AddressManager, BanManager};
+use async_trait::async_trait;
+use connectionmanager::ConnectionManager;
+use consensus_core::time::{DurationMs, TimestampMs};
+use kaspa_core::info;
+use p2p::Hub;
+use parking_lot::Mutex;
+use rpc_core::{server::peers::PeerManagerApi, RpcError, RpcIpAddress, RpcPeerAddress, RpcPeerInfo, RpcResult};
+use std::sync::Arc;
+
+/// Bridges the RPC peer management to the P2P connection, address and ban managers
+pub struct PeerManager {
+    connection_manager: Arc<ConnectionManager>,
+    hub: Hub,
+    address_manager: Arc<Mutex<AddressManager>>,
+    ban_manager: Arc<std::sync::Mutex<BanManager>>,
+}
+
+impl PeerManager {
+    pub fn new(
+        connection_manager: Arc<ConnectionManager>,
+        hub: Hub,
+        address_manager: Arc<Mutex<AddressManager>>,
+        ban_manager: Arc<std::sync::Mutex<BanManager>>,
+    ) -> Self {
+        Self { connection_manager, hub, address_manager, ban_manager }
+    }
+}
+
+#[async_trait]
+impl PeerManagerApi for PeerManager {
+    fn connected_peers(&self) -> Vec<RpcPeerInfo> {
+        let now = TimestampMs::now();
+        self.connection_manager
+            .peer_infos()
+            .into_iter()
+            .map(|info| RpcPeerInfo {
+                id: info.id.to_string(),
+                address: info.address,
+                is_outbound: info.is_outbound,
+                time_offset: info.time_offset,
+                user_agent: info.user_agent,
+                advertised_protocol_version: info.advertised_protocol_version,
+                time_connected: now.saturating_sub(DurationMs::from_millis(info.time_connected.as_millis() as u64)),
+            })
+            .collect()
+    }
+
+    fn known_addresses(&self) -> Vec<RpcPeerAddress> {
+        self.address_manager.lock().addresses()
+    }
+
+    fn banned_ips(&self) -> Vec<RpcIpAddress> {
+        self.ban_manager.lock().unwrap().banned_ips().into_iter().map(|entry| entry.ip.into()).collect()
+    }
+
+    fn add_peer(&self, address: RpcPeerAddress, is_permanent: bool) {
+        info!("Adding peer {} (permanent: {})", address, is_permanent);
+        self.connection_manager.add_peer(address, is_permanent);
+    }
+
+    async fn ban(&self, ip: RpcIpAddress) -> RpcResult<()> {
+        let banned_peers: Vec<_> = {
+            let mut ban_manager = self.ban_manager.lock().unwrap();
+            ban_manager.ban(*ip).map_err(|err| RpcError::General(format!("Failed storing the ban of {0}: {1}", ip, err)))?;
+            self.hub.active_peers().into_iter().filter(|router| ban_manager.is_banned(router.net_address().ip())).collect()
+        };
+        info!("Banned {}, disconnecting {} peers", ip, banned_peers.len());
+        for router in banned_peers {
+            router.close().await;
+        }
+        Ok(())
+    }
+
+    fn unban(&self, ip: RpcIpAddress) -> RpcResult<()> {
+        self.ban_manager
+            .lock()
+            .unwrap()
+            .unban(*ip)
+            .map_err(|err| RpcError::General(format!("Failed removing the ban of {0}: {1}", ip, err)))?;
+        info!("Unbanned {}", ip);
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use consensus::{consensus::Consensus, model::stores::DB, pipeline::PipelineQueueLengths};
+use consensus_core::time::TimestampMs;
+use kaspa_core::{
+    core::Core,
+    info,
+    service::{RestartPolicy, Service},
+    trace,
+};
+use parking_lot::RwLock;
+use rpc_core::{server::perf::PerfMonitorApi, RpcPerfMetrics};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, spawn, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// The interval at which resource usage is sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Every how many samples one is logged
+const LOG_EVERY_SAMPLES: u64 = 6;
+
+/// Cumulative resource usage of the node process, zeroed where unavailable
+#[derive(Clone, Copy, Default)]
+struct ProcessStats {
+    cpu_time: Duration,
+    resident_set_size: u64,
+    disk_read_bytes: u64,
+    disk_write_bytes: u64,
+}
+
+impl ProcessStats {
+    #[cfg(target_os = "linux")]
+    fn read() -> Self {
+        use std::fs::read_to_string;
+
+        // The unit of the CPU times of /proc/<pid>/stat, fixed to 100 Hz by the Linux ABI
+        const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+        let mut stats = Self::default();
+        if let Ok(stat) = read_to_string("/proc/self/stat") {
+            // The process name may contain spaces, hence fields are counted from its closing parenthesis, which is
+            // followed by the 3rd field. User and system CPU times are the 14th and 15th fields
+            let fields: Vec<u64> = stat
+                .rsplit_once(')')
+                .map_or(vec![], |(_, rest)| rest.split_whitespace().skip(11).take(2).filter_map(|field| field.parse().ok()).collect());
+            if let [user_ticks, system_ticks] = fields[..] {
+                stats.cpu_time = Duration::from_millis((user_ticks + system_ticks) * 1000 / CLOCK_TICKS_PER_SEC);
+            }
+        }
+        if let Ok(status) = read_to_string("/proc/self/status") {
+            stats.resident_set_size = proc_field(&status, "VmRSS").unwrap_or_default() * 1024;
+        }
+        // Requires the permission to trace the process, which some containers restrict
+        if let Ok(io) = read_to_string("/proc/self/io") {
+            stats.disk_read_bytes = proc_field(&io, "read_bytes").unwrap_or_default();
+            stats.disk_write_bytes = proc_field(&io, "write_bytes").unwrap_or_default();
+        }
+        stats
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read() -> Self {
+        Self::default()
+    }
+}
+
+/// Parses the first number of the `key: value` line of a /proc file
+#[cfg(target_os = "linux")]
+fn proc_field(content: &str, key: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// A sample of the resource usage of the node process and its database
+#[derive(Clone, Copy)]
+pub struct PerfSample {
+    pub time: TimestampMs,
+    /// The CPU time used per unit of time, in percents of a single core
+    pub cpu_usage: f64,
+    pub resident_set_size: u64,
+    /// Bytes per second read from storage
+    pub disk_read_rate: f64,
+    /// Bytes per second written to storage
+    pub disk_write_rate: f64,
+    pub db_sst_files_size: u64,
+    pub db_memtables_size: u64,
+    pub db_pending_compaction_bytes: u64,
+    pub db_running_compactions: u64,
+    pub queue_lengths: PipelineQueueLengths,
+}
+
+impl From<&PerfSample> for RpcPerfMetrics {
+    fn from(item: &PerfSample) -> Self {
+        Self {
+            sample_time: item.time,
+            cpu_usage: item.cpu_usage,
+            resident_set_size: item.resident_set_size,
+            disk_read_rate: item.disk_read_rate,
+            disk_write_rate: item.disk_write_rate,
+            db_sst_files_size: item.db_sst_files_size,
+            db_memtables_size: item.db_memtables_size,
+            db_pending_compaction_bytes: item.db_pending_compaction_bytes,
+            db_running_compactions: item.db_running_compactions,
+            header_queue_length: item.queue_lengths.header as u64,
+            body_queue_length: item.queue_lengths.body as u64,
+            virtual_queue_length: item.queue_lengths.virtual_state as u64,
+        }
+    }
+}
+
+/// Periodically samples the CPU, memory and disk usage of the process, the RocksDB storage and compaction
+/// properties and the queue lengths of the consensus pipeline. Samples are logged, served by the metrics
+/// endpoint and returned by the `GetMetrics` RPC.
+pub struct PerfMonitor {
+    terminate: AtomicBool,
+    consensus: Arc<Consensus>,
+    db: Arc<DB>,
+    last_sample: RwLock<Option<PerfSample>>,
+}
+
+impl PerfMonitor {
+    pub fn new(consensus: Arc<Consensus>, db: Arc<DB>) -> Self {
+        Self { terminate: AtomicBool::new(false), consensus, db, last_sample: RwLock::new(None) }
+    }
+
+    /// Returns the latest sample, if any was taken yet
+    pub fn last_sample(&self) -> Option<PerfSample> {
+        *self.last_sample.read()
+    }
+
+    fn db_property(&self, name: &str) -> u64 {
+        self.db.property_int_value(name).ok().flatten().unwrap_or_default()
+    }
+
+    fn worker(self: &Arc<PerfMonitor>) {
+        let mut last_stats = ProcessStats::read();
+        let mut last_instant = Instant::now();
+        let mut samples = 0u64;
+        loop {
+            thread::sleep(SAMPLE_INTERVAL);
+
+            if self.terminate.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let stats = ProcessStats::read();
+            let elapsed = last_instant.elapsed().as_secs_f64();
+            last_instant = Instant::now();
+            let sample = PerfSample {
+                time: TimestampMs::now(),
+                cpu_usage: stats.cpu_time.saturating_sub(last_stats.cpu_time).as_secs_f64() * 100.0 / elapsed,
+                resident_set_size: stats.resident_set_size,
+                disk_read_rate: stats.disk_read_bytes.saturating_sub(last_stats.disk_read_bytes) as f64 / elapsed,
+                disk_write_rate: stats.disk_write_bytes.saturating_sub(last_stats.disk_write_bytes) as f64 / elapsed,
+                db_sst_files_size: self.db_property("rocksdb.total-sst-files-size"),
+                db_memtables_size: self.db_property("rocksdb.cur-size-all-mem-tables"),
+                db_pending_compaction_bytes: self.db_property("rocksdb.estimate-pending-compaction-bytes"),
+                db_running_compactions: self.db_property("rocksdb.num-running-compactions"),
+                queue_lengths: self.consensus.pipeline_queue_lengths(),
+            };
+            last_stats = stats;
+
+            if samples % LOG_EVERY_SAMPLES == 0 {
+                info!(
+                    "perf: cpu: {:.1}%, rss: {}, disk read: {}/s, disk write: {}/s, db sst files: {}, db memtables: {}, pending compaction: {}, running compactions: {}, queued header/body/virtual: {}/{}/{}",
+                    sample.cpu_usage,
+                    format_bytes(sample.resident_set_size as f64),
+                    format_bytes(sample.disk_read_rate),
+                    format_bytes(sample.disk_write_rate),
+                    format_bytes(sample.db_sst_files_size as f64),
+                    format_bytes(sample.db_memtables_size as f64),
+                    format_bytes(sample.db_pending_compaction_bytes as f64),
+                    sample.db_running_compactions,
+                    sample.queue_lengths.header,
+                    sample.queue_lengths.body,
+                    sample.queue_lengths.virtual_state,
+                );
+            }
+            samples += 1;
+            *self.last_sample.write() = Some(sample);
+        }
+
+        trace!("perf monitor thread exiting");
+    }
+}
+
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+impl PerfMonitorApi for PerfMonitor {
+    fn perf_metrics(&self) -> Option<RpcPerfMetrics> {
+        self.last_sample().as_ref().map(RpcPerfMetrics::from)
+    }
+}
+
+impl Service for PerfMonitor {
+    fn ident(self: Arc<PerfMonitor>) -> &'static str {
+        "perf-monitor"
+    }
+
+    fn start(self: Arc<PerfMonitor>, _core: Arc<Core>) -> Vec<JoinHandle<()>> {
+        self.terminate.store(false, Ordering::SeqCst);
+        vec![spawn(move || self.worker())]
+    }
+
+    fn stop(self: Arc<PerfMonitor>) {
+        self.terminate.store(true, Ordering::SeqCst);
+    }
+
+    /// The monitor only samples, so the node keeps running if it fails
+    fn restart_policy(self: Arc<PerfMonitor>) -> Option<RestartPolicy> {
+        Some(RestartPolicy::new(3, Duration::from_secs(1)))
+    }
+}
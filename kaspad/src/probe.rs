@@ -0,0 +1,168 @@
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::DuplexTrigger;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+const PROBE_SERVER: &str = "probe-server";
+
+/// Serves the HTTP probes of container orchestrators:
+/// - `/healthy` answers `200 OK` for as long as the node runs
+/// - `/ready` answers `200 OK` once the node services are started, and `503 Service Unavailable` once they are shutting down
+///
+/// Any other path answers `404 Not Found`.
+pub struct ProbeServer {
+    address: SocketAddr,
+    ready: Arc<AtomicBool>,
+    shutdown: DuplexTrigger,
+}
+
+impl ProbeServer {
+    pub fn new(address: SocketAddr) -> Self {
+        Self { address, ready: Arc::new(AtomicBool::new(false)), shutdown: DuplexTrigger::default() }
+    }
+}
+
+impl AsyncService for ProbeServer {
+    fn ident(self: Arc<Self>) -> &'static str {
+        PROBE_SERVER
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", PROBE_SERVER);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            match TcpListener::bind(self.address).await {
+                Ok(listener) => {
+                    trace!("probe server listening on: {}", self.address);
+                    // Services are started together, all of them having been checked by the startup self-test
+                    self.ready.store(true, Ordering::SeqCst);
+                    tokio::pin!(shutdown_signal);
+                    loop {
+                        tokio::select! {
+                            biased;
+                            _ = &mut shutdown_signal => break,
+                            result = listener.accept() => match result {
+                                Ok((stream, _)) => {
+                                    tokio::spawn(respond(stream, self.ready.load(Ordering::SeqCst)));
+                                }
+                                Err(err) => trace!("probe server failed accepting a connection: {0}", err),
+                            },
+                        }
+                    }
+                }
+                Err(err) => warn!("Probe server failed binding {0}: {1}", self.address, err),
+            }
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", PROBE_SERVER);
+        self.ready.store(false, Ordering::SeqCst);
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} stopping", PROBE_SERVER);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        Box::pin(async move {
+            shutdown_executed_signal.await;
+            trace!("{} exiting", PROBE_SERVER);
+        })
+    }
+}
+
+/// Answers a single probe request and closes the connection. Only the request line is read.
+async fn respond(mut stream: TcpStream, ready: bool) {
+    let mut buffer = [0u8; 1024];
+    let len = match stream.read(&mut buffer).await {
+        Ok(len) => len,
+        Err(_) => return,
+    };
+    let request = String::from_utf8_lossy(&buffer[..len]);
+    let path = request.lines().next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or_default();
+    let (status, body) = match path {
+        "/healthy" => ("200 OK", "healthy"),
+        "/ready" if ready => ("200 OK", "ready"),
+        "/ready" => ("503 Service Unavailable", "not ready"),
+        _ => ("404 Not Found", "not found"),
+    };
+    let response = format!(
+        "HTTP/1.1 {0}\r\nContent-Type: text/plain\r\nContent-Length: {1}\r\nConnection: close\r\n\r\n{2}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Sends a probe request over `stream` and returns the status line of the response
+    async fn probe(mut stream: TcpStream, path: &str) -> String {
+        stream.write_all(format!("GET {0} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_probe_responses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        for (path, ready, expected) in [
+            ("/healthy", false, "HTTP/1.1 200 OK"),
+            ("/ready", false, "HTTP/1.1 503 Service Unavailable"),
+            ("/healthy", true, "HTTP/1.1 200 OK"),
+            ("/ready", true, "HTTP/1.1 200 OK"),
+            ("/metrics", true, "HTTP/1.1 404 Not Found"),
+        ] {
+            let client = TcpStream::connect(address).await.unwrap();
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(respond(stream, ready));
+            assert_eq!(probe(client, path).await, expected, "{0} probe with readiness {1}", path, ready);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_server() {
+        // A port known to be free, released right away for the server to bind
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let server = Arc::new(ProbeServer::new(address));
+        assert!(!server.ready.load(Ordering::SeqCst));
+
+        let running = tokio::spawn(server.clone().start());
+        while !server.ready.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(probe(TcpStream::connect(address).await.unwrap(), "/ready").await, "HTTP/1.1 200 OK");
+        assert_eq!(probe(TcpStream::connect(address).await.unwrap(), "/healthy").await, "HTTP/1.1 200 OK");
+
+        // The node is no longer ready once shutting down
+        server.clone().signal_exit();
+        assert!(!server.ready.load(Ordering::SeqCst));
+        server.clone().stop().await;
+        running.await.unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+//! Startup self-test, verifying the environment of the node before any service is started, so that a
+//! misconfigured node fails at once with a clear error rather than later on.
+
+use consensus::consensus::integrity::{IntegrityChecker, IntegrityError};
+use consensus::migrations::{MigrationError, MigrationRunner};
+use consensus::model::stores::{
+    database::prelude::CachePolicy,
+    errors::{StoreError, StoreResultExtensions},
+    headers::{DbHeadersStore, HeaderStoreReader},
+    headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
+    metadata::{DbMetadataStore, MetadataStore, MetadataStoreReader},
+    past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStoreReader},
+    retention_root::{DbRetentionRootStore, RetentionRootStoreReader},
+    DB,
+};
+use consensus_core::config::{genesis::GenesisError, params::Params};
+use consensus_core::time::TimestampMs;
+use hashes::Hash;
+use kaspa_core::{info, warn};
+use std::{
+    io,
+    net::{SocketAddr, TcpListener},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SelfTestError {
+    #[error("cannot open the database at {0}: {1}")]
+    DbNotOpenable(String, String),
+
+    #[error("invalid {0} genesis: {1}")]
+    InvalidGenesis(&'static str, GenesisError),
+
+    #[error("the database was created for genesis {0} while the {1} params have genesis {2}")]
+    GenesisMismatch(Hash, &'static str, Hash),
+
+    #[error("the system clock ({0}) is earlier than the genesis timestamp ({1})")]
+    ClockBeforeGenesis(TimestampMs, TimestampMs),
+
+    #[error("the system clock ({0}) is earlier than the timestamp of the stored headers selected tip ({1})")]
+    ClockBeforeSelectedTip(TimestampMs, TimestampMs),
+
+    #[error("cannot bind the {0} address {1}: {2}")]
+    AddressNotBindable(&'static str, SocketAddr, io::Error),
+
+    #[error("database migration failed: {0}")]
+    Migration(#[from] MigrationError),
+
+    #[error("the database was pruned by a non-archival node, resync into an empty data directory to run an archival node")]
+    NotArchival,
+
+    #[error("database integrity check failed: {0}")]
+    Integrity(#[from] IntegrityError),
+
+    #[error("store error: {0}")]
+    Store(#[from] StoreError),
+}
+
+pub type SelfTestResult<T> = Result<T, SelfTestError>;
+
+/// Runs all checks, returning the opened DB once they pass. `listen_addresses` are the addresses the node
+/// services are going to listen on, each labeled by its service. The DB schema is migrated before the checks
+/// read the DB, backing the DB up into `migration_backup_dir` first if set.
+pub fn run_self_test(
+    db_dir: &Path,
+    params: &Params,
+    listen_addresses: &[(&'static str, SocketAddr)],
+    migration_backup_dir: Option<PathBuf>,
+    archival: bool,
+) -> SelfTestResult<Arc<DB>> {
+    let db = open_db(db_dir)?;
+    migrate_db(&db, migration_backup_dir)?;
+    check_genesis(&db, params)?;
+    check_integrity(&db, params)?;
+    check_archival(&db, archival)?;
+    check_clock(&db, params, TimestampMs::now())?;
+    check_bindable(listen_addresses)?;
+    info!("Startup self-test passed");
+    Ok(db)
+}
+
+/// Opens the DB, failing if it is corrupted or already held by another process
+pub fn open_db(db_dir: &Path) -> SelfTestResult<Arc<DB>> {
+    let path = db_dir.to_str().unwrap();
+    DB::open_default(path).map(Arc::new).map_err(|err| SelfTestError::DbNotOpenable(path.to_string(), err.to_string()))
+}
+
+/// Runs the pending migrations of the DB schema, failing if the DB was written by a newer node version
+pub fn migrate_db(db: &Arc<DB>, backup_dir: Option<PathBuf>) -> SelfTestResult<()> {
+    let runner = MigrationRunner::new(db.clone());
+    let runner = match backup_dir {
+        Some(backup_dir) => runner.with_backup(backup_dir),
+        None => runner,
+    };
+    runner.run()?;
+    Ok(())
+}
+
+/// Checks that the configured genesis hash matches the genesis header, and that a previously initialized DB was
+/// created with that genesis. A fresh DB passes the latter.
+pub fn check_genesis(db: &Arc<DB>, params: &Params) -> SelfTestResult<()> {
+    params.genesis.verify().map_err(|err| SelfTestError::InvalidGenesis(params.network_name, err))?;
+    // The first past pruning point is always the genesis the DB was initialized with
+    match DbPastPruningPointsStore::new(db.clone(), CachePolicy::Count(0)).get(0).unwrap_option() {
+        Some(genesis_hash) if genesis_hash != params.genesis.hash => {
+            Err(SelfTestError::GenesisMismatch(genesis_hash, params.network_name, params.genesis.hash))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the cross-consistency of the stores, repairing the DB if an interruption left it partially written
+pub fn check_integrity(db: &Arc<DB>, params: &Params) -> SelfTestResult<()> {
+    let report = IntegrityChecker::new(db.clone(), params).run()?;
+    for repair in report.repairs.iter() {
+        warn!("Database integrity check: {}", repair);
+    }
+    info!("Database integrity check passed ({} blocks checked)", report.checked_blocks);
+    Ok(())
+}
+
+/// Records whether the node is archival, failing if an archival node is requested over a DB whose block data was
+/// already garbage collected while not archival
+pub fn check_archival(db: &Arc<DB>, archival: bool) -> SelfTestResult<()> {
+    let mut metadata_store = DbMetadataStore::new(db.clone());
+    let was_archival = metadata_store.get_archival().unwrap_option().unwrap_or_default();
+    if archival && !was_archival {
+        if DbRetentionRootStore::new(db.clone()).get().unwrap_option().is_some() {
+            return Err(SelfTestError::NotArchival);
+        }
+        info!("Archival mode enabled, the data of all blocks is kept");
+    } else if !archival && was_archival {
+        warn!("Archival mode disabled, the data of blocks below the pruning point is going to be garbage collected after the retention period");
+    }
+    metadata_store.set_archival(archival)?;
+    Ok(())
+}
+
+/// Checks that the system clock did not fall behind the genesis timestamp or behind the headers already stored.
+pub fn check_clock(db: &Arc<DB>, params: &Params, now: TimestampMs) -> SelfTestResult<()> {
+    if now < params.genesis.timestamp {
+        return Err(SelfTestError::ClockBeforeGenesis(now, params.genesis.timestamp));
+    }
+    if let Some(selected_tip) = DbHeadersSelectedTipStore::new(db.clone()).get().unwrap_option() {
+        // Headers up to the tolerated deviation into the future are valid, hence only later tips indicate a clock going backwards
+        let tolerance = params.target_time_per_block.saturating_mul(params.timestamp_deviation_tolerance);
+        let timestamp = DbHeadersStore::new(db.clone(), CachePolicy::Count(0)).get_timestamp(selected_tip.hash)?;
+        if now.saturating_add(tolerance) < timestamp {
+            return Err(SelfTestError::ClockBeforeSelectedTip(now, timestamp));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that all addresses can be bound, releasing them at once
+pub fn check_bindable(addresses: &[(&'static str, SocketAddr)]) -> SelfTestResult<()> {
+    for &(service, address) in addresses {
+        TcpListener::bind(address).map_err(|err| SelfTestError::AddressNotBindable(service, address, err))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use addresses::{Address, Prefix};
+use clap::Parser;
+use consensus_core::config::params::DEVNET_PARAMS;
+use kaspa_core::{error, info, warn};
+use mining::block_template::builder::pay_to_address_script;
+use node::{NodeConfig, NodeProcess};
+use rpc_core::api::rpc::RpcApi;
+use rpc_wrpc::client::WrpcBorshClient;
+use std::{env, fs, path::PathBuf, sync::Arc, time::Duration};
+use topology::Topology;
+use txgen::TxGenerator;
+
+mod miner;
+mod node;
+mod topology;
+mod txgen;
+
+/// The interval between two reports of the state of the nodes
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Kaspa local network orchestrator: launches a devnet of kaspad processes connected in a chosen topology,
+/// optionally mining and generating transactions, and tears it down on Ctrl-C or once the duration elapsed
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Number of nodes
+    #[arg(short, long, default_value_t = 3)]
+    nodes: usize,
+
+    /// Shape of the peer connections between the nodes
+    #[arg(short, long, value_enum, default_value_t = Topology::Ring)]
+    topology: Topology,
+
+    /// Number of nodes running a CPU miner, starting from the first node
+    #[arg(short, long, default_value_t = 1)]
+    miners: usize,
+
+    /// Transactions generated per second on the first node, spending the mined coinbase outputs. Zero disables the
+    /// transaction generator
+    #[arg(long, default_value_t = 0.0)]
+    tps: f64,
+
+    /// Path of the kaspad binary. Defaults to the kaspad binary next to this executable
+    #[arg(long)]
+    kaspad: Option<PathBuf>,
+
+    /// Directory holding the data and logs of the nodes. Defaults to a temporary directory deleted on teardown
+    #[arg(long)]
+    base_dir: Option<PathBuf>,
+
+    /// First port allocated to the nodes. Each node uses 3 ports, in blocks of 10 ports per node
+    #[arg(long, default_value_t = 17000)]
+    base_port: u16,
+
+    /// Duration of the run in seconds. If not set, the network runs until Ctrl-C
+    #[arg(short, long)]
+    duration: Option<u64>,
+
+    /// Logging level of the nodes
+    #[arg(long = "node-loglevel", default_value = "info")]
+    node_log_level: String,
+
+    /// Logging level for all subsystems {off, error, warn, info, debug, trace}
+    ///  -- You may also specify <subsystem>=<level>,<subsystem2>=<level>,... to set the log level for individual subsystems
+    #[arg(long = "loglevel", default_value = "info")]
+    log_level: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    kaspa_core::log::init_logger(None, &args.log_level);
+    assert!(args.nodes > 0, "the network needs at least one node");
+    assert!(args.miners <= args.nodes, "miners cannot exceed the number of nodes");
+
+    let kaspad =
+        args.kaspad.unwrap_or_else(|| env::current_exe().unwrap().with_file_name(format!("kaspad{0}", env::consts::EXE_SUFFIX)));
+    // Without an explicit base directory, the nodes run in a temporary directory removed on teardown
+    let temp_dir = match args.base_dir {
+        Some(_) => None,
+        None => Some(tempfile::tempdir().unwrap()),
+    };
+    let base_dir = args.base_dir.unwrap_or_else(|| temp_dir.as_ref().unwrap().path().to_path_buf());
+    fs::create_dir_all(&base_dir).unwrap();
+    info!("Launching {0} nodes in a {1:?} topology from {2}", args.nodes, args.topology, base_dir.display());
+
+    let configs: Vec<_> = (0..args.nodes).map(|index| NodeConfig::new(index, &base_dir, args.base_port)).collect();
+    let mut processes = Vec::with_capacity(args.nodes);
+    for config in configs.iter() {
+        let peers: Vec<_> = args.topology.peers_of(config.index, args.nodes).into_iter().map(|peer| &configs[peer]).collect();
+        match NodeProcess::spawn(&kaspad, config.clone(), config.args(&peers, &args.node_log_level)) {
+            Ok(process) => processes.push(process),
+            Err(err) => {
+                error!("Failed launching node {0} with {1}: {2}", config.index, kaspad.display(), err);
+                return;
+            }
+        }
+        info!("Node {0}: P2P {1}, gRPC {2}, wRPC {3}", config.index, config.p2p_address, config.grpc_address, config.wrpc_url());
+    }
+
+    let mut clients = Vec::with_capacity(args.nodes);
+    for config in configs.iter() {
+        match WrpcBorshClient::connect(&config.wrpc_url()).await {
+            Ok(client) => {
+                client.start().await;
+                clients.push(Arc::new(client));
+            }
+            Err(err) => {
+                error!("Failed connecting to node {0}: {1}, see {2}", config.index, err, config.app_dir.join("kaspad.log").display());
+                return;
+            }
+        }
+    }
+
+    // All miners and the transaction generator share a single key pair
+    let (secret_key, public_key) = secp256k1::generate_keypair(&mut rand::thread_rng());
+    let address = Address { prefix: Prefix::Devnet, payload: public_key.x_only_public_key().0.serialize().to_vec(), version: 0 };
+    info!("Mining to {0}", String::from(&address));
+
+    let mut tasks = Vec::new();
+    for (index, client) in clients.iter().enumerate().take(args.miners) {
+        tasks.push(tokio::spawn(miner::mine(client.clone(), address.clone(), index)));
+    }
+    if args.tps > 0.0 {
+        let generator = TxGenerator::new(
+            clients[0].clone(),
+            address.clone(),
+            pay_to_address_script(&address).unwrap(),
+            secret_key.secret_bytes(),
+            DEVNET_PARAMS.coinbase_maturity,
+            args.tps,
+        );
+        tasks.push(tokio::spawn(generator.run()));
+    }
+
+    let deadline = async {
+        match args.duration {
+            Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    let mut report_interval = tokio::time::interval(REPORT_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = &mut deadline => break,
+            _ = report_interval.tick() => report(&clients, &mut processes).await,
+        }
+    }
+
+    info!("Tearing down the network");
+    for task in tasks {
+        task.abort();
+    }
+    for client in clients.iter() {
+        let _ = client.disconnect().await;
+    }
+    for process in processes.iter_mut() {
+        process.kill();
+    }
+    if let Some(temp_dir) = temp_dir {
+        let _ = temp_dir.close();
+    }
+}
+
+/// Logs the DAG state of each node, warning about nodes which exited
+async fn report(clients: &[Arc<WrpcBorshClient>], processes: &mut [NodeProcess]) {
+    for (client, process) in clients.iter().zip(processes.iter_mut()) {
+        let index = process.config.index;
+        if !process.is_running() {
+            warn!("Node {0} exited, see {1}", index, process.config.app_dir.join("kaspad.log").display());
+            continue;
+        }
+        match client.get_block_dag_info().await {
+            Ok(info) => info!(
+                "Node {0}: {1} blocks, {2} headers, DAA score {3}, {4} tips",
+                index,
+                info.block_count,
+                info.header_count,
+                info.virtual_daa_score,
+                info.tip_hashes.len()
+            ),
+            Err(err) => warn!("Node {0}: failed getting the DAG info: {1}", index, err),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use consensus_core::display::HashDisplayExt;
+use kaspa_core::{trace, warn};
+use rpc_core::{api::rpc::RpcApi, RpcAddress};
+use rpc_wrpc::client::WrpcBorshClient;
+use std::{sync::Arc, time::Duration};
+
+/// The number of nonces tried on a template before fetching a fresh one, which keeps the mined blocks on top
+/// of the latest blocks of the other miners
+const NONCES_PER_TEMPLATE: u64 = 100_000;
+
+/// The delay before retrying when the node fails to provide a template
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Mines blocks paying to `pay_address` on top of the templates of a node, forever. The difficulty of the devnet
+/// being low, a CPU miner is enough to keep the network running.
+pub async fn mine(client: Arc<WrpcBorshClient>, pay_address: RpcAddress, node_index: usize) {
+    let mut nonce: u64 = rand::random();
+    loop {
+        let mut block = match client.get_block_template(pay_address.clone(), vec![]).await {
+            Ok(response) => response.block,
+            Err(err) => {
+                trace!("node {0}: failed getting a block template: {1}", node_index, err);
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        let header = block.header.clone();
+        let start_nonce = nonce;
+        let solution = tokio::task::spawn_blocking(move || {
+            let state = pow::State::new(&header);
+            (start_nonce..start_nonce.saturating_add(NONCES_PER_TEMPLATE)).find(|&nonce| state.check_pow(nonce).0)
+        })
+        .await
+        .unwrap();
+        nonce = start_nonce.wrapping_add(NONCES_PER_TEMPLATE);
+
+        if let Some(solution) = solution {
+            block.header.nonce = solution;
+            block.header.finalize();
+            let hash = block.header.hash;
+            match client.submit_block(block, false).await {
+                Ok(response) if response.report.is_success() => trace!("node {0}: mined block {1}", node_index, hash.short()),
+                Ok(response) => warn!("node {0}: mined block {1} was rejected: {2:?}", node_index, hash, response.report),
+                Err(err) => warn!("node {0}: failed submitting mined block {1}: {2}", node_index, hash, err),
+            }
+        }
+    }
+}
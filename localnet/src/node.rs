@@ -0,0 +1,105 @@
+use std::{
+    fs::{self, File},
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+
+/// The ports of a node are allocated in blocks of this size above the base port
+const PORTS_PER_NODE: u16 = 10;
+
+/// The generated configuration of a node of the local network. All nodes listen on the loopback interface.
+#[derive(Clone, Debug)]
+pub struct NodeConfig {
+    pub index: usize,
+    pub app_dir: PathBuf,
+    pub p2p_address: SocketAddr,
+    pub grpc_address: SocketAddr,
+    pub wrpc_address: SocketAddr,
+}
+
+impl NodeConfig {
+    pub fn new(index: usize, base_dir: &Path, base_port: u16) -> Self {
+        let port = base_port + index as u16 * PORTS_PER_NODE;
+        let address = |offset: u16| SocketAddr::from(([127, 0, 0, 1], port + offset));
+        Self {
+            index,
+            app_dir: base_dir.join(format!("node-{0}", index)),
+            p2p_address: address(0),
+            grpc_address: address(1),
+            wrpc_address: address(2),
+        }
+    }
+
+    /// The URL of the wRPC Borsh server of the node
+    pub fn wrpc_url(&self) -> String {
+        format!("ws://{0}", self.wrpc_address)
+    }
+
+    /// The kaspad arguments of the node, connecting to `peers` only. Outbound connections to other discovered
+    /// addresses are disabled, so that the network keeps the chosen topology.
+    pub fn args(&self, peers: &[&NodeConfig], log_level: &str) -> Vec<String> {
+        let mut args = vec![
+            "--devnet".to_string(),
+            format!("--appdir={0}", self.app_dir.display()),
+            format!("--listen={0}", self.p2p_address),
+            format!("--rpclisten={0}", self.grpc_address),
+            format!("--rpclisten-borsh={0}", self.wrpc_address),
+            "--outpeers=0".to_string(),
+            format!("--loglevel={0}", log_level),
+        ];
+        args.extend(peers.iter().map(|peer| format!("--addpeer={0}", peer.p2p_address)));
+        args
+    }
+}
+
+/// A running kaspad process, killed when dropped. Its output is written to `kaspad.log` in its app directory.
+pub struct NodeProcess {
+    pub config: NodeConfig,
+    child: Child,
+}
+
+impl NodeProcess {
+    pub fn spawn(kaspad: &Path, config: NodeConfig, args: Vec<String>) -> io::Result<Self> {
+        fs::create_dir_all(&config.app_dir)?;
+        let log = File::create(config.app_dir.join("kaspad.log"))?;
+        let child = Command::new(kaspad).args(args).stdin(Stdio::null()).stdout(log.try_clone()?).stderr(log).spawn()?;
+        Ok(Self { config, child })
+    }
+
+    /// Whether the process is still running
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    pub fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for NodeProcess {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_args() {
+        let base_dir = Path::new("/tmp/localnet");
+        let configs: Vec<_> = (0..3).map(|index| NodeConfig::new(index, base_dir, 17000)).collect();
+        assert_eq!(configs[2].p2p_address.port(), 17020);
+        assert_eq!(configs[2].wrpc_url(), "ws://127.0.0.1:17022");
+
+        let args = configs[2].args(&[&configs[0], &configs[1]], "info");
+        assert!(args.contains(&"--appdir=/tmp/localnet/node-2".to_string()));
+        assert!(args.contains(&"--rpclisten=127.0.0.1:17021".to_string()));
+        assert_eq!(args.iter().filter(|arg| arg.starts_with("--addpeer=")).count(), 2);
+        assert!(args.contains(&"--addpeer=127.0.0.1:17010".to_string()));
+    }
+}
@@ -0,0 +1,59 @@
+use clap::ValueEnum;
+
+/// The shape of the peer connections between the nodes of the local network
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Topology {
+    /// Each node is connected to the next one
+    Line,
+    /// A line closed by connecting the last node to the first one
+    Ring,
+    /// All nodes are connected to the first one
+    Star,
+    /// All nodes are connected to each other
+    Mesh,
+}
+
+impl Topology {
+    /// The connections of a network of `node_count` nodes, as pairs of node indexes `(from, to)` with `from > to`,
+    /// so that each connection is initiated by a single node
+    pub fn edges(self, node_count: usize) -> Vec<(usize, usize)> {
+        match self {
+            Topology::Line => (1..node_count).map(|i| (i, i - 1)).collect(),
+            // A ring of two nodes is a line, and closing it would connect the same nodes twice
+            Topology::Ring if node_count > 2 => {
+                let mut edges = Topology::Line.edges(node_count);
+                edges.push((node_count - 1, 0));
+                edges
+            }
+            Topology::Ring => Topology::Line.edges(node_count),
+            Topology::Star => (1..node_count).map(|i| (i, 0)).collect(),
+            Topology::Mesh => (1..node_count).flat_map(|i| (0..i).map(move |j| (i, j))).collect(),
+        }
+    }
+
+    /// The nodes to which node `index` initiates connections
+    pub fn peers_of(self, index: usize, node_count: usize) -> Vec<usize> {
+        self.edges(node_count).into_iter().filter(|&(from, _)| from == index).map(|(_, to)| to).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edges() {
+        assert_eq!(Topology::Line.edges(4), vec![(1, 0), (2, 1), (3, 2)]);
+        assert_eq!(Topology::Ring.edges(4), vec![(1, 0), (2, 1), (3, 2), (3, 0)]);
+        assert_eq!(Topology::Ring.edges(2), vec![(1, 0)]);
+        assert_eq!(Topology::Star.edges(4), vec![(1, 0), (2, 0), (3, 0)]);
+        assert_eq!(Topology::Mesh.edges(4).len(), 6);
+        for topology in [Topology::Line, Topology::Ring, Topology::Star, Topology::Mesh] {
+            assert!(topology.edges(1).is_empty());
+            assert!(topology.edges(5).iter().all(|&(from, to)| from > to && from < 5));
+        }
+
+        assert_eq!(Topology::Ring.peers_of(3, 4), vec![2, 0]);
+        assert!(Topology::Star.peers_of(0, 4).is_empty());
+    }
+}
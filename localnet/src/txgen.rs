@@ -0,0 +1,105 @@
+use consensus_core::{
+    sign::sign,
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+};
+use kaspa_core::{info, trace, warn};
+use rpc_core::{api::rpc::RpcApi, Notification, NotificationType, RpcAddress};
+use rpc_wrpc::client::WrpcBorshClient;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// The fee paid by each generated transaction, in sompi
+const FEE: u64 = 10_000;
+
+/// Outputs above this amount are split in two, so that the number of spendable UTXOs grows with the transactions
+const SPLIT_THRESHOLD: u64 = 100 * FEE;
+
+/// The interval between two updates of the virtual DAA score, which decides the maturity of coinbase outputs
+const DAA_SCORE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Generates transactions at a steady rate, spending the UTXOs of a single key pair back to its own address.
+/// The UTXOs are tracked through the UtxosChanged notifications of the node, starting with the coinbase outputs
+/// of the miners paying to the same address.
+pub struct TxGenerator {
+    client: Arc<WrpcBorshClient>,
+    address: RpcAddress,
+    script_public_key: ScriptPublicKey,
+    secret_key: [u8; 32],
+    coinbase_maturity: u64,
+    transactions_per_second: f64,
+}
+
+impl TxGenerator {
+    pub fn new(
+        client: Arc<WrpcBorshClient>,
+        address: RpcAddress,
+        script_public_key: ScriptPublicKey,
+        secret_key: [u8; 32],
+        coinbase_maturity: u64,
+        transactions_per_second: f64,
+    ) -> Self {
+        Self { client, address, script_public_key, secret_key, coinbase_maturity, transactions_per_second }
+    }
+
+    pub async fn run(self) {
+        let listener = self.client.register_new_listener(None);
+        if let Err(err) = self.client.start_notify(listener.id, NotificationType::UtxosChanged(vec![self.address.clone()])).await {
+            warn!("Transaction generator failed subscribing to UTXO changes: {0}", err);
+            return;
+        }
+        info!("Generating {0} transactions per second once coinbase outputs mature", self.transactions_per_second);
+
+        let mut utxos: HashMap<TransactionOutpoint, UtxoEntry> = HashMap::new();
+        let mut virtual_daa_score = 0;
+        let mut submit_interval = tokio::time::interval(Duration::from_secs_f64(1.0 / self.transactions_per_second));
+        let mut refresh_interval = tokio::time::interval(DAA_SCORE_REFRESH_INTERVAL);
+        loop {
+            tokio::select! {
+                notification = listener.recv_channel.recv() => match notification {
+                    Ok(notification) => {
+                        if let Notification::UtxosChanged(ref changes) = *notification {
+                            for entry in changes.removed.iter() {
+                                utxos.remove(&entry.outpoint);
+                            }
+                            utxos.extend(changes.added.iter().map(|entry| (entry.outpoint, entry.utxo_entry.clone())));
+                        }
+                    }
+                    Err(_) => break,
+                },
+                _ = refresh_interval.tick() => {
+                    if let Ok(info) = self.client.get_block_dag_info().await {
+                        virtual_daa_score = info.virtual_daa_score;
+                    }
+                }
+                _ = submit_interval.tick() => self.submit_transaction(&mut utxos, virtual_daa_score).await,
+            }
+        }
+    }
+
+    async fn submit_transaction(&self, utxos: &mut HashMap<TransactionOutpoint, UtxoEntry>, virtual_daa_score: u64) {
+        let spendable = utxos
+            .iter()
+            .find(|(_, entry)| {
+                entry.amount > FEE && (!entry.is_coinbase || entry.block_daa_score + self.coinbase_maturity <= virtual_daa_score)
+            })
+            .map(|(&outpoint, _)| outpoint);
+        // The spent UTXO is removed at once, so that it is not spent again before its removal is notified
+        let (outpoint, entry) = match spendable.and_then(|outpoint| utxos.remove_entry(&outpoint)) {
+            Some(utxo) => utxo,
+            None => return,
+        };
+
+        let amount = entry.amount - FEE;
+        let amounts = if amount > SPLIT_THRESHOLD { vec![amount / 2, amount - amount / 2] } else { vec![amount] };
+        let outputs = amounts.into_iter().map(|value| TransactionOutput::new(value, self.script_public_key.clone())).collect();
+        let inputs = vec![TransactionInput::new(outpoint, vec![], 0, 1)];
+        let transaction = Transaction::new(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let transaction = sign(MutableTransaction::with_entries(transaction, vec![entry]), self.secret_key).tx;
+
+        let id = transaction.id();
+        match self.client.submit_transaction((&transaction).into(), false).await {
+            Ok(_) => trace!("submitted transaction {0}", id),
+            Err(err) => warn!("Failed submitting transaction {0}: {1}", id, err),
+        }
+    }
+}
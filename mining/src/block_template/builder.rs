@@ -0,0 +1,67 @@
+use super::{selector::select_transactions, Policy};
+use crate::{
+    errors::{MiningManagerError, MiningManagerResult},
+    mempool::model::MempoolTransaction,
+};
+use addresses::Address;
+use consensus_core::{
+    api::DynConsensus,
+    block::BlockTemplate,
+    coinbase::MinerData,
+    errors::block::RuleError,
+    tx::{ScriptPublicKey, ScriptVec},
+};
+
+/// The mass reserved for the coinbase transaction, which is built by consensus only after transaction
+/// selection. This is a conservative upper bound for a coinbase paying the full merge set of blue blocks.
+pub const COINBASE_MASS_RESERVE: u64 = 10_000;
+
+// Opcodes and script public key versions required for building standard pay-to-address scripts
+const OP_DATA_32: u8 = 0x20;
+const OP_DATA_33: u8 = 0x21;
+const OP_EQUAL: u8 = 0x87;
+const OP_BLAKE2B: u8 = 0xaa;
+const OP_CHECK_SIG_ECDSA: u8 = 0xab;
+const OP_CHECK_SIG: u8 = 0xac;
+const ADDRESS_VERSION_PUBKEY: u8 = 0;
+const ADDRESS_VERSION_PUBKEY_ECDSA: u8 = 1;
+const ADDRESS_VERSION_SCRIPT_HASH: u8 = 8;
+const SCRIPT_PUBLIC_KEY_VERSION: u16 = 0;
+
+/// Builds the standard script public key paying to `address`
+pub fn pay_to_address_script(address: &Address) -> MiningManagerResult<ScriptPublicKey> {
+    let payload = address.payload.as_slice();
+    let script = match (address.version, payload.len()) {
+        (ADDRESS_VERSION_PUBKEY, 32) => [&[OP_DATA_32][..], payload, &[OP_CHECK_SIG]].concat(),
+        (ADDRESS_VERSION_PUBKEY_ECDSA, 33) => [&[OP_DATA_33][..], payload, &[OP_CHECK_SIG_ECDSA]].concat(),
+        (ADDRESS_VERSION_SCRIPT_HASH, 32) => [&[OP_BLAKE2B, OP_DATA_32][..], payload, &[OP_EQUAL]].concat(),
+        (version, len) => return Err(MiningManagerError::UnsupportedAddress(version, len)),
+    };
+    Ok(ScriptPublicKey::new(SCRIPT_PUBLIC_KEY_VERSION, ScriptVec::from_vec(script)))
+}
+
+pub struct BlockTemplateBuilder {
+    consensus: DynConsensus,
+    policy: Policy,
+}
+
+impl BlockTemplateBuilder {
+    pub fn new(consensus: DynConsensus, policy: Policy) -> Self {
+        Self { consensus, policy }
+    }
+
+    /// Builds a block template on top of the current virtual, containing the highest fee-rate `candidates`
+    /// which fit within the policy mass limit, after the high priority ones fitting within the policy
+    /// high priority mass. The remaining header fields (parents, merkle roots, UTXO
+    /// commitment, difficulty, DAA and blue data, pruning point and timestamp) and the coinbase transaction
+    /// are filled by consensus, leaving only the nonce to the miner.
+    pub fn build_block_template<'a>(
+        &self,
+        candidates: impl Iterator<Item = &'a MempoolTransaction>,
+        miner_data: MinerData,
+    ) -> Result<BlockTemplate, RuleError> {
+        let mass_limit = self.policy.max_block_mass.saturating_sub(COINBASE_MASS_RESERVE);
+        let transactions = select_transactions(candidates, mass_limit, self.policy.high_priority_mass);
+        self.consensus.clone().build_block_template(miner_data, transactions)
+    }
+}
@@ -0,0 +1,49 @@
+use consensus_core::{block::BlockTemplate, coinbase::MinerData};
+use hashes::Hash;
+use std::time::{Duration, Instant};
+
+/// The default duration a block template is reused for when neither the virtual parents nor the block candidate transactions changed
+pub const DEFAULT_BLOCK_TEMPLATE_CACHE_LIFETIME: Duration = Duration::from_millis(1000);
+
+struct CachedBlockTemplate {
+    template: BlockTemplate,
+    virtual_parents: Vec<Hash>,
+    candidates_revision: u64,
+    built_at: Instant,
+}
+
+/// Caches the last built block template, so that miners polling frequently do not trigger a rebuild per request.
+///
+/// A cached template is reused as long as it was built for the same miner data, on top of the same virtual
+/// parents and out of the same mempool candidate set, and is not older than the cache lifetime. The lifetime
+/// bounds the staleness of the template timestamp and of changes which are not reflected by the above.
+pub(crate) struct BlockTemplateCache {
+    lifetime: Duration,
+    cached: Option<CachedBlockTemplate>,
+}
+
+impl BlockTemplateCache {
+    pub(crate) fn new(lifetime: Duration) -> Self {
+        Self { lifetime, cached: None }
+    }
+
+    pub(crate) fn get(&self, miner_data: &MinerData, virtual_parents: &[Hash], candidates_revision: u64) -> Option<BlockTemplate> {
+        self.cached
+            .as_ref()
+            .filter(|cached| {
+                cached.built_at.elapsed() < self.lifetime
+                    && cached.candidates_revision == candidates_revision
+                    && cached.virtual_parents == virtual_parents
+                    && cached.template.miner_data == *miner_data
+            })
+            .map(|cached| cached.template.clone())
+    }
+
+    pub(crate) fn set(&mut self, template: BlockTemplate, virtual_parents: Vec<Hash>, candidates_revision: u64) {
+        self.cached = Some(CachedBlockTemplate { template, virtual_parents, candidates_revision, built_at: Instant::now() });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.cached = None;
+    }
+}
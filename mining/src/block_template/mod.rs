@@ -0,0 +1,21 @@
+pub mod builder;
+pub mod cache;
+mod selector;
+
+/// Policy houses the policy (configuration parameters) which is used to control
+/// the generation of block templates
+#[derive(Clone, Debug)]
+pub struct Policy {
+    /// The maximum block mass to be used when generating a block template,
+    /// including the mass reserved for the coinbase transaction
+    pub max_block_mass: u64,
+    /// The mass reserved for high priority transactions, which are selected into it ahead of
+    /// low priority ones regardless of their fee rate. Zero disables the reservation
+    pub high_priority_mass: u64,
+}
+
+impl Policy {
+    pub fn new(max_block_mass: u64, high_priority_mass: u64) -> Self {
+        Self { max_block_mass, high_priority_mass }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::mempool::model::{MempoolTransaction, Priority};
+use consensus_core::tx::Transaction;
+
+/// Selects transactions for a block template out of `candidates`, which are expected to be ordered by
+/// descending fee rate. High priority candidates are first selected into the high priority lane, i.e., as long
+/// as they fit within `high_priority_mass_limit`. The remaining candidates are then greedily added as long as
+/// they fit within `mass_limit`, i.e., a candidate which does not fit is skipped while smaller subsequent ones
+/// may still be selected. Selected transactions keep the candidates order.
+pub(crate) fn select_transactions<'a>(
+    candidates: impl Iterator<Item = &'a MempoolTransaction>,
+    mass_limit: u64,
+    high_priority_mass_limit: u64,
+) -> Vec<Transaction> {
+    let candidates: Vec<_> = candidates.collect();
+    let mut selected = vec![false; candidates.len()];
+    let mut total_mass = 0u64;
+    let lane_mass_limit = high_priority_mass_limit.min(mass_limit);
+    if lane_mass_limit > 0 {
+        for (i, tx) in candidates.iter().enumerate().filter(|(_, tx)| tx.priority == Priority::High) {
+            if total_mass + tx.mass() <= lane_mass_limit {
+                total_mass += tx.mass();
+                selected[i] = true;
+            }
+        }
+    }
+    for (i, tx) in candidates.iter().enumerate() {
+        if selected[i] || total_mass + tx.mass() > mass_limit {
+            continue;
+        }
+        total_mass += tx.mass();
+        selected[i] = true;
+    }
+    candidates.into_iter().zip(selected).filter(|(_, selected)| *selected).map(|(tx, _)| tx.tx().clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::{
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{MutableTransaction, ScriptPublicKey, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+    };
+    use hashes::Hash;
+
+    fn create_candidate(id_word: u64, mass: u64, priority: Priority) -> MempoolTransaction {
+        let tx = Transaction::new(
+            0,
+            vec![TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(id_word), 0), vec![], 0, 0)],
+            vec![TransactionOutput::new(1, ScriptPublicKey::default())],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        );
+        let mut mtx = MutableTransaction::new(tx);
+        mtx.entries[0] = Some(UtxoEntry::new(2, ScriptPublicKey::default(), 0, false));
+        mtx.calculated_fee = Some(1);
+        mtx.calculated_mass = Some(mass);
+        MempoolTransaction::new(mtx, priority, 0)
+    }
+
+    /// Returns the indexes of the selected candidates
+    fn select_indexes(candidates: &[MempoolTransaction], mass_limit: u64, high_priority_mass_limit: u64) -> Vec<usize> {
+        let selected = select_transactions(candidates.iter(), mass_limit, high_priority_mass_limit);
+        selected.iter().map(|tx| candidates.iter().position(|candidate| candidate.id() == tx.id()).unwrap()).collect()
+    }
+
+    #[test]
+    fn select_transactions_test() {
+        let candidates: Vec<_> =
+            [(1, 400), (2, 700), (3, 500), (4, 100)].into_iter().map(|(id, mass)| create_candidate(id, mass, Priority::Low)).collect();
+        // The second candidate does not fit and is skipped, but the following ones are still selected
+        assert_eq!(select_indexes(&candidates, 1000, 0), vec![0, 2, 3]);
+        assert!(select_transactions(candidates.iter(), 0, 0).is_empty());
+    }
+
+    #[test]
+    fn high_priority_lane_test() {
+        let candidates: Vec<_> =
+            [(1, 600, Priority::Low), (2, 300, Priority::Low), (3, 300, Priority::High), (4, 300, Priority::High)]
+                .into_iter()
+                .map(|(id, mass, priority)| create_candidate(id, mass, priority))
+                .collect();
+        // Without a lane, high priority candidates with a low fee rate are starved
+        assert_eq!(select_indexes(&candidates, 1000, 0), vec![0, 1]);
+        // The lane fits a single high priority candidate, the rest of the mass goes by fee rate
+        assert_eq!(select_indexes(&candidates, 1000, 400), vec![0, 2]);
+        // The lane is bounded by the total mass limit
+        assert_eq!(select_indexes(&candidates, 500, 1000), vec![2]);
+    }
+}
@@ -0,0 +1,54 @@
+use consensus_core::{
+    errors::{block::RuleError as BlockRuleError, coinbase::CoinbaseError, tx::TxRuleError},
+    tx::{TransactionId, TransactionOutpoint},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum RuleError {
+    #[error("transaction {0} is already in the mempool")]
+    RejectDuplicate(TransactionId),
+
+    #[error("output {0} already spent by transaction {1} in the mempool")]
+    RejectDoubleSpendInMempool(TransactionOutpoint, TransactionId),
+
+    #[error("transaction {0} is a coinbase transaction")]
+    RejectCoinbase(TransactionId),
+
+    #[error("transaction {0} was rejected: {1}")]
+    RejectTxRule(TransactionId, TxRuleError),
+
+    #[error("the mempool is full ({0} transactions)")]
+    RejectMempoolIsFull(usize),
+
+    #[error("transaction {0} is an orphan where orphans are disallowed")]
+    RejectDisallowedOrphan(TransactionId),
+
+    #[error("transaction {0} is already in the orphan pool")]
+    RejectDuplicateOrphan(TransactionId),
+
+    #[error("output {0} already spent by transaction {1} in the orphan pool")]
+    RejectDoubleSpendInOrphanPool(TransactionOutpoint, TransactionId),
+
+    #[error("the orphan pool is full ({0} transactions)")]
+    RejectOrphanPoolIsFull(usize),
+}
+
+pub type RuleResult<T> = std::result::Result<T, RuleError>;
+
+#[derive(Error, Debug, Clone)]
+pub enum MiningManagerError {
+    #[error("block template building failed: {0}")]
+    BlockTemplateBuilderError(#[from] BlockRuleError),
+
+    #[error(transparent)]
+    MempoolError(#[from] RuleError),
+
+    #[error("coinbase computation failed: {0}")]
+    CoinbaseError(#[from] CoinbaseError),
+
+    #[error("address of version {0} with a {1} bytes payload is not supported")]
+    UnsupportedAddress(u8, usize),
+}
+
+pub type MiningManagerResult<T> = std::result::Result<T, MiningManagerError>;
@@ -0,0 +1,276 @@
+use crate::mempool::model::FeeRateKey;
+use std::collections::VecDeque;
+
+/// The default number of recently accepted transactions whose fee rates are kept for estimation
+pub const DEFAULT_RECENT_FEE_RATES_CAPACITY: usize = 10_000;
+
+/// Describes the inclusion target of a fee-rate estimation bucket
+#[derive(Clone, Copy, Debug)]
+pub struct FeeRateBucket {
+    /// The number of blocks within which a transaction paying the estimated fee rate is expected to be included
+    pub target_blocks: u64,
+    /// The percentile (0-100) of recently accepted fee rates used as a lower bound for the estimation
+    pub recent_percentile: u8,
+}
+
+impl FeeRateBucket {
+    pub fn new(target_blocks: u64, recent_percentile: u8) -> Self {
+        assert!(recent_percentile <= 100);
+        Self { target_blocks, recent_percentile }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FeeRateBuckets {
+    pub priority: FeeRateBucket,
+    pub normal: FeeRateBucket,
+    pub low: FeeRateBucket,
+}
+
+impl Default for FeeRateBuckets {
+    fn default() -> Self {
+        Self { priority: FeeRateBucket::new(1, 90), normal: FeeRateBucket::new(10, 50), low: FeeRateBucket::new(60, 10) }
+    }
+}
+
+/// Fee-rate estimations, in sompi per mass unit, for each bucket
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeRateEstimations {
+    pub priority: f64,
+    pub normal: f64,
+    pub low: f64,
+}
+
+/// A bounded record of the fee rates of the most recently accepted transactions
+pub struct RecentFeeRates {
+    capacity: usize,
+    fee_rates: VecDeque<f64>,
+}
+
+impl RecentFeeRates {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, fee_rates: VecDeque::with_capacity(capacity) }
+    }
+
+    pub fn record(&mut self, fee_rate: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.fee_rates.len() == self.capacity {
+            self.fee_rates.pop_front();
+        }
+        self.fee_rates.push_back(fee_rate);
+    }
+
+    pub fn len(&self) -> usize {
+        self.fee_rates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fee_rates.is_empty()
+    }
+
+    fn sorted(&self) -> Vec<f64> {
+        let mut fee_rates: Vec<f64> = self.fee_rates.iter().copied().collect();
+        fee_rates.sort_by(f64::total_cmp);
+        fee_rates
+    }
+}
+
+impl Default for RecentFeeRates {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_FEE_RATES_CAPACITY)
+    }
+}
+
+/// Estimates the fee rate required by each bucket.
+///
+/// The backlog estimation of a bucket is the fee rate of the transaction filling up `target_blocks` blocks when
+/// `backlog` (expected in descending fee-rate order) is packed into blocks of `max_block_mass`, or zero if the
+/// backlog does not fill them up. The final estimation is the maximum of the backlog estimation and the
+/// `recent_percentile` fee rate of recently accepted transactions.
+pub fn estimate_fee_rates<'a>(
+    backlog: impl Iterator<Item = &'a FeeRateKey>,
+    recent: &RecentFeeRates,
+    buckets: &FeeRateBuckets,
+    max_block_mass: u64,
+) -> FeeRateEstimations {
+    let targets = [buckets.priority, buckets.normal, buckets.low];
+    let mut backlog_rates = [0f64; 3];
+    let mut accumulated_mass = 0u64;
+    for key in backlog {
+        accumulated_mass = accumulated_mass.saturating_add(key.mass);
+        let mut all_filled = true;
+        for (bucket, rate) in targets.iter().zip(backlog_rates.iter_mut()) {
+            if accumulated_mass > bucket.target_blocks.saturating_mul(max_block_mass) {
+                if *rate == 0f64 {
+                    *rate = key.fee_rate();
+                }
+            } else {
+                all_filled = false;
+            }
+        }
+        if all_filled {
+            break;
+        }
+    }
+
+    let sorted_recent = recent.sorted();
+    let percentile = |p: u8| {
+        if sorted_recent.is_empty() {
+            return 0f64;
+        }
+        let index = (sorted_recent.len() - 1) * p as usize / 100;
+        sorted_recent[index]
+    };
+
+    let estimate = |i: usize| f64::max(backlog_rates[i], percentile(targets[i].recent_percentile));
+    FeeRateEstimations { priority: estimate(0), normal: estimate(1), low: estimate(2) }
+}
+
+/// The number of buckets of [`FeeRateHistogram`]
+pub const FEE_RATE_HISTOGRAM_BUCKETS: usize = 32;
+
+/// A bucket of the mempool fee-rate histogram, with fee rates in sompi per mass unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeRateHistogramBucket {
+    /// The inclusive lower bound of the bucket fee rates
+    pub min_fee_rate: u64,
+    /// The exclusive upper bound of the bucket fee rates, or `None` for the last, unbounded bucket
+    pub max_fee_rate: Option<u64>,
+    pub transaction_count: u64,
+    pub total_mass: u64,
+}
+
+/// A histogram of the fee rates of mempool transactions, holding the number of transactions and their total mass
+/// per bucket. Bucket 0 holds fee rates below 1, bucket `i` holds fee rates in `[2^(i-1), 2^i)` and the last bucket
+/// is unbounded. The histogram is updated incrementally as transactions enter and leave the mempool.
+#[derive(Clone, Debug, Default)]
+pub struct FeeRateHistogram {
+    transaction_counts: [u64; FEE_RATE_HISTOGRAM_BUCKETS],
+    total_masses: [u64; FEE_RATE_HISTOGRAM_BUCKETS],
+}
+
+impl FeeRateHistogram {
+    pub fn add(&mut self, key: &FeeRateKey) {
+        let index = Self::bucket_index(key);
+        self.transaction_counts[index] += 1;
+        self.total_masses[index] += key.mass;
+    }
+
+    /// Removes a transaction previously added with the same key
+    pub fn remove(&mut self, key: &FeeRateKey) {
+        let index = Self::bucket_index(key);
+        self.transaction_counts[index] -= 1;
+        self.total_masses[index] -= key.mass;
+    }
+
+    pub fn total_mass(&self) -> u64 {
+        self.total_masses.iter().sum()
+    }
+
+    /// Returns the non-empty buckets in descending fee-rate order, i.e., in the order transactions are selected for blocks
+    pub fn buckets(&self) -> Vec<FeeRateHistogramBucket> {
+        (0..FEE_RATE_HISTOGRAM_BUCKETS)
+            .rev()
+            .filter(|&index| self.transaction_counts[index] > 0)
+            .map(|index| {
+                let (min_fee_rate, max_fee_rate) = Self::bucket_bounds(index);
+                FeeRateHistogramBucket {
+                    min_fee_rate,
+                    max_fee_rate,
+                    transaction_count: self.transaction_counts[index],
+                    total_mass: self.total_masses[index],
+                }
+            })
+            .collect()
+    }
+
+    fn bucket_index(key: &FeeRateKey) -> usize {
+        // The bucket of an integer fee rate is its bit length, which is computed without floating point arithmetic
+        let fee_rate = key.fee / key.mass.max(1);
+        usize::min((u64::BITS - fee_rate.leading_zeros()) as usize, FEE_RATE_HISTOGRAM_BUCKETS - 1)
+    }
+
+    fn bucket_bounds(index: usize) -> (u64, Option<u64>) {
+        match index {
+            0 => (0, Some(1)),
+            _ if index == FEE_RATE_HISTOGRAM_BUCKETS - 1 => (1 << (index - 1), None),
+            _ => (1 << (index - 1), Some(1 << index)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hashes::Hash;
+
+    fn keys(fee_mass: &[(u64, u64)]) -> Vec<FeeRateKey> {
+        fee_mass.iter().enumerate().map(|(i, &(fee, mass))| FeeRateKey { fee, mass, id: Hash::from_u64_word(i as u64) }).collect()
+    }
+
+    #[test]
+    fn recent_fee_rates_capacity_test() {
+        let mut recent = RecentFeeRates::new(2);
+        recent.record(1.0);
+        recent.record(2.0);
+        recent.record(3.0);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent.sorted(), vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn estimate_from_backlog_test() {
+        let buckets =
+            FeeRateBuckets { priority: FeeRateBucket::new(1, 0), normal: FeeRateBucket::new(2, 0), low: FeeRateBucket::new(5, 0) };
+        // Each transaction fills half a block of mass 100, the backlog fills up 2.5 blocks
+        let backlog = keys(&[(500, 50), (400, 50), (300, 50), (200, 50), (100, 50)]);
+        let estimations = estimate_fee_rates(backlog.iter(), &RecentFeeRates::default(), &buckets, 100);
+        assert_eq!(estimations, FeeRateEstimations { priority: 6.0, normal: 2.0, low: 0.0 });
+    }
+
+    #[test]
+    fn estimate_from_recent_test() {
+        let buckets = FeeRateBuckets::default();
+        let mut recent = RecentFeeRates::default();
+        for fee_rate in 1..=101 {
+            recent.record(fee_rate as f64);
+        }
+        let estimations = estimate_fee_rates(keys(&[]).iter(), &recent, &buckets, 100);
+        assert_eq!(estimations, FeeRateEstimations { priority: 91.0, normal: 51.0, low: 11.0 });
+
+        // A congested backlog takes precedence over lower recent fee rates
+        let backlog = keys(&[(10_000, 100), (10_000, 100)]);
+        let estimations = estimate_fee_rates(backlog.iter(), &recent, &buckets, 100);
+        assert_eq!(estimations.priority, 100.0);
+        assert_eq!(estimations.normal, 51.0);
+    }
+
+    #[test]
+    fn fee_rate_histogram_test() {
+        let mut histogram = FeeRateHistogram::default();
+        // Fee rates 0.5, 1, 3, 3.5 and 2^40
+        let txs = keys(&[(50, 100), (100, 100), (300, 100), (350, 100), (1 << 41, 2)]);
+        txs.iter().for_each(|key| histogram.add(key));
+        assert_eq!(histogram.total_mass(), 402);
+        assert_eq!(
+            histogram.buckets(),
+            vec![
+                FeeRateHistogramBucket { min_fee_rate: 1 << 30, max_fee_rate: None, transaction_count: 1, total_mass: 2 },
+                FeeRateHistogramBucket { min_fee_rate: 2, max_fee_rate: Some(4), transaction_count: 2, total_mass: 200 },
+                FeeRateHistogramBucket { min_fee_rate: 1, max_fee_rate: Some(2), transaction_count: 1, total_mass: 100 },
+                FeeRateHistogramBucket { min_fee_rate: 0, max_fee_rate: Some(1), transaction_count: 1, total_mass: 100 },
+            ]
+        );
+
+        // Removing all transactions of a bucket drops it from the histogram
+        histogram.remove(&txs[0]);
+        histogram.remove(&txs[2]);
+        let buckets = histogram.buckets();
+        assert_eq!(buckets.len(), 3);
+        assert_eq!((buckets[1].min_fee_rate, buckets[1].transaction_count, buckets[1].total_mass), (2, 1, 100));
+        assert_eq!(buckets[2].min_fee_rate, 1);
+        assert_eq!(histogram.total_mass(), 202);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod block_template;
+pub mod errors;
+pub mod fee_estimation;
+pub mod manager;
+pub mod mempool;
+
+#[cfg(test)]
+pub mod testutils;
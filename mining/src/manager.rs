@@ -0,0 +1,271 @@
+use crate::{
+    block_template::{
+        builder::{pay_to_address_script, BlockTemplateBuilder},
+        cache::BlockTemplateCache,
+        Policy,
+    },
+    errors::MiningManagerResult,
+    fee_estimation::{FeeRateBuckets, FeeRateEstimations, FeeRateHistogramBucket},
+    mempool::{
+        model::{MempoolEntry, Priority},
+        Config as MempoolConfig, Mempool,
+    },
+};
+use addresses::Address;
+use consensus_core::{
+    api::DynConsensus,
+    block::BlockTemplate,
+    coinbase::{CoinbasePreview, MinerData},
+    errors::block::RuleError,
+    tx::{Transaction, TransactionId},
+};
+use std::{
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+/// The mining manager owns the mempool and builds block templates out of its transactions
+pub struct MiningManager {
+    consensus: DynConsensus,
+    block_template_builder: BlockTemplateBuilder,
+    block_template_cache: Mutex<BlockTemplateCache>,
+    mempool: RwLock<Mempool>,
+}
+
+impl MiningManager {
+    pub fn new(consensus: DynConsensus, mempool_config: MempoolConfig, block_template_cache_lifetime: Duration) -> Self {
+        let max_block_mass = consensus.clone().max_block_mass();
+        let high_priority_mass = if mempool_config.high_priority_lane_enabled {
+            max_block_mass * mempool_config.high_priority_lane_mass_percent.min(100) / 100
+        } else {
+            0
+        };
+        let policy = Policy::new(max_block_mass, high_priority_mass);
+        Self {
+            consensus: consensus.clone(),
+            block_template_builder: BlockTemplateBuilder::new(consensus.clone(), policy),
+            block_template_cache: Mutex::new(BlockTemplateCache::new(block_template_cache_lifetime)),
+            mempool: RwLock::new(Mempool::new(consensus, mempool_config)),
+        }
+    }
+
+    /// Builds a block template paying to `miner_address`, filled with the highest fee-rate mempool
+    /// transactions which can be included on top of the current virtual. The last built template is
+    /// reused as long as the virtual parents and the block candidate transactions did not change and
+    /// the cache lifetime did not pass
+    pub fn get_block_template(&self, miner_address: &Address, extra_data: Vec<u8>) -> MiningManagerResult<BlockTemplate> {
+        let miner_data = MinerData::new(pay_to_address_script(miner_address)?, extra_data);
+        // Holding the cache lock while building makes concurrent requests wait for the template being built
+        let mut cache = self.block_template_cache.lock().unwrap();
+        loop {
+            let virtual_parents = self.consensus.clone().get_virtual_parents();
+            let (result, candidates_revision) = {
+                let mempool = self.mempool.read().unwrap();
+                let candidates_revision = mempool.block_candidates_revision();
+                if let Some(template) = cache.get(&miner_data, &virtual_parents, candidates_revision) {
+                    return Ok(template);
+                }
+                let result = self.block_template_builder.build_block_template(mempool.block_candidates(), miner_data.clone());
+                (result, candidates_revision)
+            };
+            match result {
+                Ok(template) => {
+                    cache.set(template.clone(), virtual_parents, candidates_revision);
+                    return Ok(template);
+                }
+                // The mempool is not atomically in sync with the virtual state, so selected transactions might have
+                // been invalidated in the meantime. These are evicted and the template is rebuilt without them
+                Err(RuleError::InvalidTransactionsInNewBlock(invalid)) => {
+                    let mut mempool = self.mempool.write().unwrap();
+                    // Make sure each retry evicts at least one transaction, bounding the loop by the mempool size
+                    if !invalid.iter().any(|(id, _)| mempool.has_transaction(id)) {
+                        return Err(RuleError::InvalidTransactionsInNewBlock(invalid).into());
+                    }
+                    for (id, _) in invalid.iter() {
+                        mempool.remove_transaction(id, true);
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Validates a locally submitted transaction (e.g., through RPC) and inserts it to the mempool with high priority,
+    /// making it eligible for the high priority lane and for periodic rebroadcasting. Returns the ids of all
+    /// transactions inserted to the mempool as a result, see [`Mempool::validate_and_insert_transaction`]
+    pub fn submit_transaction(&self, transaction: Transaction, allow_orphan: bool) -> MiningManagerResult<Vec<TransactionId>> {
+        Ok(self.mempool.write().unwrap().validate_and_insert_transaction(transaction, Priority::High, allow_orphan)?)
+    }
+
+    /// Drops the cached block template, forcing the next request to build a new one
+    pub fn clear_block_template(&self) {
+        self.block_template_cache.lock().unwrap().clear();
+    }
+
+    /// Returns the coinbase a block built right now would carry when paying to `miner_address`, along with the
+    /// rewards of each merged block. Useful for pools verifying the payouts of the templates they mine
+    pub fn get_coinbase_preview(&self, miner_address: &Address, extra_data: Vec<u8>) -> MiningManagerResult<CoinbasePreview> {
+        let miner_data = MinerData::new(pay_to_address_script(miner_address)?, extra_data);
+        Ok(self.consensus.clone().get_coinbase_preview(miner_data)?)
+    }
+
+    /// Returns priority, normal and low fee-rate estimations (in sompi per mass unit) for the inclusion targets
+    /// described by `buckets`, derived from the mempool backlog and recently accepted transactions
+    pub fn estimate_fee_rate(&self, buckets: &FeeRateBuckets) -> FeeRateEstimations {
+        let max_block_mass = self.consensus.clone().max_block_mass();
+        self.mempool.read().unwrap().estimate_fee_rates(buckets, max_block_mass)
+    }
+
+    /// Returns the mempool entry of the transaction with `id`, see [`Mempool::get_entry`]
+    pub fn get_mempool_entry(
+        &self,
+        id: &TransactionId,
+        include_orphan_pool: bool,
+        filter_transaction_pool: bool,
+    ) -> Option<MempoolEntry> {
+        self.mempool.read().unwrap().get_entry(id, include_orphan_pool, filter_transaction_pool)
+    }
+
+    /// Returns the mempool entries spending from or paying to any of `addresses`, or all entries if `addresses`
+    /// is empty, see [`Mempool::get_entries`]
+    pub fn get_mempool_entries(
+        &self,
+        include_orphan_pool: bool,
+        filter_transaction_pool: bool,
+        addresses: &[Address],
+    ) -> MiningManagerResult<Vec<MempoolEntry>> {
+        let script_public_keys = addresses.iter().map(pay_to_address_script).collect::<MiningManagerResult<Vec<_>>>()?;
+        Ok(self.mempool.read().unwrap().get_entries(include_orphan_pool, filter_transaction_pool, &script_public_keys))
+    }
+
+    /// Returns the non-empty buckets of the mempool fee-rate histogram, in descending fee-rate order
+    pub fn get_fee_rate_histogram(&self) -> Vec<FeeRateHistogramBucket> {
+        self.mempool.read().unwrap().fee_rate_histogram()
+    }
+
+    /// Returns a read guard to the mempool
+    pub fn mempool(&self) -> std::sync::RwLockReadGuard<'_, Mempool> {
+        self.mempool.read().unwrap()
+    }
+
+    /// Returns a write guard to the mempool
+    pub fn mempool_mut(&self) -> std::sync::RwLockWriteGuard<'_, Mempool> {
+        self.mempool.write().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{errors::MiningManagerError, testutils::consensus_mock::ConsensusMock};
+    use addresses::Prefix;
+    use consensus_core::{
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{ScriptPublicKey, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+    };
+    use hashes::Hash;
+    use std::sync::Arc;
+
+    fn create_transaction(outpoint: TransactionOutpoint, output_value: u64) -> Transaction {
+        Transaction::new(
+            0,
+            vec![TransactionInput::new(outpoint, vec![], 0, 0)],
+            vec![TransactionOutput::new(output_value, ScriptPublicKey::default())],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        )
+    }
+
+    #[test]
+    fn get_block_template_test() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let outpoints: Vec<_> = (1..=3).map(|i| TransactionOutpoint::new(Hash::from_u64_word(i), 0)).collect();
+        for outpoint in outpoints.iter() {
+            consensus.add_utxo(*outpoint, UtxoEntry::new(1000, ScriptPublicKey::default(), 0, false));
+        }
+        let mining_manager = MiningManager::new(consensus.clone(), MempoolConfig::default(), Duration::ZERO);
+        let transactions: Vec<_> =
+            outpoints.iter().zip([100, 300, 200]).map(|(outpoint, fee)| create_transaction(*outpoint, 1000 - fee)).collect();
+        for tx in transactions.iter() {
+            mining_manager.mempool_mut().validate_and_insert_transaction(tx.clone(), Priority::Low, false).unwrap();
+        }
+
+        // The transaction with the middle fee rate is invalidated by a virtual change not yet reported to the mempool
+        consensus.remove_utxo(&outpoints[2]);
+        let miner_address = Address { prefix: Prefix::Devnet, payload: vec![7; 32], version: 0 };
+        let template = mining_manager.get_block_template(&miner_address, vec![1, 2]).unwrap();
+
+        let template_ids: Vec<_> = template.block.transactions.iter().map(|tx| tx.id()).collect();
+        assert_eq!(template_ids, vec![transactions[1].id(), transactions[0].id()]);
+        assert!(!mining_manager.mempool().has_transaction(&transactions[2].id()));
+        assert_eq!(template.miner_data.extra_data, vec![1, 2]);
+        let expected_script: Vec<u8> = [&[0x20][..], &[7u8; 32], &[0xac]].concat();
+        assert_eq!(template.miner_data.script_public_key.script(), expected_script.as_slice());
+
+        let unsupported_address = Address { prefix: Prefix::Devnet, payload: vec![7; 20], version: 0 };
+        assert!(matches!(
+            mining_manager.get_block_template(&unsupported_address, vec![]),
+            Err(MiningManagerError::UnsupportedAddress(0, 20))
+        ));
+    }
+
+    #[test]
+    fn submit_transaction_test() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let outpoints: Vec<_> = (1..=2).map(|i| TransactionOutpoint::new(Hash::from_u64_word(i), 0)).collect();
+        for outpoint in outpoints.iter() {
+            consensus.add_utxo(*outpoint, UtxoEntry::new(1000, ScriptPublicKey::default(), 0, false));
+        }
+        let mining_manager = MiningManager::new(consensus.clone(), MempoolConfig::default(), Duration::ZERO);
+        let relayed = create_transaction(outpoints[0], 900);
+        let submitted = create_transaction(outpoints[1], 900);
+        mining_manager.mempool_mut().validate_and_insert_transaction(relayed, Priority::Low, false).unwrap();
+        assert_eq!(mining_manager.submit_transaction(submitted.clone(), false).unwrap(), vec![submitted.id()]);
+
+        // Submitted transactions are high priority, hence revalidated and rebroadcast
+        assert_eq!(mining_manager.mempool_mut().revalidate_high_priority_transactions(), vec![submitted.id()]);
+    }
+
+    #[test]
+    fn block_template_cache_test() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let outpoints: Vec<_> = (1..=2).map(|i| TransactionOutpoint::new(Hash::from_u64_word(i), 0)).collect();
+        for outpoint in outpoints.iter() {
+            consensus.add_utxo(*outpoint, UtxoEntry::new(1000, ScriptPublicKey::default(), 0, false));
+        }
+        let mining_manager = MiningManager::new(consensus.clone(), MempoolConfig::default(), Duration::from_secs(3600));
+        let transactions: Vec<_> = outpoints.iter().map(|outpoint| create_transaction(*outpoint, 900)).collect();
+        let miner_address = Address { prefix: Prefix::Devnet, payload: vec![7; 32], version: 0 };
+        let template_ids = |address: &Address| -> Vec<_> {
+            mining_manager.get_block_template(address, vec![]).unwrap().block.transactions.iter().map(|tx| tx.id()).collect()
+        };
+
+        mining_manager.mempool_mut().validate_and_insert_transaction(transactions[0].clone(), Priority::Low, false).unwrap();
+        assert_eq!(template_ids(&miner_address), vec![transactions[0].id()]);
+
+        // Neither the virtual parents nor the candidates changed, so the cached template is reused as is
+        consensus.remove_utxo(&outpoints[0]);
+        assert_eq!(template_ids(&miner_address), vec![transactions[0].id()]);
+
+        // New virtual parents invalidate the cached template, and the rebuild evicts the invalidated transaction
+        consensus.set_virtual_parents(vec![Hash::from_u64_word(100)]);
+        assert!(template_ids(&miner_address).is_empty());
+
+        // So does a change of the candidate set
+        mining_manager.mempool_mut().validate_and_insert_transaction(transactions[1].clone(), Priority::Low, false).unwrap();
+        assert_eq!(template_ids(&miner_address), vec![transactions[1].id()]);
+
+        // A template cached for another miner is never served
+        let other_address = Address { prefix: Prefix::Devnet, payload: vec![8; 32], version: 0 };
+        let template = mining_manager.get_block_template(&other_address, vec![]).unwrap();
+        assert_eq!(template.miner_data.script_public_key, pay_to_address_script(&other_address).unwrap());
+
+        // Explicit clearing forces a rebuild
+        consensus.remove_utxo(&outpoints[1]);
+        assert_eq!(template_ids(&other_address), vec![transactions[1].id()]);
+        mining_manager.clear_block_template();
+        assert!(template_ids(&other_address).is_empty());
+    }
+}
@@ -0,0 +1,791 @@
+use self::{
+    model::{MempoolEntry, MempoolTransaction, Priority},
+    orphan_pool::{OrphanPool, OrphanTransaction},
+    transactions_pool::TransactionsPool,
+};
+use crate::{
+    errors::{RuleError, RuleResult},
+    fee_estimation::{estimate_fee_rates, FeeRateBuckets, FeeRateEstimations, FeeRateHistogramBucket, RecentFeeRates},
+};
+use consensus_core::{
+    api::DynConsensus,
+    errors::tx::TxRuleError,
+    tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionId},
+};
+use std::collections::{HashMap, HashSet};
+
+pub mod model;
+mod orphan_pool;
+mod transactions_pool;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The maximum number of transactions the mempool can hold
+    pub maximum_transaction_count: usize,
+    /// The maximum number of orphan transactions the orphan pool can hold
+    pub maximum_orphan_transaction_count: usize,
+    /// The DAA score interval after which an orphan whose parents did not arrive is expired
+    pub orphan_expire_interval_daa_score: u64,
+    /// Whether high priority (locally submitted) transactions are given a bounded lane in mempool admission and
+    /// in block templates, so that they are not starved by relayed transactions during congestion
+    pub high_priority_lane_enabled: bool,
+    /// The number of high priority transactions admitted beyond `maximum_transaction_count`
+    pub high_priority_lane_transaction_count: usize,
+    /// The percentage of the block template mass reserved for high priority transactions, which are selected
+    /// into it ahead of low priority ones regardless of their fee rate
+    pub high_priority_lane_mass_percent: u64,
+    /// Whether the still valid high priority transactions are periodically rebroadcast to peers
+    pub high_priority_rebroadcast_enabled: bool,
+    /// The DAA score interval after which a high priority transaction is demoted to low priority, no longer being
+    /// rebroadcast nor selected through the high priority lane
+    pub high_priority_expire_interval_daa_score: u64,
+}
+
+impl Config {
+    /// Creates a config with the default high priority lane
+    pub fn new(
+        maximum_transaction_count: usize,
+        maximum_orphan_transaction_count: usize,
+        orphan_expire_interval_daa_score: u64,
+    ) -> Self {
+        Self { maximum_transaction_count, maximum_orphan_transaction_count, orphan_expire_interval_daa_score, ..Default::default() }
+    }
+
+    /// Sets whether the high priority lane is enabled
+    pub fn with_high_priority_lane(self, enabled: bool) -> Self {
+        Self { high_priority_lane_enabled: enabled, ..self }
+    }
+
+    /// Sets whether high priority transactions are periodically rebroadcast
+    pub fn with_high_priority_rebroadcast(self, enabled: bool) -> Self {
+        Self { high_priority_rebroadcast_enabled: enabled, ..self }
+    }
+
+    /// Returns whether a high priority transaction added at `added_at_daa_score` expired its priority
+    pub fn is_high_priority_expired(&self, added_at_daa_score: u64, virtual_daa_score: u64) -> bool {
+        virtual_daa_score.saturating_sub(added_at_daa_score) > self.high_priority_expire_interval_daa_score
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            maximum_transaction_count: 1_000_000,
+            maximum_orphan_transaction_count: 600,
+            orphan_expire_interval_daa_score: 60,
+            high_priority_lane_enabled: true,
+            high_priority_lane_transaction_count: 1_000,
+            high_priority_lane_mass_percent: 10,
+            high_priority_rebroadcast_enabled: true,
+            high_priority_expire_interval_daa_score: 86_400,
+        }
+    }
+}
+
+/// The outcome of validating a transaction against the virtual UTXO state and the mempool
+enum TransactionValidation {
+    Validated(MutableTransaction),
+    Orphan(Transaction),
+}
+
+/// The mempool holds transactions which were validated against the virtual UTXO state
+/// (possibly extended by the outputs of other mempool transactions), and provides
+/// candidate transactions for block template building. Transactions spending unknown
+/// outpoints are kept in an orphan pool until their parents arrive or until they expire.
+///
+/// The mempool is not internally synchronized; callers are expected to wrap it with a lock.
+pub struct Mempool {
+    consensus: DynConsensus,
+    config: Config,
+    pool: TransactionsPool,
+    orphan_pool: OrphanPool,
+    recent_fee_rates: RecentFeeRates,
+    /// The high priority transactions which left the mempool by being accepted by a block, along with the DAA score
+    /// they were added at, so that they keep their priority if a reorg returns them to the mempool. Entries are
+    /// dropped once the transactions would have expired.
+    accepted_high_priority: HashMap<TransactionId, u64>,
+}
+
+impl Mempool {
+    pub fn new(consensus: DynConsensus, config: Config) -> Self {
+        let orphan_pool = OrphanPool::new(config.maximum_orphan_transaction_count, config.orphan_expire_interval_daa_score);
+        Self {
+            consensus,
+            config,
+            pool: TransactionsPool::default(),
+            orphan_pool,
+            recent_fee_rates: RecentFeeRates::default(),
+            accepted_high_priority: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Validates the transaction against the virtual UTXO state and the current mempool content, and
+    /// inserts it to the mempool on success. If the transaction spends unknown outpoints, it is added
+    /// to the orphan pool when `allow_orphan` is set and rejected otherwise.
+    ///
+    /// Returns the ids of all transactions which were inserted to the mempool as a result, i.e., the
+    /// transaction itself followed by any orphans promoted thanks to it.
+    pub fn validate_and_insert_transaction(
+        &mut self,
+        transaction: Transaction,
+        priority: Priority,
+        allow_orphan: bool,
+    ) -> RuleResult<Vec<TransactionId>> {
+        let id = transaction.id();
+        if self.orphan_pool.has(&id) {
+            return Err(RuleError::RejectDuplicateOrphan(id));
+        }
+        match self.validate_transaction(transaction, priority)? {
+            TransactionValidation::Validated(mtx) => {
+                self.insert_validated(mtx, priority);
+                let mut accepted = vec![id];
+                accepted.extend(self.process_orphans_after_accepted(vec![id]));
+                Ok(accepted)
+            }
+            TransactionValidation::Orphan(_) if !allow_orphan => Err(RuleError::RejectDisallowedOrphan(id)),
+            TransactionValidation::Orphan(tx) => {
+                let virtual_daa_score = self.consensus.clone().get_virtual_daa_score();
+                self.orphan_pool.try_add(OrphanTransaction::new(tx, priority, virtual_daa_score))?;
+                Ok(vec![])
+            }
+        }
+    }
+
+    fn validate_transaction(&self, transaction: Transaction, priority: Priority) -> RuleResult<TransactionValidation> {
+        let id = transaction.id();
+        if transaction.is_coinbase() {
+            return Err(RuleError::RejectCoinbase(id));
+        }
+        if self.pool.has(&id) {
+            return Err(RuleError::RejectDuplicate(id));
+        }
+        for input in transaction.inputs.iter() {
+            if let Some(redeemer) = self.pool.get_redeemer(&input.previous_outpoint) {
+                return Err(RuleError::RejectDoubleSpendInMempool(input.previous_outpoint, redeemer));
+            }
+        }
+        if self.pool.len() >= self.maximum_transaction_count(priority) {
+            return Err(RuleError::RejectMempoolIsFull(self.pool.len()));
+        }
+
+        let virtual_daa_score = self.consensus.clone().get_virtual_daa_score();
+        let mut mtx = MutableTransaction::new(transaction);
+        // Outputs of mempool transactions are populated first, consensus then populates
+        // the remaining entries from the virtual UTXO set and fully validates the transaction
+        self.pool.populate_entries(&mut mtx, virtual_daa_score);
+        match self.consensus.clone().validate_mempool_transaction(&mut mtx) {
+            Ok(()) => Ok(TransactionValidation::Validated(mtx)),
+            Err(TxRuleError::MissingTxOutpoints) => Ok(TransactionValidation::Orphan(mtx.tx)),
+            Err(err) => Err(RuleError::RejectTxRule(id, err)),
+        }
+    }
+
+    /// The high priority lane lets a mempool filled by relayed transactions still admit a bounded number of local ones
+    fn maximum_transaction_count(&self, priority: Priority) -> usize {
+        match priority {
+            Priority::High if self.config.high_priority_lane_enabled => {
+                self.config.maximum_transaction_count + self.config.high_priority_lane_transaction_count
+            }
+            _ => self.config.maximum_transaction_count,
+        }
+    }
+
+    fn insert_validated(&mut self, mtx: MutableTransaction, priority: Priority) {
+        let virtual_daa_score = self.consensus.clone().get_virtual_daa_score();
+        self.pool.insert(MempoolTransaction::new(mtx, priority, virtual_daa_score));
+    }
+
+    /// Revalidates a mempool transaction against the current virtual UTXO state and the outputs of its in-pool parents
+    fn revalidate_transaction(&self, id: &TransactionId) -> bool {
+        let virtual_daa_score = self.consensus.clone().get_virtual_daa_score();
+        let mut mtx = MutableTransaction::new(self.pool.get(id).unwrap().tx().clone());
+        self.pool.populate_entries(&mut mtx, virtual_daa_score);
+        self.consensus.clone().validate_mempool_transaction(&mut mtx).is_ok()
+    }
+
+    /// Attempts to promote orphans spending outputs of the `accepted` transactions (whether accepted to the
+    /// mempool or by a block), and recursively orphans spending outputs of promoted ones. Orphans still
+    /// missing other parents are returned to the orphan pool, invalid ones are dropped.
+    fn process_orphans_after_accepted(&mut self, mut accepted: Vec<TransactionId>) -> Vec<TransactionId> {
+        let mut promoted = Vec::new();
+        while let Some(parent) = accepted.pop() {
+            for orphan in self.orphan_pool.remove_redeemers_of(&parent) {
+                let (priority, added_at_daa_score) = (orphan.priority, orphan.added_at_daa_score);
+                match self.validate_transaction(orphan.tx, priority) {
+                    Ok(TransactionValidation::Validated(mtx)) => {
+                        let id = mtx.tx.id();
+                        self.insert_validated(mtx, priority);
+                        promoted.push(id);
+                        accepted.push(id);
+                    }
+                    Ok(TransactionValidation::Orphan(tx)) => {
+                        // Failing to re-add is not an error of the caller, the orphan is simply dropped
+                        let _ = self.orphan_pool.try_add(OrphanTransaction::new(tx, priority, added_at_daa_score));
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+        promoted
+    }
+
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pool.len() == 0
+    }
+
+    pub fn orphan_count(&self) -> usize {
+        self.orphan_pool.len()
+    }
+
+    pub fn has_transaction(&self, id: &TransactionId) -> bool {
+        self.pool.has(id)
+    }
+
+    pub fn has_orphan(&self, id: &TransactionId) -> bool {
+        self.orphan_pool.has(id)
+    }
+
+    pub fn get_transaction(&self, id: &TransactionId) -> Option<&MutableTransaction> {
+        self.pool.get(id).map(|tx| &tx.mtx)
+    }
+
+    /// Returns the entry of the transaction with `id`, looked up in the transaction pool unless `filter_transaction_pool`
+    /// is set, and in the orphan pool if `include_orphan_pool` is set
+    pub fn get_entry(&self, id: &TransactionId, include_orphan_pool: bool, filter_transaction_pool: bool) -> Option<MempoolEntry> {
+        let entry = if filter_transaction_pool { None } else { self.pool.get(id).map(MempoolEntry::from) };
+        entry.or_else(|| if include_orphan_pool { self.orphan_pool.get(id).map(MempoolEntry::from) } else { None })
+    }
+
+    /// Returns the entries of the transaction pool unless `filter_transaction_pool` is set, followed by the ones of
+    /// the orphan pool if `include_orphan_pool` is set. If `script_public_keys` is not empty, only entries referencing
+    /// any of them are returned, see [`MempoolEntry::references_any`]
+    pub fn get_entries(
+        &self,
+        include_orphan_pool: bool,
+        filter_transaction_pool: bool,
+        script_public_keys: &[ScriptPublicKey],
+    ) -> Vec<MempoolEntry> {
+        let pool = self.pool.iter().filter(|_| !filter_transaction_pool).map(MempoolEntry::from);
+        let orphans = self.orphan_pool.iter().filter(|_| include_orphan_pool).map(MempoolEntry::from);
+        pool.chain(orphans).filter(|entry| script_public_keys.is_empty() || entry.references_any(script_public_keys)).collect()
+    }
+
+    /// Removes the transaction from the mempool. If `remove_redeemers` is set, all transactions
+    /// spending (directly or indirectly) outputs of the removed transaction are removed as well
+    pub fn remove_transaction(&mut self, id: &TransactionId, remove_redeemers: bool) {
+        if remove_redeemers {
+            for descendant in self.pool.descendants(id) {
+                self.pool.remove(&descendant);
+            }
+        }
+        self.pool.remove(id);
+    }
+
+    /// Updates the mempool following the acceptance of `block_transactions`: transactions included
+    /// in the block are removed (their children remain valid and may become ready) and their fee
+    /// rates are recorded for fee estimation, transactions
+    /// double spending any of the block inputs are removed along with their redeemers, orphans
+    /// spending block outputs are promoted and expired orphans are dropped.
+    ///
+    /// Returns the ids of orphans promoted to the mempool.
+    pub fn handle_new_block_transactions(&mut self, block_transactions: &[Transaction]) -> Vec<TransactionId> {
+        for transaction in block_transactions.iter().filter(|tx| !tx.is_coinbase()) {
+            // The fee of transactions which never entered the mempool is unknown, hence only mempool ones are recorded
+            if let Some(accepted) = self.pool.get(&transaction.id()) {
+                self.recent_fee_rates.record(accepted.fee_rate_key().fee_rate());
+                if accepted.priority == Priority::High {
+                    self.accepted_high_priority.insert(accepted.id(), accepted.added_at_daa_score);
+                }
+            }
+            self.remove_transaction(&transaction.id(), false);
+            for input in transaction.inputs.iter() {
+                if let Some(redeemer) = self.pool.get_redeemer(&input.previous_outpoint) {
+                    self.remove_transaction(&redeemer, true);
+                }
+                if let Some(orphan) = self.orphan_pool.get_redeemer(&input.previous_outpoint) {
+                    self.orphan_pool.remove(&orphan);
+                }
+            }
+        }
+        let promoted = self.process_orphans_after_accepted(block_transactions.iter().map(|tx| tx.id()).collect());
+        let virtual_daa_score = self.consensus.clone().get_virtual_daa_score();
+        self.orphan_pool.expire(virtual_daa_score);
+        self.accepted_high_priority
+            .retain(|_, added_at_daa_score| !self.config.is_high_priority_expired(*added_at_daa_score, virtual_daa_score));
+        promoted
+    }
+
+    /// Updates the mempool following a change of the virtual selected parent chain. Transactions accepted
+    /// by chain blocks which were removed from the chain are returned to the mempool if still valid (with
+    /// low priority, unless they were high priority and did not expire yet), transactions accepted by the added chain blocks are handled as by
+    /// [`Self::handle_new_block_transactions`], and transactions spending UTXOs which no longer exist
+    /// are removed along with their redeemers.
+    ///
+    /// Returns the ids of transactions inserted to the mempool as a result, i.e., returned transactions
+    /// and promoted orphans.
+    pub fn handle_virtual_chain_change(
+        &mut self,
+        removed_chain_transactions: &[Transaction],
+        added_chain_transactions: &[Transaction],
+    ) -> Vec<TransactionId> {
+        let mut inserted = self.handle_new_block_transactions(added_chain_transactions);
+
+        let added: HashSet<TransactionId> = added_chain_transactions.iter().map(|tx| tx.id()).collect();
+        for transaction in removed_chain_transactions.iter().filter(|tx| !tx.is_coinbase() && !added.contains(&tx.id())) {
+            let (priority, added_at_daa_score) = match self.accepted_high_priority.get(&transaction.id()) {
+                Some(&added_at_daa_score) => (Priority::High, added_at_daa_score),
+                None => (Priority::Low, self.consensus.clone().get_virtual_daa_score()),
+            };
+            // Transactions which are now invalid, orphan or already in the mempool are simply skipped
+            if let Ok(TransactionValidation::Validated(mtx)) = self.validate_transaction(transaction.clone(), priority) {
+                inserted.push(mtx.tx.id());
+                self.accepted_high_priority.remove(&mtx.tx.id());
+                self.pool.insert(MempoolTransaction::new(mtx, priority, added_at_daa_score));
+            }
+        }
+
+        // Only transactions with no in-pool parents spend UTXOs directly, and the validity of
+        // all other transactions follows from the validity of their ancestors
+        for id in self.pool.ready_ids() {
+            if self.pool.has(&id) && !self.revalidate_transaction(&id) {
+                self.remove_transaction(&id, true);
+            }
+        }
+        inserted.retain(|id| self.pool.has(id));
+        inserted
+    }
+
+    /// Revalidates all high priority transactions against the current virtual UTXO state, removing invalid
+    /// ones along with their redeemers, and demotes the ones older than the high priority expire interval
+    /// to low priority. Returns the ids of the still valid high priority transactions, which are expected
+    /// to be rebroadcast.
+    pub fn revalidate_high_priority_transactions(&mut self) -> Vec<TransactionId> {
+        let virtual_daa_score = self.consensus.clone().get_virtual_daa_score();
+        let mut valid = Vec::new();
+        for id in self.pool.ids_by_priority(Priority::High) {
+            // The transaction might have been removed as a redeemer of a previously revalidated one
+            let added_at_daa_score = match self.pool.get(&id) {
+                Some(transaction) => transaction.added_at_daa_score,
+                None => continue,
+            };
+            if self.config.is_high_priority_expired(added_at_daa_score, virtual_daa_score) {
+                self.pool.set_priority(&id, Priority::Low);
+            } else if self.revalidate_transaction(&id) {
+                valid.push(id);
+            } else {
+                self.remove_transaction(&id, true);
+            }
+        }
+        valid.retain(|id| self.pool.has(id));
+        valid
+    }
+
+    /// Returns the transactions which can be included in a block built on top of the current virtual,
+    /// i.e., transactions with no in-mempool parents, ordered by fee rate (highest first)
+    pub fn block_candidate_transactions(&self) -> impl Iterator<Item = &MutableTransaction> {
+        self.block_candidates().map(|tx| &tx.mtx)
+    }
+
+    /// Same as [`Self::block_candidate_transactions`], along with the priority of each transaction
+    pub fn block_candidates(&self) -> impl Iterator<Item = &MempoolTransaction> {
+        self.pool.ready_by_fee_rate()
+    }
+
+    /// Estimates the fee rates required for inclusion within each of the `buckets` targets, based on the backlog of
+    /// ready mempool transactions and on the fee rates of recently accepted mempool transactions
+    pub fn estimate_fee_rates(&self, buckets: &FeeRateBuckets, max_block_mass: u64) -> FeeRateEstimations {
+        estimate_fee_rates(self.pool.ready_fee_rate_keys(), &self.recent_fee_rates, buckets, max_block_mass)
+    }
+
+    /// Returns the non-empty buckets of the fee-rate histogram of all mempool transactions, in descending fee-rate order
+    pub fn fee_rate_histogram(&self) -> Vec<FeeRateHistogramBucket> {
+        self.pool.fee_rate_histogram().buckets()
+    }
+
+    /// Returns a revision number of the block candidate transactions set, which changes whenever
+    /// a transaction is added to or removed from the set
+    pub fn block_candidates_revision(&self) -> u64 {
+        self.pool.ready_revision()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fee_estimation::FeeRateBucket;
+    use crate::testutils::consensus_mock::ConsensusMock;
+    use consensus_core::{
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{scriptvec, ScriptPublicKey, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+    };
+    use hashes::Hash;
+    use std::sync::Arc;
+
+    fn create_transaction(outpoints: &[TransactionOutpoint], output_values: &[u64]) -> Transaction {
+        Transaction::new(
+            0,
+            outpoints.iter().map(|outpoint| TransactionInput::new(*outpoint, vec![], 0, 0)).collect(),
+            output_values.iter().map(|value| TransactionOutput::new(*value, ScriptPublicKey::default())).collect(),
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        )
+    }
+
+    fn setup(utxo_count: u64, utxo_amount: u64) -> (Mempool, Arc<ConsensusMock>, Vec<TransactionOutpoint>) {
+        let consensus = Arc::new(ConsensusMock::new());
+        let outpoints: Vec<TransactionOutpoint> =
+            (0..utxo_count).map(|i| TransactionOutpoint::new(Hash::from_u64_word(i + 1), 0)).collect();
+        for outpoint in outpoints.iter() {
+            consensus.add_utxo(*outpoint, UtxoEntry::new(utxo_amount, ScriptPublicKey::default(), 0, false));
+        }
+        (Mempool::new(consensus.clone(), Config::new(1_000_000, 2, 10)), consensus, outpoints)
+    }
+
+    #[test]
+    fn insert_and_double_spend_test() {
+        let (mut mempool, _, outpoints) = setup(2, 1000);
+
+        let tx = create_transaction(&outpoints[..1], &[900]);
+        mempool.validate_and_insert_transaction(tx.clone(), Priority::Low, false).unwrap();
+        assert!(mempool.has_transaction(&tx.id()));
+        assert_eq!(mempool.get_transaction(&tx.id()).unwrap().calculated_fee, Some(100));
+
+        assert!(matches!(mempool.validate_and_insert_transaction(tx, Priority::Low, false), Err(RuleError::RejectDuplicate(_))));
+        let double_spend = create_transaction(&outpoints[..1], &[800]);
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(double_spend, Priority::Low, false),
+            Err(RuleError::RejectDoubleSpendInMempool(outpoint, _)) if outpoint == outpoints[0]
+        ));
+
+        let missing = create_transaction(&[TransactionOutpoint::new(Hash::from_u64_word(100), 0)], &[1]);
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(missing, Priority::Low, false),
+            Err(RuleError::RejectDisallowedOrphan(_))
+        ));
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn chained_transactions_test() {
+        let (mut mempool, _, outpoints) = setup(1, 1000);
+
+        let parent = create_transaction(&outpoints, &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(parent.id(), 0)], &[500]);
+        mempool.validate_and_insert_transaction(parent.clone(), Priority::Low, false).unwrap();
+        mempool.validate_and_insert_transaction(child.clone(), Priority::Low, false).unwrap();
+        assert_eq!(mempool.get_transaction(&child.id()).unwrap().calculated_fee, Some(400));
+
+        // Only the parent can be included in a block on top of virtual
+        let candidates: Vec<_> = mempool.block_candidate_transactions().map(|mtx| mtx.tx.id()).collect();
+        assert_eq!(candidates, vec![parent.id()]);
+
+        // Once the parent is accepted by a block, the child becomes a candidate
+        mempool.handle_new_block_transactions(&[parent]);
+        let candidates: Vec<_> = mempool.block_candidate_transactions().map(|mtx| mtx.tx.id()).collect();
+        assert_eq!(candidates, vec![child.id()]);
+    }
+
+    #[test]
+    fn remove_with_redeemers_test() {
+        let (mut mempool, _, outpoints) = setup(1, 1000);
+
+        let parent = create_transaction(&outpoints, &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(parent.id(), 0)], &[800]);
+        let grandchild = create_transaction(&[TransactionOutpoint::new(child.id(), 0)], &[700]);
+        for tx in [parent.clone(), child, grandchild] {
+            mempool.validate_and_insert_transaction(tx, Priority::Low, false).unwrap();
+        }
+        mempool.remove_transaction(&parent.id(), true);
+        assert!(mempool.is_empty());
+        assert_eq!(mempool.block_candidate_transactions().count(), 0);
+    }
+
+    #[test]
+    fn block_double_spend_test() {
+        let (mut mempool, _, outpoints) = setup(1, 1000);
+
+        let tx = create_transaction(&outpoints, &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(tx.id(), 0)], &[800]);
+        mempool.validate_and_insert_transaction(tx, Priority::Low, false).unwrap();
+        mempool.validate_and_insert_transaction(child, Priority::Low, false).unwrap();
+
+        // A block spending the same outpoint invalidates the mempool transaction and its redeemers
+        let conflicting = create_transaction(&outpoints, &[950]);
+        mempool.handle_new_block_transactions(&[conflicting]);
+        assert!(mempool.is_empty());
+    }
+
+    #[test]
+    fn fee_rate_ordering_test() {
+        let (mut mempool, _, outpoints) = setup(3, 1000);
+
+        let fees = [100, 300, 200];
+        let txs: Vec<_> = outpoints.iter().zip(fees).map(|(outpoint, fee)| create_transaction(&[*outpoint], &[1000 - fee])).collect();
+        for tx in txs.iter() {
+            mempool.validate_and_insert_transaction(tx.clone(), Priority::Low, false).unwrap();
+        }
+        let candidates: Vec<_> = mempool.block_candidate_transactions().map(|mtx| mtx.calculated_fee.unwrap()).collect();
+        assert_eq!(candidates, vec![300, 200, 100]);
+    }
+
+    #[test]
+    fn entries_test() {
+        let (mut mempool, _, outpoints) = setup(1, 1000);
+
+        let mut tx = create_transaction(&outpoints, &[900]);
+        let script_public_key = ScriptPublicKey::new(0, scriptvec![1, 2, 3]);
+        tx.outputs[0].script_public_key = script_public_key.clone();
+        tx.finalize();
+        let orphan = create_transaction(&[TransactionOutpoint::new(Hash::from_u64_word(100), 0)], &[1]);
+        mempool.validate_and_insert_transaction(tx.clone(), Priority::Low, false).unwrap();
+        mempool.validate_and_insert_transaction(orphan.clone(), Priority::Low, true).unwrap();
+
+        let entry = mempool.get_entry(&tx.id(), false, false).unwrap();
+        assert_eq!((entry.fee, entry.is_orphan), (100, false));
+        assert!(mempool.get_entry(&tx.id(), true, true).is_none());
+        assert!(mempool.get_entry(&orphan.id(), false, false).is_none());
+        assert!(mempool.get_entry(&orphan.id(), true, true).unwrap().is_orphan);
+
+        let ids = |entries: Vec<MempoolEntry>| entries.into_iter().map(|entry| entry.transaction.id()).collect::<Vec<_>>();
+        assert_eq!(ids(mempool.get_entries(false, false, &[])), vec![tx.id()]);
+        assert_eq!(ids(mempool.get_entries(true, true, &[])), vec![orphan.id()]);
+        assert_eq!(ids(mempool.get_entries(true, false, &[script_public_key])), vec![tx.id()]);
+        // Both transactions spend from or pay to the default script public key
+        assert_eq!(ids(mempool.get_entries(true, false, &[ScriptPublicKey::default()])).len(), 2);
+    }
+
+    #[test]
+    fn fee_estimation_test() {
+        let (mut mempool, _, outpoints) = setup(3, 1000);
+
+        let fees = [100, 300, 200];
+        let txs: Vec<_> = outpoints.iter().zip(fees).map(|(outpoint, fee)| create_transaction(&[*outpoint], &[1000 - fee])).collect();
+        for tx in txs.iter() {
+            mempool.validate_and_insert_transaction(tx.clone(), Priority::Low, false).unwrap();
+        }
+        let mass = mempool.get_transaction(&txs[0].id()).unwrap().calculated_mass.unwrap();
+        let buckets = FeeRateBuckets {
+            priority: FeeRateBucket::new(u64::MAX, 100),
+            normal: FeeRateBucket::new(u64::MAX, 0),
+            low: FeeRateBucket::new(u64::MAX, 0),
+        };
+        assert_eq!(mempool.estimate_fee_rates(&buckets, 1_000_000).priority, 0.0);
+
+        // Only the fee rates of accepted mempool transactions are recorded
+        mempool.handle_new_block_transactions(&txs[..2]);
+        let estimations = mempool.estimate_fee_rates(&buckets, 1_000_000);
+        assert_eq!(estimations.priority, 300.0 / mass as f64);
+        assert_eq!(estimations.normal, 100.0 / mass as f64);
+
+        // A backlog exceeding a single block determines the priority estimation
+        let buckets = FeeRateBuckets { priority: FeeRateBucket::new(0, 0), ..buckets };
+        assert_eq!(mempool.estimate_fee_rates(&buckets, 1_000_000).priority, 200.0 / mass as f64);
+    }
+
+    #[test]
+    fn fee_rate_histogram_test() {
+        let (mut mempool, _, outpoints) = setup(1, 1000);
+
+        // Chained transactions are part of the histogram although they are not block candidates yet
+        let parent = create_transaction(&outpoints, &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(parent.id(), 0)], &[500]);
+        mempool.validate_and_insert_transaction(parent.clone(), Priority::Low, false).unwrap();
+        mempool.validate_and_insert_transaction(child.clone(), Priority::Low, false).unwrap();
+        let histogram = mempool.fee_rate_histogram();
+        assert_eq!(histogram.iter().map(|bucket| bucket.transaction_count).sum::<u64>(), 2);
+
+        mempool.handle_new_block_transactions(&[parent]);
+        let child_mass = mempool.get_transaction(&child.id()).unwrap().calculated_mass.unwrap();
+        let histogram = mempool.fee_rate_histogram();
+        assert_eq!(histogram.len(), 1);
+        assert_eq!((histogram[0].transaction_count, histogram[0].total_mass), (1, child_mass));
+
+        mempool.remove_transaction(&child.id(), false);
+        assert!(mempool.fee_rate_histogram().is_empty());
+    }
+
+    #[test]
+    fn orphan_promotion_test() {
+        let (mut mempool, consensus, outpoints) = setup(1, 1000);
+
+        let parent = create_transaction(&outpoints, &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(parent.id(), 0)], &[800]);
+        let grandchild = create_transaction(&[TransactionOutpoint::new(child.id(), 0)], &[700]);
+
+        // Orphans are accepted into the orphan pool only when allowed
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(grandchild.clone(), Priority::Low, false),
+            Err(RuleError::RejectDisallowedOrphan(_))
+        ));
+        assert!(mempool.validate_and_insert_transaction(grandchild.clone(), Priority::Low, true).unwrap().is_empty());
+        assert!(mempool.validate_and_insert_transaction(child.clone(), Priority::Low, true).unwrap().is_empty());
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(child.clone(), Priority::Low, true),
+            Err(RuleError::RejectDuplicateOrphan(_))
+        ));
+        assert_eq!(mempool.orphan_count(), 2);
+
+        // A block accepting the parent promotes the whole chain of orphans
+        consensus.add_utxo(TransactionOutpoint::new(parent.id(), 0), UtxoEntry::new(900, ScriptPublicKey::default(), 1, false));
+        let promoted = mempool.handle_new_block_transactions(&[parent]);
+        assert_eq!(promoted, vec![child.id(), grandchild.id()]);
+        assert_eq!(mempool.orphan_count(), 0);
+        assert!(mempool.has_transaction(&child.id()) && mempool.has_transaction(&grandchild.id()));
+    }
+
+    #[test]
+    fn orphan_promotion_by_mempool_insertion_test() {
+        let (mut mempool, _, outpoints) = setup(1, 1000);
+
+        let parent = create_transaction(&outpoints, &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(parent.id(), 0)], &[800]);
+        mempool.validate_and_insert_transaction(child.clone(), Priority::Low, true).unwrap();
+        let accepted = mempool.validate_and_insert_transaction(parent.clone(), Priority::Low, true).unwrap();
+        assert_eq!(accepted, vec![parent.id(), child.id()]);
+        assert!(!mempool.has_orphan(&child.id()));
+    }
+
+    #[test]
+    fn orphan_limits_test() {
+        let (mut mempool, consensus, _) = setup(0, 0);
+
+        let orphans: Vec<_> =
+            (0..3).map(|i| create_transaction(&[TransactionOutpoint::new(Hash::from_u64_word(100 + i), 0)], &[1])).collect();
+
+        // The pool holds at most two orphans, evicting the oldest when full
+        consensus.set_virtual_daa_score(0);
+        mempool.validate_and_insert_transaction(orphans[0].clone(), Priority::Low, true).unwrap();
+        consensus.set_virtual_daa_score(5);
+        mempool.validate_and_insert_transaction(orphans[1].clone(), Priority::Low, true).unwrap();
+        mempool.validate_and_insert_transaction(orphans[2].clone(), Priority::Low, true).unwrap();
+        assert_eq!(mempool.orphan_count(), 2);
+        assert!(!mempool.has_orphan(&orphans[0].id()));
+
+        // Orphans expire once the expire interval has passed
+        consensus.set_virtual_daa_score(15);
+        mempool.handle_new_block_transactions(&[]);
+        assert_eq!(mempool.orphan_count(), 2);
+        consensus.set_virtual_daa_score(16);
+        mempool.handle_new_block_transactions(&[]);
+        assert_eq!(mempool.orphan_count(), 0);
+    }
+
+    #[test]
+    fn virtual_chain_change_test() {
+        let (mut mempool, consensus, outpoints) = setup(2, 1000);
+
+        let parent = create_transaction(&outpoints[..1], &[900]);
+        let child = create_transaction(&[TransactionOutpoint::new(parent.id(), 0)], &[800]);
+        let other = create_transaction(&outpoints[1..], &[900]);
+        for tx in [parent.clone(), child.clone(), other.clone()] {
+            mempool.validate_and_insert_transaction(tx, Priority::Low, false).unwrap();
+        }
+
+        // A chain block accepts the parent
+        consensus.remove_utxo(&outpoints[0]);
+        consensus.add_utxo(TransactionOutpoint::new(parent.id(), 0), UtxoEntry::new(900, ScriptPublicKey::default(), 1, false));
+        mempool.handle_new_block_transactions(&[parent.clone()]);
+        assert!(!mempool.has_transaction(&parent.id()));
+
+        // The block is reorged out of the chain while the UTXO spent by `other` vanishes (e.g., it was
+        // created by another removed chain block)
+        consensus.remove_utxo(&TransactionOutpoint::new(parent.id(), 0));
+        consensus.remove_utxo(&outpoints[1]);
+        consensus.add_utxo(outpoints[0], UtxoEntry::new(1000, ScriptPublicKey::default(), 0, false));
+        let inserted = mempool.handle_virtual_chain_change(&[parent.clone()], &[]);
+        assert_eq!(inserted, vec![parent.id()]);
+        assert!(mempool.has_transaction(&parent.id()) && mempool.has_transaction(&child.id()));
+        assert!(!mempool.has_transaction(&other.id()));
+
+        // The returned parent is linked to its in-pool child, so only the parent is a block candidate
+        let candidates: Vec<_> = mempool.block_candidate_transactions().map(|mtx| mtx.tx.id()).collect();
+        assert_eq!(candidates, vec![parent.id()]);
+    }
+
+    #[test]
+    fn high_priority_lane_admission_test() {
+        let (_, consensus, outpoints) = setup(4, 1000);
+        let config = Config { maximum_transaction_count: 1, high_priority_lane_transaction_count: 1, ..Default::default() };
+        let txs: Vec<_> = outpoints.iter().map(|outpoint| create_transaction(&[*outpoint], &[900])).collect();
+
+        // A full mempool still admits high priority transactions, up to the lane size
+        let mut mempool = Mempool::new(consensus.clone(), config.clone());
+        mempool.validate_and_insert_transaction(txs[0].clone(), Priority::Low, false).unwrap();
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(txs[1].clone(), Priority::Low, false),
+            Err(RuleError::RejectMempoolIsFull(1))
+        ));
+        mempool.validate_and_insert_transaction(txs[1].clone(), Priority::High, false).unwrap();
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(txs[2].clone(), Priority::High, false),
+            Err(RuleError::RejectMempoolIsFull(2))
+        ));
+
+        // Unless the lane is disabled
+        let mut mempool = Mempool::new(consensus, config.with_high_priority_lane(false));
+        mempool.validate_and_insert_transaction(txs[0].clone(), Priority::Low, false).unwrap();
+        assert!(matches!(
+            mempool.validate_and_insert_transaction(txs[3].clone(), Priority::High, false),
+            Err(RuleError::RejectMempoolIsFull(1))
+        ));
+    }
+
+    #[test]
+    fn revalidate_high_priority_transactions_test() {
+        let (mut mempool, consensus, outpoints) = setup(3, 1000);
+
+        let invalidated = create_transaction(&outpoints[..1], &[900]);
+        let invalidated_child = create_transaction(&[TransactionOutpoint::new(invalidated.id(), 0)], &[800]);
+        let valid = create_transaction(&outpoints[1..2], &[900]);
+        let low_priority = create_transaction(&outpoints[2..], &[900]);
+        for tx in [invalidated.clone(), invalidated_child.clone(), valid.clone()] {
+            mempool.validate_and_insert_transaction(tx, Priority::High, false).unwrap();
+        }
+        mempool.validate_and_insert_transaction(low_priority.clone(), Priority::Low, false).unwrap();
+
+        consensus.remove_utxo(&outpoints[0]);
+        consensus.remove_utxo(&outpoints[2]);
+        assert_eq!(mempool.revalidate_high_priority_transactions(), vec![valid.id()]);
+        assert!(!mempool.has_transaction(&invalidated.id()) && !mempool.has_transaction(&invalidated_child.id()));
+        // Low priority transactions are not revalidated
+        assert!(mempool.has_transaction(&low_priority.id()));
+    }
+
+    #[test]
+    fn high_priority_churn_and_expiry_test() {
+        let (mut mempool, consensus, outpoints) = setup(1, 1000);
+        let priority = |mempool: &Mempool| mempool.block_candidates().next().unwrap().priority;
+
+        // A high priority transaction returned to the mempool by a reorg keeps its priority
+        let tx = create_transaction(&outpoints, &[900]);
+        mempool.validate_and_insert_transaction(tx.clone(), Priority::High, false).unwrap();
+        mempool.handle_new_block_transactions(&[tx.clone()]);
+        assert!(!mempool.has_transaction(&tx.id()));
+        assert_eq!(mempool.handle_virtual_chain_change(&[tx.clone()], &[]), vec![tx.id()]);
+        assert_eq!(priority(&mempool), Priority::High);
+        assert_eq!(mempool.revalidate_high_priority_transactions(), vec![tx.id()]);
+
+        // Once expired, it is demoted to low priority and no longer rebroadcast
+        consensus.set_virtual_daa_score(mempool.config().high_priority_expire_interval_daa_score + 1);
+        let revision = mempool.block_candidates_revision();
+        assert!(mempool.revalidate_high_priority_transactions().is_empty());
+        assert!(mempool.has_transaction(&tx.id()));
+        assert_eq!(priority(&mempool), Priority::Low);
+        assert_ne!(mempool.block_candidates_revision(), revision);
+
+        // Expired transactions returned by a reorg are low priority as well
+        mempool.handle_new_block_transactions(&[tx.clone()]);
+        mempool.handle_virtual_chain_change(&[tx], &[]);
+        assert_eq!(priority(&mempool), Priority::Low);
+    }
+}
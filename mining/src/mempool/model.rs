@@ -0,0 +1,130 @@
+use consensus_core::tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionId};
+use std::cmp::Ordering;
+
+/// The priority of a mempool transaction. High priority transactions are those submitted
+/// locally (e.g., through RPC) and are periodically revalidated in order to be rebroadcast.
+/// They are also given a bounded lane in mempool admission and block templates, until their
+/// priority expires, see [`super::Config`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    Low,
+    High,
+}
+
+/// A transaction held by the mempool, fully populated with UTXO entries, fee and mass
+pub struct MempoolTransaction {
+    pub mtx: MutableTransaction,
+    pub priority: Priority,
+    /// The virtual DAA score at the time the transaction was added
+    pub added_at_daa_score: u64,
+}
+
+impl MempoolTransaction {
+    pub fn new(mtx: MutableTransaction, priority: Priority, added_at_daa_score: u64) -> Self {
+        assert!(mtx.is_fully_populated());
+        Self { mtx, priority, added_at_daa_score }
+    }
+
+    pub fn id(&self) -> TransactionId {
+        self.mtx.tx.id()
+    }
+
+    pub fn tx(&self) -> &Transaction {
+        &self.mtx.tx
+    }
+
+    pub fn fee(&self) -> u64 {
+        self.mtx.calculated_fee.unwrap()
+    }
+
+    pub fn mass(&self) -> u64 {
+        self.mtx.calculated_mass.unwrap()
+    }
+
+    pub fn fee_rate_key(&self) -> FeeRateKey {
+        FeeRateKey { fee: self.fee(), mass: self.mass(), id: self.id() }
+    }
+}
+
+/// A transaction of the mempool or of the orphan pool, as exposed to mempool consumers
+#[derive(Clone, Debug)]
+pub struct MempoolEntry {
+    pub transaction: Transaction,
+    /// The transaction fee, unknown for orphans hence zero
+    pub fee: u64,
+    /// The transaction mass, unknown for orphans hence zero
+    pub mass: u64,
+    pub is_orphan: bool,
+    /// The script public keys of the transaction outputs and of the UTXO entries it spends. Entries spent by
+    /// orphans are partially unknown, hence omitted.
+    pub script_public_keys: Vec<ScriptPublicKey>,
+}
+
+impl MempoolEntry {
+    /// Returns whether the transaction spends from or pays to any of `script_public_keys`
+    pub fn references_any(&self, script_public_keys: &[ScriptPublicKey]) -> bool {
+        self.script_public_keys.iter().any(|script_public_key| script_public_keys.contains(script_public_key))
+    }
+}
+
+impl From<&MempoolTransaction> for MempoolEntry {
+    fn from(item: &MempoolTransaction) -> Self {
+        let spent = item.mtx.entries.iter().flatten().map(|entry| entry.script_public_key.clone());
+        let paid = item.tx().outputs.iter().map(|output| output.script_public_key.clone());
+        Self {
+            transaction: item.tx().clone(),
+            fee: item.fee(),
+            mass: item.mass(),
+            is_orphan: false,
+            script_public_keys: spent.chain(paid).collect(),
+        }
+    }
+}
+
+/// An ordering key sorting transactions by fee rate (fee per mass unit), with the
+/// transaction id as a tie-breaker. Fee rates are compared by cross multiplication
+/// in order to avoid floating point arithmetic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeRateKey {
+    pub fee: u64,
+    pub mass: u64,
+    pub id: TransactionId,
+}
+
+impl FeeRateKey {
+    pub fn fee_rate(&self) -> f64 {
+        self.fee as f64 / self.mass.max(1) as f64
+    }
+}
+
+impl Ord for FeeRateKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let left = self.fee as u128 * other.mass.max(1) as u128;
+        let right = other.fee as u128 * self.mass.max(1) as u128;
+        left.cmp(&right).then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for FeeRateKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hashes::Hash;
+
+    #[test]
+    fn fee_rate_key_ordering_test() {
+        let key = |fee, mass, id| FeeRateKey { fee, mass, id: Hash::from_u64_word(id) };
+        // Higher fee rate wins even with a lower absolute fee
+        assert!(key(100, 10, 1) > key(150, 20, 2));
+        // Equal fee rates are ordered by id
+        assert!(key(100, 10, 2) > key(200, 20, 1));
+        assert_eq!(key(100, 10, 1).cmp(&key(100, 10, 1)), Ordering::Equal);
+        // Zero mass is treated as a unit mass
+        assert_eq!(key(5, 0, 1).fee_rate(), 5.0);
+    }
+}
@@ -0,0 +1,132 @@
+use super::model::{MempoolEntry, Priority};
+use crate::errors::{RuleError, RuleResult};
+use consensus_core::tx::{Transaction, TransactionId, TransactionOutpoint};
+use std::collections::{HashMap, HashSet};
+
+/// A transaction which spends at least one outpoint unknown to both the UTXO set and the mempool
+pub(crate) struct OrphanTransaction {
+    pub tx: Transaction,
+    /// The priority the transaction is inserted with once promoted to the mempool
+    pub priority: Priority,
+    /// The virtual DAA score at the time the orphan was added
+    pub added_at_daa_score: u64,
+}
+
+impl OrphanTransaction {
+    pub(crate) fn new(tx: Transaction, priority: Priority, added_at_daa_score: u64) -> Self {
+        Self { tx, priority, added_at_daa_score }
+    }
+}
+
+impl From<&OrphanTransaction> for MempoolEntry {
+    fn from(item: &OrphanTransaction) -> Self {
+        Self {
+            transaction: item.tx.clone(),
+            fee: 0,
+            mass: 0,
+            is_orphan: true,
+            script_public_keys: item.tx.outputs.iter().map(|output| output.script_public_key.clone()).collect(),
+        }
+    }
+}
+
+/// Holds orphan transactions until their missing parents arrive or until they expire
+pub(crate) struct OrphanPool {
+    maximum_orphan_count: usize,
+    expire_interval_daa_score: u64,
+    all: HashMap<TransactionId, OrphanTransaction>,
+    /// Maps each outpoint spent by an orphan to the spending orphan
+    spent_outpoints: HashMap<TransactionOutpoint, TransactionId>,
+    /// Maps each transaction id referenced by orphan inputs to the referencing orphans
+    redeemers: HashMap<TransactionId, HashSet<TransactionId>>,
+}
+
+impl OrphanPool {
+    pub(crate) fn new(maximum_orphan_count: usize, expire_interval_daa_score: u64) -> Self {
+        Self {
+            maximum_orphan_count,
+            expire_interval_daa_score,
+            all: HashMap::new(),
+            spent_outpoints: HashMap::new(),
+            redeemers: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.all.len()
+    }
+
+    pub(crate) fn has(&self, id: &TransactionId) -> bool {
+        self.all.contains_key(id)
+    }
+
+    pub(crate) fn get(&self, id: &TransactionId) -> Option<&OrphanTransaction> {
+        self.all.get(id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &OrphanTransaction> {
+        self.all.values()
+    }
+
+    pub(crate) fn get_redeemer(&self, outpoint: &TransactionOutpoint) -> Option<TransactionId> {
+        self.spent_outpoints.get(outpoint).copied()
+    }
+
+    /// Adds the orphan, evicting the oldest orphan if the pool is full
+    pub(crate) fn try_add(&mut self, orphan: OrphanTransaction) -> RuleResult<()> {
+        if self.maximum_orphan_count == 0 {
+            return Err(RuleError::RejectOrphanPoolIsFull(0));
+        }
+        let id = orphan.tx.id();
+        for input in orphan.tx.inputs.iter() {
+            if let Some(redeemer) = self.get_redeemer(&input.previous_outpoint) {
+                return Err(RuleError::RejectDoubleSpendInOrphanPool(input.previous_outpoint, redeemer));
+            }
+        }
+        if self.all.len() >= self.maximum_orphan_count {
+            let oldest = self.all.values().min_by_key(|orphan| orphan.added_at_daa_score).map(|orphan| orphan.tx.id()).unwrap();
+            self.remove(&oldest);
+        }
+        for input in orphan.tx.inputs.iter() {
+            self.spent_outpoints.insert(input.previous_outpoint, id);
+            self.redeemers.entry(input.previous_outpoint.transaction_id).or_default().insert(id);
+        }
+        self.all.insert(id, orphan);
+        Ok(())
+    }
+
+    pub(crate) fn remove(&mut self, id: &TransactionId) -> Option<OrphanTransaction> {
+        let orphan = self.all.remove(id)?;
+        for input in orphan.tx.inputs.iter() {
+            self.spent_outpoints.remove(&input.previous_outpoint);
+            let parent = input.previous_outpoint.transaction_id;
+            if let Some(redeemers) = self.redeemers.get_mut(&parent) {
+                redeemers.remove(id);
+                if redeemers.is_empty() {
+                    self.redeemers.remove(&parent);
+                }
+            }
+        }
+        Some(orphan)
+    }
+
+    /// Removes and returns all orphans spending outputs of transaction `parent`
+    pub(crate) fn remove_redeemers_of(&mut self, parent: &TransactionId) -> Vec<OrphanTransaction> {
+        let ids: Vec<TransactionId> = self.redeemers.get(parent).into_iter().flatten().copied().collect();
+        ids.iter().filter_map(|id| self.remove(id)).collect()
+    }
+
+    /// Removes all orphans which were added more than the expire interval before `virtual_daa_score`
+    pub(crate) fn expire(&mut self, virtual_daa_score: u64) -> Vec<TransactionId> {
+        let expired: Vec<TransactionId> = self
+            .all
+            .values()
+            .filter(|orphan| orphan.added_at_daa_score + self.expire_interval_daa_score < virtual_daa_score)
+            .map(|orphan| orphan.tx.id())
+            .collect();
+        for id in expired.iter() {
+            self.remove(id);
+        }
+        expired
+    }
+}
@@ -0,0 +1,183 @@
+use super::model::{FeeRateKey, MempoolTransaction, Priority};
+use crate::fee_estimation::FeeRateHistogram;
+use consensus_core::tx::{MutableTransaction, TransactionId, TransactionOutpoint, UtxoEntry};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Holds the mempool transactions along with the indexes required for double-spend
+/// detection, dependency (chained transactions) tracking and fee-rate ordering
+#[derive(Default)]
+pub(crate) struct TransactionsPool {
+    /// All transactions by id
+    all: HashMap<TransactionId, MempoolTransaction>,
+    /// In-pool parents of each transaction, i.e., transactions whose outputs are spent by it
+    parents: HashMap<TransactionId, HashSet<TransactionId>>,
+    /// In-pool children of each transaction, i.e., transactions spending its outputs
+    children: HashMap<TransactionId, HashSet<TransactionId>>,
+    /// Maps each outpoint spent by a mempool transaction to the spending transaction
+    spent_outpoints: HashMap<TransactionOutpoint, TransactionId>,
+    /// Transactions with no in-pool parents, ordered by fee rate. These are the
+    /// transactions which can be included in a block built on top of virtual
+    ready: BTreeSet<FeeRateKey>,
+    /// Incremented on every change of the ready set, allowing consumers to detect candidate changes cheaply
+    ready_revision: u64,
+    /// Fee-rate histogram of all transactions
+    fee_rate_histogram: FeeRateHistogram,
+}
+
+impl TransactionsPool {
+    pub(crate) fn len(&self) -> usize {
+        self.all.len()
+    }
+
+    pub(crate) fn has(&self, id: &TransactionId) -> bool {
+        self.all.contains_key(id)
+    }
+
+    pub(crate) fn get(&self, id: &TransactionId) -> Option<&MempoolTransaction> {
+        self.all.get(id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &MempoolTransaction> {
+        self.all.values()
+    }
+
+    /// Returns the id of the mempool transaction spending `outpoint`, if such exists
+    pub(crate) fn get_redeemer(&self, outpoint: &TransactionOutpoint) -> Option<TransactionId> {
+        self.spent_outpoints.get(outpoint).copied()
+    }
+
+    /// Populates the entries of all `mtx` inputs which spend outputs of mempool transactions.
+    /// Such outputs are not part of the UTXO set yet, so `virtual_daa_score` is used as their DAA score
+    pub(crate) fn populate_entries(&self, mtx: &mut MutableTransaction, virtual_daa_score: u64) {
+        for (i, input) in mtx.tx.inputs.iter().enumerate() {
+            if let Some(parent) = self.all.get(&input.previous_outpoint.transaction_id) {
+                if let Some(output) = parent.tx().outputs.get(input.previous_outpoint.index as usize) {
+                    mtx.entries[i] = Some(UtxoEntry::new(output.value, output.script_public_key.clone(), virtual_daa_score, false));
+                }
+            }
+        }
+    }
+
+    /// Inserts the transaction and links it to its in-pool parents, as well as to in-pool children which
+    /// already spend its outputs (as is the case for transactions returned to the pool after a reorg)
+    pub(crate) fn insert(&mut self, transaction: MempoolTransaction) {
+        let id = transaction.id();
+        let parents: HashSet<TransactionId> = transaction
+            .tx()
+            .inputs
+            .iter()
+            .map(|input| input.previous_outpoint.transaction_id)
+            .filter(|parent| self.all.contains_key(parent))
+            .collect();
+        let children: HashSet<TransactionId> = (0..transaction.tx().outputs.len() as u32)
+            .filter_map(|index| self.get_redeemer(&TransactionOutpoint::new(id, index)))
+            .collect();
+        for input in transaction.tx().inputs.iter() {
+            self.spent_outpoints.insert(input.previous_outpoint, id);
+        }
+        for parent in parents.iter() {
+            self.children.entry(*parent).or_default().insert(id);
+        }
+        for child in children.iter() {
+            let child_parents = self.parents.get_mut(child).unwrap();
+            if child_parents.is_empty() {
+                self.ready.remove(&self.all[child].fee_rate_key());
+                self.ready_revision += 1;
+            }
+            child_parents.insert(id);
+        }
+        if !children.is_empty() {
+            self.children.insert(id, children);
+        }
+        if parents.is_empty() {
+            self.ready.insert(transaction.fee_rate_key());
+            self.ready_revision += 1;
+        }
+        self.parents.insert(id, parents);
+        self.fee_rate_histogram.add(&transaction.fee_rate_key());
+        self.all.insert(id, transaction);
+    }
+
+    /// Removes a single transaction. Children of the removed transaction are kept, and
+    /// become ready once they have no remaining in-pool parents
+    pub(crate) fn remove(&mut self, id: &TransactionId) -> Option<MempoolTransaction> {
+        let transaction = self.all.remove(id)?;
+        self.fee_rate_histogram.remove(&transaction.fee_rate_key());
+        if self.ready.remove(&transaction.fee_rate_key()) {
+            self.ready_revision += 1;
+        }
+        for input in transaction.tx().inputs.iter() {
+            self.spent_outpoints.remove(&input.previous_outpoint);
+        }
+        for parent in self.parents.remove(id).unwrap_or_default() {
+            if let Some(siblings) = self.children.get_mut(&parent) {
+                siblings.remove(id);
+            }
+        }
+        for child in self.children.remove(id).unwrap_or_default() {
+            let child_parents = self.parents.get_mut(&child).unwrap();
+            child_parents.remove(id);
+            if child_parents.is_empty() {
+                self.ready.insert(self.all[&child].fee_rate_key());
+                self.ready_revision += 1;
+            }
+        }
+        Some(transaction)
+    }
+
+    /// Returns all in-pool descendants of `id` (excluding itself)
+    pub(crate) fn descendants(&self, id: &TransactionId) -> Vec<TransactionId> {
+        let mut visited = HashSet::new();
+        let mut queue = vec![*id];
+        let mut descendants = Vec::new();
+        while let Some(current) = queue.pop() {
+            for child in self.children.get(&current).into_iter().flatten() {
+                if visited.insert(*child) {
+                    descendants.push(*child);
+                    queue.push(*child);
+                }
+            }
+        }
+        descendants
+    }
+
+    /// Sets the priority of the transaction, which decides the block template lane it is selected through
+    pub(crate) fn set_priority(&mut self, id: &TransactionId, priority: Priority) {
+        if let Some(transaction) = self.all.get_mut(id) {
+            if transaction.priority != priority {
+                transaction.priority = priority;
+                if self.ready.contains(&transaction.fee_rate_key()) {
+                    self.ready_revision += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the ids of all transactions with the given priority
+    pub(crate) fn ids_by_priority(&self, priority: Priority) -> Vec<TransactionId> {
+        self.all.values().filter(|tx| tx.priority == priority).map(|tx| tx.id()).collect()
+    }
+
+    /// Returns the ids of all ready transactions, i.e., transactions with no in-pool parents
+    pub(crate) fn ready_ids(&self) -> Vec<TransactionId> {
+        self.ready.iter().map(|key| key.id).collect()
+    }
+
+    pub(crate) fn ready_revision(&self) -> u64 {
+        self.ready_revision
+    }
+
+    /// Iterates ready transactions in descending fee-rate order
+    pub(crate) fn ready_by_fee_rate(&self) -> impl Iterator<Item = &MempoolTransaction> {
+        self.ready.iter().rev().map(|key| &self.all[&key.id])
+    }
+
+    /// Iterates the fee-rate keys of ready transactions in descending fee-rate order
+    pub(crate) fn ready_fee_rate_keys(&self) -> impl Iterator<Item = &FeeRateKey> {
+        self.ready.iter().rev()
+    }
+
+    pub(crate) fn fee_rate_histogram(&self) -> &FeeRateHistogram {
+        &self.fee_rate_histogram
+    }
+}
@@ -0,0 +1,327 @@
+use consensus_core::{
+    api::{session::SessionLock, ConsensusApi},
+    block::{Block, BlockDagData, BlockTemplate, MutableBlock, VirtualInfo},
+    blockstatus::BlockStatus,
+    chain::{ChainBlocksBatch, VirtualChainBatch},
+    coinbase::{CoinbasePreview, MinerData},
+    errors::{
+        block::{BlockProcessResult, RuleError},
+        coinbase::CoinbaseResult,
+        sync::SyncManagerResult,
+        tx::{TxResult, TxRuleError},
+    },
+    ghostdag::ExternalGhostdagData,
+    header::Header,
+    revalidation::BlockRevalidationReport,
+    stats::{BlockDagInfo, ChainThroughputStats, DaaScoreDrift},
+    trusted::{PruningPointTrustedData, TrustedBlock},
+    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+};
+use futures_util::future::BoxFuture;
+use hashes::{Hash, ZERO_HASH};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+/// A minimal consensus mock backed by an in-memory UTXO set, performing only the UTXO
+/// population and fee calculation parts of mempool validation, and building block
+/// templates with a placeholder header and no coinbase transaction
+#[derive(Default)]
+pub struct ConsensusMock {
+    utxo_set: RwLock<HashMap<TransactionOutpoint, UtxoEntry>>,
+    virtual_daa_score: AtomicU64,
+    virtual_parents: RwLock<Vec<Hash>>,
+}
+
+impl ConsensusMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_utxo(&self, outpoint: TransactionOutpoint, entry: UtxoEntry) {
+        self.utxo_set.write().unwrap().insert(outpoint, entry);
+    }
+
+    pub fn remove_utxo(&self, outpoint: &TransactionOutpoint) {
+        self.utxo_set.write().unwrap().remove(outpoint);
+    }
+
+    pub fn set_virtual_daa_score(&self, daa_score: u64) {
+        self.virtual_daa_score.store(daa_score, Ordering::SeqCst);
+    }
+
+    pub fn set_virtual_parents(&self, parents: Vec<Hash>) {
+        *self.virtual_parents.write().unwrap() = parents;
+    }
+}
+
+impl ConsensusApi for ConsensusMock {
+    fn build_block_template(self: Arc<Self>, miner_data: MinerData, txs: Vec<Transaction>) -> Result<BlockTemplate, RuleError> {
+        let utxo_set = self.utxo_set.read().unwrap();
+        let invalid_transactions: Vec<_> = txs
+            .iter()
+            .filter(|tx| tx.inputs.iter().any(|input| !utxo_set.contains_key(&input.previous_outpoint)))
+            .map(|tx| (tx.id(), TxRuleError::MissingTxOutpoints))
+            .collect();
+        if !invalid_transactions.is_empty() {
+            return Err(RuleError::InvalidTransactionsInNewBlock(invalid_transactions));
+        }
+        let daa_score = self.virtual_daa_score.load(Ordering::SeqCst);
+        let header = Header::new(
+            0,
+            vec![],
+            ZERO_HASH,
+            ZERO_HASH,
+            ZERO_HASH,
+            Default::default(),
+            0,
+            0,
+            daa_score,
+            Default::default(),
+            0,
+            ZERO_HASH,
+        );
+        Ok(BlockTemplate::new(MutableBlock::new(header, txs), miner_data, false, Default::default()))
+    }
+
+    fn validate_and_insert_block(
+        self: Arc<Self>,
+        _block: Block,
+        _update_virtual: bool,
+    ) -> BoxFuture<'static, BlockProcessResult<BlockStatus>> {
+        unimplemented!()
+    }
+
+    fn validate_and_insert_blocks(
+        self: Arc<Self>,
+        _blocks: Vec<Block>,
+        _update_virtual: bool,
+    ) -> BoxFuture<'static, Vec<BlockProcessResult<BlockStatus>>> {
+        unimplemented!()
+    }
+
+    fn begin_trusted_sync(self: Arc<Self>) -> BlockProcessResult<()> {
+        unimplemented!()
+    }
+
+    fn end_trusted_sync(self: Arc<Self>) {
+        unimplemented!()
+    }
+
+    fn validate_and_insert_trusted_block(self: Arc<Self>, _block: TrustedBlock) -> BlockProcessResult<BlockStatus> {
+        unimplemented!()
+    }
+
+    fn clear_imported_pruning_point_utxos(self: Arc<Self>) -> BlockProcessResult<()> {
+        unimplemented!()
+    }
+
+    fn append_imported_pruning_point_utxos(self: Arc<Self>, _utxos: &[(TransactionOutpoint, UtxoEntry)]) -> BlockProcessResult<()> {
+        unimplemented!()
+    }
+
+    fn import_pruning_point_utxo_set(self: Arc<Self>, _past_pruning_points: Vec<Hash>) -> BlockProcessResult<()> {
+        unimplemented!()
+    }
+
+    fn validate_mempool_transaction(self: Arc<Self>, mutable_tx: &mut MutableTransaction) -> TxResult<()> {
+        let utxo_set = self.utxo_set.read().unwrap();
+        let mut has_missing_outpoints = false;
+        for (i, input) in mutable_tx.tx.inputs.iter().enumerate() {
+            if mutable_tx.entries[i].is_some() {
+                continue;
+            }
+            match utxo_set.get(&input.previous_outpoint) {
+                Some(entry) => mutable_tx.entries[i] = Some(entry.clone()),
+                None => has_missing_outpoints = true,
+            }
+        }
+        if has_missing_outpoints {
+            return Err(TxRuleError::MissingTxOutpoints);
+        }
+        let total_in: u64 = mutable_tx.entries.iter().map(|entry| entry.as_ref().unwrap().amount).sum();
+        let total_out: u64 = mutable_tx.tx.outputs.iter().map(|output| output.value).sum();
+        if total_out > total_in {
+            return Err(TxRuleError::SpendTooHigh(total_out, total_in));
+        }
+        mutable_tx.calculated_fee = Some(total_in - total_out);
+        mutable_tx.calculated_mass = Some(self.clone().calculate_transaction_mass(&mutable_tx.tx));
+        Ok(())
+    }
+
+    fn calculate_transaction_mass(self: Arc<Self>, transaction: &Transaction) -> u64 {
+        100 * (transaction.inputs.len() + transaction.outputs.len()) as u64
+    }
+
+    fn get_virtual_daa_score(self: Arc<Self>) -> u64 {
+        self.virtual_daa_score.load(Ordering::SeqCst)
+    }
+
+    fn get_virtual_parents(self: Arc<Self>) -> Vec<Hash> {
+        self.virtual_parents.read().unwrap().clone()
+    }
+
+    fn get_virtual_info(self: Arc<Self>) -> VirtualInfo {
+        unimplemented!()
+    }
+
+    fn session_lock(self: Arc<Self>) -> SessionLock {
+        unimplemented!()
+    }
+
+    fn get_virtual_utxos(
+        self: Arc<Self>,
+        _from_outpoint: Option<TransactionOutpoint>,
+        _chunk_size: usize,
+    ) -> Vec<(TransactionOutpoint, UtxoEntry)> {
+        unimplemented!()
+    }
+
+    fn revalidate_block(self: Arc<Self>, _hash: Hash) -> Option<BlockRevalidationReport> {
+        unimplemented!()
+    }
+
+    fn get_coinbase_preview(self: Arc<Self>, _miner_data: MinerData) -> CoinbaseResult<CoinbasePreview> {
+        unimplemented!()
+    }
+
+    fn get_chain_throughput_stats(self: Arc<Self>, _window_size: u64) -> ChainThroughputStats {
+        unimplemented!()
+    }
+
+    fn get_block_dag_info(self: Arc<Self>) -> BlockDagInfo {
+        unimplemented!()
+    }
+
+    fn get_daa_score_drift(self: Arc<Self>) -> DaaScoreDrift {
+        unimplemented!()
+    }
+
+    fn max_block_mass(self: Arc<Self>) -> u64 {
+        500_000
+    }
+
+    fn get_block_status(self: Arc<Self>, _hash: Hash) -> Option<BlockStatus> {
+        unimplemented!()
+    }
+
+    fn get_header(self: Arc<Self>, _hash: Hash) -> Option<Arc<Header>> {
+        unimplemented!()
+    }
+
+    fn get_block(self: Arc<Self>, _hash: Hash) -> Option<Block> {
+        unimplemented!()
+    }
+
+    fn get_payload(self: Arc<Self>, _payload_hash: Hash) -> Option<Arc<Vec<u8>>> {
+        unimplemented!()
+    }
+
+    fn get_block_dag_data(self: Arc<Self>, _hash: Hash) -> Option<BlockDagData> {
+        unimplemented!()
+    }
+
+    fn get_ghostdag_data(self: Arc<Self>, _hash: Hash) -> Option<ExternalGhostdagData> {
+        unimplemented!()
+    }
+
+    fn is_chain_ancestor_of(self: Arc<Self>, _low: Hash, _high: Hash) -> SyncManagerResult<bool> {
+        unimplemented!()
+    }
+
+    fn get_confirmations(self: Arc<Self>, _hash: Hash) -> Option<u64> {
+        unimplemented!()
+    }
+
+    fn get_headers_selected_tip(self: Arc<Self>) -> Hash {
+        unimplemented!()
+    }
+
+    fn get_sink(self: Arc<Self>) -> Hash {
+        unimplemented!()
+    }
+
+    fn hint_virtual_selected_parent(self: Arc<Self>, _hint: Hash) {
+        unimplemented!()
+    }
+
+    fn get_pruning_point(self: Arc<Self>) -> Hash {
+        unimplemented!()
+    }
+
+    fn pin_blocks(self: Arc<Self>, _hashes: &[Hash]) -> SyncManagerResult<()> {
+        unimplemented!()
+    }
+
+    fn unpin_blocks(self: Arc<Self>, _hashes: &[Hash]) {
+        unimplemented!()
+    }
+
+    fn is_archival(self: Arc<Self>) -> bool {
+        unimplemented!()
+    }
+
+    fn get_history_root(self: Arc<Self>) -> Hash {
+        unimplemented!()
+    }
+
+    fn get_pinned_blocks(self: Arc<Self>) -> Vec<Hash> {
+        unimplemented!()
+    }
+
+    fn get_block_region(self: Arc<Self>, _low: Hash, _high: Hash, _max_blocks: usize) -> SyncManagerResult<Vec<Hash>> {
+        unimplemented!()
+    }
+
+    fn create_headers_selected_chain_block_locator(
+        self: Arc<Self>,
+        _low: Option<Hash>,
+        _high: Option<Hash>,
+    ) -> SyncManagerResult<Vec<Hash>> {
+        unimplemented!()
+    }
+
+    fn get_hashes_between(self: Arc<Self>, _low: Hash, _high: Hash, _max_blocks: usize) -> SyncManagerResult<(Vec<Hash>, Hash)> {
+        unimplemented!()
+    }
+
+    fn get_missing_block_body_hashes(self: Arc<Self>, _high: Hash) -> SyncManagerResult<Vec<Hash>> {
+        unimplemented!()
+    }
+
+    fn get_pruning_point_trusted_data(self: Arc<Self>) -> SyncManagerResult<PruningPointTrustedData> {
+        unimplemented!()
+    }
+
+    fn get_pruning_point_utxos(
+        self: Arc<Self>,
+        _pruning_point: Hash,
+        _from_outpoint: Option<TransactionOutpoint>,
+        _chunk_size: usize,
+    ) -> SyncManagerResult<Vec<(TransactionOutpoint, UtxoEntry)>> {
+        unimplemented!()
+    }
+
+    fn get_virtual_chain_from_block(
+        self: Arc<Self>,
+        _start: Hash,
+        _include_accepted_transaction_ids: bool,
+        _max_added_blocks: usize,
+        _max_accepted_transaction_ids: usize,
+    ) -> SyncManagerResult<VirtualChainBatch> {
+        unimplemented!()
+    }
+
+    fn get_chain_blocks_with_acceptance(
+        self: Arc<Self>,
+        _low: Hash,
+        _high: Option<Hash>,
+        _max_blocks: usize,
+    ) -> SyncManagerResult<ChainBlocksBatch> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1 @@
+pub mod consensus_mock;
@@ -0,0 +1,297 @@
+//! An in-process Kaspa node for embedding in the tests of downstream applications, such as wallets and explorers.
+//!
+//! The [`InMemoryNode`] runs consensus, the mempool and the RPC service with all stores held in memory, and
+//! opens no socket. Its time is controlled by the embedder, so that tests are deterministic.
+
+use consensus::{
+    consensus::{test_consensus::create_memory_db, Consensus},
+    constants::perf::PERF_PARAMS,
+};
+use consensus_core::{
+    config::params::Params,
+    notify::{BackpressurePolicy, NotificationType},
+    time::ManualClock,
+};
+use mining::{manager::MiningManager, mempool::Config as MempoolConfig};
+use rpc_core::server::{
+    collector::{ConsensusNotificationChannel, ConsensusNotificationForwarder},
+    service::RpcCoreService,
+};
+use std::{sync::Arc, thread::JoinHandle, time::Duration};
+
+/// A consensus, mempool and RPC service running in process over an in-memory DB.
+///
+/// The node clock starts at the genesis timestamp and only moves when set or advanced through [`InMemoryNode::clock`].
+/// It drives the timestamps of block templates and the rejection of blocks from the future. Block templates are
+/// rebuilt on every request, so that they always reflect the clock.
+///
+/// Block templates can be submitted as is when the params skip proof of work, see [`Params::clone_with_skip_pow`].
+pub struct InMemoryNode {
+    consensus: Arc<Consensus>,
+    mining_manager: Arc<MiningManager>,
+    rpc_service: Arc<RpcCoreService>,
+    clock: Arc<ManualClock>,
+    wait_handles: Vec<JoinHandle<()>>,
+}
+
+impl InMemoryNode {
+    pub fn new(params: &Params) -> Self {
+        let clock = Arc::new(ManualClock::new(params.genesis.timestamp));
+        let consensus = Arc::new(Consensus::with_clock(create_memory_db(), params, &PERF_PARAMS, clock.clone()));
+        let wait_handles = consensus.init();
+
+        let notification_channel = ConsensusNotificationChannel::default();
+        consensus.notification_root().register(
+            &NotificationType::ALL,
+            BackpressurePolicy::Unbounded,
+            Arc::new(ConsensusNotificationForwarder::new(notification_channel.sender())),
+        );
+        // A zero cache lifetime disables the reuse of block templates
+        let mining_manager = Arc::new(MiningManager::new(consensus.clone(), MempoolConfig::default(), Duration::ZERO));
+        // The node has no peers, hence never runs IBD
+        let rpc_service = Arc::new(RpcCoreService::new(
+            consensus.clone(),
+            mining_manager.clone(),
+            None,
+            None,
+            None,
+            None,
+            Default::default(),
+            notification_channel.receiver(),
+        ));
+        rpc_service.start();
+
+        Self { consensus, mining_manager, rpc_service, clock, wait_handles }
+    }
+
+    /// The RPC service, implementing [`rpc_core::api::rpc::RpcApi`] without any transport
+    pub fn rpc(&self) -> &Arc<RpcCoreService> {
+        &self.rpc_service
+    }
+
+    pub fn consensus(&self) -> &Arc<Consensus> {
+        &self.consensus
+    }
+
+    pub fn mining_manager(&self) -> &Arc<MiningManager> {
+        &self.mining_manager
+    }
+
+    /// The node clock, which only moves when set or advanced
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    /// Stops the RPC service and the consensus processors
+    pub async fn shutdown(self) {
+        let _ = self.rpc_service.stop().await;
+        self.consensus.shutdown(self.wait_handles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use addresses::{Address, Prefix};
+    use consensus_core::api::ConsensusApi;
+    use consensus_core::config::params::MAINNET_PARAMS;
+    use consensus_core::errors::tx::TxRuleError;
+    use consensus_core::sign::sign;
+    use consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use consensus_core::time::DurationMs;
+    use consensus_core::tx::{MutableTransaction, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput};
+    use mining::errors::{MiningManagerError, RuleError};
+    use rpc_core::{
+        api::rpc::{DynRpcService, RpcApi},
+        RpcError, SubmitBlockRejectReason, SubmitBlockReport,
+    };
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn test_in_memory_node() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let node = InMemoryNode::new(&params);
+        let pay_address = Address { prefix: Prefix::Mainnet, payload: vec![7; 32], version: 0 };
+
+        let mut expected_timestamp = params.genesis.timestamp;
+        let mut hashes = vec![];
+        for _ in 0..3 {
+            node.clock().advance(DurationMs::from_millis(1000));
+            expected_timestamp = expected_timestamp + DurationMs::from_millis(1000);
+
+            let template = node.rpc().get_block_template(pay_address.clone(), vec![]).await.unwrap();
+            assert_eq!(template.block.header.timestamp, expected_timestamp);
+            let hash = template.block.header.hash;
+            assert!(node.rpc().submit_block(template.block, false).await.unwrap().report.is_success());
+            assert_eq!(node.rpc().get_block(hash, false).await.unwrap().block.header.hash, hash);
+            hashes.push(hash);
+        }
+
+        // Blocks are listed from the pruning point up to the virtual selected parent
+        let response = node.rpc().get_blocks(None, true, false).await.unwrap();
+        assert_eq!(response.block_hashes, hashes);
+        assert_eq!(response.blocks.iter().map(|block| block.header.hash).collect::<Vec<_>>(), hashes);
+        assert!(response.blocks.iter().all(|block| block.verbose_data.as_ref().unwrap().is_chain_block));
+        assert!(node.rpc().get_blocks(Some(hashes[2]), false, false).await.unwrap().block_hashes.is_empty());
+
+        // The node runs without a UTXO index
+        assert!(!node.rpc().get_info().await.unwrap().is_utxo_indexed);
+        assert!(node.rpc().get_balance_by_address(pay_address.clone()).await.is_err());
+        assert!(node.rpc().get_utxos_by_addresses(vec![pay_address.clone()]).await.is_err());
+
+        // The DAA score drift grows with the time elapsed since the sink
+        let metrics = node.rpc().get_metrics(true, false).await.unwrap().consensus_metrics.unwrap();
+        assert_eq!((metrics.sink_timestamp, metrics.daa_score_drift), (expected_timestamp, 0.0));
+        node.clock().advance(DurationMs::from_secs(10).unwrap());
+        let response = node.rpc().get_metrics(true, false).await.unwrap();
+        assert_eq!(response.server_time, expected_timestamp + DurationMs::from_secs(10).unwrap());
+        let metrics = response.consensus_metrics.unwrap();
+        assert_eq!(metrics.daa_score_drift, 10.0);
+        assert_eq!(metrics.expected_daa_score, metrics.virtual_daa_score + 10);
+        assert!(node.rpc().get_metrics(false, false).await.unwrap().consensus_metrics.is_none());
+        // Without a perf monitor, resource usage is never sampled
+        assert!(node.rpc().get_metrics(false, true).await.unwrap().perf_metrics.is_none());
+
+        // A block from too far into the future of the node clock is rejected
+        let mut template = node.rpc().get_block_template(pay_address, vec![]).await.unwrap();
+        template.block.header.timestamp = expected_timestamp + DurationMs::from_secs(3600).unwrap();
+        template.block.header.finalize();
+        assert!(!node.rpc().submit_block(template.block, false).await.unwrap().report.is_success());
+
+        node.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_block_during_ibd() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let node = InMemoryNode::new(&params);
+        let pay_address = Address { prefix: Prefix::Mainnet, payload: vec![7; 32], version: 0 };
+
+        // A service over the node components, sharing an IBD flag controlled by the test
+        let is_ibd_running = Arc::new(AtomicBool::new(true));
+        let rpc_service = RpcCoreService::new(
+            node.consensus().clone(),
+            node.mining_manager().clone(),
+            None,
+            None,
+            None,
+            None,
+            is_ibd_running.clone(),
+            ConsensusNotificationChannel::default().receiver(),
+        );
+        rpc_service.start();
+
+        // Blocks submitted during IBD are rejected without reaching consensus
+        node.clock().advance(DurationMs::from_millis(1000));
+        let template = node.rpc().get_block_template(pay_address.clone(), vec![]).await.unwrap();
+        let hash = template.block.header.hash;
+        let report = rpc_service.submit_block(template.block.clone(), false).await.unwrap().report;
+        assert_eq!(report, SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD));
+        assert!(node.rpc().get_block(hash, false).await.is_err());
+
+        // Once IBD is done, blocks are accepted again
+        is_ibd_running.store(false, Ordering::SeqCst);
+        assert!(rpc_service.submit_block(template.block, false).await.unwrap().report.is_success());
+        assert_eq!(node.rpc().get_block(hash, false).await.unwrap().block.header.hash, hash);
+
+        // Invalid blocks are rejected along with the broken rule
+        let mut template = node.rpc().get_block_template(pay_address, vec![]).await.unwrap();
+        template.block.header.timestamp = params.genesis.timestamp + DurationMs::from_secs(3600).unwrap();
+        template.block.header.finalize();
+        match rpc_service.submit_block(template.block, false).await.unwrap().report {
+            SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid(detail)) => assert!(!detail.is_empty()),
+            report => panic!("unexpected report {0:?}", report),
+        }
+
+        let _ = rpc_service.stop().await;
+        node.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_transaction() {
+        let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+        params.coinbase_maturity = 0;
+        let node = InMemoryNode::new(&params);
+        // The secret key 1, whose public key is the generator point of secp256k1
+        let mut secret_key = [0u8; 32];
+        secret_key[31] = 1;
+        let public_key = vec![
+            0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb,
+            0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8, 0x17, 0x98,
+        ];
+        let pay_address = Address { prefix: Prefix::Mainnet, payload: public_key, version: 0 };
+        for _ in 0..3 {
+            node.clock().advance(DurationMs::from_millis(1000));
+            let template = node.rpc().get_block_template(pay_address.clone(), vec![]).await.unwrap();
+            assert!(node.rpc().submit_block(template.block, false).await.unwrap().report.is_success());
+        }
+        let (outpoint, entry) =
+            node.consensus().clone().get_virtual_utxos(None, usize::MAX).into_iter().find(|(_, entry)| entry.amount > 0).unwrap();
+        let build_tx = |outpoint: TransactionOutpoint, value: u64| {
+            let inputs = vec![TransactionInput::new(outpoint, vec![], 0, 1)];
+            let outputs = vec![TransactionOutput::new(value, entry.script_public_key.clone())];
+            Transaction::new(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![])
+        };
+
+        // An accepted transaction enters the mempool
+        let tx = sign(MutableTransaction::with_entries(build_tx(outpoint, entry.amount - 1000), vec![entry.clone()]), secret_key).tx;
+        assert_eq!(node.rpc().submit_transaction((&tx).into(), false).await.unwrap().transaction_id, tx.id());
+        let mempool_entry = node.rpc().get_mempool_entry(tx.id(), false, false).await.unwrap().entry;
+        assert_eq!((mempool_entry.fee, mempool_entry.is_orphan), (1000, false));
+
+        // A transaction spending unknown outputs is an orphan, only accepted if allowed
+        let orphan = build_tx(TransactionOutpoint::new(7.into(), 0), 1000);
+        match node.rpc().submit_transaction((&orphan).into(), false).await {
+            Err(RpcError::MiningManagerError(MiningManagerError::MempoolError(RuleError::RejectDisallowedOrphan(id)))) => {
+                assert_eq!(id, orphan.id())
+            }
+            res => panic!("unexpected result {0:?}", res),
+        }
+        assert!(node.rpc().get_mempool_entry(orphan.id(), true, false).await.is_err());
+        assert_eq!(node.rpc().submit_transaction((&orphan).into(), true).await.unwrap().transaction_id, orphan.id());
+        assert!(node.rpc().get_mempool_entry(orphan.id(), true, false).await.unwrap().entry.is_orphan);
+
+        // An invalid transaction is rejected along with the broken rule
+        let (outpoint, entry) = node
+            .consensus()
+            .clone()
+            .get_virtual_utxos(None, usize::MAX)
+            .into_iter()
+            .find(|(other, entry)| *other != outpoint && entry.amount > 0)
+            .unwrap();
+        let invalid = build_tx(outpoint, entry.amount + 1);
+        match node.rpc().submit_transaction((&invalid).into(), false).await {
+            Err(RpcError::MiningManagerError(MiningManagerError::MempoolError(RuleError::RejectTxRule(
+                id,
+                TxRuleError::SpendTooHigh(_, _),
+            )))) => assert_eq!(id, invalid.id()),
+            res => panic!("unexpected result {0:?}", res),
+        }
+
+        node.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_dyn_rpc_service() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let node = InMemoryNode::new(&params);
+        let pay_address = Address { prefix: Prefix::Mainnet, payload: vec![7; 32], version: 0 };
+
+        // Calls through the trait object, as done by the transports, are served by the core service
+        let service: DynRpcService = node.rpc().clone();
+        node.clock().advance(DurationMs::from_millis(1000));
+        let template = service.get_block_template(pay_address, vec![]).await.unwrap();
+        let hash = template.block.header.hash;
+        assert!(service.submit_block(template.block, false).await.unwrap().report.is_success());
+        // Genesis is counted as well
+        let dag_info = service.get_block_dag_info().await.unwrap();
+        assert_eq!(
+            (dag_info.network_name, dag_info.block_count, dag_info.tip_hashes),
+            (params.network_name.to_string(), 2, vec![hash])
+        );
+        assert_eq!(node.rpc().get_block_dag_info().await.unwrap().virtual_parent_hashes, dag_info.virtual_parent_hashes);
+        assert_eq!(service.get_block(hash, false).await.unwrap().block.header.hash, hash);
+
+        node.shutdown().await;
+    }
+}
@@ -0,0 +1,130 @@
+use crate::stores::{BanEntry, BannedIpsStore, BannedIpsStoreReader, DbBannedIpsStore};
+use consensus::model::stores::{database::prelude::CachePolicy, errors::StoreResult, DB};
+use consensus_core::time::{DurationMs, TimestampMs};
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+
+/// The score at which an IP is banned by [`BanManager::record_misbehavior`]
+pub const BAN_SCORE_THRESHOLD: u32 = 100;
+
+/// The default duration of a ban
+pub const DEFAULT_BAN_DURATION: DurationMs = DurationMs::from_millis(24 * 60 * 60 * 1000);
+
+/// Tracks the misbehavior scores of peer IPs and bans the IPs reaching [`BAN_SCORE_THRESHOLD`] for the
+/// configured duration. Bans are persisted, while scores are only kept in memory.
+pub struct BanManager {
+    ban_duration: DurationMs,
+    store: DbBannedIpsStore,
+    bans: HashMap<IpAddr, TimestampMs>,
+    scores: HashMap<IpAddr, u32>,
+}
+
+impl BanManager {
+    /// Creates the ban manager and loads the bans persisted in `db`, dropping the expired ones
+    pub fn new(db: Arc<DB>, ban_duration: DurationMs) -> StoreResult<Self> {
+        // All bans are held in memory, so the store cache is redundant
+        let mut store = DbBannedIpsStore::new(db, CachePolicy::Count(0));
+        let now = TimestampMs::now();
+        let mut bans = HashMap::new();
+        for entry in store.get_all()? {
+            if entry.banned_until > now {
+                bans.insert(entry.ip, entry.banned_until);
+            } else {
+                store.remove(entry.ip)?;
+            }
+        }
+        Ok(Self { ban_duration, store, bans, scores: HashMap::new() })
+    }
+
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        matches!(self.bans.get(&canonical_ip(ip)), Some(&banned_until) if banned_until > TimestampMs::now())
+    }
+
+    /// Bans the IP for the configured duration
+    pub fn ban(&mut self, ip: IpAddr) -> StoreResult<()> {
+        let ip = canonical_ip(ip);
+        let entry = BanEntry { ip, banned_until: TimestampMs::now() + self.ban_duration };
+        self.store.set(entry)?;
+        self.bans.insert(ip, entry.banned_until);
+        self.scores.remove(&ip);
+        Ok(())
+    }
+
+    /// Lifts the ban of the IP and resets its score
+    pub fn unban(&mut self, ip: IpAddr) -> StoreResult<()> {
+        let ip = canonical_ip(ip);
+        self.scores.remove(&ip);
+        if self.bans.remove(&ip).is_some() {
+            self.store.remove(ip)?;
+        }
+        Ok(())
+    }
+
+    /// Adds `score` to the misbehavior score of the IP, banning it once the score reaches [`BAN_SCORE_THRESHOLD`].
+    /// Returns whether the IP was banned.
+    pub fn record_misbehavior(&mut self, ip: IpAddr, score: u32) -> StoreResult<bool> {
+        let ip = canonical_ip(ip);
+        let total = self.scores.entry(ip).or_default();
+        *total = total.saturating_add(score);
+        if *total < BAN_SCORE_THRESHOLD {
+            return Ok(false);
+        }
+        self.ban(ip)?;
+        Ok(true)
+    }
+
+    /// Returns the currently banned IPs along with the time their ban expires
+    pub fn banned_ips(&self) -> Vec<BanEntry> {
+        let now = TimestampMs::now();
+        self.bans
+            .iter()
+            .filter(|(_, &banned_until)| banned_until > now)
+            .map(|(&ip, &banned_until)| BanEntry { ip, banned_until })
+            .collect()
+    }
+}
+
+/// Maps IPv4-mapped IPv6 addresses to IPv4, so that a peer is tracked by a single IP regardless of the listener family
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(ip)),
+        ip => ip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::create_temp_db;
+    use std::{net::Ipv4Addr, str::FromStr};
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_misbehavior_bans() {
+        let (_lifetime, db) = create_temp_db();
+        let mut manager = BanManager::new(db.clone(), DEFAULT_BAN_DURATION).unwrap();
+        assert!(!manager.record_misbehavior(ip("1.2.3.4"), BAN_SCORE_THRESHOLD / 2).unwrap());
+        assert!(!manager.is_banned(ip("1.2.3.4")));
+        assert!(manager.record_misbehavior(ip("::ffff:1.2.3.4"), BAN_SCORE_THRESHOLD / 2).unwrap());
+        assert!(manager.is_banned(ip("1.2.3.4")));
+        assert!(manager.is_banned(ip("::ffff:1.2.3.4")));
+        manager.ban(ip("5.6.7.8")).unwrap();
+        manager.unban(ip("5.6.7.8")).unwrap();
+        assert!(!manager.is_banned(ip("5.6.7.8")));
+
+        // Bans survive restarts
+        let manager = BanManager::new(db.clone(), DEFAULT_BAN_DURATION).unwrap();
+        assert!(manager.is_banned(ip("1.2.3.4")));
+        assert_eq!(manager.banned_ips().len(), 1);
+
+        // Expired bans are dropped
+        let mut manager = BanManager::new(db.clone(), DurationMs::default()).unwrap();
+        manager.ban(Ipv4Addr::LOCALHOST.into()).unwrap();
+        assert!(!manager.is_banned(Ipv4Addr::LOCALHOST.into()));
+        let manager = BanManager::new(db, DEFAULT_BAN_DURATION).unwrap();
+        assert!(!manager.is_banned(Ipv4Addr::LOCALHOST.into()));
+        assert_eq!(manager.banned_ips().len(), 1);
+    }
+}
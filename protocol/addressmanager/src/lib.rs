@@ -0,0 +1,27 @@
+//! Peer address management.
+//!
+//! Known peer addresses are persisted in the node DB along with the time they were last seen and the number of
+//! consecutive connection failures. Addresses are bucketed by network group, each bucket holding a bounded
+//! number of addresses, so that a single operator cannot fill the address table. When no address is known,
+//! addresses are queried from the configured DNS seeders.
+//!
+//! Misbehaving peers are banned by IP through the [`BanManager`], which persists bans in the same DB.
+
+mod bans;
+mod manager;
+mod seeding;
+mod stores;
+
+pub use bans::{BanManager, BAN_SCORE_THRESHOLD, DEFAULT_BAN_DURATION};
+pub use manager::{AddressManager, Config, NetworkGroup};
+pub use seeding::query_dns_seeders;
+pub use stores::{
+    AddressEntry, AddressesStore, AddressesStoreReader, BanEntry, BannedIpsStore, BannedIpsStoreReader, DbAddressesStore,
+    DbBannedIpsStore,
+};
+
+/// The maximum number of addresses held per network group
+pub const MAX_ADDRESSES_PER_BUCKET: usize = 64;
+
+/// The number of consecutive connection failures after which an address is removed
+pub const MAX_CONNECTION_FAILURES: u32 = 10;
@@ -0,0 +1,356 @@
+use crate::{
+    seeding::query_dns_seeders,
+    stores::{AddressEntry, AddressesStore, AddressesStoreReader, DbAddressesStore},
+    MAX_ADDRESSES_PER_BUCKET, MAX_CONNECTION_FAILURES,
+};
+use consensus::model::stores::{database::prelude::CachePolicy, errors::StoreResult, DB};
+use consensus_core::time::TimestampMs;
+use kaspa_core::info;
+use kaspa_utils::networking::NetAddress;
+use rand::{distributions::WeightedIndex, prelude::Distribution, seq::SliceRandom, thread_rng};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::Arc,
+};
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// The DNS seeders queried for addresses by [`AddressManager::seed_from_dns`]
+    pub dns_seeders: Vec<String>,
+    /// The port of the addresses returned by the DNS seeders
+    pub default_port: u16,
+    /// Whether to accept addresses which are not publicly routable, e.g., on local test networks
+    pub accept_unroutable: bool,
+}
+
+impl Config {
+    pub fn new(dns_seeders: Vec<String>, default_port: u16, accept_unroutable: bool) -> Self {
+        Self { dns_seeders, default_port, accept_unroutable }
+    }
+}
+
+/// The network group of an address, i.e., its /16 prefix for IPv4 and its /32 prefix for IPv6.
+/// Addresses of the same group are usually controlled by the same operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NetworkGroup {
+    V4([u8; 2]),
+    V6([u8; 4]),
+}
+
+impl From<IpAddr> for NetworkGroup {
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => {
+                let octets = ip.octets();
+                Self::V4([octets[0], octets[1]])
+            }
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => Self::from(IpAddr::V4(ip)),
+                None => {
+                    let octets = ip.octets();
+                    Self::V6([octets[0], octets[1], octets[2], octets[3]])
+                }
+            },
+        }
+    }
+}
+
+/// Manages the known peer addresses. All addresses are held in memory and every change is persisted,
+/// so that the known addresses survive restarts.
+pub struct AddressManager {
+    config: Config,
+    store: DbAddressesStore,
+    addresses: HashMap<NetAddress, AddressEntry>,
+    buckets: HashMap<NetworkGroup, Vec<NetAddress>>,
+}
+
+impl AddressManager {
+    /// Creates the address manager and loads the addresses persisted in `db`
+    pub fn new(db: Arc<DB>, config: Config) -> StoreResult<Self> {
+        // All addresses are held in memory, so the store cache is redundant
+        let store = DbAddressesStore::new(db, CachePolicy::Count(0));
+        let mut manager = Self { config, store, addresses: HashMap::new(), buckets: HashMap::new() };
+        for entry in manager.store.get_all()? {
+            manager.insert_in_memory(entry);
+        }
+        Ok(manager)
+    }
+
+    pub fn address_count(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn get(&self, address: &NetAddress) -> Option<&AddressEntry> {
+        self.addresses.get(address)
+    }
+
+    /// Returns all the known addresses, in no particular order
+    pub fn addresses(&self) -> Vec<NetAddress> {
+        self.addresses.keys().copied().collect()
+    }
+
+    /// Adds addresses learned from peers or DNS seeders and returns the number of new addresses. Known
+    /// addresses are left unchanged. When the bucket of a new address is full, the address replaces the
+    /// bucket entry which failed the most, or is dropped if no entry of the bucket has failed.
+    pub fn add_addresses(&mut self, addresses: impl IntoIterator<Item = NetAddress>) -> StoreResult<usize> {
+        let now = TimestampMs::now();
+        self.add_addresses_last_seen(addresses.into_iter().map(|address| (address, now)))
+    }
+
+    /// Adds addresses gossiped by a peer along with the time the peer claims they were last seen, so that stale
+    /// addresses are less likely to be drawn as connection candidates. Claimed times in the future are clamped
+    /// to the current time. Returns the number of new addresses, see [`Self::add_addresses`].
+    pub fn add_gossiped_addresses(&mut self, addresses: impl IntoIterator<Item = (NetAddress, TimestampMs)>) -> StoreResult<usize> {
+        let now = TimestampMs::now();
+        self.add_addresses_last_seen(addresses.into_iter().map(|(address, last_seen)| (address, last_seen.min(now))))
+    }
+
+    fn add_addresses_last_seen(&mut self, addresses: impl IntoIterator<Item = (NetAddress, TimestampMs)>) -> StoreResult<usize> {
+        let mut added = 0;
+        for (address, last_seen) in addresses {
+            if self.addresses.contains_key(&address) || !(self.config.accept_unroutable || address.is_publicly_routable()) {
+                continue;
+            }
+            let group = NetworkGroup::from(address.ip);
+            if self.buckets.get(&group).map_or(0, Vec::len) >= MAX_ADDRESSES_PER_BUCKET {
+                match self.most_failed_address(group) {
+                    Some(evicted) => self.remove_address(evicted)?,
+                    None => continue,
+                }
+            }
+            let entry = AddressEntry::new(address, last_seen);
+            self.store.set(entry)?;
+            self.insert_in_memory(entry);
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// Removes the address, if known
+    pub fn remove_address(&mut self, address: NetAddress) -> StoreResult<()> {
+        if self.addresses.remove(&address).is_none() {
+            return Ok(());
+        }
+        let group = NetworkGroup::from(address.ip);
+        if let Some(bucket) = self.buckets.get_mut(&group) {
+            if let Some(index) = bucket.iter().position(|&bucket_address| bucket_address == address) {
+                bucket.swap_remove(index);
+            }
+            if bucket.is_empty() {
+                self.buckets.remove(&group);
+            }
+        }
+        self.store.remove(address)
+    }
+
+    /// Records a successful connection to the address, resetting its failure counter
+    pub fn mark_connection_success(&mut self, address: NetAddress) -> StoreResult<()> {
+        if let Some(entry) = self.addresses.get_mut(&address) {
+            entry.last_seen = TimestampMs::now();
+            entry.connection_failures = 0;
+            self.store.set(*entry)?;
+        }
+        Ok(())
+    }
+
+    /// Records a failed connection attempt to the address. The address is removed after
+    /// [`MAX_CONNECTION_FAILURES`] consecutive failures.
+    pub fn mark_connection_failure(&mut self, address: NetAddress) -> StoreResult<()> {
+        let entry = match self.addresses.get_mut(&address) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+        entry.connection_failures += 1;
+        if entry.connection_failures >= MAX_CONNECTION_FAILURES {
+            return self.remove_address(address);
+        }
+        self.store.set(*entry)
+    }
+
+    /// Queries the configured DNS seeders and adds the returned addresses. Returns the number of new addresses.
+    /// The call blocks on DNS resolution.
+    pub fn seed_from_dns(&mut self) -> StoreResult<usize> {
+        let addresses = query_dns_seeders(&self.config.dns_seeders, self.config.default_port);
+        let added = self.add_addresses(addresses)?;
+        info!("Added {} addresses from {} DNS seeders", added, self.config.dns_seeders.len());
+        Ok(added)
+    }
+
+    /// Returns up to `count` distinct addresses to connect to, excluding `excluded` (usually the connected peers).
+    ///
+    /// Candidates are drawn round-robin from the buckets in random order, so that connections spread across
+    /// network groups. Within a bucket, addresses which failed or were not seen recently are less likely to
+    /// be drawn.
+    pub fn connection_candidates(&self, count: usize, excluded: &HashSet<NetAddress>) -> Vec<NetAddress> {
+        let now = TimestampMs::now();
+        let mut rng = thread_rng();
+        let mut buckets: Vec<Vec<&AddressEntry>> = self
+            .buckets
+            .values()
+            .map(|bucket| {
+                bucket.iter().filter(|address| !excluded.contains(address)).map(|address| &self.addresses[address]).collect()
+            })
+            .filter(|bucket: &Vec<_>| !bucket.is_empty())
+            .collect();
+        buckets.shuffle(&mut rng);
+
+        let mut candidates = Vec::with_capacity(count);
+        while candidates.len() < count && !buckets.is_empty() {
+            buckets.retain_mut(|bucket| {
+                if candidates.len() == count {
+                    return true;
+                }
+                let weights = WeightedIndex::new(bucket.iter().map(|entry| selection_weight(entry, now))).unwrap();
+                candidates.push(bucket.swap_remove(weights.sample(&mut rng)).address);
+                !bucket.is_empty()
+            });
+        }
+        candidates
+    }
+
+    /// Returns up to `count` randomly chosen addresses whose latest connection attempt did not fail, for sharing
+    /// with peers
+    pub fn gossip_addresses(&self, count: usize) -> Vec<AddressEntry> {
+        let good: Vec<_> = self.addresses.values().filter(|entry| entry.connection_failures == 0).collect();
+        good.choose_multiple(&mut thread_rng(), count).map(|&&entry| entry).collect()
+    }
+
+    fn insert_in_memory(&mut self, entry: AddressEntry) {
+        self.buckets.entry(NetworkGroup::from(entry.address.ip)).or_default().push(entry.address);
+        self.addresses.insert(entry.address, entry);
+    }
+
+    /// Returns the address of the bucket with the most connection failures, the least recently seen first,
+    /// or `None` if no address of the bucket failed
+    fn most_failed_address(&self, group: NetworkGroup) -> Option<NetAddress> {
+        self.buckets
+            .get(&group)?
+            .iter()
+            .map(|address| &self.addresses[address])
+            .filter(|entry| entry.connection_failures > 0)
+            .max_by_key(|entry| (entry.connection_failures, Reverse(entry.last_seen)))
+            .map(|entry| entry.address)
+    }
+}
+
+/// The relative probability of drawing the address as a connection candidate, decreasing with
+/// its failures and with the days since it was last seen
+fn selection_weight(entry: &AddressEntry, now: TimestampMs) -> f64 {
+    let days_unseen = now.saturating_duration_since(entry.last_seen).as_millis() / MILLIS_PER_DAY;
+    1.0 / ((1 + entry.connection_failures as u64) * (1 + days_unseen)) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::consensus::test_consensus::create_temp_db;
+    use consensus_core::time::DurationMs;
+    use std::str::FromStr;
+
+    fn address(s: &str) -> NetAddress {
+        NetAddress::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_addresses_persistence() {
+        let (_lifetime, db) = create_temp_db();
+        let mut manager = AddressManager::new(db.clone(), Config::default()).unwrap();
+        // Unroutable addresses are rejected unless configured otherwise
+        let added = manager.add_addresses([address("1.2.3.4:16111"), address("5.6.7.8:16111"), address("127.0.0.1:16111")]).unwrap();
+        assert_eq!(added, 2);
+        assert_eq!(manager.add_addresses([address("1.2.3.4:16111")]).unwrap(), 0);
+
+        manager.mark_connection_failure(address("1.2.3.4:16111")).unwrap();
+        manager.mark_connection_failure(address("5.6.7.8:16111")).unwrap();
+        manager.mark_connection_success(address("5.6.7.8:16111")).unwrap();
+
+        let manager = AddressManager::new(db, Config::default()).unwrap();
+        assert_eq!(manager.address_count(), 2);
+        let mut addresses = manager.addresses();
+        addresses.sort();
+        assert_eq!(addresses, vec![address("1.2.3.4:16111"), address("5.6.7.8:16111")]);
+        assert_eq!(manager.get(&address("1.2.3.4:16111")).unwrap().connection_failures, 1);
+        assert_eq!(manager.get(&address("5.6.7.8:16111")).unwrap().connection_failures, 0);
+    }
+
+    #[test]
+    fn test_failed_addresses_removal() {
+        let (_lifetime, db) = create_temp_db();
+        let mut manager = AddressManager::new(db.clone(), Config::new(vec![], 16111, true)).unwrap();
+        manager.add_addresses([address("127.0.0.1:16111")]).unwrap();
+        for _ in 0..MAX_CONNECTION_FAILURES {
+            manager.mark_connection_failure(address("127.0.0.1:16111")).unwrap();
+        }
+        assert_eq!(manager.address_count(), 0);
+        assert_eq!(AddressManager::new(db, Config::default()).unwrap().address_count(), 0);
+    }
+
+    #[test]
+    fn test_bucket_eviction() {
+        let (_lifetime, db) = create_temp_db();
+        let mut manager = AddressManager::new(db, Config::default()).unwrap();
+        // All addresses fall in the 1.2.0.0/16 bucket
+        let bucket_addresses: Vec<_> =
+            (0..MAX_ADDRESSES_PER_BUCKET).map(|i| address(&format!("1.2.{}.{}:16111", i / 256, i % 256))).collect();
+        assert_eq!(manager.add_addresses(bucket_addresses.iter().copied()).unwrap(), MAX_ADDRESSES_PER_BUCKET);
+
+        // A full bucket with no failed address drops new addresses
+        assert_eq!(manager.add_addresses([address("1.2.255.255:16111")]).unwrap(), 0);
+        assert_eq!(manager.add_addresses([address("1.3.0.1:16111")]).unwrap(), 1);
+
+        // Otherwise the most failed address is evicted
+        manager.mark_connection_failure(bucket_addresses[3]).unwrap();
+        assert_eq!(manager.add_addresses([address("1.2.255.255:16111")]).unwrap(), 1);
+        assert!(manager.get(&bucket_addresses[3]).is_none());
+        assert_eq!(manager.address_count(), MAX_ADDRESSES_PER_BUCKET + 1);
+    }
+
+    #[test]
+    fn test_connection_candidates() {
+        let (_lifetime, db) = create_temp_db();
+        let mut manager = AddressManager::new(db, Config::default()).unwrap();
+        manager.add_addresses([address("1.2.0.1:16111"), address("1.2.0.2:16111"), address("1.3.0.1:16111")]).unwrap();
+
+        // The first round draws one address of each bucket
+        let candidates = manager.connection_candidates(2, &HashSet::new());
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.contains(&address("1.3.0.1:16111")));
+
+        let excluded = HashSet::from([address("1.3.0.1:16111")]);
+        let mut candidates = manager.connection_candidates(10, &excluded);
+        candidates.sort();
+        assert_eq!(candidates, vec![address("1.2.0.1:16111"), address("1.2.0.2:16111")]);
+    }
+
+    #[test]
+    fn test_gossip() {
+        let (_lifetime, db) = create_temp_db();
+        let mut manager = AddressManager::new(db, Config::default()).unwrap();
+        let now = TimestampMs::now();
+        let stale = now.saturating_sub(DurationMs::from_millis(3 * MILLIS_PER_DAY));
+        let future = now.saturating_add(DurationMs::from_millis(MILLIS_PER_DAY));
+        let added = manager
+            .add_gossiped_addresses([
+                (address("1.2.0.1:16111"), stale),
+                (address("1.3.0.1:16111"), future),
+                (address("1.4.0.1:16111"), now),
+                (address("127.0.0.1:16111"), now),
+            ])
+            .unwrap();
+        assert_eq!(added, 3);
+        assert_eq!(manager.get(&address("1.2.0.1:16111")).unwrap().last_seen, stale);
+        // A claimed time in the future is clamped
+        assert!(manager.get(&address("1.3.0.1:16111")).unwrap().last_seen <= TimestampMs::now());
+
+        // Failed addresses are not shared, and at most the requested count is returned
+        manager.mark_connection_failure(address("1.4.0.1:16111")).unwrap();
+        let mut gossiped: Vec<_> = manager.gossip_addresses(10).into_iter().map(|entry| entry.address).collect();
+        gossiped.sort();
+        assert_eq!(gossiped, vec![address("1.2.0.1:16111"), address("1.3.0.1:16111")]);
+        assert_eq!(manager.gossip_addresses(1).len(), 1);
+    }
+}
@@ -0,0 +1,20 @@
+use kaspa_core::{debug, warn};
+use kaspa_utils::networking::NetAddress;
+use std::net::ToSocketAddrs;
+
+/// Resolves the peer addresses published by the DNS seeders. Seeders are queried in turn and the ones
+/// failing to resolve are skipped. The call blocks on DNS resolution.
+pub fn query_dns_seeders(seeders: &[String], default_port: u16) -> Vec<NetAddress> {
+    let mut addresses = Vec::new();
+    for seeder in seeders {
+        match (seeder.as_str(), default_port).to_socket_addrs() {
+            Ok(resolved) => {
+                let count = addresses.len();
+                addresses.extend(resolved.map(NetAddress::from));
+                debug!("DNS seeder {} returned {} addresses", seeder, addresses.len() - count);
+            }
+            Err(err) => warn!("Failed querying DNS seeder {}: {}", seeder, err),
+        }
+    }
+    addresses
+}
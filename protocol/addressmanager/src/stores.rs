@@ -0,0 +1,172 @@
+use consensus::model::stores::{
+    database::prelude::{CachePolicy, CachedDbAccess, DirectDbWriter},
+    errors::StoreResult,
+    DB,
+};
+use consensus_core::time::TimestampMs;
+use kaspa_utils::networking::NetAddress;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv6Addr},
+    sync::Arc,
+};
+
+/// A known peer address along with its connection record
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressEntry {
+    pub address: NetAddress,
+    /// The last time the address was added or successfully connected to
+    pub last_seen: TimestampMs,
+    /// The number of consecutive failed connection attempts since the last success
+    pub connection_failures: u32,
+}
+
+impl AddressEntry {
+    pub fn new(address: NetAddress, last_seen: TimestampMs) -> Self {
+        Self { address, last_seen, connection_failures: 0 }
+    }
+}
+
+/// The DB key of an address, i.e., its fixed-size encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AddressKey([u8; NetAddress::SERIALIZED_LEN]);
+
+impl From<NetAddress> for AddressKey {
+    fn from(address: NetAddress) -> Self {
+        Self(address.to_bytes())
+    }
+}
+
+impl AsRef<[u8]> for AddressKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for AddressKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        NetAddress::from_bytes(self.0).fmt(f)
+    }
+}
+
+/// Reader API for `AddressesStore`.
+pub trait AddressesStoreReader {
+    fn get(&self, address: NetAddress) -> StoreResult<AddressEntry>;
+    fn get_all(&self) -> StoreResult<Vec<AddressEntry>>;
+}
+
+/// Write API for `AddressesStore`
+pub trait AddressesStore: AddressesStoreReader {
+    fn set(&mut self, entry: AddressEntry) -> StoreResult<()>;
+    fn remove(&mut self, address: NetAddress) -> StoreResult<()>;
+}
+
+const STORE_PREFIX: &[u8] = b"peer-addresses";
+
+/// A DB + cache implementation of `AddressesStore` trait
+#[derive(Clone)]
+pub struct DbAddressesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<AddressKey, AddressEntry>,
+}
+
+impl DbAddressesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, STORE_PREFIX) }
+    }
+}
+
+impl AddressesStoreReader for DbAddressesStore {
+    fn get(&self, address: NetAddress) -> StoreResult<AddressEntry> {
+        self.access.read(address.into())
+    }
+
+    fn get_all(&self) -> StoreResult<Vec<AddressEntry>> {
+        self.access.iterator().map(|item| item.map(|(_, entry)| entry)).collect()
+    }
+}
+
+impl AddressesStore for DbAddressesStore {
+    fn set(&mut self, entry: AddressEntry) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), entry.address.into(), entry)
+    }
+
+    fn remove(&mut self, address: NetAddress) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), address.into())
+    }
+}
+
+/// A banned IP along with the time the ban expires
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub ip: IpAddr,
+    pub banned_until: TimestampMs,
+}
+
+/// The DB key of a banned IP, i.e., its IPv6 form, with IPv4 addresses mapped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BannedIpKey([u8; 16]);
+
+impl From<IpAddr> for BannedIpKey {
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => Self(ip.to_ipv6_mapped().octets()),
+            IpAddr::V6(ip) => Self(ip.octets()),
+        }
+    }
+}
+
+impl AsRef<[u8]> for BannedIpKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for BannedIpKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ipv6Addr::from(self.0).fmt(f)
+    }
+}
+
+/// Reader API for `BannedIpsStore`.
+pub trait BannedIpsStoreReader {
+    fn get_all(&self) -> StoreResult<Vec<BanEntry>>;
+}
+
+/// Write API for `BannedIpsStore`
+pub trait BannedIpsStore: BannedIpsStoreReader {
+    fn set(&mut self, entry: BanEntry) -> StoreResult<()>;
+    fn remove(&mut self, ip: IpAddr) -> StoreResult<()>;
+}
+
+const BANNED_IPS_STORE_PREFIX: &[u8] = b"banned-ips";
+
+/// A DB + cache implementation of `BannedIpsStore` trait
+#[derive(Clone)]
+pub struct DbBannedIpsStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<BannedIpKey, BanEntry>,
+}
+
+impl DbBannedIpsStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, BANNED_IPS_STORE_PREFIX) }
+    }
+}
+
+impl BannedIpsStoreReader for DbBannedIpsStore {
+    fn get_all(&self) -> StoreResult<Vec<BanEntry>> {
+        self.access.iterator().map(|item| item.map(|(_, entry)| entry)).collect()
+    }
+}
+
+impl BannedIpsStore for DbBannedIpsStore {
+    fn set(&mut self, entry: BanEntry) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), entry.ip.into(), entry)
+    }
+
+    fn remove(&mut self, ip: IpAddr) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), ip.into())
+    }
+}
@@ -0,0 +1,50 @@
+//! P2P connection management.
+//!
+//! The [`ConnectionManager`] service runs the P2P adaptor and keeps the node connected: forced peers
+//! (`--connect` and `--addpeer`) are reconnected when dropped, and the outbound target is filled with
+//! peers drawn from the address manager. Operators may add peers at runtime, e.g. through the AddPeer RPC.
+
+use kaspa_utils::networking::NetAddress;
+use p2p::{PeerId, Router};
+use std::time::Duration;
+
+mod manager;
+
+pub use manager::{Config, ConnectionManager};
+
+/// The default number of outbound connections maintained with address manager peers
+pub const DEFAULT_OUTBOUND_TARGET: usize = 8;
+
+/// The interval between checks of the connected peers
+pub const CONNECTIONS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The info of a connected peer, as reported by RPC
+#[derive(Clone, Debug)]
+pub struct PeerInfo {
+    pub id: PeerId,
+    pub address: NetAddress,
+    /// Whether this node initiated the connection
+    pub is_outbound: bool,
+    pub user_agent: String,
+    /// The protocol version the peer claims to support
+    pub advertised_protocol_version: u32,
+    /// The peer clock offset in milliseconds
+    pub time_offset: i64,
+    /// The time elapsed since the peer connected
+    pub time_connected: Duration,
+}
+
+impl From<&Router> for PeerInfo {
+    fn from(router: &Router) -> Self {
+        let properties = router.properties();
+        Self {
+            id: properties.id,
+            address: router.net_address().into(),
+            is_outbound: router.is_outbound(),
+            user_agent: properties.user_agent.clone(),
+            advertised_protocol_version: properties.advertised_protocol_version,
+            time_offset: properties.time_offset,
+            time_connected: router.time_connected(),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use crate::{PeerInfo, CONNECTIONS_INTERVAL};
+use addressmanager::AddressManager;
+use futures_util::future::join_all;
+use kaspa_core::{
+    debug, error, info,
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::{networking::NetAddress, triggers::DuplexTrigger};
+use p2p::{Adaptor, ConnectionInitializer, Hub};
+use parking_lot::Mutex;
+use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+use tokio::sync::Notify;
+
+const CONNECTION_MANAGER: &str = "connection-manager";
+
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// The number of outbound connections to maintain with peers drawn from the address manager
+    pub outbound_target: usize,
+    /// Peers to exclusively connect to. When set, no other outbound connections are made.
+    pub connect_peers: Vec<NetAddress>,
+    /// Peers to permanently connect to, in addition to the outbound target
+    pub add_peers: Vec<NetAddress>,
+}
+
+impl Config {
+    pub fn new(outbound_target: usize, connect_peers: Vec<NetAddress>, add_peers: Vec<NetAddress>) -> Self {
+        Self { outbound_target, connect_peers, add_peers }
+    }
+
+    /// The peers which are always connected to and reconnected to when dropped
+    fn forced_peers(&self) -> impl Iterator<Item = NetAddress> + '_ {
+        self.connect_peers.iter().chain(self.add_peers.iter()).copied()
+    }
+}
+
+/// Runs the P2P adaptor and maintains its outbound connections.
+///
+/// Every [`CONNECTIONS_INTERVAL`], dropped forced peers are reconnected and missing outbound connections are
+/// replaced by peers drawn from the address manager, which records the outcome of each attempt. The DNS seeders
+/// are queried when no address is known. Peers added at runtime with [`ConnectionManager::add_peer`] are handled
+/// at once rather than on the next interval.
+pub struct ConnectionManager {
+    /// The addresses to serve inbound connections on. If empty, only outbound connections are made.
+    listen_addresses: Vec<NetAddress>,
+    hub: Hub,
+    initializer: Arc<dyn ConnectionInitializer>,
+    address_manager: Arc<Mutex<AddressManager>>,
+    config: Config,
+    /// Peers added at runtime which are reconnected to when dropped, like the configured forced peers
+    permanent_peers: Mutex<HashSet<NetAddress>>,
+    /// Peers added at runtime which are attempted once
+    pending_peers: Mutex<Vec<NetAddress>>,
+    /// Wakes the connections loop up ahead of the next interval
+    connections_requested: Notify,
+    shutdown: DuplexTrigger,
+}
+
+impl ConnectionManager {
+    pub fn new(
+        listen_addresses: Vec<NetAddress>,
+        hub: Hub,
+        initializer: Arc<dyn ConnectionInitializer>,
+        address_manager: Arc<Mutex<AddressManager>>,
+        config: Config,
+    ) -> Self {
+        Self {
+            listen_addresses,
+            hub,
+            initializer,
+            address_manager,
+            config,
+            permanent_peers: Mutex::new(HashSet::new()),
+            pending_peers: Mutex::new(Vec::new()),
+            connections_requested: Notify::new(),
+            shutdown: DuplexTrigger::default(),
+        }
+    }
+
+    /// Returns the info of the currently connected peers
+    pub fn peer_infos(&self) -> Vec<PeerInfo> {
+        self.hub.active_peers().iter().map(|router| PeerInfo::from(router.as_ref())).collect()
+    }
+
+    /// Requests a connection to `address`. A permanent peer is reconnected to whenever dropped, while any other
+    /// peer is attempted once. The connection is attempted without waiting for the next interval.
+    pub fn add_peer(&self, address: NetAddress, is_permanent: bool) {
+        if is_permanent {
+            self.permanent_peers.lock().insert(address);
+        } else {
+            self.pending_peers.lock().push(address);
+        }
+        self.connections_requested.notify_one();
+    }
+
+    /// The configured forced peers along with the permanent peers added at runtime
+    fn forced_peers(&self) -> HashSet<NetAddress> {
+        self.config.forced_peers().chain(self.permanent_peers.lock().iter().copied()).collect()
+    }
+
+    async fn handle_connections(&self, adaptor: &Adaptor) {
+        let connected: HashSet<NetAddress> = self.hub.active_peers().iter().map(|router| router.net_address().into()).collect();
+        self.handle_forced_peers(adaptor, &connected).await;
+        self.handle_pending_peers(adaptor, &connected).await;
+        if self.config.connect_peers.is_empty() {
+            self.handle_outbound_peers(adaptor, &connected).await;
+        }
+    }
+
+    async fn handle_pending_peers(&self, adaptor: &Adaptor, connected: &HashSet<NetAddress>) {
+        let pending: HashSet<NetAddress> = self.pending_peers.lock().drain(..).collect();
+        let missing: Vec<_> = pending.into_iter().filter(|address| !connected.contains(address)).collect();
+        let results = join_all(missing.iter().map(|address| adaptor.connect_peer(address.to_string()))).await;
+        for (address, result) in missing.into_iter().zip(results) {
+            match result {
+                Ok(_) => info!("Connected to added peer {}", address),
+                Err(err) => warn!("Failed connecting to added peer {}: {}", address, err),
+            }
+        }
+    }
+
+    async fn handle_forced_peers(&self, adaptor: &Adaptor, connected: &HashSet<NetAddress>) {
+        let missing: Vec<_> = self.forced_peers().into_iter().filter(|address| !connected.contains(address)).collect();
+        let results = join_all(missing.iter().map(|address| adaptor.connect_peer(address.to_string()))).await;
+        for (address, result) in missing.into_iter().zip(results) {
+            match result {
+                Ok(_) => info!("Connected to forced peer {}", address),
+                Err(err) => warn!("Failed connecting to forced peer {}: {}", address, err),
+            }
+        }
+    }
+
+    async fn handle_outbound_peers(&self, adaptor: &Adaptor, connected: &HashSet<NetAddress>) {
+        let forced = self.forced_peers();
+        let outbound_count = self
+            .hub
+            .active_peers()
+            .iter()
+            .filter(|router| router.is_outbound() && !forced.contains(&router.net_address().into()))
+            .count();
+        let missing = self.config.outbound_target.saturating_sub(outbound_count);
+        if missing == 0 {
+            return;
+        }
+
+        let excluded: HashSet<NetAddress> = connected.union(&forced).copied().collect();
+        let mut candidates = self.address_manager.lock().connection_candidates(missing, &excluded);
+        if candidates.is_empty() && self.address_manager.lock().address_count() == 0 {
+            let address_manager = self.address_manager.clone();
+            match tokio::task::spawn_blocking(move || address_manager.lock().seed_from_dns()).await.unwrap() {
+                Ok(_) => candidates = self.address_manager.lock().connection_candidates(missing, &excluded),
+                Err(err) => warn!("Failed storing the addresses of the DNS seeders: {}", err),
+            }
+        }
+        if candidates.is_empty() {
+            debug!("No addresses available for {} missing outbound connections", missing);
+            return;
+        }
+
+        debug!("Connecting to {} peers out of {} missing outbound connections", candidates.len(), missing);
+        let results = join_all(candidates.iter().map(|address| adaptor.connect_peer(address.to_string()))).await;
+        let mut address_manager = self.address_manager.lock();
+        for (address, result) in candidates.into_iter().zip(results) {
+            let marked = match result {
+                Ok(_) => address_manager.mark_connection_success(address),
+                Err(err) => {
+                    debug!("Failed connecting to peer {}: {}", address, err);
+                    address_manager.mark_connection_failure(address)
+                }
+            };
+            if let Err(err) = marked {
+                warn!("Failed storing the connection outcome of {}: {}", address, err);
+            }
+        }
+    }
+}
+
+impl AsyncService for ConnectionManager {
+    fn ident(self: Arc<Self>) -> &'static str {
+        CONNECTION_MANAGER
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", CONNECTION_MANAGER);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            let adaptor = if self.listen_addresses.is_empty() {
+                Adaptor::client_only(self.hub.clone(), self.initializer.clone())
+            } else {
+                let serve_addresses: Vec<_> = self.listen_addresses.iter().copied().map(SocketAddr::from).collect();
+                match Adaptor::serving(&serve_addresses, self.hub.clone(), self.initializer.clone()) {
+                    Ok(adaptor) => adaptor,
+                    Err(err) => {
+                        error!("P2P server failed listening: {}", err);
+                        shutdown_executed.trigger();
+                        return;
+                    }
+                }
+            };
+
+            tokio::pin!(shutdown_signal);
+            let mut interval = tokio::time::interval(CONNECTIONS_INTERVAL);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = &mut shutdown_signal => break,
+                    _ = async {
+                        tokio::select! {
+                            _ = interval.tick() => {}
+                            _ = self.connections_requested.notified() => {}
+                        }
+                        self.handle_connections(&adaptor).await
+                    } => {}
+                }
+            }
+
+            adaptor.close().await;
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", CONNECTION_MANAGER);
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} stopping", CONNECTION_MANAGER);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        Box::pin(async move {
+            // Wait for all peers to be terminated
+            shutdown_executed_signal.await;
+            trace!("{} exiting", CONNECTION_MANAGER);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use addressmanager::Config as AddressManagerConfig;
+    use consensus::consensus::test_consensus::create_temp_db;
+    use p2p::{ProtocolError, Router};
+    use std::{
+        net::TcpListener,
+        time::{Duration, Instant},
+    };
+
+    /// Accepts all connections without running any flow
+    struct NoopInitializer;
+
+    #[async_trait::async_trait]
+    impl ConnectionInitializer for NoopInitializer {
+        async fn initialize_connection(&self, new_router: Arc<Router>) -> Result<(), ProtocolError> {
+            new_router.start();
+            Ok(())
+        }
+    }
+
+    fn unused_address() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let start = Instant::now();
+        while !condition() {
+            if start.elapsed() > Duration::from_secs(10) {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        true
+    }
+
+    #[tokio::test]
+    async fn test_outbound_connections() {
+        let servers: Vec<_> = (0..3)
+            .map(|_| {
+                let address = unused_address();
+                (address, Adaptor::bidirectional(address, Hub::new(), Arc::new(NoopInitializer)).unwrap())
+            })
+            .collect();
+        let forced = NetAddress::from(servers[0].0);
+        let unreachable = NetAddress::from(unused_address());
+
+        let (_lifetime, db) = create_temp_db();
+        let mut address_manager = AddressManager::new(db, AddressManagerConfig::new(vec![], 16111, true)).unwrap();
+        address_manager.add_addresses([servers[1].0.into(), servers[2].0.into(), unreachable]).unwrap();
+        let address_manager = Arc::new(Mutex::new(address_manager));
+
+        let hub = Hub::new();
+        let manager = ConnectionManager::new(
+            vec![],
+            hub.clone(),
+            Arc::new(NoopInitializer),
+            address_manager.clone(),
+            Config::new(3, vec![], vec![forced]),
+        );
+        let adaptor = Adaptor::client_only(hub.clone(), manager.initializer.clone());
+
+        // The forced peer does not count toward the outbound target, so all known addresses are attempted
+        manager.handle_connections(&adaptor).await;
+        assert!(wait_for(|| hub.active_peers_len() == 3).await);
+        assert!(manager.peer_infos().iter().all(|info| info.is_outbound));
+        assert_eq!(address_manager.lock().get(&unreachable).unwrap().connection_failures, 1);
+
+        // A dropped forced peer is reconnected
+        let dropped = hub.active_peers().into_iter().find(|router| NetAddress::from(router.net_address()) == forced).unwrap();
+        dropped.close().await;
+        assert!(wait_for(|| hub.active_peers_len() == 2).await);
+        manager.handle_connections(&adaptor).await;
+        assert!(wait_for(|| hub.active_peers_len() == 3).await);
+        assert_eq!(address_manager.lock().get(&unreachable).unwrap().connection_failures, 2);
+
+        adaptor.close().await;
+        for (_, server) in servers {
+            server.close().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_added_peers() {
+        let servers: Vec<_> = (0..2)
+            .map(|_| {
+                let address = unused_address();
+                (address, Adaptor::bidirectional(address, Hub::new(), Arc::new(NoopInitializer)).unwrap())
+            })
+            .collect();
+        let (permanent, one_shot) = (NetAddress::from(servers[0].0), NetAddress::from(servers[1].0));
+
+        let (_lifetime, db) = create_temp_db();
+        let address_manager = Arc::new(Mutex::new(AddressManager::new(db, AddressManagerConfig::default()).unwrap()));
+        let hub = Hub::new();
+        let manager =
+            ConnectionManager::new(vec![], hub.clone(), Arc::new(NoopInitializer), address_manager, Config::new(0, vec![], vec![]));
+        let adaptor = Adaptor::client_only(hub.clone(), manager.initializer.clone());
+
+        // Adding a peer wakes the connections loop up
+        manager.add_peer(permanent, true);
+        manager.add_peer(one_shot, false);
+        assert!(tokio::time::timeout(Duration::from_secs(1), manager.connections_requested.notified()).await.is_ok());
+        manager.handle_connections(&adaptor).await;
+        assert!(wait_for(|| hub.active_peers_len() == 2).await);
+
+        // Only the permanent peer is reconnected once dropped
+        hub.terminate_all_peers().await;
+        assert!(wait_for(|| hub.active_peers_len() == 0).await);
+        manager.handle_connections(&adaptor).await;
+        assert!(wait_for(|| hub.active_peers_len() == 1).await);
+        assert_eq!(NetAddress::from(hub.active_peers()[0].net_address()), permanent);
+
+        adaptor.close().await;
+        for (_, server) in servers {
+            server.close().await;
+        }
+    }
+}
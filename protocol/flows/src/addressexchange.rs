@@ -0,0 +1,223 @@
+//! Peer address exchange.
+//!
+//! Once connected, each side requests the addresses known to the other a single time. The answer is a random
+//! subset of the addresses whose latest connection attempt succeeded, along with the time they were last seen,
+//! and the addresses received are added to the address manager. To prevent a peer from spraying addresses into
+//! the address manager, or from making the node amplify requests into large answers, the size of an answer is
+//! bounded and unsolicited or too frequent messages are counted as spam.
+
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use consensus_core::time::TimestampMs;
+use kaspa_core::{debug, warn};
+use kaspa_utils::networking::NetAddress;
+use p2p::{
+    dequeue, dequeue_with_timeout,
+    pb::{self, kaspad_message::Payload, AddressesMessage, KaspadMessage, RequestAddressesMessage},
+    ConversionError, IncomingRoute, ProtocolError, Router,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of addresses sent in, and accepted from, a single addresses message
+pub const MAX_ADDRESSES_SEND: usize = 1000;
+
+/// The minimum interval between two address requests of a peer
+pub const ADDRESS_REQUEST_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// The maximum duration to wait for the peer to answer the address request
+pub const ADDRESS_RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Answers the address requests of the peer, at most once every [`ADDRESS_REQUEST_INTERVAL`]
+pub struct SendAddresses {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for SendAddresses {
+    fn name(&self) -> &'static str {
+        "SendAddresses"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        let mut last_response: Option<Instant> = None;
+        loop {
+            dequeue!(self.incoming_route, Payload::RequestAddresses)?;
+            if let Some(last_response) = last_response {
+                if last_response.elapsed() < ADDRESS_REQUEST_INTERVAL {
+                    return Err(ProtocolError::LimitExceeded(format!(
+                        "addresses requested again within {:?}",
+                        ADDRESS_REQUEST_INTERVAL
+                    )));
+                }
+            }
+            last_response = Some(Instant::now());
+
+            let mut addresses: Vec<_> = self
+                .ctx
+                .address_manager()
+                .lock()
+                .gossip_addresses(MAX_ADDRESSES_SEND)
+                .into_iter()
+                .map(|entry| (entry.address, entry.last_seen))
+                .collect();
+            // The local address, when known, replaces one of the gossiped ones so that the answer stays within bounds
+            if let Some(local_address) = self.ctx.local_version().address {
+                addresses.truncate(MAX_ADDRESSES_SEND - 1);
+                addresses.push((local_address, TimestampMs::now()));
+            }
+            let address_list = addresses.into_iter().map(|(address, last_seen)| to_gossiped_address(address, last_seen)).collect();
+            self.router.enqueue(KaspadMessage { payload: Some(Payload::Addresses(AddressesMessage { address_list })) }).await?;
+        }
+    }
+}
+
+impl SendAddresses {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+/// Requests the addresses known to the peer once connected and adds them, along with the address the peer
+/// advertised during the handshake, to the address manager
+pub struct ReceiveAddresses {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for ReceiveAddresses {
+    fn name(&self) -> &'static str {
+        "ReceiveAddresses"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestAddresses(RequestAddressesMessage {
+                    include_all_subnetworks: false,
+                    subnetwork_id: None,
+                })),
+            })
+            .await?;
+        let msg = dequeue_with_timeout!(self.incoming_route, Payload::Addresses, ADDRESS_RESPONSE_TIMEOUT)?;
+        if msg.address_list.len() > MAX_ADDRESSES_SEND {
+            return Err(ProtocolError::LimitExceeded(format!(
+                "peer sent {} addresses while at most {} are allowed",
+                msg.address_list.len(),
+                MAX_ADDRESSES_SEND
+            )));
+        }
+        let mut addresses = msg.address_list.into_iter().map(from_gossiped_address).collect::<Result<Vec<_>, _>>()?;
+        if let Some(advertised_address) = self.router.properties().advertised_address {
+            addresses.push((advertised_address, TimestampMs::now()));
+        }
+
+        let count = addresses.len();
+        match self.ctx.address_manager().lock().add_gossiped_addresses(addresses) {
+            Ok(added) => debug!("Received {} addresses from {}, {} of them new", count, self.router, added),
+            Err(err) => warn!("Failed storing the addresses received from {}: {}", self.router, err),
+        }
+
+        // The addresses were requested once, hence any other addresses message is unsolicited
+        dequeue!(self.incoming_route, Payload::Addresses)?;
+        Err(ProtocolError::LimitExceeded("peer sent unsolicited addresses".to_string()))
+    }
+}
+
+impl ReceiveAddresses {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+fn to_gossiped_address(address: NetAddress, last_seen: TimestampMs) -> pb::NetAddress {
+    // Timestamps beyond the i64 range are far in the future, hence never stored
+    pb::NetAddress { timestamp: i64::try_from(last_seen).unwrap_or_default(), ..address.into() }
+}
+
+fn from_gossiped_address(item: pb::NetAddress) -> Result<(NetAddress, TimestampMs), ConversionError> {
+    let last_seen = TimestampMs::try_from(item.timestamp).map_err(|_| ConversionError::OutOfRange("timestamp"))?;
+    Ok((item.try_into()?, last_seen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use addressmanager::{AddressManager, BanManager, Config, DEFAULT_BAN_DURATION};
+    use consensus::consensus::test_consensus::{create_memory_db, TestConsensus};
+    use consensus_core::config::params::MAINNET_PARAMS;
+    use mining::manager::MiningManager;
+    use p2p::{handshake::LocalVersion, Adaptor, Hub};
+    use parking_lot::Mutex;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn flow_context(consensus: Arc<TestConsensus>, hub: Hub, address_manager: Arc<Mutex<AddressManager>>) -> FlowContext {
+        let mining_manager = Arc::new(MiningManager::new(consensus.clone(), Default::default(), Duration::from_secs(1)));
+        let ban_manager = BanManager::new(create_memory_db(), DEFAULT_BAN_DURATION).unwrap();
+        let local_version = LocalVersion::new(Uuid::new_v4(), "kaspa-mainnet".to_string(), "/test:0.1.0/".to_string());
+        FlowContext::new(consensus, mining_manager, hub, local_version, Arc::new(std::sync::Mutex::new(ban_manager)), address_manager)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_address_exchange() {
+        let consensus = Arc::new(TestConsensus::create_from_temp_db(&MAINNET_PARAMS));
+        let wait_handles = consensus.init();
+
+        let known = [NetAddress::from_str("1.2.3.4:16111").unwrap(), NetAddress::from_str("5.6.7.8:16111").unwrap()];
+        let failed = NetAddress::from_str("9.9.9.9:16111").unwrap();
+        let server_address_manager = Arc::new(Mutex::new(AddressManager::new(create_memory_db(), Config::default()).unwrap()));
+        server_address_manager.lock().add_addresses(known.into_iter().chain([failed])).unwrap();
+        server_address_manager.lock().mark_connection_failure(failed).unwrap();
+
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let server_hub = Hub::new();
+        let server_ctx = flow_context(consensus.clone(), server_hub.clone(), server_address_manager);
+        let server = Adaptor::bidirectional(address, server_hub, Arc::new(server_ctx)).unwrap();
+
+        let client_address_manager = Arc::new(Mutex::new(AddressManager::new(create_memory_db(), Config::default()).unwrap()));
+        let client_hub = Hub::new();
+        let client_ctx = flow_context(consensus.clone(), client_hub.clone(), client_address_manager.clone());
+        let client = Adaptor::client_only(client_hub, Arc::new(client_ctx));
+        client.connect_peer_with_retry(address.to_string(), 10, Duration::from_millis(100)).await.unwrap();
+
+        let start = Instant::now();
+        while client_address_manager.lock().address_count() < known.len() {
+            assert!(start.elapsed() < Duration::from_secs(10), "addresses were not exchanged in time");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        // Only the addresses whose latest connection attempt succeeded are gossiped
+        let mut received = client_address_manager.lock().addresses();
+        received.sort();
+        assert_eq!(received, known.to_vec());
+
+        client.close().await;
+        server.close().await;
+        consensus.shutdown(wait_handles);
+    }
+
+    #[test]
+    fn test_gossiped_address_conversion() {
+        let address = NetAddress::from_str("1.2.3.4:16111").unwrap();
+        let last_seen = TimestampMs::from_millis(1_000);
+        let item = to_gossiped_address(address, last_seen);
+        assert_eq!(item.timestamp, 1_000);
+        assert_eq!(from_gossiped_address(item.clone()).unwrap(), (address, last_seen));
+
+        let negative = pb::NetAddress { timestamp: -1, ..item };
+        assert!(matches!(from_gossiped_address(negative), Err(ConversionError::OutOfRange("timestamp"))));
+    }
+}
@@ -0,0 +1,146 @@
+use super::RELAY_BLOCK_TIMEOUT_BOUNDS;
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use consensus_core::{block::Block, blockstatus::BlockStatus, errors::block::RuleError};
+use hashes::Hash;
+use kaspa_core::debug;
+use p2p::{
+    convert::required_hash,
+    dequeue, dequeue_with_timeout,
+    pb::{kaspad_message::Payload, KaspadMessage, RequestRelayBlocksMessage},
+    IncomingRoute, ProtocolError, Router,
+};
+use std::{collections::VecDeque, sync::Arc, time::Instant};
+
+/// Requests and processes the blocks announced by a peer, resolving orphan blocks through the same peer
+pub struct HandleRelayInvs {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    invs_route: IncomingRoute,
+    blocks_route: IncomingRoute,
+    /// Announcements to handle before reading new ones from the peer, such as the missing ancestors of orphans
+    pending_invs: VecDeque<Hash>,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRelayInvs {
+    fn name(&self) -> &'static str {
+        "HandleRelayInvs"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus().clone();
+        loop {
+            let hash = self.next_inv().await?;
+            match consensus.clone().get_block_status(hash) {
+                Some(BlockStatus::StatusInvalid) => {
+                    return Err(ProtocolError::InvalidBlock(hash, "the block is known to be invalid".to_string()))
+                }
+                Some(status) if status.has_block_body() => continue,
+                _ => {}
+            }
+            // Blocks announced while syncing are downloaded by IBD
+            if self.ctx.is_ibd_running() || self.ctx.is_orphan(hash) {
+                continue;
+            }
+
+            let block = self.request_block(hash).await?;
+            match consensus.clone().validate_and_insert_block(block.clone(), true).await {
+                Ok(_) => {
+                    self.ctx.on_new_block(&block).await;
+                    for unorphaned in self.ctx.unorphan_blocks(hash).await {
+                        self.ctx.on_new_block(&unorphaned).await;
+                    }
+                }
+                Err(RuleError::MissingParents(_)) => self.process_orphan(block),
+                Err(err) => return Err(ProtocolError::InvalidBlock(hash, err.to_string())),
+            }
+        }
+    }
+}
+
+impl HandleRelayInvs {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, invs_route: IncomingRoute, blocks_route: IncomingRoute) -> Self {
+        Self { ctx, router, invs_route, blocks_route, pending_invs: VecDeque::new() }
+    }
+
+    async fn next_inv(&mut self) -> Result<Hash, ProtocolError> {
+        if let Some(hash) = self.pending_invs.pop_front() {
+            return Ok(hash);
+        }
+        let inv = dequeue!(self.invs_route, Payload::InvRelayBlock)?;
+        Ok(required_hash(inv.hash, "block hash")?)
+    }
+
+    async fn request_block(&mut self, hash: Hash) -> Result<Block, ProtocolError> {
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestRelayBlocks(RequestRelayBlocksMessage { hashes: vec![hash.into()] })),
+            })
+            .await?;
+        let requested = Instant::now();
+        // The block mass bounds the serialized block size
+        let timeout = self.router.latency().timeout(RELAY_BLOCK_TIMEOUT_BOUNDS, self.ctx.consensus().clone().max_block_mass());
+        let msg = dequeue_with_timeout!(self.blocks_route, Payload::Block, timeout)?;
+        self.router.latency().record_response(&msg, requested.elapsed());
+        let block: Block = msg.try_into()?;
+        if block.hash() != hash {
+            return Err(ProtocolError::OtherOwned(format!("expected relay block {} but got {}", hash, block.hash())));
+        }
+        Ok(block)
+    }
+
+    /// Adds the block to the orphan pool and queues requests for its missing ancestors
+    fn process_orphan(&mut self, block: Block) {
+        let hash = block.hash();
+        self.ctx.add_orphan(block);
+        let roots = self.ctx.get_orphan_roots(hash).unwrap_or_default();
+        debug!("Block {} relayed by peer {} is an orphan, requesting its {} missing ancestors", hash, self.router, roots.len());
+        for root in roots.into_iter().rev() {
+            self.pending_invs.push_front(root);
+        }
+    }
+}
+
+/// Serves the blocks requested by a peer following their announcement
+pub struct HandleRelayBlockRequests {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRelayBlockRequests {
+    fn name(&self) -> &'static str {
+        "HandleRelayBlockRequests"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let request = dequeue!(self.incoming_route, Payload::RequestRelayBlocks)?;
+            for hash in request.hashes {
+                let hash = Hash::try_from(hash)?;
+                let block = self
+                    .ctx
+                    .consensus()
+                    .clone()
+                    .get_block(hash)
+                    .ok_or_else(|| ProtocolError::OtherOwned(format!("requested relay block {} is not available", hash)))?;
+                self.router.enqueue(KaspadMessage { payload: Some(Payload::Block((&block).into())) }).await?;
+            }
+        }
+    }
+}
+
+impl HandleRelayBlockRequests {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
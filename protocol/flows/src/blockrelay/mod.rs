@@ -0,0 +1,22 @@
+//! Block relay.
+//!
+//! Newly processed blocks are announced to all peers by hash. A peer receiving an announcement of an unknown
+//! block requests it and processes it. If the parents of the block are missing, the block is kept in the orphan
+//! pool and the roots of the missing ancestry are requested from the announcing peer, after which the orphans
+//! are processed in turn.
+
+mod flow;
+mod orphans;
+
+pub use flow::{HandleRelayBlockRequests, HandleRelayInvs};
+pub use orphans::OrphanBlocksPool;
+
+use p2p::TimeoutBounds;
+use std::time::Duration;
+
+/// The maximum number of orphan blocks held by the node
+pub const MAX_ORPHAN_BLOCKS: usize = 600;
+
+/// The bounds of the timeout of a block requested from a peer, adapted to the measured peer latency
+pub const RELAY_BLOCK_TIMEOUT_BOUNDS: TimeoutBounds =
+    TimeoutBounds::new(Duration::from_secs(5), Duration::from_secs(30), Duration::from_secs(120));
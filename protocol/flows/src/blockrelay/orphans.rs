@@ -0,0 +1,121 @@
+use consensus_core::block::Block;
+use hashes::Hash;
+use indexmap::IndexMap;
+use std::collections::{HashSet, VecDeque};
+
+/// Holds relayed blocks whose parents are missing until the parents are processed. When full, the oldest orphan is evicted.
+pub struct OrphanBlocksPool {
+    orphans: IndexMap<Hash, Block>,
+    max_orphans: usize,
+}
+
+impl OrphanBlocksPool {
+    pub fn new(max_orphans: usize) -> Self {
+        Self { orphans: IndexMap::with_capacity(max_orphans), max_orphans }
+    }
+
+    pub fn len(&self) -> usize {
+        self.orphans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty()
+    }
+
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.orphans.contains_key(hash)
+    }
+
+    pub fn add_orphan(&mut self, block: Block) {
+        if self.max_orphans == 0 {
+            return;
+        }
+        if self.orphans.len() == self.max_orphans {
+            self.orphans.shift_remove_index(0);
+        }
+        self.orphans.insert(block.hash(), block);
+    }
+
+    /// Returns the missing ancestors of the orphan, i.e., the ancestors reachable through orphans only whose
+    /// body is not known according to `has_block_body`. Returns `None` if the block is not an orphan.
+    pub fn get_orphan_roots(&self, orphan: Hash, has_block_body: impl Fn(Hash) -> bool) -> Option<Vec<Hash>> {
+        if !self.orphans.contains_key(&orphan) {
+            return None;
+        }
+        let mut roots = Vec::new();
+        let mut visited = HashSet::from([orphan]);
+        let mut queue = VecDeque::from([orphan]);
+        while let Some(current) = queue.pop_front() {
+            if let Some(block) = self.orphans.get(&current) {
+                for &parent in block.header.direct_parents() {
+                    if visited.insert(parent) {
+                        queue.push_back(parent);
+                    }
+                }
+            } else if !has_block_body(current) {
+                roots.push(current);
+            }
+        }
+        Some(roots)
+    }
+
+    /// Removes and returns the orphan children of `parent` whose parents all have a known body according to `has_block_body`
+    pub fn take_unorphaned_children(&mut self, parent: Hash, has_block_body: impl Fn(Hash) -> bool) -> Vec<Block> {
+        let unorphaned: Vec<Hash> = self
+            .orphans
+            .values()
+            .filter(|block| {
+                let parents = block.header.direct_parents();
+                parents.contains(&parent) && parents.iter().all(|&hash| has_block_body(hash))
+            })
+            .map(|block| block.hash())
+            .collect();
+        unorphaned.into_iter().map(|hash| self.orphans.shift_remove(&hash).unwrap()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::header::Header;
+
+    fn block(hash: u64, parents: &[u64]) -> Block {
+        let parents = parents.iter().map(|&parent| parent.into()).collect();
+        let mut header =
+            Header::new(0, vec![parents], 1.into(), 2.into(), 3.into(), Default::default(), 0, 0, 0, 0.into(), 0, 4.into());
+        header.hash = hash.into();
+        Block::from_header(header)
+    }
+
+    #[test]
+    fn test_orphan_roots_and_unorphaning() {
+        let mut pool = OrphanBlocksPool::new(10);
+        // 10 is known, 12 and 13 are orphans, 11 and 14 are missing
+        pool.add_orphan(block(12, &[10, 11]));
+        pool.add_orphan(block(13, &[12, 14]));
+        let known: HashSet<Hash> = HashSet::from([10.into()]);
+
+        let mut roots = pool.get_orphan_roots(13.into(), |hash| known.contains(&hash)).unwrap();
+        roots.sort();
+        assert_eq!(roots, vec![11.into(), 14.into()]);
+        assert_eq!(pool.get_orphan_roots(11.into(), |hash| known.contains(&hash)), None);
+
+        // Processing 11 unorphans 12 only, since 13 still misses 14
+        let known: HashSet<Hash> = HashSet::from([10.into(), 11.into(), 12.into()]);
+        let unorphaned = pool.take_unorphaned_children(11.into(), |hash| known.contains(&hash));
+        assert_eq!(unorphaned.iter().map(|block| block.hash()).collect::<Vec<_>>(), vec![12.into()]);
+        assert!(pool.take_unorphaned_children(12.into(), |hash| known.contains(&hash)).is_empty());
+        assert_eq!(pool.get_orphan_roots(13.into(), |hash| known.contains(&hash)), Some(vec![14.into()]));
+    }
+
+    #[test]
+    fn test_orphans_eviction() {
+        let mut pool = OrphanBlocksPool::new(2);
+        for hash in 1..=3 {
+            pool.add_orphan(block(hash, &[0]));
+        }
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.contains(&1.into()));
+        assert!(pool.contains(&2.into()) && pool.contains(&3.into()));
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{
+    addressexchange::{ReceiveAddresses, SendAddresses},
+    blockrelay::{HandleRelayBlockRequests, HandleRelayInvs, OrphanBlocksPool, MAX_ORPHAN_BLOCKS},
+    flow_trait::{spawn_flow, Flow},
+    ibd::{
+        HandleIbdBlockRequests, HandleIbdChainBlockLocatorRequests, HandleRequestHeaders, HandleRequestPruningPointAndItsAnticone,
+        HandleRequestPruningPointUtxoSet, IbdFlow,
+    },
+    ping::{ReceivePings, SendPings},
+    txrelay::{
+        HandleRelayedTransactions, HandleRequestTransactions, KnownTransactions, SendTransactionInvs, TransactionsAnnouncement,
+        KNOWN_TRANSACTIONS_CAPACITY, TRANSACTION_REBROADCAST_INTERVAL,
+    },
+};
+use addressmanager::{AddressManager, BanManager};
+use consensus_core::{api::DynConsensus, block::Block, tx::TransactionId};
+use hashes::Hash;
+use kaspa_core::warn;
+use mining::manager::MiningManager;
+use p2p::{
+    handshake::{KaspadHandshake, LocalVersion},
+    pb::{kaspad_message::Payload, InvRelayBlockMessage, KaspadMessage},
+    ConnectionInitializer, Hub, KaspadMessagePayloadType, Misbehavior, ProtocolError, Router,
+};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+use tokio::sync::broadcast;
+
+/// The number of transaction announcement batches buffered for each peer
+const TRANSACTION_BROADCAST_CAPACITY: usize = 1024;
+
+/// The node-wide state shared by the flows of all peers
+#[derive(Clone)]
+pub struct FlowContext {
+    consensus: DynConsensus,
+    mining_manager: Arc<MiningManager>,
+    hub: Hub,
+    local_version: LocalVersion,
+    is_ibd_running: Arc<AtomicBool>,
+    orphan_blocks: Arc<Mutex<OrphanBlocksPool>>,
+    transaction_broadcast: broadcast::Sender<Arc<TransactionsAnnouncement>>,
+    last_rebroadcast_time: Arc<Mutex<Instant>>,
+    /// The transactions currently requested from some peer, which should not be requested from others
+    requested_transactions: Arc<Mutex<HashSet<TransactionId>>>,
+    ban_manager: Arc<Mutex<BanManager>>,
+    address_manager: Arc<parking_lot::Mutex<AddressManager>>,
+}
+
+impl FlowContext {
+    pub fn new(
+        consensus: DynConsensus,
+        mining_manager: Arc<MiningManager>,
+        hub: Hub,
+        local_version: LocalVersion,
+        ban_manager: Arc<Mutex<BanManager>>,
+        address_manager: Arc<parking_lot::Mutex<AddressManager>>,
+    ) -> Self {
+        let (transaction_broadcast, _) = broadcast::channel(TRANSACTION_BROADCAST_CAPACITY);
+        Self {
+            consensus,
+            mining_manager,
+            hub,
+            local_version,
+            is_ibd_running: Default::default(),
+            orphan_blocks: Arc::new(Mutex::new(OrphanBlocksPool::new(MAX_ORPHAN_BLOCKS))),
+            transaction_broadcast,
+            last_rebroadcast_time: Arc::new(Mutex::new(Instant::now())),
+            requested_transactions: Default::default(),
+            ban_manager,
+            address_manager,
+        }
+    }
+
+    pub fn consensus(&self) -> &DynConsensus {
+        &self.consensus
+    }
+
+    pub fn mining_manager(&self) -> &Arc<MiningManager> {
+        &self.mining_manager
+    }
+
+    pub fn hub(&self) -> &Hub {
+        &self.hub
+    }
+
+    pub fn local_version(&self) -> &LocalVersion {
+        &self.local_version
+    }
+
+    pub fn address_manager(&self) -> &Arc<parking_lot::Mutex<AddressManager>> {
+        &self.address_manager
+    }
+
+    /// The flag set while IBD is running, for sharing with other components rejecting work during IBD
+    pub fn ibd_running_flag(&self) -> Arc<AtomicBool> {
+        self.is_ibd_running.clone()
+    }
+
+    pub fn is_ibd_running(&self) -> bool {
+        self.is_ibd_running.load(Ordering::SeqCst)
+    }
+
+    /// Marks IBD as running, returning `false` if IBD is already running with another peer
+    pub fn try_set_ibd_running(&self) -> bool {
+        self.is_ibd_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+    }
+
+    pub fn unset_ibd_running(&self) {
+        self.is_ibd_running.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_orphan(&self, hash: Hash) -> bool {
+        self.orphan_blocks.lock().unwrap().contains(&hash)
+    }
+
+    pub fn add_orphan(&self, block: Block) {
+        self.orphan_blocks.lock().unwrap().add_orphan(block);
+    }
+
+    /// Returns the missing ancestors of the orphan block, or `None` if the block is not an orphan
+    pub fn get_orphan_roots(&self, orphan: Hash) -> Option<Vec<Hash>> {
+        self.orphan_blocks.lock().unwrap().get_orphan_roots(orphan, |hash| self.has_block_body(hash))
+    }
+
+    /// Processes the orphans which can be processed now that `root` was processed, along with their orphan
+    /// descendants, returning the successfully processed ones
+    pub async fn unorphan_blocks(&self, root: Hash) -> Vec<Block> {
+        let mut processed = Vec::new();
+        let mut parents = vec![root];
+        while let Some(parent) = parents.pop() {
+            let unorphaned = self.orphan_blocks.lock().unwrap().take_unorphaned_children(parent, |hash| self.has_block_body(hash));
+            for block in unorphaned {
+                let hash = block.hash();
+                match self.consensus.clone().validate_and_insert_block(block.clone(), true).await {
+                    Ok(_) => {
+                        parents.push(hash);
+                        processed.push(block);
+                    }
+                    Err(err) => warn!("Validation failed for orphan block {}: {}", hash, err),
+                }
+            }
+        }
+        processed
+    }
+
+    fn has_block_body(&self, hash: Hash) -> bool {
+        matches!(self.consensus.clone().get_block_status(hash), Some(status) if status.has_block_body())
+    }
+
+    /// Updates the mempool with the transactions of a newly processed block and announces the block to all peers.
+    /// Transactions submitted to this node are rebroadcast along, if due
+    pub async fn on_new_block(&self, block: &Block) {
+        let promoted = self.mining_manager.mempool_mut().handle_new_block_transactions(&block.transactions);
+        self.broadcast_transactions(promoted);
+        self.maybe_rebroadcast_transactions();
+        self.hub
+            .broadcast(KaspadMessage {
+                payload: Some(Payload::InvRelayBlock(InvRelayBlockMessage { hash: Some(block.hash().into()) })),
+            })
+            .await;
+    }
+
+    /// Announces the ids of transactions newly accepted to the mempool to all peers
+    pub fn broadcast_transactions(&self, ids: Vec<TransactionId>) {
+        self.announce_transactions(TransactionsAnnouncement { ids, is_rebroadcast: false });
+    }
+
+    /// Revalidates the high priority mempool transactions, i.e., the ones submitted to this node, and rebroadcasts the
+    /// valid ones to all peers, at most once every [`TRANSACTION_REBROADCAST_INTERVAL`]
+    fn maybe_rebroadcast_transactions(&self) {
+        {
+            let mut last_rebroadcast_time = self.last_rebroadcast_time.lock().unwrap();
+            if last_rebroadcast_time.elapsed() < TRANSACTION_REBROADCAST_INTERVAL {
+                return;
+            }
+            *last_rebroadcast_time = Instant::now();
+        }
+        // Revalidation also expires the priority of old transactions, hence takes place even if rebroadcasting is disabled
+        let mut mempool = self.mining_manager.mempool_mut();
+        let ids = mempool.revalidate_high_priority_transactions();
+        let enabled = mempool.config().high_priority_rebroadcast_enabled;
+        drop(mempool);
+        if enabled {
+            self.announce_transactions(TransactionsAnnouncement { ids, is_rebroadcast: true });
+        }
+    }
+
+    fn announce_transactions(&self, announcement: TransactionsAnnouncement) {
+        if !announcement.ids.is_empty() {
+            // Sending only fails if there are no peers to announce to
+            let _ = self.transaction_broadcast.send(Arc::new(announcement));
+        }
+    }
+
+    pub fn subscribe_transaction_broadcasts(&self) -> broadcast::Receiver<Arc<TransactionsAnnouncement>> {
+        self.transaction_broadcast.subscribe()
+    }
+
+    /// Registers the ids as requested from a peer, returning the ones which are not already requested from another peer
+    pub fn register_requested_transactions(&self, ids: Vec<TransactionId>) -> Vec<TransactionId> {
+        let mut requested_transactions = self.requested_transactions.lock().unwrap();
+        ids.into_iter().filter(|&id| requested_transactions.insert(id)).collect()
+    }
+
+    pub fn unregister_requested_transactions(&self, ids: &[TransactionId]) {
+        let mut requested_transactions = self.requested_transactions.lock().unwrap();
+        for id in ids {
+            requested_transactions.remove(id);
+        }
+    }
+
+    /// Counts the misbehavior against the IP of the peer, which is banned once its score reaches the threshold
+    pub fn report_misbehavior(&self, router: &Router, misbehavior: Misbehavior) {
+        let ip = router.net_address().ip();
+        match self.ban_manager.lock().unwrap().record_misbehavior(ip, misbehavior.score()) {
+            Ok(true) => warn!("Banned {} for misbehavior ({:?})", ip, misbehavior),
+            Ok(false) => {}
+            Err(err) => warn!("Failed storing the ban of {}: {}", ip, err),
+        }
+    }
+
+    fn register_flows(&self, router: &Arc<Router>) -> Vec<Box<dyn Flow>> {
+        let known_transactions = Arc::new(Mutex::new(KnownTransactions::new(KNOWN_TRANSACTIONS_CAPACITY)));
+        let mut flows: Vec<Box<dyn Flow>> = vec![
+            Box::new(ReceivePings::new(router.clone(), router.subscribe(vec![KaspadMessagePayloadType::Ping]))),
+            Box::new(SendPings::new(router.clone(), router.subscribe(vec![KaspadMessagePayloadType::Pong]))),
+            Box::new(HandleIbdChainBlockLocatorRequests::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestIbdChainBlockLocator]),
+            )),
+            Box::new(HandleRequestHeaders::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestHeaders, KaspadMessagePayloadType::RequestNextHeaders]),
+            )),
+            Box::new(HandleIbdBlockRequests::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestIbdBlocks]),
+            )),
+            Box::new(HandleRequestPruningPointAndItsAnticone::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestPruningPointAndItsAnticone]),
+            )),
+            Box::new(HandleRequestPruningPointUtxoSet::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![
+                    KaspadMessagePayloadType::RequestPruningPointUtxoSet,
+                    KaspadMessagePayloadType::RequestNextPruningPointUtxoSetChunk,
+                ]),
+            )),
+            Box::new(HandleRelayInvs::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::InvRelayBlock]),
+                router.subscribe(vec![KaspadMessagePayloadType::Block]),
+            )),
+            Box::new(HandleRelayBlockRequests::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestRelayBlocks]),
+            )),
+            Box::new(HandleRelayedTransactions::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::InvTransactions]),
+                router.subscribe(vec![KaspadMessagePayloadType::Transaction, KaspadMessagePayloadType::TransactionNotFound]),
+                known_transactions.clone(),
+            )),
+            Box::new(HandleRequestTransactions::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestTransactions]),
+            )),
+            Box::new(SendTransactionInvs::new(router.clone(), self.subscribe_transaction_broadcasts(), known_transactions)),
+            Box::new(SendAddresses::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestAddresses]),
+            )),
+            Box::new(ReceiveAddresses::new(self.clone(), router.clone(), router.subscribe(vec![KaspadMessagePayloadType::Addresses]))),
+        ];
+
+        // The node syncs from the peers it connects to
+        if router.is_outbound() {
+            flows.push(Box::new(IbdFlow::new(
+                self.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::IbdChainBlockLocator]),
+                router.subscribe(vec![KaspadMessagePayloadType::BlockHeaders, KaspadMessagePayloadType::DoneHeaders]),
+                router.subscribe(vec![KaspadMessagePayloadType::IbdBlock]),
+                router.subscribe(vec![
+                    KaspadMessagePayloadType::PruningPoints,
+                    KaspadMessagePayloadType::TrustedData,
+                    KaspadMessagePayloadType::BlockWithTrustedDataV4,
+                    KaspadMessagePayloadType::DoneBlocksWithTrustedData,
+                ]),
+                router.subscribe(vec![
+                    KaspadMessagePayloadType::PruningPointUtxoSetChunk,
+                    KaspadMessagePayloadType::DonePruningPointUtxoSetChunks,
+                    KaspadMessagePayloadType::UnexpectedPruningPoint,
+                ]),
+            )));
+        }
+
+        flows
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionInitializer for FlowContext {
+    async fn initialize_connection(&self, new_router: Arc<Router>) -> Result<(), ProtocolError> {
+        let mut handshake = KaspadHandshake::new(new_router.clone());
+        new_router.start();
+        let properties = handshake.handshake(&self.local_version).await?;
+        new_router.set_properties(Arc::new(properties));
+
+        // The flows must be subscribed before signaling the peer we are ready
+        let flows = self.register_flows(&new_router);
+        handshake.exchange_ready_messages().await?;
+
+        for flow in flows {
+            spawn_flow(flow, self.clone());
+        }
+        Ok(())
+    }
+
+    fn accept_inbound(&self, address: SocketAddr) -> bool {
+        !self.ban_manager.lock().unwrap().is_banned(address.ip())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::FlowContext;
+use kaspa_core::{debug, warn};
+use p2p::{ProtocolError, Router};
+use std::sync::Arc;
+
+/// A long running protocol flow serving a single peer
+#[async_trait::async_trait]
+pub trait Flow: Send {
+    fn name(&self) -> &'static str;
+
+    fn router(&self) -> Arc<Router>;
+
+    /// Runs the flow until the peer disconnects or a protocol error occurs
+    async fn start(&mut self) -> Result<(), ProtocolError>;
+}
+
+/// Spawns the flow on the tokio runtime. A protocol error disconnects the peer and is counted against it
+/// if it is a misbehavior.
+pub fn spawn_flow(mut flow: Box<dyn Flow>, ctx: FlowContext) {
+    tokio::spawn(async move {
+        let router = flow.router();
+        match flow.start().await {
+            Ok(()) => debug!("P2P, flow {} of peer {} exited", flow.name(), router),
+            Err(ProtocolError::ConnectionClosed) => debug!("P2P, flow {} of peer {} exited: connection closed", flow.name(), router),
+            Err(err) => {
+                warn!("P2P, flow {} of peer {} failed: {}", flow.name(), router, err);
+                if let Some(misbehavior) = err.misbehavior() {
+                    ctx.report_misbehavior(&router, misbehavior);
+                }
+                router.close().await;
+            }
+        }
+    });
+}
@@ -0,0 +1,400 @@
+use super::{HEADER_SIZE_ESTIMATE, IBD_BATCH_SIZE, IBD_TIMEOUT_BOUNDS, PRUNING_POINT_UTXO_SET_CHUNK_SIZE, UTXO_ENTRY_SIZE_ESTIMATE};
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use consensus_core::{
+    block::Block,
+    blockstatus::BlockStatus,
+    header::Header,
+    trusted::{TrustedBlock, TrustedGhostdagData},
+    tx::{TransactionOutpoint, UtxoEntry},
+};
+use hashes::Hash;
+use kaspa_core::{debug, info};
+use p2p::{
+    convert::required_hash,
+    dequeue_with_timeout,
+    pb::{
+        kaspad_message::Payload, KaspadMessage, RequestHeadersMessage, RequestIbdBlocksMessage, RequestIbdChainBlockLocatorMessage,
+        RequestNextHeadersMessage, RequestNextPruningPointUtxoSetChunkMessage, RequestPruningPointAndItsAnticoneMessage,
+        RequestPruningPointUtxoSetMessage,
+    },
+    IncomingRoute, ProtocolError, Router,
+};
+use std::{sync::Arc, time::Instant};
+
+/// The maximum number of chain block locator requests made while searching for the highest known syncer chain block
+const MAX_LOCATOR_ZOOM_IN_STEPS: usize = 64;
+
+/// Syncs the node with an outbound peer
+pub struct IbdFlow {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    chain_locator_route: IncomingRoute,
+    headers_route: IncomingRoute,
+    blocks_route: IncomingRoute,
+    trusted_data_route: IncomingRoute,
+    utxo_set_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for IbdFlow {
+    fn name(&self) -> &'static str {
+        "IBD"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        if !self.ctx.try_set_ibd_running() {
+            debug!("IBD is already running, skipping IBD with peer {}", self.router);
+            return Ok(());
+        }
+        let result = self.ibd().await;
+        self.ctx.unset_ibd_running();
+        result
+    }
+}
+
+impl IbdFlow {
+    pub fn new(
+        ctx: FlowContext,
+        router: Arc<Router>,
+        chain_locator_route: IncomingRoute,
+        headers_route: IncomingRoute,
+        blocks_route: IncomingRoute,
+        trusted_data_route: IncomingRoute,
+        utxo_set_route: IncomingRoute,
+    ) -> Self {
+        Self { ctx, router, chain_locator_route, headers_route, blocks_route, trusted_data_route, utxo_set_route }
+    }
+
+    async fn ibd(&mut self) -> Result<(), ProtocolError> {
+        let (syncer_tip, highest_known) = self.negotiate_missing_syncer_chain_segment().await?;
+        let consensus = self.ctx.consensus().clone();
+        if let Some(status) = consensus.clone().get_block_status(syncer_tip) {
+            if status != BlockStatus::StatusHeaderOnly {
+                debug!("IBD: the selected tip {} of peer {} is already synced", syncer_tip, self.router);
+                return Ok(());
+            }
+        }
+
+        info!("IBD with peer {} started: syncing from {} to the peer selected tip {}", self.router, highest_known, syncer_tip);
+        if highest_known != syncer_tip {
+            self.sync_headers(highest_known, syncer_tip).await?;
+        }
+        if consensus.clone().get_block_status(syncer_tip).is_none() {
+            return Err(ProtocolError::OtherOwned(format!("peer did not send the header of its selected tip {}", syncer_tip)));
+        }
+        self.sync_missing_block_bodies(syncer_tip).await?;
+        info!("IBD with peer {} finished", self.router);
+        Ok(())
+    }
+
+    /// Returns the syncer selected tip along with the highest block of the syncer selected chain known to this node.
+    /// The syncer pruning point is synced first if unknown, after which it is the lowest known block
+    async fn negotiate_missing_syncer_chain_segment(&mut self) -> Result<(Hash, Hash), ProtocolError> {
+        let mut locator = self.request_chain_block_locator(None, None).await?;
+        let (syncer_tip, syncer_pruning_point) = match (locator.first(), locator.last()) {
+            (Some(&tip), Some(&pruning_point)) => (tip, pruning_point),
+            _ => return Err(ProtocolError::Other("peer sent an empty chain block locator")),
+        };
+        if !self.is_known(syncer_pruning_point) {
+            self.sync_pruning_point(syncer_pruning_point).await?;
+        }
+
+        for _ in 0..MAX_LOCATOR_ZOOM_IN_STEPS {
+            // The lowest locator hash is always known at this point
+            let index = locator.iter().position(|&hash| self.is_known(hash)).unwrap();
+            // Each locator includes the selected parent of its highest hash, so if that is the highest
+            // known hash there's nothing left to zoom into
+            if index <= 1 {
+                return Ok((syncer_tip, locator[index]));
+            }
+            let (low, high) = (locator[index], locator[index - 1]);
+            locator = self.request_chain_block_locator(Some(low), Some(high)).await?;
+            if locator.first() != Some(&high) || locator.last() != Some(&low) {
+                return Err(ProtocolError::Other("peer sent a chain block locator which does not match the requested range"));
+            }
+        }
+        Err(ProtocolError::Other("peer chain block locators did not converge"))
+    }
+
+    async fn request_chain_block_locator(&mut self, low: Option<Hash>, high: Option<Hash>) -> Result<Vec<Hash>, ProtocolError> {
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestIbdChainBlockLocator(RequestIbdChainBlockLocatorMessage {
+                    low_hash: low.map(|hash| hash.into()),
+                    high_hash: high.map(|hash| hash.into()),
+                })),
+            })
+            .await?;
+        let timeout = self.router.latency().timeout(IBD_TIMEOUT_BOUNDS, 0);
+        let msg = dequeue_with_timeout!(self.chain_locator_route, Payload::IbdChainBlockLocator, timeout)?;
+        Ok(msg.block_locator_hashes.into_iter().map(Hash::try_from).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Syncs the pruning point of the syncer along with its trusted data and UTXO set, which is only done by a node
+    /// holding no blocks but genesis
+    async fn sync_pruning_point(&mut self, pruning_point: Hash) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus().clone();
+        consensus.clone().begin_trusted_sync().map_err(|err| {
+            ProtocolError::OtherOwned(format!(
+                "cannot sync from peer {} with an unknown pruning point {}: {}",
+                self.router, pruning_point, err
+            ))
+        })?;
+        info!("IBD: syncing the pruning point {} from peer {}", pruning_point, self.router);
+
+        let (past_pruning_points, trusted_blocks) = self.receive_pruning_point_trusted_data(pruning_point).await?;
+        for trusted_block in trusted_blocks {
+            let hash = trusted_block.block.hash();
+            consensus
+                .clone()
+                .validate_and_insert_trusted_block(trusted_block)
+                .map_err(|err| ProtocolError::InvalidBlock(hash, err.to_string()))?;
+        }
+        info!("IBD: processed the trusted data of the pruning point {}", pruning_point);
+
+        self.sync_pruning_point_utxo_set(pruning_point).await?;
+        consensus
+            .clone()
+            .import_pruning_point_utxo_set(past_pruning_points)
+            .map_err(|err| ProtocolError::InvalidBlock(pruning_point, err.to_string()))?;
+        consensus.end_trusted_sync();
+        info!("IBD: imported the UTXO set of the pruning point {}", pruning_point);
+        Ok(())
+    }
+
+    /// Returns the hashes of the past pruning points of the syncer, ending with `pruning_point`, along with the trusted
+    /// blocks in topological order. The DAA data of the mergesets of trusted blocks is not transferred, since it is only
+    /// read when UTXO validating a chain block, which trusted blocks never are
+    async fn receive_pruning_point_trusted_data(
+        &mut self,
+        pruning_point: Hash,
+    ) -> Result<(Vec<Hash>, Vec<TrustedBlock>), ProtocolError> {
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestPruningPointAndItsAnticone(RequestPruningPointAndItsAnticoneMessage {})),
+            })
+            .await?;
+
+        let timeout = self.router.latency().timeout(IBD_TIMEOUT_BOUNDS, 0);
+        let msg = dequeue_with_timeout!(self.trusted_data_route, Payload::PruningPoints, timeout)?;
+        let past_pruning_points =
+            msg.headers.into_iter().map(|header| Ok(Header::try_from(header)?.hash)).collect::<Result<Vec<_>, ProtocolError>>()?;
+        if past_pruning_points.last() != Some(&pruning_point) {
+            return Err(ProtocolError::OtherOwned(format!("peer sent pruning points which do not end with {}", pruning_point)));
+        }
+
+        // The trusted data is sent at once and its size is not known in advance
+        let msg = dequeue_with_timeout!(self.trusted_data_route, Payload::TrustedData, IBD_TIMEOUT_BOUNDS.max)?;
+        let mut trusted_blocks = msg
+            .daa_window
+            .into_iter()
+            .map(|daa_block| {
+                let header = Header::try_from(daa_block.header.ok_or(ProtocolError::Other("DAA block without a header"))?)?;
+                let ghostdag_data = daa_block.ghostdag_data.ok_or(ProtocolError::Other("DAA block without GHOSTDAG data"))?;
+                Ok(TrustedBlock::new(Block::from_header(header), ghostdag_data.try_into()?, vec![]))
+            })
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+        let ghostdag_data = msg
+            .ghostdag_data
+            .into_iter()
+            .map(|pair| {
+                let ghostdag_data = pair.ghostdag_data.ok_or(ProtocolError::Other("block GHOSTDAG data is missing"))?;
+                Ok((required_hash(pair.hash, "block hash")?, TrustedGhostdagData::try_from(ghostdag_data)?))
+            })
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+
+        let max_block_size = self.ctx.consensus().clone().max_block_mass();
+        loop {
+            let timeout = self.router.latency().timeout(IBD_TIMEOUT_BOUNDS, max_block_size);
+            let msg = match tokio::time::timeout(timeout, self.trusted_data_route.recv()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) => return Err(ProtocolError::ConnectionClosed),
+                Err(_) => return Err(ProtocolError::Timeout(timeout)),
+            };
+            let msg = match msg.payload {
+                Some(Payload::BlockWithTrustedDataV4(msg)) => msg,
+                Some(Payload::DoneBlocksWithTrustedData(_)) => break,
+                payload => {
+                    return Err(ProtocolError::UnexpectedMessage(
+                        "BlockWithTrustedDataV4 or DoneBlocksWithTrustedData",
+                        payload.as_ref().map(|p| p.into()),
+                    ))
+                }
+            };
+            let block = Block::try_from(msg.block.ok_or(ProtocolError::Other("block with trusted data without a block"))?)?;
+            let (hash, block_ghostdag_data) =
+                match msg.ghostdag_data_indices.first().and_then(|&index| ghostdag_data.get(index as usize)) {
+                    Some(pair) => pair,
+                    None => return Err(ProtocolError::OtherOwned(format!("block {} refers to missing GHOSTDAG data", block.hash()))),
+                };
+            if *hash != block.hash() {
+                return Err(ProtocolError::OtherOwned(format!("block {} refers to the GHOSTDAG data of {}", block.hash(), hash)));
+            }
+            trusted_blocks.push(TrustedBlock::new(block, block_ghostdag_data.clone(), vec![]));
+        }
+
+        // Blue work is strictly increasing along the edges of the DAG, hence sorting by it yields a topological order
+        trusted_blocks.sort_by_key(|trusted_block| (trusted_block.ghostdag_data.blue_work, trusted_block.block.hash()));
+        Ok((past_pruning_points, trusted_blocks))
+    }
+
+    async fn sync_pruning_point_utxo_set(&mut self, pruning_point: Hash) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus().clone();
+        // Restarts the import of an interrupted sync
+        consensus.clone().clear_imported_pruning_point_utxos().map_err(|err| ProtocolError::OtherOwned(err.to_string()))?;
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestPruningPointUtxoSet(RequestPruningPointUtxoSetMessage {
+                    pruning_point_hash: Some(pruning_point.into()),
+                })),
+            })
+            .await?;
+
+        let mut received = 0;
+        loop {
+            let timeout =
+                self.router.latency().timeout(IBD_TIMEOUT_BOUNDS, PRUNING_POINT_UTXO_SET_CHUNK_SIZE as u64 * UTXO_ENTRY_SIZE_ESTIMATE);
+            let msg = match tokio::time::timeout(timeout, self.utxo_set_route.recv()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) => return Err(ProtocolError::ConnectionClosed),
+                Err(_) => return Err(ProtocolError::Timeout(timeout)),
+            };
+            let chunk = match msg.payload {
+                Some(Payload::PruningPointUtxoSetChunk(msg)) => msg.outpoint_and_utxo_entry_pairs,
+                Some(Payload::DonePruningPointUtxoSetChunks(_)) => break,
+                Some(Payload::UnexpectedPruningPoint(_)) => {
+                    return Err(ProtocolError::OtherOwned(format!("the pruning point of peer {} moved on during IBD", self.router)))
+                }
+                payload => {
+                    return Err(ProtocolError::UnexpectedMessage(
+                        "PruningPointUtxoSetChunk or DonePruningPointUtxoSetChunks",
+                        payload.as_ref().map(|p| p.into()),
+                    ))
+                }
+            };
+            let utxos = chunk.into_iter().map(<(TransactionOutpoint, UtxoEntry)>::try_from).collect::<Result<Vec<_>, _>>()?;
+            consensus.clone().append_imported_pruning_point_utxos(&utxos).map_err(|err| ProtocolError::OtherOwned(err.to_string()))?;
+            received += utxos.len();
+            debug!("IBD: received {} UTXO set entries of the pruning point {}", received, pruning_point);
+
+            self.router
+                .enqueue(KaspadMessage {
+                    payload: Some(Payload::RequestNextPruningPointUtxoSetChunk(RequestNextPruningPointUtxoSetChunkMessage {})),
+                })
+                .await?;
+        }
+        info!("IBD: received the {} UTXO set entries of the pruning point {}", received, pruning_point);
+        Ok(())
+    }
+
+    fn is_known(&self, hash: Hash) -> bool {
+        matches!(self.ctx.consensus().clone().get_block_status(hash), Some(status) if status != BlockStatus::StatusInvalid)
+    }
+
+    async fn sync_headers(&mut self, low: Hash, high: Hash) -> Result<(), ProtocolError> {
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestHeaders(RequestHeadersMessage {
+                    low_hash: Some(low.into()),
+                    high_hash: Some(high.into()),
+                })),
+            })
+            .await?;
+
+        let mut requested = Instant::now();
+        let mut processed = 0;
+        loop {
+            let timeout = self.router.latency().timeout(IBD_TIMEOUT_BOUNDS, IBD_BATCH_SIZE as u64 * HEADER_SIZE_ESTIMATE);
+            let msg = match tokio::time::timeout(timeout, self.headers_route.recv()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) => return Err(ProtocolError::ConnectionClosed),
+                Err(_) => return Err(ProtocolError::Timeout(timeout)),
+            };
+            let headers = match msg.payload {
+                Some(Payload::BlockHeaders(msg)) => {
+                    self.router.latency().record_response(&msg, requested.elapsed());
+                    msg.block_headers
+                }
+                Some(Payload::DoneHeaders(_)) => break,
+                payload => {
+                    return Err(ProtocolError::UnexpectedMessage("BlockHeaders or DoneHeaders", payload.as_ref().map(|p| p.into())))
+                }
+            };
+            if headers.is_empty() {
+                return Err(ProtocolError::Other("peer sent an empty headers batch"));
+            }
+            let headers = headers.into_iter().map(Header::try_from).collect::<Result<Vec<_>, _>>()?;
+            processed += headers.len();
+            let last_hash = headers.last().unwrap().hash;
+            self.process_blocks(headers.into_iter().map(Block::from_header).collect(), false).await?;
+            // The syncer chain may be lighter than the local headers selected tip until fully synced, hence reachability
+            // is hinted with the topologically highest header to keep its reindex root close to the syncer chain
+            self.ctx.consensus().clone().hint_virtual_selected_parent(last_hash);
+            info!("IBD: processed {} block headers from peer {}", processed, self.router);
+
+            self.router.enqueue(KaspadMessage { payload: Some(Payload::RequestNextHeaders(RequestNextHeadersMessage {})) }).await?;
+            requested = Instant::now();
+        }
+        Ok(())
+    }
+
+    async fn sync_missing_block_bodies(&mut self, high: Hash) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus().clone();
+        // The block mass bounds the serialized block size
+        let max_block_size = consensus.clone().max_block_mass();
+        let hashes = consensus.get_missing_block_body_hashes(high).map_err(|err| ProtocolError::OtherOwned(err.to_string()))?;
+        if hashes.is_empty() {
+            return Ok(());
+        }
+
+        info!("IBD: downloading {} missing block bodies from peer {}", hashes.len(), self.router);
+        let mut processed = 0;
+        for chunk in hashes.chunks(IBD_BATCH_SIZE) {
+            self.router
+                .enqueue(KaspadMessage {
+                    payload: Some(Payload::RequestIbdBlocks(RequestIbdBlocksMessage {
+                        hashes: chunk.iter().map(|&hash| hash.into()).collect(),
+                    })),
+                })
+                .await?;
+
+            // The blocks of a batch are streamed, so each one is timed from the arrival of the previous one
+            let mut requested = Instant::now();
+            let mut blocks = Vec::with_capacity(chunk.len());
+            for &expected in chunk {
+                let timeout = self.router.latency().timeout(IBD_TIMEOUT_BOUNDS, max_block_size);
+                let msg = dequeue_with_timeout!(self.blocks_route, Payload::IbdBlock, timeout)?;
+                self.router.latency().record_response(&msg, requested.elapsed());
+                requested = Instant::now();
+                let block: Block = msg.try_into()?;
+                if block.hash() != expected {
+                    return Err(ProtocolError::OtherOwned(format!("expected IBD block {} but got {}", expected, block.hash())));
+                }
+                blocks.push(block);
+            }
+            self.process_blocks(blocks, true).await?;
+
+            processed += chunk.len();
+            info!("IBD: processed {}/{} block bodies ({}%)", processed, hashes.len(), processed * 100 / hashes.len());
+        }
+        Ok(())
+    }
+
+    /// Submits the blocks to consensus as a single batch, which is processed as a group while respecting the
+    /// dependencies of its blocks
+    async fn process_blocks(&self, blocks: Vec<Block>, update_virtual: bool) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus();
+        let hashes: Vec<Hash> = blocks.iter().map(|block| block.hash()).collect();
+        let results = consensus.validate_and_insert_blocks(blocks, update_virtual).await;
+        for (hash, result) in hashes.into_iter().zip(results) {
+            if let Err(err) = result {
+                return Err(ProtocolError::InvalidBlock(hash, err.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+//! Initial block download (IBD).
+//!
+//! The syncee negotiates with the syncer the highest block of the syncer selected chain it already knows,
+//! downloads the headers of all blocks above it in batches and finally downloads the missing block bodies.
+//! All blocks are fed through the regular consensus processing pipeline.
+//!
+//! If the syncer pruning point is unknown to the syncee, the syncee first downloads the pruning point along with
+//! its trusted data, i.e., the past pruning points, the blocks of its anticone and the headers within reach of the
+//! windows of the blocks above it, and then the UTXO set of the pruning point. This is only supported by a node
+//! holding no blocks but genesis. The pruning point proof is not validated, hence the pruning point is trusted to
+//! be on the syncer selected chain, yet its UTXO set is verified against the UTXO commitment of its header.
+
+mod flow;
+mod request_handlers;
+
+pub use flow::IbdFlow;
+pub use request_handlers::{
+    HandleIbdBlockRequests, HandleIbdChainBlockLocatorRequests, HandleRequestHeaders, HandleRequestPruningPointAndItsAnticone,
+    HandleRequestPruningPointUtxoSet,
+};
+
+use p2p::TimeoutBounds;
+use std::time::Duration;
+
+/// The maximum number of headers or block bodies sent in a single IBD batch
+pub const IBD_BATCH_SIZE: usize = 99;
+
+/// The bounds of the timeout of an IBD request sent to the syncer, adapted to the measured peer latency
+pub const IBD_TIMEOUT_BOUNDS: TimeoutBounds =
+    TimeoutBounds::new(Duration::from_secs(10), Duration::from_secs(120), Duration::from_secs(600));
+
+/// The maximum duration to wait for the syncee to request the next headers batch, which it does once it has
+/// processed the previous one
+pub const IBD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A generous estimate of the serialized size of a header, which is dominated by its parents by level
+pub const HEADER_SIZE_ESTIMATE: u64 = 4_096;
+
+/// The maximum number of UTXO set entries sent in a single pruning point UTXO set chunk
+pub const PRUNING_POINT_UTXO_SET_CHUNK_SIZE: usize = 1_000;
+
+/// A generous estimate of the serialized size of a UTXO set entry, which is dominated by its script public key
+pub const UTXO_ENTRY_SIZE_ESTIMATE: u64 = 128;
+
+#[cfg(test)]
+mod tests {
+    use crate::FlowContext;
+    use addressmanager::{AddressManager, BanManager, Config, DEFAULT_BAN_DURATION};
+    use consensus::consensus::test_consensus::{create_memory_db, TestConsensus};
+    use consensus_core::config::params::MAINNET_PARAMS;
+    use consensus_core::{api::ConsensusApi, blockstatus::BlockStatus, coinbase::MinerData, tx::ScriptPublicKey};
+    use hashes::Hash;
+    use mining::manager::MiningManager;
+    use p2p::{handshake::LocalVersion, Adaptor, Hub};
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+    use uuid::Uuid;
+
+    fn local_version() -> LocalVersion {
+        LocalVersion::new(Uuid::new_v4(), "kaspa-mainnet".to_string(), "/test:0.1.0/".to_string())
+    }
+
+    fn flow_context(consensus: Arc<TestConsensus>, hub: Hub) -> Arc<FlowContext> {
+        let mining_manager = Arc::new(MiningManager::new(consensus.clone(), Default::default(), Duration::from_secs(1)));
+        let ban_manager = BanManager::new(create_memory_db(), DEFAULT_BAN_DURATION).unwrap();
+        let address_manager = AddressManager::new(create_memory_db(), Config::default()).unwrap();
+        Arc::new(FlowContext::new(
+            consensus,
+            mining_manager,
+            hub,
+            local_version(),
+            Arc::new(Mutex::new(ban_manager)),
+            Arc::new(parking_lot::Mutex::new(address_manager)),
+        ))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ibd() {
+        let params = MAINNET_PARAMS.clone_with_skip_pow();
+        let syncer = Arc::new(TestConsensus::create_from_temp_db(&params));
+        let syncer_wait_handles = syncer.init();
+
+        // A chain spanning a few header batches, with a merged side block every few blocks
+        let mut parents = vec![params.genesis.hash];
+        for i in 0..250u64 {
+            let hash: Hash = (i + 100).into();
+            let block = syncer.build_block_with_parents_and_transactions(hash, parents.clone(), vec![]);
+            syncer.validate_and_insert_block(block.to_immutable()).await.unwrap();
+            if i % 10 == 0 {
+                let side: Hash = (i + 10_000).into();
+                let block = syncer.build_block_with_parents_and_transactions(side, parents, vec![]);
+                syncer.validate_and_insert_block(block.to_immutable()).await.unwrap();
+                parents = vec![hash, side];
+            } else {
+                parents = vec![hash];
+            }
+        }
+        let syncer_tip = syncer.clone().get_headers_selected_tip();
+
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let server_hub = Hub::new();
+        let server = Adaptor::bidirectional(address, server_hub.clone(), flow_context(syncer.clone(), server_hub)).unwrap();
+
+        let syncee = Arc::new(TestConsensus::create_from_temp_db(&params));
+        let syncee_wait_handles = syncee.init();
+        let client_hub = Hub::new();
+        let client = Adaptor::client_only(client_hub.clone(), flow_context(syncee.clone(), client_hub));
+        client.connect_peer_with_retry(address.to_string(), 10, Duration::from_millis(100)).await.unwrap();
+
+        // Blocks which are not built out of templates are disqualified from the chain, which the syncee has to agree on
+        let syncer_tip_status = syncer.clone().get_block_status(syncer_tip);
+        assert!(syncer_tip_status.unwrap().has_block_body());
+        let start = Instant::now();
+        while syncee.clone().get_block_status(syncer_tip) != syncer_tip_status {
+            assert!(start.elapsed() < Duration::from_secs(60), "IBD did not complete in time");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(syncee.body_tips(), syncer.body_tips());
+        assert_eq!(syncee.clone().get_headers_selected_tip(), syncer_tip);
+
+        client.close().await;
+        server.close().await;
+        syncer.shutdown(syncer_wait_handles);
+        syncee.shutdown(syncee_wait_handles);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ibd_from_pruning_point() {
+        let mut params = MAINNET_PARAMS.clone_with_skip_pow();
+        params.finality_depth = 10;
+        params.merge_depth = 5;
+        params.pruning_depth = 25;
+        params.difficulty_window_size = 8;
+        params.timestamp_deviation_tolerance = 3;
+        params.sampling_activation_daa_score = 30;
+        params.difficulty_sample_rate = 2;
+        params.past_median_time_sample_rate = 2;
+        let syncer = Arc::new(TestConsensus::create_from_temp_db(&params));
+        let syncer_wait_handles = syncer.init();
+
+        // A chain deep enough for the pruning point of the syncer to move on from genesis a few times, built out of
+        // templates so that the UTXO set of the pruning point is populated by coinbase outputs
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![1; 32]), vec![]);
+        let mut tip = params.genesis.hash;
+        for _ in 0..100 {
+            let block = syncer.clone().build_block_template(miner_data.clone(), vec![]).unwrap().block.to_immutable();
+            tip = block.hash();
+            syncer.validate_and_insert_block(block).await.unwrap();
+        }
+        let syncer_pruning_point = syncer.clone().get_pruning_point();
+        assert_ne!(syncer_pruning_point, params.genesis.hash);
+
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let server_hub = Hub::new();
+        let server = Adaptor::bidirectional(address, server_hub.clone(), flow_context(syncer.clone(), server_hub)).unwrap();
+
+        let syncee = Arc::new(TestConsensus::create_from_temp_db(&params));
+        let syncee_wait_handles = syncee.init();
+        let client_hub = Hub::new();
+        let client = Adaptor::client_only(client_hub.clone(), flow_context(syncee.clone(), client_hub));
+        client.connect_peer_with_retry(address.to_string(), 10, Duration::from_millis(100)).await.unwrap();
+
+        let start = Instant::now();
+        while syncee.clone().get_sink() != tip || syncee.clone().get_pruning_point() != syncer_pruning_point {
+            assert!(start.elapsed() < Duration::from_secs(60), "IBD did not complete in time");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(syncee.clone().get_block_status(tip), Some(BlockStatus::StatusUTXOValid));
+        assert_eq!(syncee.clone().get_virtual_utxos(None, usize::MAX), syncer.clone().get_virtual_utxos(None, usize::MAX));
+
+        client.close().await;
+        server.close().await;
+        syncer.shutdown(syncer_wait_handles);
+        syncee.shutdown(syncee_wait_handles);
+    }
+}
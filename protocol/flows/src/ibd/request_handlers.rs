@@ -0,0 +1,308 @@
+use super::{IBD_BATCH_SIZE, IBD_TIMEOUT, PRUNING_POINT_UTXO_SET_CHUNK_SIZE};
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use consensus_core::errors::sync::SyncManagerError;
+use hashes::Hash;
+use kaspa_core::debug;
+use p2p::{
+    convert::required_hash,
+    dequeue, dequeue_with_timeout,
+    pb::{
+        self, kaspad_message::Payload, BlockGhostdagDataHashPair, BlockHeadersMessage, BlockWithTrustedDataV4Message, DaaBlockV4,
+        DoneBlocksWithTrustedDataMessage, DoneHeadersMessage, DonePruningPointUtxoSetChunksMessage, IbdChainBlockLocatorMessage,
+        KaspadMessage, PruningPointUtxoSetChunkMessage, PruningPointsMessage, TrustedDataMessage, UnexpectedPruningPointMessage,
+    },
+    IncomingRoute, ProtocolError, Router,
+};
+use std::sync::Arc;
+
+/// Serves the chain block locators requested by a syncing peer
+pub struct HandleIbdChainBlockLocatorRequests {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleIbdChainBlockLocatorRequests {
+    fn name(&self) -> &'static str {
+        "HandleIbdChainBlockLocatorRequests"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let request = dequeue!(self.incoming_route, Payload::RequestIbdChainBlockLocator)?;
+            let mut low = request.low_hash.map(Hash::try_from).transpose()?;
+            // Archival peers are served the whole history we keep rather than only the DAG above the pruning point
+            if low.is_none() && self.router.properties().is_archival() {
+                low = Some(self.ctx.consensus().clone().get_history_root());
+            }
+            let high = request.high_hash.map(Hash::try_from).transpose()?;
+            // An empty locator lets the peer know the requested range is not in our selected chain
+            let locator = self.ctx.consensus().clone().create_headers_selected_chain_block_locator(low, high).unwrap_or_else(|err| {
+                debug!("Failed creating a chain block locator for peer {}: {}", self.router, err);
+                vec![]
+            });
+            self.router
+                .enqueue(KaspadMessage {
+                    payload: Some(Payload::IbdChainBlockLocator(IbdChainBlockLocatorMessage {
+                        block_locator_hashes: locator.into_iter().map(pb::Hash::from).collect(),
+                    })),
+                })
+                .await?;
+        }
+    }
+}
+
+impl HandleIbdChainBlockLocatorRequests {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+/// Serves the headers requested by a syncing peer, one batch at a time
+pub struct HandleRequestHeaders {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRequestHeaders {
+    fn name(&self) -> &'static str {
+        "HandleRequestHeaders"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus().clone();
+        loop {
+            let request = dequeue!(self.incoming_route, Payload::RequestHeaders)?;
+            let mut low = required_hash(request.low_hash, "low hash")?;
+            let high = required_hash(request.high_hash, "high hash")?;
+            loop {
+                let (hashes, highest_reached) = consensus
+                    .clone()
+                    .get_hashes_between(low, high, IBD_BATCH_SIZE)
+                    .map_err(|err| ProtocolError::OtherOwned(err.to_string()))?;
+                if hashes.is_empty() {
+                    break;
+                }
+                let block_headers = hashes
+                    .into_iter()
+                    .map(|hash| match consensus.clone().get_header(hash) {
+                        Some(header) => Ok(pb::BlockHeader::from(header.as_ref())),
+                        None => Err(ProtocolError::OtherOwned(format!("requested header {} is not available", hash))),
+                    })
+                    .collect::<Result<_, _>>()?;
+                self.router
+                    .enqueue(KaspadMessage { payload: Some(Payload::BlockHeaders(BlockHeadersMessage { block_headers })) })
+                    .await?;
+                dequeue_with_timeout!(self.incoming_route, Payload::RequestNextHeaders, IBD_TIMEOUT)?;
+                low = highest_reached;
+            }
+            self.router.enqueue(KaspadMessage { payload: Some(Payload::DoneHeaders(DoneHeadersMessage {})) }).await?;
+        }
+    }
+}
+
+impl HandleRequestHeaders {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+/// Serves the block bodies requested by a syncing peer
+pub struct HandleIbdBlockRequests {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleIbdBlockRequests {
+    fn name(&self) -> &'static str {
+        "HandleIbdBlockRequests"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let request = dequeue!(self.incoming_route, Payload::RequestIbdBlocks)?;
+            for hash in request.hashes {
+                let hash = Hash::try_from(hash)?;
+                let block = self
+                    .ctx
+                    .consensus()
+                    .clone()
+                    .get_block(hash)
+                    .ok_or_else(|| ProtocolError::OtherOwned(format!("requested IBD block {} is not available", hash)))?;
+                self.router.enqueue(KaspadMessage { payload: Some(Payload::IbdBlock((&block).into())) }).await?;
+            }
+        }
+    }
+}
+
+impl HandleIbdBlockRequests {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+/// Serves the pruning point along with the trusted data accompanying it to a peer syncing from scratch.
+///
+/// The headers of the past pruning points are sent first, followed by the header-only blocks of the windows above the
+/// pruning point, which make up the DAA window of the trusted data, and by the GHOSTDAG data of the blocks sent with
+/// their bodies, i.e., the pruning point and its anticone. Each of these blocks is then sent in its own message,
+/// referring to its GHOSTDAG data by index
+pub struct HandleRequestPruningPointAndItsAnticone {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRequestPruningPointAndItsAnticone {
+    fn name(&self) -> &'static str {
+        "HandleRequestPruningPointAndItsAnticone"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            dequeue!(self.incoming_route, Payload::RequestPruningPointAndItsAnticone)?;
+            let trusted_data = self
+                .ctx
+                .consensus()
+                .clone()
+                .get_pruning_point_trusted_data()
+                .map_err(|err| ProtocolError::OtherOwned(err.to_string()))?;
+            self.router
+                .enqueue(KaspadMessage {
+                    payload: Some(Payload::PruningPoints(PruningPointsMessage {
+                        headers: trusted_data.pruning_points.iter().map(|header| header.as_ref().into()).collect(),
+                    })),
+                })
+                .await?;
+
+            let (blocks, header_only): (Vec<_>, Vec<_>) =
+                trusted_data.blocks.into_iter().partition(|trusted_block| !trusted_block.block.is_header_only());
+            let daa_window = header_only
+                .iter()
+                .map(|trusted_block| DaaBlockV4 {
+                    header: Some(trusted_block.block.header.as_ref().into()),
+                    ghostdag_data: Some((&trusted_block.ghostdag_data).into()),
+                })
+                .collect();
+            let ghostdag_data = blocks
+                .iter()
+                .map(|trusted_block| BlockGhostdagDataHashPair {
+                    hash: Some(trusted_block.block.hash().into()),
+                    ghostdag_data: Some((&trusted_block.ghostdag_data).into()),
+                })
+                .collect();
+            self.router
+                .enqueue(KaspadMessage { payload: Some(Payload::TrustedData(TrustedDataMessage { daa_window, ghostdag_data })) })
+                .await?;
+
+            for (index, trusted_block) in blocks.iter().enumerate() {
+                self.router
+                    .enqueue(KaspadMessage {
+                        payload: Some(Payload::BlockWithTrustedDataV4(BlockWithTrustedDataV4Message {
+                            block: Some((&trusted_block.block).into()),
+                            daa_window_indices: vec![],
+                            ghostdag_data_indices: vec![index as u64],
+                        })),
+                    })
+                    .await?;
+            }
+            self.router
+                .enqueue(KaspadMessage { payload: Some(Payload::DoneBlocksWithTrustedData(DoneBlocksWithTrustedDataMessage {})) })
+                .await?;
+        }
+    }
+}
+
+impl HandleRequestPruningPointAndItsAnticone {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+/// Serves the UTXO set of the pruning point to a peer syncing from scratch, one chunk at a time
+pub struct HandleRequestPruningPointUtxoSet {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRequestPruningPointUtxoSet {
+    fn name(&self) -> &'static str {
+        "HandleRequestPruningPointUtxoSet"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        let consensus = self.ctx.consensus().clone();
+        loop {
+            let request = dequeue!(self.incoming_route, Payload::RequestPruningPointUtxoSet)?;
+            let pruning_point = required_hash(request.pruning_point_hash, "pruning point hash")?;
+            let mut from_outpoint = None;
+            loop {
+                // The pruning point might move on while its UTXO set is being sent, in which case the peer has to restart
+                let chunk =
+                    match consensus.clone().get_pruning_point_utxos(pruning_point, from_outpoint, PRUNING_POINT_UTXO_SET_CHUNK_SIZE) {
+                        Ok(chunk) => chunk,
+                        Err(SyncManagerError::PruningPointUtxoSetUnavailable(_)) => {
+                            self.router
+                                .enqueue(KaspadMessage {
+                                    payload: Some(Payload::UnexpectedPruningPoint(UnexpectedPruningPointMessage {})),
+                                })
+                                .await?;
+                            break;
+                        }
+                        Err(err) => return Err(ProtocolError::OtherOwned(err.to_string())),
+                    };
+                if chunk.is_empty() {
+                    self.router
+                        .enqueue(KaspadMessage {
+                            payload: Some(Payload::DonePruningPointUtxoSetChunks(DonePruningPointUtxoSetChunksMessage {})),
+                        })
+                        .await?;
+                    break;
+                }
+                from_outpoint = chunk.last().map(|(outpoint, _)| *outpoint);
+                self.router
+                    .enqueue(KaspadMessage {
+                        payload: Some(Payload::PruningPointUtxoSetChunk(PruningPointUtxoSetChunkMessage {
+                            outpoint_and_utxo_entry_pairs: chunk.iter().map(pb::OutpointAndUtxoEntryPair::from).collect(),
+                        })),
+                    })
+                    .await?;
+                dequeue_with_timeout!(self.incoming_route, Payload::RequestNextPruningPointUtxoSetChunk, IBD_TIMEOUT)?;
+            }
+        }
+    }
+}
+
+impl HandleRequestPruningPointUtxoSet {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
@@ -0,0 +1,10 @@
+pub mod addressexchange;
+pub mod blockrelay;
+pub mod flow_context;
+pub mod flow_trait;
+pub mod ibd;
+pub mod ping;
+pub mod txrelay;
+
+pub use flow_context::FlowContext;
+pub use flow_trait::Flow;
@@ -0,0 +1,90 @@
+//! Peer liveness and latency.
+//!
+//! Each side periodically pings the other, disconnecting peers which stop answering. The measured round-trip
+//! times feed the peer latency estimate from which the timeouts of the requests sent to the peer are derived,
+//! see [`p2p::PeerLatency`].
+
+use crate::flow_trait::Flow;
+use p2p::{
+    dequeue, dequeue_with_timeout,
+    pb::{kaspad_message::Payload, KaspadMessage, PingMessage, PongMessage},
+    IncomingRoute, ProtocolError, Router,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The interval at which a peer is pinged, the first ping being sent once connected
+pub const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The maximum duration to wait for the pong answering a ping
+pub const PING_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Pings the peer, recording the round-trip times in the peer latency
+pub struct SendPings {
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for SendPings {
+    fn name(&self) -> &'static str {
+        "SendPings"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        let mut interval = tokio::time::interval(PING_INTERVAL);
+        loop {
+            interval.tick().await;
+            let nonce = rand::random();
+            self.router.enqueue(KaspadMessage { payload: Some(Payload::Ping(PingMessage { nonce })) }).await?;
+            let sent = Instant::now();
+            let pong = dequeue_with_timeout!(self.incoming_route, Payload::Pong, PING_TIMEOUT)?;
+            if pong.nonce != nonce {
+                return Err(ProtocolError::Other("peer sent a pong with an unexpected nonce"));
+            }
+            self.router.latency().record_rtt(sent.elapsed());
+        }
+    }
+}
+
+impl SendPings {
+    pub fn new(router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { router, incoming_route }
+    }
+}
+
+/// Answers the pings of the peer
+pub struct ReceivePings {
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for ReceivePings {
+    fn name(&self) -> &'static str {
+        "ReceivePings"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let ping = dequeue!(self.incoming_route, Payload::Ping)?;
+            self.router.enqueue(KaspadMessage { payload: Some(Payload::Pong(PongMessage { nonce: ping.nonce })) }).await?;
+        }
+    }
+}
+
+impl ReceivePings {
+    pub fn new(router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { router, incoming_route }
+    }
+}
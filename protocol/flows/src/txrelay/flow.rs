@@ -0,0 +1,270 @@
+use super::{
+    KnownTransactions, TransactionsAnnouncement, MAX_INV_PER_TX_INV_MSG, REQUEST_TRANSACTIONS_TIMEOUT_BOUNDS,
+    TRANSACTION_INV_INTERVAL, TRANSACTION_SIZE_ESTIMATE,
+};
+use crate::{flow_context::FlowContext, flow_trait::Flow};
+use consensus_core::tx::{Transaction, TransactionId};
+use kaspa_core::debug;
+use mining::mempool::model::Priority;
+use p2p::{
+    convert::required_transaction_id,
+    dequeue,
+    pb::{
+        self, kaspad_message::Payload, InvTransactionsMessage, KaspadMessage, RequestTransactionsMessage, TransactionNotFoundMessage,
+    },
+    IncomingRoute, ProtocolError, Router,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver as BroadcastReceiver};
+
+fn check_inv_len(len: usize) -> Result<(), ProtocolError> {
+    if len > MAX_INV_PER_TX_INV_MSG {
+        return Err(ProtocolError::LimitExceeded(format!(
+            "peer sent {} transaction ids, exceeding the maximum of {}",
+            len, MAX_INV_PER_TX_INV_MSG
+        )));
+    }
+    Ok(())
+}
+
+/// Requests the transactions announced by a peer which are missing from the mempool, and submits them to the mempool
+pub struct HandleRelayedTransactions {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    invs_route: IncomingRoute,
+    transactions_route: IncomingRoute,
+    known_transactions: Arc<Mutex<KnownTransactions>>,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRelayedTransactions {
+    fn name(&self) -> &'static str {
+        "HandleRelayedTransactions"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let inv = dequeue!(self.invs_route, Payload::InvTransactions)?;
+            check_inv_len(inv.ids.len())?;
+            let ids = inv.ids.into_iter().map(TransactionId::try_from).collect::<Result<Vec<_>, _>>()?;
+            {
+                let mut known_transactions = self.known_transactions.lock().unwrap();
+                for &id in ids.iter() {
+                    known_transactions.insert(id);
+                }
+            }
+
+            // Transactions are validated against the virtual UTXO state, which is not up to date while syncing
+            if self.ctx.is_ibd_running() {
+                continue;
+            }
+            let requested = self.ctx.register_requested_transactions(self.unknown_transactions(ids));
+            if requested.is_empty() {
+                continue;
+            }
+            let result = self.request_transactions(&requested).await;
+            self.ctx.unregister_requested_transactions(&requested);
+            result?;
+        }
+    }
+}
+
+impl HandleRelayedTransactions {
+    pub fn new(
+        ctx: FlowContext,
+        router: Arc<Router>,
+        invs_route: IncomingRoute,
+        transactions_route: IncomingRoute,
+        known_transactions: Arc<Mutex<KnownTransactions>>,
+    ) -> Self {
+        Self { ctx, router, invs_route, transactions_route, known_transactions }
+    }
+
+    fn unknown_transactions(&self, ids: Vec<TransactionId>) -> Vec<TransactionId> {
+        let mempool = self.ctx.mining_manager().mempool();
+        ids.into_iter().filter(|id| !mempool.has_transaction(id) && !mempool.has_orphan(id)).collect()
+    }
+
+    /// Requests the transactions from the peer and submits them to the mempool, announcing the accepted ones to all peers
+    async fn request_transactions(&mut self, requested: &[TransactionId]) -> Result<(), ProtocolError> {
+        self.router
+            .enqueue(KaspadMessage {
+                payload: Some(Payload::RequestTransactions(RequestTransactionsMessage {
+                    ids: requested.iter().map(|&id| id.into()).collect(),
+                })),
+            })
+            .await?;
+
+        let mut accepted = Vec::new();
+        // The peer responds to each requested id in order, so each response is timed from the arrival of the previous one
+        let mut previous_arrival = Instant::now();
+        for &expected in requested {
+            let timeout = self.router.latency().timeout(REQUEST_TRANSACTIONS_TIMEOUT_BOUNDS, TRANSACTION_SIZE_ESTIMATE);
+            let msg = match tokio::time::timeout(timeout, self.transactions_route.recv()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) => return Err(ProtocolError::ConnectionClosed),
+                Err(_) => return Err(ProtocolError::Timeout(timeout)),
+            };
+            let elapsed = previous_arrival.elapsed();
+            previous_arrival = Instant::now();
+            let transaction = match msg.payload {
+                Some(Payload::Transaction(transaction)) => {
+                    self.router.latency().record_response(&transaction, elapsed);
+                    Transaction::try_from(transaction)?
+                }
+                Some(Payload::TransactionNotFound(msg)) => {
+                    let id = required_transaction_id(msg.id, "transaction id")?;
+                    if id != expected {
+                        return Err(ProtocolError::OtherOwned(format!("expected transaction {} but {} was not found", expected, id)));
+                    }
+                    // The transaction left the peer mempool in the meantime
+                    continue;
+                }
+                payload => {
+                    return Err(ProtocolError::UnexpectedMessage(
+                        "Transaction or TransactionNotFound",
+                        payload.as_ref().map(|p| p.into()),
+                    ))
+                }
+            };
+            if transaction.id() != expected {
+                return Err(ProtocolError::OtherOwned(format!("expected transaction {} but got {}", expected, transaction.id())));
+            }
+
+            let result = self.ctx.mining_manager().mempool_mut().validate_and_insert_transaction(transaction, Priority::Low, true);
+            match result {
+                Ok(ids) => accepted.extend(ids),
+                // A relayed transaction might legitimately be rejected, e.g., if it was meanwhile included in a block
+                Err(err) => debug!("Transaction {} relayed by peer {} was rejected: {}", expected, self.router, err),
+            }
+        }
+        self.ctx.broadcast_transactions(accepted);
+        Ok(())
+    }
+}
+
+/// Serves the mempool transactions requested by a peer
+pub struct HandleRequestTransactions {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleRequestTransactions {
+    fn name(&self) -> &'static str {
+        "HandleRequestTransactions"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let request = dequeue!(self.incoming_route, Payload::RequestTransactions)?;
+            check_inv_len(request.ids.len())?;
+            for id in request.ids {
+                let id = TransactionId::try_from(id)?;
+                let transaction =
+                    self.ctx.mining_manager().mempool().get_transaction(&id).map(|mtx| pb::TransactionMessage::from(&mtx.tx));
+                let payload = match transaction {
+                    Some(transaction) => Payload::Transaction(transaction),
+                    None => Payload::TransactionNotFound(TransactionNotFoundMessage { id: Some(id.into()) }),
+                };
+                self.router.enqueue(KaspadMessage { payload: Some(payload) }).await?;
+            }
+        }
+    }
+}
+
+impl HandleRequestTransactions {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+}
+
+/// Announces the transactions accepted to the mempool to a peer. Ids are batched and sent once the batch is
+/// full or every [`TRANSACTION_INV_INTERVAL`], skipping ids the peer is already known to have unless rebroadcast.
+pub struct SendTransactionInvs {
+    router: Arc<Router>,
+    broadcasts: BroadcastReceiver<Arc<TransactionsAnnouncement>>,
+    known_transactions: Arc<Mutex<KnownTransactions>>,
+    pending: Vec<TransactionId>,
+}
+
+#[async_trait::async_trait]
+impl Flow for SendTransactionInvs {
+    fn name(&self) -> &'static str {
+        "SendTransactionInvs"
+    }
+
+    fn router(&self) -> Arc<Router> {
+        self.router.clone()
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        let mut interval = tokio::time::interval(TRANSACTION_INV_INTERVAL);
+        loop {
+            tokio::select! {
+                received = self.broadcasts.recv() => match received {
+                    Ok(announcement) => {
+                        self.queue(&announcement);
+                        if self.pending.len() >= MAX_INV_PER_TX_INV_MSG {
+                            self.send_pending().await?;
+                        }
+                    }
+                    // Missed announcements only slow down propagation, since the peer learns of them from other peers too
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!("P2P, peer {} missed {} transaction announcements", self.router, skipped)
+                    }
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                _ = interval.tick() => {
+                    if self.router.is_closed() {
+                        return Err(ProtocolError::ConnectionClosed);
+                    }
+                    self.send_pending().await?;
+                }
+            }
+        }
+    }
+}
+
+impl SendTransactionInvs {
+    pub fn new(
+        router: Arc<Router>,
+        broadcasts: BroadcastReceiver<Arc<TransactionsAnnouncement>>,
+        known_transactions: Arc<Mutex<KnownTransactions>>,
+    ) -> Self {
+        Self { router, broadcasts, known_transactions, pending: Vec::new() }
+    }
+
+    fn queue(&mut self, announcement: &TransactionsAnnouncement) {
+        let mut known_transactions = self.known_transactions.lock().unwrap();
+        // Rebroadcast ids are marked as known as well, without being skipped
+        let is_rebroadcast = announcement.is_rebroadcast;
+        self.pending.extend(announcement.ids.iter().copied().filter(|&id| known_transactions.insert(id) || is_rebroadcast));
+    }
+
+    async fn send_pending(&mut self) -> Result<(), ProtocolError> {
+        let pending = std::mem::take(&mut self.pending);
+        for chunk in pending.chunks(MAX_INV_PER_TX_INV_MSG) {
+            self.router
+                .enqueue(KaspadMessage {
+                    payload: Some(Payload::InvTransactions(InvTransactionsMessage {
+                        ids: chunk.iter().map(|&id| id.into()).collect(),
+                    })),
+                })
+                .await?;
+        }
+        Ok(())
+    }
+}
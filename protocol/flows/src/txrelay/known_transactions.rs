@@ -0,0 +1,87 @@
+use consensus_core::tx::TransactionId;
+use std::collections::{HashMap, VecDeque};
+
+/// A bounded LRU set of the transaction ids a peer is known to have, either because the peer announced
+/// them or because they were announced to it. Used to avoid re-sending ids the peer already knows.
+pub struct KnownTransactions {
+    capacity: usize,
+    /// The last use stamp of each id
+    stamps: HashMap<TransactionId, u64>,
+    /// Use records in stamp order. Records of ids which were used again later are stale and skipped on eviction.
+    uses: VecDeque<(TransactionId, u64)>,
+    next_stamp: u64,
+}
+
+impl KnownTransactions {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self { capacity, stamps: HashMap::with_capacity(capacity), uses: VecDeque::with_capacity(capacity), next_stamp: 0 }
+    }
+
+    /// Marks the id as known and most recently used, returning `true` if it was not already known
+    pub fn insert(&mut self, id: TransactionId) -> bool {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        let is_new = self.stamps.insert(id, stamp).is_none();
+        self.uses.push_back((id, stamp));
+
+        if self.stamps.len() > self.capacity {
+            self.evict_least_recently_used();
+        }
+        // Bound the stale records left behind by repeated uses
+        if self.uses.len() > 2 * self.capacity {
+            let stamps = &self.stamps;
+            self.uses.retain(|(id, stamp)| stamps.get(id) == Some(stamp));
+        }
+        is_new
+    }
+
+    pub fn contains(&self, id: &TransactionId) -> bool {
+        self.stamps.contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.stamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stamps.is_empty()
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        while let Some((id, stamp)) = self.uses.pop_front() {
+            if self.stamps.get(&id) == Some(&stamp) {
+                self.stamps.remove(&id);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_transactions_eviction() {
+        let mut known = KnownTransactions::new(3);
+        for i in 0..3u64 {
+            assert!(known.insert(i.into()));
+        }
+        assert!(!known.insert(0.into()));
+
+        // 1 is now the least recently used id
+        assert!(known.insert(3.into()));
+        assert_eq!(known.len(), 3);
+        assert!(!known.contains(&1.into()));
+        assert!(known.contains(&0.into()));
+
+        // Repeated uses of a single id must not evict others nor grow the use records unboundedly
+        for _ in 0..100 {
+            assert!(!known.insert(0.into()));
+        }
+        assert_eq!(known.len(), 3);
+        assert!(known.contains(&2.into()) && known.contains(&3.into()));
+        assert!(known.uses.len() <= 6);
+    }
+}
@@ -0,0 +1,48 @@
+//! Transaction relay.
+//!
+//! Transactions accepted to the mempool are announced to all peers by id. Each peer batches the announced ids
+//! and sends them in a single inv message once the batch is full or the batch interval elapses, skipping ids
+//! the peer is already known to have. A peer receiving an inv requests the transactions missing from its
+//! mempool, validates them and announces the ones it accepted in turn.
+//!
+//! The transactions submitted to this node are additionally revalidated and rebroadcast to all peers every
+//! [`TRANSACTION_REBROADCAST_INTERVAL`], at the arrival of a new block, until they are accepted or expire, see
+//! [`mining::mempool::Config`]. Rebroadcast ids are announced even to peers known to have them, since these might
+//! have dropped them from their mempool meanwhile.
+
+mod flow;
+mod known_transactions;
+
+pub use flow::{HandleRelayedTransactions, HandleRequestTransactions, SendTransactionInvs};
+pub use known_transactions::KnownTransactions;
+
+use consensus_core::tx::TransactionId;
+use p2p::TimeoutBounds;
+use std::time::Duration;
+
+/// The maximum number of transaction ids in a single inv or transactions request message
+pub const MAX_INV_PER_TX_INV_MSG: usize = 131_072;
+
+/// The interval at which the transaction ids pending announcement to a peer are sent
+pub const TRANSACTION_INV_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The number of transaction ids kept per peer in order to avoid re-sending them
+pub const KNOWN_TRANSACTIONS_CAPACITY: usize = 100_000;
+
+/// The bounds of the timeout of each transaction requested from a peer, adapted to the measured peer latency
+pub const REQUEST_TRANSACTIONS_TIMEOUT_BOUNDS: TimeoutBounds =
+    TimeoutBounds::new(Duration::from_secs(5), Duration::from_secs(30), Duration::from_secs(120));
+
+/// A generous estimate of the serialized size of a relayed transaction
+pub const TRANSACTION_SIZE_ESTIMATE: u64 = 100_000;
+
+/// The minimum interval between two rebroadcasts of the transactions submitted to this node
+pub const TRANSACTION_REBROADCAST_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A batch of transaction ids announced to all peers
+#[derive(Clone, Debug)]
+pub struct TransactionsAnnouncement {
+    pub ids: Vec<TransactionId>,
+    /// Whether the ids are announced to peers even if they are known to have them
+    pub is_rebroadcast: bool,
+}
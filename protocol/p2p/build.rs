@@ -0,0 +1,9 @@
+fn main() {
+    let protowire_main_file = "./proto/messages.proto";
+
+    tonic_build::configure()
+        .build_server(true)
+        .build_client(true)
+        .compile(&[protowire_main_file], &["./proto/", "."])
+        .unwrap_or_else(|e| panic!("protobuf compile error: {}", e));
+}
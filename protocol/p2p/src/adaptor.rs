@@ -0,0 +1,220 @@
+use crate::{
+    common::{ConnectionError, ProtocolError},
+    connection_handler::ConnectionHandler,
+    hub::Hub,
+    Router,
+};
+use parking_lot::Mutex;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::sync::{mpsc::channel as mpsc_channel, oneshot::Sender as OneshotSender};
+
+/// The capacity of the channel of events reported to the hub
+const HUB_EVENT_CHANNEL_SIZE: usize = 128;
+
+/// Initializes new peer connections, typically by performing the handshake and subscribing the peer flows.
+///
+/// The initializer is responsible for starting the router once the initial flows have subscribed.
+/// Returning an error closes the connection.
+#[tonic::async_trait]
+pub trait ConnectionInitializer: Sync + Send {
+    async fn initialize_connection(&self, new_router: Arc<Router>) -> Result<(), ProtocolError>;
+
+    /// Decides whether to accept an inbound connection from `address`, before any message is exchanged
+    fn accept_inbound(&self, _address: SocketAddr) -> bool {
+        true
+    }
+}
+
+/// The entry point of the P2P layer, serving inbound connections and establishing outbound ones
+pub struct Adaptor {
+    /// The termination signals of the servers of the listen addresses
+    server_terminations: Mutex<Vec<OneshotSender<()>>>,
+    connection_handler: ConnectionHandler,
+    hub: Hub,
+}
+
+impl Adaptor {
+    fn new(server_terminations: Vec<OneshotSender<()>>, connection_handler: ConnectionHandler, hub: Hub) -> Self {
+        Self { server_terminations: Mutex::new(server_terminations), connection_handler, hub }
+    }
+
+    /// Creates an adaptor serving inbound connections on `serve_address` and able to connect to peers.
+    /// Must be called from within a tokio runtime.
+    pub fn bidirectional(
+        serve_address: SocketAddr,
+        hub: Hub,
+        initializer: Arc<dyn ConnectionInitializer>,
+    ) -> Result<Arc<Self>, ConnectionError> {
+        Self::serving(&[serve_address], hub, initializer)
+    }
+
+    /// Creates an adaptor serving inbound connections on all of `serve_addresses` and able to connect to peers.
+    /// Fails if any of the addresses cannot be bound. Must be called from within a tokio runtime.
+    pub fn serving(
+        serve_addresses: &[SocketAddr],
+        hub: Hub,
+        initializer: Arc<dyn ConnectionInitializer>,
+    ) -> Result<Arc<Self>, ConnectionError> {
+        let (hub_sender, hub_receiver) = mpsc_channel(HUB_EVENT_CHANNEL_SIZE);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer);
+        let mut server_terminations = Vec::with_capacity(serve_addresses.len());
+        for &serve_address in serve_addresses {
+            match connection_handler.serve(serve_address) {
+                Ok(termination) => server_terminations.push(termination),
+                Err(err) => {
+                    // Stop the servers already launched
+                    for termination in server_terminations {
+                        let _ = termination.send(());
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        hub.clone().start_event_loop(hub_receiver);
+        Ok(Arc::new(Self::new(server_terminations, connection_handler, hub)))
+    }
+
+    /// Creates an adaptor which only connects to peers. Must be called from within a tokio runtime.
+    pub fn client_only(hub: Hub, initializer: Arc<dyn ConnectionInitializer>) -> Arc<Self> {
+        let (hub_sender, hub_receiver) = mpsc_channel(HUB_EVENT_CHANNEL_SIZE);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer);
+        hub.clone().start_event_loop(hub_receiver);
+        Arc::new(Self::new(vec![], connection_handler, hub))
+    }
+
+    /// Connects to a peer and initializes the connection
+    pub async fn connect_peer(&self, peer_address: String) -> Result<Arc<Router>, ConnectionError> {
+        self.connection_handler.connect(peer_address).await
+    }
+
+    /// Connects to a peer, retrying up to `retry_attempts` times on failure
+    pub async fn connect_peer_with_retry(
+        &self,
+        peer_address: String,
+        retry_attempts: u8,
+        retry_interval: Duration,
+    ) -> Result<Arc<Router>, ConnectionError> {
+        self.connection_handler.connect_with_retry(peer_address, retry_attempts, retry_interval).await
+    }
+
+    pub fn hub(&self) -> &Hub {
+        &self.hub
+    }
+
+    pub fn active_peers_len(&self) -> usize {
+        self.hub.active_peers_len()
+    }
+
+    pub async fn terminate_all_peers(&self) {
+        self.hub.terminate_all_peers().await
+    }
+
+    /// Stops serving inbound connections and terminates all peers
+    pub async fn close(&self) {
+        for termination in self.server_terminations.lock().drain(..) {
+            let _ = termination.send(());
+        }
+        self.terminate_all_peers().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pb::{kaspad_message::Payload, KaspadMessage, PingMessage, PongMessage},
+        KaspadMessagePayloadType,
+    };
+    use std::time::Instant;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    /// Subscribes a flow answering pings with pongs and reporting the nonces of received pongs
+    struct PingPongInitializer {
+        pongs: UnboundedSender<u64>,
+    }
+
+    #[tonic::async_trait]
+    impl ConnectionInitializer for PingPongInitializer {
+        async fn initialize_connection(&self, new_router: Arc<Router>) -> Result<(), ProtocolError> {
+            let mut incoming_route = new_router.subscribe(vec![KaspadMessagePayloadType::Ping, KaspadMessagePayloadType::Pong]);
+            new_router.start();
+            let router = new_router.clone();
+            let pongs = self.pongs.clone();
+            tokio::spawn(async move {
+                while let Some(msg) = incoming_route.recv().await {
+                    match msg.payload {
+                        Some(Payload::Ping(ping)) => {
+                            let pong = KaspadMessage { payload: Some(Payload::Pong(PongMessage { nonce: ping.nonce })) };
+                            if router.enqueue(pong).await.is_err() {
+                                break;
+                            }
+                        }
+                        Some(Payload::Pong(pong)) => {
+                            let _ = pongs.send(pong.nonce);
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            });
+            Ok(())
+        }
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let start = Instant::now();
+        while !condition() {
+            if start.elapsed() > Duration::from_secs(10) {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        true
+    }
+
+    #[tokio::test]
+    async fn test_ping_pong_over_connection() {
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+        let (server_pongs, _server_pongs_receiver) = unbounded_channel();
+        let server = Adaptor::bidirectional(address, Hub::new(), Arc::new(PingPongInitializer { pongs: server_pongs })).unwrap();
+        let (client_pongs, mut client_pongs_receiver) = unbounded_channel();
+        let client = Adaptor::client_only(Hub::new(), Arc::new(PingPongInitializer { pongs: client_pongs }));
+
+        let router = client.connect_peer_with_retry(address.to_string(), 10, Duration::from_millis(100)).await.unwrap();
+        assert!(router.is_outbound());
+        router.enqueue(KaspadMessage { payload: Some(Payload::Ping(PingMessage { nonce: 7 })) }).await.unwrap();
+        assert_eq!(client_pongs_receiver.recv().await, Some(7));
+
+        assert!(wait_for(|| server.active_peers_len() == 1 && client.active_peers_len() == 1).await);
+        assert!(!server.hub().active_peers()[0].is_outbound());
+
+        // Closing one side should be observed by the other
+        client.terminate_all_peers().await;
+        assert!(router.is_closed());
+        assert!(wait_for(|| server.active_peers_len() == 0 && client.active_peers_len() == 0).await);
+
+        server.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_multiple_listen_addresses() {
+        let addresses: Vec<_> = (0..2).map(|_| std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()).collect();
+        let (server_pongs, _server_pongs_receiver) = unbounded_channel();
+        let server = Adaptor::serving(&addresses, Hub::new(), Arc::new(PingPongInitializer { pongs: server_pongs })).unwrap();
+
+        // Both addresses accept connections
+        let (client_pongs, _client_pongs_receiver) = unbounded_channel();
+        let client = Adaptor::client_only(Hub::new(), Arc::new(PingPongInitializer { pongs: client_pongs }));
+        for address in addresses.iter() {
+            client.connect_peer_with_retry(address.to_string(), 10, Duration::from_millis(100)).await.unwrap();
+        }
+        assert!(wait_for(|| server.active_peers_len() == 2).await);
+
+        // An address which is already bound fails the whole adaptor
+        let (pongs, _pongs_receiver) = unbounded_channel();
+        assert!(Adaptor::serving(&addresses[..1], Hub::new(), Arc::new(PingPongInitializer { pongs })).is_err());
+
+        client.close().await;
+        server.close().await;
+    }
+}
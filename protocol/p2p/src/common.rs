@@ -0,0 +1,129 @@
+use crate::{ConversionError, KaspadMessagePayloadType};
+use hashes::Hash;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors raised while exchanging messages with a peer. Any of these results in the peer being disconnected.
+#[derive(Error, Debug, Clone)]
+pub enum ProtocolError {
+    #[error("timeout expired after {0:?}")]
+    Timeout(Duration),
+
+    #[error("expected message type/s {0} but got {1:?}")]
+    UnexpectedMessage(&'static str, Option<KaspadMessagePayloadType>),
+
+    #[error("peer is on the wrong network: expected {0}, got {1}")]
+    WrongNetwork(String, String),
+
+    #[error("peer protocol version {1} is lower than the minimum supported version {0}")]
+    VersionMismatch(u32, u32),
+
+    #[error("loopback connection - node is connected to itself")]
+    LoopbackConnection,
+
+    #[error("no flow is registered for message type {0:?}")]
+    NoRouteForMessageType(KaspadMessagePayloadType),
+
+    #[error("incoming route capacity for message type {0:?} has been reached (peer: {1})")]
+    IncomingRouteCapacityReached(KaspadMessagePayloadType, String),
+
+    #[error("peer sent the invalid block {0}: {1}")]
+    InvalidBlock(Hash, String),
+
+    #[error("peer exceeded a message limit: {0}")]
+    LimitExceeded(String),
+
+    #[error("peer connection is closed")]
+    ConnectionClosed,
+
+    #[error(transparent)]
+    ConversionError(#[from] ConversionError),
+
+    #[error("{0}")]
+    Other(&'static str),
+
+    #[error("{0}")]
+    OtherOwned(String),
+}
+
+impl ProtocolError {
+    /// The misbehavior the error is counted as against the peer, if any
+    pub fn misbehavior(&self) -> Option<Misbehavior> {
+        match self {
+            ProtocolError::InvalidBlock(..) => Some(Misbehavior::InvalidBlock),
+            ProtocolError::UnexpectedMessage(..) | ProtocolError::ConversionError(_) => Some(Misbehavior::MalformedMessage),
+            ProtocolError::LimitExceeded(_) | ProtocolError::IncomingRouteCapacityReached(..) => Some(Misbehavior::Spam),
+            _ => None,
+        }
+    }
+}
+
+/// A protocol violation by a peer. Violations add up to a ban score, see [`Misbehavior::score`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Misbehavior {
+    InvalidBlock,
+    MalformedMessage,
+    Spam,
+}
+
+impl Misbehavior {
+    /// The score added by the violation, where a score of 100 gets the peer banned
+    pub fn score(self) -> u32 {
+        match self {
+            Misbehavior::InvalidBlock => 100,
+            Misbehavior::MalformedMessage => 50,
+            Misbehavior::Spam => 10,
+        }
+    }
+}
+
+/// Errors raised while establishing a connection with a peer
+#[derive(Error, Debug)]
+pub enum ConnectionError {
+    #[error("invalid peer address {0}")]
+    InvalidAddress(String),
+
+    #[error("failed to listen on {0}: {1}")]
+    Listen(std::net::SocketAddr, String),
+
+    #[error(transparent)]
+    TonicError(#[from] tonic::transport::Error),
+
+    #[error(transparent)]
+    TonicStatus(#[from] tonic::Status),
+
+    #[error(transparent)]
+    ProtocolError(#[from] ProtocolError),
+}
+
+/// Dequeues a message from an incoming route, extracting the inner message of the expected `$payload` variant.
+/// Evaluates to a `Result<_, ProtocolError>`, failing with [`ProtocolError::ConnectionClosed`] once the route is dropped.
+#[macro_export]
+macro_rules! dequeue {
+    ($route:expr, $payload:path) => {{
+        match $route.recv().await {
+            Some(msg) => match msg.payload {
+                Some($payload(inner)) => Ok(inner),
+                payload => Err($crate::ProtocolError::UnexpectedMessage(stringify!($payload), payload.as_ref().map(|p| p.into()))),
+            },
+            None => Err($crate::ProtocolError::ConnectionClosed),
+        }
+    }};
+}
+
+/// Dequeues a message from an incoming route within `$timeout`, extracting the inner message of the expected
+/// `$payload` variant. Evaluates to a `Result<_, ProtocolError>`.
+#[macro_export]
+macro_rules! dequeue_with_timeout {
+    ($route:expr, $payload:path, $timeout:expr) => {{
+        let timeout = $timeout;
+        match tokio::time::timeout(timeout, $route.recv()).await {
+            Ok(Some(msg)) => match msg.payload {
+                Some($payload(inner)) => Ok(inner),
+                payload => Err($crate::ProtocolError::UnexpectedMessage(stringify!($payload), payload.as_ref().map(|p| p.into()))),
+            },
+            Ok(None) => Err($crate::ProtocolError::ConnectionClosed),
+            Err(_) => Err($crate::ProtocolError::Timeout(timeout)),
+        }
+    }};
+}
@@ -0,0 +1,140 @@
+use crate::{
+    adaptor::ConnectionInitializer,
+    common::{ConnectionError, ProtocolError},
+    hub::HubEvent,
+    pb::{p2p_client::P2pClient, p2p_server::P2p, p2p_server::P2pServer, KaspadMessage},
+    router::OUTGOING_ROUTE_CAPACITY,
+    Router,
+};
+use futures::{Stream, StreamExt};
+use kaspa_core::{debug, info, warn};
+use std::{net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
+use tokio::sync::mpsc::{channel as mpsc_channel, Sender as MpscSender};
+use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{
+    codec::CompressionEncoding,
+    transport::{server::TcpIncoming, Endpoint, Server},
+    Request, Response, Status as TonicStatus, Streaming,
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// Handles the lifecycle of peer connections: accepting inbound and establishing outbound
+/// gRPC message streams, wrapping each in a [`Router`] and having it initialized.
+#[derive(Clone)]
+pub struct ConnectionHandler {
+    hub_sender: MpscSender<HubEvent>,
+    initializer: Arc<dyn ConnectionInitializer>,
+}
+
+impl ConnectionHandler {
+    pub(crate) fn new(hub_sender: MpscSender<HubEvent>, initializer: Arc<dyn ConnectionInitializer>) -> Self {
+        Self { hub_sender, initializer }
+    }
+
+    /// Binds `serve_address` and launches the P2P server, returning a signal for terminating it
+    pub(crate) fn serve(&self, serve_address: SocketAddr) -> Result<OneshotSender<()>, ConnectionError> {
+        let incoming = TcpIncoming::new(serve_address, true, Some(TCP_KEEPALIVE))
+            .map_err(|err| ConnectionError::Listen(serve_address, err.to_string()))?;
+        let (termination_sender, termination_receiver) = oneshot_channel::<()>();
+        let connection_handler = self.clone();
+        info!("P2P server listening on {}", serve_address);
+        tokio::spawn(async move {
+            let svc = P2pServer::new(connection_handler)
+                .send_compressed(CompressionEncoding::Gzip)
+                .accept_compressed(CompressionEncoding::Gzip);
+            let termination_signal = async {
+                let _ = termination_receiver.await;
+            };
+            match Server::builder().add_service(svc).serve_with_incoming_shutdown(incoming, termination_signal).await {
+                Ok(_) => debug!("P2P server exited gracefully"),
+                Err(err) => warn!("P2P server exited with error: {}", err),
+            }
+        });
+        Ok(termination_sender)
+    }
+
+    /// Connects to a peer and initializes the connection
+    pub(crate) async fn connect(&self, peer_address: String) -> Result<Arc<Router>, ConnectionError> {
+        let socket_address = peer_address.parse::<SocketAddr>().map_err(|_| ConnectionError::InvalidAddress(peer_address.clone()))?;
+        let channel = Endpoint::from_shared(format!("http://{}", peer_address))?
+            .connect_timeout(CONNECT_TIMEOUT)
+            .tcp_keepalive(Some(TCP_KEEPALIVE))
+            .connect()
+            .await?;
+        let mut client =
+            P2pClient::new(channel).send_compressed(CompressionEncoding::Gzip).accept_compressed(CompressionEncoding::Gzip);
+
+        let (outgoing_route, outgoing_receiver) = mpsc_channel(OUTGOING_ROUTE_CAPACITY);
+        let incoming_stream = client.message_stream(ReceiverStream::new(outgoing_receiver)).await?.into_inner();
+        let router = Router::new(socket_address, true, self.hub_sender.clone(), incoming_stream, outgoing_route);
+        self.initialize_connection(router.clone()).await?;
+        Ok(router)
+    }
+
+    /// Connects to a peer, retrying up to `retry_attempts` times on failure
+    pub(crate) async fn connect_with_retry(
+        &self,
+        peer_address: String,
+        retry_attempts: u8,
+        retry_interval: Duration,
+    ) -> Result<Arc<Router>, ConnectionError> {
+        let mut attempt = 0;
+        loop {
+            match self.connect(peer_address.clone()).await {
+                Ok(router) => return Ok(router),
+                Err(err) => {
+                    attempt += 1;
+                    if attempt > retry_attempts {
+                        return Err(err);
+                    }
+                    debug!("P2P, connection attempt #{} to {} failed: {}", attempt, peer_address, err);
+                    tokio::time::sleep(retry_interval).await;
+                }
+            }
+        }
+    }
+
+    async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+        match self.initializer.initialize_connection(router.clone()).await {
+            Ok(()) => self.hub_sender.send(HubEvent::NewPeer(router)).await.map_err(|_| ProtocolError::ConnectionClosed),
+            Err(err) => {
+                router.close().await;
+                Err(err)
+            }
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl P2p for ConnectionHandler {
+    type MessageStreamStream = Pin<Box<dyn Stream<Item = Result<KaspadMessage, TonicStatus>> + Send + 'static>>;
+
+    async fn message_stream(
+        &self,
+        request: Request<Streaming<KaspadMessage>>,
+    ) -> Result<Response<Self::MessageStreamStream>, TonicStatus> {
+        let remote_address = request.remote_addr().ok_or_else(|| {
+            TonicStatus::new(tonic::Code::InvalidArgument, "Incoming connection opening request has no remote address")
+        })?;
+        if !self.initializer.accept_inbound(remote_address) {
+            debug!("P2P, rejected inbound connection from {}", remote_address);
+            return Err(TonicStatus::new(tonic::Code::PermissionDenied, "Incoming connection is not accepted"));
+        }
+
+        let (outgoing_route, outgoing_receiver) = mpsc_channel(OUTGOING_ROUTE_CAPACITY);
+        let router = Router::new(remote_address, false, self.hub_sender.clone(), request.into_inner(), outgoing_route);
+
+        // The response stream must be returned for the peer to receive our messages, so initialization is run in the background
+        let connection_handler = self.clone();
+        tokio::spawn(async move {
+            if let Err(err) = connection_handler.initialize_connection(router.clone()).await {
+                debug!("P2P, failed to initialize inbound peer {}: {}", router, err);
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(outgoing_receiver).map(Ok)) as Self::MessageStreamStream))
+    }
+}
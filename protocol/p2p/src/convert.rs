@@ -0,0 +1,456 @@
+//! Conversions between the protowire P2P messages and the consensus core and network address types
+
+use crate::pb;
+use consensus_core::{
+    block::Block,
+    header::Header,
+    subnets::{SubnetworkId, SUBNETWORK_ID_SIZE},
+    time::TimestampMs,
+    trusted::TrustedGhostdagData,
+    tx::{ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+    BlueWorkType,
+};
+use hashes::{Hash, HASH_SIZE};
+use kaspa_utils::networking::{IpAddress, NetAddress};
+use std::net::{IpAddr, Ipv4Addr};
+use thiserror::Error;
+
+/// Errors raised while converting a malformed protowire message into a consensus core type
+#[derive(Error, Debug, Clone)]
+pub enum ConversionError {
+    #[error("missing required field {0}")]
+    MissingField(&'static str),
+
+    #[error("field {0} has an invalid length {1}")]
+    InvalidLength(&'static str, usize),
+
+    #[error("field {0} is out of range")]
+    OutOfRange(&'static str),
+}
+
+pub type ConversionResult<T> = std::result::Result<T, ConversionError>;
+
+fn required<T>(field: Option<T>, name: &'static str) -> ConversionResult<T> {
+    field.ok_or(ConversionError::MissingField(name))
+}
+
+fn narrow<T: TryFrom<U>, U>(value: U, name: &'static str) -> ConversionResult<T> {
+    T::try_from(value).map_err(|_| ConversionError::OutOfRange(name))
+}
+
+/// Blue work is encoded as big endian bytes without leading zeros
+fn blue_work_to_bytes(blue_work: BlueWorkType) -> Vec<u8> {
+    let bytes = blue_work.to_be_bytes();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    bytes[start..].to_vec()
+}
+
+fn blue_work_from_bytes(bytes: &[u8]) -> ConversionResult<BlueWorkType> {
+    if bytes.len() > BlueWorkType::BYTES {
+        return Err(ConversionError::InvalidLength("blue work", bytes.len()));
+    }
+    let mut blue_work = [0u8; BlueWorkType::BYTES];
+    blue_work[BlueWorkType::BYTES - bytes.len()..].copy_from_slice(bytes);
+    Ok(BlueWorkType::from_be_bytes(blue_work))
+}
+
+// ----------------------------------------------------------------------------
+// consensus_core to protowire
+// ----------------------------------------------------------------------------
+
+impl From<Hash> for pb::Hash {
+    fn from(item: Hash) -> Self {
+        Self { bytes: item.as_bytes().to_vec() }
+    }
+}
+
+impl From<TransactionId> for pb::TransactionId {
+    fn from(item: TransactionId) -> Self {
+        Self { bytes: item.as_bytes().to_vec() }
+    }
+}
+
+impl From<&Header> for pb::BlockHeader {
+    fn from(item: &Header) -> Self {
+        Self {
+            version: item.version.into(),
+            parents: item
+                .parents_by_level
+                .iter()
+                .map(|level| pb::BlockLevelParents { parent_hashes: level.iter().copied().map(pb::Hash::from).collect() })
+                .collect(),
+            hash_merkle_root: Some(item.hash_merkle_root.into()),
+            accepted_id_merkle_root: Some(item.accepted_id_merkle_root.into()),
+            utxo_commitment: Some(item.utxo_commitment.into()),
+            timestamp: item.timestamp.try_into().expect("timestamp is always convertible to i64"),
+            bits: item.bits,
+            nonce: item.nonce,
+            daa_score: item.daa_score,
+            blue_work: blue_work_to_bytes(item.blue_work),
+            pruning_point: Some(item.pruning_point.into()),
+            blue_score: item.blue_score,
+        }
+    }
+}
+
+impl From<&ScriptPublicKey> for pb::ScriptPublicKey {
+    fn from(item: &ScriptPublicKey) -> Self {
+        Self { script: item.script().to_vec(), version: item.version().into() }
+    }
+}
+
+impl From<TransactionOutpoint> for pb::Outpoint {
+    fn from(item: TransactionOutpoint) -> Self {
+        Self { transaction_id: Some(item.transaction_id.into()), index: item.index }
+    }
+}
+
+impl From<&Transaction> for pb::TransactionMessage {
+    fn from(item: &Transaction) -> Self {
+        Self {
+            version: item.version.into(),
+            inputs: item
+                .inputs
+                .iter()
+                .map(|input| pb::TransactionInput {
+                    previous_outpoint: Some(input.previous_outpoint.into()),
+                    signature_script: input.signature_script.clone(),
+                    sequence: input.sequence,
+                    sig_op_count: input.sig_op_count.into(),
+                })
+                .collect(),
+            outputs: item
+                .outputs
+                .iter()
+                .map(|output| pb::TransactionOutput {
+                    value: output.value,
+                    script_public_key: Some((&output.script_public_key).into()),
+                })
+                .collect(),
+            lock_time: item.lock_time,
+            subnetwork_id: Some(pb::SubnetworkId { bytes: item.subnetwork_id.as_ref().to_vec() }),
+            gas: item.gas,
+            payload: item.payload.clone(),
+        }
+    }
+}
+
+impl From<&Block> for pb::BlockMessage {
+    fn from(item: &Block) -> Self {
+        Self {
+            header: Some(item.header.as_ref().into()),
+            transactions: item.transactions.iter().map(pb::TransactionMessage::from).collect(),
+        }
+    }
+}
+
+impl From<&(TransactionOutpoint, UtxoEntry)> for pb::OutpointAndUtxoEntryPair {
+    fn from((outpoint, entry): &(TransactionOutpoint, UtxoEntry)) -> Self {
+        Self {
+            outpoint: Some((*outpoint).into()),
+            utxo_entry: Some(pb::UtxoEntry {
+                amount: entry.amount,
+                script_public_key: Some((&entry.script_public_key).into()),
+                block_daa_score: entry.block_daa_score,
+                is_coinbase: entry.is_coinbase,
+            }),
+        }
+    }
+}
+
+impl From<&TrustedGhostdagData> for pb::GhostdagData {
+    fn from(item: &TrustedGhostdagData) -> Self {
+        Self {
+            blue_score: item.blue_score,
+            blue_work: blue_work_to_bytes(item.blue_work),
+            selected_parent: Some(item.selected_parent.into()),
+            merge_set_blues: item.mergeset_blues.iter().copied().map(pb::Hash::from).collect(),
+            merge_set_reds: item.mergeset_reds.iter().copied().map(pb::Hash::from).collect(),
+            blues_anticone_sizes: item
+                .blues_anticone_sizes
+                .iter()
+                .map(|&(hash, size)| pb::BluesAnticoneSizes { blue_hash: Some(hash.into()), anticone_size: size.into() })
+                .collect(),
+        }
+    }
+}
+
+impl From<NetAddress> for pb::NetAddress {
+    fn from(item: NetAddress) -> Self {
+        // The timestamp is set by the caller when relevant, i.e., for gossiped addresses
+        Self { timestamp: 0, ip: IpAddress(item.ip).to_bytes().to_vec(), port: item.port.into() }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// protowire to consensus_core
+// ----------------------------------------------------------------------------
+
+impl TryFrom<pb::Hash> for Hash {
+    type Error = ConversionError;
+    fn try_from(item: pb::Hash) -> ConversionResult<Self> {
+        if item.bytes.len() != HASH_SIZE {
+            return Err(ConversionError::InvalidLength("hash", item.bytes.len()));
+        }
+        Ok(Hash::from_slice(&item.bytes))
+    }
+}
+
+/// Converts a hash field, failing if it is missing
+pub fn required_hash(item: Option<pb::Hash>, name: &'static str) -> ConversionResult<Hash> {
+    required(item, name)?.try_into()
+}
+
+impl TryFrom<pb::TransactionId> for TransactionId {
+    type Error = ConversionError;
+    fn try_from(item: pb::TransactionId) -> ConversionResult<Self> {
+        if item.bytes.len() != HASH_SIZE {
+            return Err(ConversionError::InvalidLength("transaction id", item.bytes.len()));
+        }
+        Ok(TransactionId::from_slice(&item.bytes))
+    }
+}
+
+/// Converts a transaction id field, failing if it is missing
+pub fn required_transaction_id(item: Option<pb::TransactionId>, name: &'static str) -> ConversionResult<TransactionId> {
+    required(item, name)?.try_into()
+}
+
+impl TryFrom<pb::BlockHeader> for Header {
+    type Error = ConversionError;
+    fn try_from(item: pb::BlockHeader) -> ConversionResult<Self> {
+        Ok(Header::new(
+            narrow(item.version, "header version")?,
+            item.parents
+                .into_iter()
+                .map(|level| level.parent_hashes.into_iter().map(Hash::try_from).collect::<ConversionResult<Vec<Hash>>>())
+                .collect::<ConversionResult<Vec<Vec<Hash>>>>()?,
+            required_hash(item.hash_merkle_root, "hash merkle root")?,
+            required_hash(item.accepted_id_merkle_root, "accepted id merkle root")?,
+            required_hash(item.utxo_commitment, "utxo commitment")?,
+            TimestampMs::try_from(item.timestamp).map_err(|_| ConversionError::OutOfRange("header timestamp"))?,
+            item.bits,
+            item.nonce,
+            item.daa_score,
+            blue_work_from_bytes(&item.blue_work)?,
+            item.blue_score,
+            required_hash(item.pruning_point, "pruning point")?,
+        ))
+    }
+}
+
+impl TryFrom<pb::ScriptPublicKey> for ScriptPublicKey {
+    type Error = ConversionError;
+    fn try_from(item: pb::ScriptPublicKey) -> ConversionResult<Self> {
+        Ok(ScriptPublicKey::from_vec(narrow(item.version, "script public key version")?, item.script))
+    }
+}
+
+impl TryFrom<pb::Outpoint> for TransactionOutpoint {
+    type Error = ConversionError;
+    fn try_from(item: pb::Outpoint) -> ConversionResult<Self> {
+        Ok(TransactionOutpoint::new(required_transaction_id(item.transaction_id, "outpoint transaction id")?, item.index))
+    }
+}
+
+impl TryFrom<pb::TransactionInput> for TransactionInput {
+    type Error = ConversionError;
+    fn try_from(item: pb::TransactionInput) -> ConversionResult<Self> {
+        Ok(TransactionInput::new(
+            required(item.previous_outpoint, "previous outpoint")?.try_into()?,
+            item.signature_script,
+            item.sequence,
+            narrow(item.sig_op_count, "sig op count")?,
+        ))
+    }
+}
+
+impl TryFrom<pb::TransactionOutput> for TransactionOutput {
+    type Error = ConversionError;
+    fn try_from(item: pb::TransactionOutput) -> ConversionResult<Self> {
+        Ok(TransactionOutput::new(item.value, required(item.script_public_key, "script public key")?.try_into()?))
+    }
+}
+
+impl TryFrom<pb::TransactionMessage> for Transaction {
+    type Error = ConversionError;
+    fn try_from(item: pb::TransactionMessage) -> ConversionResult<Self> {
+        let subnetwork_id = required(item.subnetwork_id, "subnetwork id")?;
+        let subnetwork_id = <[u8; SUBNETWORK_ID_SIZE]>::try_from(subnetwork_id.bytes.as_slice())
+            .map_err(|_| ConversionError::InvalidLength("subnetwork id", subnetwork_id.bytes.len()))?;
+        Ok(Transaction::new(
+            narrow(item.version, "transaction version")?,
+            item.inputs.into_iter().map(TransactionInput::try_from).collect::<ConversionResult<_>>()?,
+            item.outputs.into_iter().map(TransactionOutput::try_from).collect::<ConversionResult<_>>()?,
+            item.lock_time,
+            SubnetworkId::from_bytes(subnetwork_id),
+            item.gas,
+            item.payload,
+        ))
+    }
+}
+
+impl TryFrom<pb::BlockMessage> for Block {
+    type Error = ConversionError;
+    fn try_from(item: pb::BlockMessage) -> ConversionResult<Self> {
+        Ok(Block::new(
+            required(item.header, "block header")?.try_into()?,
+            item.transactions.into_iter().map(Transaction::try_from).collect::<ConversionResult<_>>()?,
+        ))
+    }
+}
+
+impl TryFrom<pb::OutpointAndUtxoEntryPair> for (TransactionOutpoint, UtxoEntry) {
+    type Error = ConversionError;
+    fn try_from(item: pb::OutpointAndUtxoEntryPair) -> ConversionResult<Self> {
+        let entry = required(item.utxo_entry, "utxo entry")?;
+        Ok((
+            required(item.outpoint, "outpoint")?.try_into()?,
+            UtxoEntry::new(
+                entry.amount,
+                required(entry.script_public_key, "script public key")?.try_into()?,
+                entry.block_daa_score,
+                entry.is_coinbase,
+            ),
+        ))
+    }
+}
+
+impl TryFrom<pb::GhostdagData> for TrustedGhostdagData {
+    type Error = ConversionError;
+    fn try_from(item: pb::GhostdagData) -> ConversionResult<Self> {
+        Ok(TrustedGhostdagData {
+            blue_score: item.blue_score,
+            blue_work: blue_work_from_bytes(&item.blue_work)?,
+            selected_parent: required_hash(item.selected_parent, "selected parent")?,
+            mergeset_blues: item.merge_set_blues.into_iter().map(Hash::try_from).collect::<ConversionResult<_>>()?,
+            mergeset_reds: item.merge_set_reds.into_iter().map(Hash::try_from).collect::<ConversionResult<_>>()?,
+            blues_anticone_sizes: item
+                .blues_anticone_sizes
+                .into_iter()
+                .map(|sizes| Ok((required_hash(sizes.blue_hash, "blue hash")?, narrow(sizes.anticone_size, "blue anticone size")?)))
+                .collect::<ConversionResult<_>>()?,
+        })
+    }
+}
+
+impl TryFrom<pb::NetAddress> for NetAddress {
+    type Error = ConversionError;
+    fn try_from(item: pb::NetAddress) -> ConversionResult<Self> {
+        // IPv4 addresses are sent either as 4 bytes or IPv4-mapped into 16 bytes
+        let ip = match item.ip.len() {
+            4 => IpAddr::V4(Ipv4Addr::new(item.ip[0], item.ip[1], item.ip[2], item.ip[3])),
+            IpAddress::SERIALIZED_LEN => IpAddress::from_bytes(item.ip.as_slice().try_into().unwrap()).into(),
+            len => return Err(ConversionError::InvalidLength("ip", len)),
+        };
+        Ok(NetAddress::new(ip, narrow(item.port, "port")?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_block_roundtrip() {
+        let header = Header::new(
+            1,
+            vec![vec![1.into(), 2.into()], vec![3.into()]],
+            4.into(),
+            5.into(),
+            6.into(),
+            TimestampMs::from_millis(1_000),
+            0x1e7fffff,
+            7,
+            8,
+            BlueWorkType::from_u64(0x1234),
+            9,
+            10.into(),
+        );
+        let tx = Transaction::new(
+            0,
+            vec![TransactionInput::new(TransactionOutpoint::new(11.into(), 2), vec![1, 2, 3], 4, 1)],
+            vec![TransactionOutput::new(300, ScriptPublicKey::from_vec(0, vec![5, 6]))],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        );
+        let block = Block::new(header, vec![tx]);
+
+        let message = pb::BlockMessage::from(&block);
+        assert_eq!(message.header.as_ref().unwrap().blue_work, vec![0x12, 0x34]);
+        let decoded = Block::try_from(message).unwrap();
+        assert_eq!(decoded.hash(), block.hash());
+        assert_eq!(decoded.header.blue_work, block.header.blue_work);
+        assert_eq!(decoded.transactions[0].id(), block.transactions[0].id());
+    }
+
+    #[test]
+    fn test_malformed_messages() {
+        assert!(matches!(Hash::try_from(pb::Hash { bytes: vec![0; 31] }), Err(ConversionError::InvalidLength("hash", 31))));
+        assert!(matches!(required_hash(None, "low hash"), Err(ConversionError::MissingField("low hash"))));
+
+        let mut header = pb::BlockHeader::from(&Header::new(
+            0,
+            vec![],
+            1.into(),
+            2.into(),
+            3.into(),
+            Default::default(),
+            0,
+            0,
+            0,
+            0.into(),
+            0,
+            4.into(),
+        ));
+        header.blue_work = vec![1; BlueWorkType::BYTES + 1];
+        assert!(matches!(Header::try_from(header.clone()), Err(ConversionError::InvalidLength("blue work", _))));
+        header.blue_work = vec![];
+        header.version = u32::MAX;
+        assert!(matches!(Header::try_from(header), Err(ConversionError::OutOfRange("header version"))));
+    }
+
+    #[test]
+    fn test_trusted_data_roundtrip() {
+        let ghostdag_data = TrustedGhostdagData {
+            blue_score: 5,
+            blue_work: BlueWorkType::from_u64(0x10000),
+            selected_parent: 1.into(),
+            mergeset_blues: vec![1.into(), 2.into()],
+            mergeset_reds: vec![3.into()],
+            blues_anticone_sizes: vec![(1.into(), 0), (2.into(), 1)],
+        };
+        let message = pb::GhostdagData::from(&ghostdag_data);
+        assert_eq!(message.blue_work, vec![1, 0, 0]);
+        assert_eq!(TrustedGhostdagData::try_from(message.clone()).unwrap(), ghostdag_data);
+        let mut out_of_range = message;
+        out_of_range.blues_anticone_sizes[0].anticone_size = 256;
+        assert!(matches!(TrustedGhostdagData::try_from(out_of_range), Err(ConversionError::OutOfRange("blue anticone size"))));
+
+        let utxo = (TransactionOutpoint::new(4.into(), 1), UtxoEntry::new(500, ScriptPublicKey::from_vec(0, vec![7, 8]), 6, true));
+        assert_eq!(<(TransactionOutpoint, UtxoEntry)>::try_from(pb::OutpointAndUtxoEntryPair::from(&utxo)).unwrap(), utxo);
+        let missing_entry = pb::OutpointAndUtxoEntryPair { outpoint: Some(utxo.0.into()), utxo_entry: None };
+        assert!(matches!(
+            <(TransactionOutpoint, UtxoEntry)>::try_from(missing_entry),
+            Err(ConversionError::MissingField("utxo entry"))
+        ));
+    }
+
+    #[test]
+    fn test_net_address_roundtrip() {
+        for input in ["1.2.3.4:16111", "[2606:4700::1111]:16111"] {
+            let address = NetAddress::from_str(input).unwrap();
+            assert_eq!(NetAddress::try_from(pb::NetAddress::from(address)).unwrap(), address);
+        }
+        let short_ipv4 = pb::NetAddress { timestamp: 0, ip: vec![1, 2, 3, 4], port: 16111 };
+        assert_eq!(NetAddress::try_from(short_ipv4).unwrap(), NetAddress::from_str("1.2.3.4:16111").unwrap());
+        let malformed = pb::NetAddress { timestamp: 0, ip: vec![1, 2, 3], port: 16111 };
+        assert!(matches!(NetAddress::try_from(malformed), Err(ConversionError::InvalidLength("ip", 3))));
+        let out_of_range = pb::NetAddress { timestamp: 0, ip: vec![1, 2, 3, 4], port: 1 << 16 };
+        assert!(matches!(NetAddress::try_from(out_of_range), Err(ConversionError::OutOfRange("port"))));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    dequeue_with_timeout,
+    pb::{self, kaspad_message::Payload, KaspadMessage, ReadyMessage, RejectMessage, VerackMessage, VersionMessage},
+    IncomingRoute, KaspadMessagePayloadType, PeerId, PeerProperties, ProtocolError, Router,
+};
+use kaspa_utils::networking::NetAddress;
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The P2P protocol version implemented by this node
+pub const PROTOCOL_VERSION: u32 = 5;
+
+/// The lowest protocol version accepted from peers. Version 5 introduced the ready message exchange.
+pub const MIN_PROTOCOL_VERSION: u32 = 5;
+
+/// The service flag of nodes keeping the data of all blocks, which serve the DAG down to genesis to archival peers
+pub const SERVICE_ARCHIVAL: u64 = 1 << 0;
+
+/// The maximum duration to wait for each of the handshake messages
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The local node information advertised to peers during the handshake
+#[derive(Debug, Clone)]
+pub struct LocalVersion {
+    pub id: PeerId,
+    pub network: String,
+    pub user_agent: String,
+    pub services: u64,
+    pub disable_relay_tx: bool,
+    pub protocol_version: u32,
+    /// The address peers may connect to this node on, i.e., the external or publicly routable listen address, if any
+    pub address: Option<NetAddress>,
+}
+
+impl LocalVersion {
+    pub fn new(id: PeerId, network: String, user_agent: String) -> Self {
+        Self { id, network, user_agent, services: 0, disable_relay_tx: false, protocol_version: PROTOCOL_VERSION, address: None }
+    }
+
+    fn to_message(&self) -> VersionMessage {
+        VersionMessage {
+            protocol_version: self.protocol_version,
+            services: self.services,
+            timestamp: unix_now() as i64,
+            address: self.address.map(pb::NetAddress::from),
+            id: self.id.as_bytes().to_vec(),
+            user_agent: self.user_agent.clone(),
+            disable_relay_tx: self.disable_relay_tx,
+            subnetwork_id: None,
+            network: self.network.clone(),
+        }
+    }
+}
+
+/// Performs the kaspad handshake over a new peer connection:
+///
+/// 1. both sides send their version message and validate the one they receive, rejecting
+///    peers from other networks or with an incompatible protocol version
+/// 2. both sides acknowledge the handshake with a verack message
+/// 3. once the caller has subscribed the peer flows, both sides exchange ready messages,
+///    signaling they are able to handle any flow message
+pub struct KaspadHandshake {
+    router: Arc<Router>,
+    version_route: IncomingRoute,
+    verack_route: IncomingRoute,
+    ready_route: IncomingRoute,
+}
+
+impl KaspadHandshake {
+    /// Subscribes to the handshake messages. Must be called before the router is started.
+    pub fn new(router: Arc<Router>) -> Self {
+        let version_route = router.subscribe(vec![KaspadMessagePayloadType::Version]);
+        let verack_route = router.subscribe(vec![KaspadMessagePayloadType::Verack]);
+        let ready_route = router.subscribe(vec![KaspadMessagePayloadType::Ready]);
+        Self { router, version_route, verack_route, ready_route }
+    }
+
+    /// Exchanges the version and verack messages, returning the negotiated peer properties
+    pub async fn handshake(&mut self, local: &LocalVersion) -> Result<PeerProperties, ProtocolError> {
+        let router = &self.router;
+        let version_route = &mut self.version_route;
+        let (send_result, receive_result) =
+            tokio::join!(router.enqueue(KaspadMessage { payload: Some(Payload::Version(local.to_message())) }), async {
+                dequeue_with_timeout!(version_route, Payload::Version, HANDSHAKE_TIMEOUT)
+            });
+        send_result?;
+        let version = receive_result?;
+        let time_offset = unix_now() as i64 - version.timestamp;
+        let advertised_address = version.address.clone().map(NetAddress::try_from).transpose()?;
+
+        let id = match Self::validate_version(local, &version) {
+            Ok(id) => id,
+            Err(err) => {
+                // Let the peer know why it is being disconnected. The connection is dropped anyway, so a failure is ignored.
+                let _ =
+                    router.enqueue(KaspadMessage { payload: Some(Payload::Reject(RejectMessage { reason: err.to_string() })) }).await;
+                return Err(err);
+            }
+        };
+
+        let verack_route = &mut self.verack_route;
+        let (send_result, receive_result) =
+            tokio::join!(router.enqueue(KaspadMessage { payload: Some(Payload::Verack(VerackMessage {})) }), async {
+                dequeue_with_timeout!(verack_route, Payload::Verack, HANDSHAKE_TIMEOUT)
+            });
+        send_result?;
+        receive_result?;
+
+        Ok(PeerProperties {
+            id,
+            user_agent: version.user_agent,
+            services: version.services,
+            advertised_protocol_version: version.protocol_version,
+            protocol_version: version.protocol_version.min(local.protocol_version),
+            disable_relay_tx: version.disable_relay_tx,
+            time_offset,
+            advertised_address,
+        })
+    }
+
+    /// Exchanges the ready messages. Expected to be called once the peer flows have been subscribed.
+    pub async fn exchange_ready_messages(&mut self) -> Result<(), ProtocolError> {
+        let router = &self.router;
+        let ready_route = &mut self.ready_route;
+        let (send_result, receive_result) =
+            tokio::join!(router.enqueue(KaspadMessage { payload: Some(Payload::Ready(ReadyMessage {})) }), async {
+                dequeue_with_timeout!(ready_route, Payload::Ready, HANDSHAKE_TIMEOUT)
+            });
+        send_result?;
+        receive_result?;
+        Ok(())
+    }
+
+    fn validate_version(local: &LocalVersion, version: &VersionMessage) -> Result<PeerId, ProtocolError> {
+        if version.network != local.network {
+            return Err(ProtocolError::WrongNetwork(local.network.clone(), version.network.clone()));
+        }
+        if version.protocol_version < MIN_PROTOCOL_VERSION {
+            return Err(ProtocolError::VersionMismatch(MIN_PROTOCOL_VERSION, version.protocol_version));
+        }
+        let id = PeerId::from_slice(&version.id).map_err(|_| ProtocolError::Other("peer version message has a malformed id"))?;
+        if id == local.id {
+            return Err(ProtocolError::LoopbackConnection);
+        }
+        Ok(id)
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Adaptor, ConnectionError, ConnectionInitializer, Hub};
+    use uuid::Uuid;
+
+    struct HandshakeInitializer {
+        local: LocalVersion,
+    }
+
+    #[tonic::async_trait]
+    impl ConnectionInitializer for HandshakeInitializer {
+        async fn initialize_connection(&self, new_router: Arc<Router>) -> Result<(), ProtocolError> {
+            let mut handshake = KaspadHandshake::new(new_router.clone());
+            new_router.start();
+            let properties = handshake.handshake(&self.local).await?;
+            new_router.set_properties(Arc::new(properties));
+            handshake.exchange_ready_messages().await
+        }
+    }
+
+    async fn connect(server_local: LocalVersion, client_local: LocalVersion) -> Result<Arc<Router>, ConnectionError> {
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let server = Adaptor::bidirectional(address, Hub::new(), Arc::new(HandshakeInitializer { local: server_local })).unwrap();
+        let client = Adaptor::client_only(Hub::new(), Arc::new(HandshakeInitializer { local: client_local }));
+        let result = client.connect_peer_with_retry(address.to_string(), 10, Duration::from_millis(100)).await;
+        server.close().await;
+        result
+    }
+
+    fn local_version(network: &str) -> LocalVersion {
+        LocalVersion::new(Uuid::new_v4(), network.to_string(), "/test:0.1.0/".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_handshake() {
+        let mut server_local = local_version("kaspa-mainnet");
+        server_local.protocol_version = PROTOCOL_VERSION + 1;
+        server_local.disable_relay_tx = true;
+        let router = connect(server_local.clone(), local_version("kaspa-mainnet")).await.unwrap();
+        let properties = router.properties();
+        assert_eq!(properties.id, server_local.id);
+        assert_eq!(properties.user_agent, server_local.user_agent);
+        assert_eq!(properties.advertised_protocol_version, PROTOCOL_VERSION + 1);
+        assert_eq!(properties.protocol_version, PROTOCOL_VERSION);
+        assert!(properties.disable_relay_tx);
+        assert_eq!(properties.advertised_address, None);
+
+        // The advertised address is passed along the version message
+        let mut server_local = local_version("kaspa-mainnet");
+        server_local.address = Some("8.8.8.8:16111".parse().unwrap());
+        let router = connect(server_local.clone(), local_version("kaspa-mainnet")).await.unwrap();
+        assert_eq!(router.properties().advertised_address, server_local.address);
+    }
+
+    #[tokio::test]
+    async fn test_handshake_rejections() {
+        let result = connect(local_version("kaspa-mainnet"), local_version("kaspa-testnet")).await;
+        assert!(matches!(result, Err(ConnectionError::ProtocolError(ProtocolError::WrongNetwork(..)))), "{:?}", result.err());
+
+        let mut server_local = local_version("kaspa-mainnet");
+        server_local.protocol_version = MIN_PROTOCOL_VERSION - 1;
+        let result = connect(server_local, local_version("kaspa-mainnet")).await;
+        assert!(matches!(result, Err(ConnectionError::ProtocolError(ProtocolError::VersionMismatch(..)))), "{:?}", result.err());
+
+        let server_local = local_version("kaspa-mainnet");
+        let result = connect(server_local.clone(), server_local).await;
+        assert!(matches!(result, Err(ConnectionError::ProtocolError(ProtocolError::LoopbackConnection))), "{:?}", result.err());
+    }
+}
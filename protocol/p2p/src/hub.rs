@@ -0,0 +1,88 @@
+use crate::{common::ProtocolError, pb::KaspadMessage, Router};
+use kaspa_core::debug;
+use parking_lot::RwLock;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tokio::sync::mpsc::Receiver as MpscReceiver;
+
+pub enum HubEvent {
+    /// A peer connection was successfully initialized
+    NewPeer(Arc<Router>),
+
+    /// A peer connection is closing
+    PeerClosing(Arc<Router>),
+}
+
+/// Keeps track of the active peers, fed by the [`HubEvent`]s of the connection handler and routers
+#[derive(Clone, Default)]
+pub struct Hub {
+    peers: Arc<RwLock<HashMap<SocketAddr, Arc<Router>>>>,
+}
+
+impl Hub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn start_event_loop(self, mut hub_receiver: MpscReceiver<HubEvent>) {
+        tokio::spawn(async move {
+            while let Some(event) = hub_receiver.recv().await {
+                self.handle_event(event);
+            }
+        });
+    }
+
+    fn handle_event(&self, event: HubEvent) {
+        match event {
+            HubEvent::NewPeer(router) => {
+                // A router closed during its initialization has already reported closing
+                if !router.is_closed() {
+                    debug!("P2P, new peer {}", router);
+                    self.peers.write().insert(router.net_address(), router);
+                }
+            }
+            HubEvent::PeerClosing(router) => {
+                if let Entry::Occupied(entry) = self.peers.write().entry(router.net_address()) {
+                    // Make sure a newer connection with the same address is not removed
+                    if Arc::ptr_eq(entry.get(), &router) {
+                        debug!("P2P, peer {} disconnected", router);
+                        entry.remove();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends a message to a specific peer, returning `false` if the peer is not active
+    pub async fn send(&self, peer_address: SocketAddr, msg: KaspadMessage) -> Result<bool, ProtocolError> {
+        let router = self.peers.read().get(&peer_address).cloned();
+        match router {
+            Some(router) => router.enqueue(msg).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Sends a message to all active peers. Peers failing to receive it are closing anyway, so errors are ignored.
+    pub async fn broadcast(&self, msg: KaspadMessage) {
+        for router in self.active_peers() {
+            let _ = router.enqueue(msg.clone()).await;
+        }
+    }
+
+    pub async fn terminate_all_peers(&self) {
+        for router in self.active_peers() {
+            router.close().await;
+        }
+    }
+
+    pub fn active_peers(&self) -> Vec<Arc<Router>> {
+        self.peers.read().values().cloned().collect()
+    }
+
+    pub fn active_peers_len(&self) -> usize {
+        self.peers.read().len()
+    }
+}
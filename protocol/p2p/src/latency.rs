@@ -0,0 +1,151 @@
+use parking_lot::Mutex;
+use std::time::Duration;
+
+/// The weight of a new sample in the smoothed round-trip time, as in RFC 6298
+const RTT_ALPHA: f64 = 1.0 / 8.0;
+
+/// The weight of a new sample in the round-trip time variance, as in RFC 6298
+const RTT_BETA: f64 = 1.0 / 4.0;
+
+/// The weight of a new sample in the smoothed throughput
+const THROUGHPUT_ALPHA: f64 = 1.0 / 4.0;
+
+/// Transfers smaller than this are dominated by latency rather than bandwidth, so they are not sampled
+pub const MIN_THROUGHPUT_SAMPLE_BYTES: usize = 16 * 1024;
+
+/// The margin applied to the expected response time, absorbing the processing time of the peer and bursts of load
+const TIMEOUT_SAFETY_FACTOR: u32 = 4;
+
+/// The bounds of an adaptive request timeout
+#[derive(Clone, Copy, Debug)]
+pub struct TimeoutBounds {
+    /// The shortest timeout, however fast the peer is
+    pub min: Duration,
+    /// The timeout used until the peer latency is measured
+    pub initial: Duration,
+    /// The longest timeout, however slow the peer is
+    pub max: Duration,
+}
+
+impl TimeoutBounds {
+    pub const fn new(min: Duration, initial: Duration, max: Duration) -> Self {
+        Self { min, initial, max }
+    }
+}
+
+#[derive(Default)]
+struct LatencyState {
+    /// The smoothed round-trip time and its variance, in seconds
+    rtt: Option<(f64, f64)>,
+    /// The smoothed throughput in bytes per second
+    throughput: Option<f64>,
+}
+
+/// Estimates the latency and throughput of a peer connection, from which request timeouts are derived.
+///
+/// The round-trip time is sampled by pings and smoothed as in TCP (RFC 6298), while the throughput is sampled
+/// from the responses of large requests. A request expecting a response of a given size then times out after
+/// a multiple of the round-trip timeout plus the time needed to transfer the response, clamped to the bounds
+/// of the request type. Slow peers are thus given the time they need, while fast peers stalling are detected quickly.
+#[derive(Default)]
+pub struct PeerLatency {
+    state: Mutex<LatencyState>,
+}
+
+impl PeerLatency {
+    pub fn record_rtt(&self, rtt: Duration) {
+        let sample = rtt.as_secs_f64();
+        let mut state = self.state.lock();
+        state.rtt = Some(match state.rtt {
+            Some((smoothed, variance)) => {
+                ((1.0 - RTT_ALPHA) * smoothed + RTT_ALPHA * sample, (1.0 - RTT_BETA) * variance + RTT_BETA * (smoothed - sample).abs())
+            }
+            None => (sample, sample / 2.0),
+        });
+    }
+
+    /// Records the transfer of a response of `bytes` received `elapsed` after being requested
+    pub fn record_transfer(&self, bytes: usize, elapsed: Duration) {
+        if bytes < MIN_THROUGHPUT_SAMPLE_BYTES || elapsed.is_zero() {
+            return;
+        }
+        let sample = bytes as f64 / elapsed.as_secs_f64();
+        let mut state = self.state.lock();
+        state.throughput = Some(match state.throughput {
+            Some(smoothed) => (1.0 - THROUGHPUT_ALPHA) * smoothed + THROUGHPUT_ALPHA * sample,
+            None => sample,
+        });
+    }
+
+    /// Records the transfer of a response message received `elapsed` after being requested
+    pub fn record_response(&self, response: &impl prost::Message, elapsed: Duration) {
+        self.record_transfer(response.encoded_len(), elapsed)
+    }
+
+    /// The smoothed round-trip time, if measured
+    pub fn smoothed_rtt(&self) -> Option<Duration> {
+        self.state.lock().rtt.map(|(smoothed, _)| Duration::from_secs_f64(smoothed))
+    }
+
+    /// The smoothed throughput in bytes per second, if measured
+    pub fn throughput(&self) -> Option<f64> {
+        self.state.lock().throughput
+    }
+
+    /// The timeout of a request whose response is expected to be up to `expected_bytes` long
+    pub fn timeout(&self, bounds: TimeoutBounds, expected_bytes: u64) -> Duration {
+        let state = self.state.lock();
+        let (smoothed, variance) = match state.rtt {
+            Some(rtt) => rtt,
+            None => return bounds.initial,
+        };
+        let transfer_time = state.throughput.map_or(0.0, |throughput| expected_bytes as f64 / throughput);
+        let expected = Duration::try_from_secs_f64(smoothed + 4.0 * variance + transfer_time).unwrap_or(bounds.max);
+        expected.saturating_mul(TIMEOUT_SAFETY_FACTOR).clamp(bounds.min, bounds.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: TimeoutBounds = TimeoutBounds::new(Duration::from_secs(5), Duration::from_secs(30), Duration::from_secs(120));
+
+    #[test]
+    fn test_adaptive_timeout() {
+        let latency = PeerLatency::default();
+        assert_eq!(latency.timeout(BOUNDS, 1_000_000), BOUNDS.initial);
+
+        // A fast peer is bounded by the minimum timeout
+        latency.record_rtt(Duration::from_millis(50));
+        latency.record_transfer(1_000_000, Duration::from_millis(100));
+        assert_eq!(latency.smoothed_rtt(), Some(Duration::from_millis(50)));
+        assert_eq!(latency.timeout(BOUNDS, 1_000_000), BOUNDS.min);
+
+        // A slow peer is given the time needed to transfer the expected response, up to the maximum timeout
+        let latency = PeerLatency::default();
+        latency.record_rtt(Duration::from_secs(1));
+        latency.record_transfer(100_000, Duration::from_secs(2));
+        assert_eq!(latency.throughput(), Some(50_000.0));
+        assert_eq!(latency.timeout(BOUNDS, 0), Duration::from_secs(12));
+        assert_eq!(latency.timeout(BOUNDS, 500_000), Duration::from_secs(52));
+        assert_eq!(latency.timeout(BOUNDS, 10_000_000), BOUNDS.max);
+
+        // Small transfers are not sampled
+        latency.record_transfer(1_000, Duration::from_secs(10));
+        assert_eq!(latency.throughput(), Some(50_000.0));
+    }
+
+    #[test]
+    fn test_rtt_smoothing() {
+        let latency = PeerLatency::default();
+        latency.record_rtt(Duration::from_millis(800));
+        for _ in 0..100 {
+            latency.record_rtt(Duration::from_millis(100));
+        }
+        assert!(latency.smoothed_rtt().unwrap().abs_diff(Duration::from_millis(100)) < Duration::from_millis(1));
+        // The variance decays along with the deviation, tightening the timeout of a steady peer
+        let timeout = latency.timeout(TimeoutBounds::new(Duration::ZERO, BOUNDS.initial, BOUNDS.max), 0);
+        assert!(timeout.abs_diff(Duration::from_millis(400)) < Duration::from_millis(1));
+    }
+}
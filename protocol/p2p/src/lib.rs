@@ -0,0 +1,26 @@
+pub mod adaptor;
+pub mod common;
+pub mod connection_handler;
+pub mod convert;
+pub mod handshake;
+pub mod hub;
+pub mod latency;
+pub mod payload_type;
+pub mod peer;
+pub mod router;
+
+// We have no control over the code generated by the tonic builder,
+// so clippy is fully disabled here.
+#[allow(clippy::all)]
+pub mod pb {
+    tonic::include_proto!("protowire");
+}
+
+pub use adaptor::{Adaptor, ConnectionInitializer};
+pub use common::{ConnectionError, Misbehavior, ProtocolError};
+pub use convert::ConversionError;
+pub use hub::{Hub, HubEvent};
+pub use latency::{PeerLatency, TimeoutBounds};
+pub use payload_type::KaspadMessagePayloadType;
+pub use peer::{PeerId, PeerProperties};
+pub use router::{IncomingRoute, Router};
@@ -0,0 +1,81 @@
+use crate::pb::kaspad_message::Payload as KaspadMessagePayload;
+
+/// The type of a [`KaspadMessagePayload`], used as the routing key of incoming messages
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KaspadMessagePayloadType {
+    Addresses,
+    Block,
+    Transaction,
+    RequestAddresses,
+    RequestRelayBlocks,
+    RequestTransactions,
+    IbdBlock,
+    InvRelayBlock,
+    InvTransactions,
+    Ping,
+    Pong,
+    Verack,
+    Version,
+    TransactionNotFound,
+    Reject,
+    RequestIbdBlocks,
+    BlockHeaders,
+    RequestNextHeaders,
+    DoneHeaders,
+    RequestHeaders,
+    Ready,
+    RequestIbdChainBlockLocator,
+    IbdChainBlockLocator,
+    PruningPointUtxoSetChunk,
+    UnexpectedPruningPoint,
+    RequestNextPruningPointUtxoSetChunk,
+    DonePruningPointUtxoSetChunks,
+    DoneBlocksWithTrustedData,
+    RequestPruningPointAndItsAnticone,
+    RequestPruningPointUtxoSet,
+    PruningPoints,
+    BlockWithTrustedDataV4,
+    TrustedData,
+}
+
+impl From<&KaspadMessagePayload> for KaspadMessagePayloadType {
+    fn from(payload: &KaspadMessagePayload) -> Self {
+        match payload {
+            KaspadMessagePayload::Addresses(_) => KaspadMessagePayloadType::Addresses,
+            KaspadMessagePayload::Block(_) => KaspadMessagePayloadType::Block,
+            KaspadMessagePayload::Transaction(_) => KaspadMessagePayloadType::Transaction,
+            KaspadMessagePayload::RequestAddresses(_) => KaspadMessagePayloadType::RequestAddresses,
+            KaspadMessagePayload::RequestRelayBlocks(_) => KaspadMessagePayloadType::RequestRelayBlocks,
+            KaspadMessagePayload::RequestTransactions(_) => KaspadMessagePayloadType::RequestTransactions,
+            KaspadMessagePayload::IbdBlock(_) => KaspadMessagePayloadType::IbdBlock,
+            KaspadMessagePayload::InvRelayBlock(_) => KaspadMessagePayloadType::InvRelayBlock,
+            KaspadMessagePayload::InvTransactions(_) => KaspadMessagePayloadType::InvTransactions,
+            KaspadMessagePayload::Ping(_) => KaspadMessagePayloadType::Ping,
+            KaspadMessagePayload::Pong(_) => KaspadMessagePayloadType::Pong,
+            KaspadMessagePayload::Verack(_) => KaspadMessagePayloadType::Verack,
+            KaspadMessagePayload::Version(_) => KaspadMessagePayloadType::Version,
+            KaspadMessagePayload::TransactionNotFound(_) => KaspadMessagePayloadType::TransactionNotFound,
+            KaspadMessagePayload::Reject(_) => KaspadMessagePayloadType::Reject,
+            KaspadMessagePayload::RequestIbdBlocks(_) => KaspadMessagePayloadType::RequestIbdBlocks,
+            KaspadMessagePayload::BlockHeaders(_) => KaspadMessagePayloadType::BlockHeaders,
+            KaspadMessagePayload::RequestNextHeaders(_) => KaspadMessagePayloadType::RequestNextHeaders,
+            KaspadMessagePayload::DoneHeaders(_) => KaspadMessagePayloadType::DoneHeaders,
+            KaspadMessagePayload::RequestHeaders(_) => KaspadMessagePayloadType::RequestHeaders,
+            KaspadMessagePayload::Ready(_) => KaspadMessagePayloadType::Ready,
+            KaspadMessagePayload::RequestIbdChainBlockLocator(_) => KaspadMessagePayloadType::RequestIbdChainBlockLocator,
+            KaspadMessagePayload::IbdChainBlockLocator(_) => KaspadMessagePayloadType::IbdChainBlockLocator,
+            KaspadMessagePayload::PruningPointUtxoSetChunk(_) => KaspadMessagePayloadType::PruningPointUtxoSetChunk,
+            KaspadMessagePayload::UnexpectedPruningPoint(_) => KaspadMessagePayloadType::UnexpectedPruningPoint,
+            KaspadMessagePayload::RequestNextPruningPointUtxoSetChunk(_) => {
+                KaspadMessagePayloadType::RequestNextPruningPointUtxoSetChunk
+            }
+            KaspadMessagePayload::DonePruningPointUtxoSetChunks(_) => KaspadMessagePayloadType::DonePruningPointUtxoSetChunks,
+            KaspadMessagePayload::DoneBlocksWithTrustedData(_) => KaspadMessagePayloadType::DoneBlocksWithTrustedData,
+            KaspadMessagePayload::RequestPruningPointAndItsAnticone(_) => KaspadMessagePayloadType::RequestPruningPointAndItsAnticone,
+            KaspadMessagePayload::RequestPruningPointUtxoSet(_) => KaspadMessagePayloadType::RequestPruningPointUtxoSet,
+            KaspadMessagePayload::PruningPoints(_) => KaspadMessagePayloadType::PruningPoints,
+            KaspadMessagePayload::BlockWithTrustedDataV4(_) => KaspadMessagePayloadType::BlockWithTrustedDataV4,
+            KaspadMessagePayload::TrustedData(_) => KaspadMessagePayloadType::TrustedData,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::handshake::SERVICE_ARCHIVAL;
+use kaspa_utils::networking::NetAddress;
+use uuid::Uuid;
+
+/// The unique id a node advertises in its version message
+pub type PeerId = Uuid;
+
+/// The properties of a peer, as negotiated during the handshake
+#[derive(Debug, Clone, Default)]
+pub struct PeerProperties {
+    pub id: PeerId,
+    pub user_agent: String,
+    pub services: u64,
+    /// The protocol version advertised by the peer
+    pub advertised_protocol_version: u32,
+    /// The protocol version used with the peer, i.e. the lowest of the local and advertised versions
+    pub protocol_version: u32,
+    pub disable_relay_tx: bool,
+    /// The peer clock offset in milliseconds, as measured when its version message was received
+    pub time_offset: i64,
+    /// The address the peer advertised for accepting connections, if any
+    pub advertised_address: Option<NetAddress>,
+}
+
+impl PeerProperties {
+    /// Returns whether the peer advertised keeping the data of all blocks
+    pub fn is_archival(&self) -> bool {
+        self.services & SERVICE_ARCHIVAL != 0
+    }
+}
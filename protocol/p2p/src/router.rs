@@ -0,0 +1,220 @@
+use crate::{common::ProtocolError, hub::HubEvent, latency::PeerLatency, pb::KaspadMessage, KaspadMessagePayloadType, PeerProperties};
+use kaspa_core::{debug, trace};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::{channel as mpsc_channel, error::TrySendError, Receiver as MpscReceiver, Sender as MpscSender};
+use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
+use tonic::Streaming;
+
+/// The capacity of the route feeding incoming messages of a given type to the flow which subscribed to them.
+/// A peer overflowing a route is disconnected.
+pub const INCOMING_ROUTE_CAPACITY: usize = 256;
+
+/// The capacity of the route of messages waiting to be sent to the peer
+pub const OUTGOING_ROUTE_CAPACITY: usize = 256;
+
+pub type IncomingRoute = MpscReceiver<KaspadMessage>;
+
+struct RouterMutableState {
+    /// Signals the receive loop to start routing incoming messages
+    start_signal: Option<OneshotSender<()>>,
+
+    /// Signals the receive loop to exit
+    shutdown_signal: Option<OneshotSender<()>>,
+}
+
+/// Routes the messages of a single peer connection.
+///
+/// Incoming messages are dispatched by payload type to the flows which subscribed to them,
+/// while messages enqueued by flows are streamed to the peer through the outgoing route.
+///
+/// #### Lifetime
+///
+/// - new: the receive loop is spawned but waits for the router to be started
+/// - subscribe: flows register the message types they handle
+/// - start: incoming messages start being routed
+/// - close: triggered by any routing or stream error, or explicitly. Drops all incoming routes
+///   and reports the peer as closing to the hub
+pub struct Router {
+    net_address: SocketAddr,
+    is_outbound: bool,
+    connection_started: Instant,
+    properties: RwLock<Arc<PeerProperties>>,
+    /// Measured by the flows, for deriving the timeouts of requests sent to the peer
+    latency: PeerLatency,
+    routing_map: RwLock<HashMap<KaspadMessagePayloadType, MpscSender<KaspadMessage>>>,
+    /// Dropped on close, ending the stream of messages sent to the peer
+    outgoing_route: Mutex<Option<MpscSender<KaspadMessage>>>,
+    hub_sender: MpscSender<HubEvent>,
+    mutable_state: Mutex<RouterMutableState>,
+}
+
+impl Display for Router {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.net_address, if self.is_outbound { "outbound" } else { "inbound" })
+    }
+}
+
+impl Router {
+    pub(crate) fn new(
+        net_address: SocketAddr,
+        is_outbound: bool,
+        hub_sender: MpscSender<HubEvent>,
+        mut incoming_stream: Streaming<KaspadMessage>,
+        outgoing_route: MpscSender<KaspadMessage>,
+    ) -> Arc<Self> {
+        let (start_sender, start_receiver) = oneshot_channel();
+        let (shutdown_sender, mut shutdown_receiver) = oneshot_channel();
+
+        let router = Arc::new(Router {
+            net_address,
+            is_outbound,
+            connection_started: Instant::now(),
+            properties: Default::default(),
+            latency: Default::default(),
+            routing_map: RwLock::new(HashMap::new()),
+            outgoing_route: Mutex::new(Some(outgoing_route)),
+            hub_sender,
+            mutable_state: Mutex::new(RouterMutableState { start_signal: Some(start_sender), shutdown_signal: Some(shutdown_sender) }),
+        });
+
+        // Spawn the receive loop
+        let receive_loop_router = router.clone();
+        tokio::spawn(async move {
+            let router = receive_loop_router;
+            // A dropped start signal means the router was closed before being started
+            if start_receiver.await.is_ok() {
+                loop {
+                    tokio::select! {
+                        biased;
+                        _ = &mut shutdown_receiver => break,
+                        result = incoming_stream.message() => match result {
+                            Ok(Some(msg)) => {
+                                if let Err(err) = router.route_to_flow(msg) {
+                                    debug!("P2P, route error: {} for peer {}", err, router);
+                                    break;
+                                }
+                            }
+                            Ok(None) => {
+                                debug!("P2P, incoming stream ended for peer {}", router);
+                                break;
+                            }
+                            Err(status) => {
+                                debug!("P2P, incoming stream error: {} for peer {}", status, router);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            router.close().await;
+            trace!("P2P, receive loop of peer {} exited", router);
+        });
+
+        router
+    }
+
+    pub fn net_address(&self) -> SocketAddr {
+        self.net_address
+    }
+
+    pub fn is_outbound(&self) -> bool {
+        self.is_outbound
+    }
+
+    /// The time elapsed since the connection was established
+    pub fn time_connected(&self) -> Duration {
+        self.connection_started.elapsed()
+    }
+
+    /// The peer properties, available once the handshake has completed
+    pub fn properties(&self) -> Arc<PeerProperties> {
+        self.properties.read().clone()
+    }
+
+    pub fn set_properties(&self, properties: Arc<PeerProperties>) {
+        *self.properties.write() = properties;
+    }
+
+    /// The latency and throughput measured for the peer, from which request timeouts are derived
+    pub fn latency(&self) -> &PeerLatency {
+        &self.latency
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.mutable_state.lock().shutdown_signal.is_none()
+    }
+
+    /// Subscribes to the given message types, returning the route on which the messages are received.
+    ///
+    /// Panics if one of the message types already has a subscriber, since this is a flow registration bug.
+    pub fn subscribe(&self, msg_types: Vec<KaspadMessagePayloadType>) -> IncomingRoute {
+        let (sender, receiver) = mpsc_channel(INCOMING_ROUTE_CAPACITY);
+        let mut routing_map = self.routing_map.write();
+        for msg_type in msg_types {
+            if routing_map.insert(msg_type, sender.clone()).is_some() {
+                panic!("message type {:?} is already subscribed to", msg_type);
+            }
+        }
+        receiver
+    }
+
+    /// Starts routing incoming messages. Flows are expected to have subscribed beforehand.
+    pub fn start(&self) {
+        if let Some(signal) = self.mutable_state.lock().start_signal.take() {
+            let _ = signal.send(());
+        }
+    }
+
+    /// Sends a message to the peer
+    pub async fn enqueue(&self, msg: KaspadMessage) -> Result<(), ProtocolError> {
+        let outgoing_route = self.outgoing_route.lock().clone().ok_or(ProtocolError::ConnectionClosed)?;
+        outgoing_route.send(msg).await.map_err(|_| ProtocolError::ConnectionClosed)
+    }
+
+    fn route_to_flow(&self, msg: KaspadMessage) -> Result<(), ProtocolError> {
+        let msg_type: KaspadMessagePayloadType = match msg.payload.as_ref() {
+            Some(payload) => payload.into(),
+            None => {
+                // Payloads unknown to this node are decoded as empty
+                trace!("P2P, ignoring a message with an unknown payload from peer {}", self);
+                return Ok(());
+            }
+        };
+        match self.routing_map.read().get(&msg_type) {
+            Some(sender) => match sender.try_send(msg) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(_)) => Err(ProtocolError::IncomingRouteCapacityReached(msg_type, self.to_string())),
+                Err(TrySendError::Closed(_)) => Err(ProtocolError::ConnectionClosed),
+            },
+            None => Err(ProtocolError::NoRouteForMessageType(msg_type)),
+        }
+    }
+
+    /// Closes the router. Only the first call has any effect.
+    pub async fn close(self: &Arc<Self>) {
+        let shutdown_signal = {
+            let mut state = self.mutable_state.lock();
+            state.start_signal.take();
+            state.shutdown_signal.take()
+        };
+        if let Some(signal) = shutdown_signal {
+            let _ = signal.send(());
+
+            // Dropping the incoming routes lets the subscribed flows exit
+            self.routing_map.write().clear();
+
+            // Dropping the outgoing route ends the stream, letting the peer know the connection is closed
+            self.outgoing_route.lock().take();
+
+            // The hub might already be gone if the node is shutting down
+            let _ = self.hub_sender.send(HubEvent::PeerClosing(self.clone())).await;
+        }
+    }
+}
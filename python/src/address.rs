@@ -0,0 +1,59 @@
+use crate::errors::to_py_err;
+use addresses::{Address, Prefix};
+use pyo3::prelude::*;
+
+/// A Kaspa address exposed to Python as `kaspa.Address`
+#[pyclass(name = "Address")]
+#[derive(Clone)]
+pub struct PyAddress {
+    pub(crate) inner: Address,
+}
+
+#[pymethods]
+impl PyAddress {
+    /// Parses a `prefix:payload` encoded address string
+    #[new]
+    fn new(address: String) -> PyResult<Self> {
+        Ok(Self { inner: address.try_into().map_err(to_py_err)? })
+    }
+
+    /// Builds an address from its raw parts. `prefix` is one of `kaspa`, `kaspatest` or `kaspadev`
+    #[staticmethod]
+    fn from_parts(prefix: &str, version: u8, payload: Vec<u8>) -> PyResult<Self> {
+        let prefix: Prefix = prefix.try_into().map_err(to_py_err)?;
+        Ok(Self { inner: Address { prefix, version, payload } })
+    }
+
+    #[getter]
+    fn prefix(&self) -> String {
+        self.inner.prefix.to_string()
+    }
+
+    #[getter]
+    fn version(&self) -> u8 {
+        self.inner.version
+    }
+
+    #[getter]
+    fn payload(&self) -> Vec<u8> {
+        self.inner.payload.clone()
+    }
+
+    fn __str__(&self) -> String {
+        (&self.inner).into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Address('{}')", String::from(&self.inner))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl From<Address> for PyAddress {
+    fn from(inner: Address) -> Self {
+        Self { inner }
+    }
+}
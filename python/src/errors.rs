@@ -0,0 +1,6 @@
+use pyo3::{exceptions::PyValueError, PyErr};
+
+/// Converts any displayable error into a Python `ValueError`
+pub(crate) fn to_py_err<E: std::fmt::Display>(err: E) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
@@ -0,0 +1,63 @@
+//! Python bindings for the Kaspa RPC client and transaction primitives.
+//!
+//! The module is built as a native extension named `kaspa` (see `[lib]` in Cargo.toml)
+//! and can be packaged with `maturin build`.
+
+use pyo3::prelude::*;
+
+mod address;
+mod errors;
+mod rpc;
+mod tx;
+
+pub use address::PyAddress;
+pub use rpc::PyRpcClient;
+pub use tx::{PyTransaction, PyTransactionInput, PyTransactionOutput, PyUtxoEntry};
+
+#[pymodule]
+fn kaspa(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyAddress>()?;
+    m.add_class::<PyTransactionInput>()?;
+    m.add_class::<PyTransactionOutput>()?;
+    m.add_class::<PyUtxoEntry>()?;
+    m.add_class::<PyTransaction>()?;
+    m.add_class::<PyRpcClient>()?;
+    m.add_function(wrap_pyfunction!(tx::pay_to_address_script, m)?)?;
+    m.add_function(wrap_pyfunction!(tx::sign_transaction, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::exceptions::PyValueError;
+
+    #[test]
+    fn test_pay_to_address_script() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "kaspa").unwrap();
+            kaspa(py, module).unwrap();
+            let address_class = module.getattr("Address").unwrap();
+            let pay_to_address_script = module.getattr("pay_to_address_script").unwrap();
+
+            let address = address_class.call1(("kaspa:qp0l70zd5x85ttwd6jv7g3s3a8llzj96d8dncn4zmhv4tlzx5k2jyqh70xmfj",)).unwrap();
+            let (version, script): (u16, Vec<u8>) = pay_to_address_script.call1((address,)).unwrap().extract().unwrap();
+            assert_eq!((version, script.len(), script[0], script[33]), (0, 34, 0x20, 0xac));
+            let output = module.getattr("TransactionOutput").unwrap().call_method1("to_address", (1000, address)).unwrap();
+            assert_eq!(output.getattr("script").unwrap().extract::<Vec<u8>>().unwrap(), script);
+
+            // Only schnorr pay-to-pubkey addresses are supported
+            for (version, payload) in [(8u8, vec![7u8; 32]), (0, vec![7; 33])] {
+                let payload_len = payload.len();
+                let address = address_class.call_method1("from_parts", ("kaspa", version, payload)).unwrap();
+                let err = pay_to_address_script.call1((address,)).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py));
+                assert_eq!(
+                    err.value(py).to_string(),
+                    format!("address of version {0} with a {1} bytes payload is not supported", version, payload_len)
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{address::PyAddress, errors::to_py_err, tx::PyTransaction};
+use consensus_core::tx::Transaction;
+use pyo3::{prelude::*, types::PyDict};
+use rpc_core::{api::rpc::RpcApi, RpcBlock, RpcHash};
+use rpc_grpc::client::GrpcClient;
+use std::str::FromStr;
+use tokio::runtime::Runtime;
+
+/// A blocking gRPC client exposed to Python as `kaspa.RpcClient`.
+///
+/// Each instance owns a dedicated tokio runtime on which all async RPC calls are driven.
+#[pyclass(name = "RpcClient")]
+pub struct PyRpcClient {
+    runtime: Runtime,
+    client: Option<GrpcClient>,
+}
+
+impl PyRpcClient {
+    fn client(&self) -> PyResult<&GrpcClient> {
+        self.client.as_ref().ok_or_else(|| to_py_err("RPC client is disconnected"))
+    }
+}
+
+#[pymethods]
+impl PyRpcClient {
+    /// Connects to a kaspad gRPC endpoint, e.g. `grpc://127.0.0.1:16110`
+    #[new]
+    fn new(py: Python<'_>, address: String) -> PyResult<Self> {
+        let runtime = Runtime::new().map_err(to_py_err)?;
+        let client = py.allow_threads(|| {
+            runtime.block_on(async {
+                let client = GrpcClient::connect(address).await.map_err(to_py_err)?;
+                client.start().await;
+                Ok::<_, PyErr>(client)
+            })
+        })?;
+        Ok(Self { runtime, client: Some(client) })
+    }
+
+    /// Returns general information about the node as a dict
+    fn get_info<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let client = self.client()?;
+        let info = py.allow_threads(|| self.runtime.block_on(client.get_info())).map_err(to_py_err)?;
+        let dict = PyDict::new(py);
+        dict.set_item("p2p_id", info.p2p_id)?;
+        dict.set_item("mempool_size", info.mempool_size)?;
+        dict.set_item("server_version", info.server_version)?;
+        dict.set_item("is_utxo_indexed", info.is_utxo_indexed)?;
+        dict.set_item("is_synced", info.is_synced)?;
+        Ok(dict)
+    }
+
+    /// Returns the block identified by the hex-encoded `hash` as a dict
+    #[args(include_transactions = "true")]
+    fn get_block<'py>(&self, py: Python<'py>, hash: &str, include_transactions: bool) -> PyResult<&'py PyDict> {
+        let client = self.client()?;
+        let hash = RpcHash::from_str(hash).map_err(to_py_err)?;
+        let response = py.allow_threads(|| self.runtime.block_on(client.get_block(hash, include_transactions))).map_err(to_py_err)?;
+        block_to_dict(py, &response.block)
+    }
+
+    /// Requests a block template paying to `pay_address`, returned as a dict
+    #[args(extra_data = "Vec::new()")]
+    fn get_block_template<'py>(&self, py: Python<'py>, pay_address: &PyAddress, extra_data: Vec<u8>) -> PyResult<&'py PyDict> {
+        let client = self.client()?;
+        let pay_address = pay_address.inner.clone();
+        let response =
+            py.allow_threads(|| self.runtime.block_on(client.get_block_template(pay_address, extra_data))).map_err(to_py_err)?;
+        let dict = block_to_dict(py, &response.block)?;
+        dict.set_item("is_synced", response.is_synced)?;
+        Ok(dict)
+    }
+
+    /// Disconnects from the node. Any further call raises a `ValueError`
+    fn close(&mut self, py: Python<'_>) -> PyResult<()> {
+        if let Some(mut client) = self.client.take() {
+            py.allow_threads(|| {
+                self.runtime.block_on(async {
+                    client.stop().await.map_err(to_py_err)?;
+                    client.shutdown().await.map_err(to_py_err)
+                })
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn block_to_dict<'py>(py: Python<'py>, block: &RpcBlock) -> PyResult<&'py PyDict> {
+    let dict = PyDict::new(py);
+    let header = &block.header;
+    dict.set_item("hash", header.hash.to_string())?;
+    dict.set_item("version", header.version)?;
+    dict.set_item("timestamp", header.timestamp.as_millis())?;
+    dict.set_item("bits", header.bits)?;
+    dict.set_item("nonce", header.nonce)?;
+    dict.set_item("daa_score", header.daa_score)?;
+    dict.set_item("blue_score", header.blue_score)?;
+    dict.set_item("parents", header.direct_parents().iter().map(|h| h.to_string()).collect::<Vec<_>>())?;
+    let transactions = block
+        .transactions
+        .iter()
+        .map(|tx| Transaction::try_from(tx).map(|inner| PyTransaction { inner }))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(to_py_err)?;
+    dict.set_item("transactions", transactions.into_py(py))?;
+    Ok(dict)
+}
@@ -0,0 +1,227 @@
+use crate::{address::PyAddress, errors::to_py_err};
+use consensus_core::{
+    sign::sign,
+    subnets::SubnetworkId,
+    tx::{
+        MutableTransaction, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput,
+        UtxoEntry,
+    },
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::str::FromStr;
+
+/// Script opcodes used to build a pay-to-pubkey script
+const OP_DATA_32: u8 = 0x20;
+const OP_CHECK_SIG: u8 = 0xac;
+
+/// A transaction input exposed to Python as `kaspa.TransactionInput`
+#[pyclass(name = "TransactionInput")]
+#[derive(Clone)]
+pub struct PyTransactionInput {
+    pub(crate) inner: TransactionInput,
+}
+
+#[pymethods]
+impl PyTransactionInput {
+    #[new]
+    #[args(signature_script = "Vec::new()", sequence = "0", sig_op_count = "1")]
+    fn new(transaction_id: &str, index: u32, signature_script: Vec<u8>, sequence: u64, sig_op_count: u8) -> PyResult<Self> {
+        let transaction_id = TransactionId::from_str(transaction_id).map_err(to_py_err)?;
+        let previous_outpoint = TransactionOutpoint::new(transaction_id, index);
+        Ok(Self { inner: TransactionInput::new(previous_outpoint, signature_script, sequence, sig_op_count) })
+    }
+
+    #[getter]
+    fn transaction_id(&self) -> String {
+        self.inner.previous_outpoint.transaction_id.to_string()
+    }
+
+    #[getter]
+    fn index(&self) -> u32 {
+        self.inner.previous_outpoint.index
+    }
+
+    #[getter]
+    fn signature_script(&self) -> Vec<u8> {
+        self.inner.signature_script.clone()
+    }
+
+    #[getter]
+    fn sequence(&self) -> u64 {
+        self.inner.sequence
+    }
+
+    #[getter]
+    fn sig_op_count(&self) -> u8 {
+        self.inner.sig_op_count
+    }
+}
+
+/// A transaction output exposed to Python as `kaspa.TransactionOutput`
+#[pyclass(name = "TransactionOutput")]
+#[derive(Clone)]
+pub struct PyTransactionOutput {
+    pub(crate) inner: TransactionOutput,
+}
+
+#[pymethods]
+impl PyTransactionOutput {
+    #[new]
+    fn new(value: u64, script_version: u16, script: Vec<u8>) -> Self {
+        Self { inner: TransactionOutput::new(value, ScriptPublicKey::from_vec(script_version, script)) }
+    }
+
+    /// Builds an output paying `value` sompi to `address`
+    #[staticmethod]
+    fn to_address(value: u64, address: &PyAddress) -> PyResult<Self> {
+        let (version, script) = pay_to_address_script(address)?;
+        Ok(Self::new(value, version, script))
+    }
+
+    #[getter]
+    fn value(&self) -> u64 {
+        self.inner.value
+    }
+
+    #[getter]
+    fn script_version(&self) -> u16 {
+        self.inner.script_public_key.version()
+    }
+
+    #[getter]
+    fn script(&self) -> Vec<u8> {
+        self.inner.script_public_key.script().to_vec()
+    }
+}
+
+/// A UTXO entry exposed to Python as `kaspa.UtxoEntry`. Entries are required for signing
+#[pyclass(name = "UtxoEntry")]
+#[derive(Clone)]
+pub struct PyUtxoEntry {
+    pub(crate) inner: UtxoEntry,
+}
+
+#[pymethods]
+impl PyUtxoEntry {
+    #[new]
+    #[args(is_coinbase = "false")]
+    fn new(amount: u64, script_version: u16, script: Vec<u8>, block_daa_score: u64, is_coinbase: bool) -> Self {
+        Self { inner: UtxoEntry::new(amount, ScriptPublicKey::from_vec(script_version, script), block_daa_score, is_coinbase) }
+    }
+
+    #[getter]
+    fn amount(&self) -> u64 {
+        self.inner.amount
+    }
+
+    #[getter]
+    fn block_daa_score(&self) -> u64 {
+        self.inner.block_daa_score
+    }
+
+    #[getter]
+    fn is_coinbase(&self) -> bool {
+        self.inner.is_coinbase
+    }
+}
+
+/// A transaction exposed to Python as `kaspa.Transaction`
+#[pyclass(name = "Transaction")]
+#[derive(Clone)]
+pub struct PyTransaction {
+    pub(crate) inner: Transaction,
+}
+
+#[pymethods]
+impl PyTransaction {
+    #[new]
+    #[args(version = "0", lock_time = "0", subnetwork_id = "None", gas = "0", payload = "Vec::new()")]
+    fn new(
+        inputs: Vec<PyTransactionInput>,
+        outputs: Vec<PyTransactionOutput>,
+        version: u16,
+        lock_time: u64,
+        subnetwork_id: Option<&str>,
+        gas: u64,
+        payload: Vec<u8>,
+    ) -> PyResult<Self> {
+        let subnetwork_id = match subnetwork_id {
+            Some(id) => SubnetworkId::from_str(id).map_err(to_py_err)?,
+            None => SubnetworkId::default(),
+        };
+        Ok(Self {
+            inner: Transaction::new(
+                version,
+                inputs.into_iter().map(|i| i.inner).collect(),
+                outputs.into_iter().map(|o| o.inner).collect(),
+                lock_time,
+                subnetwork_id,
+                gas,
+                payload,
+            ),
+        })
+    }
+
+    /// The transaction ID as a hex string
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id().to_string()
+    }
+
+    #[getter]
+    fn inputs(&self) -> Vec<PyTransactionInput> {
+        self.inner.inputs.iter().cloned().map(|inner| PyTransactionInput { inner }).collect()
+    }
+
+    #[getter]
+    fn outputs(&self) -> Vec<PyTransactionOutput> {
+        self.inner.outputs.iter().cloned().map(|inner| PyTransactionOutput { inner }).collect()
+    }
+
+    #[getter]
+    fn lock_time(&self) -> u64 {
+        self.inner.lock_time
+    }
+
+    #[getter]
+    fn payload(&self) -> Vec<u8> {
+        self.inner.payload.clone()
+    }
+
+    fn is_coinbase(&self) -> bool {
+        self.inner.is_coinbase()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Transaction('{}')", self.inner.id())
+    }
+}
+
+/// Returns the `(version, script)` pair of the script public key paying to `address`.
+/// Only schnorr pay-to-pubkey addresses (version 0) are currently supported
+#[pyfunction]
+pub(crate) fn pay_to_address_script(address: &PyAddress) -> PyResult<(u16, Vec<u8>)> {
+    let address = &address.inner;
+    if address.version != 0 || address.payload.len() != 32 {
+        return Err(PyValueError::new_err(format!(
+            "address of version {} with a {} bytes payload is not supported",
+            address.version,
+            address.payload.len()
+        )));
+    }
+    let script = std::iter::once(OP_DATA_32).chain(address.payload.iter().copied()).chain(std::iter::once(OP_CHECK_SIG)).collect();
+    Ok((0, script))
+}
+
+/// Signs all inputs of `tx` with the schnorr private key `private_key`, given the UTXO
+/// entries spent by each input (in input order). Returns the signed transaction
+#[pyfunction]
+pub(crate) fn sign_transaction(tx: &PyTransaction, entries: Vec<PyUtxoEntry>, private_key: Vec<u8>) -> PyResult<PyTransaction> {
+    if tx.inner.inputs.len() != entries.len() {
+        return Err(PyValueError::new_err(format!("expected {} utxo entries but got {}", tx.inner.inputs.len(), entries.len())));
+    }
+    let private_key: [u8; 32] =
+        private_key.try_into().map_err(|_| PyValueError::new_err("private key must be exactly 32 bytes long"))?;
+    let mutable_tx = MutableTransaction::with_entries(tx.inner.clone(), entries.into_iter().map(|e| e.inner).collect());
+    Ok(PyTransaction { inner: sign(mutable_tx, private_key).tx })
+}
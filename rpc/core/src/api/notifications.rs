@@ -1,5 +1,6 @@
 use crate::model::message::*;
 use crate::stubs::*;
+use crate::RpcAddress;
 use async_std::channel::{Receiver, Sender};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
@@ -9,21 +10,27 @@ use std::sync::Arc;
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub enum NotificationType {
     BlockAdded,
-    VirtualSelectedParentChainChanged,
+    /// Scoped by whether the accepted transaction ids are included
+    VirtualSelectedParentChainChanged(bool),
     FinalityConflicts,
     FinalityConflictResolved,
-    UtxosChanged(Vec<RpcUtxoAddress>),
+    /// Scoped by addresses, none standing for all addresses
+    UtxosChanged(Vec<RpcAddress>),
     VirtualSelectedParentBlueScoreChanged,
     VirtualDaaScoreChanged,
     PruningPointUTXOSetOverride,
     NewBlockTemplate,
+    /// Scoped by transaction ids and addresses, none standing for all transactions
+    TransactionAccepted(RpcTransactionScope),
+    /// Scoped by transaction ids and addresses, none standing for all transactions
+    TransactionReverted(RpcTransactionScope),
 }
 
 impl From<&Notification> for NotificationType {
     fn from(item: &Notification) -> Self {
         match item {
             Notification::BlockAdded(_) => NotificationType::BlockAdded,
-            Notification::VirtualSelectedParentChainChanged(_) => NotificationType::VirtualSelectedParentChainChanged,
+            Notification::VirtualSelectedParentChainChanged(_) => NotificationType::VirtualSelectedParentChainChanged(true),
             Notification::FinalityConflict(_) => NotificationType::FinalityConflicts,
             Notification::FinalityConflictResolved(_) => NotificationType::FinalityConflictResolved,
             Notification::UtxosChanged(_) => NotificationType::UtxosChanged(vec![]),
@@ -31,6 +38,8 @@ impl From<&Notification> for NotificationType {
             Notification::VirtualDaaScoreChanged(_) => NotificationType::VirtualDaaScoreChanged,
             Notification::PruningPointUTXOSetOverride(_) => NotificationType::PruningPointUTXOSetOverride,
             Notification::NewBlockTemplate(_) => NotificationType::NewBlockTemplate,
+            Notification::TransactionAccepted(_) => NotificationType::TransactionAccepted(RpcTransactionScope::default()),
+            Notification::TransactionReverted(_) => NotificationType::TransactionReverted(RpcTransactionScope::default()),
         }
     }
 }
@@ -47,6 +56,8 @@ pub enum Notification {
     VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification),
     PruningPointUTXOSetOverride(PruningPointUTXOSetOverrideNotification),
     NewBlockTemplate(NewBlockTemplateNotification),
+    TransactionAccepted(TransactionAcceptedNotification),
+    TransactionReverted(TransactionRevertedNotification),
 }
 
 impl Display for Notification {
@@ -55,14 +66,34 @@ impl Display for Notification {
             Notification::BlockAdded(ref notification) => {
                 write!(f, "BlockAdded notification with hash {}", notification.block.header.hash)
             }
+            Notification::VirtualSelectedParentChainChanged(ref notification) => {
+                write!(
+                    f,
+                    "VirtualSelectedParentChainChanged notification with {} removed and {} added chain blocks",
+                    notification.removed_chain_block_hashes.len(),
+                    notification.added_chain_block_hashes.len()
+                )
+            }
+            Notification::UtxosChanged(ref notification) => {
+                write!(
+                    f,
+                    "UtxosChanged notification with {} added and {} removed UTXOs",
+                    notification.added.len(),
+                    notification.removed.len()
+                )
+            }
             Notification::NewBlockTemplate(_) => {
                 write!(f, "NewBlockTemplate notification")
             }
+            Notification::TransactionAccepted(ref notification) => {
+                write!(f, "TransactionAccepted notification with {} transactions", notification.accepted.len())
+            }
+            Notification::TransactionReverted(ref notification) => {
+                write!(f, "TransactionReverted notification with {} transactions", notification.reverted.len())
+            }
             _ => write!(f, "Notification type not implemented yet"),
-            // Notification::VirtualSelectedParentChainChanged(_) => todo!(),
             // Notification::FinalityConflict(_) => todo!(),
             // Notification::FinalityConflictResolved(_) => todo!(),
-            // Notification::UtxosChanged(_) => todo!(),
             // Notification::VirtualSelectedParentBlueScoreChanged(_) => todo!(),
             // Notification::VirtualDaaScoreChanged(_) => todo!(),
             // Notification::PruningPointUTXOSetOverride(_) => todo!(),
@@ -1,7 +1,8 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
 #[repr(u32)]
 pub enum RpcApiOps {
     Ping = 0,
@@ -17,7 +18,7 @@ pub enum RpcApiOps {
     SubmitTransaction,
     GetBlock,
     GetSubnetwork,
-    GetVirtualSelectedParentChainFromBlock,
+    GetVirtualChainFromBlock,
     GetBlocks,
     GetBlockCount,
     GetBlockDagInfo,
@@ -34,10 +35,24 @@ pub enum RpcApiOps {
     EstimateNetworkHashesPerSecond,
     GetMempoolEntriesByAddresses,
     GetCoinSupply,
+    GetChainThroughputStats,
+    RevalidateBlock,
+    GetCoinbasePreview,
+    GetMempoolFeeRateHistogram,
+    GetTransactionAcceptance,
+    GetBlocksWithAcceptance,
+    GetMetrics,
+    GetPayload,
+    GetTransactionById,
+    PinBlocks,
+    UnpinBlocks,
+    DumpBlockRegion,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
     NotifyNewBlockTemplate,
+    /// Subscription command of any notification type, for transports multiplexing notifications over a single socket
+    Subscribe,
 
     // Server to client notification
     Notification,
@@ -53,6 +68,8 @@ impl From<RpcApiOps> for u32 {
 pub enum SubscribeCommand {
     Start = 0,
     Stop = 1,
+    /// Replaces the scope of an active subscription, so that no notification is missed as with a stop/start sequence
+    ModifyScope = 2,
 }
 
 impl From<SubscribeCommand> for i32 {
@@ -64,10 +81,10 @@ impl From<SubscribeCommand> for i32 {
 impl From<i32> for SubscribeCommand {
     // We make this conversion infallible by falling back to Start from any unexpected value.
     fn from(item: i32) -> Self {
-        if item == 1 {
-            SubscribeCommand::Stop
-        } else {
-            SubscribeCommand::Start
+        match item {
+            1 => SubscribeCommand::Stop,
+            2 => SubscribeCommand::ModifyScope,
+            _ => SubscribeCommand::Start,
         }
     }
 }
@@ -13,12 +13,16 @@ use crate::{
     NotificationType, RpcResult,
 };
 use async_trait::async_trait;
+use std::sync::Arc;
 
 /// Client RPC Api
 ///
 /// The [`RpcApi`] trait defines RPC calls taking a request message as unique parameter.
 ///
 /// For each RPC call a matching readily implemented function taking detailed parameters is also provided.
+///
+/// The node implements this trait once, in [`crate::server::service::RpcCoreService`]. Transports serve any
+/// [`DynRpcService`], so that remote and in-process callers share the same implementation and conversions.
 #[async_trait]
 pub trait RpcApi: Sync + Send {
     // async fn ping(
@@ -44,39 +48,56 @@ pub trait RpcApi: Sync + Send {
     }
     async fn get_block_template_call(&self, request: GetBlockTemplateRequest) -> RpcResult<GetBlockTemplateResponse>;
 
-    // async fn get_peer_addresses(
-    //     &self
-    // ) -> RpcResult<GetPeerAddressesResponse>;
+    /// Requests the peer addresses known to the node along with the banned IPs.
+    async fn get_peer_addresses(&self) -> RpcResult<GetPeerAddressesResponse> {
+        self.get_peer_addresses_call(GetPeerAddressesRequest {}).await
+    }
+    async fn get_peer_addresses_call(&self, request: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse>;
 
     // async fn get_selected_tip_hash(
     //     &self
     // ) -> RpcResult<GetSelectedTipHashResponse>;
 
-    // async fn get_mempool_entry(
-    //     &self,
-    //     request:  GetMempoolEntryRequest
-    // ) -> RpcResult<GetMempoolEntriesResponse>;
-
-    // async fn get_mempool_entries(
-    //     &self,
-    //     include_orphan_pool: bool,
-    //     filter_transaction_pool: bool
-    // ) -> RpcResult<GetMempoolEntriesResponse>;
+    /// Requests a transaction of the mempool, see [`GetMempoolEntryRequest`] for which pools are searched.
+    async fn get_mempool_entry(
+        &self,
+        transaction_id: RpcTransactionId,
+        include_orphan_pool: bool,
+        filter_transaction_pool: bool,
+    ) -> RpcResult<GetMempoolEntryResponse> {
+        self.get_mempool_entry_call(GetMempoolEntryRequest::new(transaction_id, include_orphan_pool, filter_transaction_pool)).await
+    }
+    async fn get_mempool_entry_call(&self, request: GetMempoolEntryRequest) -> RpcResult<GetMempoolEntryResponse>;
+
+    /// Requests the transactions of the mempool, optionally only the ones spending from or paying to any of `addresses`.
+    /// See [`GetMempoolEntriesRequest`] for which pools are searched.
+    async fn get_mempool_entries(
+        &self,
+        include_orphan_pool: bool,
+        filter_transaction_pool: bool,
+        addresses: Vec<RpcAddress>,
+    ) -> RpcResult<GetMempoolEntriesResponse> {
+        self.get_mempool_entries_call(GetMempoolEntriesRequest::new(include_orphan_pool, filter_transaction_pool, addresses)).await
+    }
+    async fn get_mempool_entries_call(&self, request: GetMempoolEntriesRequest) -> RpcResult<GetMempoolEntriesResponse>;
 
-    // async fn get_connected_peer_info(
-    //     &self
-    // ) -> RpcResult<GetConnectedPeerInfoResponse>;
+    /// Requests information about the peers currently connected to the node.
+    async fn get_connected_peer_info(&self) -> RpcResult<GetConnectedPeerInfoResponse> {
+        self.get_connected_peer_info_call(GetConnectedPeerInfoRequest {}).await
+    }
+    async fn get_connected_peer_info_call(&self, request: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse>;
 
-    // async fn add_peer(
-    //     &self,
-    //     request: AddPeerRequest
-    // ) -> RpcResult<AddPeerResponse>;
+    /// Requests the node to connect to a peer, see [`AddPeerRequest`] for permanent peers.
+    async fn add_peer(&self, peer_address: RpcPeerAddress, is_permanent: bool) -> RpcResult<AddPeerResponse> {
+        self.add_peer_call(AddPeerRequest::new(peer_address, is_permanent)).await
+    }
+    async fn add_peer_call(&self, request: AddPeerRequest) -> RpcResult<AddPeerResponse>;
 
-    // async fn submit_transaction(
-    //     &self,
-    //     transaction: RpcTransaction,
-    //     allow_orphan: bool,
-    // ) -> RpcResult<SubmitTransactionResponse>;
+    /// Submits a transaction to the mempool.
+    async fn submit_transaction(&self, transaction: RpcTransaction, allow_orphan: bool) -> RpcResult<SubmitTransactionResponse> {
+        self.submit_transaction_call(SubmitTransactionRequest::new(transaction, allow_orphan)).await
+    }
+    async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse>;
 
     /// Requests information about a specific block.
     async fn get_block(&self, hash: RpcHash, include_transactions: bool) -> RpcResult<GetBlockResponse> {
@@ -89,25 +110,74 @@ pub trait RpcApi: Sync + Send {
     //     request: GetSubnetworkRequest
     // ) -> RpcResult<GetSubnetworkResponse>;
 
-    // async fn get_virtual_selected_parent_chain_from_block(
-    //     &self,
-    //     request: GetVirtualSelectedParentChainFromBlockRequest
-    // ) -> RpcResult<GetVirtualSelectedParentChainFromBlockResponse>;
-
-    // async fn get_blocks(
-    //     &self,
-    //     request: GetBlocksRequest
-    // ) -> RpcResult<GetBlocksResponse>;
+    /// Requests the virtual selected parent chain from some start block, in batches.
+    /// See [`GetVirtualChainFromBlockRequest`] for how to request the next batch.
+    async fn get_virtual_chain_from_block(
+        &self,
+        start_hash: RpcHash,
+        include_accepted_transaction_ids: bool,
+    ) -> RpcResult<GetVirtualChainFromBlockResponse> {
+        self.get_virtual_chain_from_block_call(GetVirtualChainFromBlockRequest::new(start_hash, include_accepted_transaction_ids))
+            .await
+    }
+    async fn get_virtual_chain_from_block_call(
+        &self,
+        request: GetVirtualChainFromBlockRequest,
+    ) -> RpcResult<GetVirtualChainFromBlockResponse>;
+
+    /// Requests the chain block which accepted a transaction, from the transaction index.
+    /// See [`GetTransactionAcceptanceRequest`] for transactions older than the index retention window.
+    async fn get_transaction_acceptance(&self, transaction_id: RpcTransactionId) -> RpcResult<GetTransactionAcceptanceResponse> {
+        self.get_transaction_acceptance_call(GetTransactionAcceptanceRequest::new(transaction_id)).await
+    }
+    async fn get_transaction_acceptance_call(
+        &self,
+        request: GetTransactionAcceptanceRequest,
+    ) -> RpcResult<GetTransactionAcceptanceResponse>;
+
+    /// Requests an accepted transaction along with its including and accepting blocks, from the transaction index.
+    async fn get_transaction_by_id(&self, transaction_id: RpcTransactionId) -> RpcResult<GetTransactionByIdResponse> {
+        self.get_transaction_by_id_call(GetTransactionByIdRequest::new(transaction_id)).await
+    }
+    async fn get_transaction_by_id_call(&self, request: GetTransactionByIdRequest) -> RpcResult<GetTransactionByIdResponse>;
+
+    /// Requests the blocks of a virtual chain range along with their GHOSTDAG data and accepted transactions, in batches.
+    /// See [`GetBlocksWithAcceptanceRequest`] for how to request the next batch.
+    async fn get_blocks_with_acceptance(
+        &self,
+        low_hash: RpcHash,
+        high_hash: Option<RpcHash>,
+    ) -> RpcResult<GetBlocksWithAcceptanceResponse> {
+        self.get_blocks_with_acceptance_call(GetBlocksWithAcceptanceRequest::new(low_hash, high_hash)).await
+    }
+    async fn get_blocks_with_acceptance_call(
+        &self,
+        request: GetBlocksWithAcceptanceRequest,
+    ) -> RpcResult<GetBlocksWithAcceptanceResponse>;
+
+    /// Requests the blocks above `low_hash` up to the virtual selected parent, in batches.
+    /// See [`GetBlocksRequest`] for how to request the next batch.
+    async fn get_blocks(
+        &self,
+        low_hash: Option<RpcHash>,
+        include_blocks: bool,
+        include_transactions: bool,
+    ) -> RpcResult<GetBlocksResponse> {
+        self.get_blocks_call(GetBlocksRequest::new(low_hash, include_blocks, include_transactions)).await
+    }
+    async fn get_blocks_call(&self, request: GetBlocksRequest) -> RpcResult<GetBlocksResponse>;
 
     // async fn get_block_count(
     //     &self,
     //     request: GetBlockCountRequest
     // ) -> RpcResult<GetBlockCountResponse>;
 
-    // async fn get_block_dag_info(
-    //     &self,
-    //     request: GetBlockDagInfoRequest
-    // ) -> RpcResult<GetBlockDagInfoResponse>;
+    /// Requests general information about the current state of the DAG of the node, such as the block
+    /// counts, the tips and the virtual difficulty.
+    async fn get_block_dag_info(&self) -> RpcResult<GetBlockDagInfoResponse> {
+        self.get_block_dag_info_call(GetBlockDagInfoRequest {}).await
+    }
+    async fn get_block_dag_info_call(&self, request: GetBlockDagInfoRequest) -> RpcResult<GetBlockDagInfoResponse>;
 
     // async fn resolve_finality_conflict(
     //     &self,
@@ -123,15 +193,17 @@ pub trait RpcApi: Sync + Send {
     //     request: GetHeadersRequest
     // ) -> RpcResult<GetHeadersResponse>;
 
-    // async fn get_utxos_by_address(
-    //     &self,
-    //     addresses : Vec<Address>
-    // ) -> RpcResult<GetUtxosByAddressesResponse>;
+    /// Requests the current UTXOs paying to any of `addresses`, from the UTXO index.
+    async fn get_utxos_by_addresses(&self, addresses: Vec<RpcAddress>) -> RpcResult<GetUtxosByAddressesResponse> {
+        self.get_utxos_by_addresses_call(GetUtxosByAddressesRequest::new(addresses)).await
+    }
+    async fn get_utxos_by_addresses_call(&self, request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse>;
 
-    // async fn get_balance_by_address(
-    //     &self,
-    //     address : Address
-    // ) -> RpcResult<u64>;
+    /// Requests the total amount of the current UTXOs paying to `address`, from the UTXO index.
+    async fn get_balance_by_address(&self, address: RpcAddress) -> RpcResult<GetBalanceByAddressResponse> {
+        self.get_balance_by_address_call(GetBalanceByAddressRequest::new(address)).await
+    }
+    async fn get_balance_by_address_call(&self, request: GetBalanceByAddressRequest) -> RpcResult<GetBalanceByAddressResponse>;
 
     // async fn get_balances_by_addresses(
     //     &self,
@@ -142,15 +214,40 @@ pub trait RpcApi: Sync + Send {
     //     &self
     // ) -> RpcResult<u64>;
 
-    // async fn ban(
-    //     &self,
-    //     request: BanRequest
-    // ) -> RpcResult<BanResponse>;
+    /// Bans an IP, disconnecting the peers connected from it.
+    async fn ban(&self, ip: RpcIpAddress) -> RpcResult<BanResponse> {
+        self.ban_call(BanRequest::new(ip)).await
+    }
+    async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse>;
 
-    // async fn unban(
-    //     &self,
-    //     request: UnbanRequest
-    // ) -> RpcResult<UnbanResponse>;
+    /// Lifts the ban of an IP.
+    async fn unban(&self, ip: RpcIpAddress) -> RpcResult<UnbanResponse> {
+        self.unban_call(UnbanRequest::new(ip)).await
+    }
+    async fn unban_call(&self, request: UnbanRequest) -> RpcResult<UnbanResponse>;
+
+    /// Pins blocks, so that the node keeps them along with their data when pruning.
+    async fn pin_blocks(&self, hashes: Vec<RpcHash>) -> RpcResult<PinBlocksResponse> {
+        self.pin_blocks_call(PinBlocksRequest::new(hashes)).await
+    }
+    async fn pin_blocks_call(&self, request: PinBlocksRequest) -> RpcResult<PinBlocksResponse>;
+
+    /// Lifts the pins of blocks.
+    async fn unpin_blocks(&self, hashes: Vec<RpcHash>) -> RpcResult<UnpinBlocksResponse> {
+        self.unpin_blocks_call(UnpinBlocksRequest::new(hashes)).await
+    }
+    async fn unpin_blocks_call(&self, request: UnpinBlocksRequest) -> RpcResult<UnpinBlocksResponse>;
+
+    /// Exports the blocks between `low_hash` and `high_hash` as JSON.
+    async fn dump_block_region(
+        &self,
+        low_hash: RpcHash,
+        high_hash: RpcHash,
+        include_transactions: bool,
+    ) -> RpcResult<DumpBlockRegionResponse> {
+        self.dump_block_region_call(DumpBlockRegionRequest::new(low_hash, high_hash, include_transactions)).await
+    }
+    async fn dump_block_region_call(&self, request: DumpBlockRegionRequest) -> RpcResult<DumpBlockRegionResponse>;
 
     async fn get_info_call(&self, request: GetInfoRequest) -> RpcResult<GetInfoResponse>;
     async fn get_info(&self) -> RpcResult<GetInfoResponse> {
@@ -171,6 +268,42 @@ pub trait RpcApi: Sync + Send {
     //     &self
     // ) -> RpcResult<GetCoinSupplyResponse>;
 
+    async fn get_chain_throughput_stats_call(
+        &self,
+        request: GetChainThroughputStatsRequest,
+    ) -> RpcResult<GetChainThroughputStatsResponse>;
+    async fn get_chain_throughput_stats(&self, window_size: u64) -> RpcResult<GetChainThroughputStatsResponse> {
+        self.get_chain_throughput_stats_call(GetChainThroughputStatsRequest::new(window_size)).await
+    }
+
+    async fn revalidate_block_call(&self, request: RevalidateBlockRequest) -> RpcResult<RevalidateBlockResponse>;
+    async fn revalidate_block(&self, hash: RpcHash) -> RpcResult<RevalidateBlockResponse> {
+        self.revalidate_block_call(RevalidateBlockRequest::new(hash)).await
+    }
+
+    async fn get_coinbase_preview_call(&self, request: GetCoinbasePreviewRequest) -> RpcResult<GetCoinbasePreviewResponse>;
+    async fn get_coinbase_preview(&self, pay_address: RpcAddress, extra_data: RpcExtraData) -> RpcResult<GetCoinbasePreviewResponse> {
+        self.get_coinbase_preview_call(GetCoinbasePreviewRequest::new(pay_address, extra_data)).await
+    }
+
+    async fn get_mempool_fee_rate_histogram_call(
+        &self,
+        request: GetMempoolFeeRateHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeRateHistogramResponse>;
+    async fn get_mempool_fee_rate_histogram(&self) -> RpcResult<GetMempoolFeeRateHistogramResponse> {
+        self.get_mempool_fee_rate_histogram_call(GetMempoolFeeRateHistogramRequest {}).await
+    }
+
+    async fn get_metrics_call(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse>;
+    async fn get_metrics(&self, consensus_metrics: bool, perf_metrics: bool) -> RpcResult<GetMetricsResponse> {
+        self.get_metrics_call(GetMetricsRequest::new(consensus_metrics, perf_metrics)).await
+    }
+
+    async fn get_payload_call(&self, request: GetPayloadRequest) -> RpcResult<GetPayloadResponse>;
+    async fn get_payload(&self, payload_hash: RpcHash) -> RpcResult<GetPayloadResponse> {
+        self.get_payload_call(GetPayloadRequest::new(payload_hash)).await
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -187,4 +320,10 @@ pub trait RpcApi: Sync + Send {
 
     /// Stop sending notifications of some type to a listener.
     async fn stop_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()>;
+
+    /// Replace the scope of the notifications of some type sent to a listener, e.g., its UtxosChanged addresses,
+    /// without stopping them.
+    async fn modify_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()>;
 }
+
+pub type DynRpcService = Arc<dyn RpcApi>;
@@ -1,7 +1,15 @@
 use std::sync::Arc;
 
-use crate::{GetBlockTemplateResponse, RpcBlock, RpcError, RpcResult, RpcTransaction};
-use consensus_core::block::{Block, BlockTemplate, MutableBlock};
+use crate::{
+    GetBlockTemplateResponse, GetBlocksWithAcceptanceResponse, GetCoinbasePreviewResponse, GetVirtualChainFromBlockResponse,
+    RpcAcceptedTransactionIds, RpcBlock, RpcChainBlockWithAcceptance, RpcError, RpcMergesetBlockReward, RpcResult, RpcTransaction,
+    RpcTransactionOutput,
+};
+use consensus_core::{
+    block::{Block, BlockTemplate, MutableBlock},
+    chain::{ChainBlockAcceptanceData, ChainBlockWithAcceptance, ChainBlocksBatch, VirtualChainBatch},
+    coinbase::{CoinbasePreview, MergesetBlockReward},
+};
 
 // ----------------------------------------------------------------------------
 // consensus_core to rpc_core
@@ -18,6 +26,21 @@ impl From<&Block> for RpcBlock {
     }
 }
 
+/// Moves the header and transactions out of their `Arc`s when these are not shared, e.g., by the block stores
+/// caches, and clones them otherwise
+impl From<Block> for RpcBlock {
+    fn from(item: Block) -> Self {
+        Self {
+            header: Arc::try_unwrap(item.header).unwrap_or_else(|header| (*header).clone()),
+            transactions: match Arc::try_unwrap(item.transactions) {
+                Ok(transactions) => transactions.into_iter().map(RpcTransaction::from).collect(),
+                Err(transactions) => transactions.iter().map(RpcTransaction::from).collect(),
+            },
+            verbose_data: None,
+        }
+    }
+}
+
 impl From<&MutableBlock> for RpcBlock {
     fn from(item: &MutableBlock) -> Self {
         Self {
@@ -28,6 +51,16 @@ impl From<&MutableBlock> for RpcBlock {
     }
 }
 
+impl From<MutableBlock> for RpcBlock {
+    fn from(item: MutableBlock) -> Self {
+        Self {
+            header: item.header,
+            transactions: item.transactions.into_iter().map(RpcTransaction::from).collect(),
+            verbose_data: None,
+        }
+    }
+}
+
 impl From<&BlockTemplate> for GetBlockTemplateResponse {
     fn from(item: &BlockTemplate) -> Self {
         Self {
@@ -39,6 +72,82 @@ impl From<&BlockTemplate> for GetBlockTemplateResponse {
     }
 }
 
+impl From<BlockTemplate> for GetBlockTemplateResponse {
+    fn from(item: BlockTemplate) -> Self {
+        Self { block: item.block.into(), is_synced: true }
+    }
+}
+
+impl From<&MergesetBlockReward> for RpcMergesetBlockReward {
+    fn from(item: &MergesetBlockReward) -> Self {
+        Self {
+            hash: item.hash,
+            is_blue: item.is_blue,
+            subsidy: item.subsidy,
+            total_fees: item.total_fees,
+            script_public_key: item.script_public_key.clone(),
+        }
+    }
+}
+
+impl From<&CoinbasePreview> for GetCoinbasePreviewResponse {
+    fn from(item: &CoinbasePreview) -> Self {
+        Self {
+            daa_score: item.daa_score,
+            blue_score: item.blue_score,
+            subsidy: item.subsidy,
+            merged_block_rewards: item.merged_block_rewards.iter().map(RpcMergesetBlockReward::from).collect(),
+            outputs: item.outputs.iter().map(RpcTransactionOutput::from).collect(),
+        }
+    }
+}
+
+impl From<&ChainBlockAcceptanceData> for RpcAcceptedTransactionIds {
+    fn from(item: &ChainBlockAcceptanceData) -> Self {
+        Self {
+            accepting_block_hash: item.accepting_block_hash,
+            accepted_transaction_ids: item
+                .mergeset
+                .iter()
+                .flat_map(|merged| merged.accepted_transaction_ids.iter().copied())
+                .collect(),
+        }
+    }
+}
+
+impl From<&VirtualChainBatch> for GetVirtualChainFromBlockResponse {
+    fn from(item: &VirtualChainBatch) -> Self {
+        Self {
+            removed_chain_block_hashes: item.removed_chain_block_hashes.clone(),
+            added_chain_block_hashes: item.added_chain_block_hashes.clone(),
+            accepted_transaction_ids: item.accepted_transaction_ids.iter().map(RpcAcceptedTransactionIds::from).collect(),
+            has_more: item.has_more,
+        }
+    }
+}
+
+impl From<ChainBlockWithAcceptance> for RpcChainBlockWithAcceptance {
+    fn from(item: ChainBlockWithAcceptance) -> Self {
+        Self {
+            block: item.block.into(),
+            blue_score: item.blue_score,
+            blue_work: item.blue_work,
+            selected_parent_hash: item.selected_parent,
+            accepted_transaction_ids: item
+                .mergeset
+                .into_iter()
+                .flat_map(|merged| merged.accepted_transaction_ids.into_iter())
+                .collect(),
+        }
+    }
+}
+
+impl From<ChainBlocksBatch> for GetBlocksWithAcceptanceResponse {
+    fn from(item: ChainBlocksBatch) -> Self {
+        Self { blocks: item.blocks.into_iter().map(RpcChainBlockWithAcceptance::from).collect(), has_more: item.has_more }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // rpc_core to consensus_core
 // ----------------------------------------------------------------------------
@@ -57,3 +166,53 @@ impl TryFrom<&RpcBlock> for Block {
         })
     }
 }
+
+/// Moves the transactions of the block rather than cloning them
+impl TryFrom<RpcBlock> for Block {
+    type Error = RpcError;
+    fn try_from(item: RpcBlock) -> RpcResult<Self> {
+        Ok(Self {
+            header: Arc::new(item.header),
+            transactions: Arc::new(
+                item.transactions
+                    .into_iter()
+                    .map(consensus_core::tx::Transaction::try_from)
+                    .collect::<RpcResult<Vec<consensus_core::tx::Transaction>>>()?,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::{
+        header::Header,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{Transaction, TransactionInput, TransactionOutpoint, TransactionOutput},
+    };
+
+    fn block() -> Block {
+        let header =
+            Header::new(0, vec![vec![1.into()]], 2.into(), 3.into(), 4.into(), Default::default(), 0, 0, 0, 0.into(), 0, 5.into());
+        let input = TransactionInput::new(TransactionOutpoint::new(6.into(), 0), vec![1, 2, 3], 0, 1);
+        let output = TransactionOutput::new(1000, Default::default());
+        Block::new(header, vec![Transaction::new(0, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![4, 5])])
+    }
+
+    #[test]
+    fn test_owned_block_conversions() {
+        let block = block();
+        let tx_id = block.transactions[0].id();
+
+        // A shared block is cloned and left untouched
+        let shared: RpcBlock = block.clone().into();
+        assert_eq!(shared.header.hash, block.hash());
+        assert_eq!(shared.transactions[0].inputs[0].signature_script, block.transactions[0].inputs[0].signature_script);
+
+        let owned: RpcBlock = block.into();
+        let round_trip: Block = owned.try_into().unwrap();
+        assert_eq!(round_trip.transactions[0].id(), tx_id);
+        assert_eq!(round_trip.hash(), RpcBlock::from(&round_trip).header.hash);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{RpcFeeRateHistogramBucket, RpcMempoolEntry};
+use mining::{fee_estimation::FeeRateHistogramBucket, mempool::model::MempoolEntry};
+
+// ----------------------------------------------------------------------------
+// mining to rpc_core
+// ----------------------------------------------------------------------------
+
+impl From<&FeeRateHistogramBucket> for RpcFeeRateHistogramBucket {
+    fn from(item: &FeeRateHistogramBucket) -> Self {
+        Self {
+            min_fee_rate: item.min_fee_rate,
+            max_fee_rate: item.max_fee_rate,
+            transaction_count: item.transaction_count,
+            total_mass: item.total_mass,
+        }
+    }
+}
+
+impl From<&MempoolEntry> for RpcMempoolEntry {
+    fn from(item: &MempoolEntry) -> Self {
+        Self { fee: item.fee, mass: item.mass, transaction: (&item.transaction).into(), is_orphan: item.is_orphan }
+    }
+}
@@ -0,0 +1,17 @@
+use crate::RpcConsensusMetrics;
+use consensus_core::stats::DaaScoreDrift;
+
+// ----------------------------------------------------------------------------
+// consensus_core to rpc_core
+// ----------------------------------------------------------------------------
+
+impl From<&DaaScoreDrift> for RpcConsensusMetrics {
+    fn from(item: &DaaScoreDrift) -> Self {
+        Self {
+            virtual_daa_score: item.virtual_daa_score,
+            sink_timestamp: item.sink_timestamp,
+            expected_daa_score: item.expected_daa_score(),
+            daa_score_drift: item.drift(),
+        }
+    }
+}
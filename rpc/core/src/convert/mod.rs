@@ -1,3 +1,5 @@
 pub mod block;
+pub mod mempool;
+pub mod metrics;
 pub mod notification;
 pub mod tx;
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
-use crate::{notify::collector::ArcConvert, BlockAddedNotification, NewBlockTemplateNotification, Notification};
-use consensus_core::notify as consensus_notify;
+use crate::{
+    notify::collector::ArcConvert, BlockAddedNotification, NewBlockTemplateNotification, Notification, RpcAcceptedTransactionIds,
+    RpcTransactionAcceptance, RpcUtxosByAddressesEntry, TransactionAcceptedNotification, TransactionRevertedNotification,
+    UtxosChangedNotification, VirtualSelectedParentChainChangedNotification,
+};
+use consensus_core::{notify as consensus_notify, utxo::utxo_collection::UtxoCollection};
 
 // ----------------------------------------------------------------------------
 // consensus_core to rpc_core
@@ -11,7 +15,11 @@ impl From<&consensus_notify::Notification> for Notification {
     fn from(item: &consensus_notify::Notification) -> Self {
         match item {
             consensus_notify::Notification::BlockAdded(msg) => Notification::BlockAdded(msg.into()),
+            consensus_notify::Notification::VirtualChainChanged(msg) => Notification::VirtualSelectedParentChainChanged(msg.into()),
+            consensus_notify::Notification::UtxosChanged(msg) => Notification::UtxosChanged(msg.into()),
             consensus_notify::Notification::NewBlockTemplate(msg) => Notification::NewBlockTemplate(msg.into()),
+            consensus_notify::Notification::TransactionsAccepted(msg) => Notification::TransactionAccepted(msg.into()),
+            consensus_notify::Notification::TransactionsReverted(msg) => Notification::TransactionReverted(msg.into()),
         }
     }
 }
@@ -22,12 +30,59 @@ impl From<&consensus_notify::BlockAddedNotification> for BlockAddedNotification
     }
 }
 
+impl From<&consensus_notify::VirtualChainChangedNotification> for VirtualSelectedParentChainChangedNotification {
+    fn from(item: &consensus_notify::VirtualChainChangedNotification) -> Self {
+        Self {
+            removed_chain_block_hashes: item.removed_chain_block_hashes.clone(),
+            added_chain_block_hashes: item.added_chain_block_hashes.clone(),
+            accepted_transaction_ids: item.added_chain_blocks_acceptance_data.iter().map(RpcAcceptedTransactionIds::from).collect(),
+        }
+    }
+}
+
+impl From<&consensus_notify::UtxosChangedNotification> for UtxosChangedNotification {
+    fn from(item: &consensus_notify::UtxosChangedNotification) -> Self {
+        // The addresses are only known once the notification is scoped to the addresses of a listener
+        fn entries(collection: &UtxoCollection) -> Vec<RpcUtxosByAddressesEntry> {
+            collection
+                .iter()
+                .map(|(outpoint, entry)| RpcUtxosByAddressesEntry { address: None, outpoint: *outpoint, utxo_entry: entry.clone() })
+                .collect()
+        }
+        Self { added: entries(&item.accumulated_utxo_diff.add), removed: entries(&item.accumulated_utxo_diff.remove) }
+    }
+}
+
 impl From<&consensus_notify::NewBlockTemplateNotification> for NewBlockTemplateNotification {
     fn from(_: &consensus_notify::NewBlockTemplateNotification) -> Self {
         Self {}
     }
 }
 
+impl From<&consensus_notify::TransactionAcceptance> for RpcTransactionAcceptance {
+    fn from(item: &consensus_notify::TransactionAcceptance) -> Self {
+        // The addresses are only known once the notification is scoped to the addresses of a listener
+        Self {
+            transaction_id: item.transaction_id,
+            accepting_block_hash: item.accepting_block_hash,
+            output_script_public_keys: item.output_script_public_keys.clone(),
+            addresses: vec![],
+        }
+    }
+}
+
+impl From<&consensus_notify::TransactionsAcceptedNotification> for TransactionAcceptedNotification {
+    fn from(item: &consensus_notify::TransactionsAcceptedNotification) -> Self {
+        Self { accepted: item.accepted.iter().map(RpcTransactionAcceptance::from).collect() }
+    }
+}
+
+impl From<&consensus_notify::TransactionsRevertedNotification> for TransactionRevertedNotification {
+    fn from(item: &consensus_notify::TransactionsRevertedNotification) -> Self {
+        Self { reverted: item.reverted.iter().map(RpcTransactionAcceptance::from).collect() }
+    }
+}
+
 /// Pseudo conversion from Arc<Notification> to Arc<Notification>.
 /// This is basically a clone() op.
 impl From<ArcConvert<Notification>> for Arc<Notification> {
@@ -21,6 +21,22 @@ impl From<&Transaction> for RpcTransaction {
     }
 }
 
+/// Moves the scripts and payload of the transaction rather than cloning them
+impl From<Transaction> for RpcTransaction {
+    fn from(item: Transaction) -> Self {
+        Self {
+            version: item.version,
+            inputs: item.inputs.into_iter().map(RpcTransactionInput::from).collect(),
+            outputs: item.outputs.into_iter().map(RpcTransactionOutput::from).collect(),
+            lock_time: item.lock_time,
+            subnetwork_id: item.subnetwork_id,
+            gas: item.gas,
+            payload: item.payload,
+            verbose_data: None,
+        }
+    }
+}
+
 impl From<&TransactionOutput> for RpcTransactionOutput {
     fn from(item: &TransactionOutput) -> Self {
         Self {
@@ -32,6 +48,12 @@ impl From<&TransactionOutput> for RpcTransactionOutput {
     }
 }
 
+impl From<TransactionOutput> for RpcTransactionOutput {
+    fn from(item: TransactionOutput) -> Self {
+        Self { value: item.value, script_public_key: item.script_public_key, verbose_data: None }
+    }
+}
+
 impl From<&TransactionInput> for RpcTransactionInput {
     fn from(item: &TransactionInput) -> Self {
         Self {
@@ -45,6 +67,18 @@ impl From<&TransactionInput> for RpcTransactionInput {
     }
 }
 
+impl From<TransactionInput> for RpcTransactionInput {
+    fn from(item: TransactionInput) -> Self {
+        Self {
+            previous_outpoint: item.previous_outpoint,
+            signature_script: item.signature_script,
+            sequence: item.sequence,
+            sig_op_count: item.sig_op_count,
+            verbose_data: None,
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // rpc_core to consensus_core
 // ----------------------------------------------------------------------------
@@ -70,6 +104,22 @@ impl TryFrom<&RpcTransaction> for Transaction {
     }
 }
 
+/// Moves the scripts and payload of the transaction rather than cloning them
+impl TryFrom<RpcTransaction> for Transaction {
+    type Error = RpcError;
+    fn try_from(item: RpcTransaction) -> RpcResult<Self> {
+        Ok(Transaction::new(
+            item.version,
+            item.inputs.into_iter().map(TransactionInput::try_from).collect::<RpcResult<Vec<TransactionInput>>>()?,
+            item.outputs.into_iter().map(TransactionOutput::try_from).collect::<RpcResult<Vec<TransactionOutput>>>()?,
+            item.lock_time,
+            item.subnetwork_id,
+            item.gas,
+            item.payload,
+        ))
+    }
+}
+
 impl TryFrom<&RpcTransactionOutput> for TransactionOutput {
     type Error = RpcError;
     fn try_from(item: &RpcTransactionOutput) -> RpcResult<Self> {
@@ -83,3 +133,17 @@ impl TryFrom<&RpcTransactionInput> for TransactionInput {
         Ok(Self::new(item.previous_outpoint, item.signature_script.clone(), item.sequence, item.sig_op_count))
     }
 }
+
+impl TryFrom<RpcTransactionOutput> for TransactionOutput {
+    type Error = RpcError;
+    fn try_from(item: RpcTransactionOutput) -> RpcResult<Self> {
+        Ok(Self::new(item.value, item.script_public_key))
+    }
+}
+
+impl TryFrom<RpcTransactionInput> for TransactionInput {
+    type Error = RpcError;
+    fn try_from(item: RpcTransactionInput) -> RpcResult<Self> {
+        Ok(Self::new(item.previous_outpoint, item.signature_script, item.sequence, item.sig_op_count))
+    }
+}
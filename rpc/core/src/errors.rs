@@ -1,3 +1,4 @@
+use crate::notify::events::EventType;
 use std::num::TryFromIntError;
 use thiserror::Error;
 
@@ -12,6 +13,9 @@ pub enum RpcError {
     #[error("Hex parsing error: {0}")]
     HexParsingError(#[from] faster_hex::Error),
 
+    #[error("Network address parsing error: {0}")]
+    AddrParseError(#[from] std::net::AddrParseError),
+
     #[error("Blue work parsing error {0}")]
     RpcBlueWorkTypeParseError(std::num::ParseIntError),
 
@@ -27,12 +31,24 @@ pub enum RpcError {
     #[error("Feature not supported")]
     UnsupportedFeature,
 
+    #[error("Notifications of type {0:?} are not supported by this transport")]
+    UnsupportedNotificationType(EventType),
+
+    #[error("Timestamp conversion error: {0}")]
+    TimeConversionError(#[from] consensus_core::time::TimeConversionError),
+
     #[error("Primitive to enum conversion error")]
     PrimitiveToEnumConversionError,
 
     #[error(transparent)]
     AddressError(#[from] addresses::AddressError),
 
+    #[error(transparent)]
+    MiningManagerError(#[from] mining::errors::MiningManagerError),
+
+    #[error(transparent)]
+    SyncManagerError(#[from] consensus_core::errors::sync::SyncManagerError),
+
     #[error("{0}")]
     General(String),
 }
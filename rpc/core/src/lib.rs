@@ -21,7 +21,10 @@ pub mod prelude {
     pub use super::model::hash::*;
     pub use super::model::header::*;
     pub use super::model::hex_cnv::*;
+    pub use super::model::mempool::*;
     pub use super::model::message::*;
+    pub use super::model::metrics::*;
+    pub use super::model::peer::*;
     pub use super::model::script_class::*;
     pub use super::model::subnets::*;
     pub use super::model::tx::*;
@@ -36,7 +39,10 @@ pub use model::blue_work::*;
 pub use model::hash::*;
 pub use model::header::*;
 pub use model::hex_cnv::*;
+pub use model::mempool::*;
 pub use model::message::*;
+pub use model::metrics::*;
+pub use model::peer::*;
 pub use model::script_class::*;
 pub use model::subnets::*;
 pub use model::tx::*;
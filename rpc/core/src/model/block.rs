@@ -1,7 +1,7 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{RpcHash, RpcHeader, RpcTransaction};
+use crate::prelude::{RpcBlueWorkType, RpcHash, RpcHeader, RpcScriptPublicKey, RpcTransaction, RpcTransactionId};
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
@@ -24,4 +24,37 @@ pub struct RpcBlockVerboseData {
     pub merge_set_blues_hashes: Vec<RpcHash>,
     pub merge_set_reds_hashes: Vec<RpcHash>,
     pub is_chain_block: bool,
+    /// The blue score depth of the block below the virtual selected parent, or zero if not merged by its chain yet
+    pub confirmations: u64,
+}
+
+/// The reward a merged block contributes to the coinbase transaction of the merging block
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMergesetBlockReward {
+    pub hash: RpcHash,
+    pub is_blue: bool,
+    pub subsidy: u64,
+    pub total_fees: u64,
+    pub script_public_key: RpcScriptPublicKey,
+}
+
+/// The transactions accepted by a virtual selected parent chain block
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAcceptedTransactionIds {
+    pub accepting_block_hash: RpcHash,
+    pub accepted_transaction_ids: Vec<RpcTransactionId>,
+}
+
+/// A virtual selected parent chain block along with its GHOSTDAG data and the transactions it accepted
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcChainBlockWithAcceptance {
+    pub block: RpcBlock,
+    pub blue_score: u64,
+    pub blue_work: RpcBlueWorkType,
+    pub selected_parent_hash: RpcHash,
+    /// The accepted transactions, in merged block consensus order
+    pub accepted_transaction_ids: Vec<RpcTransactionId>,
 }
@@ -1 +1,2 @@
 pub type RpcHeader = consensus_core::header::Header;
+pub type RpcTimestamp = consensus_core::time::TimestampMs;
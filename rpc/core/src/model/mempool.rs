@@ -0,0 +1,27 @@
+use crate::RpcTransaction;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+/// A bucket of the mempool fee-rate histogram, with fee rates in sompi per mass unit
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeRateHistogramBucket {
+    /// The inclusive lower bound of the bucket fee rates
+    pub min_fee_rate: u64,
+    /// The exclusive upper bound of the bucket fee rates, or `None` for the last, unbounded bucket
+    pub max_fee_rate: Option<u64>,
+    pub transaction_count: u64,
+    pub total_mass: u64,
+}
+
+/// A transaction of the mempool or of its orphan pool
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMempoolEntry {
+    /// The transaction fee, unknown for orphans hence zero
+    pub fee: u64,
+    /// The transaction mass, unknown for orphans hence zero
+    pub mass: u64,
+    pub transaction: RpcTransaction,
+    pub is_orphan: bool,
+}
@@ -3,7 +3,12 @@ use std::fmt::{Display, Formatter};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::{api::ops::SubscribeCommand, RpcAddress, RpcBlock, RpcHash};
+use crate::{
+    api::ops::SubscribeCommand, RpcAcceptedTransactionIds, RpcAddress, RpcBlock, RpcChainBlockWithAcceptance, RpcConsensusMetrics,
+    RpcFeeRateHistogramBucket, RpcHash, RpcIpAddress, RpcMempoolEntry, RpcMergesetBlockReward, RpcPeerAddress, RpcPeerInfo,
+    RpcPerfMetrics, RpcScriptPublicKey, RpcTimestamp, RpcTransaction, RpcTransactionId, RpcTransactionOutpoint, RpcTransactionOutput,
+    RpcUtxoEntry,
+};
 
 pub type RpcExtraData = Vec<u8>;
 
@@ -27,22 +32,18 @@ impl SubmitBlockRequest {
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum SubmitBlockRejectReason {
-    // None = 0,
-    BlockInvalid = 1,
-    IsInIBD = 2,
-}
-impl SubmitBlockRejectReason {
-    fn as_str(&self) -> &'static str {
-        // see app\appmessage\rpc_submit_block.go, line 35
-        match self {
-            SubmitBlockRejectReason::BlockInvalid => "Block is invalid",
-            SubmitBlockRejectReason::IsInIBD => "Node is in IBD",
-        }
-    }
+    /// The block failed validation, along with the description of the broken rule
+    BlockInvalid(String),
+    /// The node is syncing with a peer and does not accept blocks until done
+    IsInIBD,
 }
 impl Display for SubmitBlockRejectReason {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.as_str())
+        // see app\rpc\rpchandlers\submit_block.go
+        match self {
+            SubmitBlockRejectReason::BlockInvalid(detail) => write!(f, "Block rejected. Reason: {0}", detail),
+            SubmitBlockRejectReason::IsInIBD => f.write_str("Block not submitted - IBD is running"),
+        }
     }
 }
 
@@ -116,6 +117,173 @@ pub struct GetBlockResponse {
     // block and error as mutually exclusive
 }
 
+/// GetBlocksRequest requests the blocks above `low_hash` up to the virtual selected parent, in topological order.
+///
+/// The number of returned blocks is capped. The last returned hash is a selected chain block, which serves as the
+/// `low_hash` of the request of the next blocks. No hashes are returned once the virtual selected parent is reached.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlocksRequest {
+    /// A selected chain block, defaulting to the pruning point
+    pub low_hash: Option<RpcHash>,
+
+    /// Whether to include the blocks in the response, or only their hashes
+    pub include_blocks: bool,
+
+    /// Whether to include the transactions of the included blocks
+    pub include_transactions: bool,
+}
+impl GetBlocksRequest {
+    pub fn new(low_hash: Option<RpcHash>, include_blocks: bool, include_transactions: bool) -> Self {
+        Self { low_hash, include_blocks, include_transactions }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlocksResponse {
+    pub block_hashes: Vec<RpcHash>,
+    /// The blocks of `block_hashes`, if requested
+    pub blocks: Vec<RpcBlock>,
+}
+
+/// SubmitTransactionRequest submits a transaction to the mempool.
+///
+/// Submitted transactions are local to the node, so they are inserted with high priority and
+/// periodically rebroadcast while they remain in the mempool.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionRequest {
+    pub transaction: RpcTransaction,
+    pub allow_orphan: bool,
+}
+impl SubmitTransactionRequest {
+    pub fn new(transaction: RpcTransaction, allow_orphan: bool) -> Self {
+        Self { transaction, allow_orphan }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionResponse {
+    /// The transaction ID of the submitted transaction
+    pub transaction_id: RpcTransactionId,
+}
+
+/// GetVirtualChainFromBlockRequest requests the virtual selected parent chain from some start block
+/// to the current virtual.
+///
+/// Responses are limited in size. When `has_more` is set in the response, the next batch is requested
+/// by passing the last added chain block as `start_hash`. Chain blocks which were meanwhile reorged out
+/// are then reported in the removed chain blocks of the next batch.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVirtualChainFromBlockRequest {
+    pub start_hash: RpcHash,
+    pub include_accepted_transaction_ids: bool,
+}
+impl GetVirtualChainFromBlockRequest {
+    pub fn new(start_hash: RpcHash, include_accepted_transaction_ids: bool) -> Self {
+        Self { start_hash, include_accepted_transaction_ids }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVirtualChainFromBlockResponse {
+    /// The chain blocks that were removed, in high-to-low order
+    pub removed_chain_block_hashes: Vec<RpcHash>,
+    /// The chain blocks that were added, in low-to-high order
+    pub added_chain_block_hashes: Vec<RpcHash>,
+    /// The transactions accepted by each added chain block. Filled only if requested.
+    pub accepted_transaction_ids: Vec<RpcAcceptedTransactionIds>,
+    /// Whether the virtual was not reached within the batch limits
+    pub has_more: bool,
+}
+
+/// GetTransactionAcceptanceRequest requests the virtual chain block which accepted a transaction, as recorded
+/// by the transaction index.
+///
+/// The index only retains acceptances within its retention window. A transaction which is not found while
+/// older acceptances were trimmed is reported as possibly beyond the window.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionAcceptanceRequest {
+    pub transaction_id: RpcTransactionId,
+}
+impl GetTransactionAcceptanceRequest {
+    pub fn new(transaction_id: RpcTransactionId) -> Self {
+        Self { transaction_id }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionAcceptanceResponse {
+    /// The chain block accepting the transaction, if found
+    pub accepting_block_hash: Option<RpcHash>,
+    pub accepting_daa_score: u64,
+    /// Whether the transaction was not found while acceptances below `retention_root_daa_score` were trimmed,
+    /// i.e., the transaction might have been accepted before the retention window
+    pub is_beyond_retention: bool,
+    /// The lowest DAA score of the retained accepting chain blocks
+    pub retention_root_daa_score: u64,
+}
+
+/// GetTransactionByIdRequest requests an accepted transaction by its id, along with the block including it and the
+/// virtual chain block accepting it, as recorded by the transaction index.
+///
+/// A transaction is found as long as its acceptance is retained by the index. The transaction itself is only
+/// returned while the body of its including block is still stored, i.e., until the block is pruned.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionByIdRequest {
+    pub transaction_id: RpcTransactionId,
+}
+impl GetTransactionByIdRequest {
+    pub fn new(transaction_id: RpcTransactionId) -> Self {
+        Self { transaction_id }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionByIdResponse {
+    /// The transaction, unless its including block was pruned
+    pub transaction: Option<RpcTransaction>,
+    /// The block including the transaction, out of the mergeset of the accepting block
+    pub including_block_hash: RpcHash,
+    pub accepting_block_hash: RpcHash,
+    pub accepting_daa_score: u64,
+}
+
+/// GetBlocksWithAcceptanceRequest requests the blocks of a virtual selected parent chain range along with their
+/// GHOSTDAG data and accepted transactions, in a single call, e.g., for explorer backfills.
+///
+/// The range covers the selected chain blocks of `high_hash` above `low_hash`, `high_hash` defaulting to the
+/// virtual selected parent. Responses are limited in size. When `has_more` is set in the response, the next
+/// batch is requested by passing the last returned block as `low_hash`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlocksWithAcceptanceRequest {
+    pub low_hash: RpcHash,
+    pub high_hash: Option<RpcHash>,
+}
+impl GetBlocksWithAcceptanceRequest {
+    pub fn new(low_hash: RpcHash, high_hash: Option<RpcHash>) -> Self {
+        Self { low_hash, high_hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlocksWithAcceptanceResponse {
+    /// The chain blocks, in low-to-high order
+    pub blocks: Vec<RpcChainBlockWithAcceptance>,
+    /// Whether the high block was not reached within the batch limit
+    pub has_more: bool,
+}
+
 /// NotifyBlockAddedRequest registers this connection for blockAdded notifications.
 ///
 /// See: [`BlockAddedNotification`]
@@ -144,6 +312,132 @@ pub struct BlockAddedNotification {
     pub block: RpcBlock,
 }
 
+/// VirtualSelectedParentChainChangedNotification is sent whenever the DAG's selected parent
+/// chain had changed.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualSelectedParentChainChangedNotification {
+    /// The chain blocks that were removed, in high-to-low order
+    pub removed_chain_block_hashes: Vec<RpcHash>,
+    /// The chain blocks that were added, in low-to-high order
+    pub added_chain_block_hashes: Vec<RpcHash>,
+    /// The transactions accepted by each added chain block
+    pub accepted_transaction_ids: Vec<RpcAcceptedTransactionIds>,
+}
+
+/// A UTXO along with the address it pays to, when known
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcUtxosByAddressesEntry {
+    pub address: Option<RpcAddress>,
+    pub outpoint: RpcTransactionOutpoint,
+    pub utxo_entry: RpcUtxoEntry,
+}
+
+/// UtxosChangedNotification is sent whenever the UTXO set of the virtual changed.
+///
+/// A listener subscribed to some addresses only receives the UTXOs paying to these addresses, with their address set.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxosChangedNotification {
+    pub added: Vec<RpcUtxosByAddressesEntry>,
+    pub removed: Vec<RpcUtxosByAddressesEntry>,
+}
+
+/// The scope of the transaction acceptance notifications: the transactions with any of the ids, along with the
+/// transactions having an output paying to any of the addresses. An empty scope stands for all transactions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionScope {
+    pub transaction_ids: Vec<RpcTransactionId>,
+    pub addresses: Vec<RpcAddress>,
+}
+
+impl RpcTransactionScope {
+    pub fn new(transaction_ids: Vec<RpcTransactionId>, addresses: Vec<RpcAddress>) -> Self {
+        Self { transaction_ids, addresses }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transaction_ids.is_empty() && self.addresses.is_empty()
+    }
+}
+
+/// A transaction accepted by a virtual selected parent chain block
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionAcceptance {
+    pub transaction_id: RpcTransactionId,
+    pub accepting_block_hash: RpcHash,
+    /// The script public keys the outputs of the transaction pay to
+    pub output_script_public_keys: Vec<RpcScriptPublicKey>,
+    /// The addresses of the listener scope the outputs pay to, only set once the notification is scoped by addresses
+    pub addresses: Vec<RpcAddress>,
+}
+
+/// TransactionAcceptedNotification is sent whenever the virtual selected parent chain changed, listing the
+/// transactions accepted by the added chain blocks. A transaction moving to another accepting block during
+/// a reorg is accepted again, without being reverted first.
+///
+/// A listener subscribed to a transaction scope only receives the transactions in scope.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionAcceptedNotification {
+    pub accepted: Vec<RpcTransactionAcceptance>,
+}
+
+/// TransactionRevertedNotification is sent whenever a reorg removed chain blocks, listing the transactions
+/// which are no longer accepted by the virtual selected parent chain along with the removed chain block
+/// which accepted them.
+///
+/// A listener subscribed to a transaction scope only receives the transactions in scope.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRevertedNotification {
+    pub reverted: Vec<RpcTransactionAcceptance>,
+}
+
+/// GetUtxosByAddressesRequest requests all current UTXOs paying to any of the given addresses.
+///
+/// This call is only available when the node runs with `--utxoindex`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxosByAddressesRequest {
+    pub addresses: Vec<RpcAddress>,
+}
+impl GetUtxosByAddressesRequest {
+    pub fn new(addresses: Vec<RpcAddress>) -> Self {
+        Self { addresses }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxosByAddressesResponse {
+    /// The UTXOs, each with the requested address it pays to
+    pub entries: Vec<RpcUtxosByAddressesEntry>,
+}
+
+/// GetBalanceByAddressRequest requests the total amount of the current UTXOs paying to an address.
+///
+/// This call is only available when the node runs with `--utxoindex`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBalanceByAddressRequest {
+    pub address: RpcAddress,
+}
+impl GetBalanceByAddressRequest {
+    pub fn new(address: RpcAddress) -> Self {
+        Self { address }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBalanceByAddressResponse {
+    pub balance: u64,
+}
+
 /// GetInfoRequest returns info about the node.
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
@@ -160,6 +454,341 @@ pub struct GetInfoResponse {
     pub has_notify_command: bool,
 }
 
+/// GetBlockDagInfoRequest requests general information about the current state of the DAG of the node.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockDagInfoRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockDagInfoResponse {
+    pub network_name: String,
+    pub block_count: u64,
+    pub header_count: u64,
+    pub tip_hashes: Vec<RpcHash>,
+    pub difficulty: f64,
+    pub past_median_time: RpcTimestamp,
+    pub virtual_parent_hashes: Vec<RpcHash>,
+    pub pruning_point_hash: RpcHash,
+    pub virtual_daa_score: u64,
+}
+
+/// GetChainThroughputStatsRequest requests transaction throughput and mass utilization statistics
+/// aggregated over the last `window_size` selected chain blocks. Windows larger than 10,000 chain blocks are clamped to
+/// that size, which is reflected by the `chain_block_count` of the response.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainThroughputStatsRequest {
+    pub window_size: u64,
+}
+impl GetChainThroughputStatsRequest {
+    pub fn new(window_size: u64) -> Self {
+        Self { window_size }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainThroughputStatsResponse {
+    pub chain_block_count: u64,
+    pub merged_block_count: u64,
+    pub accepted_transaction_count: u64,
+    pub accepted_mass: u64,
+    pub total_fees: u64,
+    pub time_span_millis: u64,
+    pub transactions_per_second: f64,
+    pub mass_utilization: f64,
+}
+
+/// RevalidateBlockRequest re-runs the header and body validations of a stored block against the
+/// current consensus rules and reports any disagreement with its stored status.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidateBlockRequest {
+    pub hash: RpcHash,
+}
+impl RevalidateBlockRequest {
+    pub fn new(hash: RpcHash) -> Self {
+        Self { hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidateBlockResponse {
+    pub stored_status: String,
+    pub body_revalidated: bool,
+    pub discrepancies: Vec<String>,
+}
+
+/// GetCoinbasePreviewRequest requests the coinbase transaction outputs a block built right now on top of
+/// the virtual would carry when paying to `pay_address`, along with the rewards of each merged block
+///
+/// See: [`GetBlockTemplateRequest`]
+#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCoinbasePreviewRequest {
+    pub pay_address: RpcAddress,
+    pub extra_data: RpcExtraData,
+}
+impl GetCoinbasePreviewRequest {
+    pub fn new(pay_address: RpcAddress, extra_data: RpcExtraData) -> Self {
+        Self { pay_address, extra_data }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCoinbasePreviewResponse {
+    pub daa_score: u64,
+    pub blue_score: u64,
+    /// The subsidy of the built block itself, paid by a future block merging it
+    pub subsidy: u64,
+    pub merged_block_rewards: Vec<RpcMergesetBlockReward>,
+    pub outputs: Vec<RpcTransactionOutput>,
+}
+
+/// GetMempoolFeeRateHistogramRequest requests a compact histogram of the fee rates of all mempool
+/// transactions, with the number of transactions and their total mass per fee-rate bucket
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolFeeRateHistogramRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolFeeRateHistogramResponse {
+    /// The non-empty buckets in descending fee-rate order
+    pub buckets: Vec<RpcFeeRateHistogramBucket>,
+    /// Allows relating the mass pending above a fee rate to the number of blocks required to include it
+    pub max_block_mass: u64,
+}
+
+/// GetMempoolEntryRequest requests a transaction of the mempool, looked up in the transaction pool unless
+/// `filter_transaction_pool` is set and in the orphan pool if `include_orphan_pool` is set.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolEntryRequest {
+    pub transaction_id: RpcTransactionId,
+    pub include_orphan_pool: bool,
+    pub filter_transaction_pool: bool,
+}
+impl GetMempoolEntryRequest {
+    pub fn new(transaction_id: RpcTransactionId, include_orphan_pool: bool, filter_transaction_pool: bool) -> Self {
+        Self { transaction_id, include_orphan_pool, filter_transaction_pool }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolEntryResponse {
+    pub entry: RpcMempoolEntry,
+}
+
+/// GetMempoolEntriesRequest requests the transactions of the mempool, the ones of the transaction pool unless
+/// `filter_transaction_pool` is set and the ones of the orphan pool if `include_orphan_pool` is set.
+///
+/// When `addresses` is not empty, only transactions spending from or paying to any of these addresses are returned.
+/// The addresses spent from by orphans are partially unknown, hence orphans are only matched by their outputs.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolEntriesRequest {
+    pub include_orphan_pool: bool,
+    pub filter_transaction_pool: bool,
+    pub addresses: Vec<RpcAddress>,
+}
+impl GetMempoolEntriesRequest {
+    pub fn new(include_orphan_pool: bool, filter_transaction_pool: bool, addresses: Vec<RpcAddress>) -> Self {
+        Self { include_orphan_pool, filter_transaction_pool, addresses }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolEntriesResponse {
+    pub entries: Vec<RpcMempoolEntry>,
+}
+
+/// GetMetricsRequest requests the health metrics of the node, each group being included only if requested
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMetricsRequest {
+    pub consensus_metrics: bool,
+    pub perf_metrics: bool,
+}
+impl GetMetricsRequest {
+    pub fn new(consensus_metrics: bool, perf_metrics: bool) -> Self {
+        Self { consensus_metrics, perf_metrics }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMetricsResponse {
+    /// The server time the metrics are measured at
+    pub server_time: RpcTimestamp,
+    pub consensus_metrics: Option<RpcConsensusMetrics>,
+    /// The latest resource usage sample, missing until the node took one or when the node does not sample resources
+    pub perf_metrics: Option<RpcPerfMetrics>,
+}
+
+/// GetPayloadRequest requests a transaction payload by its payload hash. Only payloads large enough to be
+/// deduplicated by the node storage can be retrieved this way.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPayloadRequest {
+    pub payload_hash: RpcHash,
+}
+impl GetPayloadRequest {
+    pub fn new(payload_hash: RpcHash) -> Self {
+        Self { payload_hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPayloadResponse {
+    pub payload: Vec<u8>,
+}
+
+/// GetConnectedPeerInfoRequest requests information about the peers currently connected to the node.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConnectedPeerInfoRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConnectedPeerInfoResponse {
+    pub peer_info: Vec<RpcPeerInfo>,
+}
+
+/// GetPeerAddressesRequest requests the peer addresses known to the node along with the currently banned IPs.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPeerAddressesRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPeerAddressesResponse {
+    pub known_addresses: Vec<RpcPeerAddress>,
+    pub banned_addresses: Vec<RpcIpAddress>,
+}
+
+/// AddPeerRequest requests the node to connect to a peer. A permanent peer is reconnected whenever the
+/// connection drops, while any other peer is attempted once.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddPeerRequest {
+    pub peer_address: RpcPeerAddress,
+    pub is_permanent: bool,
+}
+impl AddPeerRequest {
+    pub fn new(peer_address: RpcPeerAddress, is_permanent: bool) -> Self {
+        Self { peer_address, is_permanent }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddPeerResponse {}
+
+/// BanRequest requests the node to ban an IP, disconnecting all the peers connected from it.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BanRequest {
+    pub ip: RpcIpAddress,
+}
+impl BanRequest {
+    pub fn new(ip: RpcIpAddress) -> Self {
+        Self { ip }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BanResponse {}
+
+/// UnbanRequest requests the node to lift the ban of an IP.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnbanRequest {
+    pub ip: RpcIpAddress,
+}
+impl UnbanRequest {
+    pub fn new(ip: RpcIpAddress) -> Self {
+        Self { ip }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnbanResponse {}
+
+/// PinBlocksRequest requests the node to keep blocks along with their data when pruning, e.g., to preserve an
+/// anomalous DAG region for later investigation. Pins are persisted until lifted by an [`UnpinBlocksRequest`].
+///
+/// No block is pinned if one of them is not known.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PinBlocksRequest {
+    pub hashes: Vec<RpcHash>,
+}
+impl PinBlocksRequest {
+    pub fn new(hashes: Vec<RpcHash>) -> Self {
+        Self { hashes }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PinBlocksResponse {
+    /// All the blocks pinned once the request was applied
+    pub pinned_hashes: Vec<RpcHash>,
+}
+
+/// UnpinBlocksRequest requests the node to lift the pins of blocks, letting them be pruned. Blocks which are not
+/// pinned are ignored.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnpinBlocksRequest {
+    pub hashes: Vec<RpcHash>,
+}
+impl UnpinBlocksRequest {
+    pub fn new(hashes: Vec<RpcHash>) -> Self {
+        Self { hashes }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnpinBlocksResponse {
+    /// All the blocks still pinned once the request was applied
+    pub pinned_hashes: Vec<RpcHash>,
+}
+
+/// DumpBlockRegionRequest requests the blocks in the future of `low_hash` and in the past of `high_hash`, both
+/// included, exported as a JSON array of blocks with their verbose data in topological order, e.g., for attaching
+/// a DAG region to a bug report.
+///
+/// The region must be in the past of `high_hash` and hold at most a few thousand blocks.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpBlockRegionRequest {
+    pub low_hash: RpcHash,
+    pub high_hash: RpcHash,
+    pub include_transactions: bool,
+}
+impl DumpBlockRegionRequest {
+    pub fn new(low_hash: RpcHash, high_hash: RpcHash, include_transactions: bool) -> Self {
+        Self { low_hash, high_hash, include_transactions }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpBlockRegionResponse {
+    pub json: String,
+}
+
 /// NotifyNewBlockTemplateRequest registers this connection for blockAdded notifications.
 ///
 /// See: [`NewBlockTemplateNotification`]
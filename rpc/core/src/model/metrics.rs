@@ -0,0 +1,46 @@
+use crate::RpcTimestamp;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+/// Health metrics of the consensus, measured against the server clock
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConsensusMetrics {
+    pub virtual_daa_score: u64,
+    /// The timestamp of the sink, i.e., of the virtual selected parent
+    pub sink_timestamp: RpcTimestamp,
+    /// The DAA score the virtual would have reached by now, had blocks kept coming at the target rate since the sink
+    pub expected_daa_score: u64,
+    /// The time elapsed since the sink timestamp in units of target time per block. It grows continuously when the
+    /// node stalls or when the network hashrate collapses.
+    pub daa_score_drift: f64,
+}
+
+/// Resource usage of the node process and its database, sampled periodically by the node. Rates are averaged over
+/// the sampling interval, and process metrics unavailable on the platform of the node are reported as zero.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPerfMetrics {
+    /// The timestamp of the sample
+    pub sample_time: RpcTimestamp,
+    /// The CPU time used per unit of time, in percents of a single core
+    pub cpu_usage: f64,
+    pub resident_set_size: u64,
+    /// Bytes per second read from storage
+    pub disk_read_rate: f64,
+    /// Bytes per second written to storage
+    pub disk_write_rate: f64,
+    /// The total size of the database SST files
+    pub db_sst_files_size: u64,
+    /// The size of the database memtables, i.e., of the writes not yet flushed to SST files
+    pub db_memtables_size: u64,
+    /// The estimated number of bytes the database compactions have to rewrite to settle
+    pub db_pending_compaction_bytes: u64,
+    pub db_running_compactions: u64,
+    /// The number of blocks queued for header processing
+    pub header_queue_length: u64,
+    /// The number of blocks queued for body processing
+    pub body_queue_length: u64,
+    /// The number of blocks queued for the resolution of the virtual state
+    pub virtual_queue_length: u64,
+}
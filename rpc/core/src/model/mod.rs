@@ -4,7 +4,10 @@ pub mod blue_work;
 pub mod hash;
 pub mod header;
 pub mod hex_cnv;
+pub mod mempool;
+pub mod metrics;
 pub mod message;
+pub mod peer;
 pub mod script_class;
 pub mod subnets;
 pub mod tx;
@@ -15,6 +18,9 @@ pub use blue_work::*;
 pub use hash::*;
 pub use header::*;
 pub use hex_cnv::*;
+pub use mempool::*;
+pub use metrics::*;
 pub use message::*;
+pub use peer::*;
 pub use subnets::*;
 pub use tx::*;
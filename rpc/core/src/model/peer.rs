@@ -0,0 +1,22 @@
+use crate::RpcTimestamp;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use kaspa_utils::networking::{IpAddress, NetAddress};
+use serde::{Deserialize, Serialize};
+
+pub type RpcPeerAddress = NetAddress;
+pub type RpcIpAddress = IpAddress;
+
+/// Information about a peer connected to the node
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPeerInfo {
+    pub id: String,
+    pub address: RpcPeerAddress,
+    pub is_outbound: bool,
+    /// The clock offset of the peer relative to the node, in milliseconds
+    pub time_offset: i64,
+    pub user_agent: String,
+    pub advertised_protocol_version: u32,
+    /// The time at which the connection was established
+    pub time_connected: RpcTimestamp,
+}
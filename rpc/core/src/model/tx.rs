@@ -72,4 +72,6 @@ pub struct RpcTransactionVerboseData {
     pub mass: u64,
     pub block_hash: RpcHash,
     pub block_time: u64,
+    /// The confirmations of the block accepting the transaction, or zero if not accepted yet
+    pub confirmations: u64,
 }
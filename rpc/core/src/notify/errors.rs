@@ -16,6 +16,9 @@ pub enum Error {
 
     #[error("object already stopped")]
     AlreadyStoppedError,
+
+    #[error("Notification: invalid UTXO address: {0}")]
+    InvalidUtxoAddress(String),
 }
 
 impl From<Error> for RpcError {
@@ -1,6 +1,6 @@
 use std::ops::{Index, IndexMut};
 
-use crate::{Notification, NotificationType};
+use crate::{Notification, NotificationType, RpcTransactionScope};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EventType {
@@ -13,10 +13,12 @@ pub enum EventType {
     VirtualDaaScoreChanged,
     PruningPointUTXOSetOverride,
     NewBlockTemplate,
+    TransactionAccepted,
+    TransactionReverted,
 }
 
 // TODO: write a macro or use an external crate to get this
-pub(crate) const EVENT_COUNT: usize = 9;
+pub(crate) const EVENT_COUNT: usize = 11;
 
 // TODO: write a macro or use an external crate to get this
 pub const EVENT_TYPE_ARRAY: [EventType; EVENT_COUNT] = [
@@ -29,6 +31,8 @@ pub const EVENT_TYPE_ARRAY: [EventType; EVENT_COUNT] = [
     EventType::VirtualDaaScoreChanged,
     EventType::PruningPointUTXOSetOverride,
     EventType::NewBlockTemplate,
+    EventType::TransactionAccepted,
+    EventType::TransactionReverted,
 ];
 
 // TODO: write a macro to get this
@@ -36,7 +40,7 @@ impl From<EventType> for NotificationType {
     fn from(item: EventType) -> Self {
         match item {
             EventType::BlockAdded => NotificationType::BlockAdded,
-            EventType::VirtualSelectedParentChainChanged => NotificationType::VirtualSelectedParentChainChanged,
+            EventType::VirtualSelectedParentChainChanged => NotificationType::VirtualSelectedParentChainChanged(true),
             EventType::FinalityConflicts => NotificationType::FinalityConflicts,
             EventType::FinalityConflictResolved => NotificationType::FinalityConflictResolved,
             EventType::UtxosChanged => NotificationType::UtxosChanged(vec![]),
@@ -44,6 +48,8 @@ impl From<EventType> for NotificationType {
             EventType::VirtualDaaScoreChanged => NotificationType::VirtualDaaScoreChanged,
             EventType::PruningPointUTXOSetOverride => NotificationType::PruningPointUTXOSetOverride,
             EventType::NewBlockTemplate => NotificationType::NewBlockTemplate,
+            EventType::TransactionAccepted => NotificationType::TransactionAccepted(RpcTransactionScope::default()),
+            EventType::TransactionReverted => NotificationType::TransactionReverted(RpcTransactionScope::default()),
         }
     }
 }
@@ -61,6 +67,8 @@ impl From<&Notification> for EventType {
             Notification::VirtualDaaScoreChanged(_) => EventType::VirtualDaaScoreChanged,
             Notification::PruningPointUTXOSetOverride(_) => EventType::PruningPointUTXOSetOverride,
             Notification::NewBlockTemplate(_) => EventType::NewBlockTemplate,
+            Notification::TransactionAccepted(_) => EventType::TransactionAccepted,
+            Notification::TransactionReverted(_) => EventType::TransactionReverted,
         }
     }
 }
@@ -70,7 +78,7 @@ impl From<&NotificationType> for EventType {
     fn from(item: &NotificationType) -> Self {
         match item {
             NotificationType::BlockAdded => EventType::BlockAdded,
-            NotificationType::VirtualSelectedParentChainChanged => EventType::VirtualSelectedParentChainChanged,
+            NotificationType::VirtualSelectedParentChainChanged(_) => EventType::VirtualSelectedParentChainChanged,
             NotificationType::FinalityConflicts => EventType::FinalityConflicts,
             NotificationType::FinalityConflictResolved => EventType::FinalityConflictResolved,
             NotificationType::UtxosChanged(_) => EventType::UtxosChanged,
@@ -78,6 +86,8 @@ impl From<&NotificationType> for EventType {
             NotificationType::VirtualDaaScoreChanged => EventType::VirtualDaaScoreChanged,
             NotificationType::PruningPointUTXOSetOverride => EventType::PruningPointUTXOSetOverride,
             NotificationType::NewBlockTemplate => EventType::NewBlockTemplate,
+            NotificationType::TransactionAccepted(_) => EventType::TransactionAccepted,
+            NotificationType::TransactionReverted(_) => EventType::TransactionReverted,
         }
     }
 }
@@ -1,14 +1,23 @@
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use crate::notify::{
     channel::NotificationChannel,
     events::{EventArray, EventType},
     result::Result,
-    utxo_address_set::RpcUtxoAddressSet,
+    transaction_scope::TransactionScope,
+    utxo_address_set::{with_script_public_keys, RpcUtxoAddressSet},
+};
+use crate::{
+    Notification, NotificationReceiver, NotificationSender, NotificationType, RpcAddress, RpcTransactionScope,
+    RpcUtxosByAddressesEntry, TransactionAcceptedNotification, TransactionRevertedNotification, UtxosChangedNotification,
+    VirtualSelectedParentChainChangedNotification,
 };
-use crate::stubs::RpcUtxoAddress;
-use crate::{Notification, NotificationReceiver, NotificationSender, NotificationType};
+use async_std::channel::TrySendError;
+use consensus_core::notify::BackpressurePolicy;
 
 pub type ListenerID = u64;
 
@@ -31,18 +40,38 @@ pub enum ListenerUtxoNotificationFilterSetting {
 ///
 /// Any ListenerSenderSide derived from a [Listener] should also be rebuilt
 /// upon relevant mutation by a call to toggle.
+///
+/// The UtxosChanged scope of a listener is its set of addresses, an empty set
+/// standing for all addresses while the event is active. The VirtualSelectedParentChainChanged
+/// scope is whether the accepted transaction ids are included. The TransactionAccepted and
+/// TransactionReverted scopes are each a set of transaction ids and addresses, following the
+/// same rules as the UtxosChanged addresses.
 #[derive(Debug)]
 pub(crate) struct Listener {
     id: u64,
     channel: NotificationChannel,
+    policy: BackpressurePolicy,
+    dropped: Arc<AtomicU64>,
     active_event: EventArray<bool>,
     utxo_addresses: RpcUtxoAddressSet,
+    include_accepted_transaction_ids: bool,
+    accepted_transaction_scope: TransactionScope,
+    reverted_transaction_scope: TransactionScope,
 }
 
 impl Listener {
-    pub(crate) fn new(id: ListenerID, channel: Option<NotificationChannel>) -> Listener {
-        let channel = channel.unwrap_or_default();
-        Self { id, channel, active_event: EventArray::default(), utxo_addresses: RpcUtxoAddressSet::new() }
+    pub(crate) fn new(id: ListenerID, channel: NotificationChannel, policy: BackpressurePolicy) -> Listener {
+        Self {
+            id,
+            channel,
+            policy,
+            dropped: Arc::new(AtomicU64::new(0)),
+            active_event: EventArray::default(),
+            utxo_addresses: RpcUtxoAddressSet::new(),
+            include_accepted_transaction_ids: false,
+            accepted_transaction_scope: TransactionScope::default(),
+            reverted_transaction_scope: TransactionScope::default(),
+        }
     }
 
     pub(crate) fn id(&self) -> ListenerID {
@@ -54,30 +83,148 @@ impl Listener {
         self.active_event[event]
     }
 
-    fn toggle_utxo_addresses(&mut self, utxo_addresses: &[RpcUtxoAddress]) -> bool {
-        let utxo_addresses = RpcUtxoAddressSet::from_iter(utxo_addresses.iter().cloned());
-        if utxo_addresses != self.utxo_addresses {
-            self.utxo_addresses = utxo_addresses;
-            return true;
+    /// Returns the number of notifications dropped for this listener due to its backpressure policy
+    pub(crate) fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Starting with no address widens the scope to all addresses, otherwise the addresses are added to the scope.
+    /// Stopping with no address stops the notifications, otherwise the addresses are removed from the scope and
+    /// the notifications stop once no address is left. A scope covering all addresses can only be stopped as a whole.
+    fn toggle_utxo_addresses(&mut self, utxo_addresses: &[RpcAddress], active: bool) -> Result<bool> {
+        let utxo_addresses = with_script_public_keys(utxo_addresses)?;
+        let event = EventType::UtxosChanged;
+        let was_active = self.active_event[event];
+        let scope_len = self.utxo_addresses.len();
+        if active {
+            if utxo_addresses.is_empty() {
+                self.utxo_addresses.clear();
+            } else if was_active && scope_len == 0 {
+                return Ok(false);
+            } else {
+                self.utxo_addresses.extend(utxo_addresses);
+            }
+            self.active_event[event] = true;
+            Ok(!was_active || self.utxo_addresses.len() != scope_len)
+        } else {
+            if !was_active || (!utxo_addresses.is_empty() && scope_len == 0) {
+                return Ok(false);
+            }
+            if utxo_addresses.is_empty() {
+                self.utxo_addresses.clear();
+            } else {
+                utxo_addresses.iter().for_each(|(script_public_key, _)| {
+                    self.utxo_addresses.remove(script_public_key);
+                });
+            }
+            self.active_event[event] = !self.utxo_addresses.is_empty();
+            Ok(self.utxo_addresses.len() != scope_len)
+        }
+    }
+
+    fn transaction_scope_mut(&mut self, event: EventType) -> &mut TransactionScope {
+        match event {
+            EventType::TransactionReverted => &mut self.reverted_transaction_scope,
+            _ => &mut self.accepted_transaction_scope,
+        }
+    }
+
+    /// Follows the rules of [`Listener::toggle_utxo_addresses`], the scope being made of transaction ids and addresses
+    fn toggle_transaction_scope(&mut self, event: EventType, scope: &RpcTransactionScope, active: bool) -> Result<bool> {
+        let scope = TransactionScope::try_from(scope)?;
+        let was_active = self.active_event[event];
+        let current_scope = self.transaction_scope_mut(event);
+        let scope_len = current_scope.len();
+        if active {
+            if scope.is_empty() {
+                current_scope.clear();
+            } else if was_active && scope_len == 0 {
+                return Ok(false);
+            } else {
+                current_scope.extend(scope);
+            }
+            let changed = !was_active || current_scope.len() != scope_len;
+            self.active_event[event] = true;
+            Ok(changed)
+        } else {
+            if !was_active || (!scope.is_empty() && scope_len == 0) {
+                return Ok(false);
+            }
+            if scope.is_empty() {
+                current_scope.clear();
+            } else {
+                current_scope.remove(&scope);
+            }
+            let (is_empty, changed) = (current_scope.is_empty(), current_scope.len() != scope_len);
+            self.active_event[event] = !is_empty;
+            Ok(changed)
         }
-        false
     }
 
     /// Toggle registration for [`NotificationType`] notifications.
     /// Return true if any change occured in the registration state.
-    pub(crate) fn toggle(&mut self, notification_type: NotificationType, active: bool) -> bool {
-        let mut changed = false;
-        let event: EventType = (&notification_type).into();
+    pub(crate) fn toggle(&mut self, notification_type: NotificationType, active: bool) -> Result<bool> {
+        match notification_type {
+            NotificationType::UtxosChanged(ref utxo_addresses) => return self.toggle_utxo_addresses(utxo_addresses, active),
+            NotificationType::TransactionAccepted(ref scope) => {
+                return self.toggle_transaction_scope(EventType::TransactionAccepted, scope, active)
+            }
+            NotificationType::TransactionReverted(ref scope) => {
+                return self.toggle_transaction_scope(EventType::TransactionReverted, scope, active)
+            }
+            _ => {}
+        }
 
+        let event: EventType = (&notification_type).into();
+        let mut changed = false;
         if self.active_event[event] != active {
             self.active_event[event] = active;
             changed = true;
         }
+        if let NotificationType::VirtualSelectedParentChainChanged(include_accepted_transaction_ids) = notification_type {
+            if active && self.include_accepted_transaction_ids != include_accepted_transaction_ids {
+                self.include_accepted_transaction_ids = include_accepted_transaction_ids;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
 
-        if let NotificationType::UtxosChanged(ref utxo_addresses) = notification_type {
-            changed = self.toggle_utxo_addresses(utxo_addresses);
+    /// Replaces the scope of an active registration for [`NotificationType`] notifications, an empty address set
+    /// or transaction scope standing for all addresses or transactions. Return true if any change occured in the
+    /// registration state.
+    pub(crate) fn modify_scope(&mut self, notification_type: NotificationType) -> Result<bool> {
+        let event: EventType = (&notification_type).into();
+        if !self.active_event[event] {
+            return Ok(false);
+        }
+        match notification_type {
+            NotificationType::UtxosChanged(utxo_addresses) => {
+                let utxo_addresses = RpcUtxoAddressSet::from_iter(with_script_public_keys(&utxo_addresses)?);
+                if utxo_addresses != self.utxo_addresses {
+                    self.utxo_addresses = utxo_addresses;
+                    return Ok(true);
+                }
+                Ok(false)
+            }
+            NotificationType::VirtualSelectedParentChainChanged(include_accepted_transaction_ids) => {
+                if include_accepted_transaction_ids != self.include_accepted_transaction_ids {
+                    self.include_accepted_transaction_ids = include_accepted_transaction_ids;
+                    return Ok(true);
+                }
+                Ok(false)
+            }
+            NotificationType::TransactionAccepted(ref scope) | NotificationType::TransactionReverted(ref scope) => {
+                let scope = TransactionScope::try_from(scope)?;
+                let current_scope = self.transaction_scope_mut(event);
+                if scope != *current_scope {
+                    *current_scope = scope;
+                    return Ok(true);
+                }
+                Ok(false)
+            }
+            _ => Ok(false),
         }
-        changed
     }
 
     pub(crate) fn close(&mut self) {
@@ -108,36 +255,70 @@ impl From<&Listener> for ListenerReceiverSide {
 /// Contains the sender side of a listener
 pub(crate) struct ListenerSenderSide {
     send_channel: NotificationSender,
+    /// A receiver end kept for evicting the oldest notifications under [`BackpressurePolicy::DropOldest`]
+    recv_channel: NotificationReceiver,
+    policy: BackpressurePolicy,
+    dropped: Arc<AtomicU64>,
     filter: Box<dyn Filter + Send + Sync>,
 }
 
 impl ListenerSenderSide {
     pub(crate) fn new(listener: &Listener, sending_changed_utxos: ListenerUtxoNotificationFilterSetting, event: EventType) -> Self {
-        match event {
-            EventType::UtxosChanged if sending_changed_utxos == ListenerUtxoNotificationFilterSetting::FilteredByAddress => Self {
-                send_channel: listener.channel.sender(),
-                filter: Box::new(FilterUtxoAddress { utxos_addresses: listener.utxo_addresses.clone() }),
-            },
-            _ => Self { send_channel: listener.channel.sender(), filter: Box::new(Unfiltered {}) },
+        let filter: Box<dyn Filter + Send + Sync> = match event {
+            EventType::UtxosChanged if sending_changed_utxos == ListenerUtxoNotificationFilterSetting::FilteredByAddress => {
+                Box::new(FilterUtxoAddress { utxos_addresses: listener.utxo_addresses.clone() })
+            }
+            EventType::VirtualSelectedParentChainChanged if !listener.include_accepted_transaction_ids => {
+                Box::new(ExcludeAcceptedTransactionIds {})
+            }
+            EventType::TransactionAccepted => Box::new(FilterTransactionScope { scope: listener.accepted_transaction_scope.clone() }),
+            EventType::TransactionReverted => Box::new(FilterTransactionScope { scope: listener.reverted_transaction_scope.clone() }),
+            _ => Box::new(Unfiltered {}),
+        };
+        Self {
+            send_channel: listener.channel.sender(),
+            recv_channel: listener.channel.receiver(),
+            policy: listener.policy,
+            dropped: listener.dropped.clone(),
+            filter,
         }
     }
 
-    /// Try to send a notification.
+    /// Send a notification according to the listener backpressure policy.
     ///
-    /// If the notification does not meet requirements (see [`Notification::UtxosChanged`]) returns `Ok(false)`,
-    /// otherwise returns `Ok(true)`.
-    pub(crate) fn try_send(&self, notification: Arc<Notification>) -> Result<bool> {
-        if self.filter.matches(notification.clone()) {
-            match self.send_channel.try_send(notification) {
-                Ok(_) => {
-                    return Ok(true);
+    /// The notification is first narrowed to the listener scope. If nothing is left in scope (see
+    /// [`Notification::UtxosChanged`] and [`Notification::TransactionAccepted`]) returns `Ok(false)`, otherwise
+    /// returns `Ok(true)`, even if the notification was dropped due to a full channel.
+    pub(crate) async fn send(&self, notification: Arc<Notification>) -> Result<bool> {
+        let mut notification = match self.filter.apply(notification) {
+            Some(notification) => notification,
+            None => return Ok(false),
+        };
+        match self.policy {
+            BackpressurePolicy::Block(_) => self.send_channel.send(notification).await?,
+            // A channel provided at registration may be bounded even with an unbounded policy
+            BackpressurePolicy::Unbounded | BackpressurePolicy::DropNewest(_) => match self.send_channel.try_send(notification) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
                 }
-                Err(err) => {
-                    return Err(err.into());
+                Err(err) => return Err(err.into()),
+            },
+            BackpressurePolicy::DropOldest(_) => loop {
+                match self.send_channel.try_send(notification) {
+                    Ok(()) => break,
+                    Err(TrySendError::Full(returned)) => {
+                        // The listener might have drained the channel concurrently, in which case nothing is evicted
+                        if self.recv_channel.try_recv().is_ok() {
+                            self.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        notification = returned;
+                    }
+                    Err(err) => return Err(err.into()),
                 }
-            }
+            },
         }
-        Ok(false)
+        Ok(true)
     }
 
     pub(crate) fn is_closed(&self) -> bool {
@@ -146,7 +327,8 @@ impl ListenerSenderSide {
 }
 
 trait InnerFilter {
-    fn matches(&self, notification: Arc<Notification>) -> bool;
+    /// Narrows the notification to the listener scope, returning `None` if nothing is left in scope
+    fn apply(&self, notification: Arc<Notification>) -> Option<Arc<Notification>>;
 }
 
 trait Filter: InnerFilter + Debug {}
@@ -154,8 +336,8 @@ trait Filter: InnerFilter + Debug {}
 #[derive(Clone, Debug)]
 struct Unfiltered;
 impl InnerFilter for Unfiltered {
-    fn matches(&self, _: Arc<Notification>) -> bool {
-        true
+    fn apply(&self, notification: Arc<Notification>) -> Option<Arc<Notification>> {
+        Some(notification)
     }
 }
 impl Filter for Unfiltered {}
@@ -165,12 +347,90 @@ struct FilterUtxoAddress {
     utxos_addresses: RpcUtxoAddressSet,
 }
 
+impl FilterUtxoAddress {
+    fn in_scope(&self, entries: &[RpcUtxosByAddressesEntry]) -> Vec<RpcUtxosByAddressesEntry> {
+        entries
+            .iter()
+            .filter_map(|entry| {
+                self.utxos_addresses.get(&entry.utxo_entry.script_public_key).map(|address| RpcUtxosByAddressesEntry {
+                    address: Some(address.clone()),
+                    outpoint: entry.outpoint,
+                    utxo_entry: entry.utxo_entry.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
 impl InnerFilter for FilterUtxoAddress {
-    fn matches(&self, notification: Arc<Notification>) -> bool {
-        if let Notification::UtxosChanged(ref notification) = *notification {
-            return self.utxos_addresses.contains(&notification.utxo_address);
+    fn apply(&self, notification: Arc<Notification>) -> Option<Arc<Notification>> {
+        if let Notification::UtxosChanged(ref inner) = *notification {
+            // An empty address set stands for all addresses
+            if self.utxos_addresses.is_empty() {
+                return Some(notification);
+            }
+            let added = self.in_scope(&inner.added);
+            let removed = self.in_scope(&inner.removed);
+            if added.is_empty() && removed.is_empty() {
+                return None;
+            }
+            return Some(Arc::new(Notification::UtxosChanged(UtxosChangedNotification { added, removed })));
         }
-        false
+        None
     }
 }
 impl Filter for FilterUtxoAddress {}
+
+#[derive(Clone, Debug)]
+struct ExcludeAcceptedTransactionIds;
+
+impl InnerFilter for ExcludeAcceptedTransactionIds {
+    fn apply(&self, notification: Arc<Notification>) -> Option<Arc<Notification>> {
+        if let Notification::VirtualSelectedParentChainChanged(ref inner) = *notification {
+            if !inner.accepted_transaction_ids.is_empty() {
+                return Some(Arc::new(Notification::VirtualSelectedParentChainChanged(
+                    VirtualSelectedParentChainChangedNotification {
+                        removed_chain_block_hashes: inner.removed_chain_block_hashes.clone(),
+                        added_chain_block_hashes: inner.added_chain_block_hashes.clone(),
+                        accepted_transaction_ids: vec![],
+                    },
+                )));
+            }
+        }
+        Some(notification)
+    }
+}
+impl Filter for ExcludeAcceptedTransactionIds {}
+
+#[derive(Clone, Debug)]
+struct FilterTransactionScope {
+    scope: TransactionScope,
+}
+
+impl InnerFilter for FilterTransactionScope {
+    fn apply(&self, notification: Arc<Notification>) -> Option<Arc<Notification>> {
+        // An empty scope stands for all transactions
+        if self.scope.is_empty() {
+            return Some(notification);
+        }
+        let notification = match *notification {
+            Notification::TransactionAccepted(ref inner) => {
+                let accepted = self.scope.in_scope(&inner.accepted);
+                if accepted.is_empty() {
+                    return None;
+                }
+                Notification::TransactionAccepted(TransactionAcceptedNotification { accepted })
+            }
+            Notification::TransactionReverted(ref inner) => {
+                let reverted = self.scope.in_scope(&inner.reverted);
+                if reverted.is_empty() {
+                    return None;
+                }
+                Notification::TransactionReverted(TransactionRevertedNotification { reverted })
+            }
+            _ => return None,
+        };
+        Some(Arc::new(notification))
+    }
+}
+impl Filter for FilterTransactionScope {}
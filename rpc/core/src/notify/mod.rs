@@ -7,4 +7,5 @@ pub mod message;
 pub mod notifier;
 pub mod result;
 pub mod subscriber;
+pub mod transaction_scope;
 pub mod utxo_address_set;
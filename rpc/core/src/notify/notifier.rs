@@ -10,8 +10,9 @@ use super::{
 };
 use crate::{api::ops::SubscribeCommand, Notification, NotificationType, RpcResult};
 use ahash::AHashMap;
-use async_std::channel::{Receiver, Sender};
+use async_std::channel::{bounded, Receiver, Sender};
 use async_trait::async_trait;
+use consensus_core::notify::BackpressurePolicy;
 use kaspa_core::trace;
 use kaspa_utils::channel::Channel;
 use std::sync::{
@@ -23,6 +24,10 @@ use std::sync::{
 ///
 /// Manages a collection of [`Listener`] and, for each one, a set of events to be notified.
 /// Actually notify the listeners of incoming events.
+///
+/// Every listener has a [`BackpressurePolicy`] deciding what happens when its channel is full.
+/// A listener is unregistered once its channel gets closed, either by [`Notifier::unregister_listener`]
+/// or by its receiver side.
 #[derive(Debug)]
 pub struct Notifier {
     inner: Arc<Inner>,
@@ -41,8 +46,27 @@ impl Notifier {
         self.inner.clone().start(self.clone());
     }
 
+    /// Registers a listener notified through `channel`, or through a new unbounded channel if `None`.
+    /// Notifications not fitting in a bounded `channel` are dropped.
     pub fn register_new_listener(&self, channel: Option<NotificationChannel>) -> ListenerReceiverSide {
-        self.inner.clone().register_new_listener(channel)
+        self.inner.clone().register_new_listener(channel.unwrap_or_default(), BackpressurePolicy::Unbounded)
+    }
+
+    /// Registers a listener notified through a new channel bounded according to `policy`
+    ///
+    /// # Panics
+    /// Panics if a bounded policy has a zero capacity
+    pub fn register_new_listener_with_policy(&self, policy: BackpressurePolicy) -> ListenerReceiverSide {
+        let channel = match policy {
+            BackpressurePolicy::Unbounded => NotificationChannel::default(),
+            BackpressurePolicy::Block(capacity)
+            | BackpressurePolicy::DropNewest(capacity)
+            | BackpressurePolicy::DropOldest(capacity) => {
+                assert!(capacity > 0, "a bounded backpressure policy requires a positive capacity");
+                NotificationChannel::new(bounded(capacity))
+            }
+        };
+        self.inner.clone().register_new_listener(channel, policy)
     }
 
     pub fn unregister_listener(&self, id: ListenerID) -> Result<()> {
@@ -70,6 +94,20 @@ impl Notifier {
         self.inner.clone().stop_notify(id, notification_type)
     }
 
+    /// Replaces the scope of an active subscription, ignoring inactive ones
+    pub fn modify_notify(&self, id: ListenerID, notification_type: NotificationType) -> Result<()> {
+        self.inner.clone().modify_notify(id, notification_type)
+    }
+
+    /// Returns the number of notifications dropped for the listener due to its backpressure policy
+    pub fn dropped_count(&self, id: ListenerID) -> Option<u64> {
+        self.inner.listeners.lock().unwrap().get(&id).map(|listener| listener.dropped_count())
+    }
+
+    pub fn listener_count(&self) -> usize {
+        self.inner.listeners.lock().unwrap().len()
+    }
+
     pub async fn stop(&self) -> Result<()> {
         self.inner.clone().stop().await
     }
@@ -88,6 +126,16 @@ impl SubscriptionManager for Notifier {
         self.inner.clone().stop_notify(id, notification_type)?;
         Ok(())
     }
+
+    async fn modify_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        trace!(
+            "[Notifier] as subscription manager modify the scope of listener {0} notifications of type {1:?}",
+            id,
+            notification_type
+        );
+        self.inner.clone().modify_notify(id, notification_type)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -157,6 +205,10 @@ impl Inner {
     /// corresponding type. The dispatcher receives and executes messages
     /// instructing to modify the map. This happens without blocking
     /// the whole notifier.
+    ///
+    /// A listener with a [`BackpressurePolicy::Block`] policy and a full
+    /// channel blocks the dispatcher, and thus all listeners of the type,
+    /// until it catches up.
     fn spawn_dispatcher_task(&self, event: EventType, shutdown_trigger: triggered::Trigger, dispatch_rx: Receiver<DispatchMessage>) {
         // Feedback
         let send_subscriber = self.subscriber.clone().as_ref().as_ref().map(|x| x.sender());
@@ -164,11 +216,16 @@ impl Inner {
 
         let sending_changed_utxos = self.sending_changed_utxos;
 
-        // This holds the map of all active listeners for the event type
-        let mut listeners: AHashMap<ListenerID, Arc<ListenerSenderSide>> = AHashMap::new();
-
-        // TODO: feed the listeners map with pre-existing self.listeners having event active
-        // This is necessary for the correct handling of repeating start/stop cycles.
+        // This holds the map of all active listeners for the event type, fed with the pre-existing
+        // listeners so that repeating start/stop cycles keep them notified
+        let registered_listeners = self.listeners.clone();
+        let mut listeners: AHashMap<ListenerID, Arc<ListenerSenderSide>> = registered_listeners
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|listener| listener.has(event))
+            .map(|listener| (listener.id(), Arc::new(ListenerSenderSide::new(listener, sending_changed_utxos, event))))
+            .collect();
 
         workflow_core::task::spawn(async move {
             trace!("[Notifier] dispatcher_task starting for notification type {:?}", event);
@@ -188,7 +245,7 @@ impl Inner {
             let report_all_changes =
                 event == EventType::UtxosChanged && sending_changed_utxos == ListenerUtxoNotificationFilterSetting::FilteredByAddress;
 
-            let mut need_subscribe: bool = false;
+            let mut need_subscribe: bool = !listeners.is_empty();
             loop {
                 // If needed, send subscribe message based on listeners map being empty or not
                 if need_subscribe && has_subscriber {
@@ -209,7 +266,7 @@ impl Inner {
 
                         // Broadcast the notification to all listeners
                         for (id, listener) in listeners.iter() {
-                            match listener.try_send(notification.clone()) {
+                            match listener.send(notification.clone()).await {
                                 Ok(_) => {}
                                 Err(_) => {
                                     if listener.is_closed() {
@@ -222,9 +279,14 @@ impl Inner {
                         // Feedback needed if purge will empty listeners or if reporting any change
                         need_subscribe = (!purge.is_empty() && (purge.len() == listeners.len())) || report_all_changes;
 
-                        // Remove closed listeners
-                        for id in purge {
-                            listeners.remove(&id);
+                        // Remove closed listeners, which the dispatchers of other event types purge on their own
+                        if !purge.is_empty() {
+                            let mut registered_listeners = registered_listeners.lock().unwrap();
+                            for id in purge {
+                                trace!("[Notifier] dispatcher_task purging closed listener {0}", id);
+                                listeners.remove(&id);
+                                registered_listeners.remove(&id);
+                            }
                         }
                     }
 
@@ -253,14 +315,14 @@ impl Inner {
         });
     }
 
-    fn register_new_listener(self: Arc<Self>, channel: Option<NotificationChannel>) -> ListenerReceiverSide {
+    fn register_new_listener(self: Arc<Self>, channel: NotificationChannel, policy: BackpressurePolicy) -> ListenerReceiverSide {
         let mut listeners = self.listeners.lock().unwrap();
         loop {
             let id = u64::from_le_bytes(rand::random::<[u8; 8]>());
 
             // This is very unlikely to happen but still, check for duplicates
             if !listeners.contains_key(&id) {
-                let listener = Listener::new(id, channel);
+                let listener = Listener::new(id, channel, policy);
                 let registration: ListenerReceiverSide = (&listener).into();
                 listeners.insert(id, listener);
                 return registration;
@@ -272,11 +334,11 @@ impl Inner {
         let mut listeners = self.listeners.lock().unwrap();
         if let Some(mut listener) = listeners.remove(&id) {
             drop(listeners);
-            let active_events: Vec<EventType> = EVENT_TYPE_ARRAY.into_iter().filter(|event| listener.has(*event)).collect();
-            for event in active_events.iter() {
-                self.clone().stop_notify(listener.id(), (*event).into())?;
-            }
+            // The listener is closed first so that it gets purged by dispatchers even if removing it fails
             listener.close();
+            for event in EVENT_TYPE_ARRAY.into_iter().filter(|event| listener.has(*event)) {
+                self.clone().try_send_dispatch(event, DispatchMessage::RemoveListener(id))?;
+            }
         }
         Ok(())
     }
@@ -290,6 +352,7 @@ impl Inner {
         match command {
             SubscribeCommand::Start => self.start_notify(id, notification_type),
             SubscribeCommand::Stop => self.stop_notify(id, notification_type),
+            SubscribeCommand::ModifyScope => self.modify_notify(id, notification_type),
         }
     }
 
@@ -302,7 +365,7 @@ impl Inner {
             // Any mutation in the listener will trigger a dispatch of a brand new ListenerSenderSide
             // eventually creating or replacing this listener in the matching dispatcher.
 
-            if listener.toggle(notification_type, true) {
+            if listener.toggle(notification_type, true)? {
                 let listener_sender_side = ListenerSenderSide::new(listener, self.sending_changed_utxos, event);
                 let msg = DispatchMessage::AddListener(listener.id(), Arc::new(listener_sender_side));
                 self.clone().try_send_dispatch(event, msg)?;
@@ -322,9 +385,31 @@ impl Inner {
         let event: EventType = (&notification_type).into();
         let mut listeners = self.listeners.lock().unwrap();
         if let Some(listener) = listeners.get_mut(&id) {
-            if listener.toggle(notification_type.clone(), false) {
+            if listener.toggle(notification_type.clone(), false)? {
                 trace!("[Notifier] stop notifying to {0} about {1:?}", id, notification_type);
-                let msg = DispatchMessage::RemoveListener(listener.id());
+                // A listener still having the event active only got its scope narrowed, so it is replaced
+                let msg = match listener.has(event) {
+                    true => {
+                        let listener_sender_side = ListenerSenderSide::new(listener, self.sending_changed_utxos, event);
+                        DispatchMessage::AddListener(listener.id(), Arc::new(listener_sender_side))
+                    }
+                    false => DispatchMessage::RemoveListener(listener.id()),
+                };
+                self.clone().try_send_dispatch(event, msg)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn modify_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> Result<()> {
+        let event: EventType = (&notification_type).into();
+        let mut listeners = self.listeners.lock().unwrap();
+        if let Some(listener) = listeners.get_mut(&id) {
+            if listener.modify_scope(notification_type.clone())? {
+                trace!("[Notifier] modify the scope of notifying to {0} about {1:?}", id, notification_type);
+                // The dispatcher replaces the listener at once, so that no notification is missed
+                let listener_sender_side = ListenerSenderSide::new(listener, self.sending_changed_utxos, event);
+                let msg = DispatchMessage::AddListener(listener.id(), Arc::new(listener_sender_side));
                 self.clone().try_send_dispatch(event, msg)?;
             }
         }
@@ -371,3 +456,221 @@ impl Inner {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        NewBlockTemplateNotification, RpcAcceptedTransactionIds, RpcAddress, RpcTransactionAcceptance, RpcTransactionScope,
+        RpcUtxoEntry, RpcUtxosByAddressesEntry, TransactionAcceptedNotification, TransactionRevertedNotification,
+        UtxosChangedNotification, VirtualSelectedParentChainChangedNotification,
+    };
+    use addresses::Prefix;
+    use consensus_core::tx::TransactionOutpoint;
+    use mining::block_template::builder::pay_to_address_script;
+    use std::time::{Duration, Instant};
+
+    fn address(id: u8) -> RpcAddress {
+        RpcAddress { prefix: Prefix::Mainnet, payload: vec![id; 32], version: 0 }
+    }
+
+    /// Adds a UTXO paying to each of the addresses
+    fn utxos_changed(ids: &[u8]) -> Arc<Notification> {
+        let added = ids
+            .iter()
+            .map(|&id| RpcUtxosByAddressesEntry {
+                address: None,
+                outpoint: TransactionOutpoint::new((id as u64).into(), 0),
+                utxo_entry: RpcUtxoEntry::new(1000, pay_to_address_script(&address(id)).unwrap(), 0, false),
+            })
+            .collect();
+        Arc::new(Notification::UtxosChanged(UtxosChangedNotification { added, removed: vec![] }))
+    }
+
+    /// Transactions identified by their id, each paying to the address of the same id
+    fn transactions(ids: &[u8]) -> Vec<RpcTransactionAcceptance> {
+        ids.iter()
+            .map(|&id| RpcTransactionAcceptance {
+                transaction_id: (id as u64).into(),
+                accepting_block_hash: 1.into(),
+                output_script_public_keys: vec![pay_to_address_script(&address(id)).unwrap()],
+                addresses: vec![],
+            })
+            .collect()
+    }
+
+    fn new_block_template() -> Arc<Notification> {
+        Arc::new(Notification::NewBlockTemplate(NewBlockTemplateNotification {}))
+    }
+
+    /// Receives a UtxosChanged notification and returns the ids of the addresses of its added UTXOs
+    async fn recv_utxo_addresses(listener: &ListenerReceiverSide) -> Vec<u8> {
+        let notification = async_std::future::timeout(Duration::from_secs(1), listener.recv_channel.recv()).await.unwrap().unwrap();
+        match &*notification {
+            Notification::UtxosChanged(notification) => {
+                notification.added.iter().map(|entry| entry.address.as_ref().unwrap().payload[0]).collect()
+            }
+            notification => panic!("unexpected notification {}", notification),
+        }
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let start = Instant::now();
+        while !condition() {
+            if start.elapsed() > Duration::from_secs(10) {
+                return false;
+            }
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        true
+    }
+
+    #[tokio::test]
+    async fn test_notifier_scopes_and_backpressure() {
+        let notifier = Arc::new(Notifier::new(None, None, ListenerUtxoNotificationFilterSetting::FilteredByAddress));
+        notifier.clone().start();
+
+        // UtxosChanged notifications are scoped by the addresses of the listener
+        let utxos_listener = notifier.register_new_listener(None);
+        notifier.as_ref().start_notify(utxos_listener.id, NotificationType::UtxosChanged(vec![address(1)])).unwrap();
+        for id in [2, 1] {
+            notifier.clone().notify(utxos_changed(&[id])).unwrap();
+        }
+        assert_eq!(recv_utxo_addresses(&utxos_listener).await, vec![1]);
+        notifier.as_ref().start_notify(utxos_listener.id, NotificationType::UtxosChanged(vec![address(2)])).unwrap();
+        notifier.as_ref().stop_notify(utxos_listener.id, NotificationType::UtxosChanged(vec![address(1)])).unwrap();
+        for id in [1, 2] {
+            notifier.clone().notify(utxos_changed(&[id])).unwrap();
+        }
+        assert_eq!(recv_utxo_addresses(&utxos_listener).await, vec![2]);
+
+        // The UTXOs out of the scope are left out of the notification
+        notifier.clone().notify(utxos_changed(&[1, 2, 3])).unwrap();
+        assert_eq!(recv_utxo_addresses(&utxos_listener).await, vec![2]);
+        let unsupported = RpcAddress { version: 9, ..address(4) };
+        assert!(notifier.as_ref().start_notify(utxos_listener.id, NotificationType::UtxosChanged(vec![unsupported])).is_err());
+
+        // A slow listener gets the newest notifications under a drop-oldest policy
+        let slow_listener = notifier.register_new_listener_with_policy(BackpressurePolicy::DropOldest(1));
+        notifier.as_ref().start_notify(slow_listener.id, NotificationType::NewBlockTemplate).unwrap();
+        for _ in 0..3 {
+            notifier.clone().notify(new_block_template()).unwrap();
+        }
+        assert!(wait_for(|| notifier.dropped_count(slow_listener.id) == Some(2)).await);
+        assert!(slow_listener.recv_channel.try_recv().is_ok());
+        assert!(slow_listener.recv_channel.try_recv().is_err());
+
+        // A listener closed by its receiver side is unregistered on the next notification
+        slow_listener.recv_channel.close();
+        notifier.clone().notify(new_block_template()).unwrap();
+        assert!(wait_for(|| notifier.listener_count() == 1).await);
+        assert_eq!(notifier.dropped_count(slow_listener.id), None);
+
+        notifier.unregister_listener(utxos_listener.id).unwrap();
+        assert_eq!(notifier.listener_count(), 0);
+        assert!(utxos_listener.recv_channel.is_closed());
+        notifier.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_notifier_modify_scope() {
+        let notifier = Arc::new(Notifier::new(None, None, ListenerUtxoNotificationFilterSetting::FilteredByAddress));
+        notifier.clone().start();
+
+        let listener = notifier.register_new_listener(None);
+        notifier.as_ref().start_notify(listener.id, NotificationType::UtxosChanged(vec![address(1), address(2)])).unwrap();
+        notifier.as_ref().modify_notify(listener.id, NotificationType::UtxosChanged(vec![address(3)])).unwrap();
+        for id in [1, 2, 3] {
+            notifier.clone().notify(utxos_changed(&[id])).unwrap();
+        }
+        assert_eq!(recv_utxo_addresses(&listener).await, vec![3]);
+
+        // The accepted transaction ids are only sent if included in the scope
+        let chain_changed = Arc::new(Notification::VirtualSelectedParentChainChanged(VirtualSelectedParentChainChangedNotification {
+            removed_chain_block_hashes: vec![],
+            added_chain_block_hashes: vec![1.into()],
+            accepted_transaction_ids: vec![RpcAcceptedTransactionIds {
+                accepting_block_hash: 1.into(),
+                accepted_transaction_ids: vec![],
+            }],
+        }));
+        notifier.as_ref().start_notify(listener.id, NotificationType::VirtualSelectedParentChainChanged(false)).unwrap();
+        notifier.clone().notify(chain_changed.clone()).unwrap();
+        notifier.as_ref().modify_notify(listener.id, NotificationType::VirtualSelectedParentChainChanged(true)).unwrap();
+        notifier.clone().notify(chain_changed).unwrap();
+        for expected_len in [0, 1] {
+            let notification =
+                async_std::future::timeout(Duration::from_secs(1), listener.recv_channel.recv()).await.unwrap().unwrap();
+            match &*notification {
+                Notification::VirtualSelectedParentChainChanged(notification) => {
+                    assert_eq!(notification.accepted_transaction_ids.len(), expected_len)
+                }
+                notification => panic!("unexpected notification {}", notification),
+            }
+        }
+
+        // Modifying an inactive subscription has no effect
+        notifier.as_ref().stop_notify(listener.id, NotificationType::UtxosChanged(vec![])).unwrap();
+        notifier.as_ref().modify_notify(listener.id, NotificationType::UtxosChanged(vec![address(1)])).unwrap();
+        notifier.clone().notify(utxos_changed(&[1])).unwrap();
+        assert!(async_std::future::timeout(Duration::from_millis(100), listener.recv_channel.recv()).await.is_err());
+        notifier.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_notifier_transaction_scopes() {
+        let notifier = Arc::new(Notifier::new(None, None, ListenerUtxoNotificationFilterSetting::FilteredByAddress));
+        notifier.clone().start();
+        let accepted =
+            |ids: &[u8]| Arc::new(Notification::TransactionAccepted(TransactionAcceptedNotification { accepted: transactions(ids) }));
+        let reverted =
+            |ids: &[u8]| Arc::new(Notification::TransactionReverted(TransactionRevertedNotification { reverted: transactions(ids) }));
+
+        // Transactions are in scope by id or by an output paying to an address of the scope
+        let listener = notifier.register_new_listener(None);
+        let scope = RpcTransactionScope::new(vec![1.into()], vec![address(2)]);
+        notifier.as_ref().start_notify(listener.id, NotificationType::TransactionAccepted(scope)).unwrap();
+        notifier.as_ref().start_notify(listener.id, NotificationType::TransactionReverted(RpcTransactionScope::default())).unwrap();
+        notifier.clone().notify(accepted(&[1, 2, 3])).unwrap();
+        let notification = async_std::future::timeout(Duration::from_secs(1), listener.recv_channel.recv()).await.unwrap().unwrap();
+        match &*notification {
+            Notification::TransactionAccepted(notification) => {
+                let ids = notification.accepted.iter().map(|transaction| transaction.transaction_id).collect::<Vec<_>>();
+                assert_eq!(ids, vec![1.into(), 2.into()]);
+                assert!(notification.accepted[0].addresses.is_empty());
+                assert_eq!(notification.accepted[1].addresses, vec![address(2)]);
+            }
+            notification => panic!("unexpected notification {}", notification),
+        }
+
+        // Once the id is removed from the scope, only the transactions paying to the address are notified, while
+        // reverted transactions are all notified
+        notifier
+            .as_ref()
+            .stop_notify(listener.id, NotificationType::TransactionAccepted(RpcTransactionScope::new(vec![1.into()], vec![])))
+            .unwrap();
+        notifier.clone().notify(accepted(&[1])).unwrap();
+        notifier.clone().notify(reverted(&[1, 3])).unwrap();
+        let notification = async_std::future::timeout(Duration::from_secs(1), listener.recv_channel.recv()).await.unwrap().unwrap();
+        match &*notification {
+            Notification::TransactionReverted(notification) => assert_eq!(notification.reverted.len(), 2),
+            notification => panic!("unexpected notification {}", notification),
+        }
+
+        // Stopping the last address of the scope stops the notifications
+        notifier
+            .as_ref()
+            .stop_notify(listener.id, NotificationType::TransactionAccepted(RpcTransactionScope::new(vec![], vec![address(2)])))
+            .unwrap();
+        notifier.clone().notify(accepted(&[2])).unwrap();
+        assert!(async_std::future::timeout(Duration::from_millis(100), listener.recv_channel.recv()).await.is_err());
+        assert!(notifier
+            .as_ref()
+            .start_notify(
+                listener.id,
+                NotificationType::TransactionAccepted(RpcTransactionScope::new(vec![], vec![RpcAddress { version: 9, ..address(4) }]))
+            )
+            .is_err());
+        notifier.stop().await.unwrap();
+    }
+}
@@ -8,7 +8,10 @@ use std::sync::{
 };
 extern crate derive_more;
 use super::{errors::Error, listener::ListenerID, message::SubscribeMessage, result::Result};
-use crate::{api::ops::SubscribeCommand, NotificationType, RpcResult};
+use crate::{
+    api::{ops::SubscribeCommand, rpc::DynRpcService},
+    NotificationType, RpcError, RpcResult,
+};
 use kaspa_utils::channel::Channel;
 
 /// A manager of subscriptions to notifications for registered listeners
@@ -17,6 +20,11 @@ pub trait SubscriptionManager: Send + Sync + Debug {
     async fn start_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()>;
     async fn stop_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()>;
 
+    /// Replaces the scope of an active subscription
+    async fn modify_notify(self: Arc<Self>, _id: ListenerID, _notification_type: NotificationType) -> RpcResult<()> {
+        Err(RpcError::UnsupportedFeature)
+    }
+
     async fn execute_notify_command(
         self: Arc<Self>,
         id: ListenerID,
@@ -26,12 +34,46 @@ pub trait SubscriptionManager: Send + Sync + Debug {
         match command {
             SubscribeCommand::Start => self.start_notify(id, notification_type).await,
             SubscribeCommand::Stop => self.stop_notify(id, notification_type).await,
+            SubscribeCommand::ModifyScope => self.modify_notify(id, notification_type).await,
         }
     }
 }
 
 pub type DynSubscriptionManager = Arc<dyn SubscriptionManager>;
 
+/// A [`SubscriptionManager`] executing the subscriptions into an RPC service, for transports relaying
+/// the notifications of the service they serve
+pub struct RpcServiceSubscriptionManager {
+    service: DynRpcService,
+}
+
+impl RpcServiceSubscriptionManager {
+    pub fn new(service: DynRpcService) -> Self {
+        Self { service }
+    }
+}
+
+impl Debug for RpcServiceSubscriptionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcServiceSubscriptionManager").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl SubscriptionManager for RpcServiceSubscriptionManager {
+    async fn start_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.service.start_notify(id, notification_type).await
+    }
+
+    async fn stop_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.service.stop_notify(id, notification_type).await
+    }
+
+    async fn modify_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.service.modify_notify(id, notification_type).await
+    }
+}
+
 /// A subscriber handling subscription messages executing them into a [SubscriptionManager].
 #[derive(Debug)]
 pub struct Subscriber {
@@ -0,0 +1,76 @@
+use crate::{
+    notify::{
+        errors::Error,
+        result::Result,
+        utxo_address_set::{with_script_public_keys, RpcUtxoAddressSet},
+    },
+    RpcTransactionAcceptance, RpcTransactionId, RpcTransactionScope,
+};
+use ahash::AHashSet;
+
+/// The transaction scope of a listener, with its addresses keyed by the script public key paying them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TransactionScope {
+    transaction_ids: AHashSet<RpcTransactionId>,
+    addresses: RpcUtxoAddressSet,
+}
+
+impl TransactionScope {
+    pub(crate) fn len(&self) -> usize {
+        self.transaction_ids.len() + self.addresses.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.transaction_ids.is_empty() && self.addresses.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.transaction_ids.clear();
+        self.addresses.clear();
+    }
+
+    pub(crate) fn extend(&mut self, other: TransactionScope) {
+        self.transaction_ids.extend(other.transaction_ids);
+        self.addresses.extend(other.addresses);
+    }
+
+    pub(crate) fn remove(&mut self, other: &TransactionScope) {
+        other.transaction_ids.iter().for_each(|transaction_id| {
+            self.transaction_ids.remove(transaction_id);
+        });
+        other.addresses.keys().for_each(|script_public_key| {
+            self.addresses.remove(script_public_key);
+        });
+    }
+
+    /// Returns the transactions in scope, along with the addresses of the scope their outputs pay to
+    pub(crate) fn in_scope(&self, transactions: &[RpcTransactionAcceptance]) -> Vec<RpcTransactionAcceptance> {
+        transactions
+            .iter()
+            .filter_map(|transaction| {
+                let paid_addresses = transaction.output_script_public_keys.iter().filter_map(|spk| self.addresses.get(spk));
+                let mut addresses = Vec::new();
+                for address in paid_addresses {
+                    if !addresses.contains(address) {
+                        addresses.push(address.clone());
+                    }
+                }
+                if addresses.is_empty() && !self.transaction_ids.contains(&transaction.transaction_id) {
+                    return None;
+                }
+                Some(RpcTransactionAcceptance { addresses, ..transaction.clone() })
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<&RpcTransactionScope> for TransactionScope {
+    type Error = Error;
+
+    fn try_from(item: &RpcTransactionScope) -> Result<Self> {
+        Ok(Self {
+            transaction_ids: item.transaction_ids.iter().copied().collect(),
+            addresses: with_script_public_keys(&item.addresses)?.into_iter().collect(),
+        })
+    }
+}
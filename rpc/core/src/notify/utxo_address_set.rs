@@ -1,4 +1,20 @@
-use crate::stubs::RpcUtxoAddress;
-use ahash::AHashSet;
+use crate::{
+    notify::{errors::Error, result::Result},
+    RpcAddress, RpcScriptPublicKey,
+};
+use ahash::AHashMap;
+use mining::block_template::builder::pay_to_address_script;
 
-pub type RpcUtxoAddressSet = AHashSet<RpcUtxoAddress>;
+/// A set of addresses, keyed by the script public key UTXO entries pay them with
+pub type RpcUtxoAddressSet = AHashMap<RpcScriptPublicKey, RpcAddress>;
+
+/// Pairs each address with the script public key paying to it
+pub(crate) fn with_script_public_keys(addresses: &[RpcAddress]) -> Result<Vec<(RpcScriptPublicKey, RpcAddress)>> {
+    addresses
+        .iter()
+        .map(|address| match pay_to_address_script(address) {
+            Ok(script_public_key) => Ok((script_public_key, address.clone())),
+            Err(err) => Err(Error::InvalidUtxoAddress(err.to_string())),
+        })
+        .collect()
+}
@@ -1,6 +1,6 @@
 use crate::notify::collector::CollectorFrom;
 use async_std::channel::{Receiver, Sender};
-use consensus_core::notify::Notification as ConsensusNotification;
+use consensus_core::notify::{Notification as ConsensusNotification, NotificationConsumer};
 use kaspa_utils::channel::Channel;
 use std::sync::Arc;
 
@@ -9,3 +9,24 @@ pub(crate) type ConsensusCollector = CollectorFrom<ConsensusNotification>;
 pub type ConsensusNotificationChannel = Channel<Arc<ConsensusNotification>>;
 pub type ConsensusNotificationSender = Sender<Arc<ConsensusNotification>>;
 pub type ConsensusNotificationReceiver = Receiver<Arc<ConsensusNotification>>;
+
+/// A consensus notification consumer forwarding all notifications into a [`ConsensusNotificationSender`].
+///
+/// Forwarding into a bounded channel blocks while the channel is full, so that the notifications queued meanwhile
+/// are handled by the [`consensus_core::notify::BackpressurePolicy`] the forwarder was registered with.
+pub struct ConsensusNotificationForwarder {
+    sender: ConsensusNotificationSender,
+}
+
+impl ConsensusNotificationForwarder {
+    pub fn new(sender: ConsensusNotificationSender) -> Self {
+        Self { sender }
+    }
+}
+
+impl NotificationConsumer for ConsensusNotificationForwarder {
+    fn consume(&self, notification: Arc<ConsensusNotification>) {
+        // Sending can only fail once the channel is closed
+        let _ = self.sender.send_blocking(notification);
+    }
+}
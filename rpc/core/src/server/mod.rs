@@ -1,15 +1,19 @@
-use std::sync::Arc;
+use std::sync::{atomic::AtomicBool, Arc};
 
 use consensus_core::api::DynConsensus;
+use index_core::{txindex::DynTxIndex, utxoindex::DynUtxoIndex};
 use kaspa_core::{
     task::service::{AsyncService, AsyncServiceFuture},
     trace,
 };
 use kaspa_utils::triggers::DuplexTrigger;
+use mining::manager::MiningManager;
 
-use self::{collector::ConsensusNotificationReceiver, service::RpcCoreService};
+use self::{collector::ConsensusNotificationReceiver, peers::DynPeerManager, perf::DynPerfMonitor, service::RpcCoreService};
 
 pub mod collector;
+pub mod peers;
+pub mod perf;
 pub mod service;
 
 const RPC_CORE_SERVICE: &str = "rpc-core-service";
@@ -21,8 +25,26 @@ pub struct RpcCoreServer {
 }
 
 impl RpcCoreServer {
-    pub fn new(consensus: DynConsensus, consensus_recv: ConsensusNotificationReceiver) -> Self {
-        let service = Arc::new(RpcCoreService::new(consensus, consensus_recv));
+    pub fn new(
+        consensus: DynConsensus,
+        mining_manager: Arc<MiningManager>,
+        tx_index: Option<DynTxIndex>,
+        utxo_index: Option<DynUtxoIndex>,
+        peer_manager: Option<DynPeerManager>,
+        perf_monitor: Option<DynPerfMonitor>,
+        is_ibd_running: Arc<AtomicBool>,
+        consensus_recv: ConsensusNotificationReceiver,
+    ) -> Self {
+        let service = Arc::new(RpcCoreService::new(
+            consensus,
+            mining_manager,
+            tx_index,
+            utxo_index,
+            peer_manager,
+            perf_monitor,
+            is_ibd_running,
+            consensus_recv,
+        ));
         Self { service, shutdown: DuplexTrigger::default() }
     }
 
@@ -0,0 +1,27 @@
+use crate::{RpcIpAddress, RpcPeerAddress, RpcPeerInfo, RpcResult};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Abstracts the P2P peer management exposed to node operators, bridging the connection, address and ban managers
+#[async_trait]
+pub trait PeerManagerApi: Send + Sync {
+    /// Returns the info of the currently connected peers
+    fn connected_peers(&self) -> Vec<RpcPeerInfo>;
+
+    /// Returns the peer addresses known to the address manager
+    fn known_addresses(&self) -> Vec<RpcPeerAddress>;
+
+    /// Returns the currently banned IPs
+    fn banned_ips(&self) -> Vec<RpcIpAddress>;
+
+    /// Requests a connection to `address`, reconnecting to it whenever the connection drops if `is_permanent`
+    fn add_peer(&self, address: RpcPeerAddress, is_permanent: bool);
+
+    /// Bans `ip` and disconnects the peers connected from it
+    async fn ban(&self, ip: RpcIpAddress) -> RpcResult<()>;
+
+    /// Lifts the ban of `ip`
+    fn unban(&self, ip: RpcIpAddress) -> RpcResult<()>;
+}
+
+pub type DynPeerManager = Arc<dyn PeerManagerApi>;
@@ -0,0 +1,10 @@
+use crate::RpcPerfMetrics;
+use std::sync::Arc;
+
+/// Abstracts the periodic sampling of the resource usage of the node
+pub trait PerfMonitorApi: Send + Sync {
+    /// Returns the latest sample, if any was taken yet
+    fn perf_metrics(&self) -> Option<RpcPerfMetrics>;
+}
+
+pub type DynPerfMonitor = Arc<dyn PerfMonitorApi>;
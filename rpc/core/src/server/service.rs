@@ -1,6 +1,10 @@
 //! Core server implementation for ClientAPI
 
-use super::collector::{ConsensusCollector, ConsensusNotificationReceiver};
+use super::{
+    collector::{ConsensusCollector, ConsensusNotificationReceiver},
+    peers::DynPeerManager,
+    perf::DynPerfMonitor,
+};
 use crate::{
     api::rpc::RpcApi,
     model::*,
@@ -9,24 +13,48 @@ use crate::{
         listener::{ListenerID, ListenerReceiverSide, ListenerUtxoNotificationFilterSetting},
         notifier::Notifier,
     },
-    FromRpcHex, Notification, NotificationType, RpcError, RpcResult,
+    Notification, NotificationType, RpcError, RpcResult,
 };
 use async_trait::async_trait;
 use consensus_core::{
-    api::DynConsensus,
+    api::{session::ConsensusSession, DynConsensus},
     block::Block,
-    coinbase::MinerData,
-    tx::{ScriptPublicKey, ScriptVec},
+    hashing,
+    tx::Transaction,
 };
 use hashes::Hash;
+use index_core::{
+    txindex::{DynTxIndex, TxIndexLookup},
+    utxoindex::DynUtxoIndex,
+};
 use kaspa_core::trace;
+use mining::{block_template::builder::pay_to_address_script, manager::MiningManager};
 use std::{
-    str::FromStr,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     vec,
 };
 
+/// The maximum number of added chain blocks returned by a single GetVirtualChainFromBlock call
+const MAX_VIRTUAL_CHAIN_BLOCKS: usize = 1_000;
+
+/// The maximum number of accepted transaction ids returned by a single GetVirtualChainFromBlock call
+const MAX_VIRTUAL_CHAIN_ACCEPTED_TRANSACTION_IDS: usize = 100_000;
+
+/// The maximum number of chain blocks returned by a single GetBlocksWithAcceptance call
+const MAX_BLOCKS_WITH_ACCEPTANCE: usize = 100;
+
+/// The maximum number of block hashes returned by a single GetBlocks call excluding the blocks
+const MAX_GET_BLOCKS_HASHES: usize = 1_000;
+
+/// The maximum number of blocks returned by a single GetBlocks call including the blocks
+const MAX_GET_BLOCKS_BLOCKS: usize = 100;
+
+/// The maximum number of blocks exported by a single DumpBlockRegion call
+const MAX_DUMP_BLOCK_REGION_BLOCKS: usize = 5_000;
+
 /// A service implementing the Rpc API at rpc_core level.
 ///
 /// Collects notifications from the consensus and forwards them to
@@ -46,11 +74,31 @@ use std::{
 /// Subscriber.
 pub struct RpcCoreService {
     consensus: DynConsensus,
+    mining_manager: Arc<MiningManager>,
+    /// The transaction index, if enabled
+    tx_index: Option<DynTxIndex>,
+    /// The UTXO index, if enabled
+    utxo_index: Option<DynUtxoIndex>,
+    /// The P2P peer management, if the node runs the P2P layer
+    peer_manager: Option<DynPeerManager>,
+    /// The resource usage sampling, if the node runs it
+    perf_monitor: Option<DynPerfMonitor>,
+    /// Whether the node is syncing with a peer, during which submitted blocks are rejected
+    is_ibd_running: Arc<AtomicBool>,
     notifier: Arc<Notifier>,
 }
 
 impl RpcCoreService {
-    pub fn new(consensus: DynConsensus, consensus_recv: ConsensusNotificationReceiver) -> Self {
+    pub fn new(
+        consensus: DynConsensus,
+        mining_manager: Arc<MiningManager>,
+        tx_index: Option<DynTxIndex>,
+        utxo_index: Option<DynUtxoIndex>,
+        peer_manager: Option<DynPeerManager>,
+        perf_monitor: Option<DynPerfMonitor>,
+        is_ibd_running: Arc<AtomicBool>,
+        consensus_recv: ConsensusNotificationReceiver,
+    ) -> Self {
         // TODO: instead of getting directly a DynConsensus, rely on some Context equivalent
         //       See app\rpc\rpccontext\context.go
         // TODO: the channel receiver should be obtained by registering to a consensus notification service
@@ -60,7 +108,7 @@ impl RpcCoreService {
         // TODO: Some consensus-compatible subscriber could be provided here
         let notifier = Arc::new(Notifier::new(Some(collector), None, ListenerUtxoNotificationFilterSetting::All));
 
-        Self { consensus, notifier }
+        Self { consensus, mining_manager, tx_index, utxo_index, peer_manager, perf_monitor, is_ibd_running, notifier }
     }
 
     pub fn start(&self) {
@@ -75,89 +123,373 @@ impl RpcCoreService {
     pub fn notifier(&self) -> Arc<Notifier> {
         self.notifier.clone()
     }
+
+    /// Builds the RPC block of a stored block along with its verbose data. Transactions are only included when
+    /// requested and the block has a body.
+    fn get_rpc_block(session: &ConsensusSession, hash: Hash, include_transactions: bool) -> RpcResult<RpcBlock> {
+        let not_found = || RpcError::General(format!("Block {0} not found", hash));
+        let header = session.get_header(hash).ok_or_else(not_found)?;
+        let dag_data = session.get_block_dag_data(hash).ok_or_else(not_found)?;
+        let transactions =
+            if dag_data.is_header_only { Arc::default() } else { session.get_block(hash).ok_or_else(not_found)?.transactions };
+        let verbose_data = RpcBlockVerboseData {
+            hash,
+            difficulty: dag_data.difficulty,
+            selected_parent_hash: dag_data.selected_parent,
+            transaction_ids: transactions.iter().map(|tx| tx.id()).collect(),
+            is_header_only: dag_data.is_header_only,
+            blue_score: dag_data.blue_score,
+            children_hashes: dag_data.children,
+            merge_set_blues_hashes: dag_data.mergeset_blues,
+            merge_set_reds_hashes: dag_data.mergeset_reds,
+            is_chain_block: dag_data.is_chain_block,
+            confirmations: session.get_confirmations(hash).unwrap_or_default(),
+        };
+        Ok(RpcBlock {
+            header: (*header).clone(),
+            transactions: if include_transactions { transactions.iter().map(RpcTransaction::from).collect() } else { vec![] },
+            verbose_data: Some(verbose_data),
+        })
+    }
+
+    fn tx_index(&self) -> RpcResult<&DynTxIndex> {
+        self.tx_index.as_ref().ok_or_else(|| RpcError::General("Method unavailable when kaspad is run without --txindex".to_string()))
+    }
+
+    fn utxo_index(&self) -> RpcResult<&DynUtxoIndex> {
+        self.utxo_index
+            .as_ref()
+            .ok_or_else(|| RpcError::General("Method unavailable when kaspad is run without --utxoindex".to_string()))
+    }
+
+    fn peer_manager(&self) -> RpcResult<&DynPeerManager> {
+        self.peer_manager.as_ref().ok_or_else(|| RpcError::General("Method unavailable when the node runs without P2P".to_string()))
+    }
 }
 
 #[async_trait]
 impl RpcApi for RpcCoreService {
     async fn submit_block_call(&self, request: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
-        let try_block: RpcResult<Block> = (&request.block).try_into();
+        let try_block: RpcResult<Block> = request.block.try_into();
         if let Err(ref err) = try_block {
             trace!("incoming SubmitBlockRequest with block conversion error: {}", err);
         }
         let block = try_block?;
 
-        // We recreate a RpcBlock for the BlockAdded notification.
-        // This guaranties that we have the right hash.
-        // TODO: remove it when consensus emit a BlockAdded notification.
-        let rpc_block: RpcBlock = (&block).into();
-
         trace!("incoming SubmitBlockRequest for block {}", block.header.hash);
 
-        let result = match self.consensus.clone().validate_and_insert_block(block, true).await {
-            Ok(_) => Ok(SubmitBlockResponse { report: SubmitBlockReport::Success }),
+        if self.is_ibd_running.load(Ordering::SeqCst) {
+            return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD) });
+        }
+
+        // Wait for the block to be fully processed by the pipeline, including the virtual state update
+        match self.consensus.clone().validate_and_insert_block(block, true).await {
+            Ok(_) => {
+                // Emit a NewBlockTemplate notification
+                self.notifier.clone().notify(Arc::new(Notification::NewBlockTemplate(NewBlockTemplateNotification {}))).unwrap();
+                Ok(SubmitBlockResponse { report: SubmitBlockReport::Success })
+            }
             Err(err) => {
                 trace!("submit block error: {}", err);
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) })
-            } // TODO: handle also the IsInIBD reject reason
-        };
+                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid(err.to_string())) })
+            }
+        }
+    }
 
-        // Notify about new added block
-        // TODO: let consensus emit this notification through an event channel
-        self.notifier.clone().notify(Arc::new(Notification::BlockAdded(BlockAddedNotification { block: rpc_block }))).unwrap();
+    async fn get_block_template_call(&self, request: GetBlockTemplateRequest) -> RpcResult<GetBlockTemplateResponse> {
+        trace!("incoming GetBlockTemplate request");
 
-        // Emit a NewBlockTemplate notification
-        self.notifier.clone().notify(Arc::new(Notification::NewBlockTemplate(NewBlockTemplateNotification {}))).unwrap();
+        let block_template = self.mining_manager.get_block_template(&request.pay_address, request.extra_data)?;
 
-        result
+        Ok(block_template.into())
     }
 
-    async fn get_block_template_call(&self, request: GetBlockTemplateRequest) -> RpcResult<GetBlockTemplateResponse> {
-        trace!("incoming GetBlockTemplate request");
+    async fn get_block_call(&self, request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
+        let session = ConsensusSession::acquire(&self.consensus);
+        Ok(GetBlockResponse { block: Self::get_rpc_block(&session, request.hash, request.include_transactions)? })
+    }
 
-        // TODO: Replace this hack by a call to build the script (some txscript.PayToAddrScript(payAddress) equivalent).
-        //       See app\rpc\rpchandlers\get_block_template.go HandleGetBlockTemplate
-        const ADDRESS_PUBLIC_KEY_SCRIPT_PUBLIC_KEY_VERSION: u16 = 0;
-        const OP_CHECK_SIG: u8 = 172;
-        let mut script_addr = request.pay_address.payload.clone();
-        let mut pay_to_pub_key_script = Vec::with_capacity(34);
-        pay_to_pub_key_script.push(u8::try_from(script_addr.len()).unwrap());
-        pay_to_pub_key_script.append(&mut script_addr);
-        pay_to_pub_key_script.push(OP_CHECK_SIG);
+    async fn get_blocks_call(&self, request: GetBlocksRequest) -> RpcResult<GetBlocksResponse> {
+        let low_hash = request.low_hash.unwrap_or_else(|| self.consensus.clone().get_pruning_point());
+        let max_blocks = if request.include_blocks { MAX_GET_BLOCKS_BLOCKS } else { MAX_GET_BLOCKS_HASHES };
+        let session = ConsensusSession::acquire(&self.consensus);
+        let (block_hashes, _) = self.consensus.clone().get_hashes_between(low_hash, session.get_sink(), max_blocks)?;
+        let blocks = if request.include_blocks {
+            block_hashes
+                .iter()
+                .map(|&hash| Self::get_rpc_block(&session, hash, request.include_transactions))
+                .collect::<RpcResult<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+        Ok(GetBlocksResponse { block_hashes, blocks })
+    }
 
-        let script = ScriptVec::from_vec(pay_to_pub_key_script);
+    async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
+        let transaction: Transaction = request.transaction.try_into()?;
+        let transaction_id = transaction.id();
+        trace!("incoming SubmitTransactionRequest for transaction {}", transaction_id);
+        self.mining_manager.submit_transaction(transaction, request.allow_orphan)?;
+        Ok(SubmitTransactionResponse { transaction_id })
+    }
 
-        let script_public_key = ScriptPublicKey::new(ADDRESS_PUBLIC_KEY_SCRIPT_PUBLIC_KEY_VERSION, script);
-        let miner_data: MinerData = MinerData::new(script_public_key, request.extra_data);
-        // TODO: handle error properly when managed through mining manager
-        let block_template = self.consensus.clone().build_block_template(miner_data, vec![]).unwrap();
+    async fn get_virtual_chain_from_block_call(
+        &self,
+        request: GetVirtualChainFromBlockRequest,
+    ) -> RpcResult<GetVirtualChainFromBlockResponse> {
+        let batch = self.consensus.clone().get_virtual_chain_from_block(
+            request.start_hash,
+            request.include_accepted_transaction_ids,
+            MAX_VIRTUAL_CHAIN_BLOCKS,
+            MAX_VIRTUAL_CHAIN_ACCEPTED_TRANSACTION_IDS,
+        )?;
+        Ok((&batch).into())
+    }
 
-        Ok((&block_template).into())
+    async fn get_blocks_with_acceptance_call(
+        &self,
+        request: GetBlocksWithAcceptanceRequest,
+    ) -> RpcResult<GetBlocksWithAcceptanceResponse> {
+        let batch = self.consensus.clone().get_chain_blocks_with_acceptance(
+            request.low_hash,
+            request.high_hash,
+            MAX_BLOCKS_WITH_ACCEPTANCE,
+        )?;
+        Ok(batch.into())
+    }
+
+    async fn get_transaction_acceptance_call(
+        &self,
+        request: GetTransactionAcceptanceRequest,
+    ) -> RpcResult<GetTransactionAcceptanceResponse> {
+        let tx_index = self.tx_index()?;
+        let retention_root_daa_score = tx_index.stats().retention_root_daa_score;
+        let mut response = GetTransactionAcceptanceResponse {
+            accepting_block_hash: None,
+            accepting_daa_score: 0,
+            is_beyond_retention: false,
+            retention_root_daa_score,
+        };
+        match tx_index.get_transaction_acceptance(request.transaction_id) {
+            TxIndexLookup::Accepted(acceptance) => {
+                response.accepting_block_hash = Some(acceptance.accepting_block_hash);
+                response.accepting_daa_score = acceptance.accepting_daa_score;
+            }
+            TxIndexLookup::NotFound => {}
+            TxIndexLookup::BeyondRetention => response.is_beyond_retention = true,
+        }
+        Ok(response)
     }
 
-    async fn get_block_call(&self, req: GetBlockRequest) -> RpcResult<GetBlockResponse> {
-        // TODO: Remove the following test when consensus is used to fetch data
+    async fn get_transaction_by_id_call(&self, request: GetTransactionByIdRequest) -> RpcResult<GetTransactionByIdResponse> {
+        let acceptance = match self.tx_index()?.get_transaction_acceptance(request.transaction_id) {
+            TxIndexLookup::Accepted(acceptance) => acceptance,
+            TxIndexLookup::NotFound => {
+                return Err(RpcError::General(format!("Transaction {0} not found in the transaction index", request.transaction_id)))
+            }
+            TxIndexLookup::BeyondRetention => {
+                return Err(RpcError::General(format!(
+                    "Transaction {0} not found, it might have been accepted before the transaction index retention window",
+                    request.transaction_id
+                )))
+            }
+        };
+        let session = ConsensusSession::acquire(&self.consensus);
+        let transaction = session.get_block(acceptance.including_block_hash).and_then(|block| {
+            let transaction = block.transactions.iter().find(|transaction| transaction.id() == request.transaction_id)?;
+            let verbose_data = RpcTransactionVerboseData {
+                transaction_id: request.transaction_id,
+                hash: hashing::tx::hash(transaction),
+                mass: self.consensus.clone().calculate_transaction_mass(transaction),
+                block_hash: acceptance.including_block_hash,
+                block_time: block.header.timestamp.as_millis(),
+                confirmations: session.get_confirmations(acceptance.accepting_block_hash).unwrap_or_default(),
+            };
+            Some(RpcTransaction { verbose_data: Some(verbose_data), ..RpcTransaction::from(transaction) })
+        });
+        Ok(GetTransactionByIdResponse {
+            transaction,
+            including_block_hash: acceptance.including_block_hash,
+            accepting_block_hash: acceptance.accepting_block_hash,
+            accepting_daa_score: acceptance.accepting_daa_score,
+        })
+    }
 
-        // This is a test to simulate a consensus error
-        if req.hash.as_bytes()[0] == 0 {
-            return Err(RpcError::General(format!("Block {0} not found", req.hash)));
+    async fn get_utxos_by_addresses_call(&self, request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
+        let utxo_index = self.utxo_index()?;
+        let mut entries = vec![];
+        for address in request.addresses {
+            let script_public_key = pay_to_address_script(&address)?;
+            entries.extend(
+                utxo_index
+                    .get_utxos_by_script_public_key(&script_public_key)
+                    .into_iter()
+                    .map(|(outpoint, utxo_entry)| RpcUtxosByAddressesEntry { address: Some(address.clone()), outpoint, utxo_entry }),
+            );
         }
+        Ok(GetUtxosByAddressesResponse { entries })
+    }
 
-        // TODO: query info from consensus and use it to build the response
-        Ok(GetBlockResponse { block: create_dummy_rpc_block() })
+    async fn get_balance_by_address_call(&self, request: GetBalanceByAddressRequest) -> RpcResult<GetBalanceByAddressResponse> {
+        let utxo_index = self.utxo_index()?;
+        let script_public_key = pay_to_address_script(&request.address)?;
+        Ok(GetBalanceByAddressResponse { balance: utxo_index.get_balance_by_script_public_key(&script_public_key) })
     }
 
     async fn get_info_call(&self, _req: GetInfoRequest) -> RpcResult<GetInfoResponse> {
-        // TODO: query info from consensus and use it to build the response
+        // TODO: query the remaining info from the p2p and index services
         Ok(GetInfoResponse {
             p2p_id: "test".to_string(),
-            mempool_size: 1,
-            server_version: "0.12.8".to_string(),
-            is_utxo_indexed: false,
+            mempool_size: self.mining_manager.mempool().len() as u64,
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            is_utxo_indexed: self.utxo_index.is_some(),
             is_synced: false,
             has_notify_command: true,
         })
     }
 
+    async fn get_block_dag_info_call(&self, _req: GetBlockDagInfoRequest) -> RpcResult<GetBlockDagInfoResponse> {
+        let info = self.consensus.clone().get_block_dag_info();
+        Ok(GetBlockDagInfoResponse {
+            network_name: info.network_name,
+            block_count: info.block_count,
+            header_count: info.header_count,
+            tip_hashes: info.tip_hashes,
+            difficulty: info.difficulty,
+            past_median_time: info.past_median_time,
+            virtual_parent_hashes: info.virtual_parent_hashes,
+            pruning_point_hash: info.pruning_point_hash,
+            virtual_daa_score: info.virtual_daa_score,
+        })
+    }
+
+    async fn get_chain_throughput_stats_call(
+        &self,
+        req: GetChainThroughputStatsRequest,
+    ) -> RpcResult<GetChainThroughputStatsResponse> {
+        let stats = self.consensus.clone().get_chain_throughput_stats(req.window_size);
+        let max_block_mass = self.consensus.clone().max_block_mass();
+        Ok(GetChainThroughputStatsResponse {
+            chain_block_count: stats.chain_block_count,
+            merged_block_count: stats.merged_block_count,
+            accepted_transaction_count: stats.accepted_transaction_count,
+            accepted_mass: stats.accepted_mass,
+            total_fees: stats.total_fees,
+            time_span_millis: stats.time_span_millis,
+            transactions_per_second: stats.transactions_per_second(),
+            mass_utilization: stats.mass_utilization(max_block_mass),
+        })
+    }
+
+    async fn revalidate_block_call(&self, req: RevalidateBlockRequest) -> RpcResult<RevalidateBlockResponse> {
+        let report = self
+            .consensus
+            .clone()
+            .revalidate_block(req.hash)
+            .ok_or_else(|| RpcError::General(format!("Block {} not found", req.hash)))?;
+        Ok(RevalidateBlockResponse {
+            stored_status: format!("{:?}", report.stored_status),
+            body_revalidated: report.body_revalidated,
+            discrepancies: report.discrepancies(),
+        })
+    }
+
+    async fn get_coinbase_preview_call(&self, request: GetCoinbasePreviewRequest) -> RpcResult<GetCoinbasePreviewResponse> {
+        let preview = self.mining_manager.get_coinbase_preview(&request.pay_address, request.extra_data)?;
+        Ok((&preview).into())
+    }
+
+    async fn get_mempool_fee_rate_histogram_call(
+        &self,
+        _request: GetMempoolFeeRateHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeRateHistogramResponse> {
+        let buckets = self.mining_manager.get_fee_rate_histogram();
+        Ok(GetMempoolFeeRateHistogramResponse {
+            buckets: buckets.iter().map(RpcFeeRateHistogramBucket::from).collect(),
+            max_block_mass: self.consensus.clone().max_block_mass(),
+        })
+    }
+
+    async fn get_metrics_call(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse> {
+        let drift = self.consensus.clone().get_daa_score_drift();
+        Ok(GetMetricsResponse {
+            server_time: drift.now,
+            consensus_metrics: request.consensus_metrics.then(|| RpcConsensusMetrics::from(&drift)),
+            perf_metrics: self.perf_monitor.as_ref().filter(|_| request.perf_metrics).and_then(|monitor| monitor.perf_metrics()),
+        })
+    }
+
+    async fn get_payload_call(&self, request: GetPayloadRequest) -> RpcResult<GetPayloadResponse> {
+        let payload = self
+            .consensus
+            .clone()
+            .get_payload(request.payload_hash)
+            .ok_or_else(|| RpcError::General(format!("Payload {0} not found", request.payload_hash)))?;
+        Ok(GetPayloadResponse { payload: payload.to_vec() })
+    }
+
+    async fn get_mempool_entry_call(&self, request: GetMempoolEntryRequest) -> RpcResult<GetMempoolEntryResponse> {
+        let entry = self
+            .mining_manager
+            .get_mempool_entry(&request.transaction_id, request.include_orphan_pool, request.filter_transaction_pool)
+            .ok_or_else(|| RpcError::General(format!("Transaction {0} not found in the mempool", request.transaction_id)))?;
+        Ok(GetMempoolEntryResponse { entry: (&entry).into() })
+    }
+
+    async fn get_mempool_entries_call(&self, request: GetMempoolEntriesRequest) -> RpcResult<GetMempoolEntriesResponse> {
+        let entries = self.mining_manager.get_mempool_entries(
+            request.include_orphan_pool,
+            request.filter_transaction_pool,
+            &request.addresses,
+        )?;
+        Ok(GetMempoolEntriesResponse { entries: entries.iter().map(RpcMempoolEntry::from).collect() })
+    }
+
+    async fn get_connected_peer_info_call(&self, _request: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse> {
+        Ok(GetConnectedPeerInfoResponse { peer_info: self.peer_manager()?.connected_peers() })
+    }
+
+    async fn get_peer_addresses_call(&self, _request: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse> {
+        let peer_manager = self.peer_manager()?;
+        Ok(GetPeerAddressesResponse { known_addresses: peer_manager.known_addresses(), banned_addresses: peer_manager.banned_ips() })
+    }
+
+    async fn add_peer_call(&self, request: AddPeerRequest) -> RpcResult<AddPeerResponse> {
+        self.peer_manager()?.add_peer(request.peer_address, request.is_permanent);
+        Ok(AddPeerResponse {})
+    }
+
+    async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse> {
+        self.peer_manager()?.ban(request.ip).await?;
+        Ok(BanResponse {})
+    }
+
+    async fn unban_call(&self, request: UnbanRequest) -> RpcResult<UnbanResponse> {
+        self.peer_manager()?.unban(request.ip)?;
+        Ok(UnbanResponse {})
+    }
+
+    async fn pin_blocks_call(&self, request: PinBlocksRequest) -> RpcResult<PinBlocksResponse> {
+        self.consensus.clone().pin_blocks(&request.hashes)?;
+        Ok(PinBlocksResponse { pinned_hashes: self.consensus.clone().get_pinned_blocks() })
+    }
+
+    async fn unpin_blocks_call(&self, request: UnpinBlocksRequest) -> RpcResult<UnpinBlocksResponse> {
+        self.consensus.clone().unpin_blocks(&request.hashes);
+        Ok(UnpinBlocksResponse { pinned_hashes: self.consensus.clone().get_pinned_blocks() })
+    }
+
+    async fn dump_block_region_call(&self, request: DumpBlockRegionRequest) -> RpcResult<DumpBlockRegionResponse> {
+        let session = ConsensusSession::acquire(&self.consensus);
+        let hashes = self.consensus.clone().get_block_region(request.low_hash, request.high_hash, MAX_DUMP_BLOCK_REGION_BLOCKS)?;
+        let blocks = hashes
+            .iter()
+            .map(|&hash| Self::get_rpc_block(&session, hash, request.include_transactions))
+            .collect::<RpcResult<Vec<_>>>()?;
+        let json = serde_json::to_string_pretty(&blocks).map_err(|err| RpcError::General(err.to_string()))?;
+        Ok(DumpBlockRegionResponse { json })
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -185,39 +517,10 @@ impl RpcApi for RpcCoreService {
         self.notifier.stop_notify(id, notification_type)?;
         Ok(())
     }
-}
 
-// TODO: Remove the following function when consensus is used to fetch data
-fn create_dummy_rpc_block() -> RpcBlock {
-    let sel_parent_hash = Hash::from_str("5963be67f12da63004ce1baceebd7733c4fb601b07e9b0cfb447a3c5f4f3c4f0").unwrap();
-    RpcBlock {
-        header: RpcHeader {
-            hash: Hash::from_str("8270e63a0295d7257785b9c9b76c9a2efb7fb8d6ac0473a1bff1571c5030e995").unwrap(),
-            version: 1,
-            parents_by_level: vec![],
-            hash_merkle_root: Hash::from_str("4b5a041951c4668ecc190c6961f66e54c1ce10866bef1cf1308e46d66adab270").unwrap(),
-            accepted_id_merkle_root: Hash::from_str("1a1310d49d20eab15bf62c106714bdc81e946d761701e81fabf7f35e8c47b479").unwrap(),
-            utxo_commitment: Hash::from_str("e7cdeaa3a8966f3fff04e967ed2481615c76b7240917c5d372ee4ed353a5cc15").unwrap(),
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
-            bits: 1,
-            nonce: 1234,
-            daa_score: 123456,
-            blue_work: RpcBlueWorkType::from_rpc_hex("1234567890abcdef").unwrap(),
-            pruning_point: Hash::from_str("7190c08d42a0f7994b183b52e7ef2f99bac0b91ef9023511cadf4da3a2184b16").unwrap(),
-            blue_score: 12345678901,
-        },
-        transactions: vec![],
-        verbose_data: Some(RpcBlockVerboseData {
-            hash: Hash::from_str("8270e63a0295d7257785b9c9b76c9a2efb7fb8d6ac0473a1bff1571c5030e995").unwrap(),
-            difficulty: 5678.0,
-            selected_parent_hash: sel_parent_hash,
-            transaction_ids: vec![],
-            is_header_only: true,
-            blue_score: 98765,
-            children_hashes: vec![],
-            merge_set_blues_hashes: vec![],
-            merge_set_reds_hashes: vec![],
-            is_chain_block: true,
-        }),
+    /// Replace the scope of the notifications of some type sent to a listener.
+    async fn modify_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.notifier.modify_notify(id, notification_type)?;
+        Ok(())
     }
 }
@@ -12,16 +12,6 @@ pub enum NetworkType {
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct Address;
 
-pub type RpcUtxoAddress = Vec<u8>;
-
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-pub struct UtxosChangedNotification {
-    pub utxo_address: RpcUtxoAddress,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-pub struct VirtualSelectedParentChainChangedNotification;
-
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct FinalityConflictNotification;
 
@@ -13,21 +13,36 @@ use rpc_core::{
         notifier::Notifier,
         subscriber::Subscriber,
     },
-    GetBlockRequest, GetBlockResponse, GetBlockTemplateRequest, GetBlockTemplateResponse, GetInfoRequest, GetInfoResponse,
-    NotificationType, RpcError, RpcResult, SubmitBlockRequest, SubmitBlockResponse,
+    AddPeerRequest, AddPeerResponse, BanRequest, BanResponse, DumpBlockRegionRequest, DumpBlockRegionResponse,
+    GetBalanceByAddressRequest, GetBalanceByAddressResponse, GetBlockDagInfoRequest, GetBlockDagInfoResponse, GetBlockRequest,
+    GetBlockResponse, GetBlockTemplateRequest, GetBlockTemplateResponse, GetBlocksRequest, GetBlocksResponse,
+    GetBlocksWithAcceptanceRequest, GetBlocksWithAcceptanceResponse, GetChainThroughputStatsRequest, GetChainThroughputStatsResponse,
+    GetCoinbasePreviewRequest, GetCoinbasePreviewResponse, GetConnectedPeerInfoRequest, GetConnectedPeerInfoResponse, GetInfoRequest,
+    GetInfoResponse, GetMempoolEntriesRequest, GetMempoolEntriesResponse, GetMempoolEntryRequest, GetMempoolEntryResponse,
+    GetMempoolFeeRateHistogramRequest, GetMempoolFeeRateHistogramResponse, GetMetricsRequest, GetMetricsResponse, GetPayloadRequest,
+    GetPayloadResponse, GetPeerAddressesRequest, GetPeerAddressesResponse, GetTransactionAcceptanceRequest,
+    GetTransactionAcceptanceResponse, GetTransactionByIdRequest, GetTransactionByIdResponse, GetUtxosByAddressesRequest,
+    GetUtxosByAddressesResponse, GetVirtualChainFromBlockRequest, GetVirtualChainFromBlockResponse, NotificationType,
+    PinBlocksRequest, PinBlocksResponse, RevalidateBlockRequest, RevalidateBlockResponse, RpcError, RpcResult, SubmitBlockRequest,
+    SubmitBlockResponse, SubmitTransactionRequest, SubmitTransactionResponse, UnbanRequest, UnbanResponse, UnpinBlocksRequest,
+    UnpinBlocksResponse,
 };
 
 mod errors;
 mod resolver;
 mod result;
 
-pub struct RpcApiGrpc {
+/// A gRPC client of a kaspad node, implementing [`RpcApi`].
+///
+/// Requests failing to get a response within the resolver request timeout fail with a timeout error. Notifications
+/// are received by the listeners registered with [`RpcApi::register_new_listener`] once the client is started.
+pub struct GrpcClient {
     inner: Arc<Resolver>,
     notifier: Arc<Notifier>,
 }
 
-impl RpcApiGrpc {
-    pub async fn connect(address: String) -> Result<RpcApiGrpc> {
+impl GrpcClient {
+    pub async fn connect(address: String) -> Result<GrpcClient> {
         let notify_channel = NotificationChannel::default();
         let inner = Resolver::connect(address, notify_channel.sender()).await?;
         let collector = Arc::new(RpcCoreCollector::new(notify_channel.receiver()));
@@ -59,7 +74,7 @@ impl RpcApiGrpc {
 }
 
 #[async_trait]
-impl RpcApi for RpcApiGrpc {
+impl RpcApi for GrpcClient {
     async fn submit_block_call(&self, request: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
         self.inner.clone().call(RpcApiOps::SubmitBlock, request).await?.as_ref().try_into()
     }
@@ -72,10 +87,125 @@ impl RpcApi for RpcApiGrpc {
         self.inner.clone().call(RpcApiOps::GetBlock, request).await?.as_ref().try_into()
     }
 
+    async fn get_blocks_call(&self, request: GetBlocksRequest) -> RpcResult<GetBlocksResponse> {
+        self.inner.clone().call(RpcApiOps::GetBlocks, request).await?.as_ref().try_into()
+    }
+
+    async fn get_utxos_by_addresses_call(&self, request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
+        self.inner.clone().call(RpcApiOps::GetUtxosByAddresses, request).await?.as_ref().try_into()
+    }
+
+    async fn get_balance_by_address_call(&self, request: GetBalanceByAddressRequest) -> RpcResult<GetBalanceByAddressResponse> {
+        self.inner.clone().call(RpcApiOps::GetBalanceByAddress, request).await?.as_ref().try_into()
+    }
+
+    async fn get_mempool_entry_call(&self, request: GetMempoolEntryRequest) -> RpcResult<GetMempoolEntryResponse> {
+        self.inner.clone().call(RpcApiOps::GetMempoolEntry, request).await?.as_ref().try_into()
+    }
+
+    async fn get_mempool_entries_call(&self, request: GetMempoolEntriesRequest) -> RpcResult<GetMempoolEntriesResponse> {
+        self.inner.clone().call(RpcApiOps::GetMempoolEntries, request).await?.as_ref().try_into()
+    }
+
+    async fn get_block_dag_info_call(&self, request: GetBlockDagInfoRequest) -> RpcResult<GetBlockDagInfoResponse> {
+        self.inner.clone().call(RpcApiOps::GetBlockDagInfo, request).await?.as_ref().try_into()
+    }
+
     async fn get_info_call(&self, request: GetInfoRequest) -> RpcResult<GetInfoResponse> {
         self.inner.clone().call(RpcApiOps::GetInfo, request).await?.as_ref().try_into()
     }
 
+    async fn get_chain_throughput_stats_call(
+        &self,
+        request: GetChainThroughputStatsRequest,
+    ) -> RpcResult<GetChainThroughputStatsResponse> {
+        self.inner.clone().call(RpcApiOps::GetChainThroughputStats, request).await?.as_ref().try_into()
+    }
+
+    async fn revalidate_block_call(&self, request: RevalidateBlockRequest) -> RpcResult<RevalidateBlockResponse> {
+        self.inner.clone().call(RpcApiOps::RevalidateBlock, request).await?.as_ref().try_into()
+    }
+
+    async fn get_coinbase_preview_call(&self, request: GetCoinbasePreviewRequest) -> RpcResult<GetCoinbasePreviewResponse> {
+        self.inner.clone().call(RpcApiOps::GetCoinbasePreview, request).await?.as_ref().try_into()
+    }
+
+    async fn get_mempool_fee_rate_histogram_call(
+        &self,
+        request: GetMempoolFeeRateHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeRateHistogramResponse> {
+        self.inner.clone().call(RpcApiOps::GetMempoolFeeRateHistogram, request).await?.as_ref().try_into()
+    }
+
+    async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
+        self.inner.clone().call(RpcApiOps::SubmitTransaction, request).await?.as_ref().try_into()
+    }
+
+    async fn get_virtual_chain_from_block_call(
+        &self,
+        request: GetVirtualChainFromBlockRequest,
+    ) -> RpcResult<GetVirtualChainFromBlockResponse> {
+        self.inner.clone().call(RpcApiOps::GetVirtualChainFromBlock, request).await?.as_ref().try_into()
+    }
+
+    async fn get_transaction_acceptance_call(
+        &self,
+        request: GetTransactionAcceptanceRequest,
+    ) -> RpcResult<GetTransactionAcceptanceResponse> {
+        self.inner.clone().call(RpcApiOps::GetTransactionAcceptance, request).await?.as_ref().try_into()
+    }
+
+    async fn get_transaction_by_id_call(&self, request: GetTransactionByIdRequest) -> RpcResult<GetTransactionByIdResponse> {
+        self.inner.clone().call(RpcApiOps::GetTransactionById, request).await?.as_ref().try_into()
+    }
+
+    async fn get_blocks_with_acceptance_call(
+        &self,
+        request: GetBlocksWithAcceptanceRequest,
+    ) -> RpcResult<GetBlocksWithAcceptanceResponse> {
+        self.inner.clone().call(RpcApiOps::GetBlocksWithAcceptance, request).await?.as_ref().try_into()
+    }
+
+    async fn get_metrics_call(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse> {
+        self.inner.clone().call(RpcApiOps::GetMetrics, request).await?.as_ref().try_into()
+    }
+
+    async fn get_payload_call(&self, request: GetPayloadRequest) -> RpcResult<GetPayloadResponse> {
+        self.inner.clone().call(RpcApiOps::GetPayload, request).await?.as_ref().try_into()
+    }
+
+    async fn get_peer_addresses_call(&self, request: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse> {
+        self.inner.clone().call(RpcApiOps::GetPeerAddresses, request).await?.as_ref().try_into()
+    }
+
+    async fn get_connected_peer_info_call(&self, request: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse> {
+        self.inner.clone().call(RpcApiOps::GetConnectedPeerInfo, request).await?.as_ref().try_into()
+    }
+
+    async fn add_peer_call(&self, request: AddPeerRequest) -> RpcResult<AddPeerResponse> {
+        self.inner.clone().call(RpcApiOps::AddPeer, request).await?.as_ref().try_into()
+    }
+
+    async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse> {
+        self.inner.clone().call(RpcApiOps::Ban, request).await?.as_ref().try_into()
+    }
+
+    async fn unban_call(&self, request: UnbanRequest) -> RpcResult<UnbanResponse> {
+        self.inner.clone().call(RpcApiOps::Unban, request).await?.as_ref().try_into()
+    }
+
+    async fn pin_blocks_call(&self, request: PinBlocksRequest) -> RpcResult<PinBlocksResponse> {
+        self.inner.clone().call(RpcApiOps::PinBlocks, request).await?.as_ref().try_into()
+    }
+
+    async fn unpin_blocks_call(&self, request: UnpinBlocksRequest) -> RpcResult<UnpinBlocksResponse> {
+        self.inner.clone().call(RpcApiOps::UnpinBlocks, request).await?.as_ref().try_into()
+    }
+
+    async fn dump_block_region_call(&self, request: DumpBlockRegionRequest) -> RpcResult<DumpBlockRegionResponse> {
+        self.inner.clone().call(RpcApiOps::DumpBlockRegion, request).await?.as_ref().try_into()
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -94,6 +224,7 @@ impl RpcApi for RpcApiGrpc {
 
     /// Start sending notifications of some type to a listener.
     async fn start_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        check_notification_type(&notification_type)?;
         self.notifier.start_notify(id, notification_type)?;
         Ok(())
     }
@@ -107,4 +238,23 @@ impl RpcApi for RpcApiGrpc {
             Err(RpcError::UnsupportedFeature)
         }
     }
+
+    /// Replace the scope of the notifications of some type sent to a listener.
+    ///
+    /// The scope is applied by the client notifier, so the server keeps sending the notifications of the event type.
+    async fn modify_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        check_notification_type(&notification_type)?;
+        self.notifier.modify_notify(id, notification_type)?;
+        Ok(())
+    }
+}
+
+/// Rejects the notification types the protowire has no messages for, which the server never sends
+fn check_notification_type(notification_type: &NotificationType) -> RpcResult<()> {
+    match notification_type {
+        NotificationType::TransactionAccepted(_) | NotificationType::TransactionReverted(_) => {
+            Err(RpcError::UnsupportedNotificationType(notification_type.into()))
+        }
+        _ => Ok(()),
+    }
 }
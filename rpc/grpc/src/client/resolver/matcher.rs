@@ -38,6 +38,31 @@ impl Matcher<&kaspad_response::Payload> for kaspad_request::Payload {
             kaspad_request::Payload::GetCurrentNetworkRequest(_) => true,
             kaspad_request::Payload::NotifyBlockAddedRequest(_) => true,
             kaspad_request::Payload::GetInfoRequest(_) => true,
+            kaspad_request::Payload::GetChainThroughputStatsRequest(_) => true,
+            kaspad_request::Payload::RevalidateBlockRequest(_) => true,
+            kaspad_request::Payload::GetCoinbasePreviewRequest(_) => true,
+            kaspad_request::Payload::GetMempoolFeeRateHistogramRequest(_) => true,
+            kaspad_request::Payload::GetVirtualChainFromBlockRequest(_) => true,
+            kaspad_request::Payload::SubmitTransactionRequest(_) => true,
+            kaspad_request::Payload::GetTransactionAcceptanceRequest(_) => true,
+            kaspad_request::Payload::GetBlocksWithAcceptanceRequest(_) => true,
+            kaspad_request::Payload::GetMetricsRequest(_) => true,
+            kaspad_request::Payload::GetPayloadRequest(_) => true,
+            kaspad_request::Payload::GetTransactionByIdRequest(_) => true,
+            kaspad_request::Payload::PinBlocksRequest(_) => true,
+            kaspad_request::Payload::UnpinBlocksRequest(_) => true,
+            kaspad_request::Payload::DumpBlockRegionRequest(_) => true,
+            kaspad_request::Payload::GetPeerAddressesRequest(_) => true,
+            kaspad_request::Payload::GetConnectedPeerInfoRequest(_) => true,
+            kaspad_request::Payload::AddPeerRequest(_) => true,
+            kaspad_request::Payload::BanRequest(_) => true,
+            kaspad_request::Payload::UnbanRequest(_) => true,
+            kaspad_request::Payload::GetBlocksRequest(_) => true,
+            kaspad_request::Payload::GetUtxosByAddressesRequest(_) => true,
+            kaspad_request::Payload::GetBalanceByAddressRequest(_) => true,
+            kaspad_request::Payload::GetMempoolEntryRequest(_) => true,
+            kaspad_request::Payload::GetMempoolEntriesRequest(_) => true,
+            kaspad_request::Payload::GetBlockDagInfoRequest(_) => true,
             kaspad_request::Payload::NotifyNewBlockTemplateRequest(_) => true,
         }
     }
@@ -33,6 +33,8 @@ use matcher::*;
 mod matcher;
 
 pub const CONNECT_TIMEOUT_DURATION: u64 = 20_000;
+pub const CONNECT_RETRY_COUNT: usize = 5;
+pub const CONNECT_RETRY_INTERVAL: u64 = 1_000;
 pub const KEEP_ALIVE_DURATION: u64 = 5_000;
 pub const REQUEST_TIMEOUT_DURATION: u64 = 5_000;
 pub const TIMEOUT_MONITORING_INTERVAL: u64 = 1_000;
@@ -77,8 +79,8 @@ impl Pending {
 ///
 /// TODO:
 ///
-/// Carry any subscribe call result up to the initial RpcApiGrpc::start_notify execution.
-/// For now, RpcApiGrpc::start_notify only gets a result reflecting the call to
+/// Carry any subscribe call result up to the initial GrpcClient::start_notify execution.
+/// For now, GrpcClient::start_notify only gets a result reflecting the call to
 /// Notifier::try_send_dispatch. This is not complete.
 ///
 /// Investigate a possible bottleneck in handle_response with the processing of pendings.
@@ -128,13 +130,25 @@ impl Resolver {
         }
     }
 
+    /// Connects to the server, retrying up to [`CONNECT_RETRY_COUNT`] times, [`CONNECT_RETRY_INTERVAL`] apart,
+    /// so that a client can be started along with a node still opening its listener
     pub(crate) async fn connect(address: String, notify_send: NotificationSender) -> Result<Arc<Self>> {
-        let channel = Endpoint::from_shared(address.clone())?
+        let endpoint = Endpoint::from_shared(address.clone())?
             .timeout(tokio::time::Duration::from_millis(REQUEST_TIMEOUT_DURATION))
             .connect_timeout(tokio::time::Duration::from_millis(CONNECT_TIMEOUT_DURATION))
-            .tcp_keepalive(Some(tokio::time::Duration::from_millis(KEEP_ALIVE_DURATION)))
-            .connect()
-            .await?;
+            .tcp_keepalive(Some(tokio::time::Duration::from_millis(KEEP_ALIVE_DURATION)));
+        let mut attempt = 0;
+        let channel = loop {
+            match endpoint.connect().await {
+                Ok(channel) => break channel,
+                Err(err) if attempt < CONNECT_RETRY_COUNT => {
+                    attempt += 1;
+                    trace!("[Resolver] connecting to {0} failed, retrying ({1}/{2}): {3}", address, attempt, CONNECT_RETRY_COUNT, err);
+                    tokio::time::sleep(Duration::from_millis(CONNECT_RETRY_INTERVAL)).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
 
         let mut client =
             RpcClient::new(channel).send_compressed(CompressionEncoding::Gzip).accept_compressed(CompressionEncoding::Gzip);
@@ -1,5 +1,5 @@
 use crate::protowire;
-use rpc_core::{RpcError, RpcHash, RpcResult};
+use rpc_core::{FromRpcHex, RpcError, RpcHash, RpcResult, ToRpcHex};
 use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
@@ -29,6 +29,40 @@ impl From<&rpc_core::RpcBlockVerboseData> for protowire::RpcBlockVerboseData {
             merge_set_blues_hashes: item.merge_set_blues_hashes.iter().map(|x| x.to_string()).collect(),
             merge_set_reds_hashes: item.merge_set_reds_hashes.iter().map(|x| x.to_string()).collect(),
             is_chain_block: item.is_chain_block,
+            confirmations: item.confirmations,
+        }
+    }
+}
+
+impl From<&rpc_core::RpcMergesetBlockReward> for protowire::RpcMergesetBlockReward {
+    fn from(item: &rpc_core::RpcMergesetBlockReward) -> Self {
+        Self {
+            hash: item.hash.to_string(),
+            is_blue: item.is_blue,
+            subsidy: item.subsidy,
+            total_fees: item.total_fees,
+            script_public_key: Some((&item.script_public_key).into()),
+        }
+    }
+}
+
+impl From<&rpc_core::RpcAcceptedTransactionIds> for protowire::AcceptedTransactionIds {
+    fn from(item: &rpc_core::RpcAcceptedTransactionIds) -> Self {
+        Self {
+            accepting_block_hash: item.accepting_block_hash.to_string(),
+            accepted_transaction_ids: item.accepted_transaction_ids.iter().map(|x| x.to_string()).collect(),
+        }
+    }
+}
+
+impl From<&rpc_core::RpcChainBlockWithAcceptance> for protowire::RpcChainBlockWithAcceptance {
+    fn from(item: &rpc_core::RpcChainBlockWithAcceptance) -> Self {
+        Self {
+            block: Some((&item.block).into()),
+            blue_score: item.blue_score,
+            blue_work: item.blue_work.to_rpc_hex(),
+            selected_parent_hash: item.selected_parent_hash.to_string(),
+            accepted_transaction_ids: item.accepted_transaction_ids.iter().map(|x| x.to_string()).collect(),
         }
     }
 }
@@ -86,6 +120,59 @@ impl TryFrom<&protowire::RpcBlockVerboseData> for rpc_core::RpcBlockVerboseData
                 .map(|x| RpcHash::from_str(x))
                 .collect::<Result<Vec<rpc_core::RpcHash>, faster_hex::Error>>()?,
             is_chain_block: item.is_chain_block,
+            confirmations: item.confirmations,
+        })
+    }
+}
+
+impl TryFrom<&protowire::RpcMergesetBlockReward> for rpc_core::RpcMergesetBlockReward {
+    type Error = RpcError;
+    fn try_from(item: &protowire::RpcMergesetBlockReward) -> RpcResult<Self> {
+        Ok(Self {
+            hash: RpcHash::from_str(&item.hash)?,
+            is_blue: item.is_blue,
+            subsidy: item.subsidy,
+            total_fees: item.total_fees,
+            script_public_key: item
+                .script_public_key
+                .as_ref()
+                .ok_or_else(|| RpcError::MissingRpcFieldError("RpcMergesetBlockReward".to_string(), "script_public_key".to_string()))?
+                .try_into()?,
+        })
+    }
+}
+
+impl TryFrom<&protowire::AcceptedTransactionIds> for rpc_core::RpcAcceptedTransactionIds {
+    type Error = RpcError;
+    fn try_from(item: &protowire::AcceptedTransactionIds) -> RpcResult<Self> {
+        Ok(Self {
+            accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+            accepted_transaction_ids: item
+                .accepted_transaction_ids
+                .iter()
+                .map(|x| rpc_core::RpcTransactionId::from_str(x))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<&protowire::RpcChainBlockWithAcceptance> for rpc_core::RpcChainBlockWithAcceptance {
+    type Error = RpcError;
+    fn try_from(item: &protowire::RpcChainBlockWithAcceptance) -> RpcResult<Self> {
+        Ok(Self {
+            block: item
+                .block
+                .as_ref()
+                .ok_or_else(|| RpcError::MissingRpcFieldError("RpcChainBlockWithAcceptance".to_string(), "block".to_string()))?
+                .try_into()?,
+            blue_score: item.blue_score,
+            blue_work: rpc_core::RpcBlueWorkType::from_rpc_hex(&item.blue_work)?,
+            selected_parent_hash: RpcHash::from_str(&item.selected_parent_hash)?,
+            accepted_transaction_ids: item
+                .accepted_transaction_ids
+                .iter()
+                .map(|x| rpc_core::RpcTransactionId::from_str(x))
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
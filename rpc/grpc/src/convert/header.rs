@@ -65,7 +65,7 @@ impl TryFrom<&protowire::RpcBlockLevelParents> for Vec<RpcHash> {
 #[cfg(test)]
 mod tests {
     use crate::protowire;
-    use rpc_core::{RpcHash, RpcHeader};
+    use rpc_core::{RpcHash, RpcHeader, RpcTimestamp};
 
     fn new_unique() -> RpcHash {
         use std::sync::atomic::{AtomicU64, Ordering};
@@ -122,7 +122,7 @@ mod tests {
             new_unique(),
             new_unique(),
             new_unique(),
-            123,
+            RpcTimestamp::from_millis(123),
             12345,
             98765,
             120055,
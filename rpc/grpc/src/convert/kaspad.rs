@@ -8,7 +8,32 @@ impl From<&kaspad_request::Payload> for RpcApiOps {
             kaspad_request::Payload::GetBlockTemplateRequest(_) => RpcApiOps::GetBlockTemplate,
             kaspad_request::Payload::GetCurrentNetworkRequest(_) => RpcApiOps::GetCurrentNetwork,
             kaspad_request::Payload::GetBlockRequest(_) => RpcApiOps::GetBlock,
+            kaspad_request::Payload::GetBlocksRequest(_) => RpcApiOps::GetBlocks,
+            kaspad_request::Payload::GetUtxosByAddressesRequest(_) => RpcApiOps::GetUtxosByAddresses,
+            kaspad_request::Payload::GetBalanceByAddressRequest(_) => RpcApiOps::GetBalanceByAddress,
+            kaspad_request::Payload::GetMempoolEntryRequest(_) => RpcApiOps::GetMempoolEntry,
+            kaspad_request::Payload::GetMempoolEntriesRequest(_) => RpcApiOps::GetMempoolEntries,
             kaspad_request::Payload::GetInfoRequest(_) => RpcApiOps::GetInfo,
+            kaspad_request::Payload::GetChainThroughputStatsRequest(_) => RpcApiOps::GetChainThroughputStats,
+            kaspad_request::Payload::RevalidateBlockRequest(_) => RpcApiOps::RevalidateBlock,
+            kaspad_request::Payload::GetCoinbasePreviewRequest(_) => RpcApiOps::GetCoinbasePreview,
+            kaspad_request::Payload::GetMempoolFeeRateHistogramRequest(_) => RpcApiOps::GetMempoolFeeRateHistogram,
+            kaspad_request::Payload::GetVirtualChainFromBlockRequest(_) => RpcApiOps::GetVirtualChainFromBlock,
+            kaspad_request::Payload::SubmitTransactionRequest(_) => RpcApiOps::SubmitTransaction,
+            kaspad_request::Payload::GetTransactionAcceptanceRequest(_) => RpcApiOps::GetTransactionAcceptance,
+            kaspad_request::Payload::GetBlocksWithAcceptanceRequest(_) => RpcApiOps::GetBlocksWithAcceptance,
+            kaspad_request::Payload::GetMetricsRequest(_) => RpcApiOps::GetMetrics,
+            kaspad_request::Payload::GetPayloadRequest(_) => RpcApiOps::GetPayload,
+            kaspad_request::Payload::GetTransactionByIdRequest(_) => RpcApiOps::GetTransactionById,
+            kaspad_request::Payload::PinBlocksRequest(_) => RpcApiOps::PinBlocks,
+            kaspad_request::Payload::UnpinBlocksRequest(_) => RpcApiOps::UnpinBlocks,
+            kaspad_request::Payload::DumpBlockRegionRequest(_) => RpcApiOps::DumpBlockRegion,
+            kaspad_request::Payload::GetPeerAddressesRequest(_) => RpcApiOps::GetPeerAddresses,
+            kaspad_request::Payload::GetConnectedPeerInfoRequest(_) => RpcApiOps::GetConnectedPeerInfo,
+            kaspad_request::Payload::AddPeerRequest(_) => RpcApiOps::AddPeer,
+            kaspad_request::Payload::BanRequest(_) => RpcApiOps::Ban,
+            kaspad_request::Payload::UnbanRequest(_) => RpcApiOps::Unban,
+            kaspad_request::Payload::GetBlockDagInfoRequest(_) => RpcApiOps::GetBlockDagInfo,
 
             // Subscription commands for starting/stopping notifications
             kaspad_request::Payload::NotifyBlockAddedRequest(_) => RpcApiOps::NotifyBlockAdded,
@@ -24,7 +49,32 @@ impl From<&kaspad_response::Payload> for RpcApiOps {
             kaspad_response::Payload::GetBlockTemplateResponse(_) => RpcApiOps::GetBlockTemplate,
             kaspad_response::Payload::GetCurrentNetworkResponse(_) => RpcApiOps::GetCurrentNetwork,
             kaspad_response::Payload::GetBlockResponse(_) => RpcApiOps::GetBlock,
+            kaspad_response::Payload::GetBlocksResponse(_) => RpcApiOps::GetBlocks,
+            kaspad_response::Payload::GetUtxosByAddressesResponse(_) => RpcApiOps::GetUtxosByAddresses,
+            kaspad_response::Payload::GetBalanceByAddressResponse(_) => RpcApiOps::GetBalanceByAddress,
+            kaspad_response::Payload::GetMempoolEntryResponse(_) => RpcApiOps::GetMempoolEntry,
+            kaspad_response::Payload::GetMempoolEntriesResponse(_) => RpcApiOps::GetMempoolEntries,
             kaspad_response::Payload::GetInfoResponse(_) => RpcApiOps::GetInfo,
+            kaspad_response::Payload::GetChainThroughputStatsResponse(_) => RpcApiOps::GetChainThroughputStats,
+            kaspad_response::Payload::RevalidateBlockResponse(_) => RpcApiOps::RevalidateBlock,
+            kaspad_response::Payload::GetCoinbasePreviewResponse(_) => RpcApiOps::GetCoinbasePreview,
+            kaspad_response::Payload::GetMempoolFeeRateHistogramResponse(_) => RpcApiOps::GetMempoolFeeRateHistogram,
+            kaspad_response::Payload::GetVirtualChainFromBlockResponse(_) => RpcApiOps::GetVirtualChainFromBlock,
+            kaspad_response::Payload::SubmitTransactionResponse(_) => RpcApiOps::SubmitTransaction,
+            kaspad_response::Payload::GetTransactionAcceptanceResponse(_) => RpcApiOps::GetTransactionAcceptance,
+            kaspad_response::Payload::GetBlocksWithAcceptanceResponse(_) => RpcApiOps::GetBlocksWithAcceptance,
+            kaspad_response::Payload::GetMetricsResponse(_) => RpcApiOps::GetMetrics,
+            kaspad_response::Payload::GetPayloadResponse(_) => RpcApiOps::GetPayload,
+            kaspad_response::Payload::GetTransactionByIdResponse(_) => RpcApiOps::GetTransactionById,
+            kaspad_response::Payload::PinBlocksResponse(_) => RpcApiOps::PinBlocks,
+            kaspad_response::Payload::UnpinBlocksResponse(_) => RpcApiOps::UnpinBlocks,
+            kaspad_response::Payload::DumpBlockRegionResponse(_) => RpcApiOps::DumpBlockRegion,
+            kaspad_response::Payload::GetPeerAddressesResponse(_) => RpcApiOps::GetPeerAddresses,
+            kaspad_response::Payload::GetConnectedPeerInfoResponse(_) => RpcApiOps::GetConnectedPeerInfo,
+            kaspad_response::Payload::AddPeerResponse(_) => RpcApiOps::AddPeer,
+            kaspad_response::Payload::BanResponse(_) => RpcApiOps::Ban,
+            kaspad_response::Payload::UnbanResponse(_) => RpcApiOps::Unban,
+            kaspad_response::Payload::GetBlockDagInfoResponse(_) => RpcApiOps::GetBlockDagInfo,
 
             // Subscription commands for starting/stopping notifications
             kaspad_response::Payload::NotifyBlockAddedResponse(_) => RpcApiOps::NotifyBlockAdded,
@@ -64,6 +114,51 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(rpc_core::GetBlockRequest, GetBlockRequestMessage, GetBlockRequest);
     impl_into_kaspad_request!(rpc_core::NotifyBlockAddedRequest, NotifyBlockAddedRequestMessage, NotifyBlockAddedRequest);
     impl_into_kaspad_request!(rpc_core::GetInfoRequest, GetInfoRequestMessage, GetInfoRequest);
+    impl_into_kaspad_request!(
+        rpc_core::GetChainThroughputStatsRequest,
+        GetChainThroughputStatsRequestMessage,
+        GetChainThroughputStatsRequest
+    );
+    impl_into_kaspad_request!(rpc_core::RevalidateBlockRequest, RevalidateBlockRequestMessage, RevalidateBlockRequest);
+    impl_into_kaspad_request!(rpc_core::GetCoinbasePreviewRequest, GetCoinbasePreviewRequestMessage, GetCoinbasePreviewRequest);
+    impl_into_kaspad_request!(
+        rpc_core::GetMempoolFeeRateHistogramRequest,
+        GetMempoolFeeRateHistogramRequestMessage,
+        GetMempoolFeeRateHistogramRequest
+    );
+    impl_into_kaspad_request!(
+        rpc_core::GetVirtualChainFromBlockRequest,
+        GetVirtualChainFromBlockRequestMessage,
+        GetVirtualChainFromBlockRequest
+    );
+    impl_into_kaspad_request!(rpc_core::SubmitTransactionRequest, SubmitTransactionRequestMessage, SubmitTransactionRequest);
+    impl_into_kaspad_request!(
+        rpc_core::GetTransactionAcceptanceRequest,
+        GetTransactionAcceptanceRequestMessage,
+        GetTransactionAcceptanceRequest
+    );
+    impl_into_kaspad_request!(
+        rpc_core::GetBlocksWithAcceptanceRequest,
+        GetBlocksWithAcceptanceRequestMessage,
+        GetBlocksWithAcceptanceRequest
+    );
+    impl_into_kaspad_request!(rpc_core::GetBlocksRequest, GetBlocksRequestMessage, GetBlocksRequest);
+    impl_into_kaspad_request!(rpc_core::GetUtxosByAddressesRequest, GetUtxosByAddressesRequestMessage, GetUtxosByAddressesRequest);
+    impl_into_kaspad_request!(rpc_core::GetBalanceByAddressRequest, GetBalanceByAddressRequestMessage, GetBalanceByAddressRequest);
+    impl_into_kaspad_request!(rpc_core::GetMempoolEntryRequest, GetMempoolEntryRequestMessage, GetMempoolEntryRequest);
+    impl_into_kaspad_request!(rpc_core::GetMempoolEntriesRequest, GetMempoolEntriesRequestMessage, GetMempoolEntriesRequest);
+    impl_into_kaspad_request!(rpc_core::GetBlockDagInfoRequest, GetBlockDagInfoRequestMessage, GetBlockDagInfoRequest);
+    impl_into_kaspad_request!(rpc_core::GetMetricsRequest, GetMetricsRequestMessage, GetMetricsRequest);
+    impl_into_kaspad_request!(rpc_core::GetPayloadRequest, GetPayloadRequestMessage, GetPayloadRequest);
+    impl_into_kaspad_request!(rpc_core::GetTransactionByIdRequest, GetTransactionByIdRequestMessage, GetTransactionByIdRequest);
+    impl_into_kaspad_request!(rpc_core::PinBlocksRequest, PinBlocksRequestMessage, PinBlocksRequest);
+    impl_into_kaspad_request!(rpc_core::UnpinBlocksRequest, UnpinBlocksRequestMessage, UnpinBlocksRequest);
+    impl_into_kaspad_request!(rpc_core::DumpBlockRegionRequest, DumpBlockRegionRequestMessage, DumpBlockRegionRequest);
+    impl_into_kaspad_request!(rpc_core::GetPeerAddressesRequest, GetPeerAddressesRequestMessage, GetPeerAddressesRequest);
+    impl_into_kaspad_request!(rpc_core::GetConnectedPeerInfoRequest, GetConnectedPeerInfoRequestMessage, GetConnectedPeerInfoRequest);
+    impl_into_kaspad_request!(rpc_core::AddPeerRequest, AddPeerRequestMessage, AddPeerRequest);
+    impl_into_kaspad_request!(rpc_core::BanRequest, BanRequestMessage, BanRequest);
+    impl_into_kaspad_request!(rpc_core::UnbanRequest, UnbanRequestMessage, UnbanRequest);
     impl_into_kaspad_request!(
         rpc_core::NotifyNewBlockTemplateRequest,
         NotifyNewBlockTemplateRequestMessage,
@@ -155,6 +250,59 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_response!(rpc_core::GetBlockTemplateResponse, GetBlockTemplateResponseMessage, GetBlockTemplateResponse);
     impl_into_kaspad_response!(rpc_core::GetBlockResponse, GetBlockResponseMessage, GetBlockResponse);
     impl_into_kaspad_response!(rpc_core::GetInfoResponse, GetInfoResponseMessage, GetInfoResponse);
+    impl_into_kaspad_response!(
+        rpc_core::GetChainThroughputStatsResponse,
+        GetChainThroughputStatsResponseMessage,
+        GetChainThroughputStatsResponse
+    );
+    impl_into_kaspad_response!(rpc_core::RevalidateBlockResponse, RevalidateBlockResponseMessage, RevalidateBlockResponse);
+    impl_into_kaspad_response!(rpc_core::GetCoinbasePreviewResponse, GetCoinbasePreviewResponseMessage, GetCoinbasePreviewResponse);
+    impl_into_kaspad_response!(
+        rpc_core::GetMempoolFeeRateHistogramResponse,
+        GetMempoolFeeRateHistogramResponseMessage,
+        GetMempoolFeeRateHistogramResponse
+    );
+    impl_into_kaspad_response!(
+        rpc_core::GetVirtualChainFromBlockResponse,
+        GetVirtualChainFromBlockResponseMessage,
+        GetVirtualChainFromBlockResponse
+    );
+    impl_into_kaspad_response!(rpc_core::SubmitTransactionResponse, SubmitTransactionResponseMessage, SubmitTransactionResponse);
+    impl_into_kaspad_response!(
+        rpc_core::GetTransactionAcceptanceResponse,
+        GetTransactionAcceptanceResponseMessage,
+        GetTransactionAcceptanceResponse
+    );
+    impl_into_kaspad_response!(
+        rpc_core::GetBlocksWithAcceptanceResponse,
+        GetBlocksWithAcceptanceResponseMessage,
+        GetBlocksWithAcceptanceResponse
+    );
+    impl_into_kaspad_response!(rpc_core::GetBlocksResponse, GetBlocksResponseMessage, GetBlocksResponse);
+    impl_into_kaspad_response!(rpc_core::GetUtxosByAddressesResponse, GetUtxosByAddressesResponseMessage, GetUtxosByAddressesResponse);
+    impl_into_kaspad_response!(rpc_core::GetBalanceByAddressResponse, GetBalanceByAddressResponseMessage, GetBalanceByAddressResponse);
+    impl_into_kaspad_response!(rpc_core::GetMempoolEntryResponse, GetMempoolEntryResponseMessage, GetMempoolEntryResponse);
+    impl_into_kaspad_response!(rpc_core::GetMempoolEntriesResponse, GetMempoolEntriesResponseMessage, GetMempoolEntriesResponse);
+    impl_into_kaspad_response!(rpc_core::GetBlockDagInfoResponse, GetBlockDagInfoResponseMessage, GetBlockDagInfoResponse);
+    impl_into_kaspad_response!(rpc_core::GetMetricsResponse, GetMetricsResponseMessage, GetMetricsResponse);
+    impl_into_kaspad_response!(rpc_core::GetPayloadResponse, GetPayloadResponseMessage, GetPayloadResponse);
+    impl_into_kaspad_response!(
+        rpc_core::GetTransactionByIdResponse,
+        GetTransactionByIdResponseMessage,
+        GetTransactionByIdResponse
+    );
+    impl_into_kaspad_response!(rpc_core::PinBlocksResponse, PinBlocksResponseMessage, PinBlocksResponse);
+    impl_into_kaspad_response!(rpc_core::UnpinBlocksResponse, UnpinBlocksResponseMessage, UnpinBlocksResponse);
+    impl_into_kaspad_response!(rpc_core::DumpBlockRegionResponse, DumpBlockRegionResponseMessage, DumpBlockRegionResponse);
+    impl_into_kaspad_response!(rpc_core::GetPeerAddressesResponse, GetPeerAddressesResponseMessage, GetPeerAddressesResponse);
+    impl_into_kaspad_response!(
+        rpc_core::GetConnectedPeerInfoResponse,
+        GetConnectedPeerInfoResponseMessage,
+        GetConnectedPeerInfoResponse
+    );
+    impl_into_kaspad_response!(rpc_core::AddPeerResponse, AddPeerResponseMessage, AddPeerResponse);
+    impl_into_kaspad_response!(rpc_core::BanResponse, BanResponseMessage, BanResponse);
+    impl_into_kaspad_response!(rpc_core::UnbanResponse, UnbanResponseMessage, UnbanResponse);
 
     impl_into_kaspad_response!(rpc_core::NotifyBlockAddedResponse, NotifyBlockAddedResponseMessage, NotifyBlockAddedResponse);
     impl_into_kaspad_notify_response!(rpc_core::NotifyBlockAddedResponse, NotifyBlockAddedResponseMessage, NotifyBlockAddedResponse);
@@ -0,0 +1,56 @@
+use crate::protowire;
+use rpc_core::{RpcError, RpcResult};
+
+// ----------------------------------------------------------------------------
+// rpc_core to protowire
+// ----------------------------------------------------------------------------
+
+impl From<&rpc_core::RpcFeeRateHistogramBucket> for protowire::RpcFeeRateHistogramBucket {
+    fn from(item: &rpc_core::RpcFeeRateHistogramBucket) -> Self {
+        Self {
+            min_fee_rate: item.min_fee_rate,
+            max_fee_rate: item.max_fee_rate.unwrap_or_default(),
+            transaction_count: item.transaction_count,
+            total_mass: item.total_mass,
+        }
+    }
+}
+
+impl From<&rpc_core::RpcMempoolEntry> for protowire::MempoolEntry {
+    fn from(item: &rpc_core::RpcMempoolEntry) -> Self {
+        Self { fee: item.fee, transaction: Some((&item.transaction).into()), is_orphan: item.is_orphan, mass: item.mass }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// protowire to rpc_core
+// ----------------------------------------------------------------------------
+
+impl TryFrom<&protowire::RpcFeeRateHistogramBucket> for rpc_core::RpcFeeRateHistogramBucket {
+    type Error = RpcError;
+    fn try_from(item: &protowire::RpcFeeRateHistogramBucket) -> RpcResult<Self> {
+        Ok(Self {
+            min_fee_rate: item.min_fee_rate,
+            // The upper bound of a bucket is always positive, so zero encodes the unbounded bucket
+            max_fee_rate: (item.max_fee_rate > 0).then_some(item.max_fee_rate),
+            transaction_count: item.transaction_count,
+            total_mass: item.total_mass,
+        })
+    }
+}
+
+impl TryFrom<&protowire::MempoolEntry> for rpc_core::RpcMempoolEntry {
+    type Error = RpcError;
+    fn try_from(item: &protowire::MempoolEntry) -> RpcResult<Self> {
+        Ok(Self {
+            fee: item.fee,
+            mass: item.mass,
+            transaction: item
+                .transaction
+                .as_ref()
+                .ok_or_else(|| RpcError::MissingRpcFieldError("MempoolEntry".to_string(), "transaction".to_string()))?
+                .try_into()?,
+            is_orphan: item.is_orphan,
+        })
+    }
+}
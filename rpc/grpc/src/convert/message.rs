@@ -1,5 +1,5 @@
 use crate::protowire::{self, submit_block_response_message::RejectReason};
-use rpc_core::{RpcError, RpcExtraData, RpcHash, RpcResult};
+use rpc_core::{FromRpcHex, RpcError, RpcExtraData, RpcHash, RpcIpAddress, RpcPeerAddress, RpcResult, ToRpcHex};
 use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
@@ -12,21 +12,27 @@ impl From<&rpc_core::SubmitBlockRequest> for protowire::SubmitBlockRequestMessag
     }
 }
 
-impl From<&rpc_core::SubmitBlockReport> for RejectReason {
-    fn from(item: &rpc_core::SubmitBlockReport) -> Self {
+impl From<&rpc_core::SubmitBlockRejectReason> for RejectReason {
+    fn from(item: &rpc_core::SubmitBlockRejectReason) -> Self {
         match item {
-            rpc_core::SubmitBlockReport::Success => RejectReason::None,
-            rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::BlockInvalid) => RejectReason::BlockInvalid,
-            rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::IsInIBD) => RejectReason::IsInIbd,
+            rpc_core::SubmitBlockRejectReason::BlockInvalid(_) => RejectReason::BlockInvalid,
+            rpc_core::SubmitBlockRejectReason::IsInIBD => RejectReason::IsInIbd,
         }
     }
 }
 
 impl From<RpcResult<&rpc_core::SubmitBlockResponse>> for protowire::SubmitBlockResponseMessage {
     fn from(item: RpcResult<&rpc_core::SubmitBlockResponse>) -> Self {
-        Self {
-            reject_reason: item.as_ref().map(|x| RejectReason::from(&x.report)).unwrap_or(RejectReason::None) as i32,
-            error: item.map_err(protowire::RpcError::from).err(),
+        match item {
+            Ok(rpc_core::SubmitBlockResponse { report: rpc_core::SubmitBlockReport::Success }) => {
+                Self { reject_reason: RejectReason::None as i32, error: None }
+            }
+            // As in kaspad Go, a rejection also carries a descriptive error
+            Ok(rpc_core::SubmitBlockResponse { report: rpc_core::SubmitBlockReport::Reject(reason) }) => Self {
+                reject_reason: RejectReason::from(reason) as i32,
+                error: Some(protowire::RpcError { message: reason.to_string() }),
+            },
+            Err(err) => Self { reject_reason: RejectReason::None as i32, error: Some(err.into()) },
         }
     }
 }
@@ -64,6 +70,199 @@ impl From<RpcResult<&rpc_core::GetBlockResponse>> for protowire::GetBlockRespons
     }
 }
 
+impl From<&rpc_core::GetBlocksRequest> for protowire::GetBlocksRequestMessage {
+    fn from(item: &rpc_core::GetBlocksRequest) -> Self {
+        Self {
+            low_hash: item.low_hash.map(|x| x.to_string()).unwrap_or_default(),
+            include_blocks: item.include_blocks,
+            include_transactions: item.include_transactions,
+        }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetBlocksResponse>> for protowire::GetBlocksResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetBlocksResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                block_hashes: response.block_hashes.iter().map(|x| x.to_string()).collect(),
+                blocks: response.blocks.iter().map(protowire::RpcBlock::from).collect(),
+                error: None,
+            },
+            Err(err) => Self { block_hashes: vec![], blocks: vec![], error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::RpcUtxosByAddressesEntry> for protowire::UtxosByAddressesEntry {
+    fn from(item: &rpc_core::RpcUtxosByAddressesEntry) -> Self {
+        Self {
+            address: item.address.as_ref().map(|x| x.into()).unwrap_or_default(),
+            outpoint: Some((&item.outpoint).into()),
+            utxo_entry: Some((&item.utxo_entry).into()),
+        }
+    }
+}
+
+impl From<&rpc_core::GetUtxosByAddressesRequest> for protowire::GetUtxosByAddressesRequestMessage {
+    fn from(item: &rpc_core::GetUtxosByAddressesRequest) -> Self {
+        Self { addresses: item.addresses.iter().map(|x| x.into()).collect() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetUtxosByAddressesResponse>> for protowire::GetUtxosByAddressesResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetUtxosByAddressesResponse>) -> Self {
+        match item {
+            Ok(response) => Self { entries: response.entries.iter().map(|x| x.into()).collect(), error: None },
+            Err(err) => Self { entries: vec![], error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::GetMempoolEntryRequest> for protowire::GetMempoolEntryRequestMessage {
+    fn from(item: &rpc_core::GetMempoolEntryRequest) -> Self {
+        Self {
+            tx_id: item.transaction_id.to_string(),
+            include_orphan_pool: item.include_orphan_pool,
+            filter_transaction_pool: item.filter_transaction_pool,
+        }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetMempoolEntryResponse>> for protowire::GetMempoolEntryResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetMempoolEntryResponse>) -> Self {
+        match item {
+            Ok(response) => Self { entry: Some((&response.entry).into()), error: None },
+            Err(err) => Self { entry: None, error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::GetMempoolEntriesRequest> for protowire::GetMempoolEntriesRequestMessage {
+    fn from(item: &rpc_core::GetMempoolEntriesRequest) -> Self {
+        Self {
+            include_orphan_pool: item.include_orphan_pool,
+            filter_transaction_pool: item.filter_transaction_pool,
+            addresses: item.addresses.iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetMempoolEntriesResponse>> for protowire::GetMempoolEntriesResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetMempoolEntriesResponse>) -> Self {
+        match item {
+            Ok(response) => Self { entries: response.entries.iter().map(|x| x.into()).collect(), error: None },
+            Err(err) => Self { entries: vec![], error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::GetBalanceByAddressRequest> for protowire::GetBalanceByAddressRequestMessage {
+    fn from(item: &rpc_core::GetBalanceByAddressRequest) -> Self {
+        Self { address: (&item.address).into() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetBalanceByAddressResponse>> for protowire::GetBalanceByAddressResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetBalanceByAddressResponse>) -> Self {
+        match item {
+            Ok(response) => Self { balance: response.balance, error: None },
+            Err(err) => Self { balance: 0, error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::SubmitTransactionRequest> for protowire::SubmitTransactionRequestMessage {
+    fn from(item: &rpc_core::SubmitTransactionRequest) -> Self {
+        Self { transaction: Some((&item.transaction).into()), allow_orphan: item.allow_orphan }
+    }
+}
+
+impl From<RpcResult<&rpc_core::SubmitTransactionResponse>> for protowire::SubmitTransactionResponseMessage {
+    fn from(item: RpcResult<&rpc_core::SubmitTransactionResponse>) -> Self {
+        Self {
+            transaction_id: item.as_ref().map(|x| x.transaction_id.to_string()).unwrap_or_default(),
+            error: item.map_err(protowire::RpcError::from).err(),
+        }
+    }
+}
+
+impl From<&rpc_core::GetTransactionAcceptanceRequest> for protowire::GetTransactionAcceptanceRequestMessage {
+    fn from(item: &rpc_core::GetTransactionAcceptanceRequest) -> Self {
+        Self { transaction_id: item.transaction_id.to_string() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetTransactionAcceptanceResponse>> for protowire::GetTransactionAcceptanceResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetTransactionAcceptanceResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                accepting_block_hash: response.accepting_block_hash.map(|x| x.to_string()).unwrap_or_default(),
+                accepting_daa_score: response.accepting_daa_score,
+                is_beyond_retention: response.is_beyond_retention,
+                retention_root_daa_score: response.retention_root_daa_score,
+                error: None,
+            },
+            Err(err) => Self {
+                accepting_block_hash: String::default(),
+                accepting_daa_score: 0,
+                is_beyond_retention: false,
+                retention_root_daa_score: 0,
+                error: Some(err.into()),
+            },
+        }
+    }
+}
+
+impl From<&rpc_core::GetVirtualChainFromBlockRequest> for protowire::GetVirtualChainFromBlockRequestMessage {
+    fn from(item: &rpc_core::GetVirtualChainFromBlockRequest) -> Self {
+        Self { start_hash: item.start_hash.to_string(), include_accepted_transaction_ids: item.include_accepted_transaction_ids }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetVirtualChainFromBlockResponse>> for protowire::GetVirtualChainFromBlockResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetVirtualChainFromBlockResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                removed_chain_block_hashes: response.removed_chain_block_hashes.iter().map(|x| x.to_string()).collect(),
+                added_chain_block_hashes: response.added_chain_block_hashes.iter().map(|x| x.to_string()).collect(),
+                accepted_transaction_ids: response
+                    .accepted_transaction_ids
+                    .iter()
+                    .map(protowire::AcceptedTransactionIds::from)
+                    .collect(),
+                has_more: response.has_more,
+                error: None,
+            },
+            Err(err) => Self {
+                removed_chain_block_hashes: vec![],
+                added_chain_block_hashes: vec![],
+                accepted_transaction_ids: vec![],
+                has_more: false,
+                error: Some(err.into()),
+            },
+        }
+    }
+}
+
+impl From<&rpc_core::GetBlocksWithAcceptanceRequest> for protowire::GetBlocksWithAcceptanceRequestMessage {
+    fn from(item: &rpc_core::GetBlocksWithAcceptanceRequest) -> Self {
+        Self { low_hash: item.low_hash.to_string(), high_hash: item.high_hash.map(|x| x.to_string()).unwrap_or_default() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetBlocksWithAcceptanceResponse>> for protowire::GetBlocksWithAcceptanceResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetBlocksWithAcceptanceResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                blocks: response.blocks.iter().map(protowire::RpcChainBlockWithAcceptance::from).collect(),
+                has_more: response.has_more,
+                error: None,
+            },
+            Err(err) => Self { blocks: vec![], has_more: false, error: Some(err.into()) },
+        }
+    }
+}
+
 impl From<&rpc_core::NotifyBlockAddedRequest> for protowire::NotifyBlockAddedRequestMessage {
     fn from(item: &rpc_core::NotifyBlockAddedRequest) -> Self {
         Self { command: item.command.into() }
@@ -76,6 +275,43 @@ impl From<RpcResult<&rpc_core::NotifyBlockAddedResponse>> for protowire::NotifyB
     }
 }
 
+impl From<&rpc_core::GetBlockDagInfoRequest> for protowire::GetBlockDagInfoRequestMessage {
+    fn from(_item: &rpc_core::GetBlockDagInfoRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetBlockDagInfoResponse>> for protowire::GetBlockDagInfoResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetBlockDagInfoResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                network_name: response.network_name.clone(),
+                block_count: response.block_count,
+                header_count: response.header_count,
+                tip_hashes: response.tip_hashes.iter().map(|x| x.to_string()).collect(),
+                difficulty: response.difficulty,
+                past_median_time: response.past_median_time.try_into().expect("timestamp is always convertible to i64"),
+                virtual_parent_hashes: response.virtual_parent_hashes.iter().map(|x| x.to_string()).collect(),
+                pruning_point_hash: response.pruning_point_hash.to_string(),
+                virtual_daa_score: response.virtual_daa_score,
+                error: None,
+            },
+            Err(err) => Self {
+                network_name: String::default(),
+                block_count: 0,
+                header_count: 0,
+                tip_hashes: vec![],
+                difficulty: 0.0,
+                past_median_time: 0,
+                virtual_parent_hashes: vec![],
+                pruning_point_hash: String::default(),
+                virtual_daa_score: 0,
+                error: Some(err.into()),
+            },
+        }
+    }
+}
+
 impl From<&rpc_core::GetInfoRequest> for protowire::GetInfoRequestMessage {
     fn from(_item: &rpc_core::GetInfoRequest) -> Self {
         Self {}
@@ -107,135 +343,996 @@ impl From<RpcResult<&rpc_core::GetInfoResponse>> for protowire::GetInfoResponseM
     }
 }
 
-impl From<&rpc_core::NotifyNewBlockTemplateRequest> for protowire::NotifyNewBlockTemplateRequestMessage {
-    fn from(item: &rpc_core::NotifyNewBlockTemplateRequest) -> Self {
-        Self { command: item.command.into() }
+impl From<&rpc_core::GetChainThroughputStatsRequest> for protowire::GetChainThroughputStatsRequestMessage {
+    fn from(item: &rpc_core::GetChainThroughputStatsRequest) -> Self {
+        Self { window_size: item.window_size }
     }
 }
 
-impl From<RpcResult<&rpc_core::NotifyNewBlockTemplateResponse>> for protowire::NotifyNewBlockTemplateResponseMessage {
-    fn from(item: RpcResult<&rpc_core::NotifyNewBlockTemplateResponse>) -> Self {
-        Self { error: item.map_err(protowire::RpcError::from).err() }
+impl From<RpcResult<&rpc_core::GetChainThroughputStatsResponse>> for protowire::GetChainThroughputStatsResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetChainThroughputStatsResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                chain_block_count: response.chain_block_count,
+                merged_block_count: response.merged_block_count,
+                accepted_transaction_count: response.accepted_transaction_count,
+                accepted_mass: response.accepted_mass,
+                total_fees: response.total_fees,
+                time_span_millis: response.time_span_millis,
+                transactions_per_second: response.transactions_per_second,
+                mass_utilization: response.mass_utilization,
+                error: None,
+            },
+            Err(err) => Self {
+                chain_block_count: 0,
+                merged_block_count: 0,
+                accepted_transaction_count: 0,
+                accepted_mass: 0,
+                total_fees: 0,
+                time_span_millis: 0,
+                transactions_per_second: 0.0,
+                mass_utilization: 0.0,
+                error: Some(err.into()),
+            },
+        }
     }
 }
 
-// ----------------------------------------------------------------------------
-// protowire to rpc_core
-// ----------------------------------------------------------------------------
+impl From<&rpc_core::GetCoinbasePreviewRequest> for protowire::GetCoinbasePreviewRequestMessage {
+    fn from(item: &rpc_core::GetCoinbasePreviewRequest) -> Self {
+        Self {
+            pay_address: (&item.pay_address).into(),
+            extra_data: String::from_utf8(item.extra_data.clone()).expect("extra data has to be valid UTF-8"),
+        }
+    }
+}
 
-impl TryFrom<&protowire::SubmitBlockRequestMessage> for rpc_core::SubmitBlockRequest {
-    type Error = RpcError;
-    fn try_from(item: &protowire::SubmitBlockRequestMessage) -> RpcResult<Self> {
-        if item.block.is_none() {
-            return Err(RpcError::MissingRpcFieldError("SubmitBlockRequestMessage".to_string(), "block".to_string()));
+impl From<RpcResult<&rpc_core::GetCoinbasePreviewResponse>> for protowire::GetCoinbasePreviewResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetCoinbasePreviewResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                daa_score: response.daa_score,
+                blue_score: response.blue_score,
+                subsidy: response.subsidy,
+                merged_block_rewards: response.merged_block_rewards.iter().map(protowire::RpcMergesetBlockReward::from).collect(),
+                outputs: response.outputs.iter().map(protowire::RpcTransactionOutput::from).collect(),
+                error: None,
+            },
+            Err(err) => Self {
+                daa_score: 0,
+                blue_score: 0,
+                subsidy: 0,
+                merged_block_rewards: vec![],
+                outputs: vec![],
+                error: Some(err.into()),
+            },
         }
-        Ok(Self { block: item.block.as_ref().unwrap().try_into()?, allow_non_daa_blocks: item.allow_non_daa_blocks })
     }
 }
 
-impl From<RejectReason> for rpc_core::SubmitBlockReport {
-    fn from(item: RejectReason) -> Self {
+impl From<&rpc_core::GetMempoolFeeRateHistogramRequest> for protowire::GetMempoolFeeRateHistogramRequestMessage {
+    fn from(_item: &rpc_core::GetMempoolFeeRateHistogramRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetMempoolFeeRateHistogramResponse>> for protowire::GetMempoolFeeRateHistogramResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetMempoolFeeRateHistogramResponse>) -> Self {
         match item {
-            RejectReason::None => rpc_core::SubmitBlockReport::Success,
-            RejectReason::BlockInvalid => rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::BlockInvalid),
-            RejectReason::IsInIbd => rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::IsInIBD),
+            Ok(response) => Self {
+                buckets: response.buckets.iter().map(protowire::RpcFeeRateHistogramBucket::from).collect(),
+                max_block_mass: response.max_block_mass,
+                error: None,
+            },
+            Err(err) => Self { buckets: vec![], max_block_mass: 0, error: Some(err.into()) },
         }
     }
 }
 
-impl TryFrom<&protowire::SubmitBlockResponseMessage> for rpc_core::SubmitBlockResponse {
-    type Error = RpcError;
-    fn try_from(item: &protowire::SubmitBlockResponseMessage) -> RpcResult<Self> {
-        Ok(Self { report: RejectReason::from_i32(item.reject_reason).ok_or(RpcError::PrimitiveToEnumConversionError)?.into() })
+impl From<&rpc_core::GetMetricsRequest> for protowire::GetMetricsRequestMessage {
+    fn from(item: &rpc_core::GetMetricsRequest) -> Self {
+        Self { consensus_metrics: item.consensus_metrics, perf_metrics: item.perf_metrics }
     }
 }
 
-impl TryFrom<&protowire::GetBlockTemplateRequestMessage> for rpc_core::GetBlockTemplateRequest {
-    type Error = RpcError;
-    fn try_from(item: &protowire::GetBlockTemplateRequestMessage) -> RpcResult<Self> {
-        Ok(Self { pay_address: item.pay_address.clone().try_into()?, extra_data: RpcExtraData::from_iter(item.extra_data.bytes()) })
+impl From<RpcResult<&rpc_core::GetMetricsResponse>> for protowire::GetMetricsResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetMetricsResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                server_time: response.server_time.as_millis(),
+                consensus_metrics: response.consensus_metrics.as_ref().map(|x| x.into()),
+                perf_metrics: response.perf_metrics.as_ref().map(|x| x.into()),
+                error: None,
+            },
+            Err(err) => Self { server_time: 0, consensus_metrics: None, perf_metrics: None, error: Some(err.into()) },
+        }
     }
 }
 
-impl TryFrom<&protowire::GetBlockTemplateResponseMessage> for rpc_core::GetBlockTemplateResponse {
-    type Error = RpcError;
-    fn try_from(item: &protowire::GetBlockTemplateResponseMessage) -> RpcResult<Self> {
-        item.block
-            .as_ref()
-            .map_or_else(
-                || {
-                    item.error
-                        .as_ref()
-                        .map_or(Err(RpcError::MissingRpcFieldError("GetBlockResponseMessage".to_string(), "error".to_string())), |x| {
-                            Err(x.into())
-                        })
-                },
-                rpc_core::RpcBlock::try_from,
-            )
-            .map(|x| rpc_core::GetBlockTemplateResponse { block: x, is_synced: item.is_synced })
+impl From<&rpc_core::GetTransactionByIdRequest> for protowire::GetTransactionByIdRequestMessage {
+    fn from(item: &rpc_core::GetTransactionByIdRequest) -> Self {
+        Self { transaction_id: item.transaction_id.to_string() }
     }
 }
 
-impl TryFrom<&protowire::GetBlockRequestMessage> for rpc_core::GetBlockRequest {
-    type Error = RpcError;
-    fn try_from(item: &protowire::GetBlockRequestMessage) -> RpcResult<Self> {
-        Ok(Self { hash: RpcHash::from_str(&item.hash)?, include_transactions: item.include_transactions })
+impl From<RpcResult<&rpc_core::GetTransactionByIdResponse>> for protowire::GetTransactionByIdResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetTransactionByIdResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                transaction: response.transaction.as_ref().map(protowire::RpcTransaction::from),
+                including_block_hash: response.including_block_hash.to_string(),
+                accepting_block_hash: response.accepting_block_hash.to_string(),
+                accepting_daa_score: response.accepting_daa_score,
+                error: None,
+            },
+            Err(err) => Self {
+                transaction: None,
+                including_block_hash: String::default(),
+                accepting_block_hash: String::default(),
+                accepting_daa_score: 0,
+                error: Some(err.into()),
+            },
+        }
     }
 }
 
-impl TryFrom<&protowire::GetBlockResponseMessage> for rpc_core::GetBlockResponse {
-    type Error = RpcError;
-    fn try_from(item: &protowire::GetBlockResponseMessage) -> RpcResult<Self> {
-        item.block
-            .as_ref()
-            .map_or_else(
-                || {
-                    item.error
-                        .as_ref()
-                        .map_or(Err(RpcError::MissingRpcFieldError("GetBlockResponseMessage".to_string(), "error".to_string())), |x| {
-                            Err(x.into())
-                        })
-                },
-                rpc_core::RpcBlock::try_from,
-            )
-            .map(|x| rpc_core::GetBlockResponse { block: x })
+impl From<&rpc_core::GetPayloadRequest> for protowire::GetPayloadRequestMessage {
+    fn from(item: &rpc_core::GetPayloadRequest) -> Self {
+        Self { payload_hash: item.payload_hash.to_string() }
     }
 }
 
-impl TryFrom<&protowire::NotifyBlockAddedRequestMessage> for rpc_core::NotifyBlockAddedRequest {
-    type Error = RpcError;
-    fn try_from(item: &protowire::NotifyBlockAddedRequestMessage) -> RpcResult<Self> {
-        Ok(Self { command: item.command.into() })
+impl From<RpcResult<&rpc_core::GetPayloadResponse>> for protowire::GetPayloadResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetPayloadResponse>) -> Self {
+        match item {
+            Ok(response) => Self { payload: response.payload.to_rpc_hex(), error: None },
+            Err(err) => Self { payload: Default::default(), error: Some(err.into()) },
+        }
     }
 }
 
-impl TryFrom<&protowire::NotifyBlockAddedResponseMessage> for rpc_core::NotifyBlockAddedResponse {
-    type Error = RpcError;
-    fn try_from(item: &protowire::NotifyBlockAddedResponseMessage) -> RpcResult<Self> {
-        item.error.as_ref().map_or(Ok(rpc_core::NotifyBlockAddedResponse {}), |x| Err(x.into()))
+impl From<&rpc_core::GetConnectedPeerInfoRequest> for protowire::GetConnectedPeerInfoRequestMessage {
+    fn from(_: &rpc_core::GetConnectedPeerInfoRequest) -> Self {
+        Self {}
     }
 }
 
-impl TryFrom<&protowire::GetInfoRequestMessage> for rpc_core::GetInfoRequest {
-    type Error = RpcError;
-    fn try_from(_: &protowire::GetInfoRequestMessage) -> RpcResult<Self> {
-        Ok(Self {})
+impl From<RpcResult<&rpc_core::GetConnectedPeerInfoResponse>> for protowire::GetConnectedPeerInfoResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetConnectedPeerInfoResponse>) -> Self {
+        match item {
+            Ok(response) => Self { infos: response.peer_info.iter().map(|info| info.into()).collect(), error: None },
+            Err(err) => Self { infos: vec![], error: Some(err.into()) },
+        }
     }
 }
 
-impl TryFrom<&protowire::GetInfoResponseMessage> for rpc_core::GetInfoResponse {
-    type Error = RpcError;
-    fn try_from(item: &protowire::GetInfoResponseMessage) -> RpcResult<Self> {
-        if let Some(err) = item.error.as_ref() {
-            Err(err.into())
-        } else {
-            Ok(Self {
-                p2p_id: item.p2p_id.clone(),
-                mempool_size: item.mempool_size,
-                server_version: item.server_version.clone(),
-                is_utxo_indexed: item.is_utxo_indexed,
-                is_synced: item.is_synced,
-                has_notify_command: item.has_notify_command,
+impl From<&rpc_core::GetPeerAddressesRequest> for protowire::GetPeerAddressesRequestMessage {
+    fn from(_: &rpc_core::GetPeerAddressesRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetPeerAddressesResponse>> for protowire::GetPeerAddressesResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetPeerAddressesResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                addresses: response
+                    .known_addresses
+                    .iter()
+                    .map(|address| protowire::GetPeerAddressesKnownAddressMessage { addr: address.to_string() })
+                    .collect(),
+                banned_addresses: response
+                    .banned_addresses
+                    .iter()
+                    .map(|ip| protowire::GetPeerAddressesKnownAddressMessage { addr: ip.to_string() })
+                    .collect(),
+                error: None,
+            },
+            Err(err) => Self { addresses: vec![], banned_addresses: vec![], error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::AddPeerRequest> for protowire::AddPeerRequestMessage {
+    fn from(item: &rpc_core::AddPeerRequest) -> Self {
+        Self { address: item.peer_address.to_string(), is_permanent: item.is_permanent }
+    }
+}
+
+impl From<RpcResult<&rpc_core::AddPeerResponse>> for protowire::AddPeerResponseMessage {
+    fn from(item: RpcResult<&rpc_core::AddPeerResponse>) -> Self {
+        Self { error: item.map_err(protowire::RpcError::from).err() }
+    }
+}
+
+impl From<&rpc_core::BanRequest> for protowire::BanRequestMessage {
+    fn from(item: &rpc_core::BanRequest) -> Self {
+        Self { ip: item.ip.to_string() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::BanResponse>> for protowire::BanResponseMessage {
+    fn from(item: RpcResult<&rpc_core::BanResponse>) -> Self {
+        Self { error: item.map_err(protowire::RpcError::from).err() }
+    }
+}
+
+impl From<&rpc_core::UnbanRequest> for protowire::UnbanRequestMessage {
+    fn from(item: &rpc_core::UnbanRequest) -> Self {
+        Self { ip: item.ip.to_string() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::UnbanResponse>> for protowire::UnbanResponseMessage {
+    fn from(item: RpcResult<&rpc_core::UnbanResponse>) -> Self {
+        Self { error: item.map_err(protowire::RpcError::from).err() }
+    }
+}
+
+impl From<&rpc_core::PinBlocksRequest> for protowire::PinBlocksRequestMessage {
+    fn from(item: &rpc_core::PinBlocksRequest) -> Self {
+        Self { hashes: item.hashes.iter().map(|x| x.to_string()).collect() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::PinBlocksResponse>> for protowire::PinBlocksResponseMessage {
+    fn from(item: RpcResult<&rpc_core::PinBlocksResponse>) -> Self {
+        match item {
+            Ok(response) => Self { pinned_hashes: response.pinned_hashes.iter().map(|x| x.to_string()).collect(), error: None },
+            Err(err) => Self { pinned_hashes: vec![], error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::UnpinBlocksRequest> for protowire::UnpinBlocksRequestMessage {
+    fn from(item: &rpc_core::UnpinBlocksRequest) -> Self {
+        Self { hashes: item.hashes.iter().map(|x| x.to_string()).collect() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::UnpinBlocksResponse>> for protowire::UnpinBlocksResponseMessage {
+    fn from(item: RpcResult<&rpc_core::UnpinBlocksResponse>) -> Self {
+        match item {
+            Ok(response) => Self { pinned_hashes: response.pinned_hashes.iter().map(|x| x.to_string()).collect(), error: None },
+            Err(err) => Self { pinned_hashes: vec![], error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::DumpBlockRegionRequest> for protowire::DumpBlockRegionRequestMessage {
+    fn from(item: &rpc_core::DumpBlockRegionRequest) -> Self {
+        Self {
+            low_hash: item.low_hash.to_string(),
+            high_hash: item.high_hash.to_string(),
+            include_transactions: item.include_transactions,
+        }
+    }
+}
+
+impl From<RpcResult<&rpc_core::DumpBlockRegionResponse>> for protowire::DumpBlockRegionResponseMessage {
+    fn from(item: RpcResult<&rpc_core::DumpBlockRegionResponse>) -> Self {
+        match item {
+            Ok(response) => Self { json: response.json.clone(), error: None },
+            Err(err) => Self { json: String::default(), error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::RevalidateBlockRequest> for protowire::RevalidateBlockRequestMessage {
+    fn from(item: &rpc_core::RevalidateBlockRequest) -> Self {
+        Self { hash: item.hash.to_string() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::RevalidateBlockResponse>> for protowire::RevalidateBlockResponseMessage {
+    fn from(item: RpcResult<&rpc_core::RevalidateBlockResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                stored_status: response.stored_status.clone(),
+                body_revalidated: response.body_revalidated,
+                discrepancies: response.discrepancies.clone(),
+                error: None,
+            },
+            Err(err) => {
+                Self { stored_status: String::default(), body_revalidated: false, discrepancies: vec![], error: Some(err.into()) }
+            }
+        }
+    }
+}
+
+impl From<&rpc_core::NotifyNewBlockTemplateRequest> for protowire::NotifyNewBlockTemplateRequestMessage {
+    fn from(item: &rpc_core::NotifyNewBlockTemplateRequest) -> Self {
+        Self { command: item.command.into() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::NotifyNewBlockTemplateResponse>> for protowire::NotifyNewBlockTemplateResponseMessage {
+    fn from(item: RpcResult<&rpc_core::NotifyNewBlockTemplateResponse>) -> Self {
+        Self { error: item.map_err(protowire::RpcError::from).err() }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// protowire to rpc_core
+// ----------------------------------------------------------------------------
+
+impl TryFrom<&protowire::SubmitBlockRequestMessage> for rpc_core::SubmitBlockRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::SubmitBlockRequestMessage) -> RpcResult<Self> {
+        if item.block.is_none() {
+            return Err(RpcError::MissingRpcFieldError("SubmitBlockRequestMessage".to_string(), "block".to_string()));
+        }
+        Ok(Self { block: item.block.as_ref().unwrap().try_into()?, allow_non_daa_blocks: item.allow_non_daa_blocks })
+    }
+}
+
+impl TryFrom<&protowire::SubmitBlockResponseMessage> for rpc_core::SubmitBlockResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::SubmitBlockResponseMessage) -> RpcResult<Self> {
+        let reason = match RejectReason::from_i32(item.reject_reason).ok_or(RpcError::PrimitiveToEnumConversionError)? {
+            RejectReason::None => {
+                return item.error.as_ref().map_or(Ok(Self { report: rpc_core::SubmitBlockReport::Success }), |x| Err(x.into()))
+            }
+            RejectReason::BlockInvalid => {
+                // Recover the rule description from the error built out of the reject reason
+                let message = item.error.as_ref().map(|x| x.message.as_str()).unwrap_or_default();
+                let detail = message.strip_prefix("Block rejected. Reason: ").unwrap_or(message);
+                rpc_core::SubmitBlockRejectReason::BlockInvalid(detail.to_string())
+            }
+            RejectReason::IsInIbd => rpc_core::SubmitBlockRejectReason::IsInIBD,
+        };
+        Ok(Self { report: rpc_core::SubmitBlockReport::Reject(reason) })
+    }
+}
+
+impl TryFrom<&protowire::GetBlockTemplateRequestMessage> for rpc_core::GetBlockTemplateRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlockTemplateRequestMessage) -> RpcResult<Self> {
+        Ok(Self { pay_address: item.pay_address.clone().try_into()?, extra_data: RpcExtraData::from_iter(item.extra_data.bytes()) })
+    }
+}
+
+impl TryFrom<&protowire::GetBlockTemplateResponseMessage> for rpc_core::GetBlockTemplateResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlockTemplateResponseMessage) -> RpcResult<Self> {
+        item.block
+            .as_ref()
+            .map_or_else(
+                || {
+                    item.error
+                        .as_ref()
+                        .map_or(Err(RpcError::MissingRpcFieldError("GetBlockResponseMessage".to_string(), "error".to_string())), |x| {
+                            Err(x.into())
+                        })
+                },
+                rpc_core::RpcBlock::try_from,
+            )
+            .map(|x| rpc_core::GetBlockTemplateResponse { block: x, is_synced: item.is_synced })
+    }
+}
+
+impl TryFrom<&protowire::GetBlockRequestMessage> for rpc_core::GetBlockRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlockRequestMessage) -> RpcResult<Self> {
+        Ok(Self { hash: RpcHash::from_str(&item.hash)?, include_transactions: item.include_transactions })
+    }
+}
+
+impl TryFrom<&protowire::GetBlockResponseMessage> for rpc_core::GetBlockResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlockResponseMessage) -> RpcResult<Self> {
+        item.block
+            .as_ref()
+            .map_or_else(
+                || {
+                    item.error
+                        .as_ref()
+                        .map_or(Err(RpcError::MissingRpcFieldError("GetBlockResponseMessage".to_string(), "error".to_string())), |x| {
+                            Err(x.into())
+                        })
+                },
+                rpc_core::RpcBlock::try_from,
+            )
+            .map(|x| rpc_core::GetBlockResponse { block: x })
+    }
+}
+
+impl TryFrom<&protowire::GetBlocksRequestMessage> for rpc_core::GetBlocksRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlocksRequestMessage) -> RpcResult<Self> {
+        let low_hash = if item.low_hash.is_empty() { None } else { Some(RpcHash::from_str(&item.low_hash)?) };
+        Ok(Self { low_hash, include_blocks: item.include_blocks, include_transactions: item.include_transactions })
+    }
+}
+
+impl TryFrom<&protowire::GetBlocksResponseMessage> for rpc_core::GetBlocksResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlocksResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                block_hashes: item.block_hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+                blocks: item.blocks.iter().map(rpc_core::RpcBlock::try_from).collect::<RpcResult<Vec<_>>>()?,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::UtxosByAddressesEntry> for rpc_core::RpcUtxosByAddressesEntry {
+    type Error = RpcError;
+    fn try_from(item: &protowire::UtxosByAddressesEntry) -> RpcResult<Self> {
+        Ok(Self {
+            address: if item.address.is_empty() { None } else { Some(item.address.clone().try_into()?) },
+            outpoint: item
+                .outpoint
+                .as_ref()
+                .ok_or_else(|| RpcError::MissingRpcFieldError("UtxosByAddressesEntry".to_string(), "outpoint".to_string()))?
+                .try_into()?,
+            utxo_entry: item
+                .utxo_entry
+                .as_ref()
+                .ok_or_else(|| RpcError::MissingRpcFieldError("UtxosByAddressesEntry".to_string(), "utxo_entry".to_string()))?
+                .try_into()?,
+        })
+    }
+}
+
+impl TryFrom<&protowire::GetUtxosByAddressesRequestMessage> for rpc_core::GetUtxosByAddressesRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetUtxosByAddressesRequestMessage) -> RpcResult<Self> {
+        Ok(Self { addresses: item.addresses.iter().map(|x| x.clone().try_into()).collect::<Result<Vec<_>, _>>()? })
+    }
+}
+
+impl TryFrom<&protowire::GetUtxosByAddressesResponseMessage> for rpc_core::GetUtxosByAddressesResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetUtxosByAddressesResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { entries: item.entries.iter().map(rpc_core::RpcUtxosByAddressesEntry::try_from).collect::<RpcResult<Vec<_>>>()? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetMempoolEntryRequestMessage> for rpc_core::GetMempoolEntryRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMempoolEntryRequestMessage) -> RpcResult<Self> {
+        Ok(Self {
+            transaction_id: rpc_core::RpcTransactionId::from_str(&item.tx_id)?,
+            include_orphan_pool: item.include_orphan_pool,
+            filter_transaction_pool: item.filter_transaction_pool,
+        })
+    }
+}
+
+impl TryFrom<&protowire::GetMempoolEntryResponseMessage> for rpc_core::GetMempoolEntryResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMempoolEntryResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                entry: item
+                    .entry
+                    .as_ref()
+                    .ok_or_else(|| RpcError::MissingRpcFieldError("GetMempoolEntryResponseMessage".to_string(), "entry".to_string()))?
+                    .try_into()?,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetMempoolEntriesRequestMessage> for rpc_core::GetMempoolEntriesRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMempoolEntriesRequestMessage) -> RpcResult<Self> {
+        Ok(Self {
+            include_orphan_pool: item.include_orphan_pool,
+            filter_transaction_pool: item.filter_transaction_pool,
+            addresses: item.addresses.iter().map(|x| x.clone().try_into()).collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<&protowire::GetMempoolEntriesResponseMessage> for rpc_core::GetMempoolEntriesResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMempoolEntriesResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { entries: item.entries.iter().map(rpc_core::RpcMempoolEntry::try_from).collect::<RpcResult<Vec<_>>>()? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetBalanceByAddressRequestMessage> for rpc_core::GetBalanceByAddressRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBalanceByAddressRequestMessage) -> RpcResult<Self> {
+        Ok(Self { address: item.address.clone().try_into()? })
+    }
+}
+
+impl TryFrom<&protowire::GetBalanceByAddressResponseMessage> for rpc_core::GetBalanceByAddressResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBalanceByAddressResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { balance: item.balance })
+        }
+    }
+}
+
+impl TryFrom<&protowire::SubmitTransactionRequestMessage> for rpc_core::SubmitTransactionRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::SubmitTransactionRequestMessage) -> RpcResult<Self> {
+        let transaction = item
+            .transaction
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("SubmitTransactionRequestMessage".to_string(), "transaction".to_string()))?;
+        Ok(Self { transaction: transaction.try_into()?, allow_orphan: item.allow_orphan })
+    }
+}
+
+impl TryFrom<&protowire::SubmitTransactionResponseMessage> for rpc_core::SubmitTransactionResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::SubmitTransactionResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { transaction_id: rpc_core::RpcTransactionId::from_str(&item.transaction_id)? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetTransactionAcceptanceRequestMessage> for rpc_core::GetTransactionAcceptanceRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetTransactionAcceptanceRequestMessage) -> RpcResult<Self> {
+        Ok(Self { transaction_id: rpc_core::RpcTransactionId::from_str(&item.transaction_id)? })
+    }
+}
+
+impl TryFrom<&protowire::GetTransactionAcceptanceResponseMessage> for rpc_core::GetTransactionAcceptanceResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetTransactionAcceptanceResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            let accepting_block_hash =
+                if item.accepting_block_hash.is_empty() { None } else { Some(RpcHash::from_str(&item.accepting_block_hash)?) };
+            Ok(Self {
+                accepting_block_hash,
+                accepting_daa_score: item.accepting_daa_score,
+                is_beyond_retention: item.is_beyond_retention,
+                retention_root_daa_score: item.retention_root_daa_score,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetVirtualChainFromBlockRequestMessage> for rpc_core::GetVirtualChainFromBlockRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetVirtualChainFromBlockRequestMessage) -> RpcResult<Self> {
+        Ok(Self {
+            start_hash: RpcHash::from_str(&item.start_hash)?,
+            include_accepted_transaction_ids: item.include_accepted_transaction_ids,
+        })
+    }
+}
+
+impl TryFrom<&protowire::GetVirtualChainFromBlockResponseMessage> for rpc_core::GetVirtualChainFromBlockResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetVirtualChainFromBlockResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                removed_chain_block_hashes: item
+                    .removed_chain_block_hashes
+                    .iter()
+                    .map(|x| RpcHash::from_str(x))
+                    .collect::<Result<Vec<_>, _>>()?,
+                added_chain_block_hashes: item
+                    .added_chain_block_hashes
+                    .iter()
+                    .map(|x| RpcHash::from_str(x))
+                    .collect::<Result<Vec<_>, _>>()?,
+                accepted_transaction_ids: item
+                    .accepted_transaction_ids
+                    .iter()
+                    .map(rpc_core::RpcAcceptedTransactionIds::try_from)
+                    .collect::<RpcResult<Vec<_>>>()?,
+                has_more: item.has_more,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetBlocksWithAcceptanceRequestMessage> for rpc_core::GetBlocksWithAcceptanceRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlocksWithAcceptanceRequestMessage) -> RpcResult<Self> {
+        let high_hash = if item.high_hash.is_empty() { None } else { Some(RpcHash::from_str(&item.high_hash)?) };
+        Ok(Self { low_hash: RpcHash::from_str(&item.low_hash)?, high_hash })
+    }
+}
+
+impl TryFrom<&protowire::GetBlocksWithAcceptanceResponseMessage> for rpc_core::GetBlocksWithAcceptanceResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlocksWithAcceptanceResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                blocks: item.blocks.iter().map(rpc_core::RpcChainBlockWithAcceptance::try_from).collect::<RpcResult<Vec<_>>>()?,
+                has_more: item.has_more,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::NotifyBlockAddedRequestMessage> for rpc_core::NotifyBlockAddedRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::NotifyBlockAddedRequestMessage) -> RpcResult<Self> {
+        Ok(Self { command: item.command.into() })
+    }
+}
+
+impl TryFrom<&protowire::NotifyBlockAddedResponseMessage> for rpc_core::NotifyBlockAddedResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::NotifyBlockAddedResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(rpc_core::NotifyBlockAddedResponse {}), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::GetBlockDagInfoRequestMessage> for rpc_core::GetBlockDagInfoRequest {
+    type Error = RpcError;
+    fn try_from(_: &protowire::GetBlockDagInfoRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetBlockDagInfoResponseMessage> for rpc_core::GetBlockDagInfoResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetBlockDagInfoResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                network_name: item.network_name.clone(),
+                block_count: item.block_count,
+                header_count: item.header_count,
+                tip_hashes: item.tip_hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+                difficulty: item.difficulty,
+                past_median_time: item.past_median_time.try_into()?,
+                virtual_parent_hashes: item
+                    .virtual_parent_hashes
+                    .iter()
+                    .map(|x| RpcHash::from_str(x))
+                    .collect::<Result<Vec<_>, _>>()?,
+                pruning_point_hash: RpcHash::from_str(&item.pruning_point_hash)?,
+                virtual_daa_score: item.virtual_daa_score,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetInfoRequestMessage> for rpc_core::GetInfoRequest {
+    type Error = RpcError;
+    fn try_from(_: &protowire::GetInfoRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetInfoResponseMessage> for rpc_core::GetInfoResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetInfoResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                p2p_id: item.p2p_id.clone(),
+                mempool_size: item.mempool_size,
+                server_version: item.server_version.clone(),
+                is_utxo_indexed: item.is_utxo_indexed,
+                is_synced: item.is_synced,
+                has_notify_command: item.has_notify_command,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetChainThroughputStatsRequestMessage> for rpc_core::GetChainThroughputStatsRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetChainThroughputStatsRequestMessage) -> RpcResult<Self> {
+        Ok(Self { window_size: item.window_size })
+    }
+}
+
+impl TryFrom<&protowire::GetChainThroughputStatsResponseMessage> for rpc_core::GetChainThroughputStatsResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetChainThroughputStatsResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                chain_block_count: item.chain_block_count,
+                merged_block_count: item.merged_block_count,
+                accepted_transaction_count: item.accepted_transaction_count,
+                accepted_mass: item.accepted_mass,
+                total_fees: item.total_fees,
+                time_span_millis: item.time_span_millis,
+                transactions_per_second: item.transactions_per_second,
+                mass_utilization: item.mass_utilization,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetCoinbasePreviewRequestMessage> for rpc_core::GetCoinbasePreviewRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetCoinbasePreviewRequestMessage) -> RpcResult<Self> {
+        Ok(Self { pay_address: item.pay_address.clone().try_into()?, extra_data: RpcExtraData::from_iter(item.extra_data.bytes()) })
+    }
+}
+
+impl TryFrom<&protowire::GetCoinbasePreviewResponseMessage> for rpc_core::GetCoinbasePreviewResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetCoinbasePreviewResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                daa_score: item.daa_score,
+                blue_score: item.blue_score,
+                subsidy: item.subsidy,
+                merged_block_rewards: item
+                    .merged_block_rewards
+                    .iter()
+                    .map(rpc_core::RpcMergesetBlockReward::try_from)
+                    .collect::<RpcResult<Vec<_>>>()?,
+                outputs: item.outputs.iter().map(rpc_core::RpcTransactionOutput::try_from).collect::<RpcResult<Vec<_>>>()?,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetMempoolFeeRateHistogramRequestMessage> for rpc_core::GetMempoolFeeRateHistogramRequest {
+    type Error = RpcError;
+    fn try_from(_: &protowire::GetMempoolFeeRateHistogramRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetMempoolFeeRateHistogramResponseMessage> for rpc_core::GetMempoolFeeRateHistogramResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMempoolFeeRateHistogramResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                buckets: item.buckets.iter().map(rpc_core::RpcFeeRateHistogramBucket::try_from).collect::<RpcResult<Vec<_>>>()?,
+                max_block_mass: item.max_block_mass,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetMetricsRequestMessage> for rpc_core::GetMetricsRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMetricsRequestMessage) -> RpcResult<Self> {
+        Ok(Self { consensus_metrics: item.consensus_metrics, perf_metrics: item.perf_metrics })
+    }
+}
+
+impl TryFrom<&protowire::GetMetricsResponseMessage> for rpc_core::GetMetricsResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMetricsResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                server_time: rpc_core::RpcTimestamp::from_millis(item.server_time),
+                consensus_metrics: item.consensus_metrics.as_ref().map(rpc_core::RpcConsensusMetrics::try_from).transpose()?,
+                perf_metrics: item.perf_metrics.as_ref().map(rpc_core::RpcPerfMetrics::try_from).transpose()?,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetTransactionByIdRequestMessage> for rpc_core::GetTransactionByIdRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetTransactionByIdRequestMessage) -> RpcResult<Self> {
+        Ok(Self { transaction_id: rpc_core::RpcTransactionId::from_str(&item.transaction_id)? })
+    }
+}
+
+impl TryFrom<&protowire::GetTransactionByIdResponseMessage> for rpc_core::GetTransactionByIdResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetTransactionByIdResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                transaction: item.transaction.as_ref().map(rpc_core::RpcTransaction::try_from).transpose()?,
+                including_block_hash: RpcHash::from_str(&item.including_block_hash)?,
+                accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+                accepting_daa_score: item.accepting_daa_score,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetPayloadRequestMessage> for rpc_core::GetPayloadRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetPayloadRequestMessage) -> RpcResult<Self> {
+        Ok(Self { payload_hash: RpcHash::from_str(&item.payload_hash)? })
+    }
+}
+
+impl TryFrom<&protowire::GetPayloadResponseMessage> for rpc_core::GetPayloadResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetPayloadResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { payload: Vec::from_rpc_hex(&item.payload)? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetConnectedPeerInfoRequestMessage> for rpc_core::GetConnectedPeerInfoRequest {
+    type Error = RpcError;
+    fn try_from(_: &protowire::GetConnectedPeerInfoRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetConnectedPeerInfoResponseMessage> for rpc_core::GetConnectedPeerInfoResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetConnectedPeerInfoResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { peer_info: item.infos.iter().map(rpc_core::RpcPeerInfo::try_from).collect::<RpcResult<Vec<_>>>()? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::GetPeerAddressesRequestMessage> for rpc_core::GetPeerAddressesRequest {
+    type Error = RpcError;
+    fn try_from(_: &protowire::GetPeerAddressesRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetPeerAddressesResponseMessage> for rpc_core::GetPeerAddressesResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetPeerAddressesResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                known_addresses: item.addresses.iter().map(|address| address.addr.parse()).collect::<Result<_, _>>()?,
+                banned_addresses: item.banned_addresses.iter().map(|address| address.addr.parse()).collect::<Result<_, _>>()?,
+            })
+        }
+    }
+}
+
+impl TryFrom<&protowire::AddPeerRequestMessage> for rpc_core::AddPeerRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::AddPeerRequestMessage) -> RpcResult<Self> {
+        Ok(Self { peer_address: RpcPeerAddress::from_str(&item.address)?, is_permanent: item.is_permanent })
+    }
+}
+
+impl TryFrom<&protowire::AddPeerResponseMessage> for rpc_core::AddPeerResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::AddPeerResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(rpc_core::AddPeerResponse {}), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::BanRequestMessage> for rpc_core::BanRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::BanRequestMessage) -> RpcResult<Self> {
+        Ok(Self { ip: RpcIpAddress::from_str(&item.ip)? })
+    }
+}
+
+impl TryFrom<&protowire::BanResponseMessage> for rpc_core::BanResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::BanResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(rpc_core::BanResponse {}), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::UnbanRequestMessage> for rpc_core::UnbanRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::UnbanRequestMessage) -> RpcResult<Self> {
+        Ok(Self { ip: RpcIpAddress::from_str(&item.ip)? })
+    }
+}
+
+impl TryFrom<&protowire::UnbanResponseMessage> for rpc_core::UnbanResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::UnbanResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(rpc_core::UnbanResponse {}), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::PinBlocksRequestMessage> for rpc_core::PinBlocksRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::PinBlocksRequestMessage) -> RpcResult<Self> {
+        Ok(Self { hashes: item.hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()? })
+    }
+}
+
+impl TryFrom<&protowire::PinBlocksResponseMessage> for rpc_core::PinBlocksResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::PinBlocksResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { pinned_hashes: item.pinned_hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::UnpinBlocksRequestMessage> for rpc_core::UnpinBlocksRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::UnpinBlocksRequestMessage) -> RpcResult<Self> {
+        Ok(Self { hashes: item.hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()? })
+    }
+}
+
+impl TryFrom<&protowire::UnpinBlocksResponseMessage> for rpc_core::UnpinBlocksResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::UnpinBlocksResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self { pinned_hashes: item.pinned_hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()? })
+        }
+    }
+}
+
+impl TryFrom<&protowire::DumpBlockRegionRequestMessage> for rpc_core::DumpBlockRegionRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::DumpBlockRegionRequestMessage) -> RpcResult<Self> {
+        Ok(Self {
+            low_hash: RpcHash::from_str(&item.low_hash)?,
+            high_hash: RpcHash::from_str(&item.high_hash)?,
+            include_transactions: item.include_transactions,
+        })
+    }
+}
+
+impl TryFrom<&protowire::DumpBlockRegionResponseMessage> for rpc_core::DumpBlockRegionResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::DumpBlockRegionResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(rpc_core::DumpBlockRegionResponse { json: item.json.clone() }), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::RevalidateBlockRequestMessage> for rpc_core::RevalidateBlockRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::RevalidateBlockRequestMessage) -> RpcResult<Self> {
+        Ok(Self { hash: RpcHash::from_str(&item.hash)? })
+    }
+}
+
+impl TryFrom<&protowire::RevalidateBlockResponseMessage> for rpc_core::RevalidateBlockResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::RevalidateBlockResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                stored_status: item.stored_status.clone(),
+                body_revalidated: item.body_revalidated,
+                discrepancies: item.discrepancies.clone(),
             })
         }
     }
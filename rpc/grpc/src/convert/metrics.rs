@@ -0,0 +1,72 @@
+use crate::protowire;
+use rpc_core::{RpcError, RpcResult, RpcTimestamp};
+
+// ----------------------------------------------------------------------------
+// rpc_core to protowire
+// ----------------------------------------------------------------------------
+
+impl From<&rpc_core::RpcConsensusMetrics> for protowire::ConsensusMetrics {
+    fn from(item: &rpc_core::RpcConsensusMetrics) -> Self {
+        Self {
+            virtual_daa_score: item.virtual_daa_score,
+            sink_timestamp: item.sink_timestamp.as_millis(),
+            expected_daa_score: item.expected_daa_score,
+            daa_score_drift: item.daa_score_drift,
+        }
+    }
+}
+
+impl From<&rpc_core::RpcPerfMetrics> for protowire::PerfMetrics {
+    fn from(item: &rpc_core::RpcPerfMetrics) -> Self {
+        Self {
+            sample_time: item.sample_time.as_millis(),
+            cpu_usage: item.cpu_usage,
+            resident_set_size: item.resident_set_size,
+            disk_read_rate: item.disk_read_rate,
+            disk_write_rate: item.disk_write_rate,
+            db_sst_files_size: item.db_sst_files_size,
+            db_memtables_size: item.db_memtables_size,
+            db_pending_compaction_bytes: item.db_pending_compaction_bytes,
+            db_running_compactions: item.db_running_compactions,
+            header_queue_length: item.header_queue_length,
+            body_queue_length: item.body_queue_length,
+            virtual_queue_length: item.virtual_queue_length,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// protowire to rpc_core
+// ----------------------------------------------------------------------------
+
+impl TryFrom<&protowire::ConsensusMetrics> for rpc_core::RpcConsensusMetrics {
+    type Error = RpcError;
+    fn try_from(item: &protowire::ConsensusMetrics) -> RpcResult<Self> {
+        Ok(Self {
+            virtual_daa_score: item.virtual_daa_score,
+            sink_timestamp: RpcTimestamp::from_millis(item.sink_timestamp),
+            expected_daa_score: item.expected_daa_score,
+            daa_score_drift: item.daa_score_drift,
+        })
+    }
+}
+
+impl TryFrom<&protowire::PerfMetrics> for rpc_core::RpcPerfMetrics {
+    type Error = RpcError;
+    fn try_from(item: &protowire::PerfMetrics) -> RpcResult<Self> {
+        Ok(Self {
+            sample_time: RpcTimestamp::from_millis(item.sample_time),
+            cpu_usage: item.cpu_usage,
+            resident_set_size: item.resident_set_size,
+            disk_read_rate: item.disk_read_rate,
+            disk_write_rate: item.disk_write_rate,
+            db_sst_files_size: item.db_sst_files_size,
+            db_memtables_size: item.db_memtables_size,
+            db_pending_compaction_bytes: item.db_pending_compaction_bytes,
+            db_running_compactions: item.db_running_compactions,
+            header_queue_length: item.header_queue_length,
+            body_queue_length: item.body_queue_length,
+            virtual_queue_length: item.virtual_queue_length,
+        })
+    }
+}
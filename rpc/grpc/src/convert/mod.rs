@@ -2,6 +2,9 @@ pub mod block;
 pub mod error;
 pub mod header;
 pub mod kaspad;
+pub mod mempool;
 pub mod message;
+pub mod metrics;
 pub mod notification;
+pub mod peer;
 pub mod tx;
@@ -1,4 +1,4 @@
-use rpc_core::{Notification, RpcError, RpcResult};
+use rpc_core::{notify::events::EventType, Notification, RpcError, RpcResult};
 
 use crate::protowire::{
     kaspad_response::Payload, BlockAddedNotificationMessage, KaspadResponse, NewBlockTemplateNotificationMessage, RpcNotifyCommand,
@@ -8,17 +8,19 @@ use crate::protowire::{
 // rpc_core to protowire
 // ----------------------------------------------------------------------------
 
-impl From<&rpc_core::Notification> for KaspadResponse {
-    fn from(item: &rpc_core::Notification) -> Self {
-        Self { payload: Some(item.into()) }
+impl TryFrom<&rpc_core::Notification> for KaspadResponse {
+    type Error = RpcError;
+    fn try_from(item: &rpc_core::Notification) -> RpcResult<Self> {
+        Ok(Self { payload: Some(item.try_into()?) })
     }
 }
 
-impl From<&rpc_core::Notification> for Payload {
-    fn from(item: &rpc_core::Notification) -> Self {
+impl TryFrom<&rpc_core::Notification> for Payload {
+    type Error = RpcError;
+    fn try_from(item: &rpc_core::Notification) -> RpcResult<Self> {
         match item {
-            Notification::BlockAdded(ref notif) => Payload::BlockAddedNotification(notif.into()),
-            Notification::NewBlockTemplate(ref notif) => Payload::NewBlockTemplateNotification(notif.into()),
+            Notification::BlockAdded(ref notif) => Ok(Payload::BlockAddedNotification(notif.into())),
+            Notification::NewBlockTemplate(ref notif) => Ok(Payload::NewBlockTemplateNotification(notif.into())),
             Notification::VirtualSelectedParentChainChanged(_) => todo!(),
             Notification::FinalityConflict(_) => todo!(),
             Notification::FinalityConflictResolved(_) => todo!(),
@@ -26,6 +28,10 @@ impl From<&rpc_core::Notification> for Payload {
             Notification::VirtualSelectedParentBlueScoreChanged(_) => todo!(),
             Notification::VirtualDaaScoreChanged(_) => todo!(),
             Notification::PruningPointUTXOSetOverride(_) => todo!(),
+            // The protowire has no transaction acceptance notification messages
+            Notification::TransactionAccepted(_) | Notification::TransactionReverted(_) => {
+                Err(RpcError::UnsupportedNotificationType(EventType::from(item)))
+            }
         }
     }
 }
@@ -47,6 +53,7 @@ impl From<rpc_core::api::ops::SubscribeCommand> for RpcNotifyCommand {
         match item {
             rpc_core::api::ops::SubscribeCommand::Start => RpcNotifyCommand::NotifyStart,
             rpc_core::api::ops::SubscribeCommand::Stop => RpcNotifyCommand::NotifyStop,
+            rpc_core::api::ops::SubscribeCommand::ModifyScope => RpcNotifyCommand::NotifyModifyScope,
         }
     }
 }
@@ -98,6 +105,7 @@ impl From<RpcNotifyCommand> for rpc_core::api::ops::SubscribeCommand {
         match item {
             RpcNotifyCommand::NotifyStart => rpc_core::api::ops::SubscribeCommand::Start,
             RpcNotifyCommand::NotifyStop => rpc_core::api::ops::SubscribeCommand::Stop,
+            RpcNotifyCommand::NotifyModifyScope => rpc_core::api::ops::SubscribeCommand::ModifyScope,
         }
     }
 }
@@ -0,0 +1,44 @@
+use crate::protowire;
+use rpc_core::{RpcError, RpcPeerAddress, RpcResult};
+use std::str::FromStr;
+
+// ----------------------------------------------------------------------------
+// rpc_core to protowire
+// ----------------------------------------------------------------------------
+
+impl From<&rpc_core::RpcPeerInfo> for protowire::GetConnectedPeerInfoMessage {
+    fn from(item: &rpc_core::RpcPeerInfo) -> Self {
+        Self {
+            id: item.id.clone(),
+            address: item.address.to_string(),
+            // Pings are not measured yet
+            last_ping_duration: 0,
+            is_outbound: item.is_outbound,
+            time_offset: item.time_offset,
+            user_agent: item.user_agent.clone(),
+            advertised_protocol_version: item.advertised_protocol_version,
+            time_connected: item.time_connected.try_into().expect("timestamp is always convertible to i64"),
+            // The IBD peer is not tracked yet
+            is_ibd_peer: false,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// protowire to rpc_core
+// ----------------------------------------------------------------------------
+
+impl TryFrom<&protowire::GetConnectedPeerInfoMessage> for rpc_core::RpcPeerInfo {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetConnectedPeerInfoMessage) -> RpcResult<Self> {
+        Ok(Self {
+            id: item.id.clone(),
+            address: RpcPeerAddress::from_str(&item.address)?,
+            is_outbound: item.is_outbound,
+            time_offset: item.time_offset,
+            user_agent: item.user_agent.clone(),
+            advertised_protocol_version: item.advertised_protocol_version,
+            time_connected: item.time_connected.try_into()?,
+        })
+    }
+}
@@ -74,6 +74,7 @@ impl From<&rpc_core::RpcTransactionVerboseData> for protowire::RpcTransactionVer
             mass: item.mass,
             block_hash: item.block_hash.to_string(),
             block_time: item.block_time,
+            confirmations: item.confirmations,
         }
     }
 }
@@ -192,6 +193,7 @@ impl TryFrom<&protowire::RpcTransactionVerboseData> for rpc_core::RpcTransaction
             mass: item.mass,
             block_hash: RpcHash::from_str(&item.block_hash)?,
             block_time: item.block_time,
+            confirmations: item.confirmations,
         })
     }
 }
@@ -65,7 +65,14 @@ impl GrpcConnection {
                         match notification {
                             Ok(notification) => {
                                 trace!("sending {} to listener id {}", notification, listener_id);
-                                match sender.send(Ok((&*notification).into())).await {
+                                let response = match KaspadResponse::try_from(&*notification) {
+                                    Ok(response) => response,
+                                    Err(err) => {
+                                        trace!("[Connection] notification conversion error: {:?}", err);
+                                        continue;
+                                    }
+                                };
+                                match sender.send(Ok(response)).await {
                                     Ok(_) => (),
                                     Err(err) => {
 
@@ -111,18 +118,27 @@ impl GrpcConnectionManager {
         let connection = Arc::new(GrpcConnection::new(address, sender, notify_listener));
         trace!("registering a new gRPC connection from: {0} with listener id {1}", connection.address, connection.notify_listener.id);
 
-        // A pre-existing connection with same address is ignored here
-        // TODO: see if some close pattern can be applied to the replaced connection
-        self.connections.insert(address, connection.clone());
+        // A pre-existing connection with same address is replaced
+        if let Some(replaced) = self.connections.insert(address, connection.clone()) {
+            self.dismiss(replaced).await;
+        }
         connection.clone().start();
         connection.notify_listener.id
     }
 
     pub(crate) async fn unregister(&mut self, address: SocketAddr) {
         if let Some(connection) = self.connections.remove(&address) {
-            trace!("dismiss a gRPC connection from: {}", connection.address);
-            //connection.sender.closed().await;
-            connection.stop().await;
+            self.dismiss(connection).await;
+        }
+    }
+
+    /// Stops the connection and unregisters its listener, so that the notifier drops its subscriptions
+    async fn dismiss(&self, connection: Arc<GrpcConnection>) {
+        trace!("dismiss a gRPC connection from: {}", connection.address);
+        let listener_id = connection.notify_listener.id;
+        connection.stop().await;
+        if let Err(err) = self.notifier.unregister_listener(listener_id) {
+            trace!("[GrpcConnectionManager] unregister listener {0} error: {1:?}", listener_id, err);
         }
     }
 }
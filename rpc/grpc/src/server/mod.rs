@@ -4,7 +4,7 @@ use kaspa_core::{
     trace,
 };
 use kaspa_utils::triggers::DuplexTrigger;
-use rpc_core::server::service::RpcCoreService;
+use rpc_core::api::rpc::DynRpcService;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tonic::{codec::CompressionEncoding, transport::Server};
@@ -23,7 +23,7 @@ pub struct GrpcServer {
 }
 
 impl GrpcServer {
-    pub fn new(address: SocketAddr, core_service: Arc<RpcCoreService>) -> Self {
+    pub fn new(address: SocketAddr, core_service: DynRpcService) -> Self {
         let grpc_service = Arc::new(service::GrpcService::new(core_service));
         Self { address, grpc_service, shutdown: DuplexTrigger::default() }
     }
@@ -1,21 +1,26 @@
 use super::connection::{GrpcConnectionManager, GrpcSender};
 use crate::protowire::NotifyNewBlockTemplateResponseMessage;
 use crate::protowire::{
-    kaspad_request::Payload, rpc_server::Rpc, GetBlockResponseMessage, GetBlockTemplateResponseMessage, GetInfoResponseMessage,
-    KaspadRequest, KaspadResponse, NotifyBlockAddedResponseMessage, SubmitBlockResponseMessage,
+    kaspad_request::Payload, rpc_server::Rpc, AddPeerResponseMessage, BanResponseMessage, DumpBlockRegionResponseMessage,
+    GetBalanceByAddressResponseMessage, GetBlockDagInfoResponseMessage, GetBlockResponseMessage, GetBlockTemplateResponseMessage,
+    GetBlocksResponseMessage, GetBlocksWithAcceptanceResponseMessage, GetChainThroughputStatsResponseMessage,
+    GetCoinbasePreviewResponseMessage, GetConnectedPeerInfoResponseMessage, GetInfoResponseMessage, GetMempoolEntriesResponseMessage,
+    GetMempoolEntryResponseMessage, GetMempoolFeeRateHistogramResponseMessage, GetMetricsResponseMessage, GetPayloadResponseMessage,
+    GetPeerAddressesResponseMessage, GetTransactionAcceptanceResponseMessage, GetTransactionByIdResponseMessage,
+    GetUtxosByAddressesResponseMessage, GetVirtualChainFromBlockResponseMessage, KaspadRequest, KaspadResponse,
+    NotifyBlockAddedResponseMessage, PinBlocksResponseMessage, RevalidateBlockResponseMessage, SubmitBlockResponseMessage,
+    SubmitTransactionResponseMessage, UnbanResponseMessage, UnpinBlocksResponseMessage,
 };
 use crate::server::StatusResult;
 use futures::Stream;
 use kaspa_core::trace;
 use rpc_core::notify::channel::NotificationChannel;
 use rpc_core::notify::listener::{ListenerID, ListenerReceiverSide, ListenerUtxoNotificationFilterSetting};
-use rpc_core::notify::subscriber::DynSubscriptionManager;
-use rpc_core::notify::subscriber::Subscriber;
+use rpc_core::notify::subscriber::{RpcServiceSubscriptionManager, Subscriber};
 use rpc_core::RpcResult;
 use rpc_core::{
-    api::rpc::RpcApi,
+    api::rpc::{DynRpcService, RpcApi},
     notify::{collector::RpcCoreCollector, events::EVENT_TYPE_ARRAY, notifier::Notifier},
-    server::service::RpcCoreService,
 };
 use std::{io::ErrorKind, net::SocketAddr, pin::Pin, sync::Arc};
 use tokio::sync::{mpsc, RwLock};
@@ -24,7 +29,8 @@ use tonic::{Request, Response};
 
 /// A protowire RPC service.
 ///
-/// Relay requests to a central core service that queries the consensus.
+/// Relay requests to a central core service, usually the [`rpc_core::server::service::RpcCoreService`] querying
+/// the consensus.
 ///
 /// Registers into a central core service in order to receive consensus notifications and
 /// send those forward to the registered clients.
@@ -52,7 +58,7 @@ use tonic::{Request, Response};
 ///
 /// TODO: implement a queue of requests and a pool of workers preparing and sending back the responses.
 pub struct GrpcService {
-    core_service: Arc<RpcCoreService>,
+    core_service: DynRpcService,
     core_channel: NotificationChannel,
     core_listener: Arc<ListenerReceiverSide>,
     connection_manager: Arc<RwLock<GrpcConnectionManager>>,
@@ -60,14 +66,14 @@ pub struct GrpcService {
 }
 
 impl GrpcService {
-    pub fn new(core_service: Arc<RpcCoreService>) -> Self {
+    pub fn new(core_service: DynRpcService) -> Self {
         // Prepare core objects
         let core_channel = NotificationChannel::default();
         let core_listener = Arc::new(core_service.register_new_listener(Some(core_channel.clone())));
 
         // Prepare internals
         let collector = Arc::new(RpcCoreCollector::new(core_channel.receiver()));
-        let subscription_manager: DynSubscriptionManager = core_service.notifier();
+        let subscription_manager = Arc::new(RpcServiceSubscriptionManager::new(core_service.clone()));
         let subscriber = Subscriber::new(subscription_manager, core_listener.id);
         let notifier =
             Arc::new(Notifier::new(Some(collector), Some(subscriber), ListenerUtxoNotificationFilterSetting::FilteredByAddress));
@@ -176,6 +182,131 @@ impl Rpc for Arc<GrpcService> {
                                 Err(err) => GetInfoResponseMessage::from(err).into(),
                             },
 
+                            Some(Payload::GetChainThroughputStatsRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_chain_throughput_stats_call(request).await.into(),
+                                Err(err) => GetChainThroughputStatsResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::RevalidateBlockRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.revalidate_block_call(request).await.into(),
+                                Err(err) => RevalidateBlockResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetCoinbasePreviewRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_coinbase_preview_call(request).await.into(),
+                                Err(err) => GetCoinbasePreviewResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetMempoolFeeRateHistogramRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_mempool_fee_rate_histogram_call(request).await.into(),
+                                Err(err) => GetMempoolFeeRateHistogramResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::SubmitTransactionRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.submit_transaction_call(request).await.into(),
+                                Err(err) => SubmitTransactionResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetVirtualChainFromBlockRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_virtual_chain_from_block_call(request).await.into(),
+                                Err(err) => GetVirtualChainFromBlockResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetTransactionAcceptanceRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_transaction_acceptance_call(request).await.into(),
+                                Err(err) => GetTransactionAcceptanceResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetTransactionByIdRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_transaction_by_id_call(request).await.into(),
+                                Err(err) => GetTransactionByIdResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetBlocksRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_blocks_call(request).await.into(),
+                                Err(err) => GetBlocksResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetUtxosByAddressesRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_utxos_by_addresses_call(request).await.into(),
+                                Err(err) => GetUtxosByAddressesResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetBalanceByAddressRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_balance_by_address_call(request).await.into(),
+                                Err(err) => GetBalanceByAddressResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetMempoolEntryRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_mempool_entry_call(request).await.into(),
+                                Err(err) => GetMempoolEntryResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetMempoolEntriesRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_mempool_entries_call(request).await.into(),
+                                Err(err) => GetMempoolEntriesResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetBlockDagInfoRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_block_dag_info_call(request).await.into(),
+                                Err(err) => GetBlockDagInfoResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetBlocksWithAcceptanceRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_blocks_with_acceptance_call(request).await.into(),
+                                Err(err) => GetBlocksWithAcceptanceResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetMetricsRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_metrics_call(request).await.into(),
+                                Err(err) => GetMetricsResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetPayloadRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_payload_call(request).await.into(),
+                                Err(err) => GetPayloadResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetPeerAddressesRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_peer_addresses_call(request).await.into(),
+                                Err(err) => GetPeerAddressesResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::GetConnectedPeerInfoRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.get_connected_peer_info_call(request).await.into(),
+                                Err(err) => GetConnectedPeerInfoResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::AddPeerRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.add_peer_call(request).await.into(),
+                                Err(err) => AddPeerResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::BanRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.ban_call(request).await.into(),
+                                Err(err) => BanResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::UnbanRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.unban_call(request).await.into(),
+                                Err(err) => UnbanResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::PinBlocksRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.pin_blocks_call(request).await.into(),
+                                Err(err) => PinBlocksResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::UnpinBlocksRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.unpin_blocks_call(request).await.into(),
+                                Err(err) => UnpinBlocksResponseMessage::from(err).into(),
+                            },
+
+                            Some(Payload::DumpBlockRegionRequest(ref request)) => match request.try_into() {
+                                Ok(request) => core_service.dump_block_region_call(request).await.into(),
+                                Err(err) => DumpBlockRegionResponseMessage::from(err).into(),
+                            },
+
                             Some(Payload::NotifyBlockAddedRequest(ref request)) => NotifyBlockAddedResponseMessage::from({
                                 let request = rpc_core::NotifyBlockAddedRequest::try_from(request).unwrap();
                                 notifier.clone().execute_subscribe_command(
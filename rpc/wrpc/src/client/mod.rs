@@ -0,0 +1,506 @@
+use crate::{
+    codec::{BorshCodec, Codec, JsonCodec},
+    errors::Error,
+    message::{Request, ServerMessage, SubscribeRequest, SubscribeResponse},
+    result::Result,
+};
+use async_trait::async_trait;
+use borsh::{BorshDeserialize, BorshSerialize};
+use futures::{SinkExt, StreamExt};
+use kaspa_core::trace;
+use rpc_core::{
+    api::{
+        ops::{RpcApiOps, SubscribeCommand},
+        rpc::RpcApi,
+    },
+    notify::{
+        channel::NotificationChannel,
+        collector::RpcCoreCollector,
+        listener::{ListenerID, ListenerReceiverSide, ListenerUtxoNotificationFilterSetting},
+        notifier::Notifier,
+        subscriber::{Subscriber, SubscriptionManager},
+    },
+    AddPeerRequest, AddPeerResponse, BanRequest, BanResponse, DumpBlockRegionRequest, DumpBlockRegionResponse,
+    GetBalanceByAddressRequest, GetBalanceByAddressResponse, GetBlockDagInfoRequest, GetBlockDagInfoResponse, GetBlockRequest,
+    GetBlockResponse, GetBlockTemplateRequest, GetBlockTemplateResponse, GetBlocksRequest, GetBlocksResponse,
+    GetBlocksWithAcceptanceRequest, GetBlocksWithAcceptanceResponse, GetChainThroughputStatsRequest, GetChainThroughputStatsResponse,
+    GetCoinbasePreviewRequest, GetCoinbasePreviewResponse, GetConnectedPeerInfoRequest, GetConnectedPeerInfoResponse, GetInfoRequest,
+    GetInfoResponse, GetMempoolEntriesRequest, GetMempoolEntriesResponse, GetMempoolEntryRequest, GetMempoolEntryResponse,
+    GetMempoolFeeRateHistogramRequest, GetMempoolFeeRateHistogramResponse, GetMetricsRequest, GetMetricsResponse, GetPayloadRequest,
+    GetPayloadResponse, GetPeerAddressesRequest, GetPeerAddressesResponse, GetTransactionAcceptanceRequest,
+    GetTransactionAcceptanceResponse, GetTransactionByIdRequest, GetTransactionByIdResponse, GetUtxosByAddressesRequest,
+    GetUtxosByAddressesResponse, GetVirtualChainFromBlockRequest, GetVirtualChainFromBlockResponse, Notification, NotificationSender,
+    NotificationType, PinBlocksRequest, PinBlocksResponse, RevalidateBlockRequest, RevalidateBlockResponse, RpcResult,
+    SubmitBlockRequest, SubmitBlockResponse, SubmitTransactionRequest, SubmitTransactionResponse, UnbanRequest, UnbanResponse,
+    UnpinBlocksRequest, UnpinBlocksResponse,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage, MaybeTlsStream, WebSocketStream};
+
+pub const CONNECT_RETRY_COUNT: usize = 5;
+pub const CONNECT_RETRY_INTERVAL: u64 = 1_000;
+pub const REQUEST_TIMEOUT_DURATION: u64 = 5_000;
+
+/// The capacity of the queue of outgoing messages
+const OUTGOING_CAPACITY: usize = 128;
+
+pub type WrpcBorshClient = WrpcClient<BorshCodec>;
+pub type WrpcJsonClient = WrpcClient<JsonCodec>;
+
+/// A wRPC client of a kaspad node, implementing [`RpcApi`] over a WebSocket served with the encoding of `C`.
+///
+/// Requests are multiplexed over the socket and fail with [`Error::Timeout`] when not answered within the request
+/// timeout. Notifications are received by the listeners registered with [`RpcApi::register_new_listener`] once the
+/// client is started. A closed socket fails all pending and later requests with [`Error::Disconnected`].
+pub struct WrpcClient<C: Codec> {
+    inner: Arc<Inner<C>>,
+    notifier: Arc<Notifier>,
+}
+
+impl<C: Codec> WrpcClient<C> {
+    /// Connects to a wRPC server, e.g. `ws://127.0.0.1:17110`
+    pub async fn connect(url: &str) -> Result<Self> {
+        Self::connect_with_timeout(url, Duration::from_millis(REQUEST_TIMEOUT_DURATION)).await
+    }
+
+    /// Connects to a wRPC server, retrying up to [`CONNECT_RETRY_COUNT`] times, [`CONNECT_RETRY_INTERVAL`] apart,
+    /// and fails requests which are not answered within `request_timeout`
+    pub async fn connect_with_timeout(url: &str, request_timeout: Duration) -> Result<Self> {
+        let socket = connect_with_retry(url).await?;
+        let notify_channel = NotificationChannel::default();
+        let inner = Inner::<C>::start(socket, request_timeout, notify_channel.sender());
+        let collector = Arc::new(RpcCoreCollector::new(notify_channel.receiver()));
+        let subscriber = Subscriber::new(inner.clone(), 0);
+
+        let notifier =
+            Arc::new(Notifier::new(Some(collector), Some(subscriber), ListenerUtxoNotificationFilterSetting::FilteredByAddress));
+
+        Ok(Self { inner, notifier })
+    }
+
+    pub async fn start(&self) {
+        self.notifier.clone().start();
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        self.notifier.clone().stop().await?;
+        Ok(())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.inner.is_connected.load(Ordering::SeqCst)
+    }
+
+    /// Closes the socket, failing all pending requests
+    pub async fn disconnect(&self) -> Result<()> {
+        self.inner.sender.send(WsMessage::Close(None)).await.map_err(|_| Error::Disconnected)
+    }
+}
+
+async fn connect_with_retry(url: &str) -> Result<WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>> {
+    let mut attempt = 0;
+    loop {
+        match connect_async(url).await {
+            Ok((socket, _)) => return Ok(socket),
+            Err(err) if attempt < CONNECT_RETRY_COUNT => {
+                attempt += 1;
+                trace!("[WrpcClient] connecting to {0} failed, retrying ({1}/{2}): {3}", url, attempt, CONNECT_RETRY_COUNT, err);
+                tokio::time::sleep(Duration::from_millis(CONNECT_RETRY_INTERVAL)).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// The socket side of a [`WrpcClient`], matching the responses to the pending requests by id and pushing the
+/// notifications forward
+struct Inner<C: Codec> {
+    sender: mpsc::Sender<WsMessage>,
+    pending: Mutex<HashMap<u64, oneshot::Sender<Result<C::Params>>>>,
+    next_id: AtomicU64,
+    request_timeout: Duration,
+    is_connected: AtomicBool,
+    _codec: PhantomData<C>,
+}
+
+impl<C: Codec> Inner<C> {
+    /// Spawns the tasks writing to and reading from the socket
+    fn start(
+        socket: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+        request_timeout: Duration,
+        notify_send: NotificationSender,
+    ) -> Arc<Self> {
+        let (mut sink, mut stream) = socket.split();
+        let (sender, mut receiver) = mpsc::channel::<WsMessage>(OUTGOING_CAPACITY);
+        let inner = Arc::new(Self {
+            sender,
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            request_timeout,
+            is_connected: AtomicBool::new(true),
+            _codec: PhantomData,
+        });
+
+        // Writer, ending once the socket is closed
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                if let Err(err) = sink.send(message).await {
+                    trace!("[WrpcClient] send error: {0}", err);
+                    break;
+                }
+            }
+            let _ = sink.close().await;
+        });
+
+        // Reader, ending once the socket is closed
+        let reader = inner.clone();
+        tokio::spawn(async move {
+            while let Some(message) = stream.next().await {
+                match message {
+                    Ok(WsMessage::Close(_)) => break,
+                    // Pings are answered by the WebSocket layer
+                    Ok(WsMessage::Ping(_)) | Ok(WsMessage::Pong(_)) => continue,
+                    Ok(message) => reader.handle_message(message, &notify_send),
+                    Err(err) => {
+                        trace!("[WrpcClient] receive error: {0}", err);
+                        break;
+                    }
+                }
+            }
+            reader.handle_disconnect();
+        });
+
+        inner
+    }
+
+    async fn call<Req, Resp>(&self, method: RpcApiOps, request: &Req) -> Result<Resp>
+    where
+        Req: Serialize + BorshSerialize,
+        Resp: DeserializeOwned + BorshDeserialize,
+    {
+        if !self.is_connected.load(Ordering::SeqCst) {
+            return Err(Error::Disconnected);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let message = C::encode_request(&Request { id, method, params: C::encode_params(request)? })?;
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, sender);
+        if self.sender.send(message).await.is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(Error::Disconnected);
+        }
+        match tokio::time::timeout(self.request_timeout, receiver).await {
+            Ok(Ok(params)) => C::decode_params(params?),
+            Ok(Err(_)) => Err(Error::Disconnected),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(Error::Timeout)
+            }
+        }
+    }
+
+    fn handle_message(&self, message: WsMessage, notify_send: &NotificationSender) {
+        match C::decode_server_message(message) {
+            Ok(ServerMessage::Response { id, params }) => self.resolve(id, Ok(params)),
+            Ok(ServerMessage::Error { id: Some(id), error }) => self.resolve(id, Err(Error::ServerError(error))),
+            Ok(ServerMessage::Error { id: None, error }) => trace!("[WrpcClient] the server failed decoding a request: {0}", error),
+            Ok(ServerMessage::Notification { params }) => match C::decode_params::<Notification>(params) {
+                Ok(notification) => {
+                    // Here we ignore any returned error
+                    if let Err(err) = notify_send.try_send(Arc::new(notification)) {
+                        trace!("[WrpcClient] error while trying to send a notification to the notifier: {0:?}", err);
+                    }
+                }
+                Err(err) => trace!("[WrpcClient] decoding a notification error: {0}", err),
+            },
+            Err(err) => trace!("[WrpcClient] decoding a server message error: {0}", err),
+        }
+    }
+
+    fn resolve(&self, id: u64, result: Result<C::Params>) {
+        match self.pending.lock().unwrap().remove(&id) {
+            Some(sender) => {
+                // The request might have timed out meanwhile
+                let _ = sender.send(result);
+            }
+            None => trace!("[WrpcClient] no pending request matches response id {0}", id),
+        }
+    }
+
+    fn handle_disconnect(&self) {
+        trace!("[WrpcClient] the socket is closed");
+        self.is_connected.store(false, Ordering::SeqCst);
+        for (_, sender) in self.pending.lock().unwrap().drain() {
+            let _ = sender.send(Err(Error::Disconnected));
+        }
+    }
+
+    async fn subscribe(&self, notification_type: NotificationType, command: SubscribeCommand) -> RpcResult<()> {
+        trace!("[WrpcClient] subscribe command {0:?} for notifications of type {1:?}", command, notification_type);
+        let _: SubscribeResponse = self.call(RpcApiOps::Subscribe, &SubscribeRequest::new(notification_type, command)).await?;
+        Ok(())
+    }
+}
+
+impl<C: Codec> Debug for Inner<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WrpcClientInner").field("is_connected", &self.is_connected).finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<C: Codec> SubscriptionManager for Inner<C> {
+    async fn start_notify(self: Arc<Self>, _: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.subscribe(notification_type, SubscribeCommand::Start).await
+    }
+
+    async fn stop_notify(self: Arc<Self>, _: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.subscribe(notification_type, SubscribeCommand::Stop).await
+    }
+
+    async fn modify_notify(self: Arc<Self>, _: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.subscribe(notification_type, SubscribeCommand::ModifyScope).await
+    }
+}
+
+#[async_trait]
+impl<C: Codec> RpcApi for WrpcClient<C> {
+    async fn submit_block_call(&self, request: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
+        Ok(self.inner.call(RpcApiOps::SubmitBlock, &request).await?)
+    }
+
+    async fn get_block_template_call(&self, request: GetBlockTemplateRequest) -> RpcResult<GetBlockTemplateResponse> {
+        Ok(self.inner.call(RpcApiOps::GetBlockTemplate, &request).await?)
+    }
+
+    async fn get_block_call(&self, request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
+        Ok(self.inner.call(RpcApiOps::GetBlock, &request).await?)
+    }
+
+    async fn get_blocks_call(&self, request: GetBlocksRequest) -> RpcResult<GetBlocksResponse> {
+        Ok(self.inner.call(RpcApiOps::GetBlocks, &request).await?)
+    }
+
+    async fn get_utxos_by_addresses_call(&self, request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
+        Ok(self.inner.call(RpcApiOps::GetUtxosByAddresses, &request).await?)
+    }
+
+    async fn get_balance_by_address_call(&self, request: GetBalanceByAddressRequest) -> RpcResult<GetBalanceByAddressResponse> {
+        Ok(self.inner.call(RpcApiOps::GetBalanceByAddress, &request).await?)
+    }
+
+    async fn get_mempool_entry_call(&self, request: GetMempoolEntryRequest) -> RpcResult<GetMempoolEntryResponse> {
+        Ok(self.inner.call(RpcApiOps::GetMempoolEntry, &request).await?)
+    }
+
+    async fn get_mempool_entries_call(&self, request: GetMempoolEntriesRequest) -> RpcResult<GetMempoolEntriesResponse> {
+        Ok(self.inner.call(RpcApiOps::GetMempoolEntries, &request).await?)
+    }
+
+    async fn get_block_dag_info_call(&self, request: GetBlockDagInfoRequest) -> RpcResult<GetBlockDagInfoResponse> {
+        Ok(self.inner.call(RpcApiOps::GetBlockDagInfo, &request).await?)
+    }
+
+    async fn get_info_call(&self, request: GetInfoRequest) -> RpcResult<GetInfoResponse> {
+        Ok(self.inner.call(RpcApiOps::GetInfo, &request).await?)
+    }
+
+    async fn get_chain_throughput_stats_call(
+        &self,
+        request: GetChainThroughputStatsRequest,
+    ) -> RpcResult<GetChainThroughputStatsResponse> {
+        Ok(self.inner.call(RpcApiOps::GetChainThroughputStats, &request).await?)
+    }
+
+    async fn revalidate_block_call(&self, request: RevalidateBlockRequest) -> RpcResult<RevalidateBlockResponse> {
+        Ok(self.inner.call(RpcApiOps::RevalidateBlock, &request).await?)
+    }
+
+    async fn get_coinbase_preview_call(&self, request: GetCoinbasePreviewRequest) -> RpcResult<GetCoinbasePreviewResponse> {
+        Ok(self.inner.call(RpcApiOps::GetCoinbasePreview, &request).await?)
+    }
+
+    async fn get_mempool_fee_rate_histogram_call(
+        &self,
+        request: GetMempoolFeeRateHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeRateHistogramResponse> {
+        Ok(self.inner.call(RpcApiOps::GetMempoolFeeRateHistogram, &request).await?)
+    }
+
+    async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
+        Ok(self.inner.call(RpcApiOps::SubmitTransaction, &request).await?)
+    }
+
+    async fn get_virtual_chain_from_block_call(
+        &self,
+        request: GetVirtualChainFromBlockRequest,
+    ) -> RpcResult<GetVirtualChainFromBlockResponse> {
+        Ok(self.inner.call(RpcApiOps::GetVirtualChainFromBlock, &request).await?)
+    }
+
+    async fn get_transaction_acceptance_call(
+        &self,
+        request: GetTransactionAcceptanceRequest,
+    ) -> RpcResult<GetTransactionAcceptanceResponse> {
+        Ok(self.inner.call(RpcApiOps::GetTransactionAcceptance, &request).await?)
+    }
+
+    async fn get_transaction_by_id_call(&self, request: GetTransactionByIdRequest) -> RpcResult<GetTransactionByIdResponse> {
+        Ok(self.inner.call(RpcApiOps::GetTransactionById, &request).await?)
+    }
+
+    async fn get_blocks_with_acceptance_call(
+        &self,
+        request: GetBlocksWithAcceptanceRequest,
+    ) -> RpcResult<GetBlocksWithAcceptanceResponse> {
+        Ok(self.inner.call(RpcApiOps::GetBlocksWithAcceptance, &request).await?)
+    }
+
+    async fn get_metrics_call(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse> {
+        Ok(self.inner.call(RpcApiOps::GetMetrics, &request).await?)
+    }
+
+    async fn get_payload_call(&self, request: GetPayloadRequest) -> RpcResult<GetPayloadResponse> {
+        Ok(self.inner.call(RpcApiOps::GetPayload, &request).await?)
+    }
+
+    async fn get_peer_addresses_call(&self, request: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse> {
+        Ok(self.inner.call(RpcApiOps::GetPeerAddresses, &request).await?)
+    }
+
+    async fn get_connected_peer_info_call(&self, request: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse> {
+        Ok(self.inner.call(RpcApiOps::GetConnectedPeerInfo, &request).await?)
+    }
+
+    async fn add_peer_call(&self, request: AddPeerRequest) -> RpcResult<AddPeerResponse> {
+        Ok(self.inner.call(RpcApiOps::AddPeer, &request).await?)
+    }
+
+    async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse> {
+        Ok(self.inner.call(RpcApiOps::Ban, &request).await?)
+    }
+
+    async fn unban_call(&self, request: UnbanRequest) -> RpcResult<UnbanResponse> {
+        Ok(self.inner.call(RpcApiOps::Unban, &request).await?)
+    }
+
+    async fn pin_blocks_call(&self, request: PinBlocksRequest) -> RpcResult<PinBlocksResponse> {
+        Ok(self.inner.call(RpcApiOps::PinBlocks, &request).await?)
+    }
+
+    async fn unpin_blocks_call(&self, request: UnpinBlocksRequest) -> RpcResult<UnpinBlocksResponse> {
+        Ok(self.inner.call(RpcApiOps::UnpinBlocks, &request).await?)
+    }
+
+    async fn dump_block_region_call(&self, request: DumpBlockRegionRequest) -> RpcResult<DumpBlockRegionResponse> {
+        Ok(self.inner.call(RpcApiOps::DumpBlockRegion, &request).await?)
+    }
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Notification API
+
+    /// Register a new listener and returns an id and a channel receiver.
+    fn register_new_listener(&self, channel: Option<NotificationChannel>) -> ListenerReceiverSide {
+        self.notifier.register_new_listener(channel)
+    }
+
+    /// Unregister an existing listener.
+    ///
+    /// Stop all notifications for this listener and drop its channel.
+    async fn unregister_listener(&self, id: ListenerID) -> RpcResult<()> {
+        self.notifier.unregister_listener(id)?;
+        Ok(())
+    }
+
+    /// Start sending notifications of some type to a listener.
+    async fn start_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.notifier.as_ref().start_notify(id, notification_type)?;
+        Ok(())
+    }
+
+    /// Stop sending notifications of some type to a listener.
+    async fn stop_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.notifier.as_ref().stop_notify(id, notification_type)?;
+        Ok(())
+    }
+
+    /// Replace the scope of the notifications of some type sent to a listener.
+    async fn modify_notify(&self, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
+        self.notifier.as_ref().modify_notify(id, notification_type)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpc_core::NewBlockTemplateNotification;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// Serves a single connection, answering GetInfo and Subscribe requests, the latter followed by a
+    /// NewBlockTemplate notification, and leaving any other request unanswered
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                let request = match message {
+                    WsMessage::Close(_) => break,
+                    message => JsonCodec::decode_request(message).unwrap(),
+                };
+                let params = match request.method {
+                    RpcApiOps::GetInfo => JsonCodec::encode_params(&GetInfoResponse {
+                        p2p_id: "id".to_string(),
+                        mempool_size: 3,
+                        server_version: "0.1.0".to_string(),
+                        is_utxo_indexed: false,
+                        is_synced: true,
+                        has_notify_command: true,
+                    }),
+                    RpcApiOps::Subscribe => JsonCodec::encode_params(&SubscribeResponse {}),
+                    _ => continue,
+                };
+                let response = ServerMessage::Response { id: request.id, params: params.unwrap() };
+                socket.send(JsonCodec::encode_server_message(&response).unwrap()).await.unwrap();
+                if request.method == RpcApiOps::Subscribe {
+                    let notification = Notification::NewBlockTemplate(NewBlockTemplateNotification {});
+                    let message = ServerMessage::Notification { params: JsonCodec::encode_params(&notification).unwrap() };
+                    socket.send(JsonCodec::encode_server_message(&message).unwrap()).await.unwrap();
+                }
+            }
+        });
+        format!("ws://{}", address)
+    }
+
+    #[tokio::test]
+    async fn test_wrpc_client() {
+        let url = spawn_server().await;
+        let client = WrpcJsonClient::connect_with_timeout(&url, Duration::from_millis(200)).await.unwrap();
+        client.start().await;
+
+        assert_eq!(client.get_info().await.unwrap().mempool_size, 3);
+        assert!(client.get_block(Default::default(), false).await.is_err());
+
+        let listener = client.register_new_listener(None);
+        client.start_notify(listener.id, NotificationType::NewBlockTemplate).await.unwrap();
+        let notification = tokio::time::timeout(Duration::from_secs(5), listener.recv_channel.recv()).await.unwrap().unwrap();
+        assert!(matches!(*notification, Notification::NewBlockTemplate(_)));
+
+        client.disconnect().await.unwrap();
+        client.stop().await.unwrap();
+    }
+}
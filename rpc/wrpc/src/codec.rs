@@ -0,0 +1,153 @@
+use crate::{
+    errors::Error,
+    message::{Request, ServerMessage},
+    result::Result,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// The serialization of the messages exchanged over a socket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Borsh over binary frames, for native and WASM clients sharing the `rpc-core` types
+    Borsh,
+    /// JSON over text frames, for browser clients
+    SerdeJson,
+}
+
+/// Encodes and decodes the messages of an [`Encoding`].
+///
+/// Params are encoded on their own into [`Codec::Params`] and embedded into the message envelopes, so that both
+/// sides can decode an envelope before knowing the type of its params.
+pub trait Codec: Send + Sync + 'static {
+    type Params: Send + Sync + 'static;
+
+    fn encode_params<T: Serialize + BorshSerialize>(value: &T) -> Result<Self::Params>;
+    fn decode_params<T: DeserializeOwned + BorshDeserialize>(params: Self::Params) -> Result<T>;
+
+    fn encode_request<M: Serialize + BorshSerialize>(request: &Request<Self::Params, M>) -> Result<WsMessage>;
+    fn decode_request<M: DeserializeOwned + BorshDeserialize>(message: WsMessage) -> Result<Request<Self::Params, M>>;
+
+    fn encode_server_message(message: &ServerMessage<Self::Params>) -> Result<WsMessage>;
+    fn decode_server_message(message: WsMessage) -> Result<ServerMessage<Self::Params>>;
+}
+
+pub struct BorshCodec;
+
+impl BorshCodec {
+    fn binary(message: WsMessage) -> Result<Vec<u8>> {
+        match message {
+            WsMessage::Binary(bytes) => Ok(bytes),
+            _ => Err(Error::UnexpectedFrame("non-binary")),
+        }
+    }
+}
+
+impl Codec for BorshCodec {
+    type Params = Vec<u8>;
+
+    fn encode_params<T: Serialize + BorshSerialize>(value: &T) -> Result<Self::Params> {
+        Ok(value.try_to_vec()?)
+    }
+
+    fn decode_params<T: DeserializeOwned + BorshDeserialize>(params: Self::Params) -> Result<T> {
+        Ok(T::try_from_slice(&params)?)
+    }
+
+    fn encode_request<M: Serialize + BorshSerialize>(request: &Request<Self::Params, M>) -> Result<WsMessage> {
+        Ok(WsMessage::Binary(request.try_to_vec()?))
+    }
+
+    fn decode_request<M: DeserializeOwned + BorshDeserialize>(message: WsMessage) -> Result<Request<Self::Params, M>> {
+        Ok(Request::try_from_slice(&Self::binary(message)?)?)
+    }
+
+    fn encode_server_message(message: &ServerMessage<Self::Params>) -> Result<WsMessage> {
+        Ok(WsMessage::Binary(message.try_to_vec()?))
+    }
+
+    fn decode_server_message(message: WsMessage) -> Result<ServerMessage<Self::Params>> {
+        Ok(ServerMessage::try_from_slice(&Self::binary(message)?)?)
+    }
+}
+
+pub struct JsonCodec;
+
+impl JsonCodec {
+    fn text(message: WsMessage) -> Result<String> {
+        match message {
+            WsMessage::Text(text) => Ok(text),
+            _ => Err(Error::UnexpectedFrame("non-text")),
+        }
+    }
+}
+
+impl Codec for JsonCodec {
+    type Params = serde_json::Value;
+
+    fn encode_params<T: Serialize + BorshSerialize>(value: &T) -> Result<Self::Params> {
+        Ok(serde_json::to_value(value)?)
+    }
+
+    fn decode_params<T: DeserializeOwned + BorshDeserialize>(params: Self::Params) -> Result<T> {
+        Ok(serde_json::from_value(params)?)
+    }
+
+    fn encode_request<M: Serialize + BorshSerialize>(request: &Request<Self::Params, M>) -> Result<WsMessage> {
+        Ok(WsMessage::Text(serde_json::to_string(request)?))
+    }
+
+    fn decode_request<M: DeserializeOwned + BorshDeserialize>(message: WsMessage) -> Result<Request<Self::Params, M>> {
+        Ok(serde_json::from_str(&Self::text(message)?)?)
+    }
+
+    fn encode_server_message(message: &ServerMessage<Self::Params>) -> Result<WsMessage> {
+        Ok(WsMessage::Text(serde_json::to_string(message)?))
+    }
+
+    fn decode_server_message(message: WsMessage) -> Result<ServerMessage<Self::Params>> {
+        Ok(serde_json::from_str(&Self::text(message)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpc_core::{api::ops::RpcApiOps, GetInfoRequest, GetInfoResponse};
+
+    fn round_trip<C: Codec>() {
+        let request = Request { id: 7, method: RpcApiOps::GetInfo, params: C::encode_params(&GetInfoRequest {}).unwrap() };
+        let request = C::decode_request(C::encode_request(&request).unwrap()).unwrap();
+        assert_eq!((request.id, request.method), (7, RpcApiOps::GetInfo));
+        let _: GetInfoRequest = C::decode_params(request.params).unwrap();
+
+        let response = GetInfoResponse {
+            p2p_id: "id".to_string(),
+            mempool_size: 3,
+            server_version: "0.1.0".to_string(),
+            is_utxo_indexed: false,
+            is_synced: true,
+            has_notify_command: true,
+        };
+        let message = ServerMessage::Response { id: 7, params: C::encode_params(&response).unwrap() };
+        match C::decode_server_message(C::encode_server_message(&message).unwrap()).unwrap() {
+            ServerMessage::Response { id, params } => {
+                assert_eq!(id, 7);
+                let decoded: GetInfoResponse = C::decode_params(params).unwrap();
+                assert_eq!(decoded.mempool_size, 3);
+            }
+            _ => panic!("expected a response"),
+        }
+    }
+
+    #[test]
+    fn test_codecs_round_trip() {
+        round_trip::<BorshCodec>();
+        round_trip::<JsonCodec>();
+
+        // Each encoding uses its own frame type
+        let request = Request { id: 1, method: RpcApiOps::GetInfo, params: vec![] };
+        assert!(JsonCodec::decode_request::<RpcApiOps>(BorshCodec::encode_request(&request).unwrap()).is_err());
+    }
+}
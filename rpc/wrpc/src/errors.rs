@@ -0,0 +1,41 @@
+use rpc_core::{api::ops::RpcApiOps, RpcError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Borsh serialization error: {0}")]
+    Borsh(#[from] std::io::Error),
+
+    #[error("JSON serialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("unexpected {0} frame")]
+    UnexpectedFrame(&'static str),
+
+    #[error("RPC request timeout")]
+    Timeout,
+
+    #[error("the WebSocket is disconnected")]
+    Disconnected,
+
+    #[error("server error: {0}")]
+    ServerError(String),
+
+    #[error("method {0:?} is not served over wRPC")]
+    MethodNotServed(RpcApiOps),
+
+    #[error("Notify error: {0}")]
+    NotifyError(#[from] rpc_core::notify::errors::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] RpcError),
+}
+
+impl From<Error> for RpcError {
+    fn from(value: Error) -> Self {
+        RpcError::General(value.to_string())
+    }
+}
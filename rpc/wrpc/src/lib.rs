@@ -0,0 +1,13 @@
+//! WebSocket RPC (wRPC) server exposing the [`rpc_core::api::rpc::RpcApi`] to native, WASM and browser clients,
+//! along with a native client implementing it.
+//!
+//! Each server serves a single [`codec::Encoding`]: Borsh over binary frames or JSON over text frames. Requests
+//! carry an id echoed by their response, so that a client can have several requests in flight, while the
+//! notifications subscribed to are multiplexed over the same socket.
+
+pub mod client;
+pub mod codec;
+pub mod errors;
+pub mod message;
+pub mod result;
+pub mod server;
@@ -0,0 +1,55 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use rpc_core::{api::ops::RpcApiOps, api::ops::SubscribeCommand, NotificationType};
+use serde::{Deserialize, Serialize};
+
+/// A request sent by a client, answered by a [`ServerMessage::Response`] or a [`ServerMessage::Error`] bearing
+/// the same id.
+///
+/// The params are encoded on their own, so that their type can be resolved from the method once the envelope is
+/// decoded. See [`crate::codec::Codec`]. The methods are the [`RpcApiOps`] of the node, while other services served
+/// over wRPC, such as the wallet daemon, bring their own method enum.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Request<P, M = RpcApiOps> {
+    pub id: u64,
+    pub method: M,
+    pub params: P,
+}
+
+/// A message sent by the server
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ServerMessage<P> {
+    Response {
+        id: u64,
+        params: P,
+    },
+    Error {
+        /// `None` if the request could not be decoded
+        id: Option<u64>,
+        error: String,
+    },
+    /// A [`rpc_core::Notification`] the connection subscribed to
+    Notification {
+        params: P,
+    },
+}
+
+/// SubscribeRequest starts, stops or modifies the scope of the notifications of any type sent over the socket.
+///
+/// Served under [`RpcApiOps::Subscribe`].
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeRequest {
+    pub notification_type: NotificationType,
+    pub command: SubscribeCommand,
+}
+
+impl SubscribeRequest {
+    pub fn new(notification_type: NotificationType, command: SubscribeCommand) -> Self {
+        Self { notification_type, command }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeResponse {}
@@ -0,0 +1,2 @@
+use super::errors::Error;
+pub type Result<T> = std::result::Result<T, Error>;
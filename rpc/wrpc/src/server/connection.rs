@@ -0,0 +1,92 @@
+use super::service::WrpcService;
+use crate::{codec::Codec, message::ServerMessage};
+use futures::{SinkExt, StreamExt};
+use kaspa_core::trace;
+use std::{net::SocketAddr, sync::Arc};
+use tokio::{net::TcpStream, sync::mpsc};
+use tokio_tungstenite::{accept_async, tungstenite::Message as WsMessage};
+
+/// The capacity of the queue of outgoing messages of a connection
+const OUTGOING_CAPACITY: usize = 128;
+
+/// Serves a WebSocket connection until it is closed by the client.
+///
+/// Requests are handled concurrently, each in its own task, so that a slow call does not delay the others. The
+/// responses, along with the notifications the connection subscribed to, are queued to a single writer task.
+pub(crate) async fn serve_connection<C: Codec>(service: Arc<WrpcService>, stream: TcpStream, address: SocketAddr) {
+    let socket = match accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(err) => {
+            trace!("[WrpcConnection] WebSocket handshake with {0} failed: {1}", address, err);
+            return;
+        }
+    };
+    let (mut sink, mut stream) = socket.split();
+    let (sender, mut receiver) = mpsc::channel::<WsMessage>(OUTGOING_CAPACITY);
+    let listener = service.notifier().register_new_listener(None);
+    let listener_id = listener.id;
+    trace!("[WrpcConnection] serving {0} with listener id {1}", address, listener_id);
+
+    // Writer, ending once all senders are dropped
+    tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if let Err(err) = sink.send(message).await {
+                trace!("[WrpcConnection] send to {0} error: {1}", address, err);
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    // Notification forwarder, ending once the listener is unregistered
+    let notification_sender = sender.clone();
+    let recv_channel = listener.recv_channel.clone();
+    tokio::spawn(async move {
+        while let Ok(notification) = recv_channel.recv().await {
+            let message =
+                C::encode_params(&*notification).and_then(|params| C::encode_server_message(&ServerMessage::Notification { params }));
+            match message {
+                Ok(message) => {
+                    if notification_sender.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                Err(err) => trace!("[WrpcConnection] encoding {0} for listener id {1} error: {2}", notification, listener_id, err),
+            }
+        }
+    });
+
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(WsMessage::Close(_)) => break,
+            // Pings are answered by the WebSocket layer
+            Ok(WsMessage::Ping(_)) | Ok(WsMessage::Pong(_)) => continue,
+            Ok(message) => message,
+            Err(err) => {
+                trace!("[WrpcConnection] receive from {0} error: {1}", address, err);
+                break;
+            }
+        };
+        let service = service.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let response = match C::decode_request(message) {
+                Ok(request) => service.handle_request::<C>(listener_id, request).await,
+                Err(err) => ServerMessage::Error { id: None, error: err.to_string() },
+            };
+            match C::encode_server_message(&response) {
+                Ok(message) => {
+                    // The connection might have been closed meanwhile
+                    let _ = sender.send(message).await;
+                }
+                Err(err) => trace!("[WrpcConnection] encoding a response to {0} error: {1}", address, err),
+            }
+        });
+    }
+
+    // Unregistering the listener drops the subscriptions of the connection and closes its channel
+    trace!("[WrpcConnection] connection from {0} closed", address);
+    if let Err(err) = service.notifier().unregister_listener(listener_id) {
+        trace!("[WrpcConnection] unregister listener {0} error: {1:?}", listener_id, err);
+    }
+}
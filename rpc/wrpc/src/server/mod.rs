@@ -0,0 +1,114 @@
+use crate::codec::{BorshCodec, Encoding, JsonCodec};
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::DuplexTrigger;
+use rpc_core::api::rpc::DynRpcService;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+mod connection;
+pub mod service;
+
+const WRPC_BORSH_SERVER: &str = "wrpc-borsh-server";
+const WRPC_JSON_SERVER: &str = "wrpc-json-server";
+
+/// Serves the core service over WebSockets, with the messages serialized according to a single [`Encoding`]
+pub struct WrpcServer {
+    address: SocketAddr,
+    encoding: Encoding,
+    wrpc_service: Arc<service::WrpcService>,
+    shutdown: DuplexTrigger,
+}
+
+impl WrpcServer {
+    pub fn new(address: SocketAddr, encoding: Encoding, core_service: DynRpcService) -> Self {
+        let wrpc_service = Arc::new(service::WrpcService::new(core_service));
+        Self { address, encoding, wrpc_service, shutdown: DuplexTrigger::default() }
+    }
+}
+
+impl AsyncService for WrpcServer {
+    fn ident(self: Arc<Self>) -> &'static str {
+        match self.encoding {
+            Encoding::Borsh => WRPC_BORSH_SERVER,
+            Encoding::SerdeJson => WRPC_JSON_SERVER,
+        }
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        let ident = self.clone().ident();
+        trace!("{} starting", ident);
+
+        // Prepare a start shutdown signal receiver and a shutdown ended signal sender
+        let shutdown_signal = self.shutdown.request.listener.clone();
+        let shutdown_executed = self.shutdown.response.trigger.clone();
+
+        Box::pin(async move {
+            // Start the wRPC service
+            self.wrpc_service.start();
+
+            let listener = match TcpListener::bind(self.address).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    warn!("wRPC server failed listening on {}: {}", self.address, err);
+                    shutdown_executed.trigger();
+                    return;
+                }
+            };
+            trace!("wRPC server listening on: {}", self.address);
+
+            tokio::pin!(shutdown_signal);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = &mut shutdown_signal => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, address)) => {
+                            let wrpc_service = self.wrpc_service.clone();
+                            match self.encoding {
+                                Encoding::Borsh => {
+                                    tokio::spawn(connection::serve_connection::<BorshCodec>(wrpc_service, stream, address));
+                                }
+                                Encoding::SerdeJson => {
+                                    tokio::spawn(connection::serve_connection::<JsonCodec>(wrpc_service, stream, address));
+                                }
+                            }
+                        }
+                        Err(err) => trace!("wRPC server accept error: {}", err),
+                    }
+                }
+            }
+
+            // Send a signal telling the shutdown is done
+            shutdown_executed.trigger();
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", self.clone().ident());
+        self.shutdown.request.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        let ident = self.clone().ident();
+        trace!("{} stopping", ident);
+        let shutdown_executed_signal = self.shutdown.response.listener.clone();
+        let wrpc_service = self.wrpc_service.clone();
+        Box::pin(async move {
+            // Wait for the accept loop to exit
+            shutdown_executed_signal.await;
+
+            // Stop the wRPC service gracefully
+            if let Err(err) = wrpc_service.stop().await {
+                trace!("Error while stopping the wRPC service: {0}", err);
+            }
+            if let Err(err) = wrpc_service.finalize().await {
+                trace!("Error while finalizing the wRPC service: {0}", err);
+            }
+            trace!("{} exiting", ident);
+        })
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    codec::Codec,
+    errors::Error,
+    message::{Request, ServerMessage, SubscribeRequest, SubscribeResponse},
+    result::Result,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use rpc_core::{
+    api::{
+        ops::{RpcApiOps, SubscribeCommand},
+        rpc::DynRpcService,
+    },
+    notify::{
+        channel::NotificationChannel,
+        collector::RpcCoreCollector,
+        events::EVENT_TYPE_ARRAY,
+        listener::{ListenerID, ListenerReceiverSide, ListenerUtxoNotificationFilterSetting},
+        notifier::Notifier,
+        subscriber::{RpcServiceSubscriptionManager, Subscriber},
+    },
+    NotificationType, NotifyBlockAddedRequest, NotifyBlockAddedResponse, NotifyNewBlockTemplateRequest,
+    NotifyNewBlockTemplateResponse, RpcError, RpcResult,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{future::Future, sync::Arc};
+
+/// A wRPC service, relaying the requests of all connections of a server to a central core service.
+///
+/// Like the gRPC service, it registers as a listener of the core service and dispatches the notifications
+/// to the connections subscribed to them through an internal notifier, UtxosChanged notifications being
+/// filtered by the addresses of each connection.
+///
+/// #### Lifetime and usage
+///
+/// - new -> Self
+///     - start
+///         - handle_request
+///     - stop
+/// - finalize
+pub struct WrpcService {
+    core_service: DynRpcService,
+    core_channel: NotificationChannel,
+    core_listener: Arc<ListenerReceiverSide>,
+    notifier: Arc<Notifier>,
+}
+
+impl WrpcService {
+    pub fn new(core_service: DynRpcService) -> Self {
+        // Prepare core objects
+        let core_channel = NotificationChannel::default();
+        let core_listener = Arc::new(core_service.register_new_listener(Some(core_channel.clone())));
+
+        // Prepare internals
+        let collector = Arc::new(RpcCoreCollector::new(core_channel.receiver()));
+        let subscription_manager = Arc::new(RpcServiceSubscriptionManager::new(core_service.clone()));
+        let subscriber = Subscriber::new(subscription_manager, core_listener.id);
+        let notifier =
+            Arc::new(Notifier::new(Some(collector), Some(subscriber), ListenerUtxoNotificationFilterSetting::FilteredByAddress));
+
+        Self { core_service, core_channel, core_listener, notifier }
+    }
+
+    pub fn start(&self) {
+        // Start the internal notifier
+        self.notifier.clone().start();
+    }
+
+    pub(crate) fn notifier(&self) -> &Arc<Notifier> {
+        &self.notifier
+    }
+
+    /// Serves a request of the connection whose notifications are sent to `listener_id`
+    pub(crate) async fn handle_request<C: Codec>(
+        &self,
+        listener_id: ListenerID,
+        request: Request<C::Params>,
+    ) -> ServerMessage<C::Params> {
+        let id = request.id;
+        let service = &self.core_service;
+        let params = request.params;
+        let result = match request.method {
+            RpcApiOps::SubmitBlock => route::<C, _, _, _>(params, |request| service.submit_block_call(request)).await,
+            RpcApiOps::GetBlockTemplate => route::<C, _, _, _>(params, |request| service.get_block_template_call(request)).await,
+            RpcApiOps::SubmitTransaction => route::<C, _, _, _>(params, |request| service.submit_transaction_call(request)).await,
+            RpcApiOps::GetBlock => route::<C, _, _, _>(params, |request| service.get_block_call(request)).await,
+            RpcApiOps::GetVirtualChainFromBlock => {
+                route::<C, _, _, _>(params, |request| service.get_virtual_chain_from_block_call(request)).await
+            }
+            RpcApiOps::GetTransactionAcceptance => {
+                route::<C, _, _, _>(params, |request| service.get_transaction_acceptance_call(request)).await
+            }
+            RpcApiOps::GetTransactionById => route::<C, _, _, _>(params, |request| service.get_transaction_by_id_call(request)).await,
+            RpcApiOps::GetBlocksWithAcceptance => {
+                route::<C, _, _, _>(params, |request| service.get_blocks_with_acceptance_call(request)).await
+            }
+            RpcApiOps::GetMetrics => route::<C, _, _, _>(params, |request| service.get_metrics_call(request)).await,
+            RpcApiOps::GetPayload => route::<C, _, _, _>(params, |request| service.get_payload_call(request)).await,
+            RpcApiOps::GetPeerAddresses => route::<C, _, _, _>(params, |request| service.get_peer_addresses_call(request)).await,
+            RpcApiOps::GetConnectedPeerInfo => {
+                route::<C, _, _, _>(params, |request| service.get_connected_peer_info_call(request)).await
+            }
+            RpcApiOps::AddPeer => route::<C, _, _, _>(params, |request| service.add_peer_call(request)).await,
+            RpcApiOps::Ban => route::<C, _, _, _>(params, |request| service.ban_call(request)).await,
+            RpcApiOps::Unban => route::<C, _, _, _>(params, |request| service.unban_call(request)).await,
+            RpcApiOps::PinBlocks => route::<C, _, _, _>(params, |request| service.pin_blocks_call(request)).await,
+            RpcApiOps::UnpinBlocks => route::<C, _, _, _>(params, |request| service.unpin_blocks_call(request)).await,
+            RpcApiOps::DumpBlockRegion => route::<C, _, _, _>(params, |request| service.dump_block_region_call(request)).await,
+            RpcApiOps::GetBlocks => route::<C, _, _, _>(params, |request| service.get_blocks_call(request)).await,
+            RpcApiOps::GetUtxosByAddresses => {
+                route::<C, _, _, _>(params, |request| service.get_utxos_by_addresses_call(request)).await
+            }
+            RpcApiOps::GetBalanceByAddress => {
+                route::<C, _, _, _>(params, |request| service.get_balance_by_address_call(request)).await
+            }
+            RpcApiOps::GetMempoolEntry => route::<C, _, _, _>(params, |request| service.get_mempool_entry_call(request)).await,
+            RpcApiOps::GetMempoolEntries => route::<C, _, _, _>(params, |request| service.get_mempool_entries_call(request)).await,
+            RpcApiOps::GetBlockDagInfo => route::<C, _, _, _>(params, |request| service.get_block_dag_info_call(request)).await,
+            RpcApiOps::GetInfo => route::<C, _, _, _>(params, |request| service.get_info_call(request)).await,
+            RpcApiOps::GetChainThroughputStats => {
+                route::<C, _, _, _>(params, |request| service.get_chain_throughput_stats_call(request)).await
+            }
+            RpcApiOps::RevalidateBlock => route::<C, _, _, _>(params, |request| service.revalidate_block_call(request)).await,
+            RpcApiOps::GetCoinbasePreview => route::<C, _, _, _>(params, |request| service.get_coinbase_preview_call(request)).await,
+            RpcApiOps::GetMempoolFeeRateHistogram => {
+                route::<C, _, _, _>(params, |request| service.get_mempool_fee_rate_histogram_call(request)).await
+            }
+            RpcApiOps::NotifyBlockAdded => {
+                route::<C, _, _, _>(params, |request: NotifyBlockAddedRequest| async move {
+                    self.subscribe(listener_id, NotificationType::BlockAdded, request.command).map(|_| NotifyBlockAddedResponse {})
+                })
+                .await
+            }
+            RpcApiOps::NotifyNewBlockTemplate => {
+                route::<C, _, _, _>(params, |request: NotifyNewBlockTemplateRequest| async move {
+                    self.subscribe(listener_id, NotificationType::NewBlockTemplate, request.command)
+                        .map(|_| NotifyNewBlockTemplateResponse {})
+                })
+                .await
+            }
+            RpcApiOps::Subscribe => {
+                route::<C, _, _, _>(params, |request: SubscribeRequest| async move {
+                    self.subscribe(listener_id, request.notification_type, request.command).map(|_| SubscribeResponse {})
+                })
+                .await
+            }
+            method => Err(Error::MethodNotServed(method)),
+        };
+        match result {
+            Ok(params) => ServerMessage::Response { id, params },
+            Err(err) => ServerMessage::Error { id: Some(id), error: err.to_string() },
+        }
+    }
+
+    fn subscribe(&self, listener_id: ListenerID, notification_type: NotificationType, command: SubscribeCommand) -> RpcResult<()> {
+        self.notifier.clone().execute_subscribe_command(listener_id, notification_type, command).map_err(RpcError::from)
+    }
+
+    pub async fn stop(&self) -> RpcResult<()> {
+        // Unsubscribe from all notification types
+        let listener_id = self.core_listener.id;
+        for event in EVENT_TYPE_ARRAY.into_iter() {
+            self.core_service.stop_notify(listener_id, event.into()).await?;
+        }
+
+        // Stop the internal notifier
+        self.notifier.clone().stop().await?;
+
+        Ok(())
+    }
+
+    pub async fn finalize(&self) -> RpcResult<()> {
+        self.core_service.unregister_listener(self.core_listener.id).await?;
+        self.core_channel.receiver().close();
+        Ok(())
+    }
+}
+
+/// Decodes the params of a request, calls `call` with them and encodes the response
+async fn route<C, Req, Resp, Fut>(params: C::Params, call: impl FnOnce(Req) -> Fut) -> Result<C::Params>
+where
+    C: Codec,
+    Req: DeserializeOwned + BorshDeserialize,
+    Resp: Serialize + BorshSerialize,
+    Fut: Future<Output = RpcResult<Resp>>,
+{
+    let request = C::decode_params(params)?;
+    let response = call(request).await?;
+    C::encode_params(&response)
+}
@@ -11,14 +11,14 @@ use consensus::{
         headers::HeaderStoreReader,
         relations::RelationsStoreReader,
     },
-    params::{Params, DEVNET_PARAMS},
     processes::ghostdag::ordering::SortableBlock,
 };
 use consensus_core::{
     block::Block,
     blockstatus::BlockStatus,
+    config::params::{Params, DEVNET_PARAMS},
     errors::block::{BlockProcessResult, RuleError},
-    header::Header,
+    time::DurationMs,
     BlockHashSet, HashMapCustomHasher,
 };
 use futures::{future::join_all, Future};
@@ -83,6 +83,10 @@ struct Args {
     /// Input directory of a previous simulation DB (NOTE: simulation args must be compatible with the original run)
     #[arg(short, long)]
     input_dir: Option<String>,
+
+    /// Sample the difficulty window from genesis on, at a rate matching the simulation BPS
+    #[arg(long, default_value_t = false)]
+    sampled_windows: bool,
 }
 
 /// Calculates the k parameter of the GHOSTDAG protocol such that anticones lager than k will be created
@@ -106,7 +110,7 @@ fn calculate_ghostdag_k(x: f64, delta: f64) -> u64 {
 
 fn main() {
     let args = Args::parse();
-    kaspa_core::log::init_logger(&args.log_level);
+    kaspa_core::log::init_logger(None, &args.log_level);
     assert!(args.bps * args.delay < 250.0, "The delay times bps product is larger than 250");
     if args.miners > 1 {
         println!(
@@ -149,7 +153,7 @@ fn adjust_consensus_params(args: &Args, params: &mut Params) {
         params.ghostdag_k = k;
         params.mergeset_size_limit = k as u64 * 10;
         params.max_block_parents = u8::max((0.66 * k as f64) as u8, 10);
-        params.target_time_per_block = (1000.0 / args.bps) as u64;
+        params.target_time_per_block = DurationMs::from_millis((1000.0 / args.bps) as u64);
         params.merge_depth = (params.merge_depth as f64 * args.bps) as u64;
         params.coinbase_maturity = (params.coinbase_maturity as f64 * f64::max(1.0, args.bps * args.delay * 0.25)) as u64;
         params.difficulty_window_size = (params.difficulty_window_size as f64 * args.bps) as usize; // Scale the DAA window linearly with BPS
@@ -161,23 +165,23 @@ fn adjust_consensus_params(args: &Args, params: &mut Params) {
             params.difficulty_window_size
         );
     }
+    if args.sampled_windows {
+        // The DAA window is scaled with BPS, so sampling at this rate keeps it at its 1 BPS size. The past median time
+        // window is not scaled, so it is kept full
+        params.sampling_activation_daa_score = 0;
+        params.difficulty_sample_rate = u64::max(args.bps.round() as u64, 1);
+        params.past_median_time_sample_rate = 1;
+    }
 }
 
 fn adjust_perf_params(args: &Args, consensus_params: &Params, perf_params: &mut PerfParams) {
-    // Allow caching up to ~2000 full blocks
-    perf_params.block_data_cache_size = (perf_params.block_data_cache_size as f64 * args.bps.clamp(1.0, 10.0)) as u64;
-
     let daa_window_memory_budget = 1_000_000_000u64; // 1GB
-    let single_window_byte_size = consensus_params.difficulty_window_size as u64 * size_of::<SortableBlock>() as u64;
+    let difficulty_window = consensus_params.difficulty_window();
+    let window_size = if args.sampled_windows { difficulty_window.sampled_size } else { difficulty_window.size };
+    let single_window_byte_size = window_size as u64 * size_of::<SortableBlock>() as u64;
     let max_daa_window_cache_size = daa_window_memory_budget / single_window_byte_size;
     perf_params.block_window_cache_size = u64::min(perf_params.block_window_cache_size, max_daa_window_cache_size);
 
-    let headers_memory_budget = 1_000_000_000u64; // 1GB
-    let approx_header_num_parents = (args.bps * args.delay) as u64 * 2; // x2 for multi-levels
-    let approx_header_byte_size = approx_header_num_parents * size_of::<Hash>() as u64 + size_of::<Header>() as u64;
-    let max_headers_cache_size = headers_memory_budget / approx_header_byte_size;
-    perf_params.header_data_cache_size = u64::min(perf_params.header_data_cache_size, max_headers_cache_size);
-
     if let Some(processors_pool_threads) = args.processors_threads {
         perf_params.block_processors_num_threads = processors_pool_threads;
     }
@@ -188,7 +192,7 @@ fn adjust_perf_params(args: &Args, consensus_params: &Params, perf_params: &mut
 
 #[tokio::main]
 async fn validate(src_consensus: &Consensus, dst_consensus: &Consensus, params: &Params, delay: f64, bps: f64) {
-    let hashes = topologically_ordered_hashes(src_consensus, params.genesis_hash);
+    let hashes = topologically_ordered_hashes(src_consensus, params.genesis.hash);
     let num_blocks = hashes.len();
     let num_txs = print_stats(src_consensus, &hashes, delay, bps, params.ghostdag_k);
     println!("Validating {} blocks with {} transactions overall...", num_blocks, num_txs);
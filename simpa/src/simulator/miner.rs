@@ -1,13 +1,14 @@
 use super::infra::{Environment, Process, Resumption, Suspension};
 use consensus::consensus::Consensus;
 use consensus::model::stores::virtual_state::VirtualStateStoreReader;
-use consensus::params::Params;
 use consensus_core::block::Block;
 use consensus_core::blockstatus::BlockStatus;
 use consensus_core::coinbase::MinerData;
+use consensus_core::config::params::Params;
 use consensus_core::errors::block::{BlockProcessResult, RuleError};
 use consensus_core::sign::sign;
 use consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+use consensus_core::time::TimestampMs;
 use consensus_core::tx::{
     MutableTransaction, ScriptPublicKey, ScriptVec, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry,
 };
@@ -98,15 +99,13 @@ impl Miner {
 
         let txs = self.build_txs();
         let nonce = self.id;
-        let mut block_template = self
+        let block_template = self
             .consensus
             .as_ref()
             .build_block_template(self.miner_data.clone(), txs)
             .expect("simulation txs are selected in sync with virtual state and are expected to be valid");
-        block_template.block.header.timestamp = timestamp; // Use simulation time rather than real time
-        block_template.block.header.nonce = nonce;
-        block_template.block.header.finalize();
-        block_template.block.to_immutable()
+        // Use simulation time rather than real time
+        block_template.with_nonce_and_time(nonce, TimestampMs::from_millis(timestamp)).block.to_immutable()
     }
 
     fn build_txs(&mut self) -> Vec<Transaction> {
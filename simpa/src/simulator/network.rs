@@ -7,8 +7,8 @@ use super::miner::Miner;
 use consensus::consensus::test_consensus::{create_permanent_db, create_temp_db, TempDbLifetime};
 use consensus::consensus::Consensus;
 use consensus::constants::perf::PerfParams;
-use consensus::params::Params;
 use consensus_core::block::Block;
+use consensus_core::config::params::Params;
 
 type ConsensusWrapper = (Arc<Consensus>, Vec<JoinHandle<()>>, TempDbLifetime);
 
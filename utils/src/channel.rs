@@ -1,4 +1,4 @@
-use async_std::channel::{unbounded, Receiver, Sender};
+use async_std::channel::{bounded, unbounded, Receiver, Sender};
 
 /// Multiple producers multiple consumers channel
 #[derive(Clone, Debug)]
@@ -12,6 +12,11 @@ impl<T> Channel<T> {
         Self { sender: channel.0, receiver: channel.1 }
     }
 
+    /// Creates a channel holding up to `capacity` messages
+    pub fn bounded(capacity: usize) -> Channel<T> {
+        Self::new(bounded(capacity))
+    }
+
     pub fn sender(&self) -> Sender<T> {
         self.sender.clone()
     }
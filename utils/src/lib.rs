@@ -1,5 +1,6 @@
 pub mod arc;
 pub mod channel;
+pub mod networking;
 pub mod option;
 pub mod refs;
 pub mod triggers;
@@ -0,0 +1,343 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    ops::Deref,
+    str::FromStr,
+};
+
+/// Returns whether `ip` can be advertised to and reached by remote peers
+pub fn is_publicly_routable(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_publicly_routable(IpAddr::V4(ip));
+            }
+            let segments = ip.segments();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || (segments[0] & 0xfe00) == 0xfc00 // Unique local (fc00::/7)
+                || (segments[0] & 0xffc0) == 0xfe80 // Link local (fe80::/10)
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8)) // Documentation (2001:db8::/32)
+        }
+    }
+}
+
+/// An IPv4 or IPv6 address. Wraps the std type, which does not support Borsh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IpAddress(pub IpAddr);
+
+impl IpAddress {
+    /// The length of the fixed-size encoding returned by [`Self::to_bytes`]
+    pub const SERIALIZED_LEN: usize = 16;
+
+    /// Encodes the address as the IPv6 (or IPv4-mapped IPv6) octets
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_LEN] {
+        match self.0 {
+            IpAddr::V4(ip) => ip.to_ipv6_mapped().octets(),
+            IpAddr::V6(ip) => ip.octets(),
+        }
+    }
+
+    /// Decodes an address encoded by [`Self::to_bytes`]. IPv4-mapped addresses are decoded as IPv4
+    pub fn from_bytes(bytes: [u8; Self::SERIALIZED_LEN]) -> Self {
+        let ip = Ipv6Addr::from(bytes);
+        match ip.to_ipv4_mapped() {
+            Some(ip) => Self(IpAddr::V4(ip)),
+            None => Self(IpAddr::V6(ip)),
+        }
+    }
+}
+
+impl From<IpAddr> for IpAddress {
+    fn from(value: IpAddr) -> Self {
+        Self(value)
+    }
+}
+
+impl From<IpAddress> for IpAddr {
+    fn from(value: IpAddress) -> Self {
+        value.0
+    }
+}
+
+impl Deref for IpAddress {
+    type Target = IpAddr;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromStr for IpAddress {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IpAddr::from_str(s).map(Self)
+    }
+}
+
+impl Display for IpAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A fully specified network address (IPv4 or IPv6 and a port) of a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NetAddress {
+    pub ip: IpAddr,
+    pub port: u16,
+}
+
+impl NetAddress {
+    /// The length of the fixed-size encoding returned by [`Self::to_bytes`]
+    pub const SERIALIZED_LEN: usize = 18;
+
+    pub fn new(ip: IpAddr, port: u16) -> Self {
+        Self { ip, port }
+    }
+
+    pub fn is_publicly_routable(&self) -> bool {
+        is_publicly_routable(self.ip)
+    }
+
+    /// Encodes the address into a fixed-size, database-friendly form: the IPv6 (or
+    /// IPv4-mapped IPv6) octets followed by the big-endian port
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_LEN] {
+        let mut bytes = [0u8; Self::SERIALIZED_LEN];
+        bytes[..IpAddress::SERIALIZED_LEN].copy_from_slice(&IpAddress(self.ip).to_bytes());
+        bytes[IpAddress::SERIALIZED_LEN..].copy_from_slice(&self.port.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes an address encoded by [`Self::to_bytes`]. IPv4-mapped addresses are decoded as IPv4
+    pub fn from_bytes(bytes: [u8; Self::SERIALIZED_LEN]) -> Self {
+        let mut octets = [0u8; IpAddress::SERIALIZED_LEN];
+        octets.copy_from_slice(&bytes[..IpAddress::SERIALIZED_LEN]);
+        Self::new(IpAddress::from_bytes(octets).0, u16::from_be_bytes([bytes[16], bytes[17]]))
+    }
+}
+
+impl From<SocketAddr> for NetAddress {
+    fn from(value: SocketAddr) -> Self {
+        Self::new(value.ip(), value.port())
+    }
+}
+
+impl From<NetAddress> for SocketAddr {
+    fn from(value: NetAddress) -> Self {
+        Self::new(value.ip, value.port)
+    }
+}
+
+impl FromStr for NetAddress {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SocketAddr::from_str(s).map(Self::from)
+    }
+}
+
+impl Display for NetAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        SocketAddr::from(*self).fmt(f)
+    }
+}
+
+//
+// Borsh serializers are manually implemented since std IP types do not support Borsh.
+// The fixed-size byte encoding is used so that the serialized form can also serve as a database key
+//
+
+impl BorshSerialize for IpAddress {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl BorshDeserialize for IpAddress {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self::from_bytes(borsh::BorshDeserialize::deserialize(buf)?))
+    }
+}
+
+impl BorshSchema for IpAddress {
+    fn add_definitions_recursively(
+        definitions: &mut std::collections::HashMap<borsh::schema::Declaration, borsh::schema::Definition>,
+    ) {
+        let fields = borsh::schema::Fields::UnnamedFields(std::vec![<[u8; Self::SERIALIZED_LEN]>::declaration()]);
+        Self::add_definition(Self::declaration(), borsh::schema::Definition::Struct { fields }, definitions);
+        <[u8; Self::SERIALIZED_LEN]>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> borsh::schema::Declaration {
+        "IpAddress".to_string()
+    }
+}
+
+impl BorshSerialize for NetAddress {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl BorshDeserialize for NetAddress {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self::from_bytes(borsh::BorshDeserialize::deserialize(buf)?))
+    }
+}
+
+impl BorshSchema for NetAddress {
+    fn add_definitions_recursively(
+        definitions: &mut std::collections::HashMap<borsh::schema::Declaration, borsh::schema::Definition>,
+    ) {
+        let fields = borsh::schema::Fields::UnnamedFields(std::vec![<[u8; Self::SERIALIZED_LEN]>::declaration()]);
+        Self::add_definition(Self::declaration(), borsh::schema::Definition::Struct { fields }, definitions);
+        <[u8; Self::SERIALIZED_LEN]>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> borsh::schema::Declaration {
+        "NetAddress".to_string()
+    }
+}
+
+/// A network address possibly without an explicit port. The port is resolved
+/// from the context (usually the network default port) via [`Self::normalize`].
+///
+/// Accepted formats: `ip`, `ip:port`, `[ipv6]` and `[ipv6]:port`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContextualNetAddress {
+    pub ip: IpAddr,
+    pub port: Option<u16>,
+}
+
+impl ContextualNetAddress {
+    pub fn new(ip: IpAddr, port: Option<u16>) -> Self {
+        Self { ip, port }
+    }
+
+    /// The unspecified IPv4 address, i.e., all IPv4 interfaces
+    pub fn unspecified() -> Self {
+        Self { ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED), port: None }
+    }
+
+    pub fn normalize(&self, default_port: u16) -> NetAddress {
+        NetAddress::new(self.ip, self.port.unwrap_or(default_port))
+    }
+
+    pub fn is_publicly_routable(&self) -> bool {
+        is_publicly_routable(self.ip)
+    }
+}
+
+impl FromStr for ContextualNetAddress {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match SocketAddr::from_str(s) {
+            Ok(socket_addr) => Ok(Self::new(socket_addr.ip(), Some(socket_addr.port()))),
+            Err(_) => {
+                let ip = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')).unwrap_or(s);
+                Ok(Self::new(IpAddr::from_str(ip)?, None))
+            }
+        }
+    }
+}
+
+impl From<NetAddress> for ContextualNetAddress {
+    fn from(value: NetAddress) -> Self {
+        Self::new(value.ip, Some(value.port))
+    }
+}
+
+impl Display for ContextualNetAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.ip, self.port) {
+            (ip, Some(port)) => SocketAddr::new(ip, port).fmt(f),
+            (IpAddr::V6(ip), None) => write!(f, "[{ip}]"),
+            (ip, None) => ip.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contextual_net_address_parse_test() {
+        let cases = [
+            ("1.2.3.4", "1.2.3.4", "1.2.3.4:16111"),
+            ("1.2.3.4:5555", "1.2.3.4:5555", "1.2.3.4:5555"),
+            ("::1", "[::1]", "[::1]:16111"),
+            ("[::1]", "[::1]", "[::1]:16111"),
+            ("[2001:db8::1]:5555", "[2001:db8::1]:5555", "[2001:db8::1]:5555"),
+        ];
+        for (input, display, normalized) in cases {
+            let address = ContextualNetAddress::from_str(input).unwrap();
+            assert_eq!(address.to_string(), display);
+            assert_eq!(address.normalize(16111).to_string(), normalized);
+        }
+        for input in ["", "1.2.3", "1.2.3.4:port", "host.example", "[1.2.3.4]:"] {
+            assert!(ContextualNetAddress::from_str(input).is_err(), "{input} should fail to parse");
+        }
+    }
+
+    #[test]
+    fn publicly_routable_test() {
+        let routable = |s: &str| ContextualNetAddress::from_str(s).unwrap().is_publicly_routable();
+        assert!(routable("8.8.8.8"));
+        assert!(!routable("0.0.0.0"));
+        assert!(!routable("127.0.0.1"));
+        assert!(!routable("192.168.1.1"));
+        assert!(!routable("10.0.0.1"));
+        assert!(!routable("169.254.1.1"));
+        assert!(!routable("::"));
+        assert!(!routable("::1"));
+        assert!(routable("2606:4700::1111"));
+        assert!(!routable("fd00::1"));
+        assert!(!routable("fe80::1"));
+        assert!(!routable("2001:db8::1"));
+        assert!(!routable("ff02::1"));
+        assert!(routable("::ffff:8.8.8.8"));
+        assert!(!routable("::ffff:192.168.1.1"));
+    }
+
+    #[test]
+    fn net_address_encoding_test() {
+        for input in ["1.2.3.4:16111", "[::1]:16111", "[2606:4700::1111]:65535", "0.0.0.0:0"] {
+            let address = NetAddress::from_str(input).unwrap();
+            assert_eq!(address.to_string(), input);
+            assert_eq!(NetAddress::from_bytes(address.to_bytes()), address);
+
+            let borsh_bytes = address.try_to_vec().unwrap();
+            assert_eq!(borsh_bytes.len(), NetAddress::SERIALIZED_LEN);
+            assert_eq!(NetAddress::try_from_slice(&borsh_bytes).unwrap(), address);
+
+            let json = serde_json::to_string(&address).unwrap();
+            assert_eq!(serde_json::from_str::<NetAddress>(&json).unwrap(), address);
+        }
+        // IPv4 addresses are encoded as IPv4-mapped IPv6 addresses
+        let bytes = NetAddress::from_str("1.2.3.4:258").unwrap().to_bytes();
+        assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 1, 2, 3, 4, 1, 2]);
+
+        for input in ["1.2.3.4", "::1"] {
+            let ip = IpAddress::from_str(input).unwrap();
+            assert_eq!(IpAddress::try_from_slice(&ip.try_to_vec().unwrap()).unwrap(), ip);
+        }
+        // IPv4-mapped addresses are decoded as IPv4
+        assert_eq!(IpAddress::from_bytes(IpAddress::from_str("::ffff:1.2.3.4").unwrap().to_bytes()).to_string(), "1.2.3.4");
+    }
+}
@@ -0,0 +1,184 @@
+//! The messages of the wallet daemon wRPC API.
+//!
+//! Requests and responses travel in the envelopes of the node wRPC API, i.e., [`rpc_wrpc::message::Request`] and
+//! [`rpc_wrpc::message::ServerMessage`], with a [`WalletApiOps`] method. Each listener serves a single encoding: Borsh
+//! over binary frames, or JSON over text frames in the form `{"id": 1, "method": "getBalance", "params": {"wallet":
+//! "main"}}`, answered by `{"type": "response", "id": 1, "params": {...}}` or `{"type": "error", "id": 1, "error":
+//! "..."}`. Amounts are in sompi.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use rpc_core::{RpcAddress, RpcTransaction, RpcTransactionId};
+use serde::{Deserialize, Serialize};
+
+/// The methods of the wallet daemon API
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+#[repr(u32)]
+pub enum WalletApiOps {
+    CreateWallet = 0,
+    ListWallets,
+    NewAddress,
+    GetAddresses,
+    GetBalance,
+    Send,
+    Sign,
+}
+
+/// CreateWalletRequest creates a wallet out of a new random mnemonic, or restores one out of a given mnemonic.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWalletRequest {
+    pub name: String,
+    /// The mnemonic of a wallet to restore, in any supported language
+    pub mnemonic: Option<String>,
+    /// The number of receive addresses to restore, defaults to 1
+    pub receive_count: Option<u32>,
+    /// The number of change addresses to restore, defaults to 0
+    pub change_count: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWalletResponse {
+    /// The mnemonic of the wallet, which is the only way to restore it elsewhere
+    pub mnemonic: String,
+    /// The first receive address of the wallet
+    pub address: RpcAddress,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWalletsRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWalletsResponse {
+    pub names: Vec<String>,
+}
+
+/// NewAddressRequest hands out the next receive address of a wallet.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAddressRequest {
+    pub wallet: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAddressResponse {
+    pub address: RpcAddress,
+}
+
+/// GetAddressesRequest returns the receive addresses handed out by a wallet, in order.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAddressesRequest {
+    pub wallet: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAddressesResponse {
+    pub addresses: Vec<RpcAddress>,
+}
+
+/// GetBalanceRequest returns the balance of all addresses handed out by a wallet.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBalanceRequest {
+    pub wallet: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBalanceResponse {
+    /// The total amount of the UTXOs of the wallet
+    pub balance: u64,
+    /// The part of the balance which can be spent now, i.e., excluding immature coinbase outputs and the outputs
+    /// spent by transactions sent but not yet accepted
+    pub spendable: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentOutput {
+    pub address: RpcAddress,
+    pub amount: u64,
+}
+
+/// SendRequest builds, signs and submits a transaction paying the given outputs out of the UTXOs of a wallet,
+/// sending the change to a new change address of the wallet.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendRequest {
+    pub wallet: String,
+    pub outputs: Vec<PaymentOutput>,
+    /// The fee paid by the transaction
+    pub fee: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResponse {
+    pub transaction_id: RpcTransactionId,
+}
+
+/// SignRequest signs a transaction built by the client, all inputs of which must spend UTXOs of the wallet. The
+/// signed transaction is returned without being submitted.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequest {
+    pub wallet: String,
+    pub transaction: RpcTransaction,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignResponse {
+    pub transaction: RpcTransaction,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpc_wrpc::{
+        codec::{BorshCodec, Codec, JsonCodec},
+        message::Request,
+    };
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    fn decode_json(text: &str) -> rpc_wrpc::result::Result<Request<serde_json::Value, WalletApiOps>> {
+        JsonCodec::decode_request(WsMessage::Text(text.to_string()))
+    }
+
+    #[test]
+    fn test_request_decoding() {
+        let request = decode_json(r#"{"id": 3, "method": "getBalance", "params": {"wallet": "main"}}"#).unwrap();
+        assert_eq!((request.id, request.method), (3, WalletApiOps::GetBalance));
+        let balance: GetBalanceRequest = JsonCodec::decode_params(request.params).unwrap();
+        assert_eq!(balance.wallet, "main");
+
+        let request = decode_json(
+            r#"{"id": 4, "method": "send", "params": {"wallet": "main", "fee": 10, "outputs": [
+                {"address": "kaspa:qp0l70zd5x85ttwd6jv7g3s3a8llzj96d8dncn4zmhv4tlzx5k2jyqh70xmfj", "amount": 500}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.method, WalletApiOps::Send);
+        let send: SendRequest = JsonCodec::decode_params(request.params).unwrap();
+        assert_eq!((send.fee, send.outputs.len(), send.outputs[0].amount), (10, 1, 500));
+        assert_eq!(send.outputs[0].address.to_string(), "kaspa:qp0l70zd5x85ttwd6jv7g3s3a8llzj96d8dncn4zmhv4tlzx5k2jyqh70xmfj");
+
+        let request = decode_json(r#"{"id": 5, "method": "createWallet", "params": {"name": "w"}}"#).unwrap();
+        let create: CreateWalletRequest = JsonCodec::decode_params(request.params).unwrap();
+        assert!(create.mnemonic.is_none());
+        assert!(decode_json(r#"{"id": 6, "method": "unknown", "params": {}}"#).is_err());
+
+        // The Borsh encoding carries the same requests over binary frames
+        let params = BorshCodec::encode_params(&NewAddressRequest { wallet: "main".to_string() }).unwrap();
+        let message = BorshCodec::encode_request(&Request { id: 7, method: WalletApiOps::NewAddress, params }).unwrap();
+        let request: Request<Vec<u8>, WalletApiOps> = BorshCodec::decode_request(message).unwrap();
+        assert_eq!((request.id, request.method), (7, WalletApiOps::NewAddress));
+        let new_address: NewAddressRequest = BorshCodec::decode_params(request.params).unwrap();
+        assert_eq!(new_address.wallet, "main");
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    api::*,
+    encryption::{EncryptedMnemonic, KEY_DERIVATION_ROUNDS},
+    errors::{DaemonError, DaemonResult},
+    store::{is_valid_wallet_name, WalletFile, WalletStore},
+};
+use addresses::Prefix;
+use bip39::{Language, Mnemonic};
+use consensus_core::tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry};
+use kaspa_core::{info, trace};
+use kaspa_wallet::{
+    account::Account,
+    errors::WalletError,
+    tx::{build_transaction, Payment, Utxo},
+};
+use rpc_core::api::rpc::DynRpcService;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The number of words of the mnemonics of new wallets
+const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// The duration for which the outputs spent by a sent transaction are not selected again, unless they are removed
+/// from the UTXO set meanwhile. Past it, the transaction is assumed to have been dropped by the node.
+pub const PENDING_SPEND_EXPIRY: Duration = Duration::from_secs(10 * 60);
+
+/// A wallet hosted by the daemon
+struct Wallet {
+    name: String,
+    account: Account,
+    /// The mnemonic of the account as saved, since encrypting it anew on each save would derive a new key each time
+    mnemonic: EncryptedMnemonic,
+    /// The outputs spent by the transactions sent and not yet accepted, along with the time they were sent
+    pending: HashMap<TransactionOutpoint, Instant>,
+}
+
+impl Wallet {
+    fn to_file(&self) -> WalletFile {
+        WalletFile {
+            mnemonic: self.mnemonic.clone(),
+            receive_count: self.account.receive_count(),
+            change_count: self.account.change_count(),
+        }
+    }
+}
+
+/// Hosts multiple wallets, serving the calls of the [`crate::api`] through a node it is connected to.
+///
+/// The UTXOs of a wallet are not tracked: they are queried from the UTXO index of the node on demand, for all
+/// addresses handed out by the wallet. The calls of a single wallet are serialized, so that concurrent sends
+/// never select the same outputs, while the calls of different wallets run concurrently.
+pub struct WalletDaemon {
+    node: DynRpcService,
+    store: WalletStore,
+    /// The passphrase encrypting the mnemonics of the wallet files
+    passphrase: String,
+    prefix: Prefix,
+    coinbase_maturity: u64,
+    wallets: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Wallet>>>>,
+}
+
+impl WalletDaemon {
+    /// Opens all wallets of `store`, whose mnemonics are encrypted with `passphrase`, with addresses of `prefix`
+    pub fn new(
+        node: DynRpcService,
+        store: WalletStore,
+        passphrase: String,
+        prefix: Prefix,
+        coinbase_maturity: u64,
+    ) -> DaemonResult<Self> {
+        let mut wallets = HashMap::new();
+        for (name, file) in store.load_all()? {
+            let mnemonic = Mnemonic::parse_in_any_language(&file.mnemonic.decrypt(&passphrase)?)?;
+            let account = Account::new(mnemonic, prefix, file.receive_count, file.change_count)?;
+            let wallet = Wallet { name: name.clone(), account, mnemonic: file.mnemonic, pending: HashMap::new() };
+            wallets.insert(name, Arc::new(tokio::sync::Mutex::new(wallet)));
+        }
+        info!("Opened {0} wallets", wallets.len());
+        Ok(Self { node, store, passphrase, prefix, coinbase_maturity, wallets: Mutex::new(wallets) })
+    }
+
+    pub fn create_wallet(&self, request: CreateWalletRequest) -> DaemonResult<CreateWalletResponse> {
+        if !is_valid_wallet_name(&request.name) {
+            return Err(DaemonError::InvalidWalletName(request.name));
+        }
+        let mnemonic = match request.mnemonic {
+            Some(phrase) => Mnemonic::parse_in_any_language(&phrase)?,
+            None => Mnemonic::random(MNEMONIC_WORD_COUNT, Language::English)?,
+        };
+        let receive_count = request.receive_count.unwrap_or(1).max(1);
+        let account = Account::new(mnemonic, self.prefix, receive_count, request.change_count.unwrap_or_default())?;
+        let address = account.receive_addresses().next().unwrap().clone();
+        let phrase = account.mnemonic().phrase();
+        let mnemonic = EncryptedMnemonic::encrypt(&phrase, &self.passphrase, KEY_DERIVATION_ROUNDS);
+        let wallet = Wallet { name: request.name.clone(), account, mnemonic, pending: HashMap::new() };
+
+        let mut wallets = self.wallets.lock().unwrap();
+        if wallets.contains_key(&request.name) || self.store.contains(&request.name) {
+            return Err(DaemonError::WalletExists(request.name));
+        }
+        self.store.save(&request.name, &wallet.to_file())?;
+        wallets.insert(request.name.clone(), Arc::new(tokio::sync::Mutex::new(wallet)));
+        info!("Created wallet {0}", request.name);
+        Ok(CreateWalletResponse { mnemonic: phrase, address })
+    }
+
+    pub fn list_wallets(&self, _request: ListWalletsRequest) -> ListWalletsResponse {
+        let mut names: Vec<_> = self.wallets.lock().unwrap().keys().cloned().collect();
+        names.sort();
+        ListWalletsResponse { names }
+    }
+
+    pub async fn new_address(&self, request: NewAddressRequest) -> DaemonResult<NewAddressResponse> {
+        let wallet = self.wallet(&request.wallet)?;
+        let mut wallet = wallet.lock().await;
+        let address = wallet.account.new_receive_address()?;
+        self.store.save(&wallet.name, &wallet.to_file())?;
+        Ok(NewAddressResponse { address })
+    }
+
+    pub async fn get_addresses(&self, request: GetAddressesRequest) -> DaemonResult<GetAddressesResponse> {
+        let wallet = self.wallet(&request.wallet)?;
+        let wallet = wallet.lock().await;
+        Ok(GetAddressesResponse { addresses: wallet.account.receive_addresses().cloned().collect() })
+    }
+
+    pub async fn get_balance(&self, request: GetBalanceRequest) -> DaemonResult<GetBalanceResponse> {
+        let wallet = self.wallet(&request.wallet)?;
+        let mut wallet = wallet.lock().await;
+        let (utxos, virtual_daa_score) = self.fetch_utxos(&mut wallet).await?;
+        let balance = utxos.iter().map(|utxo| utxo.entry.amount).sum();
+        let spendable = spendable_utxos(utxos, &wallet.pending, virtual_daa_score, self.coinbase_maturity)
+            .iter()
+            .map(|utxo| utxo.entry.amount)
+            .sum();
+        Ok(GetBalanceResponse { balance, spendable })
+    }
+
+    pub async fn send(&self, request: SendRequest) -> DaemonResult<SendResponse> {
+        let wallet = self.wallet(&request.wallet)?;
+        let mut wallet = wallet.lock().await;
+        let (utxos, virtual_daa_score) = self.fetch_utxos(&mut wallet).await?;
+        let utxos = spendable_utxos(utxos, &wallet.pending, virtual_daa_score, self.coinbase_maturity);
+
+        // The change address is handed out only once the transaction is submitted
+        let change_address = wallet.account.change_address(wallet.account.change_count())?;
+        let payments: Vec<_> = request.outputs.into_iter().map(|output| Payment::new(output.address, output.amount)).collect();
+        let tx = wallet.account.sign(build_transaction(utxos, &payments, request.fee, &change_address)?)?.tx;
+        let transaction_id = self.node.submit_transaction((&tx).into(), false).await?.transaction_id;
+        trace!("Wallet {0} sent transaction {1}", wallet.name, transaction_id);
+
+        let now = Instant::now();
+        wallet.pending.extend(tx.inputs.iter().map(|input| (input.previous_outpoint, now)));
+        wallet.account.new_change_address()?;
+        self.store.save(&wallet.name, &wallet.to_file())?;
+        Ok(SendResponse { transaction_id })
+    }
+
+    pub async fn sign(&self, request: SignRequest) -> DaemonResult<SignResponse> {
+        let wallet = self.wallet(&request.wallet)?;
+        let mut wallet = wallet.lock().await;
+        let tx = Transaction::try_from(request.transaction)?;
+        let (utxos, _) = self.fetch_utxos(&mut wallet).await?;
+        let entries: HashMap<_, _> = utxos.into_iter().map(|utxo| (utxo.outpoint, utxo.entry)).collect();
+        let tx_entries = tx
+            .inputs
+            .iter()
+            .map(|input| match entries.get(&input.previous_outpoint) {
+                Some(entry) => Ok(entry.clone()),
+                None => Err(WalletError::UnknownOutpoint(input.previous_outpoint)),
+            })
+            .collect::<Result<Vec<UtxoEntry>, _>>()?;
+        let tx = wallet.account.sign(MutableTransaction::with_entries(tx, tx_entries))?.tx;
+        Ok(SignResponse { transaction: (&tx).into() })
+    }
+
+    fn wallet(&self, name: &str) -> DaemonResult<Arc<tokio::sync::Mutex<Wallet>>> {
+        match self.wallets.lock().unwrap().get(name) {
+            Some(wallet) => Ok(wallet.clone()),
+            None => Err(DaemonError::UnknownWallet(name.to_string())),
+        }
+    }
+
+    /// Fetches the UTXOs of all addresses of the wallet along with the virtual DAA score, forgetting the pending
+    /// spends which were accepted or expired meanwhile
+    async fn fetch_utxos(&self, wallet: &mut Wallet) -> DaemonResult<(Vec<Utxo>, u64)> {
+        // The DAA score is fetched first, so that it is not ahead of the UTXO set it decides the maturity of
+        let virtual_daa_score = self.node.get_block_dag_info().await?.virtual_daa_score;
+        let addresses = wallet.account.addresses().cloned().collect();
+        let entries = self.node.get_utxos_by_addresses(addresses).await?.entries;
+        let utxos: Vec<_> = entries.into_iter().map(|entry| Utxo::new(entry.outpoint, entry.utxo_entry)).collect();
+        let unspent: HashSet<_> = utxos.iter().map(|utxo| utxo.outpoint).collect();
+        wallet.pending.retain(|outpoint, sent| unspent.contains(outpoint) && sent.elapsed() < PENDING_SPEND_EXPIRY);
+        Ok((utxos, virtual_daa_score))
+    }
+}
+
+/// Filters out the immature coinbase outputs and the outputs spent by pending transactions
+fn spendable_utxos(
+    utxos: Vec<Utxo>,
+    pending: &HashMap<TransactionOutpoint, Instant>,
+    virtual_daa_score: u64,
+    coinbase_maturity: u64,
+) -> Vec<Utxo> {
+    utxos
+        .into_iter()
+        .filter(|utxo| {
+            let entry = &utxo.entry;
+            !pending.contains_key(&utxo.outpoint)
+                && (!entry.is_coinbase || entry.block_daa_score + coinbase_maturity <= virtual_daa_score)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::tx::{ScriptPublicKey, TransactionId};
+
+    #[test]
+    fn test_spendable_utxos() {
+        let utxo = |index: u8, amount: u64, block_daa_score: u64, is_coinbase: bool| {
+            let outpoint = TransactionOutpoint::new(TransactionId::from_slice(&[index; 32]), 0);
+            Utxo::new(outpoint, UtxoEntry::new(amount, ScriptPublicKey::default(), block_daa_score, is_coinbase))
+        };
+        let utxos = vec![utxo(1, 100, 50, false), utxo(2, 200, 50, true), utxo(3, 300, 100, true), utxo(4, 400, 10, false)];
+        let pending = HashMap::from([(utxos[3].outpoint, Instant::now())]);
+
+        // With a maturity of 100, coinbase outputs mature once the virtual DAA score is 100 above theirs
+        let amounts = |utxos: Vec<Utxo>| utxos.into_iter().map(|utxo| utxo.entry.amount).collect::<Vec<_>>();
+        assert_eq!(amounts(spendable_utxos(utxos.clone(), &pending, 150, 100)), vec![100, 200]);
+        assert_eq!(amounts(spendable_utxos(utxos.clone(), &pending, 200, 100)), vec![100, 200, 300]);
+        assert_eq!(amounts(spendable_utxos(utxos, &HashMap::new(), 149, 100)), vec![100, 400]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Passphrase encryption of the mnemonics kept in the wallet files.
+//!
+//! A key is derived out of the passphrase and a random salt by PBKDF2-HMAC-SHA512. Half of it encrypts the mnemonic
+//! with a keystream made of HMAC-SHA512 blocks over a counter, while the other half authenticates the salt and the
+//! ciphertext with HMAC-SHA512, so that a wrong passphrase or an altered file is detected before decrypting. Each
+//! encryption draws a new salt, hence a new key.
+
+use crate::errors::{DaemonError, DaemonResult};
+use bip39::{hmac_sha512, pbkdf2_hmac_sha512};
+use faster_hex::{hex_decode, hex_string};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// The number of PBKDF2 rounds deriving the key of a new encrypted mnemonic
+pub const KEY_DERIVATION_ROUNDS: u32 = 100_000;
+
+const SALT_SIZE: usize = 32;
+
+/// The size of each of the encryption and authentication keys
+const KEY_SIZE: usize = 32;
+
+/// The size of an HMAC-SHA512 output, i.e., of a keystream block
+const BLOCK_SIZE: usize = 64;
+
+/// A mnemonic encrypted with a passphrase, its binary fields being hex encoded
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedMnemonic {
+    /// The number of PBKDF2 rounds the key was derived with
+    pub rounds: u32,
+    pub salt: String,
+    pub ciphertext: String,
+    pub mac: String,
+}
+
+impl EncryptedMnemonic {
+    /// Encrypts a mnemonic phrase with a key derived out of `passphrase` in `rounds` PBKDF2 rounds
+    pub fn encrypt(phrase: &str, passphrase: &str, rounds: u32) -> Self {
+        let mut salt = [0u8; SALT_SIZE];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = pbkdf2_hmac_sha512(passphrase.as_bytes(), &salt, rounds);
+        let (encryption_key, mac_key) = key.split_at(KEY_SIZE);
+
+        let mut ciphertext = phrase.as_bytes().to_vec();
+        apply_keystream(encryption_key, &mut ciphertext);
+        let mac = hmac_sha512(mac_key, &[&salt, &ciphertext]);
+        Self { rounds, salt: hex_string(&salt), ciphertext: hex_string(&ciphertext), mac: hex_string(&mac) }
+    }
+
+    /// Decrypts the mnemonic phrase, failing if the passphrase is wrong or the encrypted mnemonic was altered
+    pub fn decrypt(&self, passphrase: &str) -> DaemonResult<String> {
+        let (salt, mut ciphertext, mac) = (decode(&self.salt)?, decode(&self.ciphertext)?, decode(&self.mac)?);
+        let key = pbkdf2_hmac_sha512(passphrase.as_bytes(), &salt, self.rounds);
+        let (encryption_key, mac_key) = key.split_at(KEY_SIZE);
+
+        if !constant_time_eq(&mac, &hmac_sha512(mac_key, &[&salt, &ciphertext])) {
+            return Err(DaemonError::Decryption);
+        }
+        apply_keystream(encryption_key, &mut ciphertext);
+        String::from_utf8(ciphertext).map_err(|_| DaemonError::Decryption)
+    }
+}
+
+/// XORs `data` with the keystream of `key`, which encrypts and decrypts alike
+fn apply_keystream(key: &[u8], data: &mut [u8]) {
+    for (counter, chunk) in data.chunks_mut(BLOCK_SIZE).enumerate() {
+        let block = hmac_sha512(key, &[&(counter as u64).to_be_bytes()]);
+        chunk.iter_mut().zip(block.iter()).for_each(|(byte, key_byte)| *byte ^= key_byte);
+    }
+}
+
+/// Compares two MACs in a time independent of the position of their first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn decode(hex: &str) -> DaemonResult<Vec<u8>> {
+    let mut bytes = vec![0u8; hex.len() / 2];
+    hex_decode(hex.as_bytes(), &mut bytes).map_err(|_| DaemonError::Decryption)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_mnemonic() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let encrypted = EncryptedMnemonic::encrypt(phrase, "passphrase", 2);
+        assert_eq!(encrypted.decrypt("passphrase").unwrap(), phrase);
+        assert!(!encrypted.ciphertext.contains(&hex_string(b"abandon")));
+
+        // Each encryption uses its own salt
+        assert_ne!(EncryptedMnemonic::encrypt(phrase, "passphrase", 2).ciphertext, encrypted.ciphertext);
+
+        // A wrong passphrase or any altered field fails the decryption
+        assert!(matches!(encrypted.decrypt("wrong"), Err(DaemonError::Decryption)));
+        let mut altered = encrypted.ciphertext.clone().into_bytes();
+        altered[0] = if altered[0] == b'0' { b'1' } else { b'0' };
+        let altered = EncryptedMnemonic { ciphertext: String::from_utf8(altered).unwrap(), ..encrypted.clone() };
+        assert!(matches!(altered.decrypt("passphrase"), Err(DaemonError::Decryption)));
+        let altered = EncryptedMnemonic { rounds: 3, ..encrypted.clone() };
+        assert!(matches!(altered.decrypt("passphrase"), Err(DaemonError::Decryption)));
+        let altered = EncryptedMnemonic { salt: "not hex".to_string(), ..encrypted };
+        assert!(matches!(altered.decrypt("passphrase"), Err(DaemonError::Decryption)));
+    }
+}
@@ -0,0 +1,45 @@
+use bip39::MnemonicError;
+use kaspa_wallet::errors::WalletError;
+use rpc_core::RpcError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DaemonError {
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+
+    #[error(transparent)]
+    Mnemonic(#[from] MnemonicError),
+
+    #[error("node RPC error: {0}")]
+    Rpc(#[from] RpcError),
+
+    #[error("wRPC error: {0}")]
+    Wrpc(#[from] rpc_wrpc::errors::Error),
+
+    #[error("invalid wallet name {0}, expected 1 to 64 letters, digits, '-' or '_'")]
+    InvalidWalletName(String),
+
+    #[error("wallet {0} already exists")]
+    WalletExists(String),
+
+    #[error("wallet {0} not found")]
+    UnknownWallet(String),
+
+    #[error("wrong passphrase or corrupted wallet file")]
+    Decryption,
+
+    #[error("the passphrase file {0} is empty")]
+    EmptyPassphrase(String),
+
+    #[error("wallet store error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON serialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+
+    #[error("the node serves network {0} while the daemon is configured for {1}")]
+    NetworkMismatch(String, String),
+}
+
+pub type DaemonResult<T> = std::result::Result<T, DaemonError>;
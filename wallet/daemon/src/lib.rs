@@ -0,0 +1,14 @@
+//! Kaspa wallet daemon.
+//!
+//! Hosts multiple HD wallets, each derived out of its own mnemonic, behind a wRPC API served with Borsh or JSON
+//! encoding (see [`api`]): wallets are created or restored, hand out receive addresses, report their balance, send
+//! payments and sign transactions built by the client. The daemon connects to a node through its wRPC API, whose UTXO index
+//! provides the UTXOs of the wallets, so that exchanges and services integrate against wallets rather than against
+//! the raw node RPC.
+
+pub mod api;
+pub mod daemon;
+pub mod encryption;
+pub mod errors;
+pub mod server;
+pub mod store;
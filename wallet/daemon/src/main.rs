@@ -0,0 +1,128 @@
+use addresses::Prefix;
+use clap::Parser;
+use consensus_core::config::params::NetworkType;
+use futures::future::join_all;
+use kaspa_core::{error, info};
+use kaspa_wallet_daemon::{daemon::WalletDaemon, errors::DaemonError, server::WalletServer, store::WalletStore};
+use rpc_core::api::rpc::RpcApi;
+use rpc_wrpc::{client::WrpcBorshClient, codec::Encoding};
+use std::{fs, path::PathBuf, sync::Arc};
+use tokio::net::TcpListener;
+
+/// Kaspa wallet daemon: hosts wallets behind a wRPC API, connected to a node through its wRPC Borsh API
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// The wRPC Borsh URL of the node, e.g. ws://127.0.0.1:17110. The node must run with --utxoindex
+    #[arg(long = "rpcserver")]
+    rpc_server: String,
+
+    /// Interface/port to serve the wallet wRPC API on with JSON encoding
+    #[arg(long = "listen-json", default_value = "127.0.0.1:8082")]
+    listen_json: String,
+
+    /// Interface/port to serve the wallet wRPC API on with Borsh encoding. If not set, wRPC Borsh is not served
+    #[arg(long = "listen-borsh")]
+    listen_borsh: Option<String>,
+
+    /// Directory holding the wallet files (default: the network subdirectory of ~/.kaspa-wallet-daemon)
+    #[arg(long = "walletsdir")]
+    wallets_dir: Option<PathBuf>,
+
+    /// File whose first line is the passphrase encrypting the mnemonics of the wallet files
+    #[arg(long = "passphrasefile")]
+    passphrase_file: PathBuf,
+
+    /// Use the test network
+    #[arg(long = "testnet", group = "network")]
+    testnet: bool,
+
+    /// Use the development test network
+    #[arg(long = "devnet", group = "network")]
+    devnet: bool,
+
+    /// Use the simulation test network
+    #[arg(long = "simnet", group = "network")]
+    simnet: bool,
+
+    /// Logging level for all subsystems {off, error, warn, info, debug, trace}
+    #[arg(long = "loglevel", default_value = "info")]
+    log_level: String,
+}
+
+impl Args {
+    /// The network selected by the network flags, mainnet if none is set
+    fn network(&self) -> NetworkType {
+        if self.testnet {
+            NetworkType::Testnet
+        } else if self.devnet {
+            NetworkType::Devnet
+        } else if self.simnet {
+            NetworkType::Simnet
+        } else {
+            NetworkType::Mainnet
+        }
+    }
+}
+
+/// The prefix of the addresses of a network. Simnet has no prefix of its own and uses the devnet one
+fn address_prefix(network: NetworkType) -> Prefix {
+    match network {
+        NetworkType::Mainnet => Prefix::Mainnet,
+        NetworkType::Testnet => Prefix::Testnet,
+        NetworkType::Devnet | NetworkType::Simnet => Prefix::Devnet,
+    }
+}
+
+fn get_app_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    return dirs::data_local_dir().unwrap().join("kaspa-wallet-daemon");
+    #[cfg(not(target_os = "windows"))]
+    return dirs::home_dir().unwrap().join(".kaspa-wallet-daemon");
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    kaspa_core::log::init_logger(None, &args.log_level);
+    if let Err(err) = run(args).await {
+        error!("{0}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let network = args.network();
+    let params = network.params();
+    let wallets_dir = args.wallets_dir.clone().unwrap_or_else(|| get_app_dir().join(network.to_string()));
+    let store = WalletStore::new(&wallets_dir)?;
+    info!("Wallets directory: {0}", wallets_dir.display());
+    let passphrase = fs::read_to_string(&args.passphrase_file)?.lines().next().unwrap_or_default().to_string();
+    if passphrase.is_empty() {
+        return Err(DaemonError::EmptyPassphrase(args.passphrase_file.display().to_string()).into());
+    }
+
+    let client = WrpcBorshClient::connect(&args.rpc_server).await?;
+    client.start().await;
+    let network_name = client.get_block_dag_info().await?.network_name;
+    if network_name != params.network_name {
+        return Err(DaemonError::NetworkMismatch(network_name, params.network_name.to_string()).into());
+    }
+    info!("Connected to node {0} on {1}", args.rpc_server, network_name);
+
+    let daemon = Arc::new(WalletDaemon::new(Arc::new(client), store, passphrase, address_prefix(network), params.coinbase_maturity)?);
+    let mut servers = Vec::new();
+    for (address, encoding) in [(Some(args.listen_json), Encoding::SerdeJson), (args.listen_borsh, Encoding::Borsh)] {
+        if let Some(address) = address {
+            let listener = TcpListener::bind(&address).await?;
+            info!("Serving the wallet wRPC API with {0:?} encoding on {1}", encoding, address);
+            let server = WalletServer::new(daemon.clone(), encoding);
+            servers.push(async move { server.serve(listener).await });
+        }
+    }
+    tokio::select! {
+        _ = join_all(servers) => {}
+        _ = tokio::signal::ctrl_c() => info!("Exiting"),
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use crate::{api::*, daemon::WalletDaemon, errors::DaemonResult};
+use borsh::{BorshDeserialize, BorshSerialize};
+use futures::{future::ready, Future, SinkExt, StreamExt};
+use kaspa_core::{trace, warn};
+use rpc_wrpc::{
+    codec::{BorshCodec, Codec, Encoding, JsonCodec},
+    message::{Request, ServerMessage},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{net::SocketAddr, sync::Arc};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message as WsMessage};
+
+/// The capacity of the queue of outgoing messages of a connection
+const OUTGOING_CAPACITY: usize = 128;
+
+/// Serves the wallet daemon API over wRPC, with the messages serialized according to a single [`Encoding`]
+pub struct WalletServer {
+    daemon: Arc<WalletDaemon>,
+    encoding: Encoding,
+}
+
+impl WalletServer {
+    pub fn new(daemon: Arc<WalletDaemon>, encoding: Encoding) -> Self {
+        Self { daemon, encoding }
+    }
+
+    /// Accepts connections until the listener fails
+    pub async fn serve(&self, listener: TcpListener) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, address)) => match self.encoding {
+                    Encoding::Borsh => {
+                        tokio::spawn(serve_connection::<BorshCodec>(self.daemon.clone(), stream, address));
+                    }
+                    Encoding::SerdeJson => {
+                        tokio::spawn(serve_connection::<JsonCodec>(self.daemon.clone(), stream, address));
+                    }
+                },
+                Err(err) => {
+                    warn!("Wallet server accept error: {0}", err);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Serves a WebSocket connection until it is closed by the client.
+///
+/// Requests are handled concurrently, each in its own task, while the responses are queued to a single writer task.
+async fn serve_connection<C: Codec>(daemon: Arc<WalletDaemon>, stream: TcpStream, address: SocketAddr) {
+    let socket = match accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(err) => {
+            trace!("[WalletConnection] WebSocket handshake with {0} failed: {1}", address, err);
+            return;
+        }
+    };
+    let (mut sink, mut stream) = socket.split();
+    let (sender, mut receiver) = mpsc::channel::<WsMessage>(OUTGOING_CAPACITY);
+
+    // Writer, ending once all senders are dropped
+    tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if let Err(err) = sink.send(message).await {
+                trace!("[WalletConnection] send to {0} error: {1}", address, err);
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(WsMessage::Close(_)) => break,
+            // Pings are answered by the WebSocket layer
+            Ok(WsMessage::Ping(_)) | Ok(WsMessage::Pong(_)) => continue,
+            Ok(message) => message,
+            Err(err) => {
+                trace!("[WalletConnection] receive from {0} error: {1}", address, err);
+                break;
+            }
+        };
+        let daemon = daemon.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let response = match C::decode_request(message) {
+                Ok(request) => handle_request::<C>(&daemon, request).await,
+                Err(err) => ServerMessage::Error { id: None, error: err.to_string() },
+            };
+            match C::encode_server_message(&response) {
+                Ok(message) => {
+                    // The connection might have been closed meanwhile
+                    let _ = sender.send(message).await;
+                }
+                Err(err) => trace!("[WalletConnection] encoding a response to {0} error: {1}", address, err),
+            }
+        });
+    }
+    trace!("[WalletConnection] connection from {0} closed", address);
+}
+
+/// Serves a decoded request, turning any failure into an error message
+pub async fn handle_request<C: Codec>(daemon: &WalletDaemon, request: Request<C::Params, WalletApiOps>) -> ServerMessage<C::Params> {
+    let Request { id, method, params } = request;
+    let result = match method {
+        WalletApiOps::CreateWallet => route::<C, _, _, _>(params, |request| ready(daemon.create_wallet(request))).await,
+        WalletApiOps::ListWallets => route::<C, _, _, _>(params, |request| ready(Ok(daemon.list_wallets(request)))).await,
+        WalletApiOps::NewAddress => route::<C, _, _, _>(params, |request| daemon.new_address(request)).await,
+        WalletApiOps::GetAddresses => route::<C, _, _, _>(params, |request| daemon.get_addresses(request)).await,
+        WalletApiOps::GetBalance => route::<C, _, _, _>(params, |request| daemon.get_balance(request)).await,
+        WalletApiOps::Send => route::<C, _, _, _>(params, |request| daemon.send(request)).await,
+        WalletApiOps::Sign => route::<C, _, _, _>(params, |request| daemon.sign(request)).await,
+    };
+    match result {
+        Ok(params) => ServerMessage::Response { id, params },
+        Err(err) => ServerMessage::Error { id: Some(id), error: err.to_string() },
+    }
+}
+
+/// Decodes the params of a request, calls `call` with them and encodes the response
+async fn route<C, Req, Resp, Fut>(params: C::Params, call: impl FnOnce(Req) -> Fut) -> DaemonResult<C::Params>
+where
+    C: Codec,
+    Req: DeserializeOwned + BorshDeserialize,
+    Resp: Serialize + BorshSerialize,
+    Fut: Future<Output = DaemonResult<Resp>>,
+{
+    let request = C::decode_params(params)?;
+    let response = call(request).await?;
+    Ok(C::encode_params(&response)?)
+}
@@ -0,0 +1,129 @@
+use crate::{
+    encryption::EncryptedMnemonic,
+    errors::{DaemonError, DaemonResult},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The extension of wallet files
+const WALLET_FILE_EXTENSION: &str = "json";
+
+/// The maximum length of a wallet name
+const MAX_WALLET_NAME_LEN: usize = 64;
+
+/// The persisted state of a wallet, from which its account is restored
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletFile {
+    /// The mnemonic, encrypted with the passphrase of the daemon
+    pub mnemonic: EncryptedMnemonic,
+    /// The number of receive addresses handed out
+    pub receive_count: u32,
+    /// The number of change addresses handed out
+    pub change_count: u32,
+}
+
+/// Keeps each wallet in a file named after it in a single directory.
+///
+/// The files hold the mnemonics encrypted with a passphrase, and are only readable by their owner on top of that.
+pub struct WalletStore {
+    dir: PathBuf,
+}
+
+impl WalletStore {
+    pub fn new(dir: &Path) -> DaemonResult<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    /// Loads all wallets of the directory, by name
+    pub fn load_all(&self) -> DaemonResult<Vec<(String, WalletFile)>> {
+        let mut wallets = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(WALLET_FILE_EXTENSION) {
+                continue;
+            }
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) if is_valid_wallet_name(name) => name.to_string(),
+                _ => continue,
+            };
+            wallets.push((name, serde_json::from_slice(&fs::read(&path)?)?));
+        }
+        wallets.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(wallets)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.path(name).exists()
+    }
+
+    /// Saves the wallet atomically, so that an interrupted save leaves the previous state intact
+    pub fn save(&self, name: &str, wallet: &WalletFile) -> DaemonResult<()> {
+        if !is_valid_wallet_name(name) {
+            return Err(DaemonError::InvalidWalletName(name.to_string()));
+        }
+        let temp_path = self.dir.join(format!(".{0}.tmp", name));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&temp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(wallet)?)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, self.path(name))?;
+        Ok(())
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{0}.{1}", name, WALLET_FILE_EXTENSION))
+    }
+}
+
+/// Wallet names are used as file names, hence restricted to a safe set of characters
+pub fn is_valid_wallet_name(name: &str) -> bool {
+    (1..=MAX_WALLET_NAME_LEN).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = WalletStore::new(dir.path()).unwrap();
+        let mnemonic = EncryptedMnemonic::encrypt("mnemonic words", "passphrase", 1);
+        let wallet = WalletFile { mnemonic, receive_count: 3, change_count: 1 };
+        store.save("exchange-1", &wallet).unwrap();
+        store.save("b", &WalletFile { receive_count: 0, ..wallet.clone() }).unwrap();
+        assert!(store.contains("exchange-1"));
+        assert!(!store.contains("exchange-2"));
+
+        // Updates replace the previous state, and unrelated files are ignored
+        store.save("exchange-1", &WalletFile { receive_count: 4, ..wallet.clone() }).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a wallet").unwrap();
+        let loaded = WalletStore::new(dir.path()).unwrap().load_all().unwrap();
+        let names: Vec<_> = loaded.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["b", "exchange-1"]);
+        assert_eq!(loaded[1].1.receive_count, 4);
+        assert_eq!(loaded[1].1.mnemonic.decrypt("passphrase").unwrap(), "mnemonic words");
+        assert!(!fs::read_to_string(dir.path().join("exchange-1.json")).unwrap().contains("mnemonic words"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join("exchange-1.json")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let long_name = "x".repeat(MAX_WALLET_NAME_LEN + 1);
+        for name in ["", "../escape", "with space", long_name.as_str()] {
+            assert!(matches!(store.save(name, &wallet), Err(DaemonError::InvalidWalletName(_))));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Wallet accounts, holding the keys derived out of a mnemonic.
+
+use crate::{
+    errors::{WalletError, WalletResult},
+    keys::{chain_path, ExtendedPrivateKey, CHANGE_CHAIN, RECEIVE_CHAIN},
+};
+use addresses::{Address, Prefix};
+use bip39::Mnemonic;
+use consensus_core::{sign::sign_with_multiple, tx::MutableTransaction};
+use secp256k1::SecretKey;
+
+/// The version of addresses paying to a schnorr public key
+const ADDRESS_VERSION_PUBKEY: u8 = 0;
+
+/// The BIP-44 account of the keys of an [`Account`]
+const ACCOUNT_INDEX: u32 = 0;
+
+/// The addresses of a chain handed out so far, along with their keys
+struct Chain {
+    key: ExtendedPrivateKey,
+    addresses: Vec<(Address, SecretKey)>,
+}
+
+impl Chain {
+    fn new(master: &ExtendedPrivateKey, chain: u32, prefix: Prefix, count: u32) -> WalletResult<Self> {
+        let mut chain = Self { key: master.derive_path(&chain_path(ACCOUNT_INDEX, chain))?, addresses: Vec::new() };
+        for _ in 0..count {
+            chain.next_address(prefix)?;
+        }
+        Ok(chain)
+    }
+
+    fn address(&self, index: u32, prefix: Prefix) -> WalletResult<(Address, SecretKey)> {
+        let key = self.key.derive_child(index)?;
+        let address = Address { prefix, payload: key.x_only_public_key().serialize().to_vec(), version: ADDRESS_VERSION_PUBKEY };
+        Ok((address, *key.secret_key()))
+    }
+
+    fn next_address(&mut self, prefix: Prefix) -> WalletResult<Address> {
+        let (address, key) = self.address(self.addresses.len() as u32, prefix)?;
+        self.addresses.push((address.clone(), key));
+        Ok(address)
+    }
+}
+
+/// A single account of an HD wallet, deriving its receive and change addresses out of the seed of a mnemonic.
+///
+/// The addresses are handed out in index order, so that the state of an account is fully described by its mnemonic
+/// and the number of addresses handed out on each chain. Only the addresses handed out are considered as owned by the
+/// account, e.g., when scanning for its UTXOs.
+pub struct Account {
+    mnemonic: Mnemonic,
+    prefix: Prefix,
+    receive: Chain,
+    change: Chain,
+}
+
+impl Account {
+    /// Restores the account of `mnemonic`, with `receive_count` and `change_count` addresses handed out on the
+    /// receive and change chains respectively
+    pub fn new(mnemonic: Mnemonic, prefix: Prefix, receive_count: u32, change_count: u32) -> WalletResult<Self> {
+        let master = ExtendedPrivateKey::from_seed(&mnemonic.to_seed(""))?;
+        let receive = Chain::new(&master, RECEIVE_CHAIN, prefix, receive_count)?;
+        let change = Chain::new(&master, CHANGE_CHAIN, prefix, change_count)?;
+        Ok(Self { mnemonic, prefix, receive, change })
+    }
+
+    pub fn mnemonic(&self) -> &Mnemonic {
+        &self.mnemonic
+    }
+
+    pub fn prefix(&self) -> Prefix {
+        self.prefix
+    }
+
+    pub fn receive_count(&self) -> u32 {
+        self.receive.addresses.len() as u32
+    }
+
+    pub fn change_count(&self) -> u32 {
+        self.change.addresses.len() as u32
+    }
+
+    /// Hands out the next receive address
+    pub fn new_receive_address(&mut self) -> WalletResult<Address> {
+        self.receive.next_address(self.prefix)
+    }
+
+    /// Hands out the next change address
+    pub fn new_change_address(&mut self) -> WalletResult<Address> {
+        self.change.next_address(self.prefix)
+    }
+
+    /// The change address of `index`, whether handed out or not, e.g., for building a transaction whose change
+    /// address is handed out only once it is sent
+    pub fn change_address(&self, index: u32) -> WalletResult<Address> {
+        Ok(self.change.address(index, self.prefix)?.0)
+    }
+
+    /// The receive addresses handed out so far, in index order
+    pub fn receive_addresses(&self) -> impl Iterator<Item = &Address> {
+        self.receive.addresses.iter().map(|(address, _)| address)
+    }
+
+    /// All addresses handed out so far, receive addresses first
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.receive.addresses.iter().chain(self.change.addresses.iter()).map(|(address, _)| address)
+    }
+
+    /// Signs all inputs of `tx` with the keys of the addresses their UTXO entries pay to, all of which must have been
+    /// handed out by this account
+    pub fn sign(&self, tx: MutableTransaction) -> WalletResult<MutableTransaction> {
+        let keys: Vec<_> =
+            self.receive.addresses.iter().chain(self.change.addresses.iter()).map(|(_, key)| key.secret_bytes()).collect();
+        sign_with_multiple(tx, &keys).map_err(WalletError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip39::Language;
+
+    #[test]
+    fn test_account_addresses() {
+        let mnemonic = Mnemonic::random(24, Language::English).unwrap();
+        let mut account = Account::new(mnemonic.clone(), Prefix::Testnet, 0, 0).unwrap();
+        let receive = [account.new_receive_address().unwrap(), account.new_receive_address().unwrap()];
+        let change = account.new_change_address().unwrap();
+        assert_ne!(receive[0], receive[1]);
+        assert!(receive.iter().all(|address| *address != change && address.prefix == Prefix::Testnet));
+        assert_eq!(account.addresses().count(), 3);
+        assert_eq!(account.change_address(0).unwrap(), change);
+        let next_change = account.change_address(1).unwrap();
+        assert_eq!(account.new_change_address().unwrap(), next_change);
+
+        // The addresses are restored out of the mnemonic and the counts
+        let restored = Account::new(mnemonic, Prefix::Testnet, account.receive_count(), account.change_count()).unwrap();
+        assert_eq!(restored.addresses().cloned().collect::<Vec<_>>(), account.addresses().cloned().collect::<Vec<_>>());
+        assert_eq!(restored.receive_addresses().cloned().collect::<Vec<_>>(), receive.to_vec());
+        assert_eq!(restored.change_count(), 2);
+    }
+}
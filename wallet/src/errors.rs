@@ -0,0 +1,39 @@
+use bip39::MnemonicError;
+use consensus_core::{sign::SignError, tx::TransactionOutpoint};
+use mining::errors::MiningManagerError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WalletError {
+    #[error(transparent)]
+    Mnemonic(#[from] MnemonicError),
+
+    #[error(transparent)]
+    Script(#[from] MiningManagerError),
+
+    #[error(transparent)]
+    Sign(#[from] SignError),
+
+    #[error("the derived key is invalid")]
+    InvalidDerivedKey,
+
+    #[error("a transaction must pay to at least one address")]
+    NoPayments,
+
+    #[error("payment amounts must be positive")]
+    ZeroAmount,
+
+    #[error("the total amount overflows")]
+    AmountOverflow,
+
+    #[error("insufficient funds: {0} sompi are available while {1} are required")]
+    InsufficientFunds(u64, u64),
+
+    #[error("paying {0} sompi requires more than {1} inputs")]
+    TooManyInputs(u64, usize),
+
+    #[error("outpoint {0} is not an unspent output of the wallet")]
+    UnknownOutpoint(TransactionOutpoint),
+}
+
+pub type WalletResult<T> = std::result::Result<T, WalletError>;
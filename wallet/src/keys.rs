@@ -0,0 +1,138 @@
+//! BIP-32 hierarchical deterministic keys.
+//!
+//! The keys of a wallet are derived from the seed of its mnemonic along the BIP-44 path
+//! `m/44'/111111'/<account>'/<chain>/<index>`, 111111 being the coin type registered for Kaspa.
+
+use crate::errors::{WalletError, WalletResult};
+use bip39::hmac_sha512;
+use secp256k1::{PublicKey, Scalar, SecretKey, XOnlyPublicKey, SECP256K1};
+
+/// The HMAC key deriving the master key out of a seed
+const MASTER_KEY_HMAC_KEY: &[u8] = b"Bitcoin seed";
+
+/// Child indexes from this one on are hardened, i.e., derived from the parent private key rather than its public key
+pub const HARDENED_INDEX: u32 = 1 << 31;
+
+/// The BIP-44 purpose
+pub const PURPOSE: u32 = 44;
+
+/// The BIP-44 coin type of Kaspa
+pub const KASPA_COIN_TYPE: u32 = 111111;
+
+/// The chain of the addresses handed out for receiving payments
+pub const RECEIVE_CHAIN: u32 = 0;
+
+/// The chain of the addresses receiving the change of sent transactions
+pub const CHANGE_CHAIN: u32 = 1;
+
+/// An extended private key, i.e., a private key along with the chain code its children are derived with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtendedPrivateKey {
+    secret_key: SecretKey,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+    /// Derives the master key out of a seed, such as the one of a BIP-39 mnemonic
+    pub fn from_seed(seed: &[u8]) -> WalletResult<Self> {
+        Self::from_hmac(hmac_sha512(MASTER_KEY_HMAC_KEY, &[seed]))
+    }
+
+    fn from_hmac(hmac: [u8; 64]) -> WalletResult<Self> {
+        let (key, chain_code) = hmac.split_at(32);
+        let secret_key = SecretKey::from_slice(key).map_err(|_| WalletError::InvalidDerivedKey)?;
+        Ok(Self { secret_key, chain_code: chain_code.try_into().unwrap() })
+    }
+
+    /// Derives the child key of `index`, which is hardened if at least [`HARDENED_INDEX`]
+    pub fn derive_child(&self, index: u32) -> WalletResult<Self> {
+        let index_bytes = index.to_be_bytes();
+        let hmac = if index >= HARDENED_INDEX {
+            hmac_sha512(&self.chain_code, &[&[0], &self.secret_key.secret_bytes(), &index_bytes])
+        } else {
+            hmac_sha512(&self.chain_code, &[&self.public_key().serialize(), &index_bytes])
+        };
+        let child = Self::from_hmac(hmac)?;
+        // The child key is the sum of the parent key and the derived one, which must both be valid keys
+        let tweak = Scalar::from_be_bytes(child.secret_key.secret_bytes()).unwrap();
+        let secret_key = self.secret_key.add_tweak(&tweak).map_err(|_| WalletError::InvalidDerivedKey)?;
+        Ok(Self { secret_key, ..child })
+    }
+
+    /// Derives the descendant key along `path`, a sequence of child indexes
+    pub fn derive_path(&self, path: &[u32]) -> WalletResult<Self> {
+        path.iter().try_fold(*self, |key, &index| key.derive_child(index))
+    }
+
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret_key
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_secret_key(SECP256K1, &self.secret_key)
+    }
+
+    /// The public key of the schnorr signatures made with this key, which is the one addresses pay to
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.public_key().x_only_public_key().0
+    }
+
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+}
+
+/// The path of the key deriving the addresses of `chain` of `account`
+pub fn chain_path(account: u32, chain: u32) -> [u32; 4] {
+    [PURPOSE | HARDENED_INDEX, KASPA_COIN_TYPE | HARDENED_INDEX, account | HARDENED_INDEX, chain]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip39::{Language, Mnemonic};
+    use faster_hex::hex_string;
+
+    #[test]
+    fn test_bip32_derivation() {
+        // BIP-32 test vector 1
+        let seed = (0u8..16).collect::<Vec<_>>();
+        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        assert_eq!(
+            hex_string(&master.secret_key().secret_bytes()),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+        );
+        assert_eq!(hex_string(master.chain_code()), "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508");
+
+        let tests = [
+            (
+                vec![HARDENED_INDEX],
+                "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+                "47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141",
+            ),
+            // A non-hardened child, derived from the parent public key
+            (
+                vec![HARDENED_INDEX, 1],
+                "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368",
+                "2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19",
+            ),
+        ];
+        for (path, secret_key, chain_code) in tests {
+            let key = master.derive_path(&path).unwrap();
+            assert_eq!(hex_string(&key.secret_key().secret_bytes()), secret_key);
+            assert_eq!(hex_string(key.chain_code()), chain_code);
+        }
+    }
+
+    #[test]
+    fn test_kaspa_path() {
+        let mnemonic = Mnemonic::parse(&format!("{0} about", ["abandon"; 11].join(" ")), Language::English).unwrap();
+        let master = ExtendedPrivateKey::from_seed(&mnemonic.to_seed("")).unwrap();
+        let key = master.derive_path(&chain_path(0, RECEIVE_CHAIN)).unwrap().derive_child(0).unwrap();
+        assert_eq!(hex_string(&key.secret_key().secret_bytes()), "24cd8d2875e8e17e4f88fbc554834a89b7b3e0c6f9d84fae36883604359ba440");
+        assert_eq!(
+            hex_string(&key.x_only_public_key().serialize()),
+            "1bacea84ca721c95d67ecace19bc499a77c03726bc8739af637bcd89abaaf058"
+        );
+    }
+}
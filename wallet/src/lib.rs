@@ -1,5 +1,12 @@
 extern crate self as wallet;
 
+pub mod account;
+pub mod errors;
+pub mod keys;
+pub mod qr;
+pub mod tx;
+pub mod uri;
+
 pub fn hello_wallet() {
     println!("Hello Wallet!");
 }
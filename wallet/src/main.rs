@@ -0,0 +1,109 @@
+use bip39::{Language, Mnemonic};
+use clap::{Parser, Subcommand};
+use kaspa_wallet::{
+    qr::{render_terminal, save_png},
+    uri::{format_amount, parse_amount, PaymentUri},
+};
+use std::{path::PathBuf, process::exit};
+
+/// Kaspa wallet
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Shows the QR code of a receive address, or of a payment URI when any payment detail is given
+    Qr {
+        /// The receive address, e.g. kaspa:qq...
+        address: String,
+
+        /// The requested amount, in KAS
+        #[arg(long)]
+        amount: Option<String>,
+
+        /// A label for the recipient
+        #[arg(long)]
+        label: Option<String>,
+
+        /// A message describing the payment
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Saves the QR code as a PNG image at this path instead of printing it
+        #[arg(long)]
+        png: Option<PathBuf>,
+    },
+
+    /// Parses a payment URI, e.g. as scanned from a QR code, and shows its details
+    ParseUri {
+        /// The payment URI, e.g. kaspa:qq...?amount=1.5
+        uri: String,
+    },
+
+    /// Generates a new random BIP-39 mnemonic
+    NewMnemonic {
+        /// The number of words, one of 12, 15, 18, 21 or 24
+        #[arg(long, default_value_t = 24)]
+        words: usize,
+
+        /// The wordlist language, one of english, french or spanish
+        #[arg(long, default_value_t = Language::English)]
+        language: Language,
+    },
+
+    /// Validates a BIP-39 mnemonic of any supported language
+    CheckMnemonic {
+        /// The mnemonic sentence, quoted
+        phrase: String,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args.command) {
+        eprintln!("{0}", err);
+        exit(1);
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Qr { address, amount, label, message, png } => {
+            let mut payment: PaymentUri = address.parse()?;
+            payment.amount = amount.as_deref().map(parse_amount).transpose()?;
+            payment.label = label;
+            payment.message = message;
+            let uri = payment.to_string();
+            match png {
+                Some(path) => {
+                    save_png(&uri, &path)?;
+                    println!("Saved the QR code of {0} to {1}", uri, path.display());
+                }
+                None => println!("{0}\n{1}", render_terminal(&uri)?, uri),
+            }
+        }
+        Command::ParseUri { uri } => {
+            let payment: PaymentUri = uri.parse()?;
+            println!("Address: {0}", String::from(&payment.address));
+            if let Some(amount) = payment.amount {
+                println!("Amount:  {0} KAS", format_amount(amount));
+            }
+            if let Some(label) = payment.label {
+                println!("Label:   {0}", label);
+            }
+            if let Some(message) = payment.message {
+                println!("Message: {0}", message);
+            }
+        }
+        Command::NewMnemonic { words, language } => println!("{0}", Mnemonic::random(words, language)?),
+        Command::CheckMnemonic { phrase } => {
+            let mnemonic = Mnemonic::parse_in_any_language(&phrase)?;
+            println!("Valid {0} mnemonic of {1} words", mnemonic.language(), mnemonic.word_count());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! QR codes of addresses and payment URIs, rendered for terminals or saved as PNG images
+
+use image::{ImageError, Luma};
+use qrcode::{
+    render::unicode::Dense1x2,
+    types::{EcLevel, QrError},
+    QrCode,
+};
+use std::path::Path;
+use thiserror::Error;
+
+/// The minimal width and height of PNG images, in pixels
+const PNG_MIN_DIMENSION: u32 = 256;
+
+#[derive(Error, Debug)]
+pub enum QrCodeError {
+    #[error("cannot encode a QR code: {0}")]
+    Encoding(#[from] QrError),
+
+    #[error("cannot save the QR code image: {0}")]
+    Image(#[from] ImageError),
+}
+
+pub type QrCodeResult<T> = Result<T, QrCodeError>;
+
+fn encode(data: &str) -> QrCodeResult<QrCode> {
+    // Medium error correction keeps codes of payment URIs small enough for low resolution screens
+    Ok(QrCode::with_error_correction_level(data, EcLevel::M)?)
+}
+
+/// Renders `data` with Unicode half blocks, two modules per character. Colors are inverted, so that the code
+/// scans as dark over light on the usual light over dark terminals.
+pub fn render_terminal(data: &str) -> QrCodeResult<String> {
+    Ok(encode(data)?.render::<Dense1x2>().dark_color(Dense1x2::Light).light_color(Dense1x2::Dark).build())
+}
+
+/// Saves `data` as a grayscale PNG image at `path`
+pub fn save_png(data: &str, path: &Path) -> QrCodeResult<()> {
+    let image = encode(data)?.render::<Luma<u8>>().min_dimensions(PNG_MIN_DIMENSION, PNG_MIN_DIMENSION).build();
+    Ok(image.save(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_terminal() {
+        let rendered = render_terminal("kaspa:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e?amount=1.5").unwrap();
+        let lines: Vec<_> = rendered.lines().collect();
+        // Two module rows per line, all lines having the width of the code, quiet zone included
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+        assert_eq!(lines.len(), width.div_ceil(2));
+    }
+}
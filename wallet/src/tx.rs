@@ -0,0 +1,179 @@
+//! Transaction building: selection of the UTXOs funding a set of payments and assembly of the unsigned transaction.
+
+use crate::errors::{WalletError, WalletResult};
+use addresses::Address;
+use consensus_core::{
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{MutableTransaction, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+};
+use mining::block_template::builder::pay_to_address_script;
+
+/// The maximum number of inputs of a transaction built by the wallet, bounding its mass to about a fifth of the block
+/// mass limit, most of which is due to the signature operation of each input
+pub const MAX_INPUTS: usize = 100;
+
+/// The number of signature operations of an input spending a pay-to-public-key output
+const SIG_OP_COUNT: u8 = 1;
+
+/// An unspent output owned by the wallet
+#[derive(Clone, Debug)]
+pub struct Utxo {
+    pub outpoint: TransactionOutpoint,
+    pub entry: UtxoEntry,
+}
+
+impl Utxo {
+    pub fn new(outpoint: TransactionOutpoint, entry: UtxoEntry) -> Self {
+        Self { outpoint, entry }
+    }
+}
+
+/// A payment of `amount` sompi to `address`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Payment {
+    pub address: Address,
+    pub amount: u64,
+}
+
+impl Payment {
+    pub fn new(address: Address, amount: u64) -> Self {
+        Self { address, amount }
+    }
+}
+
+/// Selects the UTXOs funding `target` sompi, largest first, so that the transaction spends as few inputs as possible
+pub fn select_utxos(mut utxos: Vec<Utxo>, target: u64) -> WalletResult<Vec<Utxo>> {
+    utxos.sort_by(|a, b| b.entry.amount.cmp(&a.entry.amount));
+    let mut total = 0u64;
+    for (count, utxo) in utxos.iter().enumerate() {
+        if count == MAX_INPUTS {
+            return Err(WalletError::TooManyInputs(target, MAX_INPUTS));
+        }
+        total += utxo.entry.amount;
+        if total >= target {
+            utxos.truncate(count + 1);
+            return Ok(utxos);
+        }
+    }
+    Err(WalletError::InsufficientFunds(total, target))
+}
+
+/// Builds the unsigned transaction paying `payments` and `fee` out of some of `utxos`, sending the change, if any,
+/// to `change_address`. The UTXO entries spent are populated, so that the transaction is ready for signing.
+pub fn build_transaction(
+    utxos: Vec<Utxo>,
+    payments: &[Payment],
+    fee: u64,
+    change_address: &Address,
+) -> WalletResult<MutableTransaction> {
+    if payments.is_empty() {
+        return Err(WalletError::NoPayments);
+    }
+    if payments.iter().any(|payment| payment.amount == 0) {
+        return Err(WalletError::ZeroAmount);
+    }
+    let target =
+        payments.iter().try_fold(fee, |total, payment| total.checked_add(payment.amount)).ok_or(WalletError::AmountOverflow)?;
+    let selected = select_utxos(utxos, target)?;
+    let change = selected.iter().map(|utxo| utxo.entry.amount).sum::<u64>() - target;
+
+    let mut outputs = payments
+        .iter()
+        .map(|payment| Ok(TransactionOutput::new(payment.amount, pay_to_address_script(&payment.address)?)))
+        .collect::<WalletResult<Vec<_>>>()?;
+    if change > 0 {
+        outputs.push(TransactionOutput::new(change, pay_to_address_script(change_address)?));
+    }
+    let inputs = selected.iter().map(|utxo| TransactionInput::new(utxo.outpoint, vec![], 0, SIG_OP_COUNT)).collect();
+    let tx = Transaction::new(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+    Ok(MutableTransaction::with_entries(tx, selected.into_iter().map(|utxo| utxo.entry).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use addresses::Prefix;
+    use bip39::{Language, Mnemonic};
+    use consensus_core::{
+        hashing::{
+            sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
+            sighash_type::SIG_HASH_ALL,
+        },
+        tx::{TransactionId, VerifiableTransaction},
+    };
+
+    fn utxos(account: &Account, amounts: &[u64]) -> Vec<Utxo> {
+        let addresses: Vec<_> = account.addresses().collect();
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(index, &amount)| {
+                let outpoint = TransactionOutpoint::new(TransactionId::from_slice(&[index as u8 + 1; 32]), 0);
+                let script_public_key = pay_to_address_script(addresses[index % addresses.len()]).unwrap();
+                Utxo::new(outpoint, UtxoEntry::new(amount, script_public_key, 0, false))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_utxos() {
+        let mut account = Account::new(Mnemonic::random(12, Language::English).unwrap(), Prefix::Testnet, 0, 0).unwrap();
+        account.new_receive_address().unwrap();
+        let available = utxos(&account, &[100, 300, 200]);
+
+        let selected = select_utxos(available.clone(), 250).unwrap();
+        assert_eq!(selected.iter().map(|utxo| utxo.entry.amount).collect::<Vec<_>>(), vec![300]);
+        let selected = select_utxos(available.clone(), 450).unwrap();
+        assert_eq!(selected.iter().map(|utxo| utxo.entry.amount).collect::<Vec<_>>(), vec![300, 200]);
+        assert!(matches!(select_utxos(available, 601), Err(WalletError::InsufficientFunds(600, 601))));
+
+        let dust = utxos(&account, &[1; MAX_INPUTS + 1]);
+        assert!(matches!(select_utxos(dust, MAX_INPUTS as u64 + 1), Err(WalletError::TooManyInputs(_, MAX_INPUTS))));
+    }
+
+    #[test]
+    fn test_build_and_sign_transaction() {
+        let mut account = Account::new(Mnemonic::random(12, Language::English).unwrap(), Prefix::Testnet, 0, 0).unwrap();
+        account.new_receive_address().unwrap();
+        account.new_receive_address().unwrap();
+        let change_address = account.new_change_address().unwrap();
+        let recipient = Account::new(Mnemonic::random(12, Language::English).unwrap(), Prefix::Testnet, 1, 0).unwrap();
+        let payment = Payment::new(recipient.receive_addresses().next().unwrap().clone(), 400);
+
+        let tx = build_transaction(utxos(&account, &[300, 250]), &[payment.clone()], 10, &change_address).unwrap();
+        let amounts: Vec<_> = tx.tx.outputs.iter().map(|output| output.value).collect();
+        assert_eq!(amounts, vec![400, 140]);
+        assert_eq!(tx.tx.outputs[0].script_public_key, pay_to_address_script(&payment.address).unwrap());
+        assert_eq!(tx.tx.outputs[1].script_public_key, pay_to_address_script(&change_address).unwrap());
+
+        // The inputs spend outputs of two different addresses, each signed by its own key
+        let signed = account.sign(tx).unwrap();
+        let verifiable = signed.as_verifiable();
+        let mut reused_values = SigHashReusedValues::new();
+        for (i, (input, entry)) in verifiable.populated_inputs().enumerate() {
+            let pk = secp256k1::XOnlyPublicKey::from_slice(&entry.script_public_key.script()[1..33]).unwrap();
+            let sig = secp256k1::schnorr::Signature::from_slice(&input.signature_script[1..65]).unwrap();
+            let sig_hash = calc_schnorr_signature_hash(&verifiable, i, SIG_HASH_ALL, &mut reused_values);
+            let msg = secp256k1::Message::from_slice(sig_hash.as_bytes().as_slice()).unwrap();
+            sig.verify(&msg, &pk).unwrap();
+        }
+
+        // No change output is added when the inputs exactly cover the payment and the fee
+        let tx = build_transaction(utxos(&account, &[410]), &[payment.clone()], 10, &change_address).unwrap();
+        assert_eq!(tx.tx.outputs.len(), 1);
+
+        assert!(matches!(build_transaction(utxos(&account, &[410]), &[], 10, &change_address), Err(WalletError::NoPayments)));
+        let zero = Payment::new(payment.address.clone(), 0);
+        assert!(matches!(build_transaction(utxos(&account, &[410]), &[zero], 10, &change_address), Err(WalletError::ZeroAmount)));
+        let overflow = Payment::new(payment.address, u64::MAX);
+        assert!(matches!(
+            build_transaction(utxos(&account, &[410]), &[overflow], 10, &change_address),
+            Err(WalletError::AmountOverflow)
+        ));
+        // An output of a foreign address cannot be signed
+        let foreign =
+            build_transaction(utxos(&recipient, &[500]), &[Payment::new(change_address.clone(), 100)], 10, &change_address).unwrap();
+        assert!(matches!(account.sign(foreign), Err(WalletError::Sign(_))));
+    }
+}
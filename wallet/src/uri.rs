@@ -0,0 +1,209 @@
+//! Payment URIs, in the form `<address>?amount=<KAS>&label=<label>&message=<message>`, where the address
+//! already carries the `kaspa:` scheme as its prefix. Parameters follow BIP-21: unknown parameters are
+//! ignored unless prefixed by `req-`, in which case the URI is rejected.
+
+use addresses::{Address, AddressError};
+use consensus_core::constants::SOMPI_PER_KASPA;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// The number of decimal digits of a KAS amount, i.e., the base 10 logarithm of [`SOMPI_PER_KASPA`]
+const KASPA_DECIMALS: usize = 8;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum UriError {
+    #[error(transparent)]
+    Address(#[from] AddressError),
+
+    #[error("invalid amount {0}")]
+    InvalidAmount(String),
+
+    #[error("invalid percent encoding in {0}")]
+    InvalidEncoding(String),
+
+    #[error("duplicate parameter {0}")]
+    DuplicateParameter(String),
+
+    #[error("unsupported required parameter {0}")]
+    UnsupportedRequiredParameter(String),
+}
+
+pub type UriResult<T> = Result<T, UriError>;
+
+/// A request for a payment to an address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentUri {
+    pub address: Address,
+    /// The requested amount, in sompi
+    pub amount: Option<u64>,
+    /// A label for the recipient
+    pub label: Option<String>,
+    /// A message describing the payment
+    pub message: Option<String>,
+}
+
+impl PaymentUri {
+    pub fn new(address: Address) -> Self {
+        Self { address, amount: None, label: None, message: None }
+    }
+
+    pub fn with_amount(self, amount: u64) -> Self {
+        Self { amount: Some(amount), ..self }
+    }
+
+    pub fn with_label(self, label: String) -> Self {
+        Self { label: Some(label), ..self }
+    }
+
+    pub fn with_message(self, message: String) -> Self {
+        Self { message: Some(message), ..self }
+    }
+}
+
+impl Display for PaymentUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&String::from(&self.address))?;
+        let params = [
+            ("amount", self.amount.map(format_amount)),
+            ("label", self.label.as_deref().map(percent_encode)),
+            ("message", self.message.as_deref().map(percent_encode)),
+        ];
+        for (i, (name, value)) in params.into_iter().filter_map(|(name, value)| Some((name, value?))).enumerate() {
+            write!(f, "{0}{1}={2}", if i == 0 { '?' } else { '&' }, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentUri {
+    type Err = UriError;
+
+    fn from_str(uri: &str) -> UriResult<Self> {
+        let (address, query) = uri.trim().split_once('?').unwrap_or((uri.trim(), ""));
+        let mut payment = PaymentUri::new(Address::try_from(address.to_string())?);
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            let field = match name {
+                "amount" => {
+                    if payment.amount.replace(parse_amount(value)?).is_some() {
+                        return Err(UriError::DuplicateParameter(name.to_string()));
+                    }
+                    continue;
+                }
+                "label" => &mut payment.label,
+                "message" => &mut payment.message,
+                _ if name.starts_with("req-") => return Err(UriError::UnsupportedRequiredParameter(name.to_string())),
+                _ => continue,
+            };
+            if field.replace(percent_decode(value)?).is_some() {
+                return Err(UriError::DuplicateParameter(name.to_string()));
+            }
+        }
+        Ok(payment)
+    }
+}
+
+/// Parses a decimal KAS amount, e.g. `1.5`, into sompi
+pub fn parse_amount(amount: &str) -> UriResult<u64> {
+    let invalid = || UriError::InvalidAmount(amount.to_string());
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_numeric = integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit());
+    if !is_numeric || integer.is_empty() || amount.ends_with('.') || fraction.len() > KASPA_DECIMALS {
+        return Err(invalid());
+    }
+    let integer: u64 = integer.parse().map_err(|_| invalid())?;
+    let fraction: u64 = format!("{0:0<1$}", fraction, KASPA_DECIMALS).parse().map_err(|_| invalid())?;
+    integer.checked_mul(SOMPI_PER_KASPA).and_then(|sompi| sompi.checked_add(fraction)).ok_or_else(invalid)
+}
+
+/// Formats an amount of sompi as a decimal KAS amount, without trailing zeros
+pub fn format_amount(sompi: u64) -> String {
+    let (integer, fraction) = (sompi / SOMPI_PER_KASPA, sompi % SOMPI_PER_KASPA);
+    if fraction == 0 {
+        return integer.to_string();
+    }
+    let fraction = format!("{0:01$}", fraction, KASPA_DECIMALS);
+    format!("{0}.{1}", integer, fraction.trim_end_matches('0'))
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{0:02X}", b),
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> UriResult<String> {
+    let invalid = || UriError::InvalidEncoding(value.to_string());
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'%' => {
+                let hex = [iter.next().ok_or_else(invalid)?, iter.next().ok_or_else(invalid)?];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use addresses::Prefix;
+
+    fn address() -> Address {
+        Address { prefix: Prefix::Mainnet, payload: vec![0u8; 32], version: 0 }
+    }
+
+    #[test]
+    fn test_amounts() {
+        assert_eq!(parse_amount("1").unwrap(), SOMPI_PER_KASPA);
+        assert_eq!(parse_amount("1.5").unwrap(), 150_000_000);
+        assert_eq!(parse_amount("0.00000001").unwrap(), 1);
+        for invalid in ["", ".5", "1.", "1.000000001", "-1", "1e3", "184467440738"] {
+            assert!(parse_amount(invalid).is_err(), "{0}", invalid);
+        }
+        for sompi in [0, 1, 150_000_000, SOMPI_PER_KASPA, 123_456_789_000] {
+            assert_eq!(parse_amount(&format_amount(sompi)).unwrap(), sompi);
+        }
+        assert_eq!(format_amount(150_000_000), "1.5");
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let address_str = String::from(&address());
+        let payment = PaymentUri::new(address()).with_amount(150_000_000).with_label("Coffee & co".to_string());
+        let uri = payment.to_string();
+        assert_eq!(uri, format!("{0}?amount=1.5&label=Coffee%20%26%20co", address_str));
+        assert_eq!(uri.parse::<PaymentUri>().unwrap(), payment);
+
+        assert_eq!(address_str.parse::<PaymentUri>().unwrap(), PaymentUri::new(address()));
+        assert_eq!(
+            format!("{0}?message=thanks+a%20lot&foo=bar", address_str).parse::<PaymentUri>().unwrap(),
+            PaymentUri::new(address()).with_message("thanks a lot".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_uris() {
+        let address_str = String::from(&address());
+        assert!(matches!("kaspa:invalid".parse::<PaymentUri>(), Err(UriError::Address(_))));
+        assert!(matches!(
+            format!("{0}?req-foo=bar", address_str).parse::<PaymentUri>(),
+            Err(UriError::UnsupportedRequiredParameter(_))
+        ));
+        assert!(matches!(format!("{0}?amount=1&amount=2", address_str).parse::<PaymentUri>(), Err(UriError::DuplicateParameter(_))));
+        assert!(matches!(format!("{0}?label=%4", address_str).parse::<PaymentUri>(), Err(UriError::InvalidEncoding(_))));
+    }
+}
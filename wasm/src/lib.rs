@@ -5,3 +5,5 @@ use kaspa_core::*;
 use kaspa_utils::*;
 use kaspa_wallet::*;
 use rpc_core::*;
+
+pub mod mnemonic;
@@ -0,0 +1,37 @@
+use bip39::Language;
+use wasm_bindgen::prelude::*;
+
+/// A BIP-39 mnemonic
+#[wasm_bindgen]
+pub struct Mnemonic(bip39::Mnemonic);
+
+#[wasm_bindgen]
+impl Mnemonic {
+    /// Parses a mnemonic sentence of any supported language, validating its checksum
+    #[wasm_bindgen(constructor)]
+    pub fn new(phrase: &str) -> Result<Mnemonic, JsError> {
+        Ok(Self(bip39::Mnemonic::parse_in_any_language(phrase)?))
+    }
+
+    /// Generates a random mnemonic of `word_count` words, in English unless another language is given
+    pub fn random(word_count: usize, language: Option<String>) -> Result<Mnemonic, JsError> {
+        let language = language.as_deref().map(str::parse::<Language>).transpose()?.unwrap_or_default();
+        Ok(Self(bip39::Mnemonic::random(word_count, language)?))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn phrase(&self) -> String {
+        self.0.phrase()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn language(&self) -> String {
+        self.0.language().to_string()
+    }
+
+    /// Derives the hex encoded seed of the mnemonic, protected by an optional passphrase
+    #[wasm_bindgen(js_name = toSeed)]
+    pub fn to_seed(&self, passphrase: Option<String>) -> String {
+        faster_hex::hex_string(&self.0.to_seed(passphrase.as_deref().unwrap_or_default()))
+    }
+}